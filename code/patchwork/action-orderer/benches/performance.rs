@@ -14,7 +14,7 @@ where
         b.iter_with_setup(
             || {
                 let seed = rand::random::<u64>();
-                let state = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
                 let actions = state.get_valid_actions();
                 let scores = vec![0.0; actions.len()];
                 (state, actions, scores)
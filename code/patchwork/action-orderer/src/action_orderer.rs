@@ -84,6 +84,12 @@ pub trait ActionOrderer {
 
     /// Picks the best action from the given actions. The given actions are ordered in place.
     ///
+    /// Actions with the same score are ordered by their action id. This makes
+    /// the ordering independent of the order the actions were generated and
+    /// scored in, so that a single-threaded search is fully reproducible.
+    /// A parallel search sharing a transposition table remains
+    /// nondeterministic as the threads race on the table entries.
+    ///
     /// # Arguments
     ///
     /// * `actions` - The actions to pick from.
@@ -98,10 +104,56 @@ pub trait ActionOrderer {
     /// `𝒪(𝑛)` where `n` is the amount of actions.
     fn pick_action(&self, actions: &mut ActionList<'_>, start_index: usize) -> ActionId {
         for i in (start_index + 1)..actions.len() {
-            if actions.get_score(i) > actions.get_score(start_index) {
+            let is_better = actions
+                .get_score(i)
+                .total_cmp(&actions.get_score(start_index))
+                .then_with(|| actions.get_action(start_index).as_bits().cmp(&actions.get_action(i).as_bits()))
+                .is_gt();
+            if is_better {
                 actions.swap(start_index, i);
             }
         }
         actions.get_action(start_index)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use super::*;
+
+    /// An action orderer that scores all actions the same so that only the
+    /// tie-break decides the order.
+    struct ConstantActionOrderer;
+
+    impl ActionOrderer for ConstantActionOrderer {
+        fn score_action(&self, _: &Patchwork, _: ActionId, _: Option<ActionId>, _: usize) -> f64 {
+            0.0
+        }
+    }
+
+    fn get_ordered_actions(state: &Patchwork, mut actions: Vec<ActionId>) -> Vec<ActionId> {
+        let mut scores = vec![0.0; actions.len()];
+        let mut action_list = ActionList::new(&mut actions, &mut scores);
+        ConstantActionOrderer.score_actions(state, &mut action_list, None, 0);
+
+        (0..action_list.len())
+            .map(|index| ConstantActionOrderer.pick_action(&mut action_list, index))
+            .collect()
+    }
+
+    #[test]
+    fn test_ties_are_ordered_independent_of_the_generation_order() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let actions = state.get_valid_actions();
+        let mut reversed_actions = actions.clone();
+        reversed_actions.reverse();
+
+        let mut expected_actions = actions.clone();
+        expected_actions.sort_unstable_by_key(ActionId::as_bits);
+
+        assert_eq!(expected_actions, get_ordered_actions(&state, actions));
+        assert_eq!(expected_actions, get_ordered_actions(&state, reversed_actions));
+    }
+}
@@ -3,9 +3,15 @@ mod action_orderer;
 mod evaluation_action_orderer;
 mod patch_square_table;
 mod table_action_orderer;
+mod table_export;
+mod table_learner;
 
 pub use action_list::ActionList;
 pub use action_orderer::ActionOrderer;
 pub use evaluation_action_orderer::EvaluationActionOrderer;
 pub use patch_square_table::*;
 pub use table_action_orderer::TableActionOrderer;
+pub use table_export::{
+    export_given_patch_placement_tables, export_patch_placement_tables, get_square_values, normalize_square_values, PatchPlacementTableExport, SquareValues,
+};
+pub use table_learner::{PatchPlacementTable, PatchPlacementTableLearner};
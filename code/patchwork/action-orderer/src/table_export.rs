@@ -0,0 +1,205 @@
+use patchwork_core::{PatchManager, QuiltBoard};
+
+use crate::{PatchPlacementTable, PATCH_PLACEMENT_ENDGAME_TABLE, PATCH_PLACEMENT_OPENING_TABLE};
+
+/// A value for every square of the quilt board.
+pub type SquareValues = [[f64; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
+
+/// The patch placement table entries of a single patch together with the
+/// values derived for every square of the quilt board.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchPlacementTableExport<'a> {
+    /// The id of the patch.
+    pub patch_id: u8,
+    /// The opening table entry for every transformation of the patch.
+    pub opening: &'a [f64],
+    /// The endgame table entry for every transformation of the patch.
+    pub endgame: &'a [f64],
+    /// The summed opening table entries of all transformations covering each square.
+    pub opening_squares: SquareValues,
+    /// The summed endgame table entries of all transformations covering each square.
+    pub endgame_squares: SquareValues,
+}
+
+impl PatchPlacementTableExport<'_> {
+    /// Gets the probability of each square to be covered by the patch in the
+    /// opening (softmax over the opening square values).
+    ///
+    /// # Returns
+    ///
+    /// The probability for every square of the quilt board.
+    #[must_use]
+    pub fn opening_square_probabilities(&self) -> SquareValues {
+        normalize_square_values(&self.opening_squares)
+    }
+
+    /// Gets the probability of each square to be covered by the patch in the
+    /// endgame (softmax over the endgame square values).
+    ///
+    /// # Returns
+    ///
+    /// The probability for every square of the quilt board.
+    #[must_use]
+    pub fn endgame_square_probabilities(&self) -> SquareValues {
+        normalize_square_values(&self.endgame_squares)
+    }
+}
+
+/// Exports the current patch placement opening and endgame tables for all
+/// normal patches as structured data.
+///
+/// # Returns
+///
+/// The table entries and derived square values for every normal patch ordered
+/// by the patch id.
+///
+/// # Complexity
+///
+/// `𝒪(𝑛 · 𝑚)` where `𝑛` is the amount of patches and `𝑚` is the amount of
+/// transformations of each patch.
+#[must_use]
+pub fn export_patch_placement_tables() -> Vec<PatchPlacementTableExport<'static>> {
+    export_given_patch_placement_tables(&PATCH_PLACEMENT_OPENING_TABLE, &PATCH_PLACEMENT_ENDGAME_TABLE)
+}
+
+/// Exports the given patch placement opening and endgame tables (e.g. the
+/// tables learned by a [`crate::PatchPlacementTableLearner`]) for all normal
+/// patches as structured data.
+///
+/// # Arguments
+///
+/// * `opening_table` - The opening table to export.
+/// * `endgame_table` - The endgame table to export.
+///
+/// # Returns
+///
+/// The table entries and derived square values for every normal patch ordered
+/// by the patch id.
+///
+/// # Complexity
+///
+/// `𝒪(𝑛 · 𝑚)` where `𝑛` is the amount of patches and `𝑚` is the amount of
+/// transformations of each patch.
+#[must_use]
+pub fn export_given_patch_placement_tables<'a>(
+    opening_table: &'a PatchPlacementTable,
+    endgame_table: &'a PatchPlacementTable,
+) -> Vec<PatchPlacementTableExport<'a>> {
+    (0..PatchManager::AMOUNT_OF_NORMAL_PATCHES)
+        .map(|patch_id| {
+            let opening = opening_table[patch_id as usize].as_slice();
+            let endgame = endgame_table[patch_id as usize].as_slice();
+
+            PatchPlacementTableExport {
+                patch_id,
+                opening,
+                endgame,
+                opening_squares: get_square_values(patch_id, opening),
+                endgame_squares: get_square_values(patch_id, endgame),
+            }
+        })
+        .collect()
+}
+
+/// Sums the table entries of all transformations of the patch that cover a
+/// square for every square of the quilt board.
+///
+/// # Arguments
+///
+/// * `patch_id` - The id of the patch.
+/// * `table_row` - The table entry for every transformation of the patch.
+///
+/// # Returns
+///
+/// The summed table entries for every square of the quilt board.
+#[must_use]
+pub fn get_square_values(patch_id: u8, table_row: &[f64]) -> SquareValues {
+    let mut squares = [[0.0; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
+
+    for (transformation, value) in PatchManager::get_transformations(patch_id).iter().zip(table_row) {
+        let board = QuiltBoard::from_bits(transformation.tiles);
+
+        for (row, row_values) in squares.iter_mut().enumerate() {
+            for (column, square) in row_values.iter_mut().enumerate() {
+                if board.get(row as u8, column as u8) {
+                    *square += value;
+                }
+            }
+        }
+    }
+
+    squares
+}
+
+/// Normalizes the square values to probabilities with the softmax function.
+///
+/// # Arguments
+///
+/// * `squares` - The values for every square of the quilt board.
+///
+/// # Returns
+///
+/// The probability for every square of the quilt board.
+#[must_use]
+pub fn normalize_square_values(squares: &SquareValues) -> SquareValues {
+    let mut probabilities = squares.map(|row| row.map(f64::exp));
+    let sum = probabilities.iter().flatten().sum::<f64>();
+
+    for probability in probabilities.iter_mut().flatten() {
+        *probability /= sum;
+    }
+
+    probabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::ActionId;
+
+    use super::*;
+
+    #[test]
+    fn test_table_rows_match_transformations() {
+        let tables = export_patch_placement_tables();
+        assert_eq!(PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize, tables.len());
+
+        for table in &tables {
+            let transformations = PatchManager::get_transformations(table.patch_id).len();
+            assert_eq!(
+                transformations,
+                table.opening.len(),
+                "Opening table row of patch {} has the wrong length",
+                table.patch_id
+            );
+            assert_eq!(
+                transformations,
+                table.endgame.len(),
+                "Endgame table row of patch {} has the wrong length",
+                table.patch_id
+            );
+        }
+    }
+
+    #[test]
+    fn test_learned_tables_are_exported() {
+        let mut learner = crate::PatchPlacementTableLearner::new();
+        learner.add_placement(ActionId::patch_placement(0, 0, 0, false), 0.0, true);
+        let (opening_table, endgame_table) = (learner.opening_table(), learner.endgame_table());
+
+        let tables = export_given_patch_placement_tables(&opening_table, &endgame_table);
+        assert_eq!(PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize, tables.len());
+        assert_eq!(opening_table[0].as_slice(), tables[0].opening);
+        assert!(tables[0].opening_squares.iter().flatten().any(|value| *value > 0.0));
+        assert!(tables[0].endgame_squares.iter().flatten().all(|value| *value == 0.0));
+    }
+
+    #[test]
+    fn test_square_probabilities_sum_to_one() {
+        for table in export_patch_placement_tables() {
+            for probabilities in [table.opening_square_probabilities(), table.endgame_square_probabilities()] {
+                let sum = probabilities.iter().flatten().sum::<f64>();
+                assert!((sum - 1.0).abs() < 1e-9, "Probabilities of patch {} sum to {sum}", table.patch_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use patchwork_core::{ActionId, GameRecord, PatchManager, PatchworkError, TerminationType};
+
+/// A patch placement table with an entry for every transformation of every
+/// normal patch. This is the shape of [`crate::PATCH_PLACEMENT_OPENING_TABLE`]
+/// and [`crate::PATCH_PLACEMENT_ENDGAME_TABLE`].
+pub type PatchPlacementTable = [Vec<f64>; PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize];
+
+/// Learns patch placement tables from a corpus of recorded games.
+///
+/// Every patch placement of a game is scored with `1` if the player placing
+/// the patch won the game and with `-1` otherwise (like the action scores of
+/// the empirical measurement). The scores are split into the opening and
+/// endgame table by the progress of the game at the time of the placement with
+/// the same linear interpolation the [`crate::TableActionOrderer`] uses. A
+/// table entry is the weighted sum of its scores divided by the sum of the
+/// weights plus [`PatchPlacementTableLearner::SMOOTHING`] so that rarely seen
+/// placements stay close to `0`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchPlacementTableLearner {
+    /// The summed weighted scores of the opening table.
+    opening_scores: PatchPlacementTable,
+    /// The summed weights of the opening table.
+    opening_weights: PatchPlacementTable,
+    /// The summed weighted scores of the endgame table.
+    endgame_scores: PatchPlacementTable,
+    /// The summed weights of the endgame table.
+    endgame_weights: PatchPlacementTable,
+    /// The amount of games that were added.
+    games: usize,
+}
+
+impl PatchPlacementTableLearner {
+    /// The weight added to the weights of each entry when building the tables.
+    pub const SMOOTHING: f64 = 10.0;
+
+    /// Creates a new [`PatchPlacementTableLearner`] without any games.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            opening_scores: Self::empty_table(),
+            opening_weights: Self::empty_table(),
+            endgame_scores: Self::empty_table(),
+            endgame_weights: Self::empty_table(),
+            games: 0,
+        }
+    }
+
+    /// Gets the amount of games that were added to the learner.
+    #[must_use]
+    pub const fn games(&self) -> usize {
+        self.games
+    }
+
+    /// Adds all patch placements of the recorded game to the learner.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The recorded game.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the game was added, `false` if the game was skipped because
+    /// it is not terminated.
+    ///
+    /// # Errors
+    ///
+    /// If the recorded actions cannot be replayed.
+    pub fn add_game(&mut self, record: &GameRecord) -> Result<bool, PatchworkError> {
+        let final_state = record.replay()?;
+        if !final_state.is_terminated() || record.is_empty() {
+            return Ok(false);
+        }
+
+        let player_1_won = final_state.get_termination_result().termination == TerminationType::Player1Won;
+
+        let mut state = record.initial_state.clone();
+        for (ply, action) in record.actions.iter().enumerate() {
+            #[allow(clippy::cast_precision_loss)]
+            let progress = ply as f64 / record.len() as f64;
+            let won = state.is_player_1() == player_1_won;
+
+            self.add_placement(*action, progress, won);
+            state.do_action(*action, false)?;
+        }
+
+        self.games += 1;
+        Ok(true)
+    }
+
+    /// Adds a single action to the learner. Actions that are not patch
+    /// placements are ignored.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action that was taken.
+    /// * `progress` - The progress of the game when the action was taken between `0.0` (start) and `1.0` (end).
+    /// * `won` - Whether the player that took the action won the game.
+    pub fn add_placement(&mut self, action: ActionId, progress: f64, won: bool) {
+        if !action.is_patch_placement() {
+            return;
+        }
+
+        let patch_id = action.get_patch_id() as usize;
+        let transformation = action.get_patch_transformation_index() as usize;
+        let score = if won { 1.0 } else { -1.0 };
+        let ratio = progress.clamp(0.0, 1.0);
+
+        self.opening_scores[patch_id][transformation] += score * (1.0 - ratio);
+        self.opening_weights[patch_id][transformation] += 1.0 - ratio;
+        self.endgame_scores[patch_id][transformation] += score * ratio;
+        self.endgame_weights[patch_id][transformation] += ratio;
+    }
+
+    /// Builds the learned opening table.
+    ///
+    /// # Returns
+    ///
+    /// The opening table in the shape of [`crate::PATCH_PLACEMENT_OPENING_TABLE`].
+    #[must_use]
+    pub fn opening_table(&self) -> PatchPlacementTable {
+        Self::build_table(&self.opening_scores, &self.opening_weights)
+    }
+
+    /// Builds the learned endgame table.
+    ///
+    /// # Returns
+    ///
+    /// The endgame table in the shape of [`crate::PATCH_PLACEMENT_ENDGAME_TABLE`].
+    #[must_use]
+    pub fn endgame_table(&self) -> PatchPlacementTable {
+        Self::build_table(&self.endgame_scores, &self.endgame_weights)
+    }
+
+    fn build_table(scores: &PatchPlacementTable, weights: &PatchPlacementTable) -> PatchPlacementTable {
+        std::array::from_fn(|patch_id| {
+            scores[patch_id]
+                .iter()
+                .zip(&weights[patch_id])
+                .map(|(score, weight)| score / (weight + Self::SMOOTHING))
+                .collect()
+        })
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    fn empty_table() -> PatchPlacementTable {
+        std::array::from_fn(|patch_id| vec![0.0; PatchManager::get_transformations(patch_id as u8).len()])
+    }
+}
+
+impl Default for PatchPlacementTableLearner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{GameOptions, Patchwork};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    fn play_random_game(seed: u64) -> GameRecord {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+        let mut record = GameRecord::new(state.clone());
+
+        while !state.is_terminated() {
+            let valid_actions = state.get_valid_actions();
+            let action = valid_actions[rng.gen_range(0..valid_actions.len())];
+            state.do_action(action, false).unwrap();
+            record.push(action);
+        }
+
+        record
+    }
+
+    #[test]
+    fn test_learned_tables_are_well_formed() {
+        let mut learner = PatchPlacementTableLearner::new();
+        for seed in 0..5 {
+            assert!(learner.add_game(&play_random_game(seed)).unwrap());
+        }
+        assert_eq!(5, learner.games());
+
+        for table in [learner.opening_table(), learner.endgame_table()] {
+            for (patch_id, row) in table.iter().enumerate() {
+                assert_eq!(PatchManager::get_transformations(patch_id as u8).len(), row.len());
+                assert!(row.iter().all(|value| value.is_finite() && value.abs() < 1.0));
+            }
+            assert!(table.iter().flatten().any(|value| *value != 0.0), "No placement was learned");
+        }
+    }
+
+    #[test]
+    fn test_unfinished_game_is_skipped() {
+        let mut record = play_random_game(42);
+        record.actions.truncate(3);
+
+        let mut learner = PatchPlacementTableLearner::new();
+        assert!(!learner.add_game(&record).unwrap());
+        assert_eq!(0, learner.games());
+        assert!(learner.opening_table().iter().flatten().all(|value| *value == 0.0));
+    }
+}
@@ -52,6 +52,9 @@ pub struct AlphaZeroOptions {
     pub end_condition: AlphaZeroEndCondition,
     /// Logging configuration on what to collect during the search.
     pub logging: Logging,
+    /// The seed of the random number generator for the Dirichlet noise at the
+    /// root node. `None` to seed from entropy.
+    pub seed: Option<u64>,
 }
 
 impl AlphaZeroOptions {
@@ -70,6 +73,7 @@ impl AlphaZeroOptions {
             parallelization,
             end_condition,
             logging,
+            seed: None,
         }
     }
 
@@ -118,6 +122,7 @@ impl Default for AlphaZeroOptions {
             batch_size: NonZeroUsize::new(20).unwrap(),
             parallelization: Self::default_parallelization(),
             logging: Logging::default(),
+            seed: None,
         }
     }
 }
@@ -94,8 +94,24 @@ impl<Policy: TreePolicy> Player for AlphaZeroPlayer<Policy> {
     }
 
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.get_restricted_action(game, &[])
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        // A forced move is played without searching
+        let valid_actions = game.get_valid_actions();
+        if valid_actions.len() == 1 {
+            return Ok(valid_actions[0]);
+        }
+        if let [action] = actions {
+            return Ok(*action);
+        }
+
         let games = [game];
-        let policies = self.search_tree.search(&games)?;
+        self.search_tree.set_root_actions(actions);
+        let policies = self.search_tree.search(&games);
+        self.search_tree.set_root_actions(&[]);
+        let policies = policies?;
 
         let (available_actions_tensor, mut corresponding_action_ids) =
             map_games_to_action_tensors(&games, &self.options.device)?;
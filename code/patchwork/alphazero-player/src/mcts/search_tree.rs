@@ -1,8 +1,9 @@
 use std::sync::Arc;
-use std::{rc::Rc, sync::atomic::AtomicBool};
+use std::{cell::RefCell, rc::Rc, sync::atomic::AtomicBool};
 
 use candle_core::{Device, Tensor};
-use patchwork_core::{NaturalActionId, Patchwork, PlayerResult, TreePolicy};
+use patchwork_core::{reset_thread_affinity, ActionId, GameRng, NaturalActionId, Patchwork, PlayerResult, TreePolicy};
+use rand::SeedableRng;
 use rand_distr::{Dirichlet, Distribution};
 
 use crate::AlphaZeroEndCondition;
@@ -33,10 +34,14 @@ pub struct SearchTree<
     dirichlet_noise: Dirichlet<f32>,
     /// The epsilon value for the Dirichlet noise. This is the fraction of the noise to add to the policy.
     dirichlet_epsilon: f32,
+    /// The random number generator to sample the dirichlet noise with.
+    rng: RefCell<GameRng>,
     /// Whether the network is in training or evaluation/interference mode
     train: bool,
     /// Whether to apply dirichlet noise to the root node or not
     apply_dirichlet_noise: bool,
+    /// The actions the root nodes are restricted to. Empty if all valid actions may be chosen.
+    root_actions: Vec<ActionId>,
     /// The policy to select nodes during the selection phase. Moved to Search Data during the search.
     tree_policy: Option<Policy>,
 }
@@ -80,8 +85,10 @@ impl<
         Self {
             train,
             apply_dirichlet_noise: false,
+            root_actions: vec![],
             dirichlet_epsilon,
             dirichlet_noise,
+            rng: RefCell::new(options.seed.map_or_else(GameRng::from_entropy, GameRng::seed_from_u64)),
             tree_policy: Some(tree_policy),
             network: Some(network),
             options,
@@ -114,6 +121,21 @@ impl<
         self.apply_dirichlet_noise = apply;
     }
 
+    /// Sets the actions the root nodes are restricted to. Only these actions
+    /// are expanded at the root nodes so that the whole search is spent on
+    /// them.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - The valid actions the search is restricted to. If empty all valid actions may be chosen.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the number of given actions.
+    pub fn set_root_actions(&mut self, actions: &[ActionId]) {
+        self.root_actions = actions.to_vec();
+    }
+
     /// Searches for the best action to take in the given game states.
     ///
     /// # Arguments
@@ -147,6 +169,7 @@ impl<
                     let search_data = &search_data;
                     let worker_flag = Arc::clone(&flag);
                     s.spawn(move || {
+                        reset_thread_affinity();
                         let worker = WorkerThread { search_data };
                         while !worker_flag.load(std::sync::atomic::Ordering::Acquire) {
                             worker.iteration()?;
@@ -245,7 +268,7 @@ impl<
 
         if self.apply_dirichlet_noise {
             let noise = Tensor::from_vec(
-                self.dirichlet_noise.sample(&mut rand::thread_rng()),
+                self.dirichlet_noise.sample(&mut *self.rng.borrow_mut()),
                 (NaturalActionId::AMOUNT_OF_NORMAL_NATURAL_ACTION_IDS,),
                 &self.options.device,
             )?
@@ -274,10 +297,23 @@ impl<
             .map(|(index, state)| {
                 let allocator = AreaAllocator::new();
                 let root = allocator.new_node((*(*state)).clone(), None, None, None);
-                let policy = &policies[index];
+                let mut policy = policies[index].clone();
                 let corresponding_actions = corresponding_action_ids.pop_front().unwrap();
 
-                Node::expand(root, policy, &corresponding_actions, &allocator)?;
+                if !self.root_actions.is_empty() {
+                    // actions with a probability of zero are not expanded
+                    for (probability, action) in policy.iter_mut().zip(&corresponding_actions) {
+                        if !self.root_actions.contains(action) {
+                            *probability = 0.0;
+                        }
+                    }
+                    let sum = policy.iter().sum::<f32>();
+                    for probability in &mut policy {
+                        *probability /= sum;
+                    }
+                }
+
+                Node::expand(root, &policy, &corresponding_actions, &allocator)?;
 
                 Ok(GameState { allocator, root })
             })
@@ -1,6 +1,6 @@
 use candle_core::{DType, Device, Module, Result, Tensor};
 use candle_nn::{Embedding, LSTMConfig, Linear, VarBuilder, LSTM, RNN};
-use patchwork_core::{time_board_flags, Patch, PatchManager, Patchwork, QuiltBoard, TimeBoard};
+use patchwork_core::{time_board_flags, PatchCircle, PatchManager, Patchwork, QuiltBoard, TimeBoard};
 
 /// A game encoder encodes a game of patchwork into a tensor.
 ///
@@ -121,7 +121,7 @@ impl<const PATCH_LAYERS: usize> GameEncoder<PATCH_LAYERS> {
     /// A tensor of shape ([`PATCH_LAYERS`] + 1, 9, 9) containing
     /// the encoded patches.
     #[rustfmt::skip]
-    fn encode_patches(&self, patches: &[&'static Patch]) -> Result<Tensor> {
+    fn encode_patches(&self, patches: &PatchCircle) -> Result<Tensor> {
         let beginning_patch_ids = Tensor::from_iter(patches.iter().take(PATCH_LAYERS).map(|patch| patch.id), &self.device)?;
         let beginning_patches = self.patch_embeddings.forward(&beginning_patch_ids)?.t()?;
 
@@ -12,7 +12,7 @@ use std::{
 
 use candle_core::{DType, Device, IndexOp, Tensor};
 use candle_nn::{Optimizer, VarBuilder, VarMap, SGD};
-use patchwork_core::{Logging, Patchwork, PlayerResult, TerminationType, TreePolicy};
+use patchwork_core::{compute_value_targets, GameRecord, Logging, Patchwork, PlayerResult, TreePolicy};
 use rand::{seq::SliceRandom, thread_rng};
 use rand_distr::{Distribution, WeightedIndex};
 use regex::Regex;
@@ -34,7 +34,8 @@ pub struct Trainer {
 struct History {
     state: Patchwork,
     policy: Tensor,
-    outcome: TerminationType,
+    /// The value target from the perspective of the player to move in the state.
+    value_target: f32,
 }
 
 impl Trainer {
@@ -198,6 +199,7 @@ impl Trainer {
             iteration += 1;
             let mut history = vec![];
             let mut game = Patchwork::get_initial_state(None);
+            let mut record = GameRecord::new(game.clone());
 
             for _ in tqdm(0..64)
                 .style(tqdm::Style::Block)
@@ -239,14 +241,16 @@ impl Trainer {
 
                 let action = actions[dist.sample(&mut thread_rng())];
                 game.do_action(action, false)?;
+                record.push(action);
 
                 if game.is_terminated() {
                     let history = std::mem::take(&mut history);
-                    for partial_history in history {
+                    let value_targets = compute_value_targets(&record);
+                    for (partial_history, value_target) in history.into_iter().zip(value_targets) {
                         channel.send(History {
                             state: partial_history.state,
                             policy: partial_history.policy,
-                            outcome: game.get_termination_result().termination,
+                            value_target,
                         })?;
                     }
                 }
@@ -255,14 +259,6 @@ impl Trainer {
     }
 
     fn train(&self, training_set: &mut [&History], var_map: &VarMap, log_file: &fs::File) -> PlayerResult<()> {
-        fn get_value_from_outcome(outcome: TerminationType, is_current_player_1: bool, device: &Device) -> Tensor {
-            let multiplier: f32 = if is_current_player_1 { 1.0 } else { -1.0 };
-            match outcome {
-                TerminationType::Player1Won => Tensor::new(multiplier * 1.0, device).unwrap(),
-                TerminationType::Player2Won => Tensor::new(multiplier * -1.0, device).unwrap(),
-            }
-        }
-
         let var_builder = VarBuilder::from_varmap(var_map, DType::F32, &self.device);
         let network = DefaultPatchZero::new(var_builder, self.device.clone())?;
 
@@ -280,8 +276,7 @@ impl Trainer {
             let (games, policy_targets, value_targets) = batch.iter().fold(
                 (vec![], vec![], vec![]),
                 |(mut games, mut policy_targets, mut value_targets), history| {
-                    let value_target =
-                        get_value_from_outcome(history.outcome, history.state.is_player_1(), &self.device);
+                    let value_target = Tensor::new(history.value_target, &self.device).unwrap();
 
                     games.push(history.state.clone());
                     policy_targets.push(history.policy.clone());
@@ -1,10 +1,25 @@
 use std::fs::OpenOptions;
 use std::io::{Result, Write};
-
-use action_orderer::{PATCH_PLACEMENT_ENDGAME_TABLE, PATCH_PLACEMENT_OPENING_TABLE};
-use patchwork_core::{PatchManager, QuiltBoard};
-
+use std::path::Path;
+
+use action_orderer::{
+    export_given_patch_placement_tables, export_patch_placement_tables, PatchPlacementTable, PatchPlacementTableExport,
+    PatchPlacementTableLearner, SquareValues,
+};
+use patchwork_core::GameRecord;
+
+/// Exports the patch placement tables to `values.csv`. If a directory with
+/// recorded games (the `games_*.game.bin` files written by `compare
+/// --save-full`) is given as the first argument, the tables are learned
+/// from these games instead of exporting the current tables.
 fn main() {
+    let learned_tables = std::env::args().nth(1).map(|directory| {
+        learn_tables(Path::new(&directory)).unwrap_or_else(|error| {
+            eprintln!("Could not learn the tables from {directory}: {error}");
+            std::process::exit(1);
+        })
+    });
+
     let file = OpenOptions::new()
         .write(true)
         .create(true)
@@ -13,95 +28,78 @@ fn main() {
         .unwrap();
     drop(file);
 
-    for patch_id in 0..PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize {
+    let tables = match &learned_tables {
+        Some((opening, endgame)) => export_given_patch_placement_tables(opening, endgame),
+        None => export_patch_placement_tables(),
+    };
+    for table in tables {
+        let patch_id = table.patch_id;
         let to_console =
             patch_id == 17 || patch_id == 20 || patch_id == 21 || patch_id == 22 || patch_id == 23 || patch_id == 24;
 
-        do_single_patch(patch_id as u8, to_console).unwrap();
+        do_single_patch(&table, to_console).unwrap();
     }
 }
 
-#[allow(clippy::needless_range_loop)]
-fn do_single_patch(patch_id: u8, output_to_console: bool) -> Result<()> {
-    let mut opening = [[0.0; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
-    let mut endgame = [[0.0; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
-
-    for (index, transformation) in PatchManager::get_transformations(patch_id).iter().enumerate() {
-        let board = QuiltBoard::from_bits(transformation.tiles);
-
-        for row in 0..QuiltBoard::ROWS as usize {
-            for column in 0..QuiltBoard::COLUMNS as usize {
-                if board.get(row as u8, column as u8) {
-                    opening[row][column] += PATCH_PLACEMENT_OPENING_TABLE[patch_id as usize][index];
-                    endgame[row][column] += PATCH_PLACEMENT_ENDGAME_TABLE[patch_id as usize][index];
-                }
-            }
+/// Learns the opening and endgame table from all recorded games in the directory.
+fn learn_tables(
+    directory: &Path,
+) -> std::result::Result<(PatchPlacementTable, PatchPlacementTable), Box<dyn std::error::Error>> {
+    let mut files = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>>>()?;
+    files.retain(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("games_") && name.ends_with(".game.bin"))
+    });
+    files.sort();
+
+    let mut learner = PatchPlacementTableLearner::new();
+    for file in files {
+        let records: Vec<GameRecord> = bincode::deserialize_from(std::io::BufReader::new(std::fs::File::open(file)?))?;
+        for record in &records {
+            learner.add_game(record)?;
         }
     }
+    println!("Learned the tables from {} games", learner.games());
+
+    Ok((learner.opening_table(), learner.endgame_table()))
+}
+
+fn do_single_patch(table: &PatchPlacementTableExport<'_>, output_to_console: bool) -> Result<()> {
+    let patch_id = table.patch_id;
 
     if output_to_console {
         println!("# Opening Patch {patch_id:?}");
-        println!("patch_{patch_id}_op = {opening:?}");
+        println!("patch_{patch_id}_op = {:?}", table.opening_squares);
         println!("# Endgame Patch {patch_id:?}");
-        println!("patch_{patch_id}_end = {endgame:?}");
+        println!("patch_{patch_id}_end = {:?}", table.endgame_squares);
         println!();
     }
 
-    let opening = normalize(opening);
-    let endgame = normalize(endgame);
-
     let mut file = OpenOptions::new().append(true).create(true).open("values.csv")?;
 
     writeln!(file, "Opening Patch {patch_id:?}")?;
-    for row in 0..QuiltBoard::ROWS as usize {
-        writeln!(
-            file,
-            "{}",
-            opening[row]
-                .iter()
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>()
-                .join(";")
-        )?;
-    }
+    write_squares(&mut file, &table.opening_square_probabilities())?;
     writeln!(file, "Endgame Patch {patch_id:?}")?;
-    for row in 0..QuiltBoard::ROWS as usize {
+    write_squares(&mut file, &table.endgame_square_probabilities())?;
+    writeln!(file)?;
+
+    Ok(())
+}
+
+fn write_squares(file: &mut impl Write, squares: &SquareValues) -> Result<()> {
+    for row in squares {
         writeln!(
             file,
             "{}",
-            endgame[row]
-                .iter()
+            row.iter()
                 .map(std::string::ToString::to_string)
                 .collect::<Vec<_>>()
                 .join(";")
         )?;
     }
-    writeln!(file)?;
 
     Ok(())
 }
-
-#[allow(clippy::needless_range_loop)]
-fn normalize(
-    mut board: [[f64; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize],
-) -> [[f64; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize] {
-    let mut exp_board = [[0.0; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
-
-    let mut sum = 0.0;
-
-    for row in 0..QuiltBoard::ROWS as usize {
-        for column in 0..QuiltBoard::COLUMNS as usize {
-            let value = board[row][column];
-            exp_board[row][column] = value.exp();
-            sum += exp_board[row][column];
-        }
-    }
-
-    for row in 0..QuiltBoard::ROWS as usize {
-        for column in 0..QuiltBoard::COLUMNS as usize {
-            board[row][column] = exp_board[row][column] / sum;
-        }
-    }
-
-    board
-}
@@ -0,0 +1,295 @@
+use std::collections::HashMap;
+
+use patchwork_core::{ActionId, PatchManager, Termination, TerminationType};
+
+use crate::deserialization::Game;
+
+/// The key of walking actions.
+const WALKING_KEY: usize = 0;
+/// The first key of special patch placements. The key of a special patch
+/// placement is this offset plus the quilt board index.
+const SPECIAL_PATCH_PLACEMENT_OFFSET: usize = 1;
+/// The first key of normal patch placements. The key of a patch placement is
+/// this offset plus the transformations of all patches with a lower id plus
+/// the transformation index.
+const PATCH_PLACEMENT_OFFSET: usize = 82;
+
+/// The aggregated scores of an action at a relative point of the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionScore {
+    /// The description of the action, e.g. `patch_placement(03, 012)`.
+    pub description: String,
+    /// The point of the game as the ply divided by the length of the game.
+    pub percentage: f64,
+    /// The sum of the scores where a win or loss is weighted with the score
+    /// difference at the end of the game plus one.
+    pub score: i64,
+    /// The sum of the wins (`1`) and losses (`-1`).
+    pub win_loss: i64,
+    /// How often the action was played at this point of the game.
+    pub count: u64,
+}
+
+/// The sums of an [`ActionScore`] without the description and percentage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct ScoreSums {
+    score: i64,
+    win_loss: i64,
+    count: u64,
+}
+
+/// How the scores are stored.
+#[derive(Debug, Clone, PartialEq)]
+enum Storage {
+    /// The sums per action key and exact percentage. The amount of entries
+    /// grows with the amount of different game lengths.
+    Map(HashMap<(usize, F64Key), ScoreSums>),
+    /// The sums per action key and percentage bucket in a preallocated table
+    /// with `buckets` entries per action key.
+    Table(Vec<ScoreSums>),
+}
+
+/// Gathers how often every action was played at which point of a game and
+/// how well the players did that played the action.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionScores {
+    /// The amount of buckets the percentages are rounded down to or `None`
+    /// if the exact percentages are used.
+    buckets: Option<usize>,
+    /// The aggregated sums.
+    storage: Storage,
+}
+
+impl ActionScores {
+    /// Creates a new empty [`ActionScores`] that stores the sums in a map.
+    ///
+    /// # Arguments
+    ///
+    /// * `buckets` - The amount of buckets the percentages are rounded down to or `None` to use the exact
+    ///   percentages.
+    #[must_use]
+    pub fn new(buckets: Option<usize>) -> Self {
+        Self {
+            buckets: buckets.map(|buckets| buckets.max(1)),
+            storage: Storage::Map(HashMap::new()),
+        }
+    }
+
+    /// Creates a new empty [`ActionScores`] that stores the sums in a table
+    /// that is allocated once. As the amount of actions is fixed, the memory
+    /// does not grow with the amount of games.
+    ///
+    /// # Arguments
+    ///
+    /// * `buckets` - The amount of buckets the percentages are rounded down to.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` memory where `𝑛` is the amount of action keys times the amount of buckets.
+    #[must_use]
+    pub fn bounded(buckets: usize) -> Self {
+        let buckets = buckets.max(1);
+        Self {
+            buckets: Some(buckets),
+            storage: Storage::Table(vec![ScoreSums::default(); Self::amount_of_keys() * buckets]),
+        }
+    }
+
+    /// Adds all actions of the given game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The recorded game.
+    /// * `termination` - The termination result of the game (see
+    ///   [`Game::get_termination_result`]).
+    pub fn add_game(&mut self, game: &Game, termination: Termination) {
+        for (ply, turn) in game.turns.iter().enumerate() {
+            let Some(action) = turn.action else {
+                continue;
+            };
+
+            let player_1_won = termination.termination == TerminationType::Player1Won;
+            let win_loss = if player_1_won == turn.state.is_player_1() {
+                1
+            } else {
+                -1
+            };
+            let score = win_loss * (i64::from(termination.player_1_score - termination.player_2_score).abs() + 1);
+
+            let percentage = ply as f64 / game.turns.len() as f64;
+            self.add_sample(Self::get_key(action), percentage, score, win_loss);
+        }
+    }
+
+    fn add_sample(&mut self, key: usize, percentage: f64, score: i64, win_loss: i64) {
+        let sums = match (&mut self.storage, self.buckets) {
+            (Storage::Map(map), None) => map.entry((key, F64Key(percentage))).or_default(),
+            (Storage::Map(map), Some(buckets)) => {
+                let bucket = Self::get_bucket(percentage, buckets);
+                map.entry((key, F64Key(bucket as f64 / buckets as f64))).or_default()
+            }
+            (Storage::Table(table), Some(buckets)) => &mut table[key * buckets + Self::get_bucket(percentage, buckets)],
+            (Storage::Table(_), None) => unreachable!("[ActionScores::add_sample] Table without buckets"),
+        };
+
+        sums.score += score;
+        sums.win_loss += win_loss;
+        sums.count += 1;
+    }
+
+    /// Gets the aggregated scores of all actions that were played.
+    ///
+    /// # Returns
+    ///
+    /// The scores sorted by the walking actions, the special patch
+    /// placements and the patch placements and within them by the
+    /// description and the percentage.
+    #[must_use]
+    pub fn get_scores(&self) -> Vec<ActionScore> {
+        let mut scores = match &self.storage {
+            Storage::Map(map) => map
+                .iter()
+                .map(|((key, percentage), sums)| (*key, percentage.0, *sums))
+                .collect::<Vec<_>>(),
+            Storage::Table(table) => {
+                let buckets = self.buckets.unwrap();
+                table
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, sums)| sums.count > 0)
+                    .map(|(index, sums)| (index / buckets, (index % buckets) as f64 / buckets as f64, *sums))
+                    .collect::<Vec<_>>()
+            }
+        };
+        // the keys are in the same order as the descriptions
+        scores.sort_by_key(|(key, percentage, _)| (*key, F64Key(*percentage)));
+
+        scores
+            .into_iter()
+            .map(|(key, percentage, sums)| ActionScore {
+                description: Self::get_description(key),
+                percentage,
+                score: sums.score,
+                win_loss: sums.win_loss,
+                count: sums.count,
+            })
+            .collect()
+    }
+
+    /// Gets the bucket of the given percentage.
+    fn get_bucket(percentage: f64, buckets: usize) -> usize {
+        ((percentage * buckets as f64) as usize).min(buckets - 1)
+    }
+
+    /// Gets the amount of different action keys.
+    fn amount_of_keys() -> usize {
+        PATCH_PLACEMENT_OFFSET
+            + (0..PatchManager::AMOUNT_OF_NORMAL_PATCHES)
+                .map(PatchManager::transformation_count)
+                .sum::<usize>()
+    }
+
+    /// Gets the key of the given action. The keys are dense and ordered
+    /// like the descriptions of the actions.
+    fn get_key(action: ActionId) -> usize {
+        if action.is_walking() {
+            WALKING_KEY
+        } else if action.is_special_patch_placement() {
+            SPECIAL_PATCH_PLACEMENT_OFFSET + usize::from(action.get_quilt_board_index())
+        } else if action.is_patch_placement() {
+            // the transformations of all patches with a lower id come first
+            let transformation_offset = (0..action.get_patch_id())
+                .map(PatchManager::transformation_count)
+                .sum::<usize>();
+            PATCH_PLACEMENT_OFFSET + transformation_offset + usize::from(action.get_patch_transformation_index())
+        } else {
+            unreachable!("[ActionScores::get_key] Other actions types should not be in the dataset")
+        }
+    }
+
+    /// Gets the description of the action with the given key.
+    fn get_description(key: usize) -> String {
+        if key == WALKING_KEY {
+            return "walking".to_string();
+        }
+        if key < PATCH_PLACEMENT_OFFSET {
+            return format!("special_patch_placement({:02})", key - SPECIAL_PATCH_PLACEMENT_OFFSET);
+        }
+
+        let mut transformation_index = key - PATCH_PLACEMENT_OFFSET;
+        for patch_id in 0..PatchManager::AMOUNT_OF_NORMAL_PATCHES {
+            let transformations = PatchManager::transformation_count(patch_id);
+            if transformation_index < transformations {
+                return format!("patch_placement({patch_id:02}, {transformation_index:03})");
+            }
+            transformation_index -= transformations;
+        }
+
+        unreachable!("[ActionScores::get_description] Invalid action key")
+    }
+}
+
+/// A wrapper to use a percentage as a key of a map.
+#[derive(Debug, Clone, Copy)]
+struct F64Key(pub f64);
+
+impl std::cmp::PartialEq for F64Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+impl std::cmp::Eq for F64Key {}
+impl std::cmp::PartialOrd for F64Key {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl std::cmp::Ord for F64Key {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.partial_cmp(&other.0).unwrap()
+    }
+}
+impl std::hash::Hash for F64Key {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.to_bits().hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{generate_game, GeneratorPlayer};
+
+    use super::*;
+
+    #[test]
+    fn test_bounded_scores_equal_map_scores() {
+        const BUCKETS: usize = 20;
+
+        let mut map = ActionScores::new(Some(BUCKETS));
+        let mut table = ActionScores::bounded(BUCKETS);
+        let mut actions = 0;
+        for seed in 0..10 {
+            let game = generate_game(seed, GeneratorPlayer::Random, GeneratorPlayer::RandomIncome);
+            actions += game.turns.iter().filter(|turn| turn.action.is_some()).count() as u64;
+            map.add_game(&game, game.get_termination_result());
+            table.add_game(&game, game.get_termination_result());
+        }
+
+        let scores = table.get_scores();
+        assert_eq!(map.get_scores(), scores);
+        assert_eq!(actions, scores.iter().map(|score| score.count).sum::<u64>());
+    }
+
+    #[test]
+    fn test_keys_are_ordered_like_descriptions() {
+        let descriptions = (0..ActionScores::amount_of_keys())
+            .map(ActionScores::get_description)
+            .collect::<Vec<_>>();
+
+        assert_eq!("walking", descriptions[0]);
+        assert_eq!("special_patch_placement(80)", descriptions[PATCH_PLACEMENT_OFFSET - 1]);
+        assert!(descriptions[PATCH_PLACEMENT_OFFSET..]
+            .windows(2)
+            .all(|pair| pair[0] < pair[1]));
+    }
+}
@@ -0,0 +1,59 @@
+use empirical_measurement::{
+    deserialization::GameLoader,
+    puzzles::{write_puzzles, PuzzleFinder},
+};
+
+fn main() {
+    let cmd = clap::Command::new("find-puzzles")
+        .bin_name("find-puzzles")
+        .about("Extracts positions from recorded games where one action is decisively better than all others")
+        .arg(
+            clap::Arg::new("input")
+                .short('i')
+                .long("input")
+                .alias("in")
+                .help("The path to the directory with the recorded games")
+                .required(true)
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            clap::Arg::new("out")
+                .short('o')
+                .long("output")
+                .alias("out")
+                .help("The path to the CSV file where to store the puzzles")
+                .default_value("puzzles.csv")
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            clap::Arg::new("depth")
+                .short('d')
+                .long("depth")
+                .help("The depth every position is searched to")
+                .default_value("4")
+                .value_parser(clap::value_parser!(u8)),
+        )
+        .arg(
+            clap::Arg::new("gap")
+                .short('g')
+                .long("gap")
+                .help("The minimum evaluation difference between the best and the second best action")
+                .default_value("20")
+                .value_parser(clap::value_parser!(i32)),
+        );
+
+    let matches = cmd.get_matches();
+    let input = matches.get_one::<std::path::PathBuf>("input").unwrap();
+    let output = matches.get_one::<std::path::PathBuf>("out").unwrap();
+    let mut finder = PuzzleFinder::new(
+        *matches.get_one::<u8>("depth").unwrap(),
+        *matches.get_one::<i32>("gap").unwrap(),
+    );
+
+    println!("Searching puzzles in {}", input.display());
+    let puzzles = GameLoader::new(input, None)
+        .flat_map(|game| finder.find_in_game(&game))
+        .collect::<Vec<_>>();
+    let written = write_puzzles(output, &puzzles).unwrap();
+    println!("Wrote {written} puzzles to {}", output.display());
+}
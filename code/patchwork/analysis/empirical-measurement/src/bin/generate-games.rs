@@ -0,0 +1,75 @@
+use empirical_measurement::generation::{generate_games, GenerationOptions, GeneratorPlayer};
+
+fn main() {
+    let cmd = clap::Command::new("generate-games")
+        .bin_name("generate-games")
+        .about("Generates deterministic self-play games in the recorded game format")
+        .arg(
+            clap::Arg::new("out")
+                .short('o')
+                .long("output")
+                .alias("out")
+                .help("The path to the directory where to store the generated games")
+                .required(true)
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            clap::Arg::new("games")
+                .short('n')
+                .long("games")
+                .help("The amount of games to generate")
+                .default_value("1000")
+                .value_parser(clap::value_parser!(usize)),
+        )
+        .arg(
+            clap::Arg::new("seed")
+                .short('s')
+                .long("seed")
+                .help("The seed of the first game. Game i is played with seed + i")
+                .default_value("0")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            clap::Arg::new("player-1")
+                .long("player-1")
+                .alias("p1")
+                .help("The first player (random, random-income)")
+                .default_value("random")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            clap::Arg::new("player-2")
+                .long("player-2")
+                .alias("p2")
+                .help("The second player (random, random-income)")
+                .default_value("random")
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            clap::Arg::new("games-per-file")
+                .long("games-per-file")
+                .help("The maximum amount of games per file")
+                .default_value("1000")
+                .value_parser(clap::value_parser!(usize)),
+        );
+
+    let matches = cmd.get_matches();
+    let get_player = |id: &str| {
+        matches.get_one::<String>(id).unwrap().parse::<GeneratorPlayer>().unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(1);
+        })
+    };
+    let output = matches.get_one::<std::path::PathBuf>("out").unwrap();
+    let options = GenerationOptions {
+        games: *matches.get_one::<usize>("games").unwrap(),
+        base_seed: *matches.get_one::<u64>("seed").unwrap(),
+        player_1: get_player("player-1"),
+        player_2: get_player("player-2"),
+        games_per_file: *matches.get_one::<usize>("games-per-file").unwrap(),
+    };
+
+    println!("Generating {} games to {}", options.games, output.display());
+    let files = generate_games(output, &options).unwrap();
+    println!("Wrote {} games to {files} files", options.games);
+}
@@ -0,0 +1,78 @@
+use empirical_measurement::{
+    deserialization::GameLoader,
+    move_agreement::{write_disagreements, AgreementEngine, MoveAgreementAnalyzer},
+};
+use evaluator::StaticEvaluator;
+use patchwork_core::PositionCorpus;
+
+fn main() {
+    let cmd = clap::Command::new("move-agreement")
+        .bin_name("move-agreement")
+        .about("Reports how often two engines choose the same action and the evaluation gap where they disagree")
+        .arg(
+            clap::Arg::new("engine-1")
+                .long("engine-1")
+                .alias("e1")
+                .help("The first engine (greedy, minimax, minimax-<depth>)")
+                .required(true)
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            clap::Arg::new("engine-2")
+                .long("engine-2")
+                .alias("e2")
+                .help("The second engine (greedy, minimax, minimax-<depth>)")
+                .required(true)
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            clap::Arg::new("input")
+                .short('i')
+                .long("input")
+                .alias("in")
+                .help("The path to the directory with the recorded games to take the positions from")
+                .required(false)
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        )
+        .arg(
+            clap::Arg::new("out")
+                .short('o')
+                .long("output")
+                .alias("out")
+                .help("The path to the CSV file where to store the disagreements")
+                .default_value("disagreements.csv")
+                .value_parser(clap::value_parser!(std::path::PathBuf)),
+        );
+
+    let matches = cmd.get_matches();
+    let get_engine = |id: &str| {
+        matches.get_one::<String>(id).unwrap().parse::<AgreementEngine>().unwrap_or_else(|error| {
+            eprintln!("{error}");
+            std::process::exit(1);
+        })
+    };
+    let mut engine_1 = get_engine("engine-1").create();
+    let mut engine_2 = get_engine("engine-2").create();
+    let output = matches.get_one::<std::path::PathBuf>("out").unwrap();
+
+    let positions = matches.get_one::<std::path::PathBuf>("input").map_or_else(PositionCorpus::standard, |input| {
+        GameLoader::new(input, None)
+            .flat_map(|game| game.turns.into_iter().map(|turn| turn.state))
+            .collect()
+    });
+
+    println!("Comparing {} and {} on {} positions", engine_1.name(), engine_2.name(), positions.len());
+    let agreement = MoveAgreementAnalyzer::new(StaticEvaluator::new())
+        .analyze(engine_1.as_mut(), engine_2.as_mut(), &positions)
+        .unwrap();
+    println!(
+        "Agreement: {:.2}% ({}/{} positions)",
+        agreement.agreement_percentage(),
+        agreement.agreements,
+        agreement.positions
+    );
+    println!("Average evaluation gap: {:.2}", agreement.average_evaluation_gap());
+
+    let written = write_disagreements(output, &agreement.disagreements).unwrap();
+    println!("Wrote {written} disagreements to {}", output.display());
+}
@@ -0,0 +1,59 @@
+use empirical_measurement::patch_timing::{PatchTimingProblem, ScheduledPurchase};
+use patchwork_core::{GameOptions, Notation, Patchwork};
+
+fn main() {
+    let cmd = clap::Command::new("patch-timing")
+        .bin_name("patch-timing")
+        .about("Plans the purchases of the player to move that end the game with the most buttons")
+        .arg(
+            clap::Arg::new("state")
+                .short('s')
+                .long("state")
+                .help("The state in the patchwork notation. Defaults to the initial state of the seed")
+                .required(false)
+                .value_parser(clap::value_parser!(String)),
+        )
+        .arg(
+            clap::Arg::new("seed")
+                .long("seed")
+                .help("The seed of the initial state if no state is given")
+                .default_value("42")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            clap::Arg::new("patches")
+                .short('p')
+                .long("patches")
+                .help("The amount of patches from the front of the patch circle to plan with")
+                .default_value("8")
+                .value_parser(clap::value_parser!(usize)),
+        );
+
+    let matches = cmd.get_matches();
+    let state = matches.get_one::<String>("state").map_or_else(
+        || Patchwork::get_initial_state(Some(GameOptions::new(*matches.get_one::<u64>("seed").unwrap()))),
+        |notation| {
+            Patchwork::load_from_notation(notation).unwrap_or_else(|error| {
+                eprintln!("Invalid state: {error}");
+                std::process::exit(1);
+            })
+        },
+    );
+
+    let problem = PatchTimingProblem::from_state(&state, *matches.get_one::<usize>("patches").unwrap());
+    let schedule = problem.solve().unwrap_or_else(|error| {
+        eprintln!("{error}");
+        std::process::exit(1);
+    });
+
+    if schedule.purchases.is_empty() {
+        println!("No patch pays off. Walk to the end of the time board.");
+    }
+    for ScheduledPurchase { patch, position } in &schedule.purchases {
+        println!(
+            "Position {position:>2}: buy patch {:>2} (button cost {}, time cost {}, income {})",
+            patch.id, patch.button_cost, patch.time_cost, patch.button_income
+        );
+    }
+    println!("Final button balance: {}", schedule.final_button_balance);
+}
@@ -0,0 +1,132 @@
+use patchwork_core::{Termination, TerminationType};
+
+use crate::deserialization::Game;
+
+/// Tracks how much of the quilt boards of the winners and the losers is
+/// covered at every point of a game.
+///
+/// The states of a game are grouped into buckets of consecutive plies. For
+/// every bucket the average fill percentage (see
+/// [`patchwork_core::QuiltBoard::percent_full`]) of the quilt board of the
+/// eventual winner and loser is gathered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardCoverage {
+    /// The amount of plies that are grouped into one bucket.
+    bucket_size: usize,
+    /// The amount of samples and the summed fill percentages of the winners
+    /// and losers per bucket.
+    buckets: Vec<(usize, f64, f64)>,
+}
+
+impl BoardCoverage {
+    /// Creates a new empty [`BoardCoverage`].
+    ///
+    /// # Arguments
+    ///
+    /// * `bucket_size` - The amount of plies that are grouped into one bucket.
+    #[must_use]
+    pub fn new(bucket_size: usize) -> Self {
+        Self {
+            bucket_size: bucket_size.max(1),
+            buckets: vec![],
+        }
+    }
+
+    /// Adds all states of the given game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The recorded game.
+    /// * `termination` - The termination result of the game (see
+    ///   [`Game::get_termination_result`]).
+    pub fn add_game(&mut self, game: &Game, termination: Termination) {
+        let player_1_won = termination.termination == TerminationType::Player1Won;
+
+        for (ply, turn) in game.turns.iter().enumerate() {
+            let player_1_fill = f64::from(turn.state.player_1.quilt_board.percent_full());
+            let player_2_fill = f64::from(turn.state.player_2.quilt_board.percent_full());
+            let (winner_fill, loser_fill) = if player_1_won {
+                (player_1_fill, player_2_fill)
+            } else {
+                (player_2_fill, player_1_fill)
+            };
+
+            let bucket = ply / self.bucket_size;
+            if bucket >= self.buckets.len() {
+                self.buckets.resize(bucket + 1, (0, 0.0, 0.0));
+            }
+            let (samples, winner_sum, loser_sum) = &mut self.buckets[bucket];
+            *samples += 1;
+            *winner_sum += winner_fill;
+            *loser_sum += loser_fill;
+        }
+    }
+
+    /// Gets the table of the average fill percentages ordered by ply.
+    ///
+    /// # Returns
+    ///
+    /// The rows `(first ply of the bucket, samples, average fill of the
+    /// winners, average fill of the losers)` of the table. Buckets without
+    /// samples are skipped.
+    #[must_use]
+    pub fn get_table(&self) -> Vec<(usize, usize, f64, f64)> {
+        self.buckets
+            .iter()
+            .enumerate()
+            .filter(|(_, (samples, _, _))| *samples > 0)
+            .map(|(bucket, (samples, winner_sum, loser_sum))| {
+                (
+                    bucket * self.bucket_size,
+                    *samples,
+                    winner_sum / *samples as f64,
+                    loser_sum / *samples as f64,
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialization::GameLoader,
+        generation::{generate_games, GenerationOptions, GeneratorPlayer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_every_ply_bucket_has_a_fill_value() {
+        const BUCKET_SIZE: usize = 5;
+
+        let corpus = std::env::temp_dir().join(format!("patchwork-board-coverage-{}", std::process::id()));
+        let options = GenerationOptions {
+            games: 3,
+            base_seed: 0,
+            player_1: GeneratorPlayer::Random,
+            player_2: GeneratorPlayer::RandomIncome,
+            games_per_file: 2,
+        };
+        generate_games(&corpus, &options).unwrap();
+
+        let mut coverage = BoardCoverage::new(BUCKET_SIZE);
+        let mut longest_game = 0;
+        for game in GameLoader::new(&corpus, None) {
+            longest_game = longest_game.max(game.turns.len());
+            coverage.add_game(&game, game.get_termination_result());
+        }
+        std::fs::remove_dir_all(&corpus).unwrap();
+
+        let table = coverage.get_table();
+        assert_eq!(longest_game.div_ceil(BUCKET_SIZE), table.len());
+        for (bucket, (first_ply, samples, winner_fill, loser_fill)) in table.iter().enumerate() {
+            assert_eq!(bucket * BUCKET_SIZE, *first_ply);
+            assert!(*samples > 0);
+            assert!((0.0..=1.0).contains(winner_fill));
+            assert!((0.0..=1.0).contains(loser_fill));
+        }
+        // the boards start empty and only get filled
+        assert!(table[0].2 < table.last().unwrap().2);
+    }
+}
@@ -1,6 +1,6 @@
 use std::num::NonZeroUsize;
 
-use patchwork_core::{ActionId, Patchwork};
+use patchwork_core::{ActionId, Patchwork, Termination};
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Game {
@@ -13,13 +13,36 @@ pub struct GameTurn {
     pub action: Option<ActionId>,
 }
 
+impl Game {
+    /// Gets the termination result of the game. The statistics of a game all
+    /// need the result, so it is computed once per game and passed to them.
+    ///
+    /// # Returns
+    ///
+    /// The termination result of the last state of the game.
+    ///
+    /// # Panics
+    ///
+    /// If the last state of the game is not terminated.
+    #[must_use]
+    pub fn get_termination_result(&self) -> Termination {
+        let end_state = &self.turns.last().unwrap().state;
+        assert!(
+            end_state.is_terminated(),
+            "[Game::get_termination_result] Game is not terminated"
+        );
+        end_state.get_termination_result()
+    }
+}
+
 pub struct GameLoader {
     rx: std::sync::mpsc::Receiver<Game>,
     loaded: std::sync::Arc<std::sync::atomic::AtomicUsize>,
 }
 
 impl GameLoader {
-    pub(crate) fn new(path: &std::path::PathBuf, parallelism: Option<NonZeroUsize>) -> Self {
+    #[must_use]
+    pub fn new(path: &std::path::PathBuf, parallelism: Option<NonZeroUsize>) -> Self {
         let dir = std::fs::read_dir(path).unwrap();
         let parallelism = parallelism.unwrap_or_else(|| std::thread::available_parallelism().unwrap());
         let (tx, rx) = std::sync::mpsc::channel();
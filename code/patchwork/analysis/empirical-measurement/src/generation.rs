@@ -0,0 +1,198 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use patchwork_core::{GameOptions, Patchwork, Player};
+use random_player::{RandomOptions, RandomPlayer, RandomWeighting};
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::deserialization::{Game, GameTurn};
+
+/// The players that can be used to generate self-play games.
+///
+/// All players are deterministic for a given seed so that the generated games
+/// are reproducible. The greedy player is not available as it breaks ties with an
+/// unseeded random number generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GeneratorPlayer {
+    /// A random player that chooses uniformly.
+    Random,
+    /// A random player that weights patch placements by their button income.
+    RandomIncome,
+}
+
+impl GeneratorPlayer {
+    /// Creates the player.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed for the player.
+    ///
+    /// # Returns
+    ///
+    /// The player.
+    #[must_use]
+    pub fn create(self, seed: u64) -> Box<dyn Player> {
+        match self {
+            Self::Random => Box::new(RandomPlayer::new("Random", Some(RandomOptions::new(seed)))),
+            Self::RandomIncome => Box::new(RandomPlayer::new(
+                "Random Income",
+                Some(RandomOptions { seed, weighting: RandomWeighting::ButtonIncome }),
+            )),
+        }
+    }
+}
+
+impl FromStr for GeneratorPlayer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "random" => Ok(Self::Random),
+            "random-income" => Ok(Self::RandomIncome),
+            _ => Err(format!("Unknown player '{s}'. Possible players: random, random-income")),
+        }
+    }
+}
+
+/// The options for the generation of self-play games.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationOptions {
+    /// The amount of games to generate.
+    pub games: usize,
+    /// The seed of the first game. Game `i` is played with seed `base_seed + i`.
+    pub base_seed: u64,
+    /// The first player.
+    pub player_1: GeneratorPlayer,
+    /// The second player.
+    pub player_2: GeneratorPlayer,
+    /// The maximum amount of games per file.
+    pub games_per_file: usize,
+}
+
+/// Plays a single self-play game and records every turn.
+///
+/// # Arguments
+///
+/// * `seed` - The seed for the game and the players.
+/// * `player_1` - The first player.
+/// * `player_2` - The second player.
+///
+/// # Returns
+///
+/// The recorded game. The last turn contains the terminated state and no action.
+#[must_use]
+pub fn generate_game(seed: u64, player_1: GeneratorPlayer, player_2: GeneratorPlayer) -> Game {
+    let mut players = [player_1.create(seed), player_2.create(seed ^ 0x9E37_79B9_7F4A_7C15)];
+    let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+    let mut turns = vec![];
+
+    while !state.is_terminated() {
+        let player = &mut players[usize::from(!state.is_player_1())];
+        let action = player.get_action(&state).unwrap();
+
+        turns.push(GameTurn { state: state.clone(), action: Some(action) });
+        state.do_action(action, false).unwrap();
+    }
+
+    turns.push(GameTurn { state, action: None });
+    Game { turns }
+}
+
+/// Generates self-play games in parallel and writes them to the given directory.
+///
+/// The games are written in chunks of [`GenerationOptions::games_per_file`]
+/// games per file. Every file contains the bincode encoded list of games that is read by the
+/// [`crate::deserialization::GameLoader`].
+///
+/// # Arguments
+///
+/// * `output` - The directory to write the games to. It is created if it does not exist.
+/// * `options` - The options for the generation.
+///
+/// # Returns
+///
+/// The amount of written files.
+///
+/// # Errors
+///
+/// If the directory or a file could not be written.
+pub fn generate_games(output: &Path, options: &GenerationOptions) -> std::io::Result<usize> {
+    std::fs::create_dir_all(output)?;
+
+    let games_per_file = options.games_per_file.max(1);
+    let files = options.games.div_ceil(games_per_file);
+
+    (0..files).into_par_iter().try_for_each(|file_index| {
+        let start = file_index * games_per_file;
+        let end = (start + games_per_file).min(options.games);
+        let games = (start..end)
+            .map(|index| generate_game(options.base_seed + index as u64, options.player_1, options.player_2))
+            .collect::<Vec<_>>();
+
+        let mut writer = BufWriter::new(File::create(output.join(format!("games_{file_index:05}.game.bin")))?);
+        bincode::serialize_into(&mut writer, &games).map_err(std::io::Error::other)?;
+        writer.flush()
+    })?;
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::deserialization::GameLoader;
+
+    use super::*;
+
+    #[test]
+    fn test_generated_games_can_be_loaded() {
+        let output = std::env::temp_dir().join(format!("patchwork-generate-games-{}", std::process::id()));
+        let options = GenerationOptions {
+            games: 5,
+            base_seed: 42,
+            player_1: GeneratorPlayer::Random,
+            player_2: GeneratorPlayer::RandomIncome,
+            games_per_file: 2,
+        };
+
+        assert_eq!(3, generate_games(&output, &options).unwrap());
+        let games = GameLoader::new(&output, None).collect::<Vec<_>>();
+        std::fs::remove_dir_all(&output).unwrap();
+
+        // the files are loaded in parallel so the games are identified by their initial state
+        let mut initial_states = (42..47)
+            .map(|seed| Patchwork::get_initial_state(Some(GameOptions::new(seed))))
+            .collect::<Vec<_>>();
+        assert_eq!(5, games.len());
+        for game in &games {
+            let (last_turn, turns) = game.turns.split_last().unwrap();
+            assert!(last_turn.state.is_terminated());
+            assert_eq!(None, last_turn.action);
+
+            let index = initial_states.iter().position(|state| *state == game.turns[0].state).unwrap();
+            let mut state = initial_states.swap_remove(index);
+            for turn in turns {
+                assert_eq!(state, turn.state);
+                state.do_action(turn.action.unwrap(), false).unwrap();
+            }
+            assert_eq!(last_turn.state, state);
+        }
+    }
+
+    #[test]
+    fn test_generation_is_deterministic() {
+        for player in [GeneratorPlayer::Random, GeneratorPlayer::RandomIncome] {
+            assert_eq!(
+                generate_game(7, player, GeneratorPlayer::Random),
+                generate_game(7, player, GeneratorPlayer::Random)
+            );
+        }
+        assert_ne!(
+            generate_game(7, GeneratorPlayer::Random, GeneratorPlayer::Random),
+            generate_game(8, GeneratorPlayer::Random, GeneratorPlayer::Random)
+        );
+    }
+}
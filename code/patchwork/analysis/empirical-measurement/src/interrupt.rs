@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A flag that is set when a long-running analysis is interrupted (e.g. with Ctrl-C).
+///
+/// The analysis checks the flag regularly and stops early so that the results
+/// gathered so far can still be written instead of being lost.
+#[derive(Debug, Clone, Default)]
+pub struct Interrupt {
+    interrupted: Arc<AtomicBool>,
+}
+
+impl Interrupt {
+    /// Creates a new interrupt that is not triggered.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new interrupt that is triggered by Ctrl-C. A second Ctrl-C
+    /// exits the application immediately.
+    ///
+    /// # Errors
+    ///
+    /// If the Ctrl-C handler could not be installed, e.g. because another
+    /// handler was already installed.
+    pub fn on_ctrl_c() -> Result<Self, ctrlc::Error> {
+        let interrupt = Self::new();
+        let handler = interrupt.clone();
+        ctrlc::set_handler(move || {
+            if handler.interrupted.swap(true, Ordering::SeqCst) {
+                println!("Received second CTRL-C command. Exiting application...");
+                std::process::exit(130);
+            }
+            println!("\nReceived CTRL-C command. Writing partial results...");
+        })?;
+        Ok(interrupt)
+    }
+
+    /// Triggers the interrupt like a received Ctrl-C.
+    pub fn trigger(&self) {
+        self.interrupted.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the interrupt was triggered.
+    #[must_use]
+    pub fn is_interrupted(&self) -> bool {
+        self.interrupted.load(Ordering::SeqCst)
+    }
+}
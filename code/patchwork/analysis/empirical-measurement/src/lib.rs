@@ -0,0 +1,10 @@
+pub mod action_scores;
+pub mod board_coverage;
+pub mod deserialization;
+pub mod generation;
+pub mod interrupt;
+pub mod move_agreement;
+pub mod patch_timing;
+pub mod placement_heatmap;
+pub mod puzzles;
+pub mod time_board_advantage;
@@ -1,11 +1,25 @@
-mod deserialization;
-
-use patchwork_core::{PatchManager, TerminationType, TurnType};
-
-use crate::deserialization::GameLoader;
-
+use std::cell::OnceCell;
+
+use empirical_measurement::{
+    action_scores::ActionScores,
+    board_coverage::BoardCoverage,
+    deserialization::{Game, GameLoader},
+    interrupt::Interrupt,
+    placement_heatmap::PlacementHeatmap,
+    time_board_advantage::TimeBoardAdvantage,
+};
+use patchwork_core::TurnType;
+
+/// Gathers the statistics of the given games and writes them to the output
+/// directory. If the interrupt is triggered no further games are read and
+/// the statistics of the games read so far are written.
 #[allow(clippy::too_many_lines)]
-fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gather: &Gather) {
+fn get_game_statistics(
+    games_iter: impl IntoIterator<Item = Game>,
+    output: &std::path::Path,
+    gather: &Gather,
+    interrupt: &Interrupt,
+) {
     if !gather.has_something() {
         println!("Nothing to gather");
         return;
@@ -57,14 +71,25 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
     } else {
         None
     };
-    let mut action_scores_map = std::collections::HashMap::new();
+    let mut action_scores = gather
+        .action_score_buckets
+        .map_or_else(|| ActionScores::new(None), ActionScores::bounded);
+    let mut time_board_advantage = TimeBoardAdvantage::new();
+    let mut placement_heatmap = PlacementHeatmap::new();
+    let mut board_coverage = BoardCoverage::new(gather.board_coverage_bucket_size);
 
-    println!("Getting game statistics from {input:?}");
     let mut games = 0;
     let mut no_games = true;
-    for game in GameLoader::new(input, None) {
+    for game in games_iter {
+        if interrupt.is_interrupted() {
+            break;
+        }
         no_games = false;
 
+        // The termination result is only computed once for all statistics that need it
+        let termination = OnceCell::new();
+        let termination = || *termination.get_or_init(|| game.get_termination_result());
+
         // Game length writer
         if gather.game {
             let game_length = game.turns.iter().filter(|turn| turn.action.is_some()).count();
@@ -115,18 +140,8 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
                         TurnType::SpecialPatchPlacement | TurnType::SpecialPhantom
                     ) && !turn.state.is_terminated()
                 })
-                .map(|turn| turn.state.get_valid_actions())
-                .filter(|actions| {
-                    if actions.is_empty() {
-                        return false;
-                    }
-
-                    if !actions[0].is_special_patch_placement() {
-                        return false;
-                    }
-
-                    true
-                })
+                .map(|turn| turn.state.available_special_actions())
+                .filter(|actions| !actions.is_empty())
                 .map(|actions| actions.len());
             for available_special_action in available_special_actions {
                 available_special_actions_writer
@@ -137,84 +152,9 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
             }
         }
 
-        // Action scores writer
+        // Action scores
         if gather.action_scores {
-            let end_state = &game.turns.last().unwrap().state;
-            assert!(
-                end_state.is_terminated(),
-                "[get_game_statistics(action_scores)] Game is not terminated"
-            );
-
-            let result = end_state.get_termination_result();
-            game.turns
-                .iter()
-                .enumerate()
-                .filter(|(_, turn)| turn.action.is_some())
-                .for_each(|(ply, turn)| {
-                    let action = turn.action.unwrap();
-                    let is_player_1 = turn.state.is_player_1();
-
-                    let score = match result.termination {
-                        TerminationType::Player1Won => {
-                            if is_player_1 {
-                                1
-                            } else {
-                                -1
-                            }
-                        }
-                        TerminationType::Player2Won => {
-                            if is_player_1 {
-                                -1
-                            } else {
-                                1
-                            }
-                        }
-                    };
-
-                    let actual_score = score * ((result.player_1_score - result.player_2_score).abs() + 1);
-                    let key = if action.is_walking() {
-                        0
-                    } else if action.is_special_patch_placement() {
-                        u32::from(action.get_quilt_board_index()) + 1
-                    } else if action.is_patch_placement() {
-                        u32::from(action.get_patch_id()) * PatchManager::MAX_AMOUNT_OF_TRANSFORMATIONS
-                            + u32::from(action.get_patch_transformation_index())
-                            + 82
-                    } else {
-                        unreachable!(
-                            "[get_game_statistics(action_scores)] Other actions types should not be in the dataset"
-                        )
-                    };
-                    let description = if action.is_walking() {
-                        "walking".to_string()
-                    } else if action.is_special_patch_placement() {
-                        format!("special_patch_placement({:02})", action.get_quilt_board_index())
-                    } else if action.is_patch_placement() {
-                        format!(
-                            "patch_placement({:02}, {:03})",
-                            action.get_patch_id(),
-                            action.get_patch_transformation_index()
-                        )
-                    } else {
-                        unreachable!(
-                            "[get_game_statistics(action_scores)] Other actions types should not be in the dataset"
-                        )
-                    };
-
-                    let percentage = ply as f64 / game.turns.len() as f64;
-
-                    let entry = action_scores_map.entry((key, F64Key(percentage))).or_insert((
-                        description,
-                        percentage,
-                        0,
-                        0,
-                        0,
-                    ));
-
-                    entry.2 += actual_score;
-                    entry.3 += score;
-                    entry.4 += 1; // count
-                });
+            action_scores.add_game(&game, termination());
         }
 
         // Gather statistics for the approximation of the game tree complexity
@@ -238,6 +178,21 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
                 .unwrap();
         }
 
+        // Time board advantage
+        if gather.time_board_advantage {
+            time_board_advantage.add_game(&game, termination());
+        }
+
+        // Placement heatmap
+        if gather.placement_heatmap {
+            placement_heatmap.add_game(&game, termination());
+        }
+
+        // Board coverage
+        if gather.board_coverage {
+            board_coverage.add_game(&game, termination());
+        }
+
         games += 1;
         if games % 10000 == 0 {
             print!("\r================= Game {games} =================");
@@ -246,10 +201,43 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
 
     println!();
 
+    if interrupt.is_interrupted() {
+        println!("Interrupted after {games} games. Writing partial results");
+    }
+
     if no_games {
         println!("No games found");
     }
 
+    if gather.time_board_advantage {
+        println!("Writing time board advantage");
+        let mut time_board_advantage_writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_path(output.join("time_board_advantage.csv"))
+            .unwrap();
+
+        for (lead, samples, win_rate) in time_board_advantage.get_table() {
+            time_board_advantage_writer.serialize((lead, samples, win_rate)).unwrap();
+        }
+    }
+
+    if gather.placement_heatmap {
+        println!("Writing placement heatmaps");
+        placement_heatmap.write_csv(output).unwrap();
+    }
+
+    if gather.board_coverage {
+        println!("Writing board coverage");
+        let mut board_coverage_writer = csv::WriterBuilder::new()
+            .has_headers(false)
+            .from_path(output.join("board_coverage.csv"))
+            .unwrap();
+
+        for row in board_coverage.get_table() {
+            board_coverage_writer.serialize(row).unwrap();
+        }
+    }
+
     if gather.action_scores {
         println!("Running post processing for action scores");
         let mut action_scores_writer = csv::WriterBuilder::new()
@@ -257,22 +245,15 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
             .from_path(output.join("action_scores.csv"))
             .unwrap();
 
-        let mut data_vector = action_scores_map.values().collect::<Vec<_>>();
-        data_vector.sort_by_key(|(desc, percentage, _, _, _)| {
-            if desc == "walking" {
-                (0, desc, F64Key(*percentage))
-            } else if desc.starts_with("special_patch_placement") {
-                (1, desc, F64Key(*percentage))
-            } else if desc.starts_with("patch_placement") {
-                (2, desc, F64Key(*percentage))
-            } else {
-                unreachable!("[get_game_statistics(action_scores)] Other actions types should not be in the dataset")
-            }
-        });
-
-        for (description, percentage, score, win_loss, count) in data_vector {
+        for score in action_scores.get_scores() {
             action_scores_writer
-                .serialize((description, percentage, score, win_loss, count))
+                .serialize((
+                    score.description,
+                    score.percentage,
+                    score.score,
+                    score.win_loss,
+                    score.count,
+                ))
                 .unwrap();
         }
     }
@@ -280,45 +261,37 @@ fn get_game_statistics(input: &std::path::PathBuf, output: &std::path::Path, gat
     println!("================= FINISHED GATHERING STATISTICS =================");
 }
 
-struct F64Key(pub f64);
-
-impl std::cmp::PartialEq for F64Key {
-    fn eq(&self, other: &Self) -> bool {
-        self.0 == other.0
-    }
-}
-impl std::cmp::Eq for F64Key {}
-impl std::cmp::PartialOrd for F64Key {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-impl std::cmp::Ord for F64Key {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.0.partial_cmp(&other.0).unwrap()
-    }
-}
-impl std::hash::Hash for F64Key {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.0.to_bits().hash(state);
-    }
-}
-
 #[allow(clippy::struct_excessive_bools)]
 struct Gather {
     game: bool,
     available_actions: bool,
     available_special_actions: bool,
     action_scores: bool,
+    /// The amount of buckets of the bounded action score aggregation or
+    /// `None` to aggregate by the exact point of the game.
+    action_score_buckets: Option<usize>,
     game_tree_complexity: bool,
+    time_board_advantage: bool,
+    placement_heatmap: bool,
+    board_coverage: bool,
+    /// The amount of plies that are grouped into one bucket of the board
+    /// coverage.
+    board_coverage_bucket_size: usize,
 }
 
 impl Gather {
     pub const fn has_something(&self) -> bool {
-        self.game || self.available_actions || self.available_special_actions || self.action_scores
+        self.game
+            || self.available_actions
+            || self.available_special_actions
+            || self.action_scores
+            || self.time_board_advantage
+            || self.placement_heatmap
+            || self.board_coverage
     }
 }
 
+#[allow(clippy::too_many_lines)]
 fn main() {
     let cmd = clap::Command::new("empirical-measurement")
         .bin_name("empirical-measurement")
@@ -371,6 +344,13 @@ fn main() {
                 .num_args(0)
                 .help("Gathers statistics about the scores of actions"),
         )
+        .arg(
+            clap::Arg::new("action-score-buckets")
+                .long("action-score-buckets")
+                .required(false)
+                .help("Aggregates the action scores in a fixed size table with this many buckets per action")
+                .value_parser(clap::value_parser!(usize)),
+        )
         .arg(
             clap::Arg::new("game-tree-complexity")
                 .long("game-tree-complexity")
@@ -378,18 +358,101 @@ fn main() {
                 .required(false)
                 .num_args(0)
                 .help("Gathers statistics about the game tree complexity"),
+        )
+        .arg(
+            clap::Arg::new("time-board-advantage")
+                .long("time-board-advantage")
+                .required(false)
+                .num_args(0)
+                .help("Gathers the win rate per lead on the time board"),
+        )
+        .arg(
+            clap::Arg::new("placement-heatmap")
+                .long("placement-heatmap")
+                .required(false)
+                .num_args(0)
+                .help("Gathers where every patch was placed in won and lost games"),
+        )
+        .arg(
+            clap::Arg::new("board-coverage")
+                .long("board-coverage")
+                .required(false)
+                .num_args(0)
+                .help("Gathers the average quilt board coverage of winners and losers per ply"),
+        )
+        .arg(
+            clap::Arg::new("board-coverage-bucket-size")
+                .long("board-coverage-bucket-size")
+                .required(false)
+                .default_value("1")
+                .help("The amount of plies that are grouped together for the board coverage")
+                .value_parser(clap::value_parser!(usize)),
         );
 
     let matches = cmd.get_matches();
+    let input = matches.get_one::<std::path::PathBuf>("in").unwrap();
+    let interrupt = Interrupt::on_ctrl_c().unwrap();
+
+    println!("Getting game statistics from {}", input.display());
     get_game_statistics(
-        matches.get_one::<std::path::PathBuf>("in").unwrap(),
+        GameLoader::new(input, None),
         matches.get_one::<std::path::PathBuf>("out").unwrap(),
         &Gather {
             game: matches.get_flag("game"),
             available_actions: matches.get_flag("available-actions"),
             available_special_actions: matches.get_flag("available-special-actions"),
             action_scores: matches.get_flag("action-scores"),
+            action_score_buckets: matches.get_one::<usize>("action-score-buckets").copied(),
             game_tree_complexity: matches.get_flag("game-tree-complexity"),
+            time_board_advantage: matches.get_flag("time-board-advantage"),
+            placement_heatmap: matches.get_flag("placement-heatmap"),
+            board_coverage: matches.get_flag("board-coverage"),
+            board_coverage_bucket_size: *matches.get_one::<usize>("board-coverage-bucket-size").unwrap(),
         },
+        &interrupt,
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use empirical_measurement::generation::{generate_game, GeneratorPlayer};
+
+    use super::*;
+
+    #[test]
+    fn test_interrupted_run_writes_partial_results() {
+        let output = std::env::temp_dir().join(format!("patchwork-interrupted-statistics-{}", std::process::id()));
+        let interrupt = Interrupt::new();
+        // the interrupt is triggered while the third game is read
+        let games = (0..5).map(|seed| {
+            if seed == 2 {
+                interrupt.trigger();
+            }
+            generate_game(seed, GeneratorPlayer::Random, GeneratorPlayer::Random)
+        });
+
+        get_game_statistics(
+            games,
+            &output,
+            &Gather {
+                game: true,
+                available_actions: false,
+                available_special_actions: false,
+                action_scores: false,
+                action_score_buckets: None,
+                game_tree_complexity: false,
+                time_board_advantage: true,
+                placement_heatmap: false,
+                board_coverage: false,
+                board_coverage_bucket_size: 1,
+            },
+            &interrupt,
+        );
+        let game_lengths = std::fs::read_to_string(output.join("game_lengths.csv"));
+        let time_board_advantage = std::fs::read_to_string(output.join("time_board_advantage.csv"));
+        std::fs::remove_dir_all(&output).unwrap();
+
+        assert_eq!(2, game_lengths.unwrap().lines().count());
+        assert!(!time_board_advantage.unwrap().is_empty());
+    }
+}
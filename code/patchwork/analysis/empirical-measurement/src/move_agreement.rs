@@ -0,0 +1,334 @@
+use std::{path::Path, str::FromStr};
+
+use evaluator::StaticEvaluator;
+use greedy_player::{GreedyOptions, GreedyPlayer};
+use minimax_player::{MinimaxOptions, MinimaxPlayer};
+use patchwork_core::{ActionId, Evaluator, Notation, Patchwork, Player, PlayerResult};
+
+/// The engines that can be compared by the [`MoveAgreementAnalyzer`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AgreementEngine {
+    /// The greedy player that plays the action with the best static evaluation.
+    Greedy,
+    /// The minimax player searching to the given depth.
+    Minimax {
+        /// The depth to search to.
+        depth: usize,
+    },
+}
+
+impl AgreementEngine {
+    /// The seed the engines break ties between equally evaluated actions
+    /// with. A fixed seed makes the engines deterministic so that only
+    /// actual differences between engines are counted as disagreements.
+    pub const TIE_BREAKING_SEED: u64 = 0;
+
+    /// Creates the player. Ties are broken with [`Self::TIE_BREAKING_SEED`].
+    ///
+    /// # Returns
+    ///
+    /// The player.
+    #[must_use]
+    pub fn create(self) -> Box<dyn Player> {
+        match self {
+            Self::Greedy => Box::new(GreedyPlayer::<StaticEvaluator>::new(
+                "Greedy",
+                Some(GreedyOptions {
+                    tie_breaking_seed: Some(Self::TIE_BREAKING_SEED),
+                    ..GreedyOptions::default()
+                }),
+            )),
+            Self::Minimax { depth } => Box::new(MinimaxPlayer::<StaticEvaluator>::new(
+                format!("Minimax({depth})"),
+                Some(MinimaxOptions {
+                    depth,
+                    tie_breaking_seed: Some(Self::TIE_BREAKING_SEED),
+                    ..MinimaxOptions::default()
+                }),
+            )),
+        }
+    }
+}
+
+impl FromStr for AgreementEngine {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_lowercase();
+        if s == "greedy" {
+            return Ok(Self::Greedy);
+        }
+        if s == "minimax" {
+            return Ok(Self::Minimax {
+                depth: MinimaxOptions::default().depth,
+            });
+        }
+        if let Some(depth) = s.strip_prefix("minimax-").and_then(|depth| depth.parse().ok()) {
+            return Ok(Self::Minimax { depth });
+        }
+
+        Err(format!("Unknown engine '{s}'. Possible engines: greedy, minimax, minimax-<depth>"))
+    }
+}
+
+/// A position where the two compared engines chose different actions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Disagreement {
+    /// The position of the disagreement.
+    pub state: Patchwork,
+    /// The action chosen by the first engine.
+    pub action_1: ActionId,
+    /// The action chosen by the second engine.
+    pub action_2: ActionId,
+    /// The evaluation of the action of the first engine minus the evaluation
+    /// of the action of the second engine from the perspective of the player
+    /// to move. Positive if the action of the first engine is rated better.
+    pub evaluation_gap: i32,
+}
+
+/// The result of comparing the actions of two engines.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveAgreement {
+    /// The amount of compared positions.
+    pub positions: usize,
+    /// The amount of positions where both engines chose the same action.
+    pub agreements: usize,
+    /// The positions where the engines chose different actions.
+    pub disagreements: Vec<Disagreement>,
+}
+
+impl MoveAgreement {
+    /// Gets the percentage of positions where both engines agreed.
+    ///
+    /// # Returns
+    ///
+    /// The agreement between `0.0` and `100.0`. `100.0` if no position was
+    /// compared.
+    #[must_use]
+    pub fn agreement_percentage(&self) -> f64 {
+        if self.positions == 0 {
+            return 100.0;
+        }
+
+        self.agreements as f64 / self.positions as f64 * 100.0
+    }
+
+    /// Gets the average absolute evaluation gap of the disagreements.
+    ///
+    /// # Returns
+    ///
+    /// The average absolute evaluation gap or `0.0` if the engines never
+    /// disagreed.
+    #[must_use]
+    pub fn average_evaluation_gap(&self) -> f64 {
+        if self.disagreements.is_empty() {
+            return 0.0;
+        }
+
+        let sum = self
+            .disagreements
+            .iter()
+            .map(|disagreement| f64::from(disagreement.evaluation_gap.unsigned_abs()))
+            .sum::<f64>();
+        sum / self.disagreements.len() as f64
+    }
+}
+
+/// Compares the actions two engines choose in a set of positions. Where the
+/// engines disagree, both actions are rated by the evaluator to get the
+/// evaluation gap between them.
+pub struct MoveAgreementAnalyzer<Eval: Evaluator> {
+    /// The evaluator that rates the state after the chosen actions.
+    evaluator: Eval,
+}
+
+impl<Eval: Evaluator> MoveAgreementAnalyzer<Eval> {
+    /// Creates a new [`MoveAgreementAnalyzer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `evaluator` - The evaluator that rates the state after the chosen actions.
+    #[must_use]
+    pub const fn new(evaluator: Eval) -> Self {
+        Self { evaluator }
+    }
+
+    /// Compares the actions of both engines in the given positions.
+    /// Terminated positions are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine_1` - The first engine.
+    /// * `engine_2` - The second engine.
+    /// * `positions` - The positions to compare the engines in.
+    ///
+    /// # Returns
+    ///
+    /// The agreement of both engines.
+    ///
+    /// # Errors
+    ///
+    /// If one of the engines or one of the chosen actions fails.
+    pub fn analyze<'a>(
+        &self,
+        engine_1: &mut dyn Player,
+        engine_2: &mut dyn Player,
+        positions: impl IntoIterator<Item = &'a Patchwork>,
+    ) -> PlayerResult<MoveAgreement> {
+        let mut agreement = MoveAgreement::default();
+
+        for state in positions {
+            if state.is_terminated() {
+                continue;
+            }
+
+            // both engines get the same fresh start in every position
+            engine_1.reset();
+            engine_2.reset();
+            let action_1 = engine_1.get_action(state)?;
+            let action_2 = engine_2.get_action(state)?;
+
+            agreement.positions += 1;
+            if action_1 == action_2 {
+                agreement.agreements += 1;
+                continue;
+            }
+
+            let evaluation_gap = self.evaluate_action(state, action_1)? - self.evaluate_action(state, action_2)?;
+            agreement.disagreements.push(Disagreement {
+                state: state.clone(),
+                action_1,
+                action_2,
+                evaluation_gap,
+            });
+        }
+
+        Ok(agreement)
+    }
+
+    /// Evaluates the state after the given action from the perspective of the
+    /// player to move.
+    fn evaluate_action(&self, state: &Patchwork, action: ActionId) -> PlayerResult<i32> {
+        // the evaluation is from the perspective of player 1
+        let sign = if state.is_player_1() { 1 } else { -1 };
+        let mut next_state = state.clone();
+        next_state.do_action(action, false)?;
+
+        Ok(sign * self.evaluator.evaluate_node(&next_state))
+    }
+}
+
+/// Writes the disagreements to a CSV file with the position and both actions
+/// in the patchwork notation. Phantom positions cannot be written as notation
+/// and are skipped.
+///
+/// # Arguments
+///
+/// * `path` - The file to write the disagreements to.
+/// * `disagreements` - The disagreements to write.
+///
+/// # Returns
+///
+/// The amount of written disagreements.
+///
+/// # Errors
+///
+/// If the file could not be written.
+pub fn write_disagreements(path: &Path, disagreements: &[Disagreement]) -> csv::Result<usize> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(["state", "action_1", "action_2", "evaluation_gap"])?;
+
+    let mut written = 0;
+    for disagreement in disagreements {
+        let (Ok(state), Ok(action_1), Ok(action_2)) = (
+            disagreement.state.save_to_notation(),
+            disagreement.action_1.save_to_notation(),
+            disagreement.action_2.save_to_notation(),
+        ) else {
+            continue;
+        };
+        writer.write_record([state, action_1, action_2, disagreement.evaluation_gap.to_string()])?;
+        written += 1;
+    }
+    writer.flush()?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{GameOptions, PositionCorpus};
+
+    use super::*;
+
+    /// A player that always plays the first or the last valid action.
+    struct EdgeActionPlayer {
+        first: bool,
+    }
+
+    impl Player for EdgeActionPlayer {
+        fn name(&self) -> &'static str {
+            "Edge Action Player"
+        }
+
+        fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+            let valid_actions = game.get_valid_actions();
+            Ok(if self.first {
+                valid_actions[0]
+            } else {
+                valid_actions[valid_actions.len() - 1]
+            })
+        }
+    }
+
+    #[test]
+    fn test_identical_engines_always_agree() {
+        let positions = PositionCorpus::generate(8, PositionCorpus::DEFAULT_SEED);
+
+        for engine in [AgreementEngine::Greedy, AgreementEngine::Minimax { depth: 2 }] {
+            let agreement = MoveAgreementAnalyzer::new(StaticEvaluator::new())
+                .analyze(engine.create().as_mut(), engine.create().as_mut(), &positions)
+                .unwrap();
+
+            assert_eq!(8, agreement.positions);
+            assert_eq!(8, agreement.agreements, "{engine:?} disagrees with itself");
+            assert!(agreement.disagreements.is_empty());
+            assert!((agreement.agreement_percentage() - 100.0).abs() < f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_different_engines_disagree() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let valid_actions = state.get_valid_actions();
+        let analyzer = MoveAgreementAnalyzer::new(StaticEvaluator::new());
+
+        let agreement = analyzer
+            .analyze(
+                &mut EdgeActionPlayer { first: true },
+                &mut EdgeActionPlayer { first: false },
+                [&state],
+            )
+            .unwrap();
+
+        assert_eq!(1, agreement.positions);
+        assert_eq!(0, agreement.agreements);
+        assert!(agreement.agreement_percentage().abs() < f64::EPSILON);
+
+        let disagreement = &agreement.disagreements[0];
+        assert_eq!(valid_actions[0], disagreement.action_1);
+        assert_eq!(valid_actions[valid_actions.len() - 1], disagreement.action_2);
+        assert_eq!(
+            analyzer.evaluate_action(&state, disagreement.action_1).unwrap()
+                - analyzer.evaluate_action(&state, disagreement.action_2).unwrap(),
+            disagreement.evaluation_gap
+        );
+    }
+
+    #[test]
+    fn test_parse_engine() {
+        assert_eq!(Ok(AgreementEngine::Greedy), "greedy".parse());
+        assert_eq!(Ok(AgreementEngine::Minimax { depth: 3 }), "minimax-3".parse());
+        assert!("mcts".parse::<AgreementEngine>().is_err());
+    }
+}
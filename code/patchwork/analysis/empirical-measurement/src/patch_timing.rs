@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+
+use patchwork_core::{Patch, Patchwork, TimeBoard};
+
+/// A patch bought at a position of the time board in a [`PatchSchedule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScheduledPurchase {
+    /// The bought patch.
+    pub patch: &'static Patch,
+    /// The position on the time board where the patch is bought.
+    pub position: u8,
+}
+
+/// The optimal purchase schedule of a [`PatchTimingProblem`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchSchedule {
+    /// The purchases in the order they are made. All time not spent on
+    /// purchases is walked.
+    pub purchases: Vec<ScheduledPurchase>,
+    /// The button balance of the player at the end of the time board.
+    pub final_button_balance: i32,
+}
+
+/// The reasons why a [`PatchTimingProblem`] cannot be solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PatchTimingError {
+    /// The player already reached the end of the time board.
+    PlayerFinished,
+    /// More patches than [`PatchTimingProblem::MAX_PATCHES`] were given.
+    TooManyPatches {
+        /// The amount of given patches.
+        amount: usize,
+    },
+}
+
+impl std::fmt::Display for PatchTimingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::PlayerFinished => write!(f, "The player already reached the end of the time board"),
+            Self::TooManyPatches { amount } => write!(
+                f,
+                "Too many patches to plan ({amount}). At most {} patches are supported",
+                PatchTimingProblem::MAX_PATCHES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PatchTimingError {}
+
+/// The problem of timing the purchases of patches such that a player ends
+/// the game with as many buttons as possible.
+///
+/// The player walks the time board alone from their position to the end.
+/// In every step the player either buys one of the remaining patches they
+/// can afford and still have room for on their quilt board or walks a
+/// single space for one button. Button income is paid at every button
+/// income trigger the player moves over.
+///
+/// This is a planning aid and not the game: the opponent, the order of the
+/// patch circle, the shape of the patches and the special patches are
+/// ignored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatchTimingProblem {
+    /// The position of the player on the time board.
+    pub position: u8,
+    /// The button balance of the player.
+    pub button_balance: i32,
+    /// The button income of the player.
+    pub button_income: i32,
+    /// The amount of free tiles on the quilt board of the player.
+    pub free_tiles: u32,
+    /// The patches that can be bought.
+    pub patches: Vec<&'static Patch>,
+}
+
+impl PatchTimingProblem {
+    /// The maximum amount of patches that can be planned. The effort of
+    /// solving a problem grows exponentially with the amount of patches.
+    pub const MAX_PATCHES: usize = 10;
+
+    /// Creates the problem for the player to move in the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to take the player and the patches from.
+    /// * `amount_of_patches` - The amount of patches to take from the front
+    ///   of the patch circle.
+    #[must_use]
+    pub fn from_state(state: &Patchwork, amount_of_patches: usize) -> Self {
+        let player = state.current_player();
+
+        Self {
+            position: player.get_position(),
+            button_balance: player.button_balance,
+            button_income: i32::from(player.quilt_board.button_income),
+            free_tiles: player.quilt_board.tiles_free(),
+            patches: state.patches.take(amount_of_patches).iter().collect(),
+        }
+    }
+
+    /// Solves the problem exactly. If purchases at different positions lead
+    /// to the same button balance, the earliest purchase is chosen.
+    ///
+    /// # Returns
+    ///
+    /// The schedule that ends with the most buttons. The schedule contains
+    /// no purchases if no patch pays off.
+    ///
+    /// # Errors
+    ///
+    /// If the player already finished or too many patches were given.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑡 · 2ⁿ · 𝑏 · 𝑛)` where `𝑡` is the amount of time board positions,
+    /// `𝑛` is the amount of patches and `𝑏` is the amount of possible
+    /// button balances.
+    pub fn solve(&self) -> Result<PatchSchedule, PatchTimingError> {
+        if self.position >= TimeBoard::MAX_POSITION {
+            return Err(PatchTimingError::PlayerFinished);
+        }
+        if self.patches.len() > Self::MAX_PATCHES {
+            return Err(PatchTimingError::TooManyPatches {
+                amount: self.patches.len(),
+            });
+        }
+
+        let mut choices = HashMap::new();
+        let final_button_balance = self.search(self.position, 0, self.button_balance, &mut choices);
+
+        // follow the best choices to recover the schedule
+        let mut purchases = vec![];
+        let (mut position, mut bought, mut button_balance) = (self.position, 0, self.button_balance);
+        while position < TimeBoard::MAX_POSITION {
+            let (_, choice) = choices[&(position, bought, button_balance)];
+            if let Some(index) = choice {
+                purchases.push(ScheduledPurchase {
+                    patch: self.patches[index],
+                    position,
+                });
+                (position, bought, button_balance) = self.buy(position, bought, button_balance, index);
+            } else {
+                (position, button_balance) = self.walk(position, bought, button_balance);
+            }
+        }
+
+        Ok(PatchSchedule {
+            purchases,
+            final_button_balance,
+        })
+    }
+
+    /// Searches the best final button balance from the given situation and
+    /// memorizes the value and the best choice (`None` for walking).
+    fn search(
+        &self,
+        position: u8,
+        bought: u16,
+        button_balance: i32,
+        choices: &mut HashMap<(u8, u16, i32), (i32, Option<usize>)>,
+    ) -> i32 {
+        if position >= TimeBoard::MAX_POSITION {
+            return button_balance;
+        }
+        if let Some((value, _)) = choices.get(&(position, bought, button_balance)) {
+            return *value;
+        }
+
+        let used_tiles = self.bought_patches(bought).map(Patch::amount_tiles).sum::<u32>();
+        let mut best = (i32::MIN, None);
+
+        // purchases are tried first so that ties prefer buying early
+        for (index, patch) in self.patches.iter().enumerate() {
+            if bought & (1 << index) != 0
+                || i32::from(patch.button_cost) > button_balance
+                || used_tiles + patch.amount_tiles() > self.free_tiles
+            {
+                continue;
+            }
+
+            let (next_position, next_bought, next_button_balance) = self.buy(position, bought, button_balance, index);
+            let value = self.search(next_position, next_bought, next_button_balance, choices);
+            if value > best.0 {
+                best = (value, Some(index));
+            }
+        }
+
+        let (next_position, next_button_balance) = self.walk(position, bought, button_balance);
+        let value = self.search(next_position, bought, next_button_balance, choices);
+        if value > best.0 {
+            best = (value, None);
+        }
+
+        choices.insert((position, bought, button_balance), best);
+        best.0
+    }
+
+    /// Buys the patch with the given index and moves by its time cost.
+    fn buy(&self, position: u8, bought: u16, button_balance: i32, index: usize) -> (u8, u16, i32) {
+        let patch = self.patches[index];
+        let bought = bought | (1 << index);
+        let next_position = (position + patch.time_cost).min(TimeBoard::MAX_POSITION);
+        let income = self.get_income(position, next_position, bought);
+
+        (next_position, bought, button_balance - i32::from(patch.button_cost) + income)
+    }
+
+    /// Walks a single space for one button.
+    fn walk(&self, position: u8, bought: u16, button_balance: i32) -> (u8, i32) {
+        let income = self.get_income(position, position + 1, bought);
+
+        (position + 1, button_balance + 1 + income)
+    }
+
+    /// Gets the button income paid when moving from `position` to
+    /// `next_position` with the given patches bought.
+    fn get_income(&self, position: u8, next_position: u8, bought: u16) -> i32 {
+        let triggers = TimeBoard::BUTTON_INCOME_TRIGGER_POSITIONS
+            .iter()
+            .filter(|trigger| (position + 1..=next_position).contains(trigger))
+            .count() as i32;
+        let button_income = self.button_income
+            + self
+                .bought_patches(bought)
+                .map(|patch| i32::from(patch.button_income))
+                .sum::<i32>();
+
+        triggers * button_income
+    }
+
+    fn bought_patches(&self, bought: u16) -> impl Iterator<Item = &'static Patch> + '_ {
+        self.patches
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| bought & (1 << index) != 0)
+            .map(|(_, patch)| *patch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{GameOptions, PatchManager};
+
+    use super::*;
+
+    /// Patch 1 costs 10 buttons and 4 time for an income of 3, patch 2 costs
+    /// 5 buttons and 3 time for an income of 1 and patch 0 costs 2 buttons
+    /// and 1 time without income. From position 25 only patch 1 pays off.
+    fn create_problem(button_balance: i32, free_tiles: u32) -> PatchTimingProblem {
+        PatchTimingProblem {
+            position: 25,
+            button_balance,
+            button_income: 0,
+            free_tiles,
+            patches: vec![
+                PatchManager::get_patch(0),
+                PatchManager::get_patch(1),
+                PatchManager::get_patch(2),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_buys_the_paying_patch_as_early_as_possible() {
+        // 10 - 10 buttons, income 3 at 29, 35, 41, 47 and 53, 24 walked spaces
+        let schedule = create_problem(10, 81).solve().unwrap();
+
+        assert_eq!(
+            vec![ScheduledPurchase {
+                patch: PatchManager::get_patch(1),
+                position: 25,
+            }],
+            schedule.purchases
+        );
+        assert_eq!(39, schedule.final_button_balance);
+    }
+
+    #[test]
+    fn test_walks_until_the_patch_is_affordable() {
+        // 9 + 1 - 10 buttons, income 3 at 29, 35, 41, 47 and 53, 23 walked spaces
+        let schedule = create_problem(9, 81).solve().unwrap();
+
+        assert_eq!(
+            vec![ScheduledPurchase {
+                patch: PatchManager::get_patch(1),
+                position: 26,
+            }],
+            schedule.purchases
+        );
+        assert_eq!(38, schedule.final_button_balance);
+    }
+
+    #[test]
+    fn test_walks_if_no_patch_fits() {
+        let schedule = create_problem(10, 4).solve().unwrap();
+
+        assert!(schedule.purchases.is_empty());
+        assert_eq!(10 + 28, schedule.final_button_balance);
+    }
+
+    #[test]
+    fn test_unsolvable_problems() {
+        let mut problem = create_problem(10, 81);
+        problem.position = TimeBoard::MAX_POSITION;
+        assert_eq!(Err(PatchTimingError::PlayerFinished), problem.solve());
+
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let problem = PatchTimingProblem::from_state(&state, PatchTimingProblem::MAX_PATCHES + 1);
+        assert_eq!(
+            Err(PatchTimingError::TooManyPatches {
+                amount: PatchTimingProblem::MAX_PATCHES + 1
+            }),
+            problem.solve()
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::Path;
+
+use patchwork_core::{ActionId, PatchManager, QuiltBoard, Termination, TerminationType};
+
+use crate::deserialization::Game;
+
+/// The amount of tiles of a quilt board.
+const TILES: usize = QuiltBoard::TILES as usize;
+
+/// A value for every tile of the quilt board, indexed by row and column.
+pub type Heatmap = [[u64; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
+
+/// Counts for every normal patch how often each tile of the quilt board was
+/// covered by the patch, separately for the games the placing player won and
+/// lost.
+///
+/// In contrast to the static tables of the action ordering this shows where
+/// the patches are actually placed. Special patches are not counted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacementHeatmap {
+    /// The amount of placements per patch and tile in won games (index `0`)
+    /// and lost games (index `1`).
+    counts: Vec<[[u64; TILES]; 2]>,
+}
+
+impl Default for PlacementHeatmap {
+    fn default() -> Self {
+        Self {
+            counts: vec![[[0; TILES]; 2]; PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize],
+        }
+    }
+}
+
+impl PlacementHeatmap {
+    /// Creates a new empty [`PlacementHeatmap`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all patch placements of the given game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The recorded game.
+    /// * `termination` - The termination result of the game (see
+    ///   [`Game::get_termination_result`]).
+    pub fn add_game(&mut self, game: &Game, termination: Termination) {
+        let player_1_won = termination.termination == TerminationType::Player1Won;
+
+        for turn in &game.turns {
+            let Some(action) = turn.action.filter(ActionId::is_patch_placement) else {
+                continue;
+            };
+
+            let won = turn.state.is_player_1() == player_1_won;
+            let transformation =
+                PatchManager::get_transformation(action.get_patch_id(), action.get_patch_transformation_index());
+            let counts = &mut self.counts[action.get_patch_id() as usize][usize::from(!won)];
+            for (index, count) in counts.iter_mut().enumerate() {
+                *count += u64::from((transformation.tiles >> index) & 1 == 1);
+            }
+        }
+    }
+
+    /// Gets the heatmap of the given patch.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch_id` - The id of the patch.
+    /// * `won` - Whether to get the placements in won or in lost games.
+    ///
+    /// # Returns
+    ///
+    /// How often the patch covered each tile of the quilt board, indexed by row and column.
+    #[must_use]
+    pub fn get_heatmap(&self, patch_id: u8, won: bool) -> Heatmap {
+        let counts = &self.counts[patch_id as usize][usize::from(!won)];
+
+        let mut heatmap = [[0; QuiltBoard::COLUMNS as usize]; QuiltBoard::ROWS as usize];
+        for (index, count) in counts.iter().enumerate() {
+            let (row, column) = QuiltBoard::get_row_column(index as u8);
+            heatmap[row as usize][column as usize] = *count;
+        }
+        heatmap
+    }
+
+    /// Writes one CSV file `placement_heatmap_<patch id>.csv` per patch to the
+    /// given directory. Every row of a file contains the row and column of a
+    /// tile together with the placements in won and in lost games.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The directory to write the files to.
+    ///
+    /// # Errors
+    ///
+    /// If a file could not be written.
+    pub fn write_csv(&self, output: &Path) -> csv::Result<()> {
+        for patch_id in 0..PatchManager::AMOUNT_OF_NORMAL_PATCHES {
+            let mut writer = csv::WriterBuilder::new()
+                .has_headers(false)
+                .from_path(output.join(format!("placement_heatmap_{patch_id:02}.csv")))?;
+
+            let won = self.get_heatmap(patch_id, true);
+            let lost = self.get_heatmap(patch_id, false);
+            for row in 0..QuiltBoard::ROWS as usize {
+                for column in 0..QuiltBoard::COLUMNS as usize {
+                    writer.serialize((row, column, won[row][column], lost[row][column]))?;
+                }
+            }
+            writer.flush()?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialization::GameLoader,
+        generation::{generate_games, GenerationOptions, GeneratorPlayer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_placements_accumulate_into_covered_tiles() {
+        let corpus = std::env::temp_dir().join(format!("patchwork-placement-heatmap-{}", std::process::id()));
+        let options = GenerationOptions {
+            games: 4,
+            base_seed: 0,
+            player_1: GeneratorPlayer::Random,
+            player_2: GeneratorPlayer::RandomIncome,
+            games_per_file: 2,
+        };
+        generate_games(&corpus, &options).unwrap();
+
+        let mut heatmap = PlacementHeatmap::new();
+        let mut expected = vec![[[0; TILES]; 2]; PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize];
+        for game in GameLoader::new(&corpus, None) {
+            let player_1_won = game.get_termination_result().termination == TerminationType::Player1Won;
+
+            // the covered tiles are the tiles that are new on the board of the player after the placement
+            for (turn, next_turn) in game.turns.iter().zip(game.turns.iter().skip(1)) {
+                let action = turn.action.unwrap();
+                if !action.is_patch_placement() {
+                    continue;
+                }
+
+                let is_player_1 = turn.state.is_player_1();
+                let (before, after) = if is_player_1 {
+                    (&turn.state.player_1, &next_turn.state.player_1)
+                } else {
+                    (&turn.state.player_2, &next_turn.state.player_2)
+                };
+                let covered = after.quilt_board.tiles & !before.quilt_board.tiles;
+                let counts = &mut expected[action.get_patch_id() as usize][usize::from(is_player_1 != player_1_won)];
+                for (index, count) in counts.iter_mut().enumerate() {
+                    *count += u64::from((covered >> index) & 1 == 1);
+                }
+            }
+            heatmap.add_game(&game, game.get_termination_result());
+        }
+        std::fs::remove_dir_all(&corpus).unwrap();
+
+        assert_eq!(expected, heatmap.counts);
+        assert!(expected.iter().flatten().flatten().any(|count| *count > 0));
+
+        let output = std::env::temp_dir().join(format!("patchwork-placement-heatmap-csv-{}", std::process::id()));
+        std::fs::create_dir_all(&output).unwrap();
+        heatmap.write_csv(&output).unwrap();
+        let written = std::fs::read_to_string(output.join("placement_heatmap_00.csv")).unwrap();
+        std::fs::remove_dir_all(&output).unwrap();
+
+        assert_eq!(TILES, written.lines().count());
+        let (won, lost) = (heatmap.get_heatmap(0, true), heatmap.get_heatmap(0, false));
+        assert_eq!(format!("0,0,{},{}", won[0][0], lost[0][0]), written.lines().next().unwrap());
+    }
+}
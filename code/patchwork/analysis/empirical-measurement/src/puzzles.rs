@@ -0,0 +1,203 @@
+use std::{path::Path, time::Duration};
+
+use action_orderer::TableActionOrderer;
+use evaluator::StaticEvaluator;
+use patchwork_core::{ActionId, Logging, Notation, Patchwork, Player};
+use principal_variation_search_player::{DefaultPVSPlayer, LazySMPFeature, PVSFeatures, PVSOptions};
+
+use crate::deserialization::Game;
+
+/// A position where one action is decisively better than all alternatives.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Puzzle {
+    /// The position of the puzzle.
+    pub state: Patchwork,
+    /// The best action in the position.
+    pub solution: ActionId,
+    /// The evaluation of the best action from the perspective of the player
+    /// to move.
+    pub evaluation: i32,
+    /// The difference between the evaluation of the best and the second best
+    /// action.
+    pub gap: i32,
+}
+
+/// Finds puzzles in recorded games. A position is a puzzle if the best action
+/// is better than every other action by at least the minimum gap according
+/// to a principal variation search with a fixed depth.
+pub struct PuzzleFinder {
+    /// The player that searches the best action and the best alternative.
+    player: Box<dyn Player>,
+    /// The minimum difference between the best and the second best action.
+    minimum_gap: i32,
+}
+
+impl PuzzleFinder {
+    /// Creates a new [`PuzzleFinder`].
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The depth every position is searched to.
+    /// * `minimum_gap` - The minimum difference between the best and the second best action.
+    #[must_use]
+    pub fn new(depth: u8, minimum_gap: i32) -> Self {
+        Self {
+            player: create_player(depth),
+            minimum_gap,
+        }
+    }
+
+    /// Finds all puzzles in the positions of the given game. Terminated
+    /// positions and positions with only one valid action are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The recorded game.
+    ///
+    /// # Returns
+    ///
+    /// The puzzles in the order of the positions of the game.
+    #[must_use]
+    pub fn find_in_game(&mut self, game: &Game) -> Vec<Puzzle> {
+        game.turns
+            .iter()
+            .filter_map(|turn| self.find_in_position(&turn.state))
+            .collect()
+    }
+
+    /// Checks whether the given position is a puzzle.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The position to check.
+    ///
+    /// # Returns
+    ///
+    /// The puzzle or `None` if no action is decisively better than all others.
+    #[must_use]
+    pub fn find_in_position(&mut self, state: &Patchwork) -> Option<Puzzle> {
+        if state.is_terminated() || state.get_valid_actions().len() < 2 {
+            return None;
+        }
+
+        // the search reports the evaluations of the best action and the best alternative
+        let (solution, diagnostics) = self.player.get_action_with_diagnostics(state).ok()?;
+        let (Some(evaluation), Some(gap)) = (diagnostics.evaluation(), diagnostics.margin()) else {
+            return None;
+        };
+
+        (gap >= self.minimum_gap).then(|| Puzzle {
+            state: state.clone(),
+            solution,
+            evaluation,
+            gap,
+        })
+    }
+}
+
+/// Creates the single threaded player that searches the positions to the
+/// given depth.
+///
+/// # Arguments
+///
+/// * `depth` - The depth every position is searched to.
+///
+/// # Returns
+///
+/// The player that searches the positions.
+fn create_player(depth: u8) -> Box<dyn Player> {
+    DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::new(
+        format!("PuzzleFinder(depth: {depth})"),
+        Some(PVSOptions {
+            // the fixed depth ends the search, the time limit only guards against a search that never ends
+            time_limit: Duration::from_secs(1000),
+            max_depth: Some(depth),
+            // a single thread makes the labels reproducible
+            features: PVSFeatures {
+                lazy_smp: LazySMPFeature::No,
+                ..PVSFeatures::default()
+            },
+            logging: Logging::Disabled,
+            ..PVSOptions::default()
+        }),
+    )
+}
+
+/// Writes the puzzles to a CSV file with the position and the solution in
+/// the patchwork notation. Phantom positions cannot be written as notation
+/// and are skipped.
+///
+/// # Arguments
+///
+/// * `path` - The file to write the puzzles to.
+/// * `puzzles` - The puzzles to write.
+///
+/// # Returns
+///
+/// The amount of written puzzles.
+///
+/// # Errors
+///
+/// If the file could not be written.
+pub fn write_puzzles(path: &Path, puzzles: &[Puzzle]) -> csv::Result<usize> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(["state", "solution", "evaluation", "gap"])?;
+
+    let mut written = 0;
+    for puzzle in puzzles {
+        let (Ok(state), Ok(solution)) = (puzzle.state.save_to_notation(), puzzle.solution.save_to_notation()) else {
+            continue;
+        };
+        writer.write_record([state, solution, puzzle.evaluation.to_string(), puzzle.gap.to_string()])?;
+        written += 1;
+    }
+    writer.flush()?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::generation::{generate_game, GeneratorPlayer};
+
+    use super::*;
+
+    #[test]
+    fn test_puzzles_are_labeled_by_the_search() {
+        let game = generate_game(7, GeneratorPlayer::Random, GeneratorPlayer::Random);
+        let mut finder = PuzzleFinder::new(1, 5);
+        let puzzles = finder.find_in_game(&game);
+        assert!(!puzzles.is_empty());
+
+        // the gap is the distance to the best alternative of the same search
+        let mut alternative_player = create_player(1);
+        for puzzle in &puzzles {
+            assert!(puzzle.gap >= 5);
+            let alternatives = puzzle
+                .state
+                .get_valid_actions()
+                .into_iter()
+                .filter(|action| *action != puzzle.solution)
+                .collect::<Vec<_>>();
+            let (_, diagnostics) = alternative_player
+                .get_restricted_action_with_diagnostics(&puzzle.state, &alternatives)
+                .unwrap();
+            assert_eq!(Some(puzzle.evaluation - puzzle.gap), diagnostics.evaluation());
+        }
+
+        let path = std::env::temp_dir().join(format!("patchwork-puzzles-{}.csv", std::process::id()));
+        let written = write_puzzles(&path, &puzzles).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(written + 1, csv.lines().count());
+        assert!(csv.contains(&puzzles[0].solution.save_to_notation().unwrap()));
+    }
+
+    #[test]
+    fn test_positions_without_gap_are_no_puzzles() {
+        let mut finder = PuzzleFinder::new(1, i32::MAX);
+        let game = generate_game(3, GeneratorPlayer::Random, GeneratorPlayer::Random);
+
+        assert!(finder.find_in_game(&game).is_empty());
+    }
+}
@@ -0,0 +1,120 @@
+use std::collections::BTreeMap;
+
+use patchwork_core::{Termination, TerminationType};
+
+use crate::deserialization::Game;
+
+/// Correlates the lead of a player on the time board with the win rate of
+/// that player.
+///
+/// For every state of a game both players are counted: the lead of a player
+/// is their position on the time board minus the position of the opponent.
+/// A negative lead means the player is behind on the track and thus usually
+/// the one to move.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TimeBoardAdvantage {
+    /// The amount of wins and the amount of samples per lead.
+    buckets: BTreeMap<i32, (usize, usize)>,
+}
+
+impl TimeBoardAdvantage {
+    /// Creates a new empty [`TimeBoardAdvantage`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all states of the given game.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The recorded game.
+    /// * `termination` - The termination result of the game (see
+    ///   [`Game::get_termination_result`]).
+    pub fn add_game(&mut self, game: &Game, termination: Termination) {
+        let player_1_won = termination.termination == TerminationType::Player1Won;
+
+        for turn in game.turns.iter().filter(|turn| !turn.state.is_terminated()) {
+            let lead =
+                i32::from(turn.state.player_1.get_position()) - i32::from(turn.state.player_2.get_position());
+
+            self.add_sample(lead, player_1_won);
+            self.add_sample(-lead, !player_1_won);
+        }
+    }
+
+    fn add_sample(&mut self, lead: i32, won: bool) {
+        let (wins, samples) = self.buckets.entry(lead).or_default();
+        *wins += usize::from(won);
+        *samples += 1;
+    }
+
+    /// Gets the win rate of a player with the given lead.
+    ///
+    /// # Returns
+    ///
+    /// The win rate or `None` if no state with this lead was seen.
+    #[must_use]
+    pub fn get_win_rate(&self, lead: i32) -> Option<f64> {
+        self.buckets.get(&lead).map(|(wins, samples)| *wins as f64 / *samples as f64)
+    }
+
+    /// Gets the table of all seen leads ordered by lead.
+    ///
+    /// # Returns
+    ///
+    /// The rows `(lead, samples, win rate)` of the table.
+    #[must_use]
+    pub fn get_table(&self) -> Vec<(i32, usize, f64)> {
+        self.buckets
+            .iter()
+            .map(|(lead, (wins, samples))| (*lead, *samples, *wins as f64 / *samples as f64))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        deserialization::GameLoader,
+        generation::{generate_games, GenerationOptions, GeneratorPlayer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_every_lead_has_a_win_rate() {
+        let corpus = std::env::temp_dir().join(format!("patchwork-time-board-advantage-{}", std::process::id()));
+        let options = GenerationOptions {
+            games: 4,
+            base_seed: 0,
+            player_1: GeneratorPlayer::Random,
+            player_2: GeneratorPlayer::Random,
+            games_per_file: 2,
+        };
+        generate_games(&corpus, &options).unwrap();
+
+        let mut advantage = TimeBoardAdvantage::new();
+        let mut expected_leads = std::collections::BTreeSet::new();
+        for game in GameLoader::new(&corpus, None) {
+            for turn in game.turns.iter().filter(|turn| !turn.state.is_terminated()) {
+                let lead =
+                    i32::from(turn.state.player_1.get_position()) - i32::from(turn.state.player_2.get_position());
+                expected_leads.extend([lead, -lead]);
+            }
+            advantage.add_game(&game, game.get_termination_result());
+        }
+        std::fs::remove_dir_all(&corpus).unwrap();
+
+        let table = advantage.get_table();
+        assert_eq!(expected_leads.into_iter().collect::<Vec<_>>(), table.iter().map(|row| row.0).collect::<Vec<_>>());
+        for (lead, samples, win_rate) in table {
+            assert!(samples > 0);
+            assert!((0.0..=1.0).contains(&win_rate));
+            // every sample is counted for both players
+            let opponent_win_rate = advantage.get_win_rate(-lead).unwrap();
+            assert!((win_rate + opponent_win_rate - 1.0).abs() < 1e-9);
+        }
+        assert_eq!(Some(0.5), advantage.get_win_rate(0));
+    }
+}
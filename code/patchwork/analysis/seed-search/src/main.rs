@@ -0,0 +1,175 @@
+use std::ops::Range;
+
+use empirical_measurement::interrupt::Interrupt;
+use patchwork_core::{GameOptions, Patch, Patchwork, QuiltBoard, TimeBoard};
+
+/// The metric by which the opening positions of the seeds are compared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    /// The first patches available to the starting player are as cheap as
+    /// possible. The metric is the negated sum of their button costs.
+    Cheapest,
+    /// One of the first patches available to the starting player is as
+    /// valuable as possible. The metric is the highest value of the patches
+    /// (see [`get_patch_value`]).
+    MostValuable,
+}
+
+impl Metric {
+    /// Evaluates the opening position with this metric. A higher value means
+    /// a more extreme opening position.
+    fn evaluate(self, state: &Patchwork) -> f64 {
+        let patches = state.patches_available_to_current_player();
+
+        match self {
+            Self::Cheapest => -patches.iter().map(|patch| f64::from(patch.button_cost)).sum::<f64>(),
+            Self::MostValuable => patches
+                .iter()
+                .map(get_patch_value)
+                .fold(f64::NEG_INFINITY, f64::max),
+        }
+    }
+}
+
+/// The opening position of a single seed.
+#[derive(Debug, Clone, PartialEq)]
+struct SeedResult {
+    /// The seed of the game.
+    seed: u64,
+    /// The value of the metric for the opening position.
+    metric: f64,
+    /// The ids of the patches available to the starting player.
+    first_patches: Vec<u8>,
+}
+
+/// Gets the value of a patch when it is taken at the start of the game. This
+/// is the button income over all button income triggers plus the points for
+/// the covered tiles minus the button cost of the patch.
+fn get_patch_value(patch: &Patch) -> f64 {
+    let income = f64::from(patch.button_income) * TimeBoard::AMOUNT_OF_BUTTON_INCOME_TRIGGERS as f64;
+    let tiles = f64::from(patch.amount_tiles()) * -f64::from(QuiltBoard::EMPTY_TILE_PENALTY);
+
+    income + tiles - f64::from(patch.button_cost)
+}
+
+/// Scans the opening positions of all given seeds.
+///
+/// # Arguments
+///
+/// * `seeds` - The seeds to scan.
+/// * `metric` - The metric to compare the opening positions with.
+/// * `interrupt` - Triggered when the search is interrupted. No further seeds are scanned afterwards.
+///
+/// # Returns
+///
+/// The opening positions of all scanned seeds sorted from the most extreme to
+/// the least extreme by the metric. Seeds with the same metric are sorted by
+/// the seed.
+fn search_seeds(seeds: Range<u64>, metric: Metric, interrupt: &Interrupt) -> Vec<SeedResult> {
+    let mut results = seeds
+        .take_while(|_| !interrupt.is_interrupted())
+        .map(|seed| {
+            let state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+
+            SeedResult {
+                seed,
+                metric: metric.evaluate(&state),
+                first_patches: state
+                    .patches_available_to_current_player()
+                    .iter()
+                    .map(|patch| patch.id)
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    results.sort_by(|a, b| b.metric.total_cmp(&a.metric).then(a.seed.cmp(&b.seed)));
+    results
+}
+
+fn main() {
+    let cmd = clap::Command::new("seed-search")
+        .bin_name("seed-search")
+        .about("Searches the seeds that produce the most extreme opening positions")
+        .arg(
+            clap::Arg::new("start")
+                .long("start")
+                .help("The first seed to scan")
+                .default_value("0")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            clap::Arg::new("seeds")
+                .short('s')
+                .long("seeds")
+                .help("The amount of seeds to scan")
+                .default_value("100000")
+                .value_parser(clap::value_parser!(u64)),
+        )
+        .arg(
+            clap::Arg::new("metric")
+                .short('m')
+                .long("metric")
+                .help("The metric to compare the opening positions with")
+                .default_value("cheapest")
+                .value_parser(["cheapest", "most-valuable"]),
+        )
+        .arg(
+            clap::Arg::new("top")
+                .short('t')
+                .long("top")
+                .help("The amount of seeds to output")
+                .default_value("10")
+                .value_parser(clap::value_parser!(usize)),
+        );
+
+    let matches = cmd.get_matches();
+    let start = *matches.get_one::<u64>("start").unwrap();
+    let seeds = *matches.get_one::<u64>("seeds").unwrap();
+    let top = *matches.get_one::<usize>("top").unwrap();
+    let metric = match matches.get_one::<String>("metric").unwrap().as_str() {
+        "most-valuable" => Metric::MostValuable,
+        _ => Metric::Cheapest,
+    };
+
+    // the seeds scanned so far are still written when the search is interrupted
+    let interrupt = Interrupt::on_ctrl_c().unwrap();
+
+    let results = search_seeds(start..start.saturating_add(seeds), metric, &interrupt);
+    if interrupt.is_interrupted() {
+        eprintln!("Interrupted after scanning {} seeds", results.len());
+    }
+
+    println!("seed;metric;first patches");
+    for result in results.iter().take(top) {
+        println!("{};{};{:?}", result.seed, result.metric, result.first_patches);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_results_are_sorted_by_metric() {
+        for metric in [Metric::Cheapest, Metric::MostValuable] {
+            let results = search_seeds(0..50, metric, &Interrupt::new());
+
+            assert_eq!(50, results.len());
+            assert!(results.windows(2).all(|pair| pair[0].metric >= pair[1].metric));
+            assert!(results.iter().all(|result| result.first_patches.len() == 3));
+            for result in &results {
+                let state = Patchwork::get_initial_state(Some(GameOptions::new(result.seed)));
+                assert!((metric.evaluate(&state) - result.metric).abs() < f64::EPSILON);
+            }
+        }
+    }
+
+    #[test]
+    fn test_interrupted_search_scans_no_seeds() {
+        let interrupt = Interrupt::new();
+        interrupt.trigger();
+
+        assert!(search_seeds(0..50, Metric::Cheapest, &interrupt).is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+use evaluator::StaticEvaluator;
+use patchwork_core::{evaluator_constants, Evaluator, Patchwork};
+
+use crate::{endgame_solver, EndgameOptions};
+
+/// An evaluator that evaluates endgame positions exactly with the
+/// [`EndgameSolver`](crate::EndgameSolver) and all other positions with the
+/// given evaluator.
+///
+/// This can be used as the evaluator of other search players (e.g. PVS or
+/// MCTS) to refine their evaluations close to the end of the game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct EndgameEvaluator<Eval: Evaluator = StaticEvaluator> {
+    /// The options of the endgame solver.
+    pub options: EndgameOptions,
+    /// The evaluator used for positions that are not solved exactly.
+    pub evaluator: Eval,
+}
+
+impl<Eval: Evaluator> EndgameEvaluator<Eval> {
+    /// Creates a new [`EndgameEvaluator`].
+    #[must_use]
+    pub const fn new(options: EndgameOptions, evaluator: Eval) -> Self {
+        Self { options, evaluator }
+    }
+
+    /// Converts the exact outcome of a game into an evaluation. The outcome
+    /// is twice the score difference plus the tie break, so the evaluation is
+    /// the score difference in 1/100 with the tie break as half a point.
+    ///
+    /// # Arguments
+    ///
+    /// * `outcome` - The outcome of the game (see [`crate::EndgameSolver::get_outcome`]).
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the outcome that is always between the evaluations of
+    /// a lost and a won game.
+    #[must_use]
+    pub const fn get_evaluation(outcome: i32) -> i32 {
+        let evaluation = outcome * 50;
+
+        if evaluation >= evaluator_constants::POSITIVE_INFINITY {
+            evaluator_constants::POSITIVE_INFINITY - 1
+        } else if evaluation <= evaluator_constants::NEGATIVE_INFINITY {
+            evaluator_constants::NEGATIVE_INFINITY + 1
+        } else {
+            evaluation
+        }
+    }
+}
+
+impl<Eval: Evaluator> Evaluator for EndgameEvaluator<Eval> {
+    fn evaluate_intermediate_node(&self, game: &Patchwork) -> i32 {
+        endgame_solver::solve(game, &self.options).map_or_else(
+            || self.evaluator.evaluate_intermediate_node(game),
+            |(_, outcome)| Self::get_evaluation(outcome),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use super::*;
+    use crate::DefaultEndgameSolver;
+
+    #[test]
+    fn test_endgame_positions_are_evaluated_exactly() {
+        let evaluator = EndgameEvaluator::new(EndgameOptions::new(6), StaticEvaluator::new());
+        let solver = DefaultEndgameSolver::new("Endgame Solver", Some(evaluator.options.clone()));
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(3)));
+        let mut solved_positions = 0;
+        for seed in 0.. {
+            if state.is_terminated() {
+                break;
+            }
+
+            match solver.solve(&state) {
+                Some((_, outcome)) => {
+                    assert_eq!(EndgameEvaluator::<StaticEvaluator>::get_evaluation(outcome), evaluator.evaluate_node(&state));
+                    solved_positions += 1;
+                }
+                None => assert_eq!(StaticEvaluator::new().evaluate_node(&state), evaluator.evaluate_node(&state)),
+            }
+
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+        }
+
+        assert!(solved_positions > 0);
+    }
+
+    #[test]
+    fn test_evaluation_within_the_threshold_finishes_within_the_budget() {
+        // every position after the first ply is within the threshold but far too large to be solved
+        let time_limit = std::time::Duration::from_millis(50);
+        let options = EndgameOptions::new(u8::MAX)
+            .with_max_nodes(usize::MAX)
+            .with_time_limit(time_limit);
+        let evaluator = EndgameEvaluator::new(options, StaticEvaluator::new());
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(3)));
+        state.do_action(state.get_seeded_random_action(3), false).unwrap();
+        assert!(endgame_solver::is_endgame(&state, &evaluator.options));
+
+        let start_time = std::time::Instant::now();
+        let evaluation = evaluator.evaluate_node(&state);
+
+        assert!(start_time.elapsed() < 20 * time_limit, "The evaluation took {:?}", start_time.elapsed());
+        assert_eq!(StaticEvaluator::new().evaluate_node(&state), evaluation);
+    }
+}
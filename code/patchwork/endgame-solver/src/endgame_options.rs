@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+/// The options for [`EndgameSolver`](crate::EndgameSolver).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EndgameOptions {
+    /// The maximum amount of time steps both players together may have left
+    /// on the time board for a position to be solved exactly. Apart from
+    /// special patch placements every action moves the player at least one
+    /// time step forward, so this also bounds the depth of the exhaustive
+    /// search.
+    pub max_remaining_time: u8,
+    /// The maximum amount of nodes a single exhaustive search may visit. The
+    /// search is abandoned once the budget is used up and the position is
+    /// treated as if it was not an endgame position.
+    pub max_nodes: usize,
+    /// The maximum time a single exhaustive search may take. The search is
+    /// abandoned like for [`EndgameOptions::max_nodes`] once the time is up.
+    /// `None` to only limit the amount of nodes.
+    pub time_limit: Option<Duration>,
+}
+
+impl EndgameOptions {
+    /// The default maximum amount of nodes a single exhaustive search may
+    /// visit.
+    pub const DEFAULT_MAX_NODES: usize = 1_000_000;
+
+    /// Creates a new [`EndgameOptions`] with the default budget.
+    #[must_use]
+    pub const fn new(max_remaining_time: u8) -> Self {
+        Self {
+            max_remaining_time,
+            max_nodes: Self::DEFAULT_MAX_NODES,
+            time_limit: None,
+        }
+    }
+
+    /// Sets the maximum amount of nodes a single exhaustive search may visit.
+    #[must_use]
+    pub const fn with_max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    /// Sets the maximum time a single exhaustive search may take.
+    #[must_use]
+    pub const fn with_time_limit(mut self, time_limit: Duration) -> Self {
+        self.time_limit = Some(time_limit);
+        self
+    }
+}
+
+impl Default for EndgameOptions {
+    fn default() -> Self {
+        Self::new(10)
+    }
+}
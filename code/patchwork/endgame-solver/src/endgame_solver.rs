@@ -0,0 +1,401 @@
+use std::time::Instant;
+
+use evaluator::StaticEvaluator;
+use patchwork_core::{ActionId, Evaluator, Patchwork, Player, PlayerResult, PlayerState, TerminationType, TimeBoard};
+
+use crate::EndgameOptions;
+
+/// A computer player that solves the endgame exactly.
+///
+/// Once both players together have at most
+/// [`EndgameOptions::max_remaining_time`] time steps left the remaining game
+/// tree is small enough to be searched exhaustively with alpha-beta pruning.
+/// This finds the exact outcome of the game under optimal play of both
+/// players. Before the endgame is reached or if the search exceeds the budget
+/// of [`EndgameOptions::max_nodes`] and [`EndgameOptions::time_limit`] the
+/// player greedily takes the action with the best evaluation of the
+/// evaluator.
+///
+/// Other search players can use the solver through the
+/// [`EndgameEvaluator`](crate::EndgameEvaluator).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EndgameSolver<Eval: Evaluator = StaticEvaluator> {
+    /// The name of the player.
+    pub name: String,
+    /// The options of the solver.
+    pub options: EndgameOptions,
+    /// The evaluator used to choose an action before the endgame is reached.
+    pub evaluator: Eval,
+}
+
+pub type DefaultEndgameSolver = EndgameSolver<StaticEvaluator>;
+
+impl<Eval: Evaluator + Default> EndgameSolver<Eval> {
+    /// Creates a new [`EndgameSolver`] with the given name and options.
+    pub fn new(name: impl Into<String>, options: Option<EndgameOptions>) -> Self {
+        Self {
+            name: name.into(),
+            options: options.unwrap_or_default(),
+            evaluator: Eval::default(),
+        }
+    }
+}
+
+impl<Eval: Evaluator + Default> Default for EndgameSolver<Eval> {
+    fn default() -> Self {
+        Self::new("Endgame Solver".to_string(), None)
+    }
+}
+
+impl<Eval: Evaluator> Player for EndgameSolver<Eval> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.get_restricted_action(game, &[])
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        // A forced move is played without solving
+        let valid_actions = game.get_valid_actions();
+        let actions = if actions.is_empty() { &valid_actions } else { actions };
+        if let [action] = actions {
+            return Ok(*action);
+        }
+
+        if let Some((action, _)) = solve_actions(game, actions, &self.options) {
+            return Ok(action);
+        }
+
+        let perspective = if game.is_player_1() { 1 } else { -1 };
+        let (action, _) = game
+            .expand()
+            .into_iter()
+            .filter(|(action, _)| actions.contains(action))
+            .max_by_key(|(_, next_state)| perspective * self.evaluator.evaluate_node(next_state))
+            .unwrap();
+
+        Ok(action)
+    }
+}
+
+impl<Eval: Evaluator> EndgameSolver<Eval> {
+    /// Whether the given position is small enough to be solved exactly.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The position to check.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the game is not terminated and both players together have at
+    /// most [`EndgameOptions::max_remaining_time`] time steps left.
+    #[must_use]
+    pub fn is_endgame(&self, game: &Patchwork) -> bool {
+        is_endgame(game, &self.options)
+    }
+
+    /// Solves the given position exactly.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The position to solve.
+    ///
+    /// # Returns
+    ///
+    /// The best action of the current player together with the exact outcome
+    /// of the game (see [`Self::get_outcome`]) or `None` if the position is
+    /// not an endgame position or the search exceeded its budget.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑏ᵈ)` where `𝑏` is the branching factor and `𝑑` is the amount of
+    /// actions until the game is terminated.
+    #[must_use]
+    pub fn solve(&self, game: &Patchwork) -> Option<(ActionId, i32)> {
+        solve(game, &self.options)
+    }
+
+    /// Gets the outcome of a terminated game from the perspective of player 1.
+    ///
+    /// The outcome is twice the score difference plus one if player 1 won and
+    /// minus one if player 2 won. This way the winner is always preferred and
+    /// a larger score difference is preferred between games with the same
+    /// winner.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The terminated game.
+    ///
+    /// # Returns
+    ///
+    /// The outcome of the game.
+    #[must_use]
+    pub fn get_outcome(game: &Patchwork) -> i32 {
+        get_outcome(game)
+    }
+}
+
+/// Whether the given position is small enough to be solved exactly with the
+/// given options (see [`EndgameSolver::is_endgame`]).
+pub fn is_endgame(game: &Patchwork, options: &EndgameOptions) -> bool {
+    let remaining_time = |player: &PlayerState| TimeBoard::MAX_POSITION - player.get_position();
+
+    !game.is_terminated()
+        && remaining_time(&game.player_1) + remaining_time(&game.player_2) <= options.max_remaining_time
+}
+
+/// Solves the given position exactly with the given options (see
+/// [`EndgameSolver::solve`]).
+pub fn solve(game: &Patchwork, options: &EndgameOptions) -> Option<(ActionId, i32)> {
+    solve_actions(game, &game.get_valid_actions(), options)
+}
+
+/// Solves the given position exactly like [`solve`] but only the given
+/// actions may be chosen at the root.
+fn solve_actions(game: &Patchwork, actions: &[ActionId], options: &EndgameOptions) -> Option<(ActionId, i32)> {
+    if !is_endgame(game, options) {
+        return None;
+    }
+
+    let mut game = game.clone();
+    let mut budget = SearchBudget::new(options);
+    let maximizing_player = game.is_player_1();
+    let mut alpha = i32::MIN;
+    let mut beta = i32::MAX;
+    let mut best = None;
+
+    for &action in actions {
+        game.do_action(action, false).unwrap();
+        let outcome = alpha_beta(&mut game, alpha, beta, &mut budget);
+        game.undo_action(action, false).unwrap();
+        let outcome = outcome?;
+
+        if maximizing_player && best.is_none_or(|(_, best_outcome)| outcome > best_outcome) {
+            best = Some((action, outcome));
+            alpha = alpha.max(outcome);
+        } else if !maximizing_player && best.is_none_or(|(_, best_outcome)| outcome < best_outcome) {
+            best = Some((action, outcome));
+            beta = beta.min(outcome);
+        }
+    }
+
+    best
+}
+
+/// Gets the outcome of a terminated game (see [`EndgameSolver::get_outcome`]).
+pub fn get_outcome(game: &Patchwork) -> i32 {
+    let termination = game.get_termination_result();
+    let tie_break = match termination.termination {
+        TerminationType::Player1Won => 1,
+        TerminationType::Player2Won => -1,
+    };
+
+    2 * termination.score() + tie_break
+}
+
+/// Searches the exact outcome of the given position with alpha-beta pruning.
+/// Player 1 is the maximizing and player 2 the minimizing player. As patchwork
+/// does not strictly alternate turns the player to move decides which one
+/// applies.
+///
+/// # Arguments
+///
+/// * `game` - The position to search. It is the same after the search.
+/// * `alpha` - The outcome player 1 is already guaranteed.
+/// * `beta` - The outcome player 2 is already guaranteed.
+/// * `budget` - The budget that is left for the search.
+///
+/// # Returns
+///
+/// The exact outcome if it is inside the window, otherwise a bound of it.
+/// `None` if the budget was used up.
+fn alpha_beta(game: &mut Patchwork, mut alpha: i32, mut beta: i32, budget: &mut SearchBudget) -> Option<i32> {
+    if !budget.visit_node() {
+        return None;
+    }
+
+    if game.is_terminated() {
+        return Some(get_outcome(game));
+    }
+
+    let maximizing_player = game.is_player_1();
+    let mut value = if maximizing_player { i32::MIN } else { i32::MAX };
+
+    for action in game.get_valid_actions() {
+        game.do_action(action, false).unwrap();
+        let outcome = alpha_beta(game, alpha, beta, budget);
+        game.undo_action(action, false).unwrap();
+        let outcome = outcome?;
+
+        if maximizing_player {
+            value = value.max(outcome);
+            alpha = alpha.max(value);
+        } else {
+            value = value.min(outcome);
+            beta = beta.min(value);
+        }
+
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    Some(value)
+}
+
+/// The amount of nodes and the time that are left for a single exhaustive
+/// search.
+struct SearchBudget {
+    /// The amount of nodes that may still be visited.
+    remaining_nodes: usize,
+    /// The time at which the search is abandoned. `None` if the time is not
+    /// limited.
+    deadline: Option<Instant>,
+}
+
+impl SearchBudget {
+    /// The amount of nodes after which the deadline is checked again, as
+    /// reading the clock at every node would slow down the search.
+    const NODES_BETWEEN_TIME_CHECKS: usize = 1024;
+
+    fn new(options: &EndgameOptions) -> Self {
+        Self {
+            remaining_nodes: options.max_nodes,
+            deadline: options.time_limit.map(|time_limit| Instant::now() + time_limit),
+        }
+    }
+
+    /// Visits a node of the search.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the budget is used up and the search has to be abandoned.
+    fn visit_node(&mut self) -> bool {
+        if self.remaining_nodes == 0 {
+            return false;
+        }
+        self.remaining_nodes -= 1;
+
+        !self.remaining_nodes.is_multiple_of(Self::NODES_BETWEEN_TIME_CHECKS)
+            || self.deadline.is_none_or(|deadline| Instant::now() < deadline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use super::*;
+
+    /// Plays seeded random games until an endgame position with more than a
+    /// single valid action is reached.
+    fn get_endgame_positions(options: &EndgameOptions, amount: usize) -> Vec<Patchwork> {
+        (0..1000)
+            .filter_map(|seed| {
+                let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+                let mut action_seed = seed;
+                while !state.is_terminated() {
+                    if is_endgame(&state, options) {
+                        return (state.get_valid_actions().len() > 1).then_some(state);
+                    }
+                    state.do_action(state.get_seeded_random_action(action_seed), false).unwrap();
+                    action_seed += 1;
+                }
+                None
+            })
+            .take(amount)
+            .collect()
+    }
+
+    /// Searches the exact outcome without any pruning.
+    fn minimax(game: &Patchwork) -> i32 {
+        if game.is_terminated() {
+            return get_outcome(game);
+        }
+
+        let outcomes = game.get_valid_actions().into_iter().map(|action| {
+            let mut next_state = game.clone();
+            next_state.do_action(action, false).unwrap();
+            minimax(&next_state)
+        });
+
+        if game.is_player_1() {
+            outcomes.max().unwrap()
+        } else {
+            outcomes.min().unwrap()
+        }
+    }
+
+    #[test]
+    fn test_solver_finds_provably_optimal_move() {
+        let options = EndgameOptions::new(8);
+        let solver = EndgameSolver::<StaticEvaluator>::new("Endgame Solver", Some(options.clone()));
+        let positions = get_endgame_positions(&options, 5);
+        assert_eq!(5, positions.len());
+
+        for position in positions {
+            let (action, outcome) = solver.solve(&position).unwrap();
+
+            let mut next_state = position.clone();
+            next_state.do_action(action, false).unwrap();
+
+            assert_eq!(minimax(&position), outcome);
+            assert_eq!(minimax(&next_state), outcome);
+        }
+    }
+
+    #[test]
+    fn test_restricted_solver_finds_best_of_the_given_actions() {
+        let options = EndgameOptions::new(8);
+        let mut solver = EndgameSolver::<StaticEvaluator>::new("Endgame Solver", Some(options.clone()));
+
+        for position in get_endgame_positions(&options, 5) {
+            let (best_action, _) = solver.solve(&position).unwrap();
+            let actions = position
+                .get_valid_actions()
+                .into_iter()
+                .filter(|action| *action != best_action)
+                .collect::<Vec<_>>();
+            let outcome_after = |action| {
+                let mut next_state = position.clone();
+                next_state.do_action(action, false).unwrap();
+                minimax(&next_state)
+            };
+            let outcomes = actions.iter().map(|action| outcome_after(*action));
+            let best_outcome = if position.is_player_1() {
+                outcomes.max().unwrap()
+            } else {
+                outcomes.min().unwrap()
+            };
+
+            let action = solver.get_restricted_action(&position, &actions).unwrap();
+            assert!(actions.contains(&action));
+            assert_eq!(best_outcome, outcome_after(action));
+        }
+    }
+
+    #[test]
+    fn test_solver_falls_back_to_evaluator_when_budget_is_used_up() {
+        let options = EndgameOptions::new(8).with_max_nodes(10);
+        let mut solver = EndgameSolver::<StaticEvaluator>::new("Endgame Solver", Some(options.clone()));
+
+        for position in get_endgame_positions(&options, 5) {
+            assert!(solver.is_endgame(&position));
+            assert_eq!(None, solver.solve(&position));
+
+            let action = solver.get_action(&position).unwrap();
+            assert!(position.get_valid_actions().contains(&action));
+        }
+    }
+
+    #[test]
+    fn test_positions_outside_endgame_are_not_solved() {
+        let solver = DefaultEndgameSolver::default();
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+
+        assert!(!solver.is_endgame(&state));
+        assert_eq!(None, solver.solve(&state));
+    }
+}
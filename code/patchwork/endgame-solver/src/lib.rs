@@ -0,0 +1,7 @@
+mod endgame_evaluator;
+mod endgame_options;
+mod endgame_solver;
+
+pub use endgame_evaluator::EndgameEvaluator;
+pub use endgame_options::EndgameOptions;
+pub use endgame_solver::{DefaultEndgameSolver, EndgameSolver};
@@ -6,12 +6,12 @@ use patchwork_core::{Evaluator, GameOptions, Patchwork};
 
 fn static_evaluator_forward(c: &mut Criterion) {
     c.bench_function("static_evaluator_forward", |b| {
-        let evaluator = StaticEvaluator;
+        let evaluator = StaticEvaluator::new();
 
         b.iter_with_setup(
             || {
                 let seed = rand::random::<u64>();
-                let mut patchwork = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let mut patchwork = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
 
                 for _ in 0..(seed % 22) {
                     patchwork.do_action(patchwork.get_random_action(), false).unwrap();
@@ -33,7 +33,7 @@ fn neural_network_evaluator_forward(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = rand::random::<u64>();
-                let mut patchwork = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let mut patchwork = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
 
                 for _ in 0..(seed % 22) {
                     patchwork.do_action(patchwork.get_random_action(), false).unwrap();
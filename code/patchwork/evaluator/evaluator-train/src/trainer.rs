@@ -9,7 +9,10 @@ use candle_core::{DType, Device, Tensor};
 use candle_nn::{Optimizer, VarBuilder, VarMap, SGD};
 use evaluator::{NeuralNetworkEvaluator, StaticEvaluator};
 use greedy_player::GreedyPlayer;
-use patchwork_core::{evaluator_constants, ActionId, Evaluator, Patchwork, PlayerResult, Termination, TerminationType};
+use patchwork_core::{
+    compute_value_targets, evaluator_constants, ActionId, Evaluator, GameRecord, Patchwork, PlayerResult,
+    TerminationType,
+};
 use rand::seq::SliceRandom;
 use rand::thread_rng;
 use rand_distr::{Distribution, WeightedIndex};
@@ -26,7 +29,8 @@ pub struct Trainer {
 
 pub struct History {
     pub state: Patchwork,
-    pub termination: Termination,
+    /// The value target from the perspective of the player to move in the state.
+    pub value_target: f32,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -157,7 +161,7 @@ impl Trainer {
         let game_counter = AtomicUsize::new(0);
         let number_of_games = self.args.number_of_games.get();
         let neural_net_player = RandomizedGreedyPlayer::new_with_evaluator(self.args.temperature, network);
-        let greedy_player = GreedyPlayer::new_with_evaluator("Greedy", StaticEvaluator);
+        let greedy_player = GreedyPlayer::new_with_evaluator("Greedy", StaticEvaluator::new());
 
         std::thread::scope(|s| {
             let mut threads = Vec::with_capacity(self.args.parallelization.get() - 1);
@@ -167,6 +171,7 @@ impl Trainer {
                         let mut states = vec![];
 
                         let mut state = Patchwork::get_initial_state(None);
+                        let mut record = GameRecord::new(state.clone());
                         loop {
                             states.push(state.clone());
 
@@ -177,16 +182,15 @@ impl Trainer {
                             };
 
                             state.do_action(action, false).expect("Failed to do action");
+                            record.push(action);
 
                             if state.is_terminated() {
                                 if game_counter.load(Ordering::Relaxed) >= number_of_games {
                                     break 'outer;
                                 }
 
-                                let termination = state.get_termination_result();
-
-                                for state in states {
-                                    history.push(History { state, termination });
+                                for (state, value_target) in states.into_iter().zip(compute_value_targets(&record)) {
+                                    history.push(History { state, value_target });
                                 }
                                 game_counter.fetch_add(1, Ordering::Relaxed);
                                 break;
@@ -203,6 +207,7 @@ impl Trainer {
                 let mut states = vec![];
 
                 let mut state = Patchwork::get_initial_state(None);
+                let mut record = GameRecord::new(state.clone());
                 loop {
                     states.push(state.clone());
 
@@ -213,16 +218,15 @@ impl Trainer {
                     };
 
                     state.do_action(action, false).expect("Failed to do action");
+                    record.push(action);
 
                     if state.is_terminated() {
                         if game_counter.load(Ordering::Relaxed) >= number_of_games {
                             break 'outer;
                         }
 
-                        let termination = state.get_termination_result();
-
-                        for state in states {
-                            history.push(History { state, termination });
+                        for (state, value_target) in states.into_iter().zip(compute_value_targets(&record)) {
+                            history.push(History { state, value_target });
                         }
                         game_counter.fetch_add(1, Ordering::Relaxed);
                         break;
@@ -292,16 +296,7 @@ impl Trainer {
                     // targets.push(target);
 
                     // Move to win loss
-                    let multiplier = if game.state.is_player_1() { 1.0 } else { -1.0 };
-
-                    let target_win: f32 = match game.termination.termination {
-                        TerminationType::Player1Won => 0.9,
-                        TerminationType::Player2Won => -0.9,
-                    };
-                    let target_score = 0.1 * (game.termination.score() as f32 / 75.0);
-                    let target = (target_win + target_score).clamp(-1.0, 1.0);
-
-                    let target = Tensor::new(multiplier * target, &Device::Cpu)?;
+                    let target = Tensor::new(game.value_target, &Device::Cpu)?;
 
                     targets.push(target);
                 }
@@ -331,7 +326,7 @@ impl Trainer {
 
         let player_1 = GreedyPlayer::new_with_evaluator("1", new_network);
         // let player_2 = GreedyPlayer::new_with_evaluator("2", old_network);
-        let player_2 = GreedyPlayer::new_with_evaluator("2", StaticEvaluator);
+        let player_2 = GreedyPlayer::new_with_evaluator("2", StaticEvaluator::new());
 
         let percentage = self.compare_players(&player_1, &player_2, self.args.evaluation_games * multiplier);
 
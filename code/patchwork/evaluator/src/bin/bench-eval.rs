@@ -0,0 +1,155 @@
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "neural")]
+use candle_core::{DType, Device};
+#[cfg(feature = "neural")]
+use candle_nn::{VarBuilder, VarMap};
+#[cfg(feature = "neural")]
+use evaluator::NeuralNetworkEvaluator;
+use evaluator::{
+    HeavyStaticEvaluator, RolloutEvaluator, RolloutOptions, ScoreEvaluator, StaticEvaluator, WinLossEvaluator,
+};
+use patchwork_core::{Evaluator, Patchwork, PositionCorpus, TurnType};
+
+/// The name of the evaluator all other evaluators are compared against.
+const BASELINE: &str = "static";
+/// The amount of times the corpus is evaluated if not given on the command line.
+const DEFAULT_ITERATIONS: usize = 100;
+
+/// The measured time a single evaluator took to evaluate the corpus.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EvaluatorTiming {
+    /// The name of the evaluator.
+    name: &'static str,
+    /// The amount of evaluations that were done.
+    evaluations: usize,
+    /// The time all evaluations took.
+    duration: Duration,
+}
+
+impl EvaluatorTiming {
+    /// Gets the amount of evaluations per second.
+    fn evaluations_per_second(&self) -> f64 {
+        self.evaluations as f64 / self.duration.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Gets how many times more expensive a single evaluation is compared to
+    /// the baseline.
+    fn relative_cost(&self, baseline: &Self) -> f64 {
+        baseline.evaluations_per_second() / self.evaluations_per_second()
+    }
+}
+
+/// Creates all evaluators that should be benchmarked. The neural network
+/// evaluator is initialized with random weights as only the cost of an
+/// evaluation is measured and is only registered with the `neural` feature.
+/// The `NNUEEvaluator` is not registered as it cannot evaluate a position
+/// without its incremental updates yet.
+fn get_registered_evaluators() -> Vec<(&'static str, Box<dyn Evaluator>)> {
+    #[allow(unused_mut)]
+    let mut evaluators: Vec<(&'static str, Box<dyn Evaluator>)> = vec![
+        (BASELINE, Box::new(StaticEvaluator::new())),
+        ("heavy-static", Box::new(HeavyStaticEvaluator::new())),
+        ("score", Box::new(ScoreEvaluator::new())),
+        ("win-loss", Box::new(WinLossEvaluator::new())),
+        ("rollout", Box::new(RolloutEvaluator::new(RolloutOptions::default()))),
+    ];
+
+    #[cfg(feature = "neural")]
+    {
+        let var_map = VarMap::new();
+        let var_builder = VarBuilder::from_varmap(&var_map, DType::F32, &Device::Cpu);
+        evaluators.push(("neural-network", Box::new(NeuralNetworkEvaluator::new(var_builder).unwrap())));
+    }
+
+    evaluators
+}
+
+/// Gets the positions of the standard corpus the evaluators are timed on.
+/// Phantom positions are skipped as the rollout based evaluators would never
+/// finish a rollout from them without forcing the player switch.
+fn get_positions() -> Vec<Patchwork> {
+    PositionCorpus::standard()
+        .into_iter()
+        .filter(|state| matches!(state.turn_type, TurnType::Normal | TurnType::SpecialPatchPlacement))
+        .collect()
+}
+
+/// Times every evaluator over the given positions.
+///
+/// # Arguments
+///
+/// * `evaluators` - The evaluators to time.
+/// * `positions` - The positions every evaluator evaluates.
+/// * `iterations` - How often all positions are evaluated.
+///
+/// # Returns
+///
+/// The timing of every evaluator in the order of the evaluators.
+fn benchmark(
+    evaluators: &[(&'static str, Box<dyn Evaluator>)],
+    positions: &[Patchwork],
+    iterations: usize,
+) -> Vec<EvaluatorTiming> {
+    evaluators
+        .iter()
+        .map(|(name, evaluator)| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                for position in positions {
+                    std::hint::black_box(evaluator.evaluate_node(std::hint::black_box(position)));
+                }
+            }
+
+            EvaluatorTiming {
+                name,
+                evaluations: iterations * positions.len(),
+                duration: start.elapsed(),
+            }
+        })
+        .collect()
+}
+
+fn main() {
+    let iterations = std::env::args()
+        .nth(1)
+        .map_or(DEFAULT_ITERATIONS, |arg| arg.parse().expect("The iterations have to be a number"));
+
+    let positions = get_positions();
+    let timings = benchmark(&get_registered_evaluators(), &positions, iterations);
+    let baseline = timings.iter().find(|timing| timing.name == BASELINE).unwrap();
+
+    println!("evaluator;evaluations;seconds;evaluations per second;relative cost");
+    for timing in &timings {
+        println!(
+            "{};{};{:.3};{:.0};{:.2}",
+            timing.name,
+            timing.evaluations,
+            timing.duration.as_secs_f64(),
+            timing.evaluations_per_second(),
+            timing.relative_cost(baseline)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_registered_evaluator_is_timed() {
+        let evaluators = get_registered_evaluators();
+        let positions = get_positions();
+        let timings = benchmark(&evaluators, &positions, 1);
+
+        assert_eq!(evaluators.len(), timings.len());
+        for ((name, _), timing) in evaluators.iter().zip(&timings) {
+            assert_eq!(*name, timing.name);
+            assert_eq!(positions.len(), timing.evaluations);
+            assert!(timing.evaluations_per_second() > 0.0);
+        }
+
+        let baseline = timings.iter().find(|timing| timing.name == BASELINE).unwrap();
+        assert!((baseline.relative_cost(baseline) - 1.0).abs() < f64::EPSILON);
+    }
+}
@@ -1,16 +1,125 @@
 use patchwork_core::{Evaluator, Patchwork, QuiltBoard, StableEvaluator};
 
-use crate::StaticEvaluator;
+use crate::{
+    static_evaluator::{parse_weights, write_weights},
+    StaticEvaluator, StaticWeights,
+};
+
+/// The weights of the terms of the [`HeavyStaticEvaluator`].
+#[derive(Debug, Clone, Copy)]
+pub struct HeavyStaticWeights {
+    /// The weights of the evaluation of the [`StaticEvaluator`].
+    pub static_weights: StaticWeights,
+    /// The weight of the free tiles that are surrounded by occupied tiles.
+    pub free_single_tiles: f64,
+    /// The weight of the free regions, which rewards bigger regions more.
+    pub free_regions: f64,
+}
+
+impl HeavyStaticWeights {
+    /// The weights the [`HeavyStaticEvaluator`] uses by default.
+    pub const DEFAULT: Self = Self {
+        static_weights: StaticWeights::DEFAULT,
+        free_single_tiles: 1.0,
+        free_regions: 1.0,
+    };
+
+    /// The names of the weights that are not part of the static weights.
+    const NAMES: [&'static str; 2] = ["free_single_tiles", "free_regions"];
+}
+
+impl Default for HeavyStaticWeights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// The weights are compared bitwise like the static weights.
+impl PartialEq for HeavyStaticWeights {
+    fn eq(&self, other: &Self) -> bool {
+        self.static_weights == other.static_weights
+            && self.free_single_tiles.to_bits() == other.free_single_tiles.to_bits()
+            && self.free_regions.to_bits() == other.free_regions.to_bits()
+    }
+}
+
+impl Eq for HeavyStaticWeights {}
+
+impl std::hash::Hash for HeavyStaticWeights {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.static_weights.hash(state);
+        self.free_single_tiles.to_bits().hash(state);
+        self.free_regions.to_bits().hash(state);
+    }
+}
+
+impl std::fmt::Display for HeavyStaticWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.static_weights)?;
+        write_weights(f, &Self::NAMES, &[self.free_single_tiles, self.free_regions])
+    }
+}
+
+impl std::str::FromStr for HeavyStaticWeights {
+    type Err = String;
+
+    /// Parses the weights from one `name = value` line per weight as written
+    /// by the [`Display`](std::fmt::Display) implementation. Missing weights
+    /// keep their default value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let names = StaticWeights::NAMES
+            .iter()
+            .chain(&Self::NAMES)
+            .copied()
+            .collect::<Vec<_>>();
+        let [board, score, position, button_income] = StaticWeights::DEFAULT.to_array();
+        let mut weights = [
+            board,
+            score,
+            position,
+            button_income,
+            Self::DEFAULT.free_single_tiles,
+            Self::DEFAULT.free_regions,
+        ];
+        parse_weights(s, &names, &mut weights)?;
+
+        Ok(Self {
+            static_weights: StaticWeights::from_array([weights[0], weights[1], weights[2], weights[3]]),
+            free_single_tiles: weights[4],
+            free_regions: weights[5],
+        })
+    }
+}
 
 /// A static evaluator for [`Patchwork`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct HeavyStaticEvaluator;
+pub struct HeavyStaticEvaluator {
+    /// The weights of the terms of the evaluation.
+    weights: HeavyStaticWeights,
+    /// The static evaluator with the static weights.
+    static_evaluator: StaticEvaluator,
+}
 
 impl HeavyStaticEvaluator {
     /// Creates a new [`HeavyStaticEvaluator`].
     #[must_use]
     pub const fn new() -> Self {
-        Self {}
+        Self::with_weights(HeavyStaticWeights::DEFAULT)
+    }
+
+    /// Creates a new [`HeavyStaticEvaluator`] with the given weights.
+    #[must_use]
+    pub const fn with_weights(weights: HeavyStaticWeights) -> Self {
+        Self {
+            weights,
+            static_evaluator: StaticEvaluator::with_weights(weights.static_weights),
+        }
+    }
+
+    /// Returns the weights of the terms of the evaluation.
+    #[must_use]
+    pub const fn weights(&self) -> HeavyStaticWeights {
+        self.weights
     }
 }
 
@@ -23,24 +132,31 @@ impl Default for HeavyStaticEvaluator {
 impl StableEvaluator for HeavyStaticEvaluator {}
 impl Evaluator for HeavyStaticEvaluator {
     fn evaluate_intermediate_node(&self, game: &Patchwork) -> i32 {
-        let player_1_score = evaluate_state_for_player(game, Patchwork::get_player_1_flag());
-        let player_2_score = evaluate_state_for_player(game, Patchwork::get_player_2_flag());
+        let player_1_score = self.evaluate_state_for_player(game, Patchwork::get_player_1_flag());
+        let player_2_score = self.evaluate_state_for_player(game, Patchwork::get_player_2_flag());
         (player_1_score - player_2_score) as i32
     }
 }
 
-fn evaluate_state_for_player(game: &Patchwork, player: u8) -> f64 {
-    let quilt_board = &game.get_player(player).quilt_board;
+impl HeavyStaticEvaluator {
+    fn evaluate_state_for_player(&self, game: &Patchwork, player: u8) -> f64 {
+        let quilt_board = &game.get_player(player).quilt_board;
 
-    // TODO: real evaluation
-    // static evaluator
-    // + region getter (exponential debuff for 1,2,3,... free places 50,25,12.5,...)
-    // maybe region tester if a patch that is still available fits into it
-    let normal_eval = StaticEvaluator.evaluate_state_for_player(game, player);
-    let free_single_tiles_score = get_free_single_tiles_score(quilt_board);
-    let free_region_score = get_free_region_score(quilt_board);
+        // TODO: real evaluation
+        // static evaluator
+        // + region getter (exponential debuff for 1,2,3,... free places 50,25,12.5,...)
+        // maybe region tester if a patch that is still available fits into it
+        let normal_eval = self.static_evaluator.evaluate_state_for_player(game, player);
+        let free_single_tiles_score = get_free_single_tiles_score(quilt_board);
+        let free_region_score = get_free_region_score(quilt_board);
 
-    normal_eval + free_single_tiles_score + free_region_score
+        self.weights.free_regions.mul_add(
+            free_region_score,
+            self.weights
+                .free_single_tiles
+                .mul_add(free_single_tiles_score, normal_eval),
+        )
+    }
 }
 
 fn get_free_single_tiles_score(quilt_board: &QuiltBoard) -> f64 {
@@ -101,12 +217,12 @@ fn get_free_region_score(quilt_board: &QuiltBoard) -> f64 {
 
                 stack.push((row + 1, col + 1));
                 stack.push((row + 1, col));
-                stack.push((row + 1, col - 1));
+                stack.push((row + 1, col.wrapping_sub(1)));
                 stack.push((row, col + 1));
-                stack.push((row, col - 1));
-                stack.push((row - 1, col + 1));
-                stack.push((row - 1, col));
-                stack.push((row - 1, col - 1));
+                stack.push((row, col.wrapping_sub(1)));
+                stack.push((row.wrapping_sub(1), col + 1));
+                stack.push((row.wrapping_sub(1), col));
+                stack.push((row.wrapping_sub(1), col.wrapping_sub(1)));
             }
 
             free_region_score += 81.0 / f64::from(region_size);
@@ -115,3 +231,68 @@ fn get_free_region_score(quilt_board: &QuiltBoard) -> f64 {
 
     free_region_score
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::PositionCorpus;
+
+    use super::*;
+
+    #[test]
+    fn test_default_weights_match_the_previous_evaluation() {
+        let evaluator = HeavyStaticEvaluator::new();
+        assert_eq!(HeavyStaticWeights::DEFAULT, evaluator.weights());
+
+        for state in PositionCorpus::standard()
+            .into_iter()
+            .filter(|state| !state.is_terminated())
+        {
+            let evaluate = |player| {
+                let quilt_board = &state.get_player(player).quilt_board;
+                StaticEvaluator::new().evaluate_state_for_player(&state, player)
+                    + get_free_single_tiles_score(quilt_board)
+                    + get_free_region_score(quilt_board)
+            };
+            let expected = (evaluate(Patchwork::get_player_1_flag()) - evaluate(Patchwork::get_player_2_flag())) as i32;
+            assert_eq!(expected, evaluator.evaluate_intermediate_node(&state));
+        }
+    }
+
+    #[test]
+    fn test_without_board_terms_matches_the_static_evaluator() {
+        let static_weights = StaticWeights {
+            board: 1.0,
+            score: 3.0,
+            position: 0.5,
+            button_income: 4.0,
+        };
+        let evaluator = HeavyStaticEvaluator::with_weights(HeavyStaticWeights {
+            static_weights,
+            free_single_tiles: 0.0,
+            free_regions: 0.0,
+        });
+        let static_evaluator = StaticEvaluator::with_weights(static_weights);
+
+        for state in PositionCorpus::standard() {
+            assert_eq!(static_evaluator.evaluate_node(&state), evaluator.evaluate_node(&state));
+        }
+    }
+
+    #[test]
+    fn test_free_regions_at_the_board_edges() {
+        // the empty board is a single region that is searched from the top left corner
+        assert!((1.0 - get_free_region_score(&QuiltBoard::new())).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_weights_round_trip_through_display() {
+        let weights = HeavyStaticWeights {
+            static_weights: StaticWeights::from_array([1.0, 2.5, 0.75, 6.0]),
+            free_single_tiles: 3.0,
+            free_regions: 0.5,
+        };
+
+        assert_eq!(Ok(weights), weights.to_string().parse());
+        assert_eq!(Ok(HeavyStaticWeights::DEFAULT), "".parse());
+    }
+}
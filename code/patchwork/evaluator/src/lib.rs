@@ -1,13 +1,19 @@
+#[cfg(feature = "neural")]
 mod neural_network_evaluator;
+#[cfg(feature = "neural")]
 mod nnue_evaluator;
+mod rollout_evaluator;
 mod score_evaluator;
 mod static_evaluator;
 mod win_loss_evaluator;
 mod heavy_static_evaluator;
 
+#[cfg(feature = "neural")]
 pub use neural_network_evaluator::NeuralNetworkEvaluator;
+#[cfg(feature = "neural")]
 pub use nnue_evaluator::NNUEEvaluator;
+pub use rollout_evaluator::{RolloutEvaluator, RolloutOptions};
 pub use score_evaluator::ScoreEvaluator;
-pub use static_evaluator::StaticEvaluator;
+pub use static_evaluator::{StaticEvaluator, StaticWeights};
 pub use win_loss_evaluator::WinLossEvaluator;
-pub use heavy_static_evaluator::HeavyStaticEvaluator;
\ No newline at end of file
+pub use heavy_static_evaluator::{HeavyStaticEvaluator, HeavyStaticWeights};
\ No newline at end of file
@@ -160,4 +160,13 @@ impl Evaluator for NeuralNetworkEvaluator {
     fn evaluate_intermediate_node(&self, game: &Patchwork) -> i32 {
         (self.forward(game).unwrap().to_scalar::<f32>().unwrap() * evaluator_constants::POSITIVE_INFINITY as f32) as i32
     }
+
+    fn win_probability(&self, game: &Patchwork) -> f32 {
+        if game.is_terminated() {
+            return if self.evaluate_terminal_node(game) > 0 { 1.0 } else { 0.0 };
+        }
+
+        // the network outputs a value in [-1, 1]
+        self.forward(game).unwrap().to_scalar::<f32>().unwrap().mul_add(0.5, 0.5)
+    }
 }
@@ -1,8 +1,11 @@
-use candle_core::{DType, Device, IndexOp, Module, Result, Tensor};
-use candle_nn::{Linear, VarBuilder};
+use std::{collections::HashMap, path::Path};
+
+use candle_core::{DType, Device, Error, IndexOp, Module, Result, Tensor};
+use candle_nn::{Linear, VarBuilder, VarMap};
 use patchwork_core::{
     evaluator_constants, ActionId, Evaluator, Patchwork, PlayerState, QuiltBoard, StableEvaluator, TurnType,
 };
+use safetensors::SafeTensors;
 
 /// ƎUИИ (Efficiently Updatable Neural Network) evaluator.
 ///
@@ -48,6 +51,20 @@ pub struct NNUEEvaluator {
 }
 
 impl NNUEEvaluator {
+    /// The version of the input feature encoding of the players and flags.
+    /// Has to be incremented whenever the encoding of the input features changes
+    /// as networks trained with another encoding produce garbage evaluations.
+    pub const FEATURE_VERSION: u32 = 1;
+
+    /// The key of the feature version in the metadata of saved networks.
+    const FEATURE_VERSION_KEY: &'static str = "feature_version";
+
+    /// Creates a new `NNUEEvaluator`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the initialization of the network
+    /// fails.
     #[rustfmt::skip]
     #[allow(clippy::unreadable_literal)]
     #[allow(clippy::needless_pass_by_value)]
@@ -77,6 +94,70 @@ impl NNUEEvaluator {
         })
     }
 
+    /// Saves the network in the given variables to a safetensors file. The
+    /// current [`NNUEEvaluator::FEATURE_VERSION`] is stamped into the metadata
+    /// of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `var_map` - The variables of the network.
+    /// * `path` - The path of the safetensors file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be written.
+    ///
+    /// # Panics
+    ///
+    /// If the lock of the variables is poisoned.
+    pub fn save<P: AsRef<Path>>(var_map: &VarMap, path: P) -> Result<()> {
+        let metadata = HashMap::from([(Self::FEATURE_VERSION_KEY.to_string(), Self::FEATURE_VERSION.to_string())]);
+        let tensors = var_map
+            .data()
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(name, var)| (name.clone(), var.as_tensor().clone()))
+            .collect::<Vec<_>>();
+
+        safetensors::serialize_to_file(tensors, &Some(metadata), path.as_ref()).map_err(Error::wrap)
+    }
+
+    /// Loads a saved network from the given safetensors file.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the file could not be read, is not
+    /// a valid network or if the network was trained with another feature version.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let buffer = std::fs::read(path)?;
+        Self::check_feature_version(&buffer)?;
+
+        let tensors = candle_core::safetensors::load_buffer(&buffer, &Device::Cpu)?;
+        Self::new(VarBuilder::from_tensors(tensors, DType::F32, &Device::Cpu))
+    }
+
+    /// Checks that the feature version in the metadata of the saved network
+    /// matches [`NNUEEvaluator::FEATURE_VERSION`].
+    fn check_feature_version(buffer: &[u8]) -> Result<()> {
+        let (_, metadata) = SafeTensors::read_metadata(buffer).map_err(Error::wrap)?;
+        let version = metadata
+            .metadata()
+            .as_ref()
+            .and_then(|metadata| metadata.get(Self::FEATURE_VERSION_KEY))
+            .ok_or_else(|| {
+                Error::Msg("[NNUEEvaluator::check_feature_version] The network has no feature version".to_string())
+            })?;
+
+        if *version != Self::FEATURE_VERSION.to_string() {
+            return Err(Error::Msg(format!(
+                "[NNUEEvaluator::check_feature_version] The network was trained with feature version {version} but feature version {} is required",
+                Self::FEATURE_VERSION
+            )));
+        }
+        Ok(())
+    }
+
     #[allow(clippy::unused_self)]
     fn get_player_tensor(&self, player: &PlayerState) -> Tensor {
         let mut vec = Vec::with_capacity(84);
@@ -286,6 +367,57 @@ impl Evaluator for NNUEEvaluator {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("patchwork-nnue-{name}-{}.safetensors", std::process::id()))
+    }
+
+    fn load_network(path: std::path::PathBuf) -> Result<NNUEEvaluator> {
+        let network = NNUEEvaluator::load(&path);
+        std::fs::remove_file(path).unwrap();
+        network
+    }
+
+    #[test]
+    fn test_load_checks_feature_version() {
+        let var_map = VarMap::new();
+        NNUEEvaluator::new(VarBuilder::from_varmap(&var_map, DType::F32, &Device::Cpu)).unwrap();
+        let tensors = var_map.data().lock().unwrap().clone();
+        assert!(!tensors.contains_key(NNUEEvaluator::FEATURE_VERSION_KEY));
+
+        let matching = get_path("matching");
+        NNUEEvaluator::save(&var_map, &matching).unwrap();
+        assert!(load_network(matching).is_ok());
+
+        let unversioned = get_path("unversioned");
+        var_map.save(&unversioned).unwrap();
+        let error = load_network(unversioned).unwrap_err().to_string();
+        assert!(error.contains("no feature version"), "Unexpected error: {error}");
+
+        let mismatched = get_path("mismatched");
+        let metadata = HashMap::from([(
+            NNUEEvaluator::FEATURE_VERSION_KEY.to_string(),
+            (NNUEEvaluator::FEATURE_VERSION - 1).to_string(),
+        )]);
+        safetensors::serialize_to_file(
+            tensors
+                .iter()
+                .map(|(name, var)| (name.clone(), var.as_tensor().clone())),
+            &Some(metadata),
+            &mismatched,
+        )
+        .unwrap();
+        let error = load_network(mismatched).unwrap_err().to_string();
+        assert!(
+            error.contains("trained with feature version"),
+            "Unexpected error: {error}"
+        );
+    }
+}
+
 // ```text
 //                          ⬐ σ=ReLU
 //         ┌─       ◯ ─── ┌──┐-_
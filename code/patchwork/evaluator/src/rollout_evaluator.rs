@@ -0,0 +1,285 @@
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use patchwork_core::{evaluator_constants, Evaluator, GameRng, Patchwork, TerminationType};
+use rand::{seq::SliceRandom, SeedableRng};
+use rayon::prelude::*;
+
+use crate::StaticEvaluator;
+
+/// The options for [`RolloutEvaluator`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RolloutOptions {
+    /// The maximum amount of random actions to take in a single rollout.
+    /// If the game is not terminated after this many actions the reached
+    /// state is evaluated with the [`StaticEvaluator`]. `None` to always play
+    /// until the end of the game.
+    pub depth_limit: Option<usize>,
+    /// The seed for the random number generator used for the rollouts.
+    pub seed: u64,
+    /// The amount of independent rollouts that are played (in parallel) per
+    /// evaluation. The evaluation is the average of all rollouts. More
+    /// rollouts reduce the variance of the evaluation.
+    pub rollouts: NonZeroUsize,
+}
+
+impl RolloutOptions {
+    /// Creates a new [`RolloutOptions`].
+    #[must_use]
+    pub const fn new(depth_limit: Option<usize>, seed: u64, rollouts: NonZeroUsize) -> Self {
+        Self {
+            depth_limit,
+            seed,
+            rollouts,
+        }
+    }
+}
+
+impl Default for RolloutOptions {
+    fn default() -> Self {
+        Self {
+            depth_limit: None,
+            seed: rand::random(),
+            rollouts: NonZeroUsize::new(1).unwrap(),
+        }
+    }
+}
+
+/// An evaluator that plays random actions until the game is over (classic
+/// MCTS rollouts) and returns whether the rollout was won or lost.
+///
+/// If a depth limit is set and reached before the game is over, the reached
+/// state is evaluated by the [`StaticEvaluator`] instead.
+///
+/// Every rollout uses its own random number generator seeded with the
+/// configured seed and the amount of rollouts that were already played, so
+/// evaluations are reproducible for the same sequence of calls. If multiple
+/// rollouts per evaluation are configured they are played in parallel and
+/// averaged.
+#[derive(Debug)]
+pub struct RolloutEvaluator {
+    /// The options of the evaluator.
+    options: RolloutOptions,
+    /// The evaluator used when the depth limit is reached.
+    fallback: StaticEvaluator,
+    /// The amount of rollouts that were already played.
+    rollouts: AtomicU64,
+}
+
+impl RolloutEvaluator {
+    /// Creates a new [`RolloutEvaluator`] with the given options.
+    #[must_use]
+    pub const fn new(options: RolloutOptions) -> Self {
+        Self {
+            options,
+            fallback: StaticEvaluator::new(),
+            rollouts: AtomicU64::new(0),
+        }
+    }
+
+    /// Plays all configured rollouts from the given state and averages them.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game state to start the rollouts from.
+    ///
+    /// # Returns
+    ///
+    /// The average evaluation of the states the rollouts ended in.
+    fn average_rollout(&self, game: &Patchwork) -> i32 {
+        let amount = self.options.rollouts.get();
+        let first_rollout = self.rollouts.fetch_add(amount as u64, Ordering::Relaxed);
+
+        if amount == 1 {
+            return self.rollout(game, first_rollout);
+        }
+
+        let sum = (0..amount as u64)
+            .into_par_iter()
+            .map(|i| i64::from(self.rollout(game, first_rollout + i)))
+            .sum::<i64>();
+
+        (sum / amount as i64) as i32
+    }
+
+    /// Plays a single random rollout from the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game state to start the rollout from.
+    /// * `rollout` - The number of the rollout used to seed the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the state the rollout ended in.
+    fn rollout(&self, game: &Patchwork, rollout: u64) -> i32 {
+        let mut random = GameRng::seed_from_u64(self.options.seed.wrapping_add(rollout));
+
+        let mut state = game.clone();
+        let mut depth = 0;
+
+        while !state.is_terminated() {
+            if self.options.depth_limit.is_some_and(|depth_limit| depth >= depth_limit) {
+                return self.fallback.evaluate_intermediate_node(&state);
+            }
+
+            // EXPECT: there is always at least one valid action in a non terminated state
+            let action = *state
+                .get_valid_actions()
+                .choose(&mut random)
+                .expect("[RolloutEvaluator::rollout] No valid actions in non terminated state");
+            // EXPECT: ACTIONS ARE ALL VALID SO NO ERRORS CAN OCCUR
+            state
+                .do_action(action, false)
+                .expect("[RolloutEvaluator::rollout] Action was not valid");
+            depth += 1;
+        }
+
+        self.evaluate_terminal_node(&state)
+    }
+}
+
+impl Default for RolloutEvaluator {
+    fn default() -> Self {
+        Self::new(RolloutOptions::default())
+    }
+}
+
+impl Clone for RolloutEvaluator {
+    fn clone(&self) -> Self {
+        Self {
+            options: self.options.clone(),
+            fallback: self.fallback.clone(),
+            rollouts: AtomicU64::new(self.rollouts.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Evaluator for RolloutEvaluator {
+    fn evaluate_intermediate_node(&self, game: &Patchwork) -> i32 {
+        self.average_rollout(game).clamp(
+            evaluator_constants::NEGATIVE_INFINITY,
+            evaluator_constants::POSITIVE_INFINITY,
+        )
+    }
+
+    fn evaluate_terminal_node(&self, game: &Patchwork) -> i32 {
+        // a win has to outrank every evaluation of a depth limited rollout and a tie on score is still decided by
+        // the player who reached the end first
+        match game.get_termination_result().termination {
+            TerminationType::Player1Won => evaluator_constants::POSITIVE_INFINITY,
+            TerminationType::Player2Won => evaluator_constants::NEGATIVE_INFINITY,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{Notation, PositionCorpus};
+
+    use super::*;
+
+    #[test]
+    fn test_near_terminal_winning_position() {
+        // player 2 is already at the end and player 1 is ahead by 100 buttons
+        let state = Patchwork::load_from_notation(
+            "000000000000000000000B100I0P52 000000000000000000000B0I0P53 33 N 1/2/3/4/5/6",
+        )
+        .unwrap();
+
+        for seed in 0..100 {
+            let evaluator = RolloutEvaluator::new(RolloutOptions::new(None, seed, NonZeroUsize::new(1).unwrap()));
+            let evaluation = evaluator.evaluate_node(&state);
+
+            assert!(
+                evaluation > 0,
+                "Expected winning evaluation but got {evaluation} for seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_win_outranks_depth_limited_rollouts() {
+        let won = Patchwork::load_from_notation(
+            "000000000000000000000B100I0P53 000000000000000000000B0I0P53 33 N 1/2/3/4/5/6",
+        )
+        .unwrap();
+        let winning = Patchwork::load_from_notation(
+            "000000000000000000000B100I0P52 000000000000000000000B0I0P53 33 N 1/2/3/4/5/6",
+        )
+        .unwrap();
+        let evaluator = RolloutEvaluator::new(RolloutOptions::new(Some(0), 42, NonZeroUsize::new(1).unwrap()));
+
+        assert_eq!(evaluator_constants::POSITIVE_INFINITY, evaluator.evaluate_node(&won));
+        assert!(evaluator.evaluate_node(&winning) < evaluator.evaluate_node(&won));
+    }
+
+    #[test]
+    fn test_tie_on_score_is_decided_by_the_first_player_at_the_end() {
+        // both players have the same score but player 2 reached the end first
+        let tied = Patchwork::load_from_notation(
+            "000000000000000000000B0I0P53 000000000000000000000B0I0P53 33 N 1/2/3/4/5/6",
+        )
+        .unwrap();
+        let termination = tied.get_termination_result();
+        assert_eq!(termination.player_1_score, termination.player_2_score);
+
+        let evaluator = RolloutEvaluator::new(RolloutOptions::new(Some(0), 42, NonZeroUsize::new(1).unwrap()));
+        assert_eq!(evaluator_constants::NEGATIVE_INFINITY, evaluator.evaluate_node(&tied));
+    }
+
+    #[test]
+    fn test_evaluations_within_bounds_on_corpus() {
+        let evaluator = RolloutEvaluator::new(RolloutOptions::new(Some(10), 42, NonZeroUsize::new(1).unwrap()));
+
+        for state in PositionCorpus::standard() {
+            let evaluation = evaluator.evaluate_node(&state);
+            assert!(
+                (evaluator_constants::NEGATIVE_INFINITY..=evaluator_constants::POSITIVE_INFINITY).contains(&evaluation),
+                "Evaluation {evaluation} out of bounds for state {state}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let state = Patchwork::get_initial_state(Some(patchwork_core::GameOptions::new(42)));
+
+        let evaluator_1 = RolloutEvaluator::new(RolloutOptions::new(None, 1337, NonZeroUsize::new(4).unwrap()));
+        let evaluator_2 = RolloutEvaluator::new(RolloutOptions::new(None, 1337, NonZeroUsize::new(4).unwrap()));
+
+        for _ in 0..10 {
+            assert_eq!(evaluator_1.evaluate_node(&state), evaluator_2.evaluate_node(&state));
+        }
+    }
+
+    #[test]
+    fn test_multiple_rollouts_reduce_variance() {
+        fn variance(rollouts: usize) -> f64 {
+            let mut state = Patchwork::get_initial_state(Some(patchwork_core::GameOptions::new(42)));
+            for i in 0..20 {
+                state.do_action(state.get_seeded_random_action(i), false).unwrap();
+            }
+
+            let evaluations = (0..200)
+                .map(|seed| {
+                    let options = RolloutOptions::new(None, seed * 1000, NonZeroUsize::new(rollouts).unwrap());
+                    f64::from(RolloutEvaluator::new(options).evaluate_node(&state))
+                })
+                .collect::<Vec<_>>();
+
+            let mean = evaluations.iter().sum::<f64>() / evaluations.len() as f64;
+            evaluations.iter().map(|e| (e - mean).powi(2)).sum::<f64>() / evaluations.len() as f64
+        }
+
+        let single_variance = variance(1);
+        let multiple_variance = variance(8);
+
+        assert!(
+            multiple_variance < single_variance,
+            "Expected variance with 8 rollouts ({multiple_variance}) to be lower than with 1 rollout ({single_variance})"
+        );
+    }
+}
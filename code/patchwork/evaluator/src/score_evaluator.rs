@@ -33,3 +33,62 @@ impl Evaluator for ScoreEvaluator {
         player_1_score - player_2_score
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{GameOptions, QuiltBoard};
+
+    use super::*;
+
+    #[test]
+    fn test_empty_tile_penalty_changes_evaluation_and_termination() {
+        const PENALTY: i32 = -5;
+
+        let evaluator = ScoreEvaluator::new();
+        let mut standard = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut custom =
+            Patchwork::get_initial_state(Some(GameOptions::builder().seed(42).empty_tile_penalty(PENALTY).build()));
+        assert_eq!(QuiltBoard::EMPTY_TILE_PENALTY, standard.get_empty_tile_penalty());
+        assert_eq!(PENALTY, custom.get_empty_tile_penalty());
+
+        let mut seed = 0;
+        while !standard.is_terminated() {
+            let action = standard.get_seeded_random_action(seed);
+            standard.do_action(action, false).unwrap();
+            custom.do_action(action, false).unwrap();
+            seed += 1;
+        }
+
+        let standard_termination = standard.get_termination_result();
+        let custom_termination = custom.get_termination_result();
+
+        assert_eq!(
+            standard_termination.player_1_score - standard_termination.player_2_score,
+            evaluator.evaluate_terminal_node(&standard)
+        );
+        assert_eq!(
+            custom_termination.player_1_score - custom_termination.player_2_score,
+            evaluator.evaluate_terminal_node(&custom)
+        );
+
+        let penalty_difference = PENALTY - QuiltBoard::EMPTY_TILE_PENALTY;
+        #[allow(clippy::cast_possible_wrap)]
+        let (player_1_free, player_2_free) = (
+            custom.player_1.quilt_board.tiles_free() as i32,
+            custom.player_2.quilt_board.tiles_free() as i32,
+        );
+        assert_eq!(
+            standard_termination.player_1_score + penalty_difference * player_1_free,
+            custom_termination.player_1_score
+        );
+        assert_eq!(
+            standard_termination.player_2_score + penalty_difference * player_2_free,
+            custom_termination.player_2_score
+        );
+        assert_ne!(
+            evaluator.evaluate_terminal_node(&standard),
+            evaluator.evaluate_terminal_node(&custom),
+            "The penalty should change the evaluation"
+        );
+    }
+}
@@ -1,14 +1,151 @@
 use patchwork_core::{Evaluator, Patchwork, QuiltBoard, StableEvaluator, TimeBoard};
 
+/// The weights of the terms of the [`StaticEvaluator`].
+#[derive(Debug, Clone, Copy)]
+pub struct StaticWeights {
+    /// The weight of the board score, which fades out over the game.
+    pub board: f64,
+    /// The weight of the current score, which fades in over the game.
+    pub score: f64,
+    /// The weight of the remaining positions on the time board.
+    pub position: f64,
+    /// The weight of the button income, which decays with every button income
+    /// trigger that was passed.
+    pub button_income: f64,
+}
+
+impl StaticWeights {
+    /// The weights the [`StaticEvaluator`] uses by default.
+    pub const DEFAULT: Self = Self {
+        board: 2.0,
+        score: 2.0,
+        position: 1.0,
+        button_income: 8.0,
+    };
+
+    /// The names of the weights in the order of [`StaticWeights::to_array`].
+    pub const NAMES: [&'static str; 4] = ["board", "score", "position", "button_income"];
+
+    /// Returns the weights as an array in the order of the fields.
+    #[must_use]
+    pub const fn to_array(&self) -> [f64; 4] {
+        [self.board, self.score, self.position, self.button_income]
+    }
+
+    /// Creates the weights from an array in the order of the fields.
+    #[must_use]
+    pub const fn from_array(weights: [f64; 4]) -> Self {
+        Self {
+            board: weights[0],
+            score: weights[1],
+            position: weights[2],
+            button_income: weights[3],
+        }
+    }
+}
+
+impl std::fmt::Display for StaticWeights {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_weights(f, &Self::NAMES, &self.to_array())
+    }
+}
+
+impl std::str::FromStr for StaticWeights {
+    type Err = String;
+
+    /// Parses the weights from one `name = value` line per weight as written
+    /// by the [`Display`](std::fmt::Display) implementation. Missing weights
+    /// keep their default value.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut weights = Self::DEFAULT.to_array();
+        parse_weights(s, &Self::NAMES, &mut weights)?;
+        Ok(Self::from_array(weights))
+    }
+}
+
+/// Writes one `name = value` line per weight.
+#[allow(clippy::redundant_pub_crate)] // shared with the heavy static evaluator only
+pub(crate) fn write_weights(f: &mut std::fmt::Formatter<'_>, names: &[&str], weights: &[f64]) -> std::fmt::Result {
+    for (name, weight) in names.iter().zip(weights) {
+        writeln!(f, "{name} = {weight}")?;
+    }
+    Ok(())
+}
+
+/// Parses weights from one `name = value` line per weight into the given
+/// weights. Empty lines and lines starting with `#` are ignored.
+///
+/// # Errors
+///
+/// If a line is not a known weight with a valid value.
+#[allow(clippy::redundant_pub_crate)] // shared with the heavy static evaluator only
+pub(crate) fn parse_weights(s: &str, names: &[&str], weights: &mut [f64]) -> Result<(), String> {
+    for line in s.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| format!("[parse_weights] Invalid weight line '{line}'"))?;
+        let index = names
+            .iter()
+            .position(|known| *known == name.trim())
+            .ok_or_else(|| format!("[parse_weights] Unknown weight '{}'", name.trim()))?;
+        weights[index] = value
+            .trim()
+            .parse()
+            .map_err(|error| format!("[parse_weights] Invalid value for weight '{}': {error}", name.trim()))?;
+    }
+    Ok(())
+}
+
+impl Default for StaticWeights {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// The weights are compared bitwise so that the evaluators stay `Eq` and `Hash`.
+impl PartialEq for StaticWeights {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_array().map(f64::to_bits) == other.to_array().map(f64::to_bits)
+    }
+}
+
+impl Eq for StaticWeights {}
+
+impl std::hash::Hash for StaticWeights {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.to_array().map(f64::to_bits).hash(state);
+    }
+}
+
 /// A static evaluator for [`Patchwork`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct StaticEvaluator;
+pub struct StaticEvaluator {
+    /// The weights of the terms of the evaluation.
+    weights: StaticWeights,
+}
 
 impl StaticEvaluator {
     /// Creates a new [`StaticEvaluator`].
     #[must_use]
     pub const fn new() -> Self {
-        Self {}
+        Self::with_weights(StaticWeights::DEFAULT)
+    }
+
+    /// Creates a new [`StaticEvaluator`] with the given weights, e.g. to tune
+    /// the weights.
+    #[must_use]
+    pub const fn with_weights(weights: StaticWeights) -> Self {
+        Self { weights }
+    }
+
+    /// Returns the weights of the terms of the evaluation.
+    #[must_use]
+    pub const fn weights(&self) -> StaticWeights {
+        self.weights
     }
 }
 
@@ -63,6 +200,7 @@ impl StaticEvaluator {
         let position_score = f64::from(TimeBoard::MAX_POSITION - player_state.get_position());
         let board_score = f64::from(get_board_score(quilt_board));
         let button_income_score = get_button_income_score(
+            self.weights.button_income,
             f64::from(quilt_board.button_income),
             &game.time_board,
             player_state.get_position(),
@@ -70,9 +208,9 @@ impl StaticEvaluator {
         // let free_single_tiles_score = get_free_single_tiles_score(quilt_board);
         // let free_region_score = self.get_free_region_score(quilt_board);
 
-        (board_score * 2.0).mul_add(
+        (board_score * self.weights.board).mul_add(
             1.0 - percentage_played,
-            (end_score * 2.0).mul_add(percentage_played, position_score),
+            (end_score * self.weights.score).mul_add(percentage_played, position_score * self.weights.position),
         ) + button_income_score
     }
 }
@@ -112,13 +250,133 @@ fn get_board_score(quilt_board: &QuiltBoard) -> i32 {
     board_score
 }
 
-fn get_button_income_score(button_income: f64, time_board: &TimeBoard, position: u8) -> f64 {
+fn get_button_income_score(weight: f64, button_income: f64, time_board: &TimeBoard, position: u8) -> f64 {
     let amount_button_income_triggers_left = time_board.get_amount_button_income_trigger_in_range(
         ((position + 1).min(TimeBoard::MAX_POSITION) as usize)..(TimeBoard::MAX_POSITION + 1) as usize,
     );
     let amount_button_income_triggers_passed =
         TimeBoard::AMOUNT_OF_BUTTON_INCOME_TRIGGERS as i32 - amount_button_income_triggers_left as i32;
 
-    // f(x) = 8exp(ln(1/8) * x / 8)
-    8.0 * ((1.0 / 8f64).ln() * f64::from(amount_button_income_triggers_passed) / 8.0).exp() * button_income
+    // f(x) = 8exp(ln(1/8) * x / 8) with the weight as the initial factor 8
+    weight * ((1.0 / 8f64).ln() * f64::from(amount_button_income_triggers_passed) / 8.0).exp() * button_income
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{Notation, PositionCorpus};
+
+    use super::*;
+
+    /// The file containing the expected evaluations of the standard position
+    /// corpus. Run the tests with `UPDATE_GOLDEN=1` to regenerate it after a
+    /// deliberate change of the evaluation.
+    const GOLDEN_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden/static_evaluator.txt");
+
+    /// Evaluates every position of the standard corpus. Every line contains
+    /// the index and evaluation of a position followed by its notation.
+    /// Phantom states cannot be saved as notation and are only marked.
+    fn evaluate_corpus(evaluator: &StaticEvaluator) -> Vec<String> {
+        PositionCorpus::standard()
+            .iter()
+            .enumerate()
+            .map(|(index, state)| {
+                let notation = state.save_to_notation().unwrap_or_else(|_| "phantom".to_string());
+                format!("{index:02} {:>5} {notation}", evaluator.evaluate_node(state))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_evaluation_matches_golden_file() {
+        let evaluations = evaluate_corpus(&StaticEvaluator::new());
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(std::path::Path::new(GOLDEN_FILE).parent().unwrap()).unwrap();
+            std::fs::write(GOLDEN_FILE, evaluations.join("\n") + "\n").unwrap();
+            return;
+        }
+
+        let golden = std::fs::read_to_string(GOLDEN_FILE).unwrap();
+        let golden = golden.lines().collect::<Vec<_>>();
+        assert_eq!(golden.len(), evaluations.len(), "The position corpus changed");
+
+        let changed = golden
+            .iter()
+            .zip(&evaluations)
+            .filter(|(expected, actual)| *expected != actual)
+            .map(|(expected, actual)| format!("expected: {expected}\nactual:   {actual}"))
+            .collect::<Vec<_>>();
+        assert!(
+            changed.is_empty(),
+            "{} evaluations changed (run with UPDATE_GOLDEN=1 to accept):\n{}",
+            changed.len(),
+            changed.join("\n")
+        );
+    }
+
+    #[test]
+    fn test_default_weights_match_the_default_evaluator() {
+        let evaluator = StaticEvaluator::new();
+        let weighted = StaticEvaluator::with_weights(StaticWeights::default());
+        assert_eq!(StaticWeights::DEFAULT, evaluator.weights());
+
+        for state in PositionCorpus::standard() {
+            assert_eq!(evaluator.evaluate_node(&state), weighted.evaluate_node(&state));
+        }
+    }
+
+    #[test]
+    fn test_custom_weights_change_the_evaluation_predictably() {
+        let position_only = StaticEvaluator::with_weights(StaticWeights {
+            board: 0.0,
+            score: 0.0,
+            position: 1.0,
+            button_income: 0.0,
+        });
+        let default = StaticEvaluator::new();
+        let doubled = StaticEvaluator::with_weights(StaticWeights::from_array(
+            StaticWeights::DEFAULT.to_array().map(|weight| weight * 2.0),
+        ));
+
+        for state in PositionCorpus::standard()
+            .into_iter()
+            .filter(|state| !state.is_terminated())
+        {
+            let position_difference =
+                i32::from(state.player_2.get_position()) - i32::from(state.player_1.get_position());
+            assert_eq!(position_difference, position_only.evaluate_intermediate_node(&state));
+
+            // the evaluation is truncated after the difference of both players
+            let evaluation = default.evaluate_intermediate_node(&state);
+            assert!((doubled.evaluate_intermediate_node(&state) - 2 * evaluation).abs() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_weights_round_trip_through_display() {
+        let weights = StaticWeights {
+            board: 1.5,
+            score: 2.25,
+            position: 0.5,
+            button_income: 7.0,
+        };
+
+        assert_eq!(Ok(weights), weights.to_string().parse());
+        assert_eq!(Ok(StaticWeights::DEFAULT), "# comment\n\nboard = 2".parse());
+        assert!("unknown = 1".parse::<StaticWeights>().is_err());
+        assert!("board = abc".parse::<StaticWeights>().is_err());
+    }
+
+    #[test]
+    fn test_evaluation_is_antisymmetric() {
+        let evaluator = StaticEvaluator::new();
+
+        for state in PositionCorpus::standard() {
+            assert_eq!(
+                evaluator.evaluate_node(&state),
+                -evaluator.evaluate_node(&state.swap_players()),
+                "Evaluation is not antisymmetric for state {state}"
+            );
+        }
+    }
 }
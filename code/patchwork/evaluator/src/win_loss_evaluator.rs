@@ -4,6 +4,9 @@ use patchwork_core::{Evaluator, Patchwork, TerminationType};
 pub struct WinLossEvaluator {}
 
 impl WinLossEvaluator {
+    /// The amount of random rollouts played to estimate the win probability.
+    pub const WIN_PROBABILITY_ROLLOUTS: usize = 32;
+
     /// Creates a new [`WinLossEvaluator`].
     #[must_use]
     pub const fn new() -> Self {
@@ -28,4 +31,59 @@ impl Evaluator for WinLossEvaluator {
             TerminationType::Player2Won => -1,
         }
     }
+
+    fn win_probability(&self, game: &Patchwork) -> f32 {
+        if game.is_terminated() {
+            return (self.evaluate_terminal_node(game) + 1) as f32 / 2.0;
+        }
+
+        let wins = (0..Self::WIN_PROBABILITY_ROLLOUTS)
+            .filter(|_| self.evaluate_terminal_node(&game.random_rollout()) > 0)
+            .count();
+        wins as f32 / Self::WIN_PROBABILITY_ROLLOUTS as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use super::*;
+
+    /// Gets a state shortly before the end of the game where the given player
+    /// has so many more buttons that they clearly win.
+    fn get_clearly_won_state(player_1_wins: bool) -> Patchwork {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut seed = 0;
+        while !state.is_terminated() {
+            let mut next_state = state.clone();
+            next_state.do_action(next_state.get_seeded_random_action(seed), false).unwrap();
+            if next_state.is_terminated() {
+                break;
+            }
+            state = next_state;
+            seed += 1;
+        }
+
+        if player_1_wins {
+            state.player_1.button_balance += 500;
+        } else {
+            state.player_2.button_balance += 500;
+        }
+        state
+    }
+
+    #[test]
+    fn test_win_probability_of_clear_positions() {
+        let evaluator = WinLossEvaluator::new();
+
+        let winning = get_clearly_won_state(true);
+        assert!(!winning.is_terminated());
+        assert!(evaluator.win_probability(&winning) > 0.95);
+        assert!(evaluator.win_probability(&winning.random_rollout()) > 0.95);
+
+        let losing = get_clearly_won_state(false);
+        assert!(evaluator.win_probability(&losing) < 0.05);
+        assert!(evaluator.win_probability(&losing.random_rollout()) < 0.05);
+    }
 }
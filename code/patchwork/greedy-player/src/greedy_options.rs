@@ -0,0 +1,36 @@
+/// The metric the [`GreedyPlayer`](crate::GreedyPlayer) uses to rank the
+/// available actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GreedyMetric {
+    /// Rank actions by the evaluation of the state directly after taking the
+    /// action.
+    #[default]
+    Evaluation,
+    /// Rank actions by the button income gained per time spent
+    /// (`button_income / time_cost`). Advancing cheaply while gaining income
+    /// is strong in patchwork. Actions with the same efficiency are ranked by
+    /// their evaluation. Actions that are not patch placements have an
+    /// efficiency of `0`.
+    Efficiency,
+}
+
+/// The options for [`GreedyPlayer`](crate::GreedyPlayer).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct GreedyOptions {
+    /// The metric used to rank the actions.
+    pub metric: GreedyMetric,
+    /// The seed used to break ties between equally ranked actions. `None`
+    /// breaks ties randomly so that games are not reproducible.
+    pub tie_breaking_seed: Option<u64>,
+}
+
+impl GreedyOptions {
+    /// Creates a new [`GreedyOptions`].
+    #[must_use]
+    pub const fn new(metric: GreedyMetric) -> Self {
+        Self {
+            metric,
+            tie_breaking_seed: None,
+        }
+    }
+}
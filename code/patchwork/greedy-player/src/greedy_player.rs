@@ -1,5 +1,7 @@
 use evaluator::StaticEvaluator;
-use patchwork_core::{ActionId, Evaluator, Patchwork, Player, PlayerResult};
+use patchwork_core::{ActionId, Evaluator, GameRng, PatchManager, Patchwork, Player, PlayerResult, SearchDiagnostics};
+
+use crate::{GreedyMetric, GreedyOptions};
 
 /// A player that selects the action with the highest score.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -8,14 +10,17 @@ pub struct GreedyPlayer<Eval: Evaluator = StaticEvaluator> {
     pub name: String,
     /// The evaluator to evaluate the game state.
     pub evaluator: Eval,
+    /// The options of the player.
+    pub options: GreedyOptions,
 }
 
 impl<Eval: Evaluator + Default> GreedyPlayer<Eval> {
-    /// Creates a new [`GreedyPlayer`] with the given name.
-    pub fn new(name: impl Into<String>) -> Self {
+    /// Creates a new [`GreedyPlayer`] with the given name and options.
+    pub fn new(name: impl Into<String>, options: Option<GreedyOptions>) -> Self {
         Self {
             name: name.into(),
             evaluator: Default::default(),
+            options: options.unwrap_or_default(),
         }
     }
 }
@@ -26,23 +31,54 @@ impl<Eval: Evaluator> GreedyPlayer<Eval> {
         Self {
             name: name.into(),
             evaluator,
+            options: GreedyOptions::default(),
         }
     }
 
     pub fn get_action(&self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.get_best_action(game, &game.get_valid_actions())
+    }
+
+    /// Gets the best action out of the given actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The current state of the game.
+    /// * `actions` - The valid actions to choose from. Must not be empty.
+    ///
+    /// # Returns
+    ///
+    /// The action with the best metric and evaluation. Ties are broken randomly
+    /// unless a tie breaking seed is set.
+    fn get_best_action(&self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
         let mut game = game.clone();
-        let valid_actions = game.get_valid_actions().into_iter().collect::<Vec<_>>();
 
         let maximizing_player = game.is_player_1();
 
-        let mut chosen_action = valid_actions[0];
+        let mut chosen_action = actions[0];
+        let mut chosen_efficiency = f64::NEG_INFINITY;
         let mut chosen_evaluation = if maximizing_player { i32::MIN } else { i32::MAX };
 
-        for action in &valid_actions {
+        for action in actions {
+            let efficiency = match self.options.metric {
+                GreedyMetric::Evaluation => 0.0,
+                GreedyMetric::Efficiency => Self::get_efficiency(*action),
+            };
+
             game.do_action(*action, false)?;
             let evaluation = self.evaluator.evaluate_node(&game);
             game.undo_action(*action, false)?;
 
+            if efficiency < chosen_efficiency {
+                continue;
+            }
+            if efficiency > chosen_efficiency {
+                chosen_action = *action;
+                chosen_efficiency = efficiency;
+                chosen_evaluation = evaluation;
+                continue;
+            }
+
             #[allow(clippy::collapsible_else_if)]
             if maximizing_player {
                 if evaluation > chosen_evaluation {
@@ -55,19 +91,76 @@ impl<Eval: Evaluator> GreedyPlayer<Eval> {
                     chosen_evaluation = evaluation;
                 }
             }
-            // break ties randomly
-            if evaluation == chosen_evaluation && rand::random() {
+            if evaluation == chosen_evaluation && self.wins_tie(*action, chosen_action) {
                 chosen_action = *action;
             }
         }
 
         Ok(chosen_action)
     }
+
+    /// Evaluates the chosen action and the best alternative to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The current state of the game.
+    /// * `chosen_action` - The action that was chosen.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the chosen action followed by the best evaluated
+    /// alternative (if any) from the perspective of the player to move.
+    fn get_root_evaluations(&self, game: &Patchwork, chosen_action: ActionId) -> PlayerResult<Vec<(ActionId, i32)>> {
+        let mut game = game.clone();
+        // the evaluator rates the state from the perspective of player 1
+        let sign = if game.is_player_1() { 1 } else { -1 };
+
+        let mut chosen = None;
+        let mut best_alternative: Option<(ActionId, i32)> = None;
+        for action in game.get_valid_actions() {
+            game.do_action(action, false)?;
+            let evaluation = sign * self.evaluator.evaluate_node(&game);
+            game.undo_action(action, false)?;
+
+            if action == chosen_action {
+                chosen = Some((action, evaluation));
+            } else if best_alternative.is_none_or(|(_, best_evaluation)| evaluation > best_evaluation) {
+                best_alternative = Some((action, evaluation));
+            }
+        }
+
+        Ok(chosen.into_iter().chain(best_alternative).collect())
+    }
+
+    /// Whether the action is preferred over the chosen action with the same
+    /// rank. Ties are broken randomly if no tie breaking seed is set.
+    fn wins_tie(&self, action: ActionId, chosen_action: ActionId) -> bool {
+        GameRng::wins_tie(self.options.tie_breaking_seed, action, chosen_action)
+    }
+
+    /// Gets the button income per time cost of the given action.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to get the efficiency for.
+    ///
+    /// # Returns
+    ///
+    /// The button income per time cost of the patch placed by the action or
+    /// `0` if the action does not place a normal patch.
+    fn get_efficiency(action: ActionId) -> f64 {
+        if !action.is_patch_placement() {
+            return 0.0;
+        }
+
+        let patch = PatchManager::get_patch(action.get_patch_id());
+        f64::from(patch.button_income) / f64::from(patch.time_cost.max(1))
+    }
 }
 
 impl<Eval: Evaluator + Default> Default for GreedyPlayer<Eval> {
     fn default() -> Self {
-        Self::new("Greedy Player".to_string())
+        Self::new("Greedy Player".to_string(), None)
     }
 }
 
@@ -79,4 +172,94 @@ impl<Eval: Evaluator> Player for GreedyPlayer<Eval> {
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
         Self::get_action(self, game)
     }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        if actions.is_empty() {
+            return Self::get_action(self, game);
+        }
+
+        self.get_best_action(game, actions)
+    }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        let start_time = std::time::Instant::now();
+        let action = Self::get_action(self, game)?;
+        let time = start_time.elapsed();
+
+        Ok((
+            action,
+            SearchDiagnostics {
+                depth: 1,
+                time,
+                principal_variation: vec![action],
+                root_evaluations: self.get_root_evaluations(game, action)?,
+                ..SearchDiagnostics::default()
+            },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::Notation;
+
+    use super::*;
+
+    /// An evaluator that only rates the covered tiles of the quilt boards so
+    /// that the raw value metric prefers the biggest patch.
+    struct CoveredTilesEvaluator;
+
+    impl Evaluator for CoveredTilesEvaluator {
+        fn evaluate_intermediate_node(&self, game: &Patchwork) -> i32 {
+            game.player_1.quilt_board.tiles_filled() as i32 - game.player_2.quilt_board.tiles_filled() as i32
+        }
+    }
+
+    // Player 1 can choose between patch 2 (11 tiles, 1 income / 3 time), patch 1 (5 tiles, 3 income / 4 time),
+    // patch 10 (5 tiles, 1 income / 3 time) and walking.
+    const STATE: &str = "000000000000000000000B20I0P0 000000000000000000000B5I0P5 1 N 2/1/10/8/3/4/5/6/7/9";
+
+    fn create_player(metric: GreedyMetric) -> GreedyPlayer<CoveredTilesEvaluator> {
+        GreedyPlayer {
+            name: "Greedy Player".to_string(),
+            evaluator: CoveredTilesEvaluator,
+            options: GreedyOptions::new(metric),
+        }
+    }
+
+    #[test]
+    fn test_evaluation_metric_prefers_raw_value() {
+        let state = Patchwork::load_from_notation(STATE).unwrap();
+        let action = create_player(GreedyMetric::Evaluation).get_action(&state).unwrap();
+
+        assert!(action.is_patch_placement(), "Expected patch placement but got {action}");
+        assert_eq!(action.get_patch_id(), 2, "Expected biggest patch 2 but got {action}");
+    }
+
+    #[test]
+    fn test_diagnostics_report_the_margin_to_the_best_alternative() {
+        let state = Patchwork::load_from_notation(STATE).unwrap();
+        let (action, diagnostics) = create_player(GreedyMetric::Evaluation)
+            .get_action_with_diagnostics(&state)
+            .unwrap();
+
+        assert_eq!(2, diagnostics.root_evaluations.len());
+        assert_eq!(action, diagnostics.root_evaluations[0].0);
+        assert_ne!(action, diagnostics.root_evaluations[1].0);
+        // the greedy player chooses the best evaluated action
+        assert!(diagnostics.margin().unwrap() >= 0);
+    }
+
+    #[test]
+    fn test_efficiency_metric_prefers_button_income_per_time() {
+        let state = Patchwork::load_from_notation(STATE).unwrap();
+        let action = create_player(GreedyMetric::Efficiency).get_action(&state).unwrap();
+
+        assert!(action.is_patch_placement(), "Expected patch placement but got {action}");
+        assert_eq!(
+            action.get_patch_id(),
+            1,
+            "Expected most efficient patch 1 but got {action}"
+        );
+    }
 }
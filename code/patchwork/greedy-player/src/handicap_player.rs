@@ -0,0 +1,170 @@
+use evaluator::StaticEvaluator;
+use patchwork_core::{ActionId, Logging, Patchwork, Player, PlayerResult, SearchDiagnostics};
+
+use crate::GreedyPlayer;
+
+/// A player that plays its first moves of a game with a weaker handicap
+/// policy before handing over to the inner player. This simulates a handicap
+/// for asymmetric matches.
+///
+/// Every call to get an action counts as one move of the player, including
+/// the placement of special patches.
+pub struct HandicapPlayer {
+    /// The name of the player.
+    name: String,
+    /// The player that plays after the handicap moves.
+    inner: Box<dyn Player>,
+    /// The player that plays the handicap moves.
+    handicap: Box<dyn Player>,
+    /// The amount of moves that are played by the handicap player.
+    handicap_moves: usize,
+    /// The amount of moves that were already played in the current game.
+    moves_played: usize,
+}
+
+impl HandicapPlayer {
+    /// Creates a new [`HandicapPlayer`] that plays the first moves greedily.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The player that plays after the handicap moves.
+    /// * `handicap_moves` - The amount of moves that are played greedily.
+    ///
+    /// # Returns
+    ///
+    /// The new [`HandicapPlayer`].
+    #[must_use]
+    pub fn new(inner: Box<dyn Player>, handicap_moves: usize) -> Self {
+        Self::with_handicap_player(
+            inner,
+            Box::new(GreedyPlayer::<StaticEvaluator>::new("Greedy Player", None)),
+            handicap_moves,
+        )
+    }
+
+    /// Creates a new [`HandicapPlayer`] with the given handicap policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The player that plays after the handicap moves.
+    /// * `handicap` - The player that plays the handicap moves.
+    /// * `handicap_moves` - The amount of moves that are played by the handicap player.
+    ///
+    /// # Returns
+    ///
+    /// The new [`HandicapPlayer`].
+    #[must_use]
+    pub fn with_handicap_player(inner: Box<dyn Player>, handicap: Box<dyn Player>, handicap_moves: usize) -> Self {
+        Self {
+            name: format!("{} (handicap: {handicap_moves})", inner.name()),
+            inner,
+            handicap,
+            handicap_moves,
+            moves_played: 0,
+        }
+    }
+
+    /// Gets the player that plays the next move and counts the move.
+    fn next_player(&mut self) -> &mut Box<dyn Player> {
+        let is_handicap_move = self.moves_played < self.handicap_moves;
+        self.moves_played += 1;
+
+        if is_handicap_move {
+            &mut self.handicap
+        } else {
+            &mut self.inner
+        }
+    }
+}
+
+impl Player for HandicapPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.next_player().get_action(game)
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        self.next_player().get_restricted_action(game, actions)
+    }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        self.next_player().get_action_with_diagnostics(game)
+    }
+
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        self.next_player().get_restricted_action_with_diagnostics(game, actions)
+    }
+
+    fn set_logging(&mut self, logging: Logging) {
+        // the handicap moves are played greedily without logging
+        self.inner.set_logging(logging);
+    }
+
+    fn reset(&mut self) {
+        self.moves_played = 0;
+        self.inner.reset();
+        self.handicap.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+
+    /// A player that plays the first valid action and counts how often it
+    /// was asked for an action.
+    struct CountingPlayer(Rc<Cell<usize>>);
+
+    impl Player for CountingPlayer {
+        fn name(&self) -> &'static str {
+            "Counting Player"
+        }
+
+        fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+            self.0.set(self.0.get() + 1);
+            Ok(game.get_valid_actions()[0])
+        }
+    }
+
+    #[test]
+    fn test_first_moves_are_played_by_handicap_player() {
+        const HANDICAP_MOVES: usize = 3;
+
+        let inner_moves = Rc::new(Cell::new(0));
+        let handicap_moves = Rc::new(Cell::new(0));
+        let mut player = HandicapPlayer::with_handicap_player(
+            Box::new(CountingPlayer(Rc::clone(&inner_moves))),
+            Box::new(CountingPlayer(Rc::clone(&handicap_moves))),
+            HANDICAP_MOVES,
+        );
+        assert_eq!("Counting Player (handicap: 3)", player.name());
+
+        for game in 1..=2 {
+            let mut state = Patchwork::get_initial_state(None);
+            for move_index in 0..2 * HANDICAP_MOVES {
+                let action = player.get_action(&state).unwrap();
+                state.do_action(action, false).unwrap();
+
+                let expected_handicap_moves = (game - 1) * HANDICAP_MOVES + (move_index + 1).min(HANDICAP_MOVES);
+                assert_eq!(expected_handicap_moves, handicap_moves.get(), "Unexpected player in move {move_index}");
+                assert_eq!(
+                    (game - 1) * HANDICAP_MOVES + (move_index + 1).saturating_sub(HANDICAP_MOVES),
+                    inner_moves.get(),
+                    "Unexpected player in move {move_index}"
+                );
+            }
+
+            // the handicap is applied again in the next game
+            player.reset();
+        }
+    }
+}
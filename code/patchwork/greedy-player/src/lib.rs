@@ -1,3 +1,9 @@
+mod greedy_options;
 mod greedy_player;
+mod handicap_player;
+mod randomized_opening_player;
 
+pub use greedy_options::{GreedyMetric, GreedyOptions};
 pub use greedy_player::GreedyPlayer;
+pub use handicap_player::HandicapPlayer;
+pub use randomized_opening_player::RandomizedOpeningPlayer;
@@ -0,0 +1,343 @@
+use evaluator::StaticEvaluator;
+use patchwork_core::{ActionId, Evaluator, GameRng, Logging, Patchwork, Player, PlayerResult, SearchDiagnostics};
+use rand::{Rng, SeedableRng};
+
+/// A player that diversifies the openings of a game.
+///
+/// For its first moves it chooses uniformly among the top actions instead of
+/// always playing the action of the inner player. Afterwards it plays exactly
+/// like the inner player.
+///
+/// The top actions are the action of the inner player and the best actions
+/// according to a one-ply evaluation with the static evaluator. The inner
+/// player is only asked for its action if it is chosen. Every call to get an
+/// action counts as one move of the player, including the placement of
+/// special patches. The random number generator is not reseeded between
+/// games so that every game gets a different opening.
+pub struct RandomizedOpeningPlayer {
+    /// The name of the player.
+    name: String,
+    /// The player whose actions are randomized.
+    inner: Box<dyn Player>,
+    /// The evaluator to rank the actions.
+    evaluator: StaticEvaluator,
+    /// The amount of moves that are randomized.
+    randomized_moves: usize,
+    /// The amount of top actions to choose from.
+    top_actions: usize,
+    /// The random number generator used to choose among the top actions.
+    rng: GameRng,
+    /// The amount of moves that were already played in the current game.
+    moves_played: usize,
+}
+
+impl RandomizedOpeningPlayer {
+    /// Creates a new [`RandomizedOpeningPlayer`].
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The player whose actions are randomized.
+    /// * `randomized_moves` - The amount of moves that are randomized.
+    /// * `top_actions` - The amount of top actions to choose from.
+    /// * `seed` - The seed of the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The new [`RandomizedOpeningPlayer`].
+    #[must_use]
+    pub fn new(inner: Box<dyn Player>, randomized_moves: usize, top_actions: usize, seed: u64) -> Self {
+        Self {
+            name: format!(
+                "{} (opening: top {top_actions} in {randomized_moves} moves)",
+                inner.name()
+            ),
+            inner,
+            evaluator: StaticEvaluator::new(),
+            randomized_moves,
+            top_actions,
+            rng: GameRng::seed_from_u64(seed),
+            moves_played: 0,
+        }
+    }
+
+    /// Gets the best actions in the given state according to a one-ply
+    /// evaluation from the perspective of the current player.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The current state of the game.
+    /// * `actions` - The valid actions that may be chosen. If empty all valid
+    ///   actions may be chosen.
+    /// * `amount` - The maximum amount of actions.
+    ///
+    /// # Returns
+    ///
+    /// At most `amount` actions starting with the best one.
+    ///
+    /// # Errors
+    ///
+    /// If an action could not be applied to the state.
+    pub fn get_best_actions(&self, game: &Patchwork, actions: &[ActionId], amount: usize) -> PlayerResult<Vec<ActionId>> {
+        let mut game = game.clone();
+        let sign = if game.is_player_1() { 1 } else { -1 };
+        let actions = if actions.is_empty() {
+            game.get_valid_actions()
+        } else {
+            actions.to_vec()
+        };
+
+        let mut evaluated_actions = vec![];
+        for action in actions {
+            game.do_action(action, false)?;
+            evaluated_actions.push((sign * self.evaluator.evaluate_node(&game), action));
+            game.undo_action(action, false)?;
+        }
+        evaluated_actions.sort_by_key(|(evaluation, _)| std::cmp::Reverse(*evaluation));
+
+        Ok(evaluated_actions.into_iter().map(|(_, action)| action).take(amount).collect())
+    }
+
+    /// Chooses one of the top actions if the move is one of the randomized
+    /// moves and counts the move. The action of the inner player is not
+    /// searched here but only requested if it is chosen.
+    ///
+    /// # Returns
+    ///
+    /// The chosen action or `None` if the action of the inner player is
+    /// played.
+    fn randomize(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<Option<ActionId>> {
+        let is_randomized_move = self.moves_played < self.randomized_moves;
+        self.moves_played += 1;
+
+        if !is_randomized_move || self.top_actions <= 1 {
+            return Ok(None);
+        }
+
+        // the first of the top actions is the action of the inner player
+        let index = self.rng.gen_range(0..self.top_actions);
+        if index == 0 {
+            return Ok(None);
+        }
+        Ok(self.get_best_actions(game, actions, index)?.get(index - 1).copied())
+    }
+}
+
+impl Player for RandomizedOpeningPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        match self.randomize(game, &[])? {
+            Some(action) => Ok(action),
+            None => self.inner.get_action(game),
+        }
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        match self.randomize(game, actions)? {
+            Some(action) => Ok(action),
+            None => self.inner.get_restricted_action(game, actions),
+        }
+    }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        self.get_restricted_action_with_diagnostics(game, &[])
+    }
+
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        let start_time = std::time::Instant::now();
+        match self.randomize(game, actions)? {
+            Some(action) => Ok((
+                action,
+                SearchDiagnostics {
+                    time: start_time.elapsed(),
+                    ..SearchDiagnostics::default()
+                },
+            )),
+            None => self.inner.get_restricted_action_with_diagnostics(game, actions),
+        }
+    }
+
+    fn set_logging(&mut self, logging: Logging) {
+        self.inner.set_logging(logging);
+    }
+
+    fn reset(&mut self) {
+        self.moves_played = 0;
+        self.inner.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use crate::GreedyPlayer;
+
+    use super::*;
+
+    /// A deterministic player that plays the best action according to the
+    /// static evaluator and the first of them on ties.
+    struct FirstBestPlayer;
+
+    impl Player for FirstBestPlayer {
+        fn name(&self) -> &'static str {
+            "First Best Player"
+        }
+
+        fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+            let evaluations = evaluate_actions(game);
+            let best = evaluations.iter().map(|(evaluation, _)| *evaluation).max().unwrap();
+            Ok(evaluations
+                .iter()
+                .find(|(evaluation, _)| *evaluation == best)
+                .unwrap()
+                .1)
+        }
+    }
+
+    /// Evaluates all valid actions from the perspective of the current player.
+    fn evaluate_actions(game: &Patchwork) -> Vec<(i32, ActionId)> {
+        let sign = if game.is_player_1() { 1 } else { -1 };
+        game.get_valid_actions()
+            .into_iter()
+            .map(|action| {
+                let mut next_state = game.clone();
+                next_state.do_action(action, false).unwrap();
+                (sign * StaticEvaluator::new().evaluate_node(&next_state), action)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_opening_moves_are_among_top_actions() {
+        const RANDOMIZED_MOVES: usize = 6;
+        const TOP_ACTIONS: usize = 3;
+
+        let mut player = RandomizedOpeningPlayer::new(Box::new(FirstBestPlayer), RANDOMIZED_MOVES, TOP_ACTIONS, 42);
+        let mut inner = FirstBestPlayer;
+        let mut randomized = false;
+
+        for seed in 0..4 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            for move_index in 0..2 * RANDOMIZED_MOVES {
+                let action = player.get_action(&state).unwrap();
+                let inner_action = inner.get_action(&state).unwrap();
+
+                if move_index < RANDOMIZED_MOVES {
+                    let mut evaluations = evaluate_actions(&state)
+                        .into_iter()
+                        .map(|(evaluation, _)| evaluation)
+                        .collect::<Vec<_>>();
+                    evaluations.sort_unstable_by(|a, b| b.cmp(a));
+                    let minimum_evaluation = evaluations[(TOP_ACTIONS - 1).min(evaluations.len() - 1)];
+
+                    let evaluation = evaluate_actions(&state)
+                        .into_iter()
+                        .find(|(_, other)| *other == action)
+                        .unwrap()
+                        .0;
+                    assert!(
+                        evaluation >= minimum_evaluation,
+                        "{action:?} is no top action in move {move_index}"
+                    );
+                    randomized |= action != inner_action;
+                } else {
+                    assert_eq!(inner_action, action, "Unexpected action in move {move_index}");
+                }
+
+                state.do_action(action, false).unwrap();
+            }
+
+            player.reset();
+        }
+
+        assert!(randomized, "The opening moves were never randomized");
+    }
+
+    #[test]
+    fn test_restricted_action_is_within_the_given_actions() {
+        let mut player = RandomizedOpeningPlayer::new(Box::new(GreedyPlayer::<StaticEvaluator>::default()), 8, 4, 3);
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(7)));
+
+        for _ in 0..8 {
+            let valid_actions = state.get_valid_actions();
+            let actions = &valid_actions[valid_actions.len() / 2..];
+
+            let action = player.get_restricted_action(&state, actions).unwrap();
+            assert!(actions.contains(&action), "{action:?} is not one of {actions:?}");
+            state.do_action(action, false).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_randomization_is_seeded() {
+        let play = |player: &mut RandomizedOpeningPlayer| {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(7)));
+            (0..8)
+                .map(|_| {
+                    let action = player.get_action(&state).unwrap();
+                    state.do_action(action, false).unwrap();
+                    action
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let mut player = RandomizedOpeningPlayer::new(Box::new(FirstBestPlayer), 8, 4, 3);
+        let first_game = play(&mut player);
+        player.reset();
+        let second_game = play(&mut player);
+
+        // the random number generator is not reseeded so that the openings differ
+        assert_ne!(first_game, second_game);
+
+        let mut other_player = RandomizedOpeningPlayer::new(Box::new(FirstBestPlayer), 8, 4, 3);
+        assert_eq!(first_game, play(&mut other_player));
+        other_player.reset();
+        assert_eq!(second_game, play(&mut other_player));
+    }
+
+    /// A player that counts how often it was asked for an action.
+    struct CountingPlayer(std::rc::Rc<std::cell::Cell<usize>>);
+
+    impl Player for CountingPlayer {
+        fn name(&self) -> &'static str {
+            "Counting Player"
+        }
+
+        fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+            self.0.set(self.0.get() + 1);
+            FirstBestPlayer.get_action(game)
+        }
+    }
+
+    #[test]
+    fn test_inner_player_is_only_asked_when_its_action_is_played() {
+        const RANDOMIZED_MOVES: usize = 8;
+
+        let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+        let mut player = RandomizedOpeningPlayer::new(
+            Box::new(CountingPlayer(std::rc::Rc::clone(&calls))),
+            RANDOMIZED_MOVES,
+            4,
+            3,
+        );
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(7)));
+
+        for _ in 0..RANDOMIZED_MOVES {
+            let action = player.get_action(&state).unwrap();
+            state.do_action(action, false).unwrap();
+        }
+        assert!(calls.get() < RANDOMIZED_MOVES, "The inner player was asked for every move");
+
+        let calls_before = calls.get();
+        let action = player.get_action(&state).unwrap();
+        assert_eq!(calls_before + 1, calls.get());
+        assert_eq!(FirstBestPlayer.get_action(&state).unwrap(), action);
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    fmt::Display,
+    io::{self, BufRead, Write},
+};
+
+use patchwork_core::{ActionId, Notation, NumberedActions, PatchManager, Patchwork, QuiltBoard, TurnType};
+use regex::Regex;
+
+/// The input and output of a [`crate::HumanPlayer`].
+///
+/// Abstracting over the input and output allows the human player to be driven
+/// programmatically, e.g. in tests or over the network.
+pub trait HumanIo {
+    /// Shows the given prompt and reads the next line of input.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt to show before reading the input.
+    ///
+    /// # Returns
+    ///
+    /// The next line without the trailing newline or `None` if the input ended.
+    ///
+    /// # Errors
+    ///
+    /// If the input could not be read or the prompt could not be written.
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>>;
+
+    /// Writes the given output followed by a newline.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The output to write.
+    ///
+    /// # Errors
+    ///
+    /// If the output could not be written.
+    fn write_line(&mut self, output: &str) -> io::Result<()>;
+
+    /// Whether the human entering `exit` should exit the whole program. This
+    /// is only sensible if the human is sitting in front of the program.
+    /// Otherwise entering `exit` ends the input of the human.
+    fn exits_program(&self) -> bool {
+        false
+    }
+}
+
+/// Reads the input of the human from the standard input and writes the output
+/// to the standard output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StandardIo;
+
+impl HumanIo for StandardIo {
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        print!("{prompt} ");
+        io::stdout().lock().flush()?;
+
+        let mut line = String::new();
+        if io::stdin().lock().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    fn write_line(&mut self, output: &str) -> io::Result<()> {
+        println!("{output}");
+        Ok(())
+    }
+
+    fn exits_program(&self) -> bool {
+        true
+    }
+}
+
+/// Reads the input of the human from a reader and writes the output and the
+/// prompts to a writer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BufferedIo<Reader: BufRead, Writer: Write> {
+    /// The reader the input is read from.
+    reader: Reader,
+    /// The writer the output is written to.
+    writer: Writer,
+}
+
+impl<Reader: BufRead, Writer: Write> BufferedIo<Reader, Writer> {
+    /// Creates a new [`BufferedIo`] from the given reader and writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader the input is read from.
+    /// * `writer` - The writer the output is written to.
+    pub const fn new(reader: Reader, writer: Writer) -> Self {
+        Self { reader, writer }
+    }
+
+    /// Gets the writer the output is written to.
+    #[must_use]
+    pub const fn writer(&self) -> &Writer {
+        &self.writer
+    }
+
+    /// Consumes the [`BufferedIo`] and returns the reader and the writer.
+    #[must_use]
+    pub fn into_inner(self) -> (Reader, Writer) {
+        (self.reader, self.writer)
+    }
+}
+
+impl<Reader: BufRead, Writer: Write> HumanIo for BufferedIo<Reader, Writer> {
+    fn read_line(&mut self, prompt: &str) -> io::Result<Option<String>> {
+        writeln!(self.writer, "{prompt}")?;
+        self.writer.flush()?;
+
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(line.trim_end_matches(['\r', '\n']).to_string()))
+    }
+
+    fn write_line(&mut self, output: &str) -> io::Result<()> {
+        writeln!(self.writer, "{output}")?;
+        self.writer.flush()
+    }
+}
+
+/// The error returned by [`crate::HumanPlayer`] when the human wants to undo
+/// their last action. The game loop can honor it by restoring the state
+/// before the last action of the human.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct UndoRequest;
+
+impl Display for UndoRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[UndoRequest] The human player requested to undo their last action")
+    }
+}
+
+impl std::error::Error for UndoRequest {}
+
+/// The meaning of a line of input of a human.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum HumanInput {
+    /// The human chose the given valid action.
+    Action(ActionId),
+    /// The human wants to take the patch with the given index and choose its
+    /// placement step by step.
+    TakePatch(u8),
+    /// The human wants a random valid action to be taken.
+    Skip,
+    /// The human wants to undo their last action.
+    Undo,
+    /// The input is not valid in the current state.
+    Invalid(String),
+}
+
+/// Parses a line of input of a human in the given state.
+///
+/// The following inputs are accepted:
+///
+/// * The number of an action as listed by [`Patchwork::display_numbered_actions`].
+/// * The notation of a valid action (e.g. `W0`).
+/// * `walk` or `w` to walk.
+/// * `take <1-3>` or `t<1-3>` to take a patch and choose its placement step by step.
+/// * `take <1-3> <row> <column> [<rotation>] [<flipped y/n>]` or the same with `t<1-3>`
+///   to place a patch directly, e.g. `t2 3 4 90 y`.
+/// * `<row> <column>` or `s <row> <column>` to place the special patch.
+/// * `skip` to take a random action and `undo` to undo the last action.
+///
+/// # Arguments
+///
+/// * `input` - The line of input.
+/// * `state` - The state the input is given in.
+/// * `numbered_actions` - The numbered valid actions of the state.
+///
+/// # Returns
+///
+/// The meaning of the input.
+#[must_use]
+pub fn parse_human_input(input: &str, state: &Patchwork, numbered_actions: &NumberedActions) -> HumanInput {
+    let input = input.trim();
+    let valid_actions = numbered_actions.iter().map(|(_, action)| action).collect::<Vec<_>>();
+
+    if input.is_empty() {
+        return HumanInput::Invalid("Please enter an action".to_string());
+    }
+
+    if let Ok(number) = input.parse::<usize>() {
+        return numbered_actions.get_action(number).map_or_else(
+            || HumanInput::Invalid(format!("There is no action with number {number} (1-{})", numbered_actions.len())),
+            HumanInput::Action,
+        );
+    }
+
+    if let Ok(action) = ActionId::load_from_notation(input) {
+        return if valid_actions.contains(&action) {
+            HumanInput::Action(action)
+        } else {
+            HumanInput::Invalid(format!("The action '{input}' is not valid in the current state"))
+        };
+    }
+
+    let input = input.to_lowercase();
+    let tokens = Regex::new(r"[, ]+").unwrap().split(&input).collect::<Vec<_>>();
+
+    match tokens.as_slice() {
+        ["skip"] => HumanInput::Skip,
+        ["undo"] => HumanInput::Undo,
+        ["walk" | "w"] => valid_actions
+            .iter()
+            .find(|action| action.is_walking())
+            .map_or_else(|| HumanInput::Invalid("Walking is not possible now".to_string()), |action| {
+                HumanInput::Action(*action)
+            }),
+        ["take", patch, placement @ ..] => parse_take_patch(patch, placement, &valid_actions),
+        [take_patch, placement @ ..] if take_patch.starts_with('t') && take_patch.len() == 2 => {
+            parse_take_patch(&take_patch[1..], placement, &valid_actions)
+        }
+        ["s", row, column] | [row, column] if state.turn_type == TurnType::SpecialPatchPlacement => {
+            let Some((row, column)) = parse_row_column(row, column) else {
+                return invalid_row_column();
+            };
+            valid_actions
+                .iter()
+                .find(|action| {
+                    action.is_special_patch_placement() && action.get_row() == row && action.get_column() == column
+                })
+                .map_or_else(
+                    || HumanInput::Invalid(format!("The special patch cannot be placed at ({}, {})", row + 1, column + 1)),
+                    |action| HumanInput::Action(*action),
+                )
+        }
+        _ => HumanInput::Invalid(format!("Unknown input '{input}'")),
+    }
+}
+
+/// Parses the input to take a patch (see [`parse_human_input`]).
+///
+/// # Arguments
+///
+/// * `patch` - The (1-based) index of the patch.
+/// * `placement` - The optional row, column, rotation and orientation of the placement.
+/// * `valid_actions` - The valid actions of the state.
+///
+/// # Returns
+///
+/// The meaning of the input.
+fn parse_take_patch(patch: &str, placement: &[&str], valid_actions: &[ActionId]) -> HumanInput {
+    let Some(patch_index) = patch
+        .parse::<u8>()
+        .ok()
+        .filter(|index| (1..=PatchManager::MAX_AMOUNT_OF_CHOOSABLE_TILES as u8).contains(index))
+        .map(|index| index - 1)
+    else {
+        return HumanInput::Invalid(format!("'{patch}' is not a patch that can be taken (1-3)"));
+    };
+
+    let mut placements = valid_actions
+        .iter()
+        .filter(|action| action.is_patch_placement() && action.get_patch_index() == patch_index)
+        .peekable();
+    if placements.peek().is_none() {
+        return HumanInput::Invalid(format!("Patch {} cannot be taken", patch_index + 1));
+    }
+
+    let (row, column, rotation, flipped) = match placement {
+        [] => return HumanInput::TakePatch(patch_index),
+        [row, column] => (row, column, "0", "n"),
+        [row, column, rotation] => (row, column, *rotation, "n"),
+        [row, column, rotation, flipped] => (row, column, *rotation, *flipped),
+        _ => return HumanInput::Invalid("Please enter 'take <patch> <row> <column> <rotation> <flipped>'".to_string()),
+    };
+
+    let Some((row, column)) = parse_row_column(row, column) else {
+        return invalid_row_column();
+    };
+    let Some(rotation) = rotation.parse::<u32>().ok().filter(|rotation| [0, 90, 180, 270].contains(rotation)) else {
+        return HumanInput::Invalid("Please enter a valid rotation (0, 90, 180, 270)".to_string());
+    };
+    let flipped = match flipped {
+        "y" | "yes" => true,
+        "n" | "no" => false,
+        _ => return HumanInput::Invalid("Please enter 'y' or 'n' for the orientation".to_string()),
+    };
+
+    placements
+        .find(|action| {
+            let transformation =
+                PatchManager::get_transformation(action.get_patch_id(), action.get_patch_transformation_index());
+            transformation.row == row
+                && transformation.column == column
+                && transformation.rotation() == rotation
+                && transformation.flipped() == flipped
+        })
+        .map_or_else(
+            || HumanInput::Invalid(format!("Patch {} cannot be placed like this", patch_index + 1)),
+            |action| HumanInput::Action(*action),
+        )
+}
+
+/// Parses a (1-based) row and column of the quilt board.
+///
+/// # Returns
+///
+/// The 0-based row and column or `None` if they are not on the quilt board.
+fn parse_row_column(row: &str, column: &str) -> Option<(u8, u8)> {
+    let row = row.parse::<u8>().ok().filter(|row| (1..=QuiltBoard::ROWS).contains(row))?;
+    let column = column.parse::<u8>().ok().filter(|column| (1..=QuiltBoard::COLUMNS).contains(column))?;
+    Some((row - 1, column - 1))
+}
+
+fn invalid_row_column() -> HumanInput {
+    HumanInput::Invalid(format!(
+        "Please enter valid numbers for row (1-{}) and column (1-{})",
+        QuiltBoard::ROWS,
+        QuiltBoard::COLUMNS
+    ))
+}
@@ -1,23 +1,47 @@
-use std::{
-    collections::HashSet,
-    io::{self, Write},
+use anyhow::anyhow;
+use patchwork_core::{
+    ActionId, GameRng, NumberedActions, PatchManager, PatchTransformation, Patchwork, Player, PlayerResult,
+    QuiltBoard, TurnType,
 };
-
-use patchwork_core::{ActionId, PatchManager, PatchTransformation, Patchwork, Player, PlayerResult, QuiltBoard};
 use rand::Rng;
 use regex::Regex;
 
+use crate::{parse_human_input, HumanInput, HumanIo, StandardIo, UndoRequest};
+
 /// A player that is human
+///
+/// The input of the human is read from and the output is written to the
+/// [`HumanIo`], which is the standard input and output by default. Invalid
+/// input is answered with a new prompt.
+/// If the human enters `undo` the [`Player::get_action`] call fails with an
+/// [`UndoRequest`] that the game loop can honor.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct HumanPlayer {
+pub struct HumanPlayer<Io: HumanIo = StandardIo> {
     /// The name of the player.
     name: String,
+    /// The input and output of the human.
+    io: Io,
 }
 
 impl HumanPlayer {
-    /// Creates a new [`HumanPlayer`] with the given name.
+    /// Creates a new [`HumanPlayer`] with the given name that uses the
+    /// standard input and output.
     pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+        Self::with_io(name, StandardIo)
+    }
+}
+
+impl<Io: HumanIo> HumanPlayer<Io> {
+    /// Creates a new [`HumanPlayer`] with the given name that uses the given
+    /// input and output.
+    pub fn with_io(name: impl Into<String>, io: Io) -> Self {
+        Self { name: name.into(), io }
+    }
+
+    /// Gets the input and output of the human.
+    #[must_use]
+    pub const fn io(&self) -> &Io {
+        &self.io
     }
 }
 
@@ -27,205 +51,77 @@ impl Default for HumanPlayer {
     }
 }
 
-impl Player for HumanPlayer {
+impl<Io: HumanIo> Player for HumanPlayer<Io> {
     fn name(&self) -> &str {
         &self.name
     }
 
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
-        let valid_actions = game.get_valid_actions();
-
-        if valid_actions[0].is_special_patch_placement() {
-            self.handle_special_patch_action(valid_actions)
-        } else {
-            self.handle_normal_action(game, valid_actions)
-        }
-    }
-}
+        let numbered_actions = game.display_numbered_actions();
+        self.io.write_line(&format!("Valid actions:\n{numbered_actions}"))?;
 
-impl HumanPlayer {
-    // Gets the input for the special patch placement.
-    //
-    // # Arguments
-    //
-    // * `state` - The current state.
-    // * `valid_actions` - The valid actions.
-    //
-    // # Returns
-    //
-    // The action.
-    fn handle_special_patch_action(&mut self, valid_actions: Vec<ActionId>) -> PlayerResult<ActionId> {
-        let mut valid_actions = valid_actions;
-        let initial_prompt = format!(
-            "Player '{}' has to place the special patch. Please enter the row and column of the patch (row, column):",
-            self.name
-        );
+        let initial_prompt = self.get_initial_prompt(game, &numbered_actions);
         #[allow(clippy::redundant_clone)] // This clone is needed but clippy does not get this
         let mut prompt = initial_prompt.clone();
 
         loop {
-            let human_input = self.get_human_input(&prompt)?;
-
-            if human_input == "skip" {
-                let index = rand::thread_rng().gen_range(0..valid_actions.len());
-                return Ok(valid_actions.remove(index));
-            }
-
-            let human_inputs = Regex::new(r"[, ]+")
-                .unwrap()
-                .split(&human_input)
-                .map(std::string::ToString::to_string)
-                .collect::<Vec<_>>();
-
-            if human_inputs.len() != 2 {
-                prompt = format!("Please enter 'row, column'. {initial_prompt}");
-                continue;
-            }
-
-            let optional_row = human_inputs[0].parse::<u8>();
-            let optional_column = human_inputs[1].parse::<u8>();
-
-            if optional_row.is_err() || optional_column.is_err() {
-                prompt = format!(
-                    "Please enter valid numbers for row (1-{}) and column (1-{}). {}",
-                    QuiltBoard::ROWS,
-                    QuiltBoard::COLUMNS,
-                    initial_prompt
-                );
-                continue;
-            }
+            let human_input = self.get_raw_human_input(&prompt)?;
 
-            let row = optional_row.unwrap();
-            let column = optional_column.unwrap();
-
-            if row > QuiltBoard::ROWS || column > QuiltBoard::COLUMNS {
-                prompt = format!(
-                    "Please enter valid numbers for row (1-{}) and column (1-{}). {}",
-                    QuiltBoard::ROWS,
-                    QuiltBoard::COLUMNS,
-                    initial_prompt
-                );
-                continue;
-            }
-
-            let patch_position = (row - 1, column - 1);
-
-            for action in &valid_actions {
-                let action_row = action.get_row();
-                let action_column = action.get_column();
-
-                if action_row == patch_position.0 && action_column == patch_position.1 {
-                    return Ok(*action);
+            match parse_human_input(&human_input, game, &numbered_actions) {
+                HumanInput::Action(action) => return Ok(action),
+                HumanInput::TakePatch(patch_index) => {
+                    let placements = numbered_actions
+                        .iter()
+                        .map(|(_, action)| action)
+                        .filter(|action| action.is_patch_placement() && action.get_patch_index() == patch_index)
+                        .collect::<Vec<_>>();
+                    return self.handle_place_patch(game, &placements, patch_index);
+                }
+                HumanInput::Skip => {
+                    let number = GameRng::with_thread_rng(|rng| rng.gen_range(1..=numbered_actions.len()));
+                    return Ok(numbered_actions.get_action(number).unwrap());
                 }
+                HumanInput::Undo => return Err(UndoRequest.into()),
+                HumanInput::Invalid(reason) => prompt = format!("{reason}. {initial_prompt}"),
             }
-
-            prompt = format!(
-                "Position ({}, {}) is not valid. Please enter a valid position ({}). {}",
-                row,
-                column,
-                valid_actions
-                    .iter()
-                    .map(|action| {
-                        if action.is_special_patch_placement() {
-                            let action_row = action.get_row();
-                            let action_column = action.get_column();
-                            format!("({}, {})", action_row + 1, action_column + 1)
-                        } else {
-                            String::new()
-                        }
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", "),
-                initial_prompt
-            );
         }
     }
+}
 
-    /// Gets the input for the normal action.
+impl<Io: HumanIo> HumanPlayer<Io> {
+    /// Gets the prompt that describes the possible inputs in the given state.
     ///
     /// # Arguments
     ///
     /// * `state` - The current state.
-    /// * `valid_actions` - The valid actions.
+    /// * `numbered_actions` - The numbered valid actions.
     ///
     /// # Returns
     ///
-    /// The action.
-    fn handle_normal_action(&mut self, state: &Patchwork, valid_actions: Vec<ActionId>) -> PlayerResult<ActionId> {
-        let mut valid_actions = valid_actions;
-        let mut actions: HashSet<&str, _> = HashSet::new();
-        actions.insert("walk");
-
-        for action in &valid_actions {
-            if action.is_first_patch_taken() {
-                actions.insert("take 1");
-            } else if action.is_second_patch_taken() {
-                actions.insert("take 2");
-            } else if action.is_third_patch_taken() {
-                actions.insert("take 3");
-            }
+    /// The prompt.
+    fn get_initial_prompt(&self, state: &Patchwork, numbered_actions: &NumberedActions) -> String {
+        if state.turn_type == TurnType::SpecialPatchPlacement {
+            return format!(
+                "Player '{}' has to place the special patch. Please enter the row and column of the patch (row, column), the number or the notation of the action ('undo' to undo):",
+                self.name
+            );
         }
 
-        let mut available_actions = actions.iter().map(|a| format!("'{a}'")).collect::<Vec<_>>();
-        available_actions.sort_unstable();
+        let mut available_actions = vec!["'walk'".to_string()];
+        for patch_index in 0..PatchManager::MAX_AMOUNT_OF_CHOOSABLE_TILES as u8 {
+            if numbered_actions
+                .iter()
+                .any(|(_, action)| action.is_patch_placement() && action.get_patch_index() == patch_index)
+            {
+                available_actions.push(format!("'take {}'", patch_index + 1));
+            }
+        }
 
-        let initial_prompt = format!(
-            "Player '{}' can choose one of the following actions: {}. Please enter the action:",
+        format!(
+            "Player '{}' can choose one of the following actions: {}. Please enter the action, its number or its notation ('undo' to undo):",
             self.name,
             available_actions.join(", ")
-        );
-        #[allow(clippy::redundant_clone)] // This clone is needed but clippy does not get this
-        let mut prompt = initial_prompt.clone();
-
-        loop {
-            let human_input = self.get_human_input(&prompt)?;
-
-            if human_input == "skip" {
-                let index = rand::thread_rng().gen_range(0..valid_actions.len());
-                return Ok(valid_actions.remove(index));
-            }
-
-            if human_input == "walk" {
-                return Ok(valid_actions.remove(0));
-            }
-
-            if human_input == "take 1" && actions.contains("take 1") {
-                return self.handle_place_patch(
-                    state,
-                    valid_actions
-                        .iter()
-                        .filter(|action| action.is_first_patch_taken())
-                        .copied()
-                        .collect::<Vec<ActionId>>()
-                        .as_slice(),
-                    0,
-                );
-            } else if human_input == "take 2" && actions.contains("take 2") {
-                return self.handle_place_patch(
-                    state,
-                    valid_actions
-                        .iter()
-                        .filter(|action| action.is_second_patch_taken())
-                        .copied()
-                        .collect::<Vec<ActionId>>()
-                        .as_slice(),
-                    1,
-                );
-            } else if human_input == "take 3" && actions.contains("take 3") {
-                return self.handle_place_patch(
-                    state,
-                    valid_actions
-                        .iter()
-                        .filter(|action| action.is_third_patch_taken())
-                        .copied()
-                        .collect::<Vec<ActionId>>()
-                        .as_slice(),
-                    2,
-                );
-            }
-
-            prompt = format!("Please enter a valid action. {initial_prompt}");
-        }
+        )
     }
 
     /// Gets the input for the patch placement.
@@ -404,28 +300,197 @@ impl HumanPlayer {
         }
     }
 
-    fn get_human_input(&mut self, prompt: &str) -> PlayerResult<String> {
-        let mut human_input = String::new();
-        print!("{prompt} ");
-        io::stdout().lock().flush().unwrap();
-        io::stdin().read_line(&mut human_input)?;
-
-        human_input = human_input.trim().to_lowercase();
-        self.handle_exit_input(&human_input);
+    /// Reads the input of the human. Exits the program if the human enters
+    /// `exit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt to show before reading the input.
+    ///
+    /// # Returns
+    ///
+    /// The trimmed input as entered by the human.
+    ///
+    /// # Errors
+    ///
+    /// If the input could not be read or the input ended.
+    fn get_raw_human_input(&mut self, prompt: &str) -> PlayerResult<String> {
+        let human_input = self
+            .io
+            .read_line(prompt)?
+            .ok_or_else(|| anyhow!("[HumanPlayer::get_raw_human_input] The input ended"))?;
+        let human_input = human_input.trim().to_string();
+        self.handle_exit_input(&human_input.to_lowercase())?;
 
         Ok(human_input)
     }
 
-    /// Handles the exit input.
+    /// Reads the input of the human in lowercase. Exits the program if the
+    /// human enters `exit`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt to show before reading the input.
+    ///
+    /// # Returns
+    ///
+    /// The trimmed input in lowercase.
+    fn get_human_input(&mut self, prompt: &str) -> PlayerResult<String> {
+        Ok(self.get_raw_human_input(prompt)?.to_lowercase())
+    }
+
+    /// Handles the exit input. Exits the program if the [`HumanIo`] allows
+    /// it, otherwise the input of the human is treated as ended.
     ///
     /// # Arguments
     ///
     /// * `human_input` - The human input.
-    #[allow(clippy::unused_self)]
-    fn handle_exit_input(&mut self, human_input: &str) {
-        if human_input == "exit" {
-            println!("Exiting...");
+    ///
+    /// # Errors
+    ///
+    /// If the human wants to exit and the program is not exited.
+    fn handle_exit_input(&mut self, human_input: &str) -> PlayerResult<()> {
+        if human_input != "exit" {
+            return Ok(());
+        }
+
+        self.io.write_line("Exiting...")?;
+        if self.io.exits_program() {
             std::process::exit(0);
         }
+
+        Err(anyhow!("[HumanPlayer::handle_exit_input] The human exited"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use patchwork_core::{GameOptions, Notation};
+
+    use crate::BufferedIo;
+
+    use super::*;
+
+    type TestIo = BufferedIo<Cursor<String>, Vec<u8>>;
+
+    fn get_player(lines: &[&str]) -> HumanPlayer<TestIo> {
+        let input = lines.iter().flat_map(|line| [line, "\n"]).collect::<String>();
+        HumanPlayer::with_io("Test", BufferedIo::new(Cursor::new(input), vec![]))
+    }
+
+    fn get_output(player: &HumanPlayer<TestIo>) -> String {
+        String::from_utf8(player.io().writer().clone()).unwrap()
+    }
+
+    #[test]
+    fn test_parser_accepts_numbers_notations_and_shorthands() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let numbered_actions = state.display_numbered_actions();
+        let parse = |input: &str| parse_human_input(input, &state, &numbered_actions);
+
+        let walking = numbered_actions.get_action(1).unwrap();
+        assert!(walking.is_walking());
+        assert_eq!(HumanInput::Action(walking), parse("1"));
+        assert_eq!(HumanInput::Action(walking), parse("walk"));
+        assert_eq!(HumanInput::Action(walking), parse(" W "));
+
+        let placement = numbered_actions.get_action(2).unwrap();
+        assert_eq!(HumanInput::Action(placement), parse(&placement.save_to_notation().unwrap()));
+
+        let patch_index = placement.get_patch_index();
+        let transformation =
+            PatchManager::get_transformation(placement.get_patch_id(), placement.get_patch_transformation_index());
+        let shorthand = format!(
+            "t{} {}, {} {} {}",
+            patch_index + 1,
+            transformation.row + 1,
+            transformation.column + 1,
+            transformation.rotation(),
+            if transformation.flipped() { "y" } else { "n" }
+        );
+        assert_eq!(HumanInput::Action(placement), parse(&shorthand));
+        assert_eq!(HumanInput::TakePatch(patch_index), parse(&format!("take {}", patch_index + 1)));
+
+        assert_eq!(HumanInput::Undo, parse("undo"));
+        assert_eq!(HumanInput::Skip, parse("skip"));
+        for invalid in ["", "0", "100000", "t4", "take 1 10 1", "t1 1 1 45", "jump", "S═0‖0"] {
+            assert!(matches!(parse(invalid), HumanInput::Invalid(_)), "'{invalid}' should be invalid");
+        }
+    }
+
+    #[test]
+    fn test_player_reprompts_on_invalid_input() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let numbered_actions = state.display_numbered_actions();
+
+        let mut player = get_player(&["", "jump", "0", "take 9", "3"]);
+        assert_eq!(numbered_actions.get_action(3).unwrap(), player.get_action(&state).unwrap());
+
+        // the step by step placement of a taken patch still works
+        let placement = numbered_actions.get_action(2).unwrap();
+        let transformation =
+            PatchManager::get_transformation(placement.get_patch_id(), placement.get_patch_transformation_index());
+        let take = format!("take {}", placement.get_patch_index() + 1);
+        let orientation = format!("{} {}", transformation.rotation(), if transformation.flipped() { "y" } else { "n" });
+        let position = format!("{} {}", transformation.row + 1, transformation.column + 1);
+        let mut player = get_player(&[&take, &orientation, &position]);
+        assert_eq!(placement, player.get_action(&state).unwrap());
+
+        // the input ending is an error instead of an endless loop
+        assert!(get_player(&["jump"]).get_action(&state).is_err());
+    }
+
+    #[test]
+    fn test_player_signals_undo() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+
+        let error = get_player(&["jump", "undo"]).get_action(&state).unwrap_err();
+        assert!(error.is::<UndoRequest>());
+    }
+
+    #[test]
+    fn test_player_places_special_patch() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut seed = 0;
+        while state.turn_type != TurnType::SpecialPatchPlacement {
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+            seed += 1;
+        }
+
+        let action = state.get_valid_actions()[0];
+        let position = format!("{}, {}", action.get_row() + 1, action.get_column() + 1);
+        let mut player = get_player(&["10, 10", &position]);
+        assert_eq!(action, player.get_action(&state).unwrap());
+    }
+
+    #[test]
+    fn test_player_plays_scripted_interaction_over_buffers() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let first_action = state.display_numbered_actions().get_action(2).unwrap();
+        let mut player = get_player(&["jump", "2", "w"]);
+
+        assert_eq!(first_action, player.get_action(&state).unwrap());
+        state.do_action(first_action, false).unwrap();
+        let second_action = player.get_action(&state).unwrap();
+        assert!(second_action.is_walking());
+
+        let output = get_output(&player);
+        assert_eq!(2, output.matches("Valid actions:").count());
+        assert!(output.contains(&format!("2: {}", first_action.save_to_notation().unwrap())));
+        assert!(output.contains("Unknown input 'jump'. Player 'Test' can choose one of the following actions"));
+
+        // the input ended
+        assert!(player.get_action(&state).is_err());
+    }
+
+    #[test]
+    fn test_exit_ends_input_of_buffered_io() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut player = get_player(&["exit", "1"]);
+
+        assert!(player.get_action(&state).is_err());
+        assert!(get_output(&player).ends_with("Exiting...\n"));
     }
 }
@@ -1,3 +1,5 @@
+mod human_input;
 mod human_player;
 
+pub use human_input::{parse_human_input, BufferedIo, HumanInput, HumanIo, StandardIo, UndoRequest};
 pub use human_player::HumanPlayer;
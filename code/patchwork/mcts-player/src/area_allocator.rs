@@ -8,12 +8,38 @@ use crate::{Node, NodeId};
 pub struct AreaAllocator {
     /// The nodes in the search tree.
     pub nodes: Vec<Node>,
+    /// The maximum amount of nodes that may be allocated. `None` for no limit.
+    max_nodes: Option<usize>,
 }
 
 impl AreaAllocator {
     /// Create a new [`AreaAllocator`] with no nodes.
     pub const fn new() -> Self {
-        Self { nodes: Vec::new() }
+        Self {
+            nodes: Vec::new(),
+            max_nodes: None,
+        }
+    }
+
+    /// Sets the maximum amount of nodes that may be allocated. Once the limit
+    /// is reached the allocator refuses to allocate further nodes. The root
+    /// node of an empty allocator can always be allocated.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_nodes` - The maximum amount of nodes or `None` for no limit.
+    pub const fn set_max_nodes(&mut self, max_nodes: Option<usize>) {
+        self.max_nodes = max_nodes;
+    }
+
+    /// Whether the maximum amount of nodes is allocated and no further node
+    /// may be allocated.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    pub fn is_full(&self) -> bool {
+        self.max_nodes.is_some_and(|max_nodes| self.nodes.len() >= max_nodes)
     }
 
     /// Clear all nodes from the allocator.
@@ -34,6 +60,41 @@ impl AreaAllocator {
         self.nodes.len()
     }
 
+    /// Reserves capacity for at least `additional` more nodes so that the
+    /// allocator does not need to reallocate while they are created.
+    ///
+    /// # Arguments
+    ///
+    /// * `additional` - The amount of nodes to reserve capacity for.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the number of nodes in the allocator (if a reallocation is needed).
+    pub fn reserve(&mut self, additional: usize) {
+        // never reserve more nodes than may be allocated
+        let additional = self.max_nodes.map_or(additional, |max_nodes| {
+            additional.min(max_nodes.saturating_sub(self.nodes.len()))
+        });
+        self.nodes.reserve(additional);
+    }
+
+    /// Get a report about the memory usage of the allocator.
+    ///
+    /// # Returns
+    ///
+    /// The report about the memory usage of the allocator.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    pub const fn report(&self) -> AllocatorReport {
+        AllocatorReport {
+            nodes: self.nodes.len(),
+            capacity: self.nodes.capacity(),
+            bytes_used: self.nodes.len() * std::mem::size_of::<Node>(),
+        }
+    }
+
     /// Create a new node in the search tree.
     ///
     /// # Arguments
@@ -50,6 +111,10 @@ impl AreaAllocator {
     ///
     /// `𝒪(𝟣)`
     pub fn new_node(&mut self, game: Patchwork, parent: Option<NodeId>, action_taken: Option<ActionId>) -> NodeId {
+        assert!(
+            self.nodes.is_empty() || !self.is_full(),
+            "[AreaAllocator::new_node] The maximum amount of nodes is already allocated"
+        );
         let next_node_id = self.nodes.len();
         let node_id = NodeId(next_node_id);
 
@@ -145,6 +210,18 @@ impl AreaAllocator {
     }
 }
 
+/// A report about the memory usage of an [`AreaAllocator`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct AllocatorReport {
+    /// The amount of nodes in the allocator.
+    pub nodes: usize,
+    /// The amount of nodes the allocator can hold without reallocating.
+    pub capacity: usize,
+    /// The bytes used by the nodes in the allocator. This does not include
+    /// memory that is owned by the nodes themselves (e.g. their children).
+    pub bytes_used: usize,
+}
+
 impl Default for AreaAllocator {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,141 @@
+use evaluator::StaticEvaluator;
+use patchwork_core::{evaluator_constants, Evaluator, Patchwork};
+use tree_policy::UCTPolicy;
+
+use crate::{mcts_player::NON_ZERO_USIZE_ONE, SearchTree};
+
+/// The options for [`HybridEvaluator`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HybridOptions {
+    /// The amount of MCTS iterations that are run for every evaluation. With
+    /// `0` iterations the evaluator is the same as the [`StaticEvaluator`].
+    pub iterations: usize,
+    /// The weight of the MCTS value in the blended evaluation between `0.0`
+    /// (only the static evaluation) and `1.0` (only the MCTS value).
+    pub mcts_weight: f64,
+}
+
+impl HybridOptions {
+    /// Creates a new [`HybridOptions`].
+    ///
+    /// # Panics
+    ///
+    /// If the weight is not between `0.0` and `1.0`.
+    #[must_use]
+    pub fn new(iterations: usize, mcts_weight: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&mcts_weight),
+            "[HybridOptions::new] The MCTS weight has to be between 0.0 and 1.0"
+        );
+        Self {
+            iterations,
+            mcts_weight,
+        }
+    }
+}
+
+impl Default for HybridOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 16,
+            mcts_weight: 0.5,
+        }
+    }
+}
+
+/// An evaluator that blends the [`StaticEvaluator`] with the value of a very
+/// shallow Monte Carlo Tree Search (MCTS) from the evaluated state.
+///
+/// This is an experiment to combine the two search paradigms, e.g. for the
+/// leaf nodes of the principal variation search. The MCTS uses the
+/// [`UCTPolicy`] and evaluates its own leaf nodes with the
+/// [`StaticEvaluator`]. The value of the search is the average score of all
+/// its iterations.
+#[derive(Default)]
+pub struct HybridEvaluator {
+    /// The options of the evaluator.
+    options: HybridOptions,
+    /// The evaluator that is blended with the MCTS value and evaluates the nodes of the MCTS.
+    static_evaluator: StaticEvaluator,
+    /// The tree policy of the MCTS.
+    policy: UCTPolicy,
+}
+
+impl HybridEvaluator {
+    /// Creates a new [`HybridEvaluator`] with the given options.
+    #[must_use]
+    pub fn new(options: HybridOptions) -> Self {
+        Self {
+            options,
+            static_evaluator: StaticEvaluator::new(),
+            policy: UCTPolicy::default(),
+        }
+    }
+
+    /// Runs the shallow MCTS from the given state.
+    ///
+    /// # Returns
+    ///
+    /// The average score of all iterations or `None` if no iteration was run.
+    fn get_mcts_value(&self, game: &Patchwork) -> Option<f64> {
+        let mut search_tree = SearchTree::new(game, &self.policy, &self.static_evaluator);
+        search_tree.reserve(self.options.iterations + 1);
+        for _ in 0..self.options.iterations {
+            search_tree.playout(NON_ZERO_USIZE_ONE).ok()?;
+        }
+        search_tree.get_average_score()
+    }
+}
+
+impl Evaluator for HybridEvaluator {
+    fn evaluate_intermediate_node(&self, game: &Patchwork) -> i32 {
+        let static_value = self.static_evaluator.evaluate_intermediate_node(game);
+        let Some(mcts_value) = self.get_mcts_value(game) else {
+            return static_value;
+        };
+
+        let weight = self.options.mcts_weight;
+        let value = (1.0 - weight).mul_add(f64::from(static_value), weight * mcts_value);
+        value.round().clamp(
+            f64::from(evaluator_constants::NEGATIVE_INFINITY),
+            f64::from(evaluator_constants::POSITIVE_INFINITY),
+        ) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::PositionCorpus;
+
+    use super::*;
+
+    #[test]
+    fn test_blends_static_evaluation_with_mcts_value() {
+        let static_evaluator = StaticEvaluator::new();
+        let without_budget = HybridEvaluator::new(HybridOptions::new(0, 0.5));
+        let with_budget = HybridEvaluator::new(HybridOptions::new(32, 0.5));
+
+        let positions = PositionCorpus::standard()
+            .into_iter()
+            .filter(|state| !state.is_terminated())
+            .collect::<Vec<_>>();
+        let mut shifted = 0;
+        for state in &positions {
+            let static_value = static_evaluator.evaluate_node(state);
+            assert_eq!(
+                static_value,
+                without_budget.evaluate_node(state),
+                "Different value for {state}"
+            );
+            if with_budget.evaluate_node(state) != static_value {
+                shifted += 1;
+            }
+        }
+
+        assert!(
+            shifted > positions.len() / 2,
+            "Only {shifted} of {} values shifted",
+            positions.len()
+        );
+    }
+}
@@ -1,4 +1,5 @@
 mod area_allocator;
+mod hybrid_evaluator;
 mod mcts_options;
 mod mcts_player;
 mod node;
@@ -12,5 +13,7 @@ use node_id::NodeId;
 use search_tree::SearchTree;
 use tree::Tree;
 
-pub use mcts_options::{MCTSEndCondition, MCTSOptions};
+pub use area_allocator::AllocatorReport;
+pub use hybrid_evaluator::{HybridEvaluator, HybridOptions};
+pub use mcts_options::{ExplorationDecay, MCTSEndCondition, MCTSOptions, OpponentModel};
 pub use mcts_player::MCTSPlayer;
@@ -4,7 +4,8 @@ use std::{
     sync::{atomic::AtomicBool, Arc},
 };
 
-use patchwork_core::Logging;
+use evaluator::StaticEvaluator;
+use patchwork_core::{ActionId, Evaluator, Logging, Patchwork, TimeBoard};
 
 /// Different end conditions for the Monte Carlo Tree Search (MCTS) algorithm.
 #[derive(Clone, Debug)]
@@ -33,6 +34,123 @@ impl Display for MCTSEndCondition {
     }
 }
 
+/// Different models of the opponent for asymmetric play.
+///
+/// With an opponent model the search no longer assumes that the opponent
+/// plays like the searching player. Instead at every node where the opponent
+/// is to move only the action of the model is expanded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum OpponentModel {
+    /// The opponent plays the action that leads to the best static
+    /// evaluation for itself like the greedy player.
+    Greedy,
+}
+
+impl OpponentModel {
+    /// Gets the action the modeled opponent would play in the given state.
+    ///
+    /// Ties are broken by the order of the valid actions so that the model is
+    /// deterministic.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state where the opponent is to move. The state must not be terminated.
+    ///
+    /// # Returns
+    ///
+    /// The action of the modeled opponent.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of valid actions.
+    #[must_use]
+    pub fn get_action(&self, state: &Patchwork) -> ActionId {
+        match self {
+            Self::Greedy => {
+                let sign = if state.is_player_1() { 1 } else { -1 };
+                let successors = state.expand();
+
+                let mut best_action = successors[0].0;
+                let mut best_evaluation = i32::MIN;
+                for (action, next_state) in successors {
+                    let evaluation = sign * StaticEvaluator::new().evaluate_node(&next_state);
+                    if evaluation > best_evaluation {
+                        best_evaluation = evaluation;
+                        best_action = action;
+                    }
+                }
+
+                best_action
+            }
+        }
+    }
+}
+
+impl Display for OpponentModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Greedy => write!(f, "greedy"),
+        }
+    }
+}
+
+/// A schedule that decays the exploration constant of the tree policy over
+/// the course of a game.
+///
+/// The exploration constant is interpolated linearly between the opening and
+/// the endgame constant by the game progress. The game progress is given by
+/// the position of the leading player on the time board. This explores more in
+/// the opening and exploits more in the endgame.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExplorationDecay {
+    /// The exploration constant at the start of the game.
+    pub opening: f64,
+    /// The exploration constant at the end of the game.
+    pub endgame: f64,
+}
+
+impl ExplorationDecay {
+    /// Creates a new [`ExplorationDecay`].
+    ///
+    /// # Arguments
+    ///
+    /// * `opening` - The exploration constant at the start of the game.
+    /// * `endgame` - The exploration constant at the end of the game.
+    #[must_use]
+    pub const fn new(opening: f64, endgame: f64) -> Self {
+        Self { opening, endgame }
+    }
+
+    /// Gets the progress of the given game between `0.0` (start) and `1.0`
+    /// (end) by the position of the leading player on the time board.
+    #[must_use]
+    pub fn get_progress(state: &Patchwork) -> f64 {
+        let leading_position = state.player_1.get_position().max(state.player_2.get_position());
+        f64::from(leading_position.min(TimeBoard::MAX_POSITION)) / f64::from(TimeBoard::MAX_POSITION)
+    }
+
+    /// Gets the exploration constant to use for a search in the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state from which the search is started.
+    ///
+    /// # Returns
+    ///
+    /// The exploration constant interpolated by the game progress.
+    #[must_use]
+    pub fn get_exploration_constant(&self, state: &Patchwork) -> f64 {
+        let progress = Self::get_progress(state);
+        (self.endgame - self.opening).mul_add(progress, self.opening)
+    }
+}
+
+impl Display for ExplorationDecay {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..{}", self.opening, self.endgame)
+    }
+}
+
 /// Different options for the Monte Carlo Tree Search (MCTS) algorithm.
 #[derive(Debug)]
 pub struct MCTSOptions {
@@ -48,9 +166,44 @@ pub struct MCTSOptions {
     pub end_condition: MCTSEndCondition,
     /// Logging configuration on what to collect during the search.
     pub logging: Logging,
+    /// The desired playing strength between `0.0` (weakest) and `1.0`
+    /// (strongest). `None` to always play at full strength.
+    ///
+    /// A skill below `1.0` caps the amount of simulations (see
+    /// [`MCTSOptions::skill_simulations`]) and injects random moves (see
+    /// [`MCTSOptions::skill_random_move_probability`]).
+    pub skill: Option<f32>,
+    /// The exploration constant of the tree policy. `None` to use the default
+    /// exploration constant of the policy.
+    pub exploration_constant: Option<f64>,
+    /// The schedule to decay the exploration constant over the course of the
+    /// game. Overrides the exploration constant if set. `None` to use the same
+    /// exploration constant for the whole game.
+    pub exploration_decay: Option<ExplorationDecay>,
+    /// The model of the opponent. `None` to assume that the opponent searches
+    /// like the player itself (symmetric self-play).
+    pub opponent_model: Option<OpponentModel>,
+    /// The maximum amount of nodes of a search tree (per root parallelized
+    /// search). Once a tree reached this size the search stops expanding and
+    /// only evaluates the existing leaf nodes. `None` to grow the trees until
+    /// the end condition is reached.
+    pub max_nodes: Option<usize>,
+    /// The seed of the random number generator that chooses the order in
+    /// which the nodes are expanded and the random moves at a reduced skill.
+    /// `None` to seed from entropy. A search with a seed is only reproducible
+    /// with an iteration end condition, without parallelization and with a
+    /// deterministic evaluator.
+    pub seed: Option<u64>,
 }
 
 impl MCTSOptions {
+    /// The amount of simulations used at the lowest skill level.
+    pub const MIN_SKILL_SIMULATIONS: usize = 10;
+    /// The amount of simulations used right below the highest skill level.
+    pub const MAX_SKILL_SIMULATIONS: usize = 10_000;
+    /// The probability to play a random move at the lowest skill level.
+    pub const MAX_SKILL_RANDOM_MOVE_PROBABILITY: f32 = 0.5;
+
     /// Creates a new [`MCTSOptions`].
     #[must_use]
     pub const fn new(
@@ -66,14 +219,76 @@ impl MCTSOptions {
             reuse_tree,
             end_condition,
             logging,
+            skill: None,
+            exploration_constant: None,
+            exploration_decay: None,
+            opponent_model: None,
+            max_nodes: None,
+            seed: None,
+        }
+    }
+
+    /// Gets the skill clamped to `[0.0, 1.0]` if a skill is set.
+    #[inline]
+    #[must_use]
+    pub fn clamped_skill(&self) -> Option<f32> {
+        self.skill.map(|skill| skill.clamp(0.0, 1.0))
+    }
+
+    /// Gets the simulation budget corresponding to the skill.
+    ///
+    /// The budget grows exponentially from [`MCTSOptions::MIN_SKILL_SIMULATIONS`]
+    /// at skill `0.0` to [`MCTSOptions::MAX_SKILL_SIMULATIONS`]. At skill `1.0`
+    /// the simulations are not capped.
+    ///
+    /// # Returns
+    ///
+    /// The maximum amount of simulations or `None` if the simulations are not
+    /// capped.
+    #[must_use]
+    pub fn skill_simulations(&self) -> Option<usize> {
+        let skill = self.clamped_skill()?;
+        if skill >= 1.0 {
+            return None;
+        }
+
+        let ratio = Self::MAX_SKILL_SIMULATIONS as f32 / Self::MIN_SKILL_SIMULATIONS as f32;
+        Some((Self::MIN_SKILL_SIMULATIONS as f32 * ratio.powf(skill)).round() as usize)
+    }
+
+    /// Gets the probability to play a random move instead of searching.
+    ///
+    /// The probability falls linearly from
+    /// [`MCTSOptions::MAX_SKILL_RANDOM_MOVE_PROBABILITY`] at skill `0.0` to `0.0`
+    /// at skill `1.0`.
+    #[must_use]
+    pub fn skill_random_move_probability(&self) -> f32 {
+        self.clamped_skill()
+            .map_or(0.0, |skill| (1.0 - skill) * Self::MAX_SKILL_RANDOM_MOVE_PROBABILITY)
+    }
+
+    /// Gets the end condition with the simulation budget of the skill applied.
+    ///
+    /// If the simulations are capped by the skill, time and flag end conditions
+    /// are replaced by the simulation budget and iteration end conditions are
+    /// limited to it.
+    #[must_use]
+    pub fn skill_end_condition(&self) -> MCTSEndCondition {
+        match (self.skill_simulations(), &self.end_condition) {
+            (None, end_condition) => end_condition.clone(),
+            (Some(simulations), MCTSEndCondition::Iterations(iterations)) => {
+                MCTSEndCondition::Iterations(simulations.min(*iterations))
+            }
+            (Some(simulations), _) => MCTSEndCondition::Iterations(simulations),
         }
     }
 }
 
 impl Default for MCTSOptions {
     fn default() -> Self {
+        // a single available thread still needs one tree to search
         let root_parallelization = std::thread::available_parallelism()
-            .map(|n| unsafe { NonZeroUsize::new_unchecked(n.get() / 2) })
+            .map(|n| NonZeroUsize::new(n.get() / 2).unwrap_or(NonZeroUsize::MIN))
             .unwrap_or(NonZeroUsize::new(4).unwrap());
 
         Self {
@@ -82,6 +297,12 @@ impl Default for MCTSOptions {
             end_condition: MCTSEndCondition::Time(std::time::Duration::from_secs(10)),
             reuse_tree: true,
             logging: Logging::default(),
+            skill: None,
+            exploration_constant: None,
+            exploration_decay: None,
+            opponent_model: None,
+            max_nodes: None,
+            seed: None,
         }
     }
 }
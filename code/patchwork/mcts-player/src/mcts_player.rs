@@ -1,17 +1,20 @@
 use std::{
     num::NonZeroUsize,
-    ops::Sub,
     sync::{atomic::AtomicUsize, Arc},
     thread,
 };
 
 use evaluator::WinLossEvaluator;
-use patchwork_core::{ActionId, Evaluator, Logging, Patchwork, Player, PlayerResult, TreePolicy, TreePolicyNode};
+use patchwork_core::{
+    reset_thread_affinity, ActionId, Evaluator, ExplorationTreePolicy, GameRng, Logging, Patchwork, PatchworkError,
+    Player, PlayerResult, SearchDiagnostics, TreePolicy, TreePolicyNode,
+};
+use rand::{Rng, SeedableRng};
 use tree_policy::UCTPolicy;
 
 pub const NON_ZERO_USIZE_ONE: NonZeroUsize = unsafe { NonZeroUsize::new_unchecked(1) };
 
-use crate::{node_id::NodeId, AreaAllocator, MCTSEndCondition, MCTSOptions, SearchTree, Tree};
+use crate::{node_id::NodeId, AllocatorReport, AreaAllocator, MCTSEndCondition, MCTSOptions, Node, SearchTree, Tree};
 
 const REUSE_TREE_SEARCH_ABORT: Option<std::time::Duration> = Some(std::time::Duration::from_millis(2));
 const TIME_LIMIT_SAFETY_MARGIN: std::time::Duration = std::time::Duration::from_millis(75);
@@ -26,14 +29,27 @@ pub struct MCTSPlayer<Policy: TreePolicy = UCTPolicy, Eval: Evaluator = WinLossE
     pub policy: Policy,
     /// The evaluator to evaluate the game state.
     pub evaluator: Eval,
+    /// Creates the policy with the given exploration constant for the
+    /// exploration decay schedule.
+    policy_with_exploration_constant: fn(f64) -> Policy,
     /// The full trees of the last run with the action that was taken to speed up the later search.
     last_trees: Vec<Tree>,
+    /// The random number generator used to play random moves at a reduced
+    /// skill and to seed the search trees.
+    rng: GameRng,
+    /// The diagnostics of the last search (without the time of the search).
+    last_diagnostics: SearchDiagnostics,
+    /// The amount of nodes to reserve in the search trees before each search.
+    reserved_nodes: usize,
+    /// The allocator report of the main search tree after the last search.
+    last_allocator_report: Option<AllocatorReport>,
 }
 
-impl<Policy: TreePolicy + Default, Eval: Evaluator + Default> MCTSPlayer<Policy, Eval> {
+impl<Policy: ExplorationTreePolicy + Default, Eval: Evaluator + Default> MCTSPlayer<Policy, Eval> {
     /// Creates a new [`MCTSPlayer`] with the given name.
     pub fn new(name: impl Into<String>, options: Option<MCTSOptions>) -> Self {
         let options = options.unwrap_or_default();
+        let rng = options.seed.map_or_else(GameRng::from_entropy, GameRng::seed_from_u64);
         let last_trees = if options.reuse_tree {
             Vec::with_capacity(options.root_parallelization.get())
         } else {
@@ -49,15 +65,45 @@ impl<Policy: TreePolicy + Default, Eval: Evaluator + Default> MCTSPlayer<Policy,
             //     if options.reuse_tree { "R" } else { "N" }
             // ),
             name: name.into(),
-            policy: Default::default(),
+            policy: options
+                .exploration_constant
+                .map_or_else(Policy::default, Policy::with_exploration_constant),
             evaluator: Default::default(),
+            policy_with_exploration_constant: Policy::with_exploration_constant,
             options,
             last_trees,
+            rng,
+            last_diagnostics: SearchDiagnostics::default(),
+            reserved_nodes: 0,
+            last_allocator_report: None,
         }
     }
 }
 
-impl<Policy: TreePolicy + Default, Eval: Evaluator + Default> Default for MCTSPlayer<Policy, Eval> {
+impl<Policy: TreePolicy, Eval: Evaluator> MCTSPlayer<Policy, Eval> {
+    /// Reserves capacity for at least `nodes` nodes in the search trees
+    /// before each following search. Pre-sizing the trees for a known
+    /// simulation budget avoids reallocations during the search.
+    ///
+    /// # Arguments
+    ///
+    /// * `nodes` - The amount of nodes to reserve capacity for.
+    pub const fn reserve(&mut self, nodes: usize) {
+        self.reserved_nodes = nodes;
+    }
+
+    /// Gets the allocator report of the main search tree after the last
+    /// search.
+    ///
+    /// # Returns
+    ///
+    /// The allocator report or `None` if no search was done yet.
+    pub const fn get_allocator_report(&self) -> Option<AllocatorReport> {
+        self.last_allocator_report
+    }
+}
+
+impl<Policy: ExplorationTreePolicy + Default, Eval: Evaluator + Default> Default for MCTSPlayer<Policy, Eval> {
     fn default() -> Self {
         Self::new("MCTS Player".to_string(), Option::default())
     }
@@ -80,7 +126,7 @@ macro_rules! play_until_end_worker_thread {
             }
             MCTSEndCondition::Time(time_limit) => {
                 // add safety margin to time limit
-                let time_limit = time_limit.sub(TIME_LIMIT_SAFETY_MARGIN);
+                let time_limit = time_limit.saturating_sub(TIME_LIMIT_SAFETY_MARGIN);
                 let mut time_passed = std::time::Instant::now().duration_since($start_time);
                 loop {
                     if time_passed >= time_limit {
@@ -131,7 +177,7 @@ macro_rules! play_until_end {
             }
             MCTSEndCondition::Time(time_limit) => {
                 // add safety margin to time limit
-                let time_limit = time_limit.sub(TIME_LIMIT_SAFETY_MARGIN);
+                let time_limit = time_limit.saturating_sub(TIME_LIMIT_SAFETY_MARGIN);
                 let mut last_print = std::time::Instant::now();
                 loop {
                     if time_passed >= time_limit {
@@ -185,17 +231,60 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
         &self.name
     }
 
-    #[allow(clippy::too_many_lines)]
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.get_restricted_action(game, &[])
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
         let start_time = std::time::Instant::now();
 
+        let valid_actions = game.get_valid_actions();
+        if let Some(action) = actions.iter().find(|action| !valid_actions.contains(action)) {
+            return Err(PatchworkError::InvalidActionError {
+                reason: "[MCTSPlayer::get_restricted_action] The search cannot be restricted to an invalid action",
+                action: *action,
+                state: Box::new(game.clone()),
+            }
+            .into());
+        }
+        let valid_actions = if actions.is_empty() { valid_actions } else { actions.to_vec() };
+
+        // A forced move is played without searching
+        if valid_actions.len() == 1 {
+            // the trees cannot be reused as they were not searched for the forced move
+            self.last_trees.clear();
+            self.last_diagnostics = SearchDiagnostics::default();
+            self.last_allocator_report = None;
+            return Ok(valid_actions[0]);
+        }
+
+        // A reduced skill sometimes plays a random move instead of searching
+        let random_move_probability = self.options.skill_random_move_probability();
+        if random_move_probability > 0.0 && self.rng.gen::<f32>() < random_move_probability {
+            // the trees cannot be reused as they were not searched for the random move
+            self.last_trees.clear();
+            self.last_diagnostics = SearchDiagnostics::default();
+            self.last_allocator_report = None;
+            return Ok(valid_actions[self.rng.gen_range(0..valid_actions.len())]);
+        }
+
+        // The exploration decays with the progress of the game
+        if let Some(exploration_decay) = self.options.exploration_decay {
+            self.policy = (self.policy_with_exploration_constant)(exploration_decay.get_exploration_constant(game));
+        }
+
+        let end_condition = self.options.skill_end_condition();
+        let opponent_model = self.options.opponent_model;
+        let max_nodes = self.options.max_nodes;
+
         Ok(match &mut self.options {
             MCTSOptions {
                 root_parallelization: NON_ZERO_USIZE_ONE,
                 leaf_parallelization,
-                end_condition,
                 reuse_tree,
                 logging,
+                ..
             } => {
                 let last_tree = if self.last_trees.is_empty() {
                     None
@@ -209,11 +298,16 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
                     &self.policy,
                     &self.evaluator,
                     REUSE_TREE_SEARCH_ABORT,
-                );
+                )
+                .with_root_actions(actions)
+                .with_opponent_model(opponent_model)
+                .with_max_nodes(max_nodes)
+                .with_seed(self.rng.gen());
+                search_tree.reserve(self.reserved_nodes);
 
                 play_until_end!(
                     start_time,
-                    end_condition,
+                    &end_condition,
                     search_tree.playout(*leaf_parallelization)?,
                     |iteration, time_passed| {
                         write_statistics(
@@ -232,6 +326,8 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
 
                 log_verbose_information(logging, &search_tree)?;
 
+                self.last_diagnostics = get_diagnostics(&search_tree);
+                self.last_allocator_report = Some(search_tree.get_allocator_report());
                 let action = pick_best_action(&search_tree);
 
                 if *reuse_tree {
@@ -245,9 +341,9 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
             MCTSOptions {
                 root_parallelization,
                 leaf_parallelization,
-                end_condition,
                 reuse_tree,
                 logging,
+                ..
             } => {
                 let other_iterations = Arc::new(AtomicUsize::new(0));
 
@@ -269,16 +365,26 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
                         let leaf_parallel = *leaf_parallelization;
                         let end_cond = end_condition.clone();
                         let iterations = Arc::clone(&other_iterations);
+                        let reserved_nodes = self.reserved_nodes;
+                        let seed = self.rng.gen();
 
                         // start worker search thread
                         handles.push(s.spawn(move || {
+                            // only the main search thread stays on the core of a pinned caller
+                            reset_thread_affinity();
+
                             let mut search_tree = SearchTree::<Policy, Eval>::from_root(
                                 last_tree,
                                 game,
                                 policy,
                                 evaluator,
                                 REUSE_TREE_SEARCH_ABORT,
-                            );
+                            )
+                            .with_root_actions(actions)
+                            .with_opponent_model(opponent_model)
+                            .with_max_nodes(max_nodes)
+                            .with_seed(seed);
+                            search_tree.reserve(reserved_nodes);
 
                             play_until_end_worker_thread!(start_time, end_cond, {
                                 search_tree.playout(leaf_parallel)?;
@@ -300,11 +406,16 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
                         &self.policy,
                         &self.evaluator,
                         REUSE_TREE_SEARCH_ABORT,
-                    );
+                    )
+                    .with_root_actions(actions)
+                    .with_opponent_model(opponent_model)
+                    .with_max_nodes(max_nodes)
+                    .with_seed(self.rng.gen());
+                    search_tree.reserve(self.reserved_nodes);
 
                     play_until_end!(
                         start_time,
-                        end_condition,
+                        &end_condition,
                         search_tree.playout(*leaf_parallelization)?,
                         |iteration, time_passed| write_statistics(
                             logging,
@@ -321,6 +432,8 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
 
                     log_verbose_information(logging, &search_tree)?;
 
+                    self.last_diagnostics = get_diagnostics(&search_tree);
+                    self.last_allocator_report = Some(search_tree.get_allocator_report());
                     let mut trees = vec![Tree::new(search_tree.root, search_tree.allocator)];
 
                     for handle in handles {
@@ -365,6 +478,37 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
             }
         })
     }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        self.get_restricted_action_with_diagnostics(game, &[])
+    }
+
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        let start_time = std::time::Instant::now();
+        let action = self.get_restricted_action(game, actions)?;
+
+        Ok((
+            action,
+            SearchDiagnostics {
+                time: start_time.elapsed(),
+                ..self.last_diagnostics.clone()
+            },
+        ))
+    }
+
+    fn set_logging(&mut self, logging: Logging) {
+        self.options.logging = logging;
+    }
+
+    fn reset(&mut self) {
+        self.last_trees.clear();
+        self.last_diagnostics = SearchDiagnostics::default();
+        self.last_allocator_report = None;
+    }
 }
 
 /// Picks the best action from the root node.
@@ -372,6 +516,8 @@ impl<Policy: TreePolicy, Eval: Evaluator> Player for MCTSPlayer<Policy, Eval> {
 /// If there are multiple child nodes with the same number of visits, the action with the
 /// greater amount of wins is chosen. If there are still multiple actions with the same amount
 /// of wins, one of them is chosen randomly.
+/// If no child node was expanded (e.g. the time limit was too short for a single simulation),
+/// the first action of the root node that may be chosen is taken.
 ///
 /// # Arguments
 ///
@@ -385,25 +531,51 @@ pub fn pick_best_action(search_tree: &SearchTree<'_, impl TreePolicy, impl Evalu
     let root = search_tree.allocator.get_node(root_id);
     let root_player = root.state.is_player_1();
 
-    let best_action_node_id = *root
-        .children
-        .iter()
-        .max_by_key(|child_id| {
-            let child = search_tree.allocator.get_node(**child_id);
-            (child.visit_count, child.wins_for(root_player))
-        })
-        .unwrap();
+    let Some(best_action_node_id) = root.children.iter().max_by_key(|child_id| {
+        let child = search_tree.allocator.get_node(**child_id);
+        (child.visit_count, child.wins_for(root_player))
+    }) else {
+        return get_fallback_action(root);
+    };
 
-    let best_action = search_tree.allocator.get_node(best_action_node_id).action_taken.unwrap();
+    let best_action = search_tree.allocator.get_node(*best_action_node_id).action_taken.unwrap();
 
     best_action
 }
 
+/// Gets the diagnostics of the given search tree. With root parallelization
+/// only the search tree of the main thread is used.
+///
+/// # Arguments
+///
+/// * `search_tree` - The search tree to get the diagnostics for.
+///
+/// # Returns
+///
+/// The diagnostics of the search tree without the time of the search.
+///
+/// # Complexity
+///
+/// `𝒪(𝑛)` where `𝑛` is the number of nodes in the search tree.
+fn get_diagnostics(search_tree: &SearchTree<'_, impl TreePolicy, impl Evaluator>) -> SearchDiagnostics {
+    let principal_variation = search_tree.get_pv_actions();
+
+    SearchDiagnostics {
+        // the depth of the most visited line as the tree is not expanded evenly
+        depth: principal_variation.len(),
+        nodes: search_tree.get_nodes(),
+        principal_variation,
+        ..SearchDiagnostics::default()
+    }
+}
+
 /// Picks the best action from the root nodes of multiple trees.
 /// This is done by merging all the root nodes into one and then selecting the child node with the
 /// highest number of visits. If there are multiple child nodes with the same number of visits, the
 /// action with the greater amount of wins is chosen. If there are still multiple actions with the
 /// same amount of wins, one of them is chosen randomly.
+/// If no child node was expanded in any tree, the first valid action of the root state is
+/// chosen.
 ///
 /// # Arguments
 ///
@@ -435,7 +607,30 @@ pub fn pick_best_action_from_multiple(nodes: &[Tree]) -> ActionId {
         }
     }
 
-    *action_map.iter().max_by_key(|(_, (visits, wins))| (*visits, *wins)).unwrap().0
+    action_map.iter().max_by_key(|(_, (visits, wins))| (*visits, *wins)).map_or_else(
+        || get_fallback_action(nodes[0].allocator.get_node(nodes[0].root)),
+        |(action, _)| *action,
+    )
+}
+
+/// Gets the action to play if no simulation was completed. This is the first
+/// action that may be chosen at the root so that the choice is deterministic.
+/// As no child was expanded these are all valid actions of the root state or
+/// the actions the search was restricted to.
+///
+/// # Arguments
+///
+/// * `root` - The root node without any children.
+///
+/// # Returns
+///
+/// The first action that may be chosen at the root node.
+///
+/// # Complexity
+///
+/// `𝒪(𝟣)`
+fn get_fallback_action(root: &Node) -> ActionId {
+    root.expandable_actions[0]
 }
 
 /// Gets the tree to reuse for the given action.
@@ -606,3 +801,443 @@ fn log_verbose_information(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use evaluator::StaticEvaluator;
+    use patchwork_core::{GameOptions, TerminationType};
+
+    use crate::{ExplorationDecay, OpponentModel};
+
+    use super::*;
+
+    const GAMES: usize = 10;
+
+    fn create_player(name: &str, iterations: usize, skill: Option<f32>) -> MCTSPlayer {
+        MCTSPlayer::new(
+            name,
+            Some(MCTSOptions {
+                skill,
+                ..MCTSOptions::new(
+                    NON_ZERO_USIZE_ONE,
+                    NON_ZERO_USIZE_ONE,
+                    MCTSEndCondition::Iterations(iterations),
+                    false,
+                    Logging::Disabled,
+                )
+            }),
+        )
+    }
+
+    fn create_seeded_player(iterations: usize, skill: Option<f32>, seed: u64) -> MCTSPlayer {
+        MCTSPlayer::new(
+            "Seeded Player",
+            Some(MCTSOptions {
+                skill,
+                seed: Some(seed),
+                ..MCTSOptions::new(
+                    NON_ZERO_USIZE_ONE,
+                    NON_ZERO_USIZE_ONE,
+                    MCTSEndCondition::Iterations(iterations),
+                    false,
+                    Logging::Disabled,
+                )
+            }),
+        )
+    }
+
+    fn wins_against_fixed_opponent(skill: f32) -> usize {
+        let mut wins = 0;
+
+        for game in 0..GAMES {
+            let mut player = create_seeded_player(400, Some(skill), game as u64);
+            let mut opponent = create_seeded_player(100, None, game as u64 + 1);
+            // the rollouts of the evaluator use the generator of the thread
+            GameRng::seed_thread_rng(game as u64);
+            let player_is_player_1 = game % 2 == 0;
+
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(game as u64)));
+            while !state.is_terminated() {
+                let action = if state.is_player_1() == player_is_player_1 {
+                    player.get_action(&state).unwrap()
+                } else {
+                    opponent.get_action(&state).unwrap()
+                };
+                state.do_action(action, false).unwrap();
+            }
+
+            let player_won = match state.get_termination_result().termination {
+                TerminationType::Player1Won => player_is_player_1,
+                TerminationType::Player2Won => !player_is_player_1,
+            };
+            wins += usize::from(player_won);
+        }
+
+        wins
+    }
+
+    #[test]
+    fn test_skill_options() {
+        let weakest = MCTSOptions {
+            skill: Some(0.0),
+            ..MCTSOptions::default()
+        };
+        let strongest = MCTSOptions {
+            skill: Some(1.0),
+            ..MCTSOptions::default()
+        };
+
+        assert_eq!(Some(MCTSOptions::MIN_SKILL_SIMULATIONS), weakest.skill_simulations());
+        assert!(
+            (weakest.skill_random_move_probability() - MCTSOptions::MAX_SKILL_RANDOM_MOVE_PROBABILITY).abs() < 1e-6
+        );
+        assert!(matches!(
+            weakest.skill_end_condition(),
+            MCTSEndCondition::Iterations(MCTSOptions::MIN_SKILL_SIMULATIONS)
+        ));
+
+        assert_eq!(None, strongest.skill_simulations());
+        assert!(strongest.skill_random_move_probability().abs() < 1e-6);
+        assert!(matches!(strongest.skill_end_condition(), MCTSEndCondition::Time(_)));
+    }
+
+    #[test]
+    fn test_lower_skill_plays_weaker() {
+        let weak_wins = wins_against_fixed_opponent(0.0);
+        let strong_wins = wins_against_fixed_opponent(1.0);
+
+        assert!(
+            weak_wins < strong_wins,
+            "skill 0 won {weak_wins}/{GAMES} games, skill 1 won {strong_wins}/{GAMES} games"
+        );
+    }
+
+    #[test]
+    fn test_diagnostics_are_consistent() {
+        let mut player = create_player("Diagnostics Player", 500, None);
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for _ in 0..3 {
+            let start_time = std::time::Instant::now();
+            let (action, diagnostics) = player.get_action_with_diagnostics(&state).unwrap();
+
+            // forced moves are played without searching
+            if state.get_valid_actions().len() == 1 {
+                assert_eq!(0, diagnostics.depth);
+                state.do_action(action, false).unwrap();
+                continue;
+            }
+
+            assert!(diagnostics.depth > 0, "No action was expanded");
+            assert!(diagnostics.nodes > diagnostics.depth, "Less nodes than depth searched");
+            assert_eq!(diagnostics.depth, diagnostics.principal_variation.len());
+            assert!(diagnostics.time <= start_time.elapsed());
+            assert!(diagnostics.transposition_table.is_none());
+
+            state.do_action(action, false).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_same_seed_plays_same_actions() {
+        let play = |seed| {
+            let mut player = MCTSPlayer::<UCTPolicy, StaticEvaluator>::new(
+                "Seeded Player",
+                Some(MCTSOptions {
+                    seed: Some(seed),
+                    ..MCTSOptions::new(
+                        NON_ZERO_USIZE_ONE,
+                        NON_ZERO_USIZE_ONE,
+                        MCTSEndCondition::Iterations(200),
+                        true,
+                        Logging::Disabled,
+                    )
+                }),
+            );
+
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+            let mut actions = vec![];
+            for _ in 0..6 {
+                let action = player.get_action(&state).unwrap();
+                state.do_action(action, false).unwrap();
+                actions.push(action);
+            }
+            actions
+        };
+
+        assert_eq!(play(1), play(1));
+    }
+
+    #[test]
+    fn test_restricted_action_is_within_the_given_actions() {
+        for root_parallelization in [NON_ZERO_USIZE_ONE, NonZeroUsize::new(2).unwrap()] {
+            let mut player: MCTSPlayer = MCTSPlayer::new(
+                "Restricted Player",
+                Some(MCTSOptions::new(
+                    root_parallelization,
+                    NON_ZERO_USIZE_ONE,
+                    MCTSEndCondition::Iterations(100),
+                    true,
+                    Logging::Disabled,
+                )),
+            );
+
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+            for _ in 0..6 {
+                // the last actions are restricted so that a reused tree has expanded other actions
+                let valid_actions = state.get_valid_actions();
+                let actions = &valid_actions[valid_actions.len().saturating_sub(2)..];
+
+                let action = player.get_restricted_action(&state, actions).unwrap();
+                assert!(actions.contains(&action), "{action:?} is not one of {actions:?}");
+                state.do_action(action, false).unwrap();
+            }
+
+            assert!(player.get_restricted_action(&state, &[ActionId::null()]).is_err());
+        }
+    }
+
+    #[test]
+    fn test_near_zero_time_limit_returns_legal_action() {
+        for root_parallelization in [NON_ZERO_USIZE_ONE, NonZeroUsize::new(2).unwrap()] {
+            let mut player: MCTSPlayer = MCTSPlayer::new(
+                "Near Zero Time Player",
+                Some(MCTSOptions::new(
+                    root_parallelization,
+                    NON_ZERO_USIZE_ONE,
+                    MCTSEndCondition::Time(std::time::Duration::from_millis(1)),
+                    true,
+                    Logging::Disabled,
+                )),
+            );
+
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+            for _ in 0..3 {
+                let action = player.get_action(&state).unwrap();
+
+                assert!(state.get_valid_actions().contains(&action), "Illegal action {action:?}");
+                assert_eq!(state.get_valid_actions()[0], action, "Fallback action is not deterministic");
+
+                state.do_action(action, false).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_reserve_avoids_reallocation() {
+        const SIMULATIONS: usize = 300;
+
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let policy = UCTPolicy::default();
+        let evaluator = WinLossEvaluator::default();
+
+        let mut search_tree = SearchTree::new(&state, &policy, &evaluator);
+        search_tree.reserve(SIMULATIONS);
+        let reserved = search_tree.get_allocator_report();
+        assert!(reserved.capacity > SIMULATIONS);
+
+        for _ in 0..SIMULATIONS {
+            search_tree.playout(NON_ZERO_USIZE_ONE).unwrap();
+        }
+
+        let report = search_tree.get_allocator_report();
+        assert_eq!(reserved.capacity, report.capacity, "The allocator was reallocated");
+        assert!(report.nodes > 1 && report.nodes <= SIMULATIONS + 1);
+        assert_eq!(report.nodes * std::mem::size_of::<crate::Node>(), report.bytes_used);
+    }
+
+    #[test]
+    fn test_opponent_model_changes_expected_value() {
+        const SIMULATIONS: usize = 1000;
+
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let policy = UCTPolicy::default();
+        let evaluator = StaticEvaluator::new();
+
+        let mut symmetric_tree = SearchTree::new(&state, &policy, &evaluator);
+        let mut modeled_tree =
+            SearchTree::new(&state, &policy, &evaluator).with_opponent_model(Some(OpponentModel::Greedy));
+        for _ in 0..SIMULATIONS {
+            symmetric_tree.playout(NON_ZERO_USIZE_ONE).unwrap();
+            modeled_tree.playout(NON_ZERO_USIZE_ONE).unwrap();
+        }
+
+        let opponent_children = |tree: &SearchTree<'_, UCTPolicy, StaticEvaluator>| {
+            let mut children = vec![];
+            let mut stack = vec![tree.root];
+            while let Some(node_id) = stack.pop() {
+                let node = tree.allocator.get_node(node_id);
+                if node.state.is_player_1() != state.is_player_1() {
+                    children.push((node.state.clone(), node.children.clone()));
+                }
+                stack.extend(node.children.iter().copied());
+            }
+            children
+        };
+
+        // the opponent only plays the greedy action in the modeled tree
+        for (opponent_state, children) in opponent_children(&modeled_tree) {
+            assert!(children.len() <= 1);
+            for child in children {
+                let action = modeled_tree.allocator.get_node(child).action_taken.unwrap();
+                assert_eq!(OpponentModel::Greedy.get_action(&opponent_state), action);
+            }
+        }
+        assert!(opponent_children(&symmetric_tree)
+            .iter()
+            .any(|(_, children)| children.len() > 1));
+
+        let expected_value = |tree: &SearchTree<'_, UCTPolicy, StaticEvaluator>| {
+            let root = tree.allocator.get_node(tree.root);
+            root.neutral_score_sum as f64 / root.visit_count as f64
+        };
+        assert!((expected_value(&symmetric_tree) - expected_value(&modeled_tree)).abs() > f64::EPSILON);
+    }
+
+    #[test]
+    fn test_player_allocator_report() {
+        const SIMULATIONS: usize = 300;
+
+        let mut player = create_player("Allocator Player", SIMULATIONS, None);
+        assert_eq!(None, player.get_allocator_report());
+
+        player.reserve(SIMULATIONS + 1);
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        player.get_action(&state).unwrap();
+
+        let report = player.get_allocator_report().unwrap();
+        assert!(report.capacity > SIMULATIONS);
+        assert!(report.nodes <= report.capacity);
+    }
+
+    #[test]
+    fn test_max_nodes_limits_tree_size() {
+        const MAX_NODES: usize = 5;
+
+        let mut player: MCTSPlayer = MCTSPlayer::new(
+            "Node Limited Player",
+            Some(MCTSOptions {
+                max_nodes: Some(MAX_NODES),
+                ..MCTSOptions::new(
+                    NON_ZERO_USIZE_ONE,
+                    NON_ZERO_USIZE_ONE,
+                    MCTSEndCondition::Iterations(200),
+                    false,
+                    Logging::Disabled,
+                )
+            }),
+        );
+        player.reserve(1000);
+
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let action = player.get_action(&state).unwrap();
+        assert!(state.get_valid_actions().contains(&action));
+
+        let report = player.get_allocator_report().unwrap();
+        assert!(report.nodes <= MAX_NODES, "The tree grew to {} nodes", report.nodes);
+        assert!(report.capacity < 1000, "More nodes than allowed were reserved");
+    }
+
+    #[test]
+    fn test_reset_clears_tree() {
+        let mut player: MCTSPlayer = MCTSPlayer::new(
+            "Reset Player",
+            Some(MCTSOptions::new(
+                NON_ZERO_USIZE_ONE,
+                NON_ZERO_USIZE_ONE,
+                MCTSEndCondition::Iterations(100),
+                true,
+                Logging::Disabled,
+            )),
+        );
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        player.get_action(&state).unwrap();
+
+        assert!(!player.last_trees.is_empty());
+        assert!(player.get_allocator_report().is_some());
+
+        player.reset();
+
+        assert!(player.last_trees.is_empty());
+        assert_eq!(None, player.get_allocator_report());
+        assert_eq!(SearchDiagnostics::default(), player.last_diagnostics);
+    }
+
+    #[test]
+    fn test_exploration_decays_over_the_game() {
+        struct TestNode {
+            visit_count: usize,
+            wins: i32,
+        }
+
+        impl TreePolicyNode for TestNode {
+            type Player = ();
+
+            fn visit_count(&self) -> usize {
+                self.visit_count
+            }
+
+            fn current_player(&self) -> Self::Player {}
+
+            fn wins_for(&self, (): Self::Player) -> i32 {
+                self.wins
+            }
+
+            fn maximum_score_for(&self, (): Self::Player) -> f64 {
+                1.0
+            }
+
+            fn minimum_score_for(&self, (): Self::Player) -> f64 {
+                -1.0
+            }
+
+            fn score_sum_for(&self, (): Self::Player) -> f64 {
+                f64::from(self.wins)
+            }
+        }
+
+        let mut player: MCTSPlayer = MCTSPlayer::new(
+            "Decaying Player",
+            Some(MCTSOptions {
+                exploration_decay: Some(ExplorationDecay::new(2.0, 0.1)),
+                ..MCTSOptions::new(
+                    NON_ZERO_USIZE_ONE,
+                    NON_ZERO_USIZE_ONE,
+                    MCTSEndCondition::Iterations(10),
+                    false,
+                    Logging::Disabled,
+                )
+            }),
+        );
+
+        let early_state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut late_state = early_state.clone();
+        while ExplorationDecay::get_progress(&late_state) < 0.75 || late_state.get_valid_actions().len() == 1 {
+            late_state.do_action(late_state.get_valid_actions()[0], false).unwrap();
+        }
+
+        // the same statistics for both phases: an exploited child and a barely visited one
+        let parent = TestNode {
+            visit_count: 22,
+            wins: 14,
+        };
+        let children = [
+            TestNode {
+                visit_count: 20,
+                wins: 14,
+            },
+            TestNode {
+                visit_count: 2,
+                wins: 0,
+            },
+        ];
+
+        player.get_action(&early_state).unwrap();
+        let early_selection = player.policy.select_node(&parent, children.iter());
+        player.get_action(&late_state).unwrap();
+        let late_selection = player.policy.select_node(&parent, children.iter());
+
+        assert_eq!(2, early_selection.visit_count, "The opening does not explore");
+        assert_eq!(20, late_selection.visit_count, "The endgame does not exploit");
+    }
+}
@@ -1,7 +1,6 @@
 use std::fmt;
 
 use patchwork_core::{ActionId, Patchwork, TreePolicyNode};
-use rand::seq::SliceRandom;
 
 use crate::{AreaAllocator, NodeId};
 
@@ -17,7 +16,8 @@ pub struct Node {
     pub action_taken: Option<ActionId>,
     /// The children nodes.
     pub children: Vec<NodeId>,
-    /// The actions that can still be taken from this node
+    /// The actions that can still be taken from this node. The search expands
+    /// them in random order.
     pub expandable_actions: Vec<ActionId>,
     /// The maximum neutral score of all the nodes in the subtree rooted at this node.
     pub neutral_max_score: i32,
@@ -29,6 +29,12 @@ pub struct Node {
     pub neutral_wins: i32,
     // The number of times this node has been visited.
     pub visit_count: usize,
+    /// The number of selections of a batch that currently pass through this
+    /// node (see [`crate::SearchTree::node_select_batch`]). They count as
+    /// visits that were lost for the player who selected the node.
+    pub virtual_visits: usize,
+    /// The neutral wins of the virtual visits (virtual loss).
+    pub virtual_neutral_wins: i32,
 }
 
 impl Node {
@@ -44,8 +50,7 @@ impl Node {
     ///
     /// The new node.
     pub fn new(node_id: NodeId, state: Patchwork, parent: Option<NodeId>, action_taken: Option<ActionId>) -> Self {
-        let mut expandable_actions: Vec<ActionId> = state.get_valid_actions().into_iter().collect();
-        expandable_actions.shuffle(&mut rand::thread_rng());
+        let expandable_actions: Vec<ActionId> = state.get_valid_actions().into_iter().collect();
 
         Self {
             id: node_id,
@@ -57,6 +62,8 @@ impl Node {
             neutral_wins: 0,
             neutral_score_sum: 0,
             visit_count: 0,
+            virtual_visits: 0,
+            virtual_neutral_wins: 0,
             action_taken,
             expandable_actions,
         }
@@ -90,7 +97,7 @@ impl TreePolicyNode for Node {
     type Player = bool;
 
     fn visit_count(&self) -> usize {
-        self.visit_count
+        self.visit_count + self.virtual_visits
     }
 
     fn current_player(&self) -> Self::Player {
@@ -98,10 +105,11 @@ impl TreePolicyNode for Node {
     }
 
     fn wins_for(&self, player: Self::Player) -> i32 {
+        let neutral_wins = self.neutral_wins + self.virtual_neutral_wins;
         if player {
-            self.neutral_wins
+            neutral_wins
         } else {
-            -self.neutral_wins
+            -neutral_wins
         }
     }
 
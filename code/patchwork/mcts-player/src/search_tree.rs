@@ -2,9 +2,13 @@ use std::{cmp::Reverse, collections::VecDeque, num::NonZeroUsize, thread};
 
 use itertools::Itertools;
 
-use patchwork_core::{Evaluator, Notation, Patchwork, PatchworkError, TreePolicy, TreePolicyNode};
+use patchwork_core::{
+    reset_thread_affinity, ActionId, Evaluator, GameRng, Notation, Patchwork, PatchworkError, TreePolicy,
+    TreePolicyNode,
+};
+use rand::{Rng, SeedableRng};
 
-use crate::{AreaAllocator, NodeDebug, NodeId, Tree};
+use crate::{AllocatorReport, AreaAllocator, NodeDebug, NodeId, OpponentModel, Tree};
 
 /// A Search Tree for the Monte Carlo Tree Search (MCTS) algorithm.
 pub struct SearchTree<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> {
@@ -20,6 +24,11 @@ pub struct SearchTree<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> {
     depth: usize,
     /// Whether the search tree is reused.
     reused: bool,
+    /// The model of the opponent together with whether the opponent is
+    /// player 1. `None` for symmetric self-play.
+    opponent_model: Option<(OpponentModel, bool)>,
+    /// The random number generator that chooses the next action to expand.
+    rng: GameRng,
 }
 
 impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifetime, Policy, Eval> {
@@ -51,6 +60,8 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
             evaluator,
             depth: 0,
             reused: false,
+            opponent_model: None,
+            rng: GameRng::from_entropy(),
         }
     }
 
@@ -87,6 +98,8 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
             evaluator,
             depth: 0,
             reused: false,
+            opponent_model: None,
+            rng: GameRng::from_entropy(),
         }
     }
 
@@ -152,6 +165,8 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
                     depth: 0,
                     reused: true,
                     allocator: last_tree.allocator,
+                    opponent_model: None,
+                    rng: GameRng::from_entropy(),
                 };
             }
 
@@ -165,8 +180,112 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
         Self::new_with_allocator(last_tree.allocator, game, tree_policy, evaluator)
     }
 
+    /// Restricts the actions that may be chosen at the root node. Children of
+    /// a reused root node with other actions are removed from the tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `actions` - The valid actions the search is restricted to. If empty
+    ///   all valid actions may be chosen.
+    ///
+    /// # Returns
+    ///
+    /// The [`SearchTree`] with the restricted root node.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑚 · 𝑛)` where `𝑚` is the number of actions of the root node and `𝑛`
+    /// is the number of given actions.
+    #[must_use]
+    pub fn with_root_actions(mut self, actions: &[ActionId]) -> Self {
+        if actions.is_empty() {
+            return self;
+        }
+
+        let children = std::mem::take(&mut self.allocator.get_node_mut(self.root).children)
+            .into_iter()
+            .filter(|child| {
+                let child = self.allocator.get_node(*child);
+                child.action_taken.is_some_and(|action| actions.contains(&action))
+            })
+            .collect();
+
+        let root = self.allocator.get_node_mut(self.root);
+        root.children = children;
+        root.expandable_actions.retain(|action| actions.contains(action));
+        self
+    }
+
+    /// Sets the model of the opponent. The opponent is the player that is not
+    /// to move at the root node.
+    ///
+    /// Every node that is expanded afterwards and where the opponent is to
+    /// move only expands the action of the model. Thereby the selection at
+    /// these nodes always follows the modeled action.
+    ///
+    /// # Arguments
+    ///
+    /// * `opponent_model` - The model of the opponent or `None` for symmetric self-play.
+    ///
+    /// # Returns
+    ///
+    /// The [`SearchTree`] with the opponent model.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn with_opponent_model(mut self, opponent_model: Option<OpponentModel>) -> Self {
+        let opponent_is_player_1 = !self.allocator.get_node(self.root).state.is_player_1();
+        self.opponent_model = opponent_model.map(|model| (model, opponent_is_player_1));
+        self
+    }
+
+    /// Sets the maximum amount of nodes of the search tree. Once the tree has
+    /// reached this size no further nodes are expanded and the playouts
+    /// evaluate the existing leaf nodes instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_nodes` - The maximum amount of nodes or `None` for no limit.
+    ///
+    /// # Returns
+    ///
+    /// The [`SearchTree`] with the node limit.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn with_max_nodes(mut self, max_nodes: Option<usize>) -> Self {
+        self.allocator.set_max_nodes(max_nodes);
+        self
+    }
+
+    /// Sets the seed of the random number generator that chooses the next
+    /// action to expand.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the random number generator.
+    ///
+    /// # Returns
+    ///
+    /// The [`SearchTree`] with the seeded random number generator.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.rng = GameRng::seed_from_u64(seed);
+        self
+    }
+
     /// Plays out a single iteration of the MCTS algorithm. The random playouts can be done in
-    /// parallel. This is controlled by the given `leaf_parallelization`.
+    /// parallel. This is controlled by the given `leaf_parallelization`. With leaf parallelization
+    /// up to `leaf_parallelization` distinct leaves are selected (see [`SearchTree::node_select_batch`])
+    /// and the playouts are spread over them.
     ///
     /// # Arguments
     ///
@@ -177,21 +296,24 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
     /// `Ok(())` if the playout was successful, otherwise a `PatchworkError`.
     #[rustfmt::skip]
     pub fn playout(&mut self, leaf_parallelization: NonZeroUsize) -> Result<(), PatchworkError> {
-        let mut node_id = self.root;
+        if leaf_parallelization.get() == 1 {
+            let mut node_id = self.root;
 
-        // 1. Selection
-        let mut new_depth = 0;                                                               // Statistics
-        while self.should_be_selected(node_id) {
-            node_id = self.node_select(node_id);
-            new_depth += 1;
-        }
-        self.depth = self.depth.max(new_depth);                                                     // Statistics
+            // 1. Selection
+            let mut new_depth = 0;                                                           // Statistics
+            while self.should_be_selected(node_id) {
+                node_id = self.node_select(node_id);
+                new_depth += 1;
+            }
+            self.depth = self.depth.max(new_depth);                                                 // Statistics
 
-        if leaf_parallelization.get() == 1 {
             let value = if self.is_terminal(node_id) {
                 // 3. Leaf/Terminal Node → Direct Evaluation
                 let node = self.allocator.get_node(node_id);
                 self.evaluator.evaluate_terminal_node(&node.state)
+            } else if self.allocator.is_full() {
+                // 2. Node Limit Reached → Simulation of the Existing Leaf Node
+                self.node_simulate(node_id)
             } else {
                 // 2. Expansion
                 node_id = self.node_expand(node_id)?;
@@ -203,20 +325,29 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
             // 4. Backpropagation
             self.node_backpropagate(node_id, value);
         } else {
-            let values = if self.is_terminal(node_id) {
-                // 3. Leaf/Terminal Node → Direct Evaluation
-                let node = self.allocator.get_node(node_id);
-                vec![self.evaluator.evaluate_terminal_node(&node.state)]
-            } else {
-                // 2. Expansion
-                node_id = self.node_expand(node_id)?;
-
-                // 3. Simulation
-                self.node_leaf_parallelized_simulate(node_id, leaf_parallelization)
-            };
+            // 1. Selection
+            let leaves = self.node_select_batch(leaf_parallelization.get());
+            let simulations = NonZeroUsize::new(leaf_parallelization.get() / leaves.len()).unwrap();
+
+            for mut node_id in leaves {
+                let values = if self.is_terminal(node_id) {
+                    // 3. Leaf/Terminal Node → Direct Evaluation
+                    let node = self.allocator.get_node(node_id);
+                    vec![self.evaluator.evaluate_terminal_node(&node.state)]
+                } else if self.allocator.is_full() {
+                    // 2. Node Limit Reached → Simulation of the Existing Leaf Node
+                    self.node_leaf_parallelized_simulate(node_id, simulations)
+                } else {
+                    // 2. Expansion
+                    node_id = self.node_expand(node_id)?;
+
+                    // 3. Simulation
+                    self.node_leaf_parallelized_simulate(node_id, simulations)
+                };
 
-            // 4. Backpropagation
-            self.node_leaf_parallelized_backpropagate(node_id, &values);
+                // 4. Backpropagation
+                self.node_leaf_parallelized_backpropagate(node_id, &values);
+            }
         }
 
         Ok(())
@@ -252,6 +383,28 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
         self.allocator.size()
     }
 
+    /// Reserves capacity for at least `additional` more nodes in this search
+    /// tree.
+    ///
+    /// # Arguments
+    ///
+    /// * `additional` - The amount of nodes to reserve capacity for.
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.allocator.reserve(additional);
+    }
+
+    /// Gets a report about the memory usage of the allocator of this search
+    /// tree.
+    ///
+    /// # Returns
+    ///
+    /// The report about the memory usage of the allocator.
+    #[inline]
+    pub fn get_allocator_report(&self) -> AllocatorReport {
+        self.allocator.report()
+    }
+
     /// Gets the win prediction for the root node.
     ///
     /// # Returns
@@ -296,6 +449,23 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
         root.maximum_score_for(root_player) as i32
     }
 
+    /// Gets the average score of all games played from the root node from the
+    /// neutral perspective (positive values are good for player 1).
+    ///
+    /// # Returns
+    ///
+    /// The average score of all games played from the root node or `None` if
+    /// no game was played yet.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_average_score(&self) -> Option<f64> {
+        let root = self.allocator.get_node(self.root);
+        if root.visit_count == 0 {
+            return None;
+        }
+
+        Some(root.neutral_score_sum as f64 / root.visit_count as f64)
+    }
+
     /// Gets the amount of actions inside the root node
     ///
     /// # Returns
@@ -306,17 +476,17 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
         root.expandable_actions.len() + root.children.len()
     }
 
-    /// Gets the action line of the principal variation (PV) [The child nodes with the most amount
-    /// of visits] from the root node.
+    /// Gets the actions of the principal variation (PV) [The child nodes with the most amount of
+    /// visits] from the root node.
     ///
     /// # Returns
     ///
-    /// The action line of the principal variation.
+    /// The actions of the principal variation.
     ///
     /// # Complexity
     ///
     /// `𝒪(𝑛)` where `𝑛` is the number of nodes in the current search tree
-    pub fn get_pv_action_line(&self) -> String {
+    pub fn get_pv_actions(&self) -> Vec<ActionId> {
         let mut actions = vec![];
         let mut current_node = self.root;
         loop {
@@ -339,7 +509,22 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
             current_node = *next;
         }
 
-        let action_line = actions
+        actions
+    }
+
+    /// Gets the action line of the principal variation (PV) [The child nodes with the most amount
+    /// of visits] from the root node.
+    ///
+    /// # Returns
+    ///
+    /// The action line of the principal variation.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the number of nodes in the current search tree
+    pub fn get_pv_action_line(&self) -> String {
+        let action_line = self
+            .get_pv_actions()
             .iter()
             .map(|action| {
                 action
@@ -437,8 +622,95 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
         selected_child.id
     }
 
+    /// Selects up to `amount` distinct leaf nodes to explore in parallel.
+    ///
+    /// After each selection a virtual loss is applied to every node on the
+    /// path from the root to the selected leaf, so that the following
+    /// selections prefer other paths. The virtual loss of every selection is
+    /// reverted exactly once after the batch was selected. The batch ends
+    /// early if a leaf would be selected a second time.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The maximum amount of leaves to select.
+    ///
+    /// # Returns
+    ///
+    /// The distinct selected leaves in the order they were selected.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑚 · 𝑛)` where `𝑚` is the amount of leaves and `𝑛` is the depth of the
+    /// search tree as every selection traverses the tree from the root.
+    pub fn node_select_batch(&mut self, amount: usize) -> Vec<NodeId> {
+        let mut leaves = Vec::with_capacity(amount);
+
+        while leaves.len() < amount {
+            let mut node_id = self.root;
+            let mut new_depth = 0;
+            while self.should_be_selected(node_id) {
+                node_id = self.node_select(node_id);
+                new_depth += 1;
+            }
+            self.depth = self.depth.max(new_depth);
+
+            if leaves.contains(&node_id) {
+                break;
+            }
+
+            self.update_virtual_loss(node_id, true);
+            leaves.push(node_id);
+        }
+
+        for leaf in &leaves {
+            self.update_virtual_loss(*leaf, false);
+        }
+
+        leaves
+    }
+
+    /// Applies or reverts the virtual loss of one selection on the path from
+    /// the given node up to the root node. Every node on the path counts as
+    /// lost for the player to move in its parent node.
+    ///
+    /// # Arguments
+    ///
+    /// * `node_id` - The id of the selected node.
+    /// * `apply` - Whether to apply (`true`) or revert (`false`) the virtual loss.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the depth of the given node.
+    fn update_virtual_loss(&mut self, mut node_id: NodeId, apply: bool) {
+        loop {
+            let parent = self.allocator.get_node(node_id).parent;
+            let parent_is_player_1 = parent.map(|parent_id| self.allocator.get_node(parent_id).state.is_player_1());
+
+            let node = self.allocator.get_node_mut(node_id);
+            let neutral_loss = match parent_is_player_1 {
+                Some(true) => -1,
+                Some(false) => 1,
+                None => 0,
+            };
+            if apply {
+                node.virtual_visits += 1;
+                node.virtual_neutral_wins += neutral_loss;
+            } else {
+                node.virtual_visits -= 1;
+                node.virtual_neutral_wins -= neutral_loss;
+            }
+
+            match parent {
+                Some(parent_id) => node_id = parent_id,
+                None => break,
+            }
+        }
+    }
+
     /// Expands the given node by adding a child node.
-    /// The child node is chosen randomly from the expandable actions.
+    /// The child node is chosen randomly from the expandable actions. If the
+    /// opponent is to move in the child node and an opponent model is set, the
+    /// child node can only be expanded with the action of the model.
     ///
     /// # Arguments
     ///
@@ -449,13 +721,21 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
     /// The new child node.
     pub fn node_expand(&mut self, node_id: NodeId) -> Result<NodeId, PatchworkError> {
         let node = self.allocator.get_node_mut(node_id);
-        let action = node.expandable_actions.remove(0);
+        let index = self.rng.gen_range(0..node.expandable_actions.len());
+        let action = node.expandable_actions.swap_remove(index);
 
         let mut next_state = node.state.clone();
         next_state.do_action(action, false)?;
 
         let child_id = self.allocator.new_node(next_state, Some(node_id), Some(action));
 
+        if let Some((model, opponent_is_player_1)) = self.opponent_model {
+            let child = self.allocator.get_node_mut(child_id);
+            if !child.state.is_terminated() && child.state.is_player_1() == opponent_is_player_1 {
+                child.expandable_actions = vec![model.get_action(&child.state)];
+            }
+        }
+
         Ok(child_id)
     }
 
@@ -493,7 +773,12 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
 
         thread::scope(|s| {
             (0..leaf_parallelization.get())
-                .map(|_| s.spawn(|| self.evaluator.evaluate_intermediate_node(&node.state)))
+                .map(|_| {
+                    s.spawn(|| {
+                        reset_thread_affinity();
+                        self.evaluator.evaluate_intermediate_node(&node.state)
+                    })
+                })
                 .map(|handle| handle.join().unwrap())
                 .collect::<Vec<_>>()
         })
@@ -594,3 +879,41 @@ impl<'tree_lifetime, Policy: TreePolicy, Eval: Evaluator> SearchTree<'tree_lifet
         node.is_fully_expanded() && !node.is_terminal()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use evaluator::StaticEvaluator;
+    use patchwork_core::GameOptions;
+    use tree_policy::UCTPolicy;
+
+    use super::*;
+
+    #[test]
+    fn test_select_batch_returns_distinct_leaves() {
+        const BATCH: usize = 8;
+
+        let policy = UCTPolicy::default();
+        let evaluator = StaticEvaluator::default();
+        let game = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut tree = SearchTree::new(&game, &policy, &evaluator).with_seed(42);
+        for _ in 0..1000 {
+            tree.playout(NonZeroUsize::new(1).unwrap()).unwrap();
+        }
+
+        let leaves = tree.node_select_batch(BATCH);
+
+        assert_eq!(BATCH, leaves.len());
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert!(
+                !leaves[index + 1..].contains(leaf),
+                "leaf {index} was selected multiple times"
+            );
+        }
+
+        // the virtual loss of every selection was reverted exactly once
+        for node in &tree.allocator.nodes {
+            assert_eq!(0, node.virtual_visits);
+            assert_eq!(0, node.virtual_neutral_wins);
+        }
+    }
+}
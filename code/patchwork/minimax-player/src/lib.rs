@@ -1,5 +1,5 @@
 mod minimax_options;
 mod minimax_player;
 
-pub use minimax_options::MinimaxOptions;
+pub use minimax_options::{MinimaxOptions, SamplingStrategy};
 pub use minimax_player::{MinimaxPlayer, DefaultMinimaxPlayer};
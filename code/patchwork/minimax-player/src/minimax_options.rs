@@ -6,15 +6,58 @@ pub struct MinimaxOptions {
     /// The amount of actions to consider per piece.
     /// This is used to reduce the branching factor.
     pub amount_actions_per_piece: usize,
+    /// The strategy used to choose which placements of a piece are considered.
+    pub sampling_strategy: SamplingStrategy,
+    /// Whether to use iterative deepening. Every depth up to the configured
+    /// depth is searched in turn and the best action of the previous depth
+    /// is searched first. The actions at the root share one alpha-beta
+    /// window so that the good first action prunes the remaining actions.
+    pub iterative: bool,
+    /// The seed used to break ties between equally evaluated actions. `None`
+    /// breaks ties randomly so that games are not reproducible.
+    pub tie_breaking_seed: Option<u64>,
+}
+
+/// The strategy used to choose the placements of a piece that are considered
+/// by the [`MinimaxPlayer`] when only `amount_actions_per_piece` placements
+/// are searched.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SamplingStrategy {
+    /// Take the first placements in the order they are generated. Only the
+    /// taken placements are evaluated, so this is the fastest strategy.
+    #[default]
+    First,
+    /// Take the placements with the best static evaluation for the current
+    /// player.
+    TopScore,
+    /// Take the best placements while spreading them across the quadrants of
+    /// the quilt board. This avoids only considering placements that are
+    /// clustered in the same region of the board.
+    Diverse,
+    /// Take a random sample of the placements that is reproducible with the
+    /// given seed.
+    Random {
+        /// The seed of the random number generator.
+        seed: u64,
+    },
 }
 
 impl MinimaxOptions {
     /// Creates a new [`MinimaxOptions`].
     #[must_use]
-    pub const fn new(depth: usize, amount_actions_per_piece: usize) -> Self {
+    pub const fn new(
+        depth: usize,
+        amount_actions_per_piece: usize,
+        sampling_strategy: SamplingStrategy,
+        iterative: bool,
+        tie_breaking_seed: Option<u64>,
+    ) -> Self {
         Self {
             depth,
             amount_actions_per_piece,
+            sampling_strategy,
+            iterative,
+            tie_breaking_seed,
         }
     }
 }
@@ -24,6 +67,9 @@ impl Default for MinimaxOptions {
         Self {
             depth: 8,
             amount_actions_per_piece: 3,
+            sampling_strategy: SamplingStrategy::First,
+            iterative: false,
+            tie_breaking_seed: None,
         }
     }
 }
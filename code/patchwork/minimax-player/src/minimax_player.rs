@@ -1,7 +1,10 @@
+use std::collections::HashMap;
+
 use evaluator::StaticEvaluator;
-use patchwork_core::{ActionId, Evaluator, Patchwork, Player, PlayerResult};
+use patchwork_core::{ActionId, Evaluator, GameRng, Patchwork, Player, PlayerResult, QuiltBoard};
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
 
-use crate::MinimaxOptions;
+use crate::{MinimaxOptions, SamplingStrategy};
 
 /// A computer player that uses the Minimax algorithm to choose an action.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -13,6 +16,14 @@ pub struct MinimaxPlayer<Eval: Evaluator = StaticEvaluator> {
     /// The amount of actions to consider per piece.
     /// This is used to reduce the branching factor.
     pub amount_actions_per_piece: usize,
+    /// The strategy used to choose which placements of a piece are considered.
+    pub sampling_strategy: SamplingStrategy,
+    /// Whether to use iterative deepening with the best action of the
+    /// previous depth searched first.
+    pub iterative: bool,
+    /// The seed used to break ties between equally evaluated actions. `None`
+    /// breaks ties randomly.
+    pub tie_breaking_seed: Option<u64>,
     /// The evaluator to evaluate the game state.
     pub evaluator: Eval,
 }
@@ -25,12 +36,18 @@ impl<Eval: Evaluator + Default> MinimaxPlayer<Eval> {
         let MinimaxOptions {
             depth,
             amount_actions_per_piece,
+            sampling_strategy,
+            iterative,
+            tie_breaking_seed,
         } = options.unwrap_or_default();
         Self {
             name: name.into(),
             evaluator: Eval::default(),
             depth,
             amount_actions_per_piece,
+            sampling_strategy,
+            iterative,
+            tie_breaking_seed,
         }
     }
 }
@@ -53,27 +70,155 @@ impl<Eval: Evaluator> Player for MinimaxPlayer<Eval> {
             return Ok(valid_actions[0]);
         }
 
-        let maximizing_player = game.is_player_1();
+        let (action, _) = self.search(game, &valid_actions, false, &mut 0);
+        Ok(action)
+    }
 
-        let mut chosen_action = valid_actions[0];
-        let mut chosen_evaluation = if maximizing_player { i32::MIN } else { i32::MAX };
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        match actions {
+            [] => self.get_action(game),
+            [action] => Ok(*action),
+            _ => Ok(self.search(game, actions, true, &mut 0).0),
+        }
+    }
+}
+
+/// The state shared by all nodes of one search.
+#[derive(Debug, Default)]
+struct SearchContext {
+    /// The amount of visited nodes.
+    nodes: usize,
+    /// The best action of every searched state (by its structural hash) at
+    /// the previous depth of iterative deepening. `None` if the search is not
+    /// iterative.
+    best_actions: Option<HashMap<u64, ActionId>>,
+}
 
+impl<Eval: Evaluator> MinimaxPlayer<Eval> {
+    /// Searches the best action in the given state either to the fixed depth
+    /// or with iterative deepening.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The state to search the best action for.
+    /// * `valid_actions` - The valid actions of the state.
+    /// * `restrict_root` - Whether exactly the given actions are searched at
+    ///   the root. Otherwise the root actions are filtered like the actions of
+    ///   every other state.
+    /// * `nodes` - The counter of the visited nodes.
+    ///
+    /// # Returns
+    ///
+    /// The best action together with its evaluation.
+    fn search(
+        &self,
+        game: &Patchwork,
+        valid_actions: &[ActionId],
+        restrict_root: bool,
+        nodes: &mut usize,
+    ) -> (ActionId, i32) {
         let filter_actions = |game: &Patchwork, valid_actions: &Vec<ActionId>| {
-            Self::get_best_actions(game, valid_actions, self.amount_actions_per_piece, &self.evaluator)
+            Self::get_best_actions(
+                game,
+                valid_actions,
+                self.amount_actions_per_piece,
+                self.sampling_strategy,
+                self.tie_breaking_seed,
+                &self.evaluator,
+            )
+        };
+
+        let mut root_actions = if restrict_root {
+            valid_actions
+                .iter()
+                .map(|action| {
+                    let mut state = game.clone();
+                    state.do_action(*action, false).unwrap();
+                    let evaluation = self.evaluator.evaluate_node(&state);
+                    (state, *action, evaluation)
+                })
+                .collect::<Vec<_>>()
+        } else {
+            Self::get_best_actions(
+                game,
+                valid_actions,
+                self.amount_actions_per_piece,
+                self.sampling_strategy,
+                self.tie_breaking_seed,
+                &self.evaluator,
+            )
+        };
+
+        if !self.iterative {
+            let mut context = SearchContext::default();
+            let result = self.search_root(game, &root_actions, self.depth, &filter_actions, &mut context);
+            *nodes += context.nodes;
+            return result;
+        }
+
+        let mut context = SearchContext {
+            nodes: 0,
+            best_actions: Some(HashMap::new()),
         };
+        let mut result = (valid_actions[0], 0);
+        for depth in 1..=self.depth {
+            result = self.search_root(game, &root_actions, depth, &filter_actions, &mut context);
+
+            // search the best action of this depth first at the next depth
+            if let Some(index) = root_actions.iter().position(|(_, action, _)| *action == result.0) {
+                root_actions[..=index].rotate_right(1);
+            }
+        }
+        *nodes += context.nodes;
+        result
+    }
+
+    /// Searches all the given actions at the root to the given depth. The
+    /// actions share one alpha-beta window.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The state at the root.
+    /// * `root_actions` - The actions to search together with the resulting states.
+    /// * `depth` - The depth to search to.
+    /// * `filter_actions` - The function to choose the actions to search in a state.
+    /// * `context` - The state shared by all nodes of the search.
+    ///
+    /// # Returns
+    ///
+    /// The best action together with its evaluation. Ties are broken randomly.
+    fn search_root<Filter>(
+        &self,
+        game: &Patchwork,
+        root_actions: &[(Patchwork, ActionId, i32)],
+        depth: usize,
+        filter_actions: &Filter,
+        context: &mut SearchContext,
+    ) -> (ActionId, i32)
+    where
+        Filter: Fn(&Patchwork, &Vec<ActionId>) -> Vec<(Patchwork, ActionId, i32)>,
+    {
+        let maximizing_player = game.is_player_1();
+
+        let mut chosen_action = root_actions[0].1;
+        let mut chosen_evaluation = if maximizing_player { i32::MIN } else { i32::MAX };
+        let mut alpha = i32::MIN;
+        let mut beta = i32::MAX;
 
-        for (next_state, action, _) in filter_actions(game, &valid_actions) {
+        for (next_state, action, _) in root_actions {
+            let action = *action;
             let evaluation = Self::minimax(
-                &next_state,
-                self.depth - 1,
-                i32::MIN,
-                i32::MAX,
+                next_state,
+                depth - 1,
+                alpha,
+                beta,
                 &self.evaluator,
-                &filter_actions,
+                filter_actions,
+                context,
             );
 
-            // break ties randomly
-            if evaluation == chosen_evaluation && rand::random() {
+            if evaluation == chosen_evaluation && Self::break_tie(self.tie_breaking_seed, action, chosen_action).is_lt()
+            {
                 chosen_action = action;
             } else {
                 #[allow(clippy::collapsible_else_if)]
@@ -89,13 +234,18 @@ impl<Eval: Evaluator> Player for MinimaxPlayer<Eval> {
                     }
                 }
             }
+
+            // the cutoffs are strict so actions as good as the best one are still evaluated exactly
+            if maximizing_player {
+                alpha = alpha.max(chosen_evaluation);
+            } else {
+                beta = beta.min(chosen_evaluation);
+            }
         }
 
-        Ok(chosen_action)
+        (chosen_action, chosen_evaluation)
     }
-}
 
-impl<Eval: Evaluator> MinimaxPlayer<Eval> {
     fn minimax<Filter>(
         game: &Patchwork,
         depth: usize,
@@ -103,10 +253,12 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
         beta: i32,
         evaluator: &impl Evaluator,
         filter_actions: &Filter, // TODO: generic filtering
+        context: &mut SearchContext,
     ) -> i32
     where
         Filter: Fn(&Patchwork, &Vec<ActionId>) -> Vec<(Patchwork, ActionId, i32)>,
     {
+        context.nodes += 1;
         if depth == 0 || game.is_terminated() {
             return evaluator.evaluate_node(game);
         }
@@ -116,11 +268,24 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
 
         let maximizing_player = game.is_player_1();
         let valid_actions = game.get_valid_actions();
+        let mut next_states = filter_actions(game, &valid_actions);
 
-        if maximizing_player {
+        // search the best action of the previous depth first
+        let hash = context.best_actions.as_ref().map(|_| game.structural_hash());
+        if let Some(best_action) = hash.and_then(|hash| context.best_actions.as_ref()?.get(&hash)) {
+            if let Some(index) = next_states.iter().position(|(_, action, _)| action == best_action) {
+                next_states[..=index].rotate_right(1);
+            }
+        }
+
+        let mut best_action = None;
+        let value = if maximizing_player {
             let mut value = i32::MIN;
-            for (next_state, _, _) in filter_actions(game, &valid_actions) {
-                let evaluation = Self::minimax(&next_state, depth - 1, alpha, beta, evaluator, filter_actions);
+            for (next_state, action, _) in next_states {
+                let evaluation = Self::minimax(&next_state, depth - 1, alpha, beta, evaluator, filter_actions, context);
+                if evaluation > value || best_action.is_none() {
+                    best_action = Some(action);
+                }
                 value = value.max(evaluation);
                 if value > beta {
                     break;
@@ -130,8 +295,11 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
             value
         } else {
             let mut value = i32::MAX;
-            for (next_state, _, _) in filter_actions(game, &valid_actions) {
-                let evaluation = Self::minimax(&next_state, depth - 1, alpha, beta, evaluator, filter_actions);
+            for (next_state, action, _) in next_states {
+                let evaluation = Self::minimax(&next_state, depth - 1, alpha, beta, evaluator, filter_actions, context);
+                if evaluation < value || best_action.is_none() {
+                    best_action = Some(action);
+                }
                 value = value.min(evaluation);
                 if value < alpha {
                     break;
@@ -139,15 +307,24 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
                 beta = beta.min(value);
             }
             value
+        };
+
+        if let (Some(hash), Some(best_action), Some(best_actions)) = (hash, best_action, &mut context.best_actions) {
+            best_actions.insert(hash, best_action);
         }
+        value
     }
 
     fn get_best_actions(
         game: &Patchwork,
         valid_actions: &[ActionId],
         amount_actions_per_piece: usize,
+        sampling_strategy: SamplingStrategy,
+        tie_breaking_seed: Option<u64>,
         evaluator: &impl Evaluator,
     ) -> Vec<(Patchwork, ActionId, i32)> {
+        let maximizing_player = game.is_player_1();
+
         let place_first_piece_tuple = valid_actions
             .iter()
             .filter(|a| a.is_first_patch_taken() || a.is_special_patch_placement())
@@ -156,24 +333,18 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
                 state.do_action(*action, false).unwrap();
                 let evaluation = evaluator.evaluate_node(&state);
                 (state, *action, evaluation)
-            })
-            .take(amount_actions_per_piece)
-            .collect::<Vec<_>>();
+            });
+        let place_first_piece_tuple = Self::sample_placements(
+            place_first_piece_tuple,
+            amount_actions_per_piece,
+            sampling_strategy,
+            maximizing_player,
+        );
 
         if place_first_piece_tuple.first().is_some_and(|(_, a, _)| a.is_special_patch_placement()) {
             let mut place_first_piece_tuple = place_first_piece_tuple;
-            place_first_piece_tuple.sort_by(|(_, _, e1), (_, _, e2)| {
-                match e2.cmp(e1) {
-                    // break ties randomly
-                    std::cmp::Ordering::Equal => {
-                        if rand::random() {
-                            std::cmp::Ordering::Greater
-                        } else {
-                            std::cmp::Ordering::Less
-                        }
-                    }
-                    ordering => ordering,
-                }
+            place_first_piece_tuple.sort_by(|(_, a1, e1), (_, a2, e2)| {
+                e2.cmp(e1).then_with(|| Self::break_tie(tie_breaking_seed, *a1, *a2))
             });
 
             // special patch placement move
@@ -199,9 +370,13 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
                 state.do_action(*action, false).unwrap();
                 let evaluation = evaluator.evaluate_node(&state);
                 (state, *action, evaluation)
-            })
-            .take(amount_actions_per_piece)
-            .collect::<Vec<_>>();
+            });
+        let place_second_piece_tuple = Self::sample_placements(
+            place_second_piece_tuple,
+            amount_actions_per_piece,
+            sampling_strategy,
+            maximizing_player,
+        );
         let place_third_piece_tuple = valid_actions
             .iter()
             .filter(|a| a.is_third_patch_taken())
@@ -210,9 +385,13 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
                 state.do_action(*action, false).unwrap();
                 let evaluation = evaluator.evaluate_node(&state);
                 (state, *action, evaluation)
-            })
-            .take(amount_actions_per_piece)
-            .collect::<Vec<_>>();
+            });
+        let place_third_piece_tuple = Self::sample_placements(
+            place_third_piece_tuple,
+            amount_actions_per_piece,
+            sampling_strategy,
+            maximizing_player,
+        );
 
         let place_first_piece_len = (amount_actions_per_piece * 3).min(place_first_piece_tuple.len());
         let place_second_piece_len = (amount_actions_per_piece * 3).min(place_second_piece_tuple.len());
@@ -225,17 +404,256 @@ impl<Eval: Evaluator> MinimaxPlayer<Eval> {
         result.extend(place_second_piece_tuple);
         result.extend(place_third_piece_tuple);
 
-        result.sort_by(|(_, _, e1), (_, _, e2)| match e2.cmp(e1) {
-            // break ties randomly
-            std::cmp::Ordering::Equal => {
-                if rand::random() {
-                    std::cmp::Ordering::Greater
-                } else {
-                    std::cmp::Ordering::Less
+        result.sort_by(|(_, a1, e1), (_, a2, e2)| {
+            e2.cmp(e1).then_with(|| Self::break_tie(tie_breaking_seed, *a1, *a2))
+        });
+        result
+    }
+
+    /// Orders two equally evaluated actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `tie_breaking_seed` - The seed to break the tie with. `None` breaks the tie randomly.
+    /// * `action` - The first action.
+    /// * `other` - The second action.
+    ///
+    /// # Returns
+    ///
+    /// [`Less`](std::cmp::Ordering::Less) if the first action is preferred, otherwise
+    /// [`Greater`](std::cmp::Ordering::Greater).
+    fn break_tie(tie_breaking_seed: Option<u64>, action: ActionId, other: ActionId) -> std::cmp::Ordering {
+        if GameRng::wins_tie(tie_breaking_seed, action, other) {
+            std::cmp::Ordering::Less
+        } else {
+            std::cmp::Ordering::Greater
+        }
+    }
+
+    /// Chooses at most `amount` placements of a single piece according to the
+    /// given sampling strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `placements` - All placements of the piece together with the resulting state and their evaluation.
+    ///   The placements are only evaluated if the sampling strategy needs all of them.
+    /// * `amount` - The maximum amount of placements to choose.
+    /// * `sampling_strategy` - The strategy used to choose the placements.
+    /// * `maximizing_player` - Whether the current player is the maximizing player (player 1).
+    ///
+    /// # Returns
+    ///
+    /// The chosen placements.
+    fn sample_placements(
+        placements: impl Iterator<Item = (Patchwork, ActionId, i32)>,
+        amount: usize,
+        sampling_strategy: SamplingStrategy,
+        maximizing_player: bool,
+    ) -> Vec<(Patchwork, ActionId, i32)> {
+        if sampling_strategy == SamplingStrategy::First {
+            return placements.take(amount).collect();
+        }
+
+        let mut placements = placements.collect::<Vec<_>>();
+        if placements.len() <= amount {
+            return placements;
+        }
+
+        if let SamplingStrategy::Random { seed } = sampling_strategy {
+            let mut rng = StdRng::seed_from_u64(seed);
+            placements.shuffle(&mut rng);
+            placements.truncate(amount);
+            return placements;
+        }
+
+        // best placements for the current player first, stable to keep the generation order for equal evaluations
+        if maximizing_player {
+            placements.sort_by(|(_, _, e1), (_, _, e2)| e2.cmp(e1));
+        } else {
+            placements.sort_by_key(|(_, _, evaluation)| *evaluation);
+        }
+
+        if sampling_strategy == SamplingStrategy::TopScore {
+            placements.truncate(amount);
+            return placements;
+        }
+
+        // diverse: take the best remaining placement of each quadrant in turn
+        let mut quadrants: [Vec<(Patchwork, ActionId, i32)>; 4] = Default::default();
+        for placement in placements.into_iter().rev() {
+            quadrants[Self::get_quadrant(placement.1)].push(placement);
+        }
+
+        let mut result = Vec::with_capacity(amount);
+        while result.len() < amount {
+            for quadrant in &mut quadrants {
+                if result.len() == amount {
+                    break;
+                }
+                if let Some(placement) = quadrant.pop() {
+                    result.push(placement);
                 }
             }
-            ordering => ordering,
-        });
+        }
         result
     }
+
+    /// Returns the quadrant of the quilt board (0 - 3) the given placement
+    /// starts in.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The patch placement or special patch placement action.
+    ///
+    /// # Returns
+    ///
+    /// The index of the quadrant: top left, top right, bottom left, bottom right.
+    fn get_quadrant(action: ActionId) -> usize {
+        let bottom = action.get_row() >= QuiltBoard::ROWS / 2;
+        let right = action.get_column() >= QuiltBoard::COLUMNS / 2;
+        usize::from(bottom) * 2 + usize::from(right)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use patchwork_core::{GameOptions, PositionCorpus};
+
+    use super::*;
+
+    const SEED: u64 = 42;
+
+    /// Returns the placements of the available piece with the most placements.
+    fn get_piece_placements(game: &Patchwork) -> Vec<(Patchwork, ActionId, i32)> {
+        let evaluator = StaticEvaluator::new();
+        let valid_actions = game.get_valid_actions();
+        let filters: [fn(&ActionId) -> bool; 3] = [
+            ActionId::is_first_patch_taken,
+            ActionId::is_second_patch_taken,
+            ActionId::is_third_patch_taken,
+        ];
+
+        filters
+            .iter()
+            .map(|filter| {
+                valid_actions
+                    .iter()
+                    .copied()
+                    .filter(filter)
+                    .map(|action| {
+                        let mut state = game.clone();
+                        state.do_action(action, false).unwrap();
+                        let evaluation = evaluator.evaluate_node(&state);
+                        (state, action, evaluation)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .max_by_key(Vec::len)
+            .unwrap()
+    }
+
+    fn get_quadrants(placements: &[(Patchwork, ActionId, i32)]) -> HashSet<usize> {
+        placements
+            .iter()
+            .map(|(_, action, _)| DefaultMinimaxPlayer::get_quadrant(*action))
+            .collect()
+    }
+
+    #[test]
+    fn test_diverse_sampling_covers_distinct_quadrants() {
+        let game = Patchwork::get_initial_state(Some(GameOptions::new(SEED)));
+        let placements = get_piece_placements(&game);
+
+        let top_score = DefaultMinimaxPlayer::sample_placements(
+            placements.clone().into_iter(),
+            4,
+            SamplingStrategy::TopScore,
+            true,
+        );
+        let diverse = DefaultMinimaxPlayer::sample_placements(placements.into_iter(), 4, SamplingStrategy::Diverse, true);
+
+        assert_eq!(top_score.len(), 4);
+        assert_eq!(diverse.len(), 4);
+        assert_eq!(get_quadrants(&diverse).len(), 4);
+        assert!(get_quadrants(&top_score).len() < get_quadrants(&diverse).len());
+    }
+
+    #[test]
+    fn test_first_sampling_only_evaluates_taken_placements() {
+        let game = Patchwork::get_initial_state(Some(GameOptions::new(SEED)));
+        let placements = get_piece_placements(&game);
+        assert!(placements.len() > 3);
+
+        let mut taken_placements = 0;
+        let first = DefaultMinimaxPlayer::sample_placements(
+            placements.iter().cloned().inspect(|_| taken_placements += 1),
+            3,
+            SamplingStrategy::First,
+            true,
+        );
+
+        assert_eq!(3, taken_placements);
+        assert_eq!(
+            placements[..3].iter().map(|(_, action, _)| *action).collect::<Vec<_>>(),
+            first.iter().map(|(_, action, _)| *action).collect::<Vec<_>>()
+        );
+        assert_eq!(SamplingStrategy::First, MinimaxOptions::default().sampling_strategy);
+    }
+
+    #[test]
+    fn test_random_sampling_is_reproducible() {
+        let game = Patchwork::get_initial_state(Some(GameOptions::new(SEED)));
+        let placements = get_piece_placements(&game);
+        let strategy = SamplingStrategy::Random { seed: 42 };
+
+        let first = DefaultMinimaxPlayer::sample_placements(placements.clone().into_iter(), 3, strategy, true);
+        let second = DefaultMinimaxPlayer::sample_placements(placements.into_iter(), 3, strategy, true);
+
+        assert_eq!(
+            first.iter().map(|(_, action, _)| *action).collect::<Vec<_>>(),
+            second.iter().map(|(_, action, _)| *action).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_iterative_deepening_visits_fewer_nodes() {
+        const DEPTH: usize = 5;
+
+        // the tie breaking is seeded so that every search orders the actions the same way
+        let options =
+            |depth, iterative| MinimaxOptions::new(depth, 2, SamplingStrategy::TopScore, iterative, Some(SEED));
+        let fixed = DefaultMinimaxPlayer::new("Fixed", Some(options(DEPTH, false)));
+        let iterative = DefaultMinimaxPlayer::new("Iterative", Some(options(DEPTH, true)));
+        let shallower_iterative = DefaultMinimaxPlayer::new("Shallower Iterative", Some(options(DEPTH - 1, true)));
+
+        let mut fixed_nodes = 0;
+        let mut iterative_nodes = 0;
+        let mut shallower_iterative_nodes = 0;
+        for game in PositionCorpus::generate(16, SEED) {
+            let valid_actions = game.get_valid_actions();
+            if game.is_terminated() || valid_actions.len() == 1 {
+                continue;
+            }
+
+            let (_, fixed_evaluation) = fixed.search(&game, &valid_actions, false, &mut fixed_nodes);
+            let (action, iterative_evaluation) = iterative.search(&game, &valid_actions, false, &mut iterative_nodes);
+            shallower_iterative.search(&game, &valid_actions, false, &mut shallower_iterative_nodes);
+
+            // ties are broken randomly so an action with the same evaluation is the same choice
+            assert_eq!(
+                fixed_evaluation, iterative_evaluation,
+                "Different evaluation for {game}"
+            );
+            assert!(valid_actions.contains(&action));
+        }
+
+        // both searches use the same root window, so the last iteration only saves nodes by the move ordering
+        let last_iteration_nodes = iterative_nodes - shallower_iterative_nodes;
+        assert!(
+            last_iteration_nodes < fixed_nodes,
+            "The last iteration visited {last_iteration_nodes} nodes, the fixed depth search {fixed_nodes} nodes"
+        );
+    }
 }
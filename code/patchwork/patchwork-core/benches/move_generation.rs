@@ -0,0 +1,174 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Instant,
+};
+
+use criterion::{black_box, criterion_group, Criterion, Throughput};
+use patchwork_core::{ActionId, Patchwork, PlacementCache, PositionCorpus};
+
+/// The global allocator that counts the allocations so that the strategies
+/// can be compared by the amount of allocations they need.
+struct CountingAllocator;
+
+/// The amount of allocations (including reallocations) since the start.
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+/// A way to generate the valid actions of every position of the corpus.
+/// Every strategy passes the actions of every position to the given function
+/// and returns the amount of generated actions.
+type MoveGenerationStrategy = fn(&[Patchwork], &mut dyn FnMut(&[ActionId])) -> usize;
+
+/// All strategies that are compared. All of them only generate the
+/// placements of the patches the current player can afford.
+const STRATEGIES: [(&str, MoveGenerationStrategy); 3] = [
+    ("full", generate_full),
+    ("reused-buffer", generate_with_reused_buffer),
+    ("cached", generate_cached),
+];
+
+/// Allocates a new list of actions for every position.
+fn generate_full(positions: &[Patchwork], on_actions: &mut dyn FnMut(&[ActionId])) -> usize {
+    positions
+        .iter()
+        .map(|position| {
+            let actions = position.get_valid_actions();
+            on_actions(&actions);
+            actions.len()
+        })
+        .sum()
+}
+
+/// Reuses a single list of actions for all positions.
+fn generate_with_reused_buffer(positions: &[Patchwork], on_actions: &mut dyn FnMut(&[ActionId])) -> usize {
+    let mut actions: Vec<ActionId> = Vec::new();
+    positions
+        .iter()
+        .map(|position| {
+            position.get_valid_actions_into(&mut actions);
+            on_actions(&actions);
+            actions.len()
+        })
+        .sum()
+}
+
+/// Reuses a single list of actions and looks up the placements of the
+/// patches in a cache that is shared by all positions.
+fn generate_cached(positions: &[Patchwork], on_actions: &mut dyn FnMut(&[ActionId])) -> usize {
+    let mut actions: Vec<ActionId> = Vec::new();
+    let mut placement_cache = PlacementCache::new(PlacementCache::DEFAULT_CAPACITY);
+    positions
+        .iter()
+        .map(|position| {
+            position.get_valid_actions_into_cached(&mut actions, &mut placement_cache);
+            on_actions(&actions);
+            actions.len()
+        })
+        .sum()
+}
+
+/// Passes the actions to [`black_box`] so that they are not optimized away.
+fn consume(actions: &[ActionId]) {
+    black_box(actions);
+}
+
+/// Gets the positions of the standard corpus the strategies are compared on.
+fn get_positions() -> Vec<Patchwork> {
+    PositionCorpus::standard()
+        .into_iter()
+        .filter(|position| !position.is_terminated())
+        .collect()
+}
+
+/// Checks that every strategy generates the same actions for every position
+/// of the corpus. The order of the actions may differ between the strategies.
+///
+/// # Panics
+///
+/// If a strategy generates different actions than the first strategy.
+fn check_same_actions(positions: &[Patchwork]) {
+    let mut expected_actions = None;
+    for (name, strategy) in STRATEGIES {
+        let mut generated_actions = vec![];
+        strategy(positions, &mut |actions| {
+            let mut actions = actions.to_vec();
+            actions.sort_unstable_by_key(ActionId::as_bits);
+            generated_actions.push(actions);
+        });
+
+        let expected_actions = expected_actions.get_or_insert_with(|| generated_actions.clone());
+        for ((position, expected), generated) in positions.iter().zip(expected_actions.iter()).zip(&generated_actions) {
+            assert_eq!(
+                expected, generated,
+                "The strategy {name} generated different actions for the position {position:?}"
+            );
+        }
+    }
+}
+
+/// Runs every strategy once over the corpus and prints the throughput and
+/// the amount of allocations of every strategy.
+fn report_allocations(positions: &[Patchwork]) {
+    println!("strategy;actions;actions per second;allocations;allocations per position");
+
+    for (name, strategy) in STRATEGIES {
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+        let start = Instant::now();
+        let actions = strategy(positions, &mut consume);
+        let elapsed = start.elapsed();
+        let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations;
+
+        println!(
+            "{name};{actions};{:.0};{allocations};{:.2}",
+            actions as f64 / elapsed.as_secs_f64().max(f64::MIN_POSITIVE),
+            allocations as f64 / positions.len() as f64
+        );
+    }
+}
+
+fn game_move_generation(c: &mut Criterion) {
+    let positions = get_positions();
+    let actions = generate_full(&positions, &mut consume);
+
+    let mut group = c.benchmark_group("game.move_generation");
+    group.throughput(Throughput::Elements(actions as u64));
+    for (name, strategy) in STRATEGIES {
+        group.bench_function(name, |b| b.iter(|| strategy(black_box(&positions), &mut consume)));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, game_move_generation);
+
+fn main() {
+    let positions = get_positions();
+    check_same_actions(&positions);
+    report_allocations(&positions);
+
+    // `cargo test` runs this target without `--bench` (see the `test` flag of
+    // the target), in that case the report over the corpus is enough and the
+    // arguments of the test harness are not passed on to criterion
+    if std::env::args().any(|argument| argument == "--bench") {
+        benches();
+        Criterion::default().configure_from_args().final_summary();
+    }
+}
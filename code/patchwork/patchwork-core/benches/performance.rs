@@ -11,7 +11,7 @@ fn game_get_initial_state(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                Some(GameOptions { seed })
+                Some(GameOptions::new(seed))
             },
             |args| black_box(Patchwork::get_initial_state(args)),
         );
@@ -24,7 +24,7 @@ fn game_get_valid_actions(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                Patchwork::get_initial_state(Some(GameOptions { seed }))
+                Patchwork::get_initial_state(Some(GameOptions::new(seed)))
             },
             |game| black_box(game.get_valid_actions()),
         );
@@ -37,7 +37,7 @@ fn game_get_random_action(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                Patchwork::get_initial_state(Some(GameOptions { seed }))
+                Patchwork::get_initial_state(Some(GameOptions::new(seed)))
             },
             |game| black_box(game.get_random_action()),
         );
@@ -50,7 +50,7 @@ fn game_do_action(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                let mut game = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let mut game = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
                 for _ in 0..(seed % 25) {
                     game.do_action(game.get_random_action(), false).unwrap();
                 }
@@ -62,13 +62,42 @@ fn game_do_action(c: &mut Criterion) {
     });
 }
 
+fn game_do_action_validated(c: &mut Criterion) {
+    let mut group = c.benchmark_group("game.do_action_validated");
+    for validated in [false, true] {
+        let name = if validated { "validated" } else { "unvalidated" };
+        group.bench_function(name, |b| {
+            let mut random = Xoshiro256PlusPlus::seed_from_u64(42);
+            b.iter_with_setup(
+                || {
+                    let seed = random.next_u64();
+                    let mut game = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+                    for _ in 0..(seed % 25) {
+                        game.do_action(game.get_random_action(), false).unwrap();
+                    }
+                    let action = game.get_random_action();
+                    (game, action)
+                },
+                |(mut game, action)| {
+                    if validated {
+                        black_box(game.do_action_validated(action, false))
+                    } else {
+                        black_box(game.do_action(action, false))
+                    }
+                },
+            );
+        });
+    }
+    group.finish();
+}
+
 fn game_undo_action(c: &mut Criterion) {
     c.bench_function("game.undo_action", |b| {
         let mut random = Xoshiro256PlusPlus::seed_from_u64(42);
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                let mut game = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let mut game = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
                 for _ in 0..(seed % 25) {
                     game.do_action(game.get_random_action(), false).unwrap();
                 }
@@ -88,7 +117,7 @@ fn game_clone(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                let mut game = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let mut game = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
                 for _ in 0..(seed % 25) {
                     game.do_action(game.get_random_action(), false).unwrap();
                 }
@@ -105,7 +134,7 @@ fn game_is_terminated(c: &mut Criterion) {
         b.iter_with_setup(
             || {
                 let seed = random.next_u64();
-                let mut game = Patchwork::get_initial_state(Some(GameOptions { seed }));
+                let mut game = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
                 for _ in 0..(seed % 25) {
                     game.do_action(game.get_random_action(), false).unwrap();
                 }
@@ -272,6 +301,7 @@ criterion_group!(
     game_get_valid_actions,
     game_get_random_action,
     game_do_action,
+    game_do_action_validated,
     game_undo_action,
     game_clone,
     game_is_terminated,
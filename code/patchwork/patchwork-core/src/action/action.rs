@@ -4,6 +4,7 @@ use crate::{ActionId, NaturalActionId, PatchManager, PatchTransformation, QuiltB
 
 /// Represents an action that can be taken in the patchwork board game.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
 pub enum Action {
     /// The player is walking.
     Walking { starting_index: u8 },
@@ -1,6 +1,6 @@
 use std::fmt::Display;
 
-use crate::{Action, NaturalActionId, PatchManager, PatchTransformation, QuiltBoard};
+use crate::{Action, ActionKind, NaturalActionId, PatchManager, PatchTransformation, QuiltBoard};
 
 /// The action id is a surrogate unique number that is used to identify an action.
 /// It is used to represent an action in a more compact way than the action itself.
@@ -22,7 +22,11 @@ use crate::{Action, NaturalActionId, PatchManager, PatchTransformation, QuiltBoa
 ///    - Containing a flag if the previous player was player 1.
 /// - \[88838, 88838]: Phantom action.
 /// - \[88839, 88839]: Null action.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+///
+/// For human-readable formats (e.g. JSON) the action id is (de)serialized in
+/// the structured form of an [`Action`]. Binary formats use the compact
+/// surrogate id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ActionId(u32);
 
 impl ActionId {
@@ -425,6 +429,31 @@ impl ActionId {
         self.0 == Self::NULL_ACTION_ID
     }
 
+    /// Gets the kind of the action.
+    ///
+    /// # Returns
+    ///
+    /// The kind of the action.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub const fn get_kind(&self) -> ActionKind {
+        if self.is_walking() {
+            ActionKind::Walking
+        } else if self.is_patch_placement() {
+            ActionKind::PatchPlacement
+        } else if self.is_special_patch_placement() {
+            ActionKind::SpecialPatchPlacement
+        } else if self.is_phantom() {
+            ActionKind::Phantom
+        } else {
+            ActionKind::Null
+        }
+    }
+
     /// Whether this action took the first patch.
     ///
     /// # Returns
@@ -785,6 +814,61 @@ impl From<NaturalActionId> for ActionId {
     }
 }
 
+impl serde::Serialize for ActionId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        if serializer.is_human_readable() {
+            Action::from_surrogate_action_id(*self).serialize(serializer)
+        } else {
+            serializer.serialize_u32(self.0)
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ActionId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        if !deserializer.is_human_readable() {
+            let bits = u32::deserialize(deserializer)?;
+            if !Self::is_valid_action_id(bits) {
+                return Err(serde::de::Error::custom(format!(
+                    "[ActionId::deserialize] Invalid action id {bits}!"
+                )));
+            }
+            return Ok(Self(bits));
+        }
+
+        let action = Action::deserialize(deserializer)?;
+        let is_valid = match action {
+            Action::Walking { starting_index } => u32::from(starting_index) <= Self::WALKING_ACTION_ID_END,
+            Action::SpecialPatchPlacement { quilt_board_index } => quilt_board_index < QuiltBoard::TILES,
+            Action::PatchPlacement {
+                patch_id,
+                patch_index,
+                patch_transformation_index,
+                ..
+            } => {
+                patch_id < PatchManager::AMOUNT_OF_NORMAL_PATCHES
+                    && u32::from(patch_index) < PatchManager::MAX_AMOUNT_OF_CHOOSABLE_TILES
+                    && usize::from(patch_transformation_index) < PatchManager::get_transformations(patch_id).len()
+            }
+            Action::Phantom | Action::Null => true,
+        };
+
+        if !is_valid {
+            return Err(serde::de::Error::custom(format!(
+                "[ActionId::deserialize] Invalid action {action:?}!"
+            )));
+        }
+
+        Ok(Self::from_action(&action))
+    }
+}
+
 impl Display for ActionId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         self.to_action().fmt(f)
@@ -820,6 +904,59 @@ mod tests {
 
     // ─────────────────────────────────────────────── TO ACTION AND BACK ───────────────────────────────────────────────
 
+    #[test]
+    pub fn serde_roundtrip_structured() {
+        let actions = [
+            ActionId::walking(13),
+            ActionId::special_patch_placement(80),
+            ActionId::patch_placement(32, 2, 7, true),
+            ActionId::patch_placement(0, 0, 0, false),
+            ActionId::phantom(),
+            ActionId::null(),
+        ];
+
+        for action in actions {
+            let json = serde_json::to_string(&action).unwrap();
+            let deserialized: ActionId = serde_json::from_str(&json).unwrap();
+            assert_eq!(action, deserialized);
+        }
+
+        assert_eq!(
+            r#"{"Walking":{"starting_index":13}}"#,
+            serde_json::to_string(&ActionId::walking(13)).unwrap()
+        );
+    }
+
+    #[test]
+    pub fn serde_roundtrip_binary() {
+        let action = ActionId::patch_placement(32, 2, 7, true);
+        let encoded = bincode::serialize(&action).unwrap();
+        let decoded: ActionId = bincode::deserialize(&encoded).unwrap();
+        assert_eq!(action, decoded);
+
+        let invalid = bincode::serialize(&(ActionId::NULL_ACTION_ID + 1)).unwrap();
+        assert!(bincode::deserialize::<ActionId>(&invalid).is_err());
+    }
+
+    #[test]
+    pub fn serde_rejects_invalid_actions() {
+        let invalid = [
+            r#"{"Walking":{"starting_index":53}}"#,
+            r#"{"SpecialPatchPlacement":{"quilt_board_index":81}}"#,
+            r#"{"PatchPlacement":{"patch_id":33,"patch_index":0,"patch_transformation_index":0,"previous_player_was_1":true}}"#,
+            r#"{"PatchPlacement":{"patch_id":0,"patch_index":3,"patch_transformation_index":0,"previous_player_was_1":true}}"#,
+            r#"{"PatchPlacement":{"patch_id":0,"patch_index":0,"patch_transformation_index":448,"previous_player_was_1":true}}"#,
+            r#"{"Walking":{"starting_index":0,"unknown":1}}"#,
+            r#"{"Teleport":{"starting_index":0}}"#,
+            r#""Walking""#,
+            "42",
+        ];
+
+        for json in invalid {
+            assert!(serde_json::from_str::<ActionId>(json).is_err(), "{json} was accepted");
+        }
+    }
+
     #[test]
     pub fn convert_to_action_and_back_walking() {
         let action = Action::Walking { starting_index: 13 };
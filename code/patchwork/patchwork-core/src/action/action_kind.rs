@@ -0,0 +1,17 @@
+/// The kind of an action without any of its parameters.
+///
+/// This is useful to reason about the flow of the game (see
+/// [`crate::TurnType::next_after`]) without having to know the exact action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum ActionKind {
+    /// The player is walking.
+    Walking,
+    /// The player is placing a patch.
+    PatchPlacement,
+    /// The player is placing a special patch.
+    SpecialPatchPlacement,
+    /// The player is doing nothing (Phantom Move).
+    Phantom,
+    /// The player is doing nothing (Null Move).
+    Null,
+}
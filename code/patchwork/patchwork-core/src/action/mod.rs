@@ -1,7 +1,9 @@
 mod action;
 mod action_id;
+mod action_kind;
 mod natural_action_id;
 
 pub use action::*;
 pub use action_id::*;
+pub use action_kind::*;
 pub use natural_action_id::*;
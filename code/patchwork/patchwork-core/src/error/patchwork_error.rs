@@ -16,4 +16,6 @@ pub enum PatchworkError {
     InvalidNotationError { notation: String, reason: &'static str },
     #[error("[PatchworkError::InvalidRangeError] The given range is invalid, reason: {reason}")]
     InvalidRangeError { reason: &'static str },
+    #[error("[PatchworkError::InvalidStateError] The state of the game violates an invariant, reason: {reason}, state: {state:?}")]
+    InvalidStateError { reason: &'static str, state: Box<Patchwork> },
 }
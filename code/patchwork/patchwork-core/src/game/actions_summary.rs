@@ -0,0 +1,42 @@
+/// The effect of the walking action ("Advance and Receive Buttons") for the
+/// current player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WalkingSummary {
+    /// The amount of buttons the current player gains by walking. This
+    /// includes the button income if a button income trigger is passed.
+    pub buttons_gained: i32,
+    /// The amount of time board tiles the current player advances.
+    pub time_advanced: u8,
+}
+
+/// The options of the current player for a single patch that they can afford
+/// and that fits onto their quilt board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatchOptionSummary {
+    /// The index of the patch in the patches available to the current player.
+    pub patch_index: u8,
+    /// The id of the patch.
+    pub patch_id: u8,
+    /// The amount of buttons the patch costs.
+    pub button_cost: u8,
+    /// The amount of time the patch costs.
+    pub time_cost: u8,
+    /// The amount of different placements of the patch on the quilt board.
+    pub placements: usize,
+    /// The highest amount of filled tiles on the quilt board after placing
+    /// the patch.
+    pub best_resulting_tiles: u32,
+}
+
+/// A structured summary of the decision the current player has to make.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ActionsSummary {
+    /// The effect of walking or `None` if walking is not allowed in the
+    /// current turn.
+    pub walking: Option<WalkingSummary>,
+    /// The options for every patch that can be taken in the current turn.
+    pub patches: Vec<PatchOptionSummary>,
+    /// The amount of placements of the special patch or `0` if no special
+    /// patch has to be placed in the current turn.
+    pub special_patch_placements: usize,
+}
@@ -0,0 +1,152 @@
+use std::io::Write;
+
+use anyhow::Result;
+
+use crate::{ActionId, GameObserver, Notation, Patchwork};
+
+/// A [`GameObserver`] that writes every move as a row of comma-separated values.
+///
+/// The following columns are written:
+///
+/// * `game` - The index of the game (starting at `0`), incremented after every finished game.
+/// * `ply` - The index of the move inside the game (starting at `0`).
+/// * `player` - The player that took the action (`1` or `2`).
+/// * `action` - The notation of the action that was taken.
+/// * `evaluation` - The evaluation reported by the engine. Empty if not available.
+/// * `principal_variation` - The principal variation as space-separated action notations.
+pub struct CsvGameObserver<W: Write> {
+    /// The writer the rows are written to.
+    writer: W,
+    /// The index of the current game.
+    game: usize,
+    /// The index of the next move in the current game.
+    ply: usize,
+}
+
+impl<W: Write> CsvGameObserver<W> {
+    /// The header row of the written values.
+    pub const HEADER: &'static str = "game,ply,player,action,evaluation,principal_variation";
+
+    /// Creates a new [`CsvGameObserver`] and writes the header row.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer the rows are written to.
+    ///
+    /// # Returns
+    ///
+    /// The new observer or an error if the header could not be written.
+    pub fn new(mut writer: W) -> Result<Self> {
+        writeln!(writer, "{}", Self::HEADER)?;
+        Ok(Self {
+            writer,
+            game: 0,
+            ply: 0,
+        })
+    }
+
+    /// Consumes the observer and returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> GameObserver for CsvGameObserver<W> {
+    fn on_move(
+        &mut self,
+        state: &Patchwork,
+        action: ActionId,
+        evaluation: Option<i32>,
+        principal_variation: &[ActionId],
+    ) -> Result<()> {
+        let principal_variation = principal_variation
+            .iter()
+            .map(Notation::save_to_notation)
+            .collect::<Result<Vec<_>, _>>()?
+            .join(" ");
+
+        writeln!(
+            self.writer,
+            "{},{},{},{},{},{}",
+            self.game,
+            self.ply,
+            if state.is_player_1() { 1 } else { 2 },
+            action.save_to_notation()?,
+            evaluation.map(|evaluation| evaluation.to_string()).unwrap_or_default(),
+            principal_variation
+        )?;
+        self.ply += 1;
+        Ok(())
+    }
+
+    fn on_undo(&mut self, _state: &Patchwork, undone_moves: usize) -> Result<()> {
+        // the rows of the undone moves were already written, the moves played
+        // instead are written again with the same plies
+        self.ply = self.ply.saturating_sub(undone_moves);
+        Ok(())
+    }
+
+    fn on_game_end(&mut self, _state: &Patchwork) -> Result<()> {
+        self.game += 1;
+        self.ply = 0;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::GameOptions;
+
+    #[test]
+    fn test_writes_one_row_per_move() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut observer = CsvGameObserver::new(vec![]).unwrap();
+
+        let mut moves = 0;
+        while !state.is_terminated() {
+            let action = state.get_valid_actions()[0];
+            observer.on_move(&state, action, Some(moves), &[action]).unwrap();
+            state.do_action(action, false).unwrap();
+            moves += 1;
+        }
+        observer.on_game_end(&state).unwrap();
+
+        let csv = String::from_utf8(observer.into_inner()).unwrap();
+        let mut lines = csv.lines();
+
+        assert_eq!(Some(CsvGameObserver::<Vec<u8>>::HEADER), lines.next());
+        for (ply, line) in lines.enumerate() {
+            let columns = line.split(',').collect::<Vec<_>>();
+            assert_eq!(6, columns.len());
+            assert_eq!("0", columns[0]);
+            assert_eq!(ply.to_string(), columns[1]);
+            assert_eq!(ply.to_string(), columns[4]);
+            assert_eq!(columns[3], columns[5]);
+        }
+        assert_eq!(moves + 1, i32::try_from(csv.lines().count()).unwrap());
+    }
+
+    #[test]
+    fn test_undone_moves_are_written_with_the_same_plies() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let action = state.get_valid_actions()[0];
+        let mut observer = CsvGameObserver::new(vec![]).unwrap();
+
+        observer.on_move(&state, action, None, &[]).unwrap();
+        observer.on_move(&state, action, None, &[]).unwrap();
+        observer.on_undo(&state, 1).unwrap();
+        observer.on_move(&state, action, None, &[]).unwrap();
+
+        let csv = String::from_utf8(observer.into_inner()).unwrap();
+        let plies = csv
+            .lines()
+            .skip(1)
+            .map(|line| line.split(',').nth(1).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["0", "1", "1"], plies);
+    }
+}
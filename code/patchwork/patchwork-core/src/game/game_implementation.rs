@@ -1,9 +1,9 @@
 use rand::{Rng, SeedableRng};
-use rand_xoshiro::Xoshiro256PlusPlus;
 
 use crate::{
-    status_flags, ActionId, GameOptions, Patch, PatchManager, Patchwork, PatchworkError, PlayerState, TimeBoard,
-    TurnType,
+    status_flags, time_board_flags, ActionId, ActionsSummary, GameOptions, GameRng, NumberedActions, Patch,
+    PatchManager, PatchOptionSummary, Patchwork, PatchworkError, PlacementCache, PlayerState, QuiltBoard,
+    ScoringRulesRef, TimeBoard, TurnType, WalkingSummary,
 };
 
 /// The game logic for Patchwork.
@@ -19,20 +19,33 @@ impl Patchwork {
     /// # Returns
     ///
     /// The initial state of the game.
+    ///
+    /// # Panics
+    ///
+    /// If the patch order of the options is not a permutation of the ids of
+    /// all non-starting patches.
     #[must_use]
     pub fn get_initial_state(options: Option<GameOptions>) -> Self {
         // 1. Each player takes a quilt board, a time token and 5 buttons
         //    (as currency). Keep the remaining buttons on the table close at
-        //    hand.
-        let player_1 = PlayerState::default();
-        let player_2 = PlayerState::default();
+        //    hand. Some variants use a different amount of starting buttons.
+        let (player_1, player_2) = options.map_or_else(
+            || (PlayerState::default(), PlayerState::default()),
+            |options| {
+                let starting_buttons = i32::from(options.starting_buttons);
+                (
+                    PlayerState::new(0, starting_buttons, QuiltBoard::new()),
+                    PlayerState::new(0, starting_buttons, QuiltBoard::new()),
+                )
+            },
+        );
 
         // 2. Place the central time board in the middle of the table.
 
         // 3. Place your time tokens on the starting space of the
         //    time board. The player who last used a needle begins
         let time_board = TimeBoard::default();
-        let status_flags = Self::get_player_1_flag();
+        let mut status_flags = Self::get_player_1_flag();
 
         // 4. Place the (regular) patches in a circle or oval around the time
         //     board.
@@ -40,20 +53,30 @@ impl Patchwork {
         // 5. Locate the smallest patch, i.e. the patch of size 1x2, and place
         //    the neutral token between this patch and the next patch in
         //    clockwise order.
-        let patches = PatchManager::generate_patches(options.map(|o| o.seed));
+        //    Some variants use an explicit order of the patches.
+        let patches = options.and_then(|o| o.patch_order).map_or_else(
+            || PatchManager::generate_patches(options.map(|o| o.seed)),
+            |patch_order| PatchManager::generate_patches_in_order(&patch_order),
+        );
 
-        // # 6. Lay out the special tile
+        // # 6. Lay out the special tile (if the variant uses it)
+        if options.is_some_and(|o| !o.special_tile_bonus) {
+            status_flags |= status_flags::SPECIAL_TILE_BONUS_DISABLED;
+        }
 
         // # 7. Place the special patches on the marked spaces of the time board
 
         // # 8. Now you are ready to go!
         Self {
-            patches,
+            patches: patches.into_iter().collect(),
             time_board,
             player_1,
             player_2,
             status_flags,
             turn_type: TurnType::Normal,
+            empty_tile_penalty: options.map_or(QuiltBoard::EMPTY_TILE_PENALTY, |o| o.empty_tile_penalty),
+            scoring_rules: options.map_or(ScoringRulesRef::STANDARD, |o| o.scoring_rules),
+            ply: 0,
         }
     }
 
@@ -74,9 +97,33 @@ impl Patchwork {
     /// `𝒪(𝑛)` where `n` is the number of valid actions.
     #[must_use]
     pub fn get_valid_actions(&self) -> Vec<ActionId> {
+        let mut valid_actions = vec![];
+        self.get_valid_actions_into(&mut valid_actions);
+        valid_actions
+    }
+
+    /// Fills the given buffer with the valid actions for the current player in
+    /// the given state. The buffer is cleared before it is filled, so it can be
+    /// reused between calls to avoid allocating a new list for every node in
+    /// the search.
+    ///
+    /// The actions are in the same order as the ones returned by
+    /// [`Patchwork::get_valid_actions`].
+    ///
+    /// # Arguments
+    ///
+    /// * `valid_actions` - The buffer to fill with the valid actions.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    pub fn get_valid_actions_into(&self, valid_actions: &mut Vec<ActionId>) {
+        valid_actions.clear();
+
         // Phantom Actions - the current player is not really allowed to take a turn
         if matches!(self.turn_type, TurnType::NormalPhantom | TurnType::SpecialPhantom) {
-            return vec![ActionId::phantom()];
+            valid_actions.push(ActionId::phantom());
+            return;
         }
 
         // Course of Play
@@ -90,19 +137,278 @@ impl Patchwork {
 
         // Placing a Special Patch is a special action
         if self.turn_type == TurnType::SpecialPatchPlacement {
-            return self.current_player().quilt_board.get_valid_actions_for_special_patch();
+            self.current_player()
+                .quilt_board
+                .push_valid_actions_for_special_patch(valid_actions);
+            return;
         }
 
         // On your turn, you carry out one of the following actions:
-        let mut valid_actions: Vec<ActionId> = vec![
-            // A: Advance and Receive Buttons
-            ActionId::walking(self.current_player().position),
-        ];
+
+        // A: Advance and Receive Buttons
+        valid_actions.push(ActionId::walking(self.current_player().position));
 
         // B: Take and Place a Patch
-        valid_actions.append(&mut self.get_take_and_place_a_patch_actions());
+        self.push_take_and_place_a_patch_actions(valid_actions);
+    }
 
-        valid_actions
+    /// Fills the given buffer with the valid actions for the current player in
+    /// the given state like [`Patchwork::get_valid_actions_into`]. The
+    /// placements of the patches are looked up in the given cache instead of
+    /// being generated again for every state with the same quilt board.
+    ///
+    /// The actions are in the same order as the ones returned by
+    /// [`Patchwork::get_valid_actions`].
+    ///
+    /// # Arguments
+    ///
+    /// * `valid_actions` - The buffer to fill with the valid actions.
+    /// * `placement_cache` - The cache for the placements of the patches.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    pub fn get_valid_actions_into_cached(
+        &self,
+        valid_actions: &mut Vec<ActionId>,
+        placement_cache: &mut PlacementCache,
+    ) {
+        if self.turn_type != TurnType::Normal {
+            self.get_valid_actions_into(valid_actions);
+            return;
+        }
+
+        valid_actions.clear();
+        valid_actions.push(ActionId::walking(self.current_player().position));
+
+        let current_player = self.current_player();
+        let is_player_1 = self.is_player_1();
+
+        for (index, patch) in self
+            .patches_available_to_current_player()
+            .iter()
+            .enumerate()
+            .filter(|patch| self.can_player_take_patch(current_player, patch.1))
+        {
+            let transformation_indices =
+                placement_cache.get_transformation_indices(patch, current_player.quilt_board.tiles);
+            valid_actions.extend(transformation_indices.iter().map(|&transformation_index| {
+                ActionId::patch_placement(patch.id, index as u8, transformation_index, is_player_1)
+            }));
+        }
+    }
+
+    /// Gets the amount of valid actions for the current player in the given
+    /// state (the branching factor of the state).
+    ///
+    /// # Returns
+    ///
+    /// The amount of valid actions for the current player.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    #[must_use]
+    pub fn legal_move_count(&self) -> usize {
+        let mut valid_actions = vec![];
+        self.get_valid_actions_into(&mut valid_actions);
+        valid_actions.len()
+    }
+
+    /// Gets the special patch placements the current player can choose from.
+    /// Only during a [`TurnType::SpecialPatchPlacement`] turn there are
+    /// special patch placements, in all other turns (including the
+    /// [`TurnType::SpecialPhantom`] turn of the other player) the returned
+    /// list is empty.
+    ///
+    /// # Returns
+    ///
+    /// The valid special patch placement actions of the current player.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid special patch placements.
+    #[must_use]
+    pub fn available_special_actions(&self) -> Vec<ActionId> {
+        let mut special_actions = vec![];
+        if self.turn_type == TurnType::SpecialPatchPlacement {
+            self.current_player()
+                .quilt_board
+                .push_valid_actions_for_special_patch(&mut special_actions);
+        }
+        special_actions
+    }
+
+    /// Gets all states that are reachable with a single action together with
+    /// the action that leads to them.
+    ///
+    /// # Returns
+    ///
+    /// Every valid action (in the order of [`Patchwork::get_valid_actions`])
+    /// paired with the state after the action was taken. Empty if the game is
+    /// terminated.
+    ///
+    /// # Panics
+    ///
+    /// If a valid action cannot be taken. This is a bug in the `patchwork_core` library.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    #[must_use]
+    pub fn expand(&self) -> Vec<(ActionId, Self)> {
+        if self.is_terminated() {
+            return vec![];
+        }
+
+        self.get_valid_actions()
+            .into_iter()
+            .map(|action| {
+                let mut next_state = self.clone();
+                next_state
+                    .do_action(action, false)
+                    .expect("[Patchwork::expand] A valid action could not be taken");
+                (action, next_state)
+            })
+            .collect()
+    }
+
+    /// Estimates how complex the decision of the current player is. The
+    /// estimate combines the amount of legal moves, the amount of patches the
+    /// current player can afford and how much of the quilt board of the
+    /// current player is still empty. It is scaled so that the average
+    /// complexity of the positions of the [`PositionCorpus`](crate::PositionCorpus)
+    /// is about `1.0`. This can be used to spend more time on complex
+    /// positions.
+    ///
+    /// # Returns
+    ///
+    /// The complexity estimate. `0.0` if the current player has no choice
+    /// (terminated game, phantom turn or a single legal move).
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn complexity_estimate(&self) -> f32 {
+        /// Scales the estimate so that the average of the position corpus is
+        /// about `1.0` (the unscaled average is about `2.67`).
+        const COMPLEXITY_SCALE: f32 = 0.375;
+
+        if self.is_terminated() || matches!(self.turn_type, TurnType::NormalPhantom | TurnType::SpecialPhantom) {
+            return 0.0;
+        }
+
+        let legal_moves = self.legal_move_count();
+        if legal_moves <= 1 {
+            return 0.0;
+        }
+
+        let current_player = self.current_player();
+        let affordable_patches = if self.turn_type == TurnType::Normal {
+            self.patches_available_to_current_player()
+                .iter()
+                .filter(|patch| self.can_player_take_patch(current_player, patch))
+                .count()
+        } else {
+            0
+        };
+        let empty_tiles = 1.0 - current_player.quilt_board.tiles_filled() as f32 / f32::from(QuiltBoard::TILES);
+
+        let mobility = (legal_moves as f32).ln();
+        let patch_choice = 1.0 + affordable_patches as f32 / 3.0;
+        let board_freedom = 0.5f32.mul_add(empty_tiles, 0.5);
+
+        COMPLEXITY_SCALE * mobility * patch_choice * board_freedom
+    }
+
+    /// Gets a structured summary of the decision the current player has to
+    /// make, e.g. to be displayed in a user interface.
+    ///
+    /// The summary contains the effect of walking and for every patch the
+    /// current player can take the amount of placements as well as the best
+    /// resulting amount of filled tiles. The placements are counted from the
+    /// same bitboards as the actions of [`Patchwork::get_valid_actions`]
+    /// without creating them.
+    ///
+    /// # Returns
+    ///
+    /// The summary of the available actions.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of transformations of the patches the
+    /// current player can take.
+    #[must_use]
+    pub fn available_actions_summary(&self) -> ActionsSummary {
+        let mut summary = ActionsSummary {
+            walking: None,
+            patches: vec![],
+            special_patch_placements: 0,
+        };
+
+        match self.turn_type {
+            TurnType::NormalPhantom | TurnType::SpecialPhantom => return summary,
+            TurnType::SpecialPatchPlacement => {
+                summary.special_patch_placements = self.current_player().quilt_board.tiles_free() as usize;
+                return summary;
+            }
+            TurnType::Normal => {}
+        }
+
+        let current_player = self.current_player();
+
+        // walking moves the current player in front of the other player and gains a button for every moved tile
+        let next_position = (self.other_player().position + 1).min(TimeBoard::MAX_POSITION);
+        let time_advanced = next_position - current_player.position;
+        let walking_range = (current_player.position as usize + 1)..=(next_position as usize);
+        let button_income_trigger = i32::from(self.time_board.is_button_income_trigger_in_range(walking_range));
+        summary.walking = Some(WalkingSummary {
+            buttons_gained: i32::from(time_advanced)
+                + button_income_trigger * i32::from(current_player.quilt_board.button_income),
+            time_advanced,
+        });
+
+        for (index, patch) in self
+            .patches_available_to_current_player()
+            .iter()
+            .enumerate()
+            .filter(|patch| self.can_player_take_patch(current_player, patch.1))
+        {
+            let placements = current_player.quilt_board.count_valid_placements_for_patch(patch);
+            if placements == 0 {
+                continue;
+            }
+
+            summary.patches.push(PatchOptionSummary {
+                patch_index: index as u8,
+                patch_id: patch.id,
+                button_cost: patch.button_cost,
+                time_cost: patch.time_cost,
+                placements,
+                // every placement of a patch fills the same amount of tiles
+                best_resulting_tiles: current_player.quilt_board.tiles_filled() + patch.amount_tiles(),
+            });
+        }
+
+        summary
+    }
+
+    /// Gets the valid actions of the current player numbered for the selection
+    /// by a human. Displaying the result lists every action with its number
+    /// and notation.
+    ///
+    /// # Returns
+    ///
+    /// The numbered valid actions in the order of [`Patchwork::get_valid_actions`].
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    #[must_use]
+    pub fn display_numbered_actions(&self) -> NumberedActions {
+        NumberedActions::new(self)
     }
 
     /// Gets a random action for the current player in the given state.
@@ -118,7 +424,7 @@ impl Patchwork {
     pub fn get_random_action(&self) -> ActionId {
         // PERF: more efficient implementation
         let mut valid_actions = self.get_valid_actions();
-        let random_index = rand::random::<usize>() % valid_actions.len();
+        let random_index = GameRng::with_thread_rng(|rng| rng.gen_range(0..valid_actions.len()));
         valid_actions.remove(random_index)
     }
 
@@ -139,7 +445,7 @@ impl Patchwork {
     /// `𝒪(𝑛)` where `n` is the number of valid actions.
     #[must_use]
     pub fn get_seeded_random_action(&self, seed: u64) -> ActionId {
-        let mut random = Xoshiro256PlusPlus::seed_from_u64(seed);
+        let mut random = GameRng::seed_from_u64(seed);
         let mut valid_actions = self.get_valid_actions();
         let random_index = random.gen::<usize>() % valid_actions.len();
         valid_actions.remove(random_index)
@@ -175,6 +481,12 @@ impl Patchwork {
 
     /// Mutates the current game state by taking an action.
     ///
+    /// This is the fast path for trusted callers like the search of the
+    /// players that only take actions generated by
+    /// [`Patchwork::get_valid_actions`]. The legality of the action is not
+    /// checked, an illegal action may silently corrupt the state. Use
+    /// [`Patchwork::do_action_validated`] for actions from untrusted sources.
+    ///
     /// # Arguments
     ///
     /// * `action` - The action to take.
@@ -198,9 +510,151 @@ impl Patchwork {
     ///
     /// When a null action is given in debug mode. In release mode this is
     /// undefined behavior.
+    ///
+    /// In debug builds the invariants of the game state are checked after the
+    /// action was taken (see [`Patchwork::check_invariants`]).
+    pub fn do_action(&mut self, action: ActionId, force_player_switch: bool) -> Result<(), PatchworkError> {
+        self.do_action_unchecked(action, force_player_switch);
+        if !action.is_phantom() {
+            self.ply += 1;
+        }
+
+        #[cfg(debug_assertions)]
+        if let Err(error) = self.check_invariants() {
+            println!("{self}");
+            println!("Action: \n{action:?}");
+            panic!("[Patchwork::do_action] The action corrupted the state: {error}");
+        }
+
+        Ok(())
+    }
+
+    /// Mutates the current game state by taking an action after checking that
+    /// the action is legal in the current state (see
+    /// [`Patchwork::validate_action`]).
+    ///
+    /// This is the safe counterpart to [`Patchwork::do_action`] for actions
+    /// from untrusted sources like user input or the network.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to take.
+    /// * `force_player_switch` - Whether the player switch should be forced (see [`Patchwork::do_action`]).
+    ///
+    /// # Returns
+    ///
+    /// Whether the action was successfully taken.
+    ///
+    /// # Errors
+    ///
+    /// If the action is not legal in the current state. The state is not
+    /// mutated in this case.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    pub fn do_action_validated(&mut self, action: ActionId, force_player_switch: bool) -> Result<(), PatchworkError> {
+        self.validate_action(action)?;
+        self.do_action(action, force_player_switch)
+    }
+
+    /// Checks whether the given action is legal in the current state.
+    ///
+    /// The result is the same as checking if the action is contained in
+    /// [`Patchwork::get_valid_actions`] but the valid actions do not need to
+    /// be generated and the error describes why the action is not legal.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action to check.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the action is legal.
+    ///
+    /// # Errors
+    ///
+    /// An [`PatchworkError::InvalidActionError`] with the reason why the action
+    /// is not legal.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    pub fn validate_action(&self, action: ActionId) -> Result<(), PatchworkError> {
+        let invalid_action = |reason: &'static str| {
+            Err(PatchworkError::InvalidActionError {
+                reason,
+                action,
+                state: Box::new(self.clone()),
+            })
+        };
+
+        if action.is_null() {
+            return invalid_action("[Patchwork::validate_action] Null actions cannot be taken");
+        }
+
+        match self.turn_type {
+            TurnType::NormalPhantom | TurnType::SpecialPhantom => {
+                if !action.is_phantom() {
+                    return invalid_action("[Patchwork::validate_action] Expected phantom action");
+                }
+            }
+            TurnType::SpecialPatchPlacement => {
+                if !action.is_special_patch_placement() {
+                    return invalid_action("[Patchwork::validate_action] Expected special patch placement action");
+                }
+                if (self.current_player().quilt_board.tiles >> action.get_quilt_board_index()) & 1 > 0 {
+                    return invalid_action("[Patchwork::validate_action] The special patch overlaps a filled tile");
+                }
+            }
+            TurnType::Normal if action.is_walking() => {
+                if action.get_starting_index() != self.current_player().position {
+                    return invalid_action(
+                        "[Patchwork::validate_action] The starting index does not match the player position",
+                    );
+                }
+            }
+            TurnType::Normal if action.is_patch_placement() => {
+                let current_player = self.current_player();
+                let Some(patch) = self
+                    .patches_available_to_current_player()
+                    .get(action.get_patch_index() as usize)
+                else {
+                    return invalid_action("[Patchwork::validate_action] The patch index is not available");
+                };
+
+                if patch.id != action.get_patch_id() {
+                    return invalid_action("[Patchwork::validate_action] The patch is not at the given patch index");
+                }
+                if action.get_previous_player_was_1() != self.is_player_1() {
+                    return invalid_action("[Patchwork::validate_action] The action was created for the other player");
+                }
+                if i32::from(patch.button_cost) > current_player.button_balance {
+                    return invalid_action("[Patchwork::validate_action] The player cannot afford the patch");
+                }
+
+                let transformations = PatchManager::get_transformations(patch.id);
+                let Some(transformation) = transformations.get(action.get_patch_transformation_index() as usize)
+                else {
+                    return invalid_action("[Patchwork::validate_action] The patch transformation does not exist");
+                };
+                if current_player.quilt_board.tiles & transformation.tiles != 0 {
+                    return invalid_action("[Patchwork::validate_action] The patch overlaps filled tiles");
+                }
+            }
+            TurnType::Normal => {
+                return invalid_action("[Patchwork::validate_action] Expected walking or patch placement action");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Mutates the current game state by taking an action without checking
+    /// the invariants of the resulting state (see [`Patchwork::do_action`]).
     #[allow(unused_variables)]
     #[allow(clippy::too_many_lines)]
-    pub fn do_action(&mut self, action: ActionId, force_player_switch: bool) -> Result<(), PatchworkError> {
+    fn do_action_unchecked(&mut self, action: ActionId, force_player_switch: bool) {
         #[cfg(debug_assertions)]
         if action.is_null() {
             println!("{self}");
@@ -213,66 +667,61 @@ impl Patchwork {
         if action.is_phantom() {
             // IF the player is not switched this is a no-op
             if !force_player_switch {
-                return Ok(());
+                return;
             }
 
+            debug_assert!(
+                matches!(self.turn_type, TurnType::NormalPhantom | TurnType::SpecialPhantom),
+                "[Patchwork::do_action] Did not expect phantom action in turn {:?}",
+                self.turn_type
+            );
+
             // IF the player is switched we need to switch back the turn type to the previous state
-            return match self.turn_type {
-                TurnType::NormalPhantom => {
-                    self.turn_type = TurnType::Normal;
-                    self.switch_player();
-                    Ok(())
-                }
-                TurnType::SpecialPhantom => {
-                    self.turn_type = TurnType::SpecialPatchPlacement;
-                    self.switch_player();
-                    Ok(())
-                }
-                _ => Err(PatchworkError::InvalidActionError {
-                    reason: "[Patchwork::do_action] Did not expect phantom action",
-                    action,
-                    state: Box::new(self.clone()),
-                }),
+            self.turn_type = if matches!(self.turn_type, TurnType::SpecialPhantom) {
+                TurnType::SpecialPatchPlacement
+            } else {
+                TurnType::Normal
             };
+            self.switch_player();
+            return;
         }
 
         // IF special patch placement
         if action.is_special_patch_placement() {
-            return if matches!(self.turn_type, TurnType::SpecialPatchPlacement) {
-                //   1. place patch
-                //      a) if the board is full the current player get +7 points
-                //   2. switch player
-                //   3. clear special patch
-                //   4. reset the turn type to normal
-
-                let current_player_position = self.current_player().position;
-
-                let special_patch_index =
-                    self.time_board.get_special_patch_before_position(current_player_position).expect(
-                        "[Patchwork::do_action] Expected special patch to be placed before current player position",
-                    );
+            debug_assert!(
+                matches!(self.turn_type, TurnType::SpecialPatchPlacement),
+                "[Patchwork::do_action] Did not expect special patch placement action in turn {:?}",
+                self.turn_type
+            );
 
-                let current_player = self.current_player_mut();
-                current_player.quilt_board.do_action(action);
-                if current_player.quilt_board.is_special_tile_condition_reached()
-                    && !self.is_special_tile_condition_reached()
-                {
-                    self.set_special_tile_condition(self.get_current_player());
-                }
+            //   1. place patch
+            //      a) if the board is full the current player get +7 points
+            //   2. switch player
+            //   3. clear special patch
+            //   4. reset the turn type to normal
 
-                self.switch_player();
+            let current_player_position = self.current_player().position;
 
-                self.time_board.unset_special_patch(special_patch_index);
+            let special_patch_index = self
+                .time_board
+                .get_special_patch_before_position(current_player_position)
+                .expect("[Patchwork::do_action] Expected special patch to be placed before current player position");
 
-                self.turn_type = TurnType::Normal;
-                Ok(())
-            } else {
-                Err(PatchworkError::InvalidActionError {
-                    reason: "[Patchwork::do_action] Did not expect special patch placement action",
-                    action,
-                    state: Box::new(self.clone()),
-                })
-            };
+            let current_player = self.current_player_mut();
+            current_player.quilt_board.do_action(action);
+            if current_player.quilt_board.is_special_tile_condition_reached()
+                && !self.is_special_tile_condition_reached()
+                && self.is_special_tile_bonus_enabled()
+            {
+                self.set_special_tile_condition(self.get_current_player());
+            }
+
+            self.switch_player();
+
+            self.time_board.unset_special_patch(special_patch_index);
+
+            self.turn_type = TurnType::Normal;
+            return;
         }
 
         debug_assert!(
@@ -327,7 +776,7 @@ impl Patchwork {
             //      a) if the board is full the current player get +7 points
             let len = self.patches.len();
             self.patches.rotate_left(patch_index + 1);
-            self.patches.remove(len - 1);
+            self.patches.truncate(len - 1);
 
             let current_player = self.current_player_mut();
 
@@ -336,6 +785,7 @@ impl Patchwork {
             current_player.quilt_board.do_action(action);
             if current_player.quilt_board.is_special_tile_condition_reached()
                 && !self.is_special_tile_condition_reached()
+                && self.is_special_tile_bonus_enabled()
             {
                 self.set_special_tile_condition(self.get_current_player());
             }
@@ -378,7 +828,7 @@ impl Patchwork {
                 // If not throw the special patch away and switch player
                 self.time_board.unset_special_patch(special_patch_index);
                 self.switch_player();
-                return Ok(());
+                return;
             }
 
             if force_player_switch {
@@ -388,7 +838,7 @@ impl Patchwork {
                 self.turn_type = TurnType::SpecialPatchPlacement;
             }
 
-            return Ok(());
+            return;
         }
 
         // test player position and optionally switch (always true if action.is_walking)
@@ -398,8 +848,6 @@ impl Patchwork {
             self.turn_type = TurnType::NormalPhantom;
             self.switch_player();
         }
-
-        Ok(())
     }
 
     /// Mutates the current game state by undoing an action.
@@ -421,8 +869,18 @@ impl Patchwork {
     ///
     /// This function has undefined if the game is in initial state or when a null action is given.
     /// This will panic in debug mode
-    #[allow(clippy::too_many_lines)]
     pub fn undo_action(&mut self, action: ActionId, force_player_switch: bool) -> Result<(), PatchworkError> {
+        let result = self.undo_action_unchecked(action, force_player_switch);
+        if result.is_ok() && !action.is_phantom() {
+            // states loaded from the notation start at ply 0 and can still be undone
+            self.ply = self.ply.saturating_sub(1);
+        }
+
+        result
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn undo_action_unchecked(&mut self, action: ActionId, force_player_switch: bool) -> Result<(), PatchworkError> {
         #[cfg(debug_assertions)]
         if action.is_null() {
             println!("{self}");
@@ -613,6 +1071,136 @@ impl Patchwork {
         Ok(())
     }
 
+    // ────────────────────────────────────────────────── INVARIANTS ───────────────────────────────────────────────────
+
+    /// Checks the invariants every reachable game state fulfills. These are
+    ///
+    /// * The button balances of both players are non-negative.
+    /// * Exactly one player is the current player.
+    /// * The positions of the players match their positions on the time board.
+    /// * The quilt boards only use the 81 tiles of the board.
+    /// * The special tile (e.g. the 7x7 tile) is awarded to at most one player
+    ///   that reached the special tile condition and is awarded if any player
+    ///   reached it.
+    /// * At most one player was first to reach the end.
+    /// * The patch circle only contains normal patches, each at most once and
+    ///   none of them is already placed on a quilt board.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if all invariants hold, otherwise an error describing the
+    /// first violated invariant.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛 + 𝑚)` where `𝑛` is the amount of tiles on the time board (usually 54)
+    /// and `𝑚` is the amount of patches.
+    pub fn check_invariants(&self) -> Result<(), PatchworkError> {
+        let invalid_state = |reason: &'static str| {
+            Err(PatchworkError::InvalidStateError {
+                reason,
+                state: Box::new(self.clone()),
+            })
+        };
+
+        if self.player_1.button_balance < 0 || self.player_2.button_balance < 0 {
+            return invalid_state("[Patchwork::check_invariants] A player has a negative button balance");
+        }
+
+        let current_player = self.status_flags & status_flags::BOTH_PLAYERS;
+        if current_player != status_flags::PLAYER_1 && current_player != status_flags::PLAYER_2 {
+            return invalid_state("[Patchwork::check_invariants] Not exactly one player is the current player");
+        }
+
+        for player_flag in [time_board_flags::PLAYER_1, time_board_flags::PLAYER_2] {
+            let amount_of_positions = self.time_board.tiles.iter().filter(|tile| *tile & player_flag > 0).count();
+            if amount_of_positions != 1 {
+                return invalid_state("[Patchwork::check_invariants] A player is not exactly once on the time board");
+            }
+        }
+        if self.time_board.get_player_positions() != (self.player_1.get_position(), self.player_2.get_position()) {
+            return invalid_state("[Patchwork::check_invariants] A player position does not match the time board");
+        }
+
+        if (self.player_1.quilt_board.tiles | self.player_2.quilt_board.tiles) >> QuiltBoard::TILES != 0 {
+            return invalid_state("[Patchwork::check_invariants] A quilt board has more than 81 tiles");
+        }
+
+        self.check_special_tile_invariants()?;
+
+        if (self.status_flags & status_flags::BOTH_PLAYERS_FIRST_AT_END) == status_flags::BOTH_PLAYERS_FIRST_AT_END {
+            return invalid_state("[Patchwork::check_invariants] Both players were first to reach the end");
+        }
+
+        // every normal patch is either in the patch circle or placed on one quilt board, never both
+        let mut seen_patches = 0u64;
+        let placed_patch_ids = self.player_1.quilt_board.placed_patch_ids().iter();
+        let placed_patch_ids = placed_patch_ids.chain(self.player_2.quilt_board.placed_patch_ids());
+        for patch_id in self.patches.iter().map(|patch| patch.id).chain(placed_patch_ids.copied()) {
+            if patch_id >= PatchManager::AMOUNT_OF_NORMAL_PATCHES {
+                return invalid_state("[Patchwork::check_invariants] The game contains a patch that is not a normal patch");
+            }
+            if seen_patches & (1 << patch_id) != 0 {
+                return invalid_state("[Patchwork::check_invariants] A patch is in the game more than once");
+            }
+            seen_patches |= 1 << patch_id;
+        }
+
+        Ok(())
+    }
+
+    /// Checks the invariants of the special tile (e.g. the 7x7 tile). It is
+    /// awarded to at most one player that reached the special tile condition
+    /// and it is awarded if any player reached it.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if all invariants hold, otherwise an error describing the
+    /// first violated invariant.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    pub(crate) fn check_special_tile_invariants(&self) -> Result<(), PatchworkError> {
+        let invalid_state = |reason: &'static str| {
+            Err(PatchworkError::InvalidStateError {
+                reason,
+                state: Box::new(self.clone()),
+            })
+        };
+
+        if (self.status_flags & status_flags::BOTH_PLAYERS_HAVE_SPECIAL_TILE)
+            == status_flags::BOTH_PLAYERS_HAVE_SPECIAL_TILE
+        {
+            return invalid_state("[Patchwork::check_special_tile_invariants] Both players have the special tile");
+        }
+        if (self.is_special_tile_condition_reached_by_player_1()
+            && !self.player_1.quilt_board.is_special_tile_condition_reached())
+            || (self.is_special_tile_condition_reached_by_player_2()
+                && !self.player_2.quilt_board.is_special_tile_condition_reached())
+        {
+            return invalid_state(
+                "[Patchwork::check_special_tile_invariants] A player has the special tile without reaching it",
+            );
+        }
+        if self.is_special_tile_condition_reached() && !self.is_special_tile_bonus_enabled() {
+            return invalid_state(
+                "[Patchwork::check_special_tile_invariants] The special tile was awarded while it is disabled",
+            );
+        }
+        if self.is_special_tile_bonus_enabled()
+            && !self.is_special_tile_condition_reached()
+            && (self.player_1.quilt_board.is_special_tile_condition_reached()
+                || self.player_2.quilt_board.is_special_tile_condition_reached())
+        {
+            return invalid_state(
+                "[Patchwork::check_special_tile_invariants] The special tile was reached but not awarded",
+            );
+        }
+
+        Ok(())
+    }
+
     // ─────────────────────────────────────────── DO AND UNDO NULL ACTIONS ────────────────────────────────────────────
 
     // TODO: null actions (get_valid_null_actions, do_null_action, undo_null_action)
@@ -654,33 +1242,31 @@ impl Patchwork {
 
     // ─────────────────────────────────────────────── UTILITY FUNCTIONS ───────────────────────────────────────────────
 
-    /// Get the valid moves for the action "Take and Place a Patch"
+    /// Pushes the valid moves for the action "Take and Place a Patch" onto the
+    /// given buffer.
     ///
     /// # Arguments
     ///
-    /// * `state` - the current state (will not be modified)
-    ///
-    /// # Returns
-    ///
-    /// a list of all valid next states
+    /// * `valid_actions` - the buffer to push the valid actions onto
     ///
     /// # Complexity
     ///
     /// `𝒪(𝑛)` where `n` is the number of valid actions.
     #[inline]
-    fn get_take_and_place_a_patch_actions(&self) -> Vec<ActionId> {
-        return self
-            .patches
+    fn push_take_and_place_a_patch_actions(&self, valid_actions: &mut Vec<ActionId>) {
+        let current_player = self.current_player();
+        let is_player_1 = self.is_player_1();
+
+        for (index, patch) in self
+            .patches_available_to_current_player()
             .iter()
-            .take(PatchManager::MAX_AMOUNT_OF_CHOOSABLE_TILES as usize)
             .enumerate()
-            .filter(|patch| self.can_player_take_patch(self.current_player(), patch.1))
-            .flat_map(|(index, patch)| {
-                self.current_player()
-                    .quilt_board
-                    .get_valid_actions_for_patch(patch, index as u8, self.is_player_1())
-            })
-            .collect();
+            .filter(|patch| self.can_player_take_patch(current_player, patch.1))
+        {
+            current_player
+                .quilt_board
+                .push_valid_actions_for_patch(patch, index as u8, is_player_1, valid_actions);
+        }
     }
 
     /// Performance fastpath for checking if a player can take a patch
@@ -717,12 +1303,11 @@ impl Patchwork {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::VecDeque;
+    use std::collections::{HashSet, VecDeque};
 
-    use crate::{status_flags, Action, Notation};
+    use crate::{status_flags, Action, Notation, PatchOrder};
     use pretty_assertions::assert_eq;
     use rand::{Rng, SeedableRng};
-    use rand_xoshiro::Xoshiro256PlusPlus;
 
     use super::*;
 
@@ -741,6 +1326,34 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_get_valid_actions_into_matches_get_valid_actions() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut random = GameRng::seed_from_u64(42);
+        let mut buffer = Vec::new();
+
+        while !state.is_terminated() {
+            let valid_actions = state.get_valid_actions();
+            state.get_valid_actions_into(&mut buffer);
+            assert_eq!(valid_actions, buffer);
+
+            let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+            state.do_action(action, false).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_get_valid_actions_into_clears_buffer() {
+        let state = Patchwork::get_initial_state(None);
+        let mut buffer = vec![ActionId::null(); 5];
+
+        state.get_valid_actions_into(&mut buffer);
+        assert_eq!(state.get_valid_actions(), buffer);
+
+        state.get_valid_actions_into(&mut buffer);
+        assert_eq!(state.get_valid_actions(), buffer);
+    }
+
     #[test]
     fn test_walking_action_at_start() {
         let mut state = Patchwork::get_initial_state(None);
@@ -809,6 +1422,289 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_special_7x7_tile_disabled() {
+        // same state as in test_special_7x7_tile but with the special tile bonus disabled (64 | 2)
+        let old_state = Patchwork::load_from_notation(
+            "1F1DDB9CDCF67F5DCA271B18I15P52 11EFF3FCFEFE7FBF9FFF0B26I6P50 66 N 14/24/6/29/3/13/11/12/15/16/17",
+        )
+        .unwrap();
+        let action = Action::load_from_notation("P14I0═3‖7↻0↔0P0").unwrap().to_surrogate_action_id();
+
+        assert!(!old_state.is_special_tile_bonus_enabled());
+
+        for force_swap in [true, false] {
+            let mut new_state = old_state.clone();
+            new_state.do_action(action, force_swap).unwrap();
+
+            assert!(
+                new_state.player_2.quilt_board.is_special_tile_condition_reached(),
+                "Player 2 should have filled a 7x7 area"
+            );
+            assert!(
+                !new_state.is_special_tile_condition_reached(),
+                "No player should have the special tile but flags were {:b}",
+                new_state.status_flags
+            );
+            assert_eq!(
+                new_state.get_score(status_flags::PLAYER_2),
+                new_state.player_2.quilt_board.score() + new_state.player_2.button_balance,
+                "Player 2 should not get the special tile bonus"
+            );
+
+            new_state.undo_action(action, force_swap).unwrap();
+            assert_eq!(old_state, new_state, "Old State != Restored State");
+        }
+    }
+
+    #[test]
+    fn test_initial_state_special_tile_bonus() {
+        let enabled = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let disabled = Patchwork::get_initial_state(Some(GameOptions {
+            special_tile_bonus: false,
+            ..GameOptions::new(42)
+        }));
+
+        assert!(enabled.is_special_tile_bonus_enabled());
+        assert!(!disabled.is_special_tile_bonus_enabled());
+    }
+
+    #[test]
+    fn test_open_special_patches_remaining() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        assert_eq!(TimeBoard::AMOUNT_OF_SPECIAL_PATCHES, state.get_open_special_patches_remaining());
+
+        // only walk until both special patches at position 26 and 32 are passed
+        while state.player_1.get_position().max(state.player_2.get_position()) <= 32 {
+            let valid_actions = state.get_valid_actions();
+            let action = valid_actions
+                .iter()
+                .find(|action| action.is_walking())
+                .copied()
+                .unwrap_or(valid_actions[0]);
+            state.do_action(action, false).unwrap();
+        }
+        assert_eq!(TimeBoard::AMOUNT_OF_SPECIAL_PATCHES - 2, state.get_open_special_patches_remaining());
+    }
+
+    #[test]
+    fn test_initial_state_starting_buttons() {
+        let default = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        assert_eq!(PlayerState::STARTING_BUTTON_BALANCE, default.player_1.button_balance);
+        assert_eq!(PlayerState::STARTING_BUTTON_BALANCE, default.player_2.button_balance);
+
+        for starting_buttons in [0, 3, 10, 20] {
+            let state = Patchwork::get_initial_state(Some(GameOptions {
+                starting_buttons,
+                ..GameOptions::new(42)
+            }));
+
+            assert_eq!(i32::from(starting_buttons), state.player_1.button_balance);
+            assert_eq!(i32::from(starting_buttons), state.player_2.button_balance);
+        }
+    }
+
+    #[test]
+    fn test_initial_state_fully_customized() {
+        let mut patch_order: PatchOrder = std::array::from_fn(|i| i as u8 + PatchManager::AMOUNT_OF_STARTING_PATCHES);
+        patch_order.reverse();
+
+        let options = GameOptions::builder()
+            .seed(1337)
+            .special_tile_bonus(false)
+            .starting_buttons(12)
+            .patch_order(patch_order)
+            .build();
+        let state = Patchwork::get_initial_state(Some(options));
+
+        assert!(!state.is_special_tile_bonus_enabled());
+        assert_eq!(12, state.player_1.button_balance);
+        assert_eq!(12, state.player_2.button_balance);
+        assert_eq!(
+            patch_order.to_vec(),
+            state.patches.take(patch_order.len()).ids().to_vec()
+        );
+        assert_eq!(PatchManager::get_starting_patch().id, state.patches.last().unwrap().id);
+
+        // the explicit patch order takes precedence over the seed
+        let other_seed = Patchwork::get_initial_state(Some(GameOptions { seed: 42, ..options }));
+        assert_eq!(state.patches, other_seed.patches);
+    }
+
+    #[test]
+    fn test_games_with_the_same_seed_are_identical() {
+        let play = || {
+            let options = GameOptions::builder().seed(1337).starting_buttons(12).build();
+            let mut state = Patchwork::get_initial_state(Some(options));
+            let mut seed = 0;
+            while !state.is_terminated() {
+                state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+                seed += 1;
+            }
+            state
+        };
+
+        let state = play();
+        let other = play();
+        assert_eq!(state, other);
+        assert_eq!(state.get_termination_result(), other.get_termination_result());
+    }
+
+    #[test]
+    #[should_panic(expected = "occurs more than once")]
+    fn test_initial_state_rejects_invalid_patch_order() {
+        let patch_order = [1; PatchManager::AMOUNT_OF_NON_STARTING_PATCHES as usize];
+        let _ = Patchwork::get_initial_state(Some(GameOptions::builder().patch_order(patch_order).build()));
+    }
+
+    #[test]
+    fn test_actions_summary_matches_valid_actions() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut random = GameRng::seed_from_u64(42);
+
+        while !state.is_terminated() {
+            let valid_actions = state.get_valid_actions();
+            let summary = state.available_actions_summary();
+
+            let patch_placements = valid_actions.iter().filter(|action| action.is_patch_placement()).count();
+            assert_eq!(
+                patch_placements,
+                summary.patches.iter().map(|patch| patch.placements).sum::<usize>()
+            );
+            for patch in &summary.patches {
+                let placements = valid_actions
+                    .iter()
+                    .filter(|action| match patch.patch_index {
+                        0 => action.is_first_patch_taken(),
+                        1 => action.is_second_patch_taken(),
+                        _ => action.is_third_patch_taken(),
+                    })
+                    .count();
+                assert_eq!(placements, patch.placements);
+                assert_eq!(
+                    state.current_player().quilt_board.tiles_filled()
+                        + PatchManager::get_patch(patch.patch_id).amount_tiles(),
+                    patch.best_resulting_tiles
+                );
+            }
+
+            let special_patch_placements = valid_actions
+                .iter()
+                .filter(|action| action.is_special_patch_placement())
+                .count();
+            assert_eq!(special_patch_placements, summary.special_patch_placements);
+            assert_eq!(
+                valid_actions.iter().any(ActionId::is_walking),
+                summary.walking.is_some()
+            );
+
+            let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+            if let (true, Some(walking)) = (action.is_walking(), summary.walking) {
+                let balance = state.current_player().button_balance;
+                let position = state.current_player().position;
+                let player = state.get_current_player();
+                state.do_action(action, false).unwrap();
+                assert_eq!(balance + walking.buttons_gained, state.get_player(player).button_balance);
+                assert_eq!(
+                    position + walking.time_advanced,
+                    state.get_player(player).position.min(TimeBoard::MAX_POSITION)
+                );
+            } else {
+                state.do_action(action, false).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_available_special_actions_are_the_special_placements() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut random = GameRng::seed_from_u64(42);
+        let mut special_turns = 0;
+
+        while !state.is_terminated() {
+            let valid_actions = state.get_valid_actions();
+            let special_actions = state.available_special_actions();
+
+            if state.turn_type == TurnType::SpecialPatchPlacement {
+                special_turns += 1;
+                assert!(!special_actions.is_empty());
+                assert!(special_actions.iter().all(ActionId::is_special_patch_placement));
+                assert_eq!(valid_actions, special_actions);
+            } else {
+                assert!(special_actions.is_empty(), "Special actions in a {:?} turn", state.turn_type);
+            }
+
+            let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+            state.do_action(action, false).unwrap();
+        }
+
+        assert!(special_turns > 0, "No special patch was placed");
+    }
+
+    #[test]
+    fn test_expand_returns_distinct_successors_of_valid_actions() {
+        for state in crate::PositionCorpus::standard() {
+            let successors = state.expand();
+            if state.is_terminated() {
+                assert!(successors.is_empty());
+                continue;
+            }
+
+            let actions = successors.iter().map(|(action, _)| *action).collect::<Vec<_>>();
+            assert_eq!(state.get_valid_actions(), actions);
+
+            let distinct_states = successors
+                .iter()
+                .map(|(_, next_state)| next_state)
+                .collect::<HashSet<_>>();
+            assert_eq!(
+                successors.len(),
+                distinct_states.len(),
+                "Duplicate successor of {state}"
+            );
+            for (action, next_state) in &successors {
+                assert!(
+                    next_state.check_invariants().is_ok(),
+                    "Invalid successor after {action:?} of {state}"
+                );
+                // phantom actions do not change the state
+                if !action.is_phantom() {
+                    assert_ne!(&state, next_state);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_complexity_estimate_is_calibrated_on_corpus() {
+        let corpus = crate::PositionCorpus::standard();
+        let average = corpus.iter().map(Patchwork::complexity_estimate).sum::<f32>() / corpus.len() as f32;
+        assert!((0.9..=1.1).contains(&average), "Average complexity of the corpus is {average}");
+    }
+
+    #[test]
+    fn test_complexity_estimate_of_mid_game_is_higher_than_forced_move() {
+        let mut mid_game = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut random = GameRng::seed_from_u64(42);
+        while mid_game.ply < 16 || mid_game.turn_type != TurnType::Normal || mid_game.legal_move_count() < 100 {
+            let valid_actions = mid_game.get_valid_actions();
+            let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+            mid_game.do_action(action, false).unwrap();
+            assert!(!mid_game.is_terminated(), "No mid game position with high mobility was found");
+        }
+
+        // player 1 has to place a special patch on the only free tile of the quilt board
+        let forced = Patchwork::load_from_notation(
+            "1FFFFFFFFFFFFFFFFFFFEB10I0P27 000000000000000000000B5I0P20 5 Y 1/2/3",
+        )
+        .unwrap();
+        assert_eq!(1, forced.legal_move_count());
+
+        assert!(mid_game.complexity_estimate() > 1.0);
+        assert!(forced.complexity_estimate().abs() < f32::EPSILON);
+        assert!(mid_game.complexity_estimate() > forced.complexity_estimate());
+    }
+
     #[test]
     fn test_undo_redo_actions_force_swap() {
         for i in 0..ITERATIONS {
@@ -828,11 +1724,11 @@ mod tests {
             "────────────── Testing undo/redo actions with force_swap = {force_swap}, seed = {seed} ──────────────"
         );
 
-        let mut state = Patchwork::get_initial_state(Some(GameOptions { seed }));
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
 
         let mut actions = VecDeque::new();
         let mut states = VecDeque::new();
-        let mut random = Xoshiro256PlusPlus::seed_from_u64(seed);
+        let mut random = GameRng::seed_from_u64(seed);
 
         let mut iteration = 0;
 
@@ -880,6 +1776,247 @@ mod tests {
             );
         }
     }
+
+    /// Plays a seeded random game until a patch was placed on the quilt board of player 1.
+    fn get_state_with_placed_patch() -> Patchwork {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut seed = 0;
+        while state.player_1.quilt_board.placed_patch_ids().is_empty() {
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+            seed += 1;
+        }
+        state
+    }
+
+    fn assert_invalid_state(state: &Patchwork, expected_reason: &str) {
+        match state.check_invariants() {
+            Err(PatchworkError::InvalidStateError { reason, .. }) => assert!(
+                reason.contains(expected_reason),
+                "Expected reason containing '{expected_reason}' but got '{reason}'"
+            ),
+            result => panic!("Expected invalid state error but got {result:?}"),
+        }
+    }
+
+    #[test]
+    fn test_invariants_hold_during_games() {
+        for seed in 0..20 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut action_seed = seed;
+            while !state.is_terminated() {
+                assert_eq!(Ok(()), state.check_invariants());
+                state.do_action(state.get_seeded_random_action(action_seed), seed % 2 == 0).unwrap();
+                action_seed += 1;
+            }
+            assert_eq!(Ok(()), state.check_invariants());
+        }
+    }
+
+    #[test]
+    fn test_invariants_detect_negative_button_balance() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.player_2.button_balance = -1;
+        assert_invalid_state(&state, "negative button balance");
+    }
+
+    #[test]
+    fn test_invariants_detect_invalid_current_player() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.status_flags |= status_flags::BOTH_PLAYERS;
+        assert_invalid_state(&state, "current player");
+    }
+
+    #[test]
+    fn test_invariants_detect_position_not_on_time_board() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.player_1.position = 10;
+        assert_invalid_state(&state, "does not match the time board");
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.time_board.tiles[5] |= time_board_flags::PLAYER_2;
+        assert_invalid_state(&state, "not exactly once on the time board");
+    }
+
+    #[test]
+    fn test_invariants_detect_too_many_tiles() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.player_1.quilt_board.tiles |= 1 << QuiltBoard::TILES;
+        assert_invalid_state(&state, "more than 81 tiles");
+    }
+
+    #[test]
+    fn test_invariants_detect_inconsistent_special_tile() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.status_flags |= status_flags::BOTH_PLAYERS_HAVE_SPECIAL_TILE;
+        assert_invalid_state(&state, "Both players have the special tile");
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.status_flags |= status_flags::PLAYER_1_HAS_SPECIAL_TILE;
+        assert_invalid_state(&state, "without reaching it");
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.player_2.quilt_board.tiles = (1 << QuiltBoard::TILES) - 1;
+        assert_invalid_state(&state, "reached but not awarded");
+
+        state.status_flags |= status_flags::SPECIAL_TILE_BONUS_DISABLED;
+        assert_eq!(Ok(()), state.check_invariants());
+        state.status_flags |= status_flags::PLAYER_2_HAS_SPECIAL_TILE;
+        assert_invalid_state(&state, "awarded while it is disabled");
+    }
+
+    #[test]
+    fn test_invariants_detect_both_players_first_at_end() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.status_flags |= status_flags::BOTH_PLAYERS_FIRST_AT_END;
+        assert_invalid_state(&state, "Both players were first to reach the end");
+    }
+
+    #[test]
+    fn test_invariants_detect_wrong_patches() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.patches.push(state.patches.first().unwrap());
+        assert_invalid_state(&state, "more than once");
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.patches.push(PatchManager::get_special_patch(usize::from(TimeBoard::FIRST_SPECIAL_PATCH_POSITION)));
+        assert_invalid_state(&state, "not a normal patch");
+
+        let mut state = get_state_with_placed_patch();
+        assert_eq!(Ok(()), state.check_invariants());
+        let placed_patch_id = state.player_1.quilt_board.placed_patch_ids()[0];
+        state.patches.push(PatchManager::get_patch(placed_patch_id));
+        assert_invalid_state(&state, "more than once");
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "The action corrupted the state")]
+    fn test_do_action_checks_invariants_in_debug_builds() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        state.status_flags |= status_flags::BOTH_PLAYERS_FIRST_AT_END;
+        state.do_action(state.get_seeded_random_action(0), false).unwrap();
+    }
+
+    #[test]
+    fn test_validate_action_accepts_all_valid_actions() {
+        let mut random = GameRng::seed_from_u64(42);
+
+        for seed in 0..20 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+
+            while !state.is_terminated() {
+                let valid_actions = state.get_valid_actions();
+                for action in &valid_actions {
+                    assert_eq!(Ok(()), state.validate_action(*action));
+                }
+
+                assert!(state.validate_action(ActionId::null()).is_err());
+                if state.turn_type == TurnType::Normal {
+                    let position = state.current_player().position;
+                    assert!(state.validate_action(ActionId::phantom()).is_err());
+                    assert!(state.validate_action(ActionId::walking(position + 1)).is_err());
+                } else if state.turn_type == TurnType::SpecialPatchPlacement {
+                    assert!(state.validate_action(ActionId::walking(state.current_player().position)).is_err());
+                }
+
+                let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+                state.do_action_validated(action, false).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_validate_action_rejects_illegal_patch_placements() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let action = state
+            .get_valid_actions()
+            .into_iter()
+            .find(|action| {
+                action.is_patch_placement() && PatchManager::get_patch(action.get_patch_id()).button_cost > 0
+            })
+            .unwrap();
+
+        // the patch is not at the given index
+        let patch = state.patches_available_to_current_player().get(1).unwrap();
+        let wrong_index = ActionId::patch_placement(patch.id, 0, 0, state.is_player_1());
+        assert!(state.validate_action(wrong_index).is_err());
+
+        // the action was created for the other player
+        let other_player = ActionId::patch_placement(
+            action.get_patch_id(),
+            action.get_patch_index(),
+            action.get_patch_transformation_index(),
+            !state.is_player_1(),
+        );
+        assert!(state.validate_action(other_player).is_err());
+
+        // the player cannot afford the patch
+        state.current_player_mut().button_balance = 0;
+        assert!(matches!(
+            state.validate_action(action),
+            Err(PatchworkError::InvalidActionError { reason, .. }) if reason.contains("cannot afford")
+        ));
+
+        // the patch overlaps filled tiles
+        state.current_player_mut().button_balance = 100;
+        state.current_player_mut().quilt_board.tiles = (1 << QuiltBoard::TILES) - 1;
+        assert!(matches!(
+            state.validate_action(action),
+            Err(PatchworkError::InvalidActionError { reason, .. }) if reason.contains("overlaps")
+        ));
+    }
+
+    #[test]
+    fn test_do_action_validated_rejects_action_that_do_action_applies() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        assert_eq!(TurnType::Normal, state.turn_type);
+
+        // the unvalidated path trusts the caller and takes the phantom action as a no-op
+        let mut unvalidated = state.clone();
+        assert_eq!(Ok(()), unvalidated.do_action(ActionId::phantom(), false));
+
+        let mut validated = state.clone();
+        assert!(matches!(
+            validated.do_action_validated(ActionId::phantom(), false),
+            Err(PatchworkError::InvalidActionError { reason, .. }) if reason.contains("Expected walking")
+        ));
+        assert_eq!(state, validated);
+    }
+
+    #[test]
+    fn test_ply_counts_taken_actions() {
+        const ACTIONS: u32 = 20;
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut history = vec![];
+        let mut phantom_actions = 0;
+        let mut seed = 0;
+        assert_eq!(0, state.ply());
+
+        // the forced player switches create phantom actions which are not counted
+        while history.len() < (ACTIONS + phantom_actions) as usize {
+            let action = state.get_seeded_random_action(seed);
+            state.do_action(action, true).unwrap();
+            history.push(action);
+            seed += 1;
+
+            phantom_actions += u32::from(action.is_phantom());
+            assert_eq!(history.len() as u32 - phantom_actions, state.ply());
+        }
+        assert_eq!(ACTIONS, state.ply());
+        assert!(phantom_actions > 0);
+
+        // the ply is not part of the equality of the state
+        let loaded_state = Patchwork::load_from_notation(&state.save_to_notation().unwrap()).unwrap();
+        assert_eq!(0, loaded_state.ply());
+        assert_eq!(state, loaded_state);
+
+        while let Some(action) = history.pop() {
+            state.undo_action(action, true).unwrap();
+            phantom_actions -= u32::from(action.is_phantom());
+            assert_eq!(history.len() as u32 - phantom_actions, state.ply());
+        }
+    }
 }
 
 #[cfg(test)]
@@ -931,8 +2068,8 @@ mod history_tests {
                 turns: Vec::<GameTurn>::new(),
             };
 
-            let mut state = Patchwork::get_initial_state(Some(GameOptions { seed: i as u64 }));
-            let mut random = Xoshiro256PlusPlus::seed_from_u64(i as u64);
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(i as u64)));
+            let mut random = GameRng::seed_from_u64(i as u64);
 
             while !state.is_terminated() {
                 let mut valid_actions: Vec<ActionId> = state.get_valid_actions();
@@ -978,7 +2115,7 @@ mod history_tests {
         let games: Vec<Game> = bincode::deserialize_from(file).unwrap();
         for (i, game) in games.iter().enumerate() {
             println!("────────────── Replaying game {i} ──────────────");
-            let mut state = Patchwork::get_initial_state(Some(GameOptions { seed: i as u64 }));
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(i as u64)));
 
             for (j, turn) in game.turns.iter().enumerate() {
                 println!("────────────── Replaying turn {j} ──────────────");
@@ -1081,8 +2218,8 @@ mod record_tests {
                 turns: Vec::<GameTurn>::new(),
             };
 
-            let mut state = Patchwork::get_initial_state(Some(GameOptions { seed: i as u64 }));
-            let mut random = Xoshiro256PlusPlus::seed_from_u64(i as u64);
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(i as u64)));
+            let mut random = GameRng::seed_from_u64(i as u64);
 
             while !state.is_terminated() {
                 let mut valid_actions: Vec<ActionId> = state.get_valid_actions();
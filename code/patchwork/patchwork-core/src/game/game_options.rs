@@ -1,6 +1,138 @@
+use crate::{PatchManager, PlayerState, QuiltBoard, ScoringRules, ScoringRulesRef};
+
+/// The ids of all non-starting patches in clockwise order around the time
+/// board, starting with the patch directly after the neutral token.
+pub type PatchOrder = [u8; PatchManager::AMOUNT_OF_NON_STARTING_PATCHES as usize];
+
 /// Options for creating a new game of patchwork.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub struct GameOptions {
     /// The seed to use for the random number generator.
     pub seed: u64,
+    /// Whether the player that first fills a 7x7 area on their quilt board is
+    /// awarded the special tile (worth 7 points). Some variants omit this rule.
+    #[serde(default = "GameOptions::default_special_tile_bonus")]
+    pub special_tile_bonus: bool,
+    /// The amount of buttons each player starts the game with.
+    #[serde(default = "GameOptions::default_starting_buttons")]
+    pub starting_buttons: u8,
+    /// An explicit order of the patches around the time board. If set, the
+    /// seed is not used to shuffle the patches. The starting patch is always
+    /// placed last.
+    #[serde(default)]
+    pub patch_order: Option<PatchOrder>,
+    /// The points every empty tile on the quilt board is worth at the end of
+    /// the game. Used by the termination scoring and all evaluators.
+    #[serde(default = "GameOptions::default_empty_tile_penalty")]
+    pub empty_tile_penalty: i32,
+    /// The rules how the players are scored at the end of the game. The
+    /// scoring rules are not serialized (see [`ScoringRulesRef`]).
+    #[serde(skip)]
+    pub scoring_rules: ScoringRulesRef,
+}
+
+impl GameOptions {
+    /// Creates new [`GameOptions`] with the given seed and the default rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed to use for the random number generator.
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            special_tile_bonus: Self::default_special_tile_bonus(),
+            starting_buttons: Self::default_starting_buttons(),
+            patch_order: None,
+            empty_tile_penalty: Self::default_empty_tile_penalty(),
+            scoring_rules: ScoringRulesRef::STANDARD,
+        }
+    }
+
+    /// Creates a [`GameOptionsBuilder`] starting from the default rules and a
+    /// random seed.
+    #[must_use]
+    pub fn builder() -> GameOptionsBuilder {
+        GameOptionsBuilder {
+            options: Self::default(),
+        }
+    }
+
+    /// The special tile bonus is part of the default rules.
+    const fn default_special_tile_bonus() -> bool {
+        true
+    }
+
+    /// Each player starts with 5 buttons in the default rules.
+    const fn default_starting_buttons() -> u8 {
+        PlayerState::STARTING_BUTTON_BALANCE as u8
+    }
+
+    /// Every empty tile costs 2 points in the default rules.
+    const fn default_empty_tile_penalty() -> i32 {
+        QuiltBoard::EMPTY_TILE_PENALTY
+    }
+}
+
+impl Default for GameOptions {
+    fn default() -> Self {
+        Self::new(rand::random())
+    }
+}
+
+/// A builder for [`GameOptions`] to conveniently customize the rules of a game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GameOptionsBuilder {
+    options: GameOptions,
+}
+
+impl GameOptionsBuilder {
+    /// Sets the seed to use for the random number generator.
+    #[must_use]
+    pub const fn seed(mut self, seed: u64) -> Self {
+        self.options.seed = seed;
+        self
+    }
+
+    /// Sets whether the special tile (worth 7 points) is awarded.
+    #[must_use]
+    pub const fn special_tile_bonus(mut self, special_tile_bonus: bool) -> Self {
+        self.options.special_tile_bonus = special_tile_bonus;
+        self
+    }
+
+    /// Sets the amount of buttons each player starts the game with.
+    #[must_use]
+    pub const fn starting_buttons(mut self, starting_buttons: u8) -> Self {
+        self.options.starting_buttons = starting_buttons;
+        self
+    }
+
+    /// Sets an explicit order of the patches around the time board.
+    #[must_use]
+    pub const fn patch_order(mut self, patch_order: PatchOrder) -> Self {
+        self.options.patch_order = Some(patch_order);
+        self
+    }
+
+    /// Sets the points every empty tile on the quilt board is worth at the end
+    /// of the game.
+    #[must_use]
+    pub const fn empty_tile_penalty(mut self, empty_tile_penalty: i32) -> Self {
+        self.options.empty_tile_penalty = empty_tile_penalty;
+        self
+    }
+
+    /// Sets the rules how the players are scored at the end of the game.
+    #[must_use]
+    pub const fn scoring_rules(mut self, scoring_rules: &'static dyn ScoringRules) -> Self {
+        self.options.scoring_rules = ScoringRulesRef(scoring_rules);
+        self
+    }
+
+    /// Builds the [`GameOptions`].
+    #[must_use]
+    pub const fn build(self) -> GameOptions {
+        self.options
+    }
 }
@@ -0,0 +1,311 @@
+use crate::{ActionId, Notation, Patchwork, PatchworkError};
+
+/// A recorded game of patchwork.
+///
+/// The record consists of the state the game was started from and all the
+/// actions that were taken in that game in order. Replaying all the actions
+/// on the initial state results in the final state of the game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct GameRecord {
+    /// The state the recorded game was started from.
+    pub initial_state: Patchwork,
+    /// The actions that were taken in the game in the order they were taken.
+    pub actions: Vec<ActionId>,
+}
+
+impl GameRecord {
+    /// Creates a new empty [`GameRecord`] starting at the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `initial_state` - The state the recorded game starts from.
+    ///
+    /// # Returns
+    ///
+    /// The new game record without any actions.
+    #[must_use]
+    pub const fn new(initial_state: Patchwork) -> Self {
+        Self {
+            initial_state,
+            actions: vec![],
+        }
+    }
+
+    /// Appends the given action to the record.
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - The action that was taken.
+    pub fn push(&mut self, action: ActionId) {
+        self.actions.push(action);
+    }
+
+    /// Returns the amount of actions (plies) in the record.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Returns if the record does not contain any actions.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Replays all the actions of the record on the initial state.
+    ///
+    /// # Returns
+    ///
+    /// The state after all recorded actions were taken or an error if one of
+    /// the recorded actions could not be taken.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of recorded actions.
+    pub fn replay(&self) -> Result<Patchwork, PatchworkError> {
+        let mut state = self.initial_state.clone();
+        for action in &self.actions {
+            state.do_action(*action, false)?;
+        }
+        Ok(state)
+    }
+}
+
+impl Notation for GameRecord {
+    /// Saves the game record as a string.
+    ///
+    /// # Record Representation
+    ///
+    /// The first line contains the notation of the initial state. Every
+    /// following line contains the notation of a single action in the order
+    /// the actions were taken.
+    fn save_to_notation(&self) -> Result<String, PatchworkError> {
+        let mut lines = vec![self.initial_state.save_to_notation()?];
+        for action in &self.actions {
+            lines.push(action.save_to_notation()?);
+        }
+        Ok(lines.join("\n"))
+    }
+
+    /// Loads the game record from a string.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    fn load_from_notation(notation: &str) -> Result<Self, PatchworkError> {
+        let mut lines = notation
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        let initial_state = lines.next().ok_or_else(|| PatchworkError::InvalidNotationError {
+            notation: notation.to_string(),
+            reason: "[GameRecord::load_from_notation] Missing initial state!",
+        })?;
+        let initial_state = Patchwork::load_from_notation(initial_state)?;
+
+        let actions = lines.map(ActionId::load_from_notation).collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self { initial_state, actions })
+    }
+}
+
+/// A step-through replay of a [`GameRecord`].
+///
+/// The replay keeps track of the current ply and allows stepping forward and
+/// backward through the recorded actions. Stepping backward uses
+/// [`Patchwork::undo_action`] so no intermediate states have to be stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameReplay {
+    /// The record that is replayed.
+    record: GameRecord,
+    /// The state after the first `ply` actions were taken.
+    state: Patchwork,
+    /// The amount of actions that were already taken.
+    ply: usize,
+}
+
+impl GameReplay {
+    /// Creates a new [`GameReplay`] positioned at the initial state of the
+    /// given record.
+    ///
+    /// # Arguments
+    ///
+    /// * `record` - The record to replay.
+    #[must_use]
+    pub fn new(record: GameRecord) -> Self {
+        Self {
+            state: record.initial_state.clone(),
+            record,
+            ply: 0,
+        }
+    }
+
+    /// Returns the record that is replayed.
+    #[inline]
+    #[must_use]
+    pub const fn record(&self) -> &GameRecord {
+        &self.record
+    }
+
+    /// Returns the state at the current ply.
+    #[inline]
+    #[must_use]
+    pub const fn state(&self) -> &Patchwork {
+        &self.state
+    }
+
+    /// Returns the amount of actions that were taken to reach the current state.
+    #[inline]
+    #[must_use]
+    pub const fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// Returns the action that will be taken by the next step forward if any.
+    #[inline]
+    #[must_use]
+    pub fn next_action(&self) -> Option<ActionId> {
+        self.record.actions.get(self.ply).copied()
+    }
+
+    /// Returns the action that was taken to reach the current state if any.
+    #[inline]
+    #[must_use]
+    pub fn previous_action(&self) -> Option<ActionId> {
+        self.ply.checked_sub(1).map(|ply| self.record.actions[ply])
+    }
+
+    /// Takes the next recorded action.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an action was taken, `false` if the replay is already at the
+    /// end of the record.
+    pub fn step_forward(&mut self) -> Result<bool, PatchworkError> {
+        let Some(action) = self.next_action() else {
+            return Ok(false);
+        };
+
+        self.state.do_action(action, false)?;
+        self.ply += 1;
+        Ok(true)
+    }
+
+    /// Undoes the previously taken recorded action.
+    ///
+    /// # Returns
+    ///
+    /// `true` if an action was undone, `false` if the replay is already at the
+    /// start of the record.
+    pub fn step_backward(&mut self) -> Result<bool, PatchworkError> {
+        let Some(action) = self.previous_action() else {
+            return Ok(false);
+        };
+
+        self.state.undo_action(action, false)?;
+        self.ply -= 1;
+        Ok(true)
+    }
+
+    /// Steps forward or backward until the given ply is reached. Plies beyond
+    /// the end of the record are clamped to the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `ply` - The ply to go to.
+    pub fn go_to(&mut self, ply: usize) -> Result<(), PatchworkError> {
+        let ply = ply.min(self.record.len());
+        while self.ply < ply {
+            self.step_forward()?;
+        }
+        while self.ply > ply {
+            self.step_backward()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::GameOptions;
+
+    fn record_random_game(seed: u64) -> GameRecord {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+        let mut record = GameRecord::new(state.clone());
+        let mut random = Xoshiro256PlusPlus::seed_from_u64(seed);
+
+        while !state.is_terminated() {
+            let valid_actions = state.get_valid_actions();
+            let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+            state.do_action(action, false).unwrap();
+            record.push(action);
+        }
+
+        record
+    }
+
+    #[test]
+    fn test_step_forward_then_back_restores_state() {
+        for seed in 0..100 {
+            let mut replay = GameReplay::new(record_random_game(seed));
+
+            while replay.next_action().is_some() {
+                let previous_state = replay.state().clone();
+                let previous_ply = replay.ply();
+
+                assert!(replay.step_forward().unwrap());
+                assert!(replay.step_backward().unwrap());
+
+                assert_eq!(previous_ply, replay.ply());
+                assert_eq!(
+                    &previous_state,
+                    replay.state(),
+                    "Stepping forward and back changed the state"
+                );
+
+                replay.step_forward().unwrap();
+            }
+
+            assert_eq!(replay.record().replay().unwrap(), *replay.state());
+            assert!(!replay.step_forward().unwrap());
+
+            replay.go_to(0).unwrap();
+            assert_eq!(replay.record().initial_state, *replay.state());
+            assert!(!replay.step_backward().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_notation_roundtrip() {
+        let record = record_random_game(42);
+        let notation = record.save_to_notation().unwrap();
+        let loaded = GameRecord::load_from_notation(&notation).unwrap();
+
+        assert_eq!(record, loaded);
+    }
+
+    #[test]
+    fn test_serde_roundtrip() {
+        let record = record_random_game(42);
+        let json = serde_json::to_string(&record).unwrap();
+        let loaded: GameRecord = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(record, loaded);
+        assert_eq!(record.replay().unwrap(), loaded.replay().unwrap());
+    }
+
+    #[test]
+    fn test_serde_rejects_invalid_actions() {
+        let record = GameRecord::new(Patchwork::get_initial_state(Some(GameOptions::new(42))));
+        let mut json = serde_json::to_value(&record).unwrap();
+        json["actions"] = serde_json::json!([{ "Walking": { "starting_index": 100 } }]);
+
+        assert!(serde_json::from_value::<GameRecord>(json).is_err());
+    }
+}
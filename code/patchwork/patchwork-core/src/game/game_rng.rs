@@ -0,0 +1,415 @@
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rand::{RngCore, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
+
+use crate::ActionId;
+
+/// A single draw of a [`GameRng`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum RngDraw {
+    /// A drawn `u32`.
+    U32(u32),
+    /// A drawn `u64`.
+    U64(u64),
+    /// Drawn bytes.
+    Bytes(Vec<u8>),
+}
+
+/// All draws of every [`GameRng`] on a thread while logging was enabled (see
+/// [`GameRng::start_logging`]). The log can be fed back with
+/// [`GameRng::start_replay`] to deterministically reproduce a run.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct RngLog {
+    /// The draws in the order they were made.
+    pub draws: Vec<RngDraw>,
+}
+
+enum RngMode {
+    Normal,
+    Logging(Vec<RngDraw>),
+    Replay(VecDeque<RngDraw>),
+}
+
+thread_local! {
+    static RNG_MODE: RefCell<RngMode> = const { RefCell::new(RngMode::Normal) };
+    static THREAD_RNG: RefCell<GameRng> = RefCell::new(GameRng::from_entropy());
+}
+
+/// The amount of threads that currently log or replay draws. This allows the
+/// draws to skip the thread local mode if no thread uses it.
+static THREADS_WITH_RNG_MODE: AtomicUsize = AtomicUsize::new(0);
+
+/// The random number generator used by all randomized components (random
+/// players, rollouts, MCTS, ...).
+///
+/// All components share this generator so that seeding works the same
+/// everywhere and results with the same seed are reproducible across crates.
+/// The generator wraps a fast non-cryptographic PRNG. Changing the wrapped
+/// generator changes all seeded sequences.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameRng(Xoshiro256PlusPlus);
+
+impl GameRng {
+    /// Starts logging all draws of every [`GameRng`] on the current thread.
+    ///
+    /// This is a debug mode to catch nondeterministic bugs. A run that is
+    /// logged can later be reproduced with [`GameRng::start_replay`] even if
+    /// the generators were seeded from entropy. Randomness that does not come
+    /// from a [`GameRng`] (e.g. `rand::thread_rng`) is not logged.
+    ///
+    /// # Panics
+    ///
+    /// If the current thread already logs or replays draws.
+    pub fn start_logging() {
+        Self::set_mode(RngMode::Logging(vec![]));
+    }
+
+    /// Stops logging the draws on the current thread. This can also be called
+    /// after a panic was caught to get the draws until the panic.
+    ///
+    /// # Returns
+    ///
+    /// The log of all draws since [`GameRng::start_logging`] or `None` if the
+    /// current thread did not log draws.
+    #[must_use]
+    pub fn stop_logging() -> Option<RngLog> {
+        match Self::reset_mode() {
+            RngMode::Logging(draws) => Some(RngLog { draws }),
+            mode => {
+                Self::set_mode(mode);
+                None
+            }
+        }
+    }
+
+    /// Starts replaying the given log on the current thread. Every draw of
+    /// every [`GameRng`] on the current thread returns the next draw of the log
+    /// instead of generating a new value.
+    ///
+    /// # Arguments
+    ///
+    /// * `log` - The log to replay.
+    ///
+    /// # Panics
+    ///
+    /// If the current thread already logs or replays draws. While replaying,
+    /// a draw panics if it differs in kind from the next logged draw or if
+    /// the log is exhausted, i.e. the replayed run diverged from the logged run.
+    pub fn start_replay(log: RngLog) {
+        Self::set_mode(RngMode::Replay(log.draws.into()));
+    }
+
+    /// Stops replaying the log on the current thread.
+    ///
+    /// # Returns
+    ///
+    /// The amount of logged draws that were not replayed or `None` if the
+    /// current thread did not replay a log.
+    #[must_use]
+    pub fn stop_replay() -> Option<usize> {
+        match Self::reset_mode() {
+            RngMode::Replay(draws) => Some(draws.len()),
+            mode => {
+                Self::set_mode(mode);
+                None
+            }
+        }
+    }
+
+    /// Calls the given function with the generator of the current thread. The
+    /// generator is seeded from entropy once per thread.
+    ///
+    /// This replaces `rand::thread_rng` for randomness that is not seeded, so
+    /// that it can still be logged and replayed (see
+    /// [`GameRng::start_logging`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - The function to call with the generator.
+    ///
+    /// # Returns
+    ///
+    /// The result of the function.
+    ///
+    /// # Panics
+    ///
+    /// If the function calls this function again.
+    pub fn with_thread_rng<R>(f: impl FnOnce(&mut Self) -> R) -> R {
+        THREAD_RNG.with_borrow_mut(f)
+    }
+
+    /// Seeds the generator of the current thread (see
+    /// [`GameRng::with_thread_rng`]). This makes the randomness that is not
+    /// seeded otherwise (e.g. [`crate::Patchwork::random_rollout`])
+    /// reproducible on the current thread.
+    ///
+    /// # Arguments
+    ///
+    /// * `seed` - The seed of the generator.
+    pub fn seed_thread_rng(seed: u64) {
+        THREAD_RNG.set(Self::seed_from_u64(seed));
+    }
+
+    /// Whether the action is preferred over another equally rated action.
+    /// With a seed the tie is broken by a reproducible pseudo random key of
+    /// the actions, which does not depend on the order the actions are
+    /// compared in and needs no generator that has to be carried through a
+    /// search. Without a seed the tie is broken randomly.
+    ///
+    /// # Arguments
+    ///
+    /// * `tie_breaking_seed` - The seed to break the tie with. `None` breaks the tie randomly.
+    /// * `action` - The action that may be preferred.
+    /// * `other` - The other action.
+    ///
+    /// # Returns
+    ///
+    /// Whether the action is preferred over the other action.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn wins_tie(tie_breaking_seed: Option<u64>, action: ActionId, other: ActionId) -> bool {
+        tie_breaking_seed.map_or_else(rand::random, |seed| {
+            Self::tie_breaking_key(seed, action) > Self::tie_breaking_key(seed, other)
+        })
+    }
+
+    const fn tie_breaking_key(seed: u64, action: ActionId) -> u64 {
+        // SplitMix64 finalizer
+        let mut key = seed ^ (action.as_bits() as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        key = (key ^ (key >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        key = (key ^ (key >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        key ^ (key >> 31)
+    }
+
+    fn set_mode(mode: RngMode) {
+        if matches!(mode, RngMode::Normal) {
+            return;
+        }
+
+        RNG_MODE.with_borrow_mut(|current_mode| {
+            assert!(
+                matches!(current_mode, RngMode::Normal),
+                "[GameRng::set_mode] The current thread already logs or replays draws"
+            );
+            *current_mode = mode;
+        });
+        THREADS_WITH_RNG_MODE.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn reset_mode() -> RngMode {
+        let mode = RNG_MODE.with_borrow_mut(|current_mode| std::mem::replace(current_mode, RngMode::Normal));
+        if !matches!(mode, RngMode::Normal) {
+            THREADS_WITH_RNG_MODE.fetch_sub(1, Ordering::Relaxed);
+        }
+        mode
+    }
+
+    /// Whether any thread logs or replays draws. Otherwise the draws can skip
+    /// the mode of the current thread.
+    #[inline]
+    fn is_intercepted() -> bool {
+        THREADS_WITH_RNG_MODE.load(Ordering::Relaxed) != 0
+    }
+
+    /// Draws with the given function and logs the draw or replaces it with the
+    /// next logged draw depending on the mode of the current thread.
+    fn draw(&mut self, generate: impl FnOnce(&mut Xoshiro256PlusPlus) -> RngDraw) -> RngDraw {
+        RNG_MODE.with_borrow_mut(|mode| match mode {
+            RngMode::Normal => generate(&mut self.0),
+            RngMode::Logging(draws) => {
+                let draw = generate(&mut self.0);
+                draws.push(draw.clone());
+                draw
+            }
+            RngMode::Replay(draws) => draws
+                .pop_front()
+                .expect("[GameRng::draw] The replayed run made more draws than the logged run"),
+        })
+    }
+}
+
+impl RngCore for GameRng {
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        if !Self::is_intercepted() {
+            return self.0.next_u32();
+        }
+
+        match self.draw(|rng| RngDraw::U32(rng.next_u32())) {
+            RngDraw::U32(value) => value,
+            draw => panic!("[GameRng::next_u32] The replayed run diverged, the logged draw was {draw:?}"),
+        }
+    }
+
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        if !Self::is_intercepted() {
+            return self.0.next_u64();
+        }
+
+        match self.draw(|rng| RngDraw::U64(rng.next_u64())) {
+            RngDraw::U64(value) => value,
+            draw => panic!("[GameRng::next_u64] The replayed run diverged, the logged draw was {draw:?}"),
+        }
+    }
+
+    #[inline]
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        if !Self::is_intercepted() {
+            self.0.fill_bytes(dest);
+            return;
+        }
+
+        let length = dest.len();
+        match self.draw(|rng| {
+            let mut bytes = vec![0; length];
+            rng.fill_bytes(&mut bytes);
+            RngDraw::Bytes(bytes)
+        }) {
+            RngDraw::Bytes(bytes) if bytes.len() == dest.len() => dest.copy_from_slice(&bytes),
+            draw => panic!("[GameRng::fill_bytes] The replayed run diverged, the logged draw was {draw:?}"),
+        }
+    }
+
+    #[inline]
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        if !Self::is_intercepted() {
+            return self.0.try_fill_bytes(dest);
+        }
+
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl SeedableRng for GameRng {
+    type Seed = <Xoshiro256PlusPlus as SeedableRng>::Seed;
+
+    #[inline]
+    fn from_seed(seed: Self::Seed) -> Self {
+        Self(Xoshiro256PlusPlus::from_seed(seed))
+    }
+
+    #[inline]
+    fn seed_from_u64(state: u64) -> Self {
+        Self(Xoshiro256PlusPlus::seed_from_u64(state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rand::Rng;
+
+    use super::*;
+
+    #[test]
+    fn test_seeded_sequence_is_fixed() {
+        let mut rng = GameRng::seed_from_u64(42);
+        let sequence = (0..5).map(|_| rng.next_u64()).collect::<Vec<_>>();
+
+        // Changing these values breaks the reproducibility of all seeded
+        // games, rollouts and players.
+        assert_eq!(
+            vec![
+                15_021_278_609_987_233_951,
+                5_881_210_131_331_364_753,
+                18_149_643_915_985_481_100,
+                12_933_668_939_759_105_464,
+                14_637_574_242_682_825_331,
+            ],
+            sequence
+        );
+    }
+
+    #[test]
+    fn test_same_seed_is_reproducible() {
+        let mut rng_1 = GameRng::seed_from_u64(1337);
+        let mut rng_2 = GameRng::seed_from_u64(1337);
+
+        for _ in 0..100 {
+            assert_eq!(rng_1.gen_range(0..1000), rng_2.gen_range(0..1000));
+        }
+    }
+
+    fn play_game(seed: u64) -> crate::GameRecord {
+        let mut state = crate::Patchwork::get_initial_state(Some(crate::GameOptions::new(seed)));
+        let mut game = crate::GameRecord::new(state.clone());
+        let mut rng = GameRng::seed_from_u64(seed);
+
+        while !state.is_terminated() {
+            let valid_actions = state.get_valid_actions();
+            let action = valid_actions[rng.gen_range(0..valid_actions.len())];
+            state.do_action(action, false).unwrap();
+            game.push(action);
+        }
+
+        game
+    }
+
+    #[test]
+    fn test_replayed_log_reproduces_game() {
+        GameRng::start_logging();
+        let game = play_game(42);
+        let log = GameRng::stop_logging().unwrap();
+        assert!(!log.draws.is_empty());
+        assert_eq!(None, GameRng::stop_logging());
+
+        // the seed is ignored as every draw comes from the log
+        GameRng::start_replay(log);
+        let replayed_game = play_game(7);
+        assert_eq!(Some(0), GameRng::stop_replay());
+
+        assert_eq!(game, replayed_game);
+        assert_ne!(game, play_game(7));
+    }
+
+    #[test]
+    fn test_thread_rng_is_logged() {
+        GameRng::start_logging();
+        let value = GameRng::with_thread_rng(RngCore::next_u64);
+        let log = GameRng::stop_logging().unwrap();
+
+        GameRng::start_replay(log);
+        assert_eq!(value, GameRng::with_thread_rng(RngCore::next_u64));
+        assert_eq!(Some(0), GameRng::stop_replay());
+    }
+
+    #[test]
+    fn test_seeded_thread_rng_is_reproducible() {
+        GameRng::seed_thread_rng(42);
+        let first = GameRng::with_thread_rng(RngCore::next_u64);
+        GameRng::seed_thread_rng(42);
+
+        assert_eq!(first, GameRng::with_thread_rng(RngCore::next_u64));
+        assert_eq!(GameRng::seed_from_u64(42).next_u64(), first);
+    }
+
+    #[test]
+    #[should_panic(expected = "more draws than the logged run")]
+    fn test_diverged_replay_panics() {
+        /// Stops the replay when the test panics so that the count of threads
+        /// with a mode is reset for the other tests.
+        struct ReplayGuard;
+
+        impl Drop for ReplayGuard {
+            fn drop(&mut self) {
+                let _ = GameRng::stop_replay();
+            }
+        }
+
+        GameRng::start_replay(RngLog { draws: vec![RngDraw::U64(1)] });
+        let _guard = ReplayGuard;
+        let mut rng = GameRng::seed_from_u64(42);
+        rng.next_u64();
+        rng.next_u64();
+    }
+}
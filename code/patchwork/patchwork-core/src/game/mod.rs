@@ -1,4 +1,20 @@
+mod actions_summary;
+mod csv_game_observer;
 mod game_implementation;
 mod game_options;
+mod game_record;
+mod game_rng;
+mod numbered_actions;
+mod position_corpus;
+mod scoring_rules;
+mod value_target;
 
-pub use game_options::GameOptions;
+pub use actions_summary::{ActionsSummary, PatchOptionSummary, WalkingSummary};
+pub use csv_game_observer::CsvGameObserver;
+pub use game_options::{GameOptions, GameOptionsBuilder, PatchOrder};
+pub use game_record::{GameRecord, GameReplay};
+pub use game_rng::{GameRng, RngDraw, RngLog};
+pub use numbered_actions::NumberedActions;
+pub use position_corpus::PositionCorpus;
+pub use scoring_rules::{ScoringRules, ScoringRulesRef, StandardScoringRules};
+pub use value_target::{compute_value_target, compute_value_targets, get_value_target, VALUE_TARGET_SCALE};
@@ -0,0 +1,129 @@
+use std::fmt::Display;
+
+use crate::{ActionId, Notation, Patchwork};
+
+/// The valid actions of a state numbered for the selection by a human (see
+/// [`Patchwork::display_numbered_actions`]).
+///
+/// The actions are numbered starting at 1 in the order of
+/// [`Patchwork::get_valid_actions`]. Displaying the numbered actions lists
+/// every action on its own line together with its notation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NumberedActions {
+    /// The valid actions in the order they are numbered.
+    actions: Vec<ActionId>,
+}
+
+impl NumberedActions {
+    /// Creates the numbered actions for the valid actions of the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state to number the valid actions of.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    #[must_use]
+    pub fn new(state: &Patchwork) -> Self {
+        Self { actions: state.get_valid_actions() }
+    }
+
+    /// Gets the action with the given number.
+    ///
+    /// # Arguments
+    ///
+    /// * `number` - The number of the action as displayed (starting at 1).
+    ///
+    /// # Returns
+    ///
+    /// The action with the given number or `None` if there is no action with
+    /// this number.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn get_action(&self, number: usize) -> Option<ActionId> {
+        number.checked_sub(1).and_then(|index| self.actions.get(index)).copied()
+    }
+
+    /// Gets the amount of numbered actions.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.actions.len()
+    }
+
+    /// Whether there are no numbered actions.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.actions.is_empty()
+    }
+
+    /// Gets an iterator over the numbers and the actions.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, ActionId)> + '_ {
+        self.actions.iter().enumerate().map(|(index, action)| (index + 1, *action))
+    }
+}
+
+impl Display for NumberedActions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = self.len().to_string().len();
+
+        for (number, action) in self.iter() {
+            let notation = action.save_to_notation().unwrap_or_else(|_| "Not Displayable".to_string());
+            writeln!(f, "{number:>width$}: {notation}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{GameOptions, TurnType};
+
+    use super::*;
+
+    #[test]
+    fn test_numbered_listing_maps_back_to_valid_actions() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut seed = 0;
+        let mut special_patch_placement_seen = false;
+
+        while !state.is_terminated() {
+            special_patch_placement_seen |= state.turn_type == TurnType::SpecialPatchPlacement;
+
+            let valid_actions = state.get_valid_actions();
+            let numbered_actions = state.display_numbered_actions();
+            let listing = numbered_actions.to_string();
+
+            assert_eq!(valid_actions.len(), listing.lines().count());
+            assert_eq!(None, numbered_actions.get_action(0));
+            assert_eq!(None, numbered_actions.get_action(valid_actions.len() + 1));
+
+            for (line, action) in listing.lines().zip(&valid_actions) {
+                let (number, notation) = line.split_once(": ").unwrap();
+                let number = number.trim().parse::<usize>().unwrap();
+
+                assert_eq!(Some(*action), numbered_actions.get_action(number));
+                assert_eq!(*action, ActionId::load_from_notation(notation).unwrap());
+            }
+
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+            seed += 1;
+        }
+
+        assert!(special_patch_placement_seen);
+    }
+}
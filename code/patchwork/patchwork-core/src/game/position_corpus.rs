@@ -0,0 +1,167 @@
+use rand::{Rng, SeedableRng};
+
+use crate::{GameOptions, GameRng, Patchwork, TimeBoard, TurnType};
+
+/// A reproducible corpus of representative patchwork positions.
+///
+/// The positions are taken from random games played from a fixed seed and are
+/// spread over the phase of the game (early, mid, late), the button balance of
+/// the current player (low, high) and all turn types. This allows evaluator,
+/// action ordering and player tests to be run on the same set of positions
+/// instead of only on the initial state of a single seed.
+pub struct PositionCorpus;
+
+impl PositionCorpus {
+    /// The seed used for the default corpus.
+    pub const DEFAULT_SEED: u64 = 42;
+    /// The amount of positions in the default corpus.
+    pub const DEFAULT_SIZE: usize = 64;
+    /// The button balance from which on a balance is considered high.
+    pub const HIGH_BUTTON_BALANCE: i32 = 10;
+    /// The maximum amount of games that are played to collect positions.
+    const MAX_GAMES: u64 = 200;
+
+    /// The amount of different phases of a game.
+    const PHASES: usize = 3;
+    /// The amount of different button balance categories.
+    const BALANCES: usize = 2;
+    /// The amount of different turn types.
+    const TURN_TYPES: usize = 4;
+    /// The amount of buckets positions are sorted into.
+    const BUCKETS: usize = Self::PHASES * Self::BALANCES * Self::TURN_TYPES;
+
+    /// Generates the default corpus of [`PositionCorpus::DEFAULT_SIZE`]
+    /// positions with the seed [`PositionCorpus::DEFAULT_SEED`].
+    ///
+    /// # Returns
+    ///
+    /// The positions of the default corpus.
+    #[must_use]
+    pub fn standard() -> Vec<Patchwork> {
+        Self::generate(Self::DEFAULT_SIZE, Self::DEFAULT_SEED)
+    }
+
+    /// Generates a corpus of the given size from the given seed.
+    ///
+    /// The positions are collected from random games into buckets by game
+    /// phase, button balance and turn type. The corpus is then filled by
+    /// taking positions from all non-empty buckets in turn so that every
+    /// category is represented as long as the corpus is large enough. If a
+    /// category is rare the remaining positions are taken from the other
+    /// buckets.
+    ///
+    /// # Arguments
+    ///
+    /// * `size` - The amount of positions to generate.
+    /// * `seed` - The seed to generate the positions from.
+    ///
+    /// # Returns
+    ///
+    /// The generated positions. The same size and seed always result in the
+    /// same positions in the same order.
+    #[must_use]
+    pub fn generate(size: usize, seed: u64) -> Vec<Patchwork> {
+        let mut random = GameRng::seed_from_u64(seed);
+        let mut buckets: Vec<Vec<Patchwork>> = vec![vec![]; Self::BUCKETS];
+        let positions_per_bucket = size.div_ceil(Self::BUCKETS);
+
+        for game in 0..Self::MAX_GAMES {
+            if buckets.iter().all(|bucket| bucket.len() >= positions_per_bucket) {
+                break;
+            }
+
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed.wrapping_add(game))));
+            while !state.is_terminated() {
+                // Buckets keep collecting beyond their share so that buckets of
+                // rare categories can be compensated by the other buckets.
+                let bucket = &mut buckets[Self::get_bucket(&state)];
+                if bucket.len() < size {
+                    bucket.push(state.clone());
+                }
+
+                let valid_actions = state.get_valid_actions();
+                let action = valid_actions[random.gen_range(0..valid_actions.len())];
+                // Forcing the player switch from time to time generates the phantom turn types
+                let force_player_switch = random.gen_bool(0.25);
+                state
+                    .do_action(action, force_player_switch)
+                    .expect("[PositionCorpus::generate] Valid action could not be taken");
+            }
+        }
+
+        let mut positions = Vec::with_capacity(size);
+        let mut index = 0;
+        while positions.len() < size && buckets.iter().any(|bucket| index < bucket.len()) {
+            for bucket in &buckets {
+                if positions.len() >= size {
+                    break;
+                }
+                if let Some(position) = bucket.get(index) {
+                    positions.push(position.clone());
+                }
+            }
+            index += 1;
+        }
+        positions
+    }
+
+    /// Gets the bucket the given state belongs to.
+    fn get_bucket(state: &Patchwork) -> usize {
+        let leading_position = state.player_1.get_position().max(state.player_2.get_position());
+        let phase = usize::from(leading_position) * Self::PHASES / (usize::from(TimeBoard::MAX_POSITION) + 1);
+        let balance = usize::from(state.current_player().button_balance >= Self::HIGH_BUTTON_BALANCE);
+        let turn_type = match state.turn_type {
+            TurnType::Normal => 0,
+            TurnType::SpecialPatchPlacement => 1,
+            TurnType::NormalPhantom => 2,
+            TurnType::SpecialPhantom => 3,
+        };
+
+        (phase * Self::BALANCES + balance) * Self::TURN_TYPES + turn_type
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_corpus_is_deterministic() {
+        let corpus = PositionCorpus::standard();
+
+        assert_eq!(PositionCorpus::DEFAULT_SIZE, corpus.len());
+        assert_eq!(corpus, PositionCorpus::standard());
+        assert_eq!(
+            corpus,
+            PositionCorpus::generate(PositionCorpus::DEFAULT_SIZE, PositionCorpus::DEFAULT_SEED)
+        );
+        assert_ne!(corpus, PositionCorpus::generate(PositionCorpus::DEFAULT_SIZE, 1337));
+    }
+
+    #[test]
+    fn test_corpus_covers_all_turn_types() {
+        let corpus = PositionCorpus::standard();
+
+        for turn_type in [
+            TurnType::Normal,
+            TurnType::SpecialPatchPlacement,
+            TurnType::NormalPhantom,
+            TurnType::SpecialPhantom,
+        ] {
+            assert!(
+                corpus.iter().any(|state| state.turn_type == turn_type),
+                "Corpus does not contain a position with turn type {turn_type:?}"
+            );
+        }
+
+        assert!(corpus
+            .iter()
+            .any(|state| state.current_player().button_balance >= PositionCorpus::HIGH_BUTTON_BALANCE));
+        assert!(corpus
+            .iter()
+            .any(|state| state.current_player().button_balance < PositionCorpus::HIGH_BUTTON_BALANCE));
+        assert!(corpus.iter().all(|state| !state.is_terminated()));
+    }
+}
@@ -0,0 +1,248 @@
+use std::{
+    fmt::{Debug, Formatter},
+    hash::{Hash, Hasher},
+    ops::Deref,
+};
+
+use crate::{status_flags, Patchwork, QuiltBoard, ScoreBreakdown, TerminationType};
+
+/// The rules how the players of a game are scored at the end of the game.
+///
+/// The default implementations of the methods are the standard rules of
+/// patchwork. Rule variants only override the parts they change and are
+/// plugged into a game with [`crate::GameOptionsBuilder::scoring_rules`].
+pub trait ScoringRules: Send + Sync {
+    /// Gets the name of the rules. Rules with the same name are considered to
+    /// be the same rules.
+    fn name(&self) -> &'static str;
+
+    /// Gets the points every empty tile on the quilt board is worth.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game that is scored.
+    ///
+    /// # Returns
+    ///
+    /// The points of an empty tile. By default this is the empty tile penalty
+    /// of the game options.
+    fn empty_tile_penalty(&self, game: &Patchwork) -> i32 {
+        game.get_empty_tile_penalty()
+    }
+
+    /// Gets the points the special tile (e.g. the 7x7 tile) is worth.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game that is scored.
+    ///
+    /// # Returns
+    ///
+    /// The points of the special tile.
+    fn special_tile_bonus(&self, _game: &Patchwork) -> i32 {
+        QuiltBoard::BOARD_EXTRA_BUTTON_INCOME
+    }
+
+    /// Gets the score of the given player. By default this is the total of the
+    /// [`ScoringRules::score_breakdown`].
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game that is scored.
+    /// * `player_flag` - The player to get the score for.
+    ///
+    /// # Returns
+    ///
+    /// The score of the given player.
+    fn score(&self, game: &Patchwork, player_flag: u8) -> i32 {
+        self.score_breakdown(game, player_flag).total()
+    }
+
+    /// Gets the components the score of the given player is made up of. By
+    /// default this is the button balance, the points for the empty tiles and
+    /// the points for the special tile.
+    ///
+    /// Rules that override [`ScoringRules::score`] should override this as
+    /// well so that the components still add up to the score.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game that is scored.
+    /// * `player_flag` - The player to get the score breakdown for.
+    ///
+    /// # Returns
+    ///
+    /// The score breakdown of the given player.
+    fn score_breakdown(&self, game: &Patchwork, player_flag: u8) -> ScoreBreakdown {
+        let player = game.get_player(player_flag);
+        let has_special_tile = (Patchwork::is_flag_player_1(player_flag)
+            && game.is_special_tile_condition_reached_by_player_1())
+            || (Patchwork::is_flag_player_2(player_flag) && game.is_special_tile_condition_reached_by_player_2());
+
+        ScoreBreakdown {
+            button_balance: player.button_balance,
+            empty_tile_penalty: player.quilt_board.score_with_penalty(self.empty_tile_penalty(game)),
+            special_tile_bonus: if has_special_tile { self.special_tile_bonus(game) } else { 0 },
+        }
+    }
+
+    /// Decides the winner of a game where both players have the same score.
+    /// By default the player that was first to reach the end wins.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game that is scored.
+    ///
+    /// # Returns
+    ///
+    /// The winner of the game.
+    fn tie_break(&self, game: &Patchwork) -> TerminationType {
+        if (game.status_flags & status_flags::PLAYER_1_FIRST_AT_END) > 0 {
+            TerminationType::Player1Won
+        } else if (game.status_flags & status_flags::PLAYER_2_FIRST_AT_END) > 0 {
+            TerminationType::Player2Won
+        } else {
+            panic!("[ScoringRules::tie_break] Both players have the same score and neither was first to reach the end")
+        }
+    }
+}
+
+/// The scoring rules of standard patchwork.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StandardScoringRules;
+
+impl StandardScoringRules {
+    /// The name of the standard scoring rules.
+    pub const NAME: &'static str = "standard";
+}
+
+impl ScoringRules for StandardScoringRules {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+}
+
+/// A reference to the [`ScoringRules`] of a game.
+///
+/// References are compared and hashed by the name of the rules. As arbitrary
+/// rules cannot be deserialized they are not part of the serialized game and
+/// a deserialized game always uses the [`StandardScoringRules`].
+#[derive(Clone, Copy)]
+pub struct ScoringRulesRef(pub &'static dyn ScoringRules);
+
+impl ScoringRulesRef {
+    /// A reference to the [`StandardScoringRules`].
+    pub const STANDARD: Self = Self(&StandardScoringRules);
+}
+
+impl Default for ScoringRulesRef {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+impl Deref for ScoringRulesRef {
+    type Target = dyn ScoringRules;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl PartialEq for ScoringRulesRef {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.name() == other.0.name()
+    }
+}
+
+impl Eq for ScoringRulesRef {}
+
+impl Hash for ScoringRulesRef {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.name().hash(state);
+    }
+}
+
+impl Debug for ScoringRulesRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScoringRulesRef").field(&self.0.name()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::GameOptions;
+
+    use super::*;
+
+    /// A variant where every empty tile only costs a single point.
+    struct MinusOnePerEmptyTile;
+
+    impl ScoringRules for MinusOnePerEmptyTile {
+        fn name(&self) -> &'static str {
+            "minus-one-per-empty-tile"
+        }
+
+        fn empty_tile_penalty(&self, _game: &Patchwork) -> i32 {
+            -1
+        }
+    }
+
+    static MINUS_ONE_PER_EMPTY_TILE: MinusOnePerEmptyTile = MinusOnePerEmptyTile;
+
+    fn play_until_end(options: GameOptions) -> Patchwork {
+        let mut state = Patchwork::get_initial_state(Some(options));
+        let mut seed = 0;
+        while !state.is_terminated() {
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+            seed += 1;
+        }
+        state
+    }
+
+    #[test]
+    fn test_custom_scoring_rules_change_termination_consistently() {
+        let standard = play_until_end(GameOptions::new(42));
+        let custom = play_until_end(GameOptions::builder().seed(42).scoring_rules(&MINUS_ONE_PER_EMPTY_TILE).build());
+        let penalty = play_until_end(GameOptions::builder().seed(42).empty_tile_penalty(-1).build());
+
+        assert_eq!(StandardScoringRules::NAME, standard.get_scoring_rules().name());
+        assert_eq!("minus-one-per-empty-tile", custom.get_scoring_rules().name());
+
+        let standard_result = standard.get_termination_result();
+        let custom_result = custom.get_termination_result();
+        assert_ne!(standard_result, custom_result);
+        assert_eq!(penalty.get_termination_result(), custom_result);
+
+        for player_flag in [Patchwork::get_player_1_flag(), Patchwork::get_player_2_flag()] {
+            let player = custom.get_player(player_flag);
+            let free_tiles = player.quilt_board.tiles_free() as i32;
+            let standard_score = standard.get_score(player_flag);
+
+            assert_eq!(standard_score + free_tiles, custom.get_score(player_flag));
+        }
+    }
+
+    #[test]
+    fn test_score_breakdown_sums_to_score() {
+        for seed in 0..10 {
+            let state = play_until_end(GameOptions::new(seed));
+            let termination = state.get_termination_result();
+
+            assert_eq!(termination.player_1_score, termination.player_1_breakdown.total());
+            assert_eq!(termination.player_2_score, termination.player_2_breakdown.total());
+            assert_eq!(state.player_1.button_balance, termination.player_1_breakdown.button_balance);
+            assert_eq!(state.player_2.button_balance, termination.player_2_breakdown.button_balance);
+            assert_eq!(
+                if state.is_special_tile_condition_reached_by_player_1() { 7 } else { 0 },
+                termination.player_1_breakdown.special_tile_bonus
+            );
+            assert_eq!(
+                if state.is_special_tile_condition_reached_by_player_2() { 7 } else { 0 },
+                termination.player_2_breakdown.special_tile_bonus
+            );
+        }
+    }
+}
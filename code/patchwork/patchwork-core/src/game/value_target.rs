@@ -0,0 +1,188 @@
+use crate::{GameRecord, Termination, TerminationType};
+
+/// The margin (difference in score) at which the value target reaches
+/// `tanh(1) ≈ 0.76`.
+pub const VALUE_TARGET_SCALE: f32 = 20.0;
+
+/// Gets the value target of a finished game from the perspective of the given
+/// player.
+///
+/// This is the training target of the value heads of the different trainers
+/// so that they all learn the same kind of value.
+///
+/// The target is the `tanh`-scaled final margin. If both players have the same
+/// score, the margin is shifted half a point towards the winner so that the
+/// sign of the target always agrees with the outcome of the game.
+///
+/// # Arguments
+///
+/// * `termination` - The termination of the game.
+/// * `is_player_1` - Whether the target is from the perspective of player 1.
+///
+/// # Returns
+///
+/// The value target in `(-1, 1)`. Positive if the given player won the game.
+///
+/// # Complexity
+///
+/// `𝒪(𝟣)`
+#[must_use]
+pub fn get_value_target(termination: &Termination, is_player_1: bool) -> f32 {
+    let winner_sign = match termination.termination {
+        TerminationType::Player1Won => 1.0,
+        TerminationType::Player2Won => -1.0,
+    };
+    let margin = if termination.is_tie_on_score() {
+        0.5 * winner_sign
+    } else {
+        termination.score() as f32
+    };
+    let perspective = if is_player_1 { 1.0 } else { -1.0 };
+
+    perspective * (margin / VALUE_TARGET_SCALE).tanh()
+}
+
+/// Computes the value targets of all plies of a recorded game, each from the
+/// perspective of the player to move at that ply (see [`get_value_target`]).
+///
+/// The game is replayed once to get the termination and the player to move
+/// at every ply, from which all targets are derived in a single pass.
+///
+/// # Arguments
+///
+/// * `game` - The recorded game. It has to be terminated after all actions.
+///
+/// # Returns
+///
+/// The value targets indexed by ply, where `0` is the initial state. The
+/// targets contain one entry more than the game has actions.
+///
+/// # Panics
+///
+/// If the record cannot be replayed or the game is not terminated.
+///
+/// # Complexity
+///
+/// `𝒪(𝑛)` where `𝑛` is the amount of recorded actions.
+#[must_use]
+pub fn compute_value_targets(game: &GameRecord) -> Vec<f32> {
+    let mut state = game.initial_state.clone();
+    let mut is_player_1 = Vec::with_capacity(game.len() + 1);
+    for action in &game.actions {
+        is_player_1.push(state.is_player_1());
+        state
+            .do_action(*action, false)
+            .expect("[compute_value_targets] The game record cannot be replayed");
+    }
+    is_player_1.push(state.is_player_1());
+
+    assert!(
+        state.is_terminated(),
+        "[compute_value_targets] The game is not terminated"
+    );
+    let player_1_target = get_value_target(&state.get_termination_result(), true);
+
+    is_player_1
+        .into_iter()
+        .map(|is_player_1| if is_player_1 { player_1_target } else { -player_1_target })
+        .collect()
+}
+
+/// Computes the value target for the given ply of a recorded game from the
+/// perspective of the player to move at that ply (see
+/// [`compute_value_targets`] to get the targets of all plies at once).
+///
+/// # Arguments
+///
+/// * `game` - The recorded game. It has to be terminated after all actions.
+/// * `ply` - The ply to get the value target for. `0` is the initial state.
+///
+/// # Returns
+///
+/// The value target from the perspective of the player to move at the ply.
+///
+/// # Panics
+///
+/// If the record cannot be replayed, the game is not terminated or the ply is
+/// greater than the amount of actions in the record.
+///
+/// # Complexity
+///
+/// `𝒪(𝑛)` where `𝑛` is the amount of recorded actions.
+#[must_use]
+pub fn compute_value_target(game: &GameRecord, ply: usize) -> f32 {
+    assert!(
+        ply <= game.len(),
+        "[compute_value_target] The ply {ply} is not part of the game with {} plies",
+        game.len()
+    );
+
+    compute_value_targets(game)[ply]
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{status_flags, GameOptions, Patchwork, ScoreBreakdown};
+
+    use super::*;
+
+    fn get_game(seed: u64) -> GameRecord {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+        let mut game = GameRecord::new(state.clone());
+        let mut action_seed = seed;
+
+        while !state.is_terminated() {
+            let action = state.get_seeded_random_action(action_seed);
+            state.do_action(action, false).unwrap();
+            game.push(action);
+            action_seed += 1;
+        }
+
+        game
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_value_target_is_antisymmetric() {
+        for seed in 0..10 {
+            let game = get_game(seed);
+            let termination = game.replay().unwrap().get_termination_result();
+            let player_1_target = get_value_target(&termination, true);
+
+            assert_eq!(-player_1_target, get_value_target(&termination, false));
+            assert!(player_1_target.abs() < 1.0);
+            assert_eq!(matches!(termination.termination, TerminationType::Player1Won), player_1_target > 0.0);
+
+            let value_targets = compute_value_targets(&game);
+            assert_eq!(game.len() + 1, value_targets.len());
+
+            let mut state = game.initial_state.clone();
+            for (ply, value_target) in value_targets.into_iter().enumerate() {
+                let expected = if state.is_player_1() { player_1_target } else { -player_1_target };
+                assert_eq!(expected, compute_value_target(&game, ply));
+                assert_eq!(expected, value_target);
+
+                if ply < game.len() {
+                    state.do_action(game.actions[ply], false).unwrap();
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_value_target_of_tie_on_score_agrees_with_winner() {
+        let termination = Termination {
+            termination: TerminationType::Player2Won,
+            player_1_score: 5,
+            player_2_score: 5,
+            player_1_breakdown: ScoreBreakdown { button_balance: 5, ..Default::default() },
+            player_2_breakdown: ScoreBreakdown { button_balance: 5, ..Default::default() },
+            first_at_end: Some(status_flags::PLAYER_2),
+        };
+
+        assert!(get_value_target(&termination, true) < 0.0);
+        assert!(get_value_target(&termination, false) > 0.0);
+    }
+}
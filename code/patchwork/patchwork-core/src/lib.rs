@@ -7,6 +7,7 @@ mod patchwork;
 mod player;
 mod quilt_board;
 mod termination;
+mod thread_affinity;
 mod time_board;
 mod traits;
 
@@ -18,5 +19,6 @@ pub use crate::patchwork::*;
 pub use crate::player::*;
 pub use crate::quilt_board::*;
 pub use crate::termination::*;
+pub use crate::thread_affinity::*;
 pub use crate::time_board::*;
 pub use crate::traits::*;
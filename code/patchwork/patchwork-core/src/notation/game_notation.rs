@@ -3,14 +3,34 @@ use std::collections::HashSet;
 use lazy_static::lazy_static;
 use regex::Regex;
 
-use crate::{Notation, PatchManager, Patchwork, PatchworkError, PlayerState, QuiltBoard, TimeBoard, TurnType};
+use crate::{
+    status_flags, Notation, PatchCircle, PatchManager, Patchwork, PatchworkError, PlayerState, QuiltBoard,
+    ScoringRulesRef, TimeBoard, TurnType,
+};
 
 lazy_static! {
     static ref STATE_REGEX: Regex = Regex::new(
-        r"^(?P<player_1_quilt_board>(?:[A-Fa-f0-9]){21})B(?P<player_1_button_balance>-?\d+)I(?P<player_1_button_income>\d+)P(?P<player_1_position>\d+) (?P<player_2_quilt_board>(?:[A-Fa-f0-9]){21})B(?P<player_2_button_balance>-?\d+)I(?P<player_2_button_income>\d+)P(?P<player_2_position>\d+) (?P<status_flags>\d+) (?P<special_patch_placement_move>[NY]) (?:(?P<patches>(?:(?:\d+/)*\d+)|-))(?P<phantom> \(Phantom\))?$",
+        r"^(?P<player_1_quilt_board>(?:[A-Fa-f0-9]){21}|\?)B(?P<player_1_button_balance>-?\d+)I(?P<player_1_button_income>\d+)P(?P<player_1_position>\d+) (?P<player_2_quilt_board>(?:[A-Fa-f0-9]){21}|\?)B(?P<player_2_button_balance>-?\d+)I(?P<player_2_button_income>\d+)P(?P<player_2_position>\d+) (?P<status_flags>\d+) (?P<special_patch_placement_move>[NY]) (?:(?P<unordered_patches>\?)?(?P<patches>(?:(?:\d+/)*\d+)|-))(?P<phantom> \(Phantom\))?$",
     ).unwrap();
 }
 
+/// The parts of a state that are omitted in a partial notation (see
+/// [`Patchwork::save_to_partial_notation`]).
+///
+/// Partial notations are used to share puzzles where not all information is
+/// known or should be revealed, e.g. "set up this board and find the best
+/// move".
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NotationMask {
+    /// Whether the quilt board of player 1 is omitted.
+    pub player_1_quilt_board: bool,
+    /// Whether the quilt board of player 2 is omitted.
+    pub player_2_quilt_board: bool,
+    /// Whether the order of the patches in the patch circle is omitted. The
+    /// patches that are left are still included.
+    pub patch_order: bool,
+}
+
 // TODO: nicer display for the status flags
 impl Notation for Patchwork {
     /// Saves the state of the game as a string.
@@ -36,6 +56,18 @@ impl Notation for Patchwork {
     ///    a slash starting from the first patch the current player can take
     ///    or '-' if no patches are left
     ///
+    /// # Partial State Representation
+    ///
+    /// A partial notation (see [`Patchwork::save_to_partial_notation`]) can
+    /// also be loaded. Omitted parts are filled with the following defaults:
+    /// * A quilt board that is replaced by '?' is loaded as an empty quilt board.
+    ///   The button income of the player is kept. An empty quilt board cannot
+    ///   have reached the special tile condition, so the special tile is given
+    ///   to the other player if their quilt board reached it and is removed
+    ///   otherwise.
+    /// * A list of patches that is prefixed by '?' (e.g. ?0/1/2) is loaded in
+    ///   ascending order of the patch ids.
+    ///
     /// # Example
     ///
     /// ```
@@ -58,7 +90,9 @@ impl Notation for Patchwork {
     ///
     /// # Returns
     ///
-    /// The state of the game or an error if the state is invalid.
+    /// The state of the game or an error if the state is invalid. A state
+    /// where the special tile was awarded inconsistently with the quilt
+    /// boards is invalid as well.
     #[allow(clippy::too_many_lines)]
     fn load_from_notation(state: &str) -> Result<Self, PatchworkError> {
         let error = PatchworkError::InvalidNotationError {
@@ -77,7 +111,7 @@ impl Notation for Patchwork {
 
         let player_1_quilt_board = captures
             .name("player_1_quilt_board")
-            .and_then(|s| u128::from_str_radix(s.as_str(), 16).ok())
+            .and_then(|s| Self::parse_quilt_board_notation(s.as_str()))
             .ok_or_else(|| error.clone())?;
         let player_1_income = captures
             .name("player_1_button_balance")
@@ -94,7 +128,7 @@ impl Notation for Patchwork {
 
         let player_2_quilt_board = captures
             .name("player_2_quilt_board")
-            .and_then(|s| u128::from_str_radix(s.as_str(), 16).ok())
+            .and_then(|s| Self::parse_quilt_board_notation(s.as_str()))
             .ok_or_else(|| error.clone())?;
         let player_2_income = captures
             .name("player_2_button_balance")
@@ -109,11 +143,31 @@ impl Notation for Patchwork {
             .and_then(|s| s.as_str().parse::<u8>().ok())
             .ok_or_else(|| error.clone())?;
 
-        let status_flags = captures
+        let mut status_flags = captures
             .name("status_flags")
             .and_then(|s| s.as_str().parse::<u8>().ok())
             .ok_or_else(|| error.clone())?;
 
+        // the special tile is given again according to the quilt boards that were actually loaded
+        let is_masked = |name| captures.name(name).is_some_and(|s| s.as_str() == "?");
+        let reaches_special_tile = |tiles| {
+            QuiltBoard {
+                tiles,
+                ..QuiltBoard::new()
+            }
+            .is_special_tile_condition_reached()
+        };
+        if is_masked("player_1_quilt_board") || is_masked("player_2_quilt_board") {
+            status_flags &= !status_flags::BOTH_PLAYERS_HAVE_SPECIAL_TILE;
+            if status_flags & status_flags::SPECIAL_TILE_BONUS_DISABLED == 0 {
+                if reaches_special_tile(player_1_quilt_board) {
+                    status_flags |= status_flags::PLAYER_1_HAS_SPECIAL_TILE;
+                } else if reaches_special_tile(player_2_quilt_board) {
+                    status_flags |= status_flags::PLAYER_2_HAS_SPECIAL_TILE;
+                }
+            }
+        }
+
         let special_patch_placement_move = captures
             .name("special_patch_placement_move")
             .and_then(|s| {
@@ -131,11 +185,14 @@ impl Notation for Patchwork {
         let patches = captures
             .name("patches")
             .map(|s| {
-                let indices = s
+                let mut indices = s
                     .as_str()
                     .split('/')
                     .filter_map(|s| s.parse::<usize>().ok())
                     .collect::<Vec<_>>();
+                if captures.name("unordered_patches").is_some() {
+                    indices.sort_unstable();
+                }
 
                 let unique: HashSet<_> = indices.iter().collect();
                 if unique.len() != indices.len() {
@@ -149,7 +206,7 @@ impl Notation for Patchwork {
                 Ok(indices
                     .iter()
                     .map(|patch_id| &PatchManager::get_instance().patches[*patch_id])
-                    .collect::<Vec<_>>())
+                    .collect::<PatchCircle>())
             })
             .ok_or_else(|| error.clone())??;
 
@@ -167,7 +224,7 @@ impl Notation for Patchwork {
         time_board.move_player_position(Self::get_player_2_flag(), 0, player_2_position);
         time_board.unset_special_patches_until(further_player_position);
 
-        Ok(Self {
+        let state = Self {
             patches,
             time_board,
             player_1: PlayerState {
@@ -176,6 +233,7 @@ impl Notation for Patchwork {
                 quilt_board: QuiltBoard {
                     tiles: player_1_quilt_board,
                     button_income: player_1_button_income,
+                    ..QuiltBoard::new()
                 },
             },
             player_2: PlayerState {
@@ -184,6 +242,7 @@ impl Notation for Patchwork {
                 quilt_board: QuiltBoard {
                     tiles: player_2_quilt_board,
                     button_income: player_2_button_income,
+                    ..QuiltBoard::new()
                 },
             },
             status_flags,
@@ -192,7 +251,19 @@ impl Notation for Patchwork {
             } else {
                 TurnType::Normal
             },
-        })
+            empty_tile_penalty: QuiltBoard::EMPTY_TILE_PENALTY,
+            scoring_rules: ScoringRulesRef::STANDARD,
+            ply: 0,
+        };
+
+        state
+            .check_special_tile_invariants()
+            .map_err(|_| PatchworkError::InvalidNotationError {
+                notation: state.to_string(),
+                reason: "[Patchwork::load_from_notation] The notation describes an invalid state!",
+            })?;
+
+        Ok(state)
     }
 }
 
@@ -203,6 +274,52 @@ impl Patchwork {
     /// If the game is in a phantom state and `allow_phantom_state` is true, the notation will end with the string
     /// `(Phantom)` appended to to usual notation.
     pub fn save_to_notation_with_phantom_state(&self, allow_phantom_state: bool) -> Result<String, PatchworkError> {
+        self.save_to_masked_notation(allow_phantom_state, NotationMask::default())
+    }
+
+    /// Saves the state of the game as a partial notation where the parts
+    /// given by the mask are omitted.
+    ///
+    /// An omitted quilt board is replaced by '?' and the list of patches is
+    /// prefixed by '?' if the patch order is omitted. The partial notation can
+    /// be loaded again with `load_from_notation` which fills the omitted parts
+    /// with defaults. With an empty mask this is the same as `save_to_notation`.
+    ///
+    /// # Arguments
+    ///
+    /// * `mask` - The parts of the state to omit.
+    ///
+    /// # Returns
+    ///
+    /// The partial notation or an error if the game is in a phantom state.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use patchwork_core::{NotationMask, Notation, Patchwork};
+    ///
+    /// let state = Patchwork::get_initial_state(None);
+    /// let mask = NotationMask { player_2_quilt_board: true, ..Default::default() };
+    /// let notation = state.save_to_partial_notation(mask).unwrap();
+    ///
+    /// assert!(notation.contains(" ?B5I0P0 "));
+    /// assert_eq!(state, Patchwork::load_from_notation(&notation).unwrap());
+    /// ```
+    pub fn save_to_partial_notation(&self, mask: NotationMask) -> Result<String, PatchworkError> {
+        self.save_to_masked_notation(false, mask)
+    }
+
+    /// Parses a quilt board of the notation. An omitted quilt board ('?') is
+    /// parsed as an empty quilt board.
+    fn parse_quilt_board_notation(quilt_board: &str) -> Option<u128> {
+        if quilt_board == "?" {
+            Some(0)
+        } else {
+            u128::from_str_radix(quilt_board, 16).ok()
+        }
+    }
+
+    fn save_to_masked_notation(&self, allow_phantom_state: bool, mask: NotationMask) -> Result<String, PatchworkError> {
         if !allow_phantom_state && matches!(self.turn_type, TurnType::NormalPhantom | TurnType::SpecialPhantom) {
             return Err(PatchworkError::InvalidNotationError {
                 notation: String::new(),
@@ -219,8 +336,8 @@ impl Patchwork {
         //     d. The position on the time board
         state.push_str(
             format!(
-                "{:021X}B{:?}I{:?}P{:?} ",
-                self.player_1.quilt_board.tiles,
+                "{}B{:?}I{:?}P{:?} ",
+                Self::format_quilt_board_notation(self.player_1.quilt_board.tiles, mask.player_1_quilt_board),
                 self.player_1.button_balance,
                 self.player_1.quilt_board.button_income,
                 self.player_1.position,
@@ -231,8 +348,8 @@ impl Patchwork {
         // 2. All Information about player 2 stored the same way as player 1
         state.push_str(
             format!(
-                "{:021X}B{:?}I{:?}P{:?} ",
-                self.player_2.quilt_board.tiles,
+                "{}B{:?}I{:?}P{:?} ",
+                Self::format_quilt_board_notation(self.player_2.quilt_board.tiles, mask.player_2_quilt_board),
                 self.player_2.button_balance,
                 self.player_2.quilt_board.button_income,
                 self.player_2.position,
@@ -257,13 +374,19 @@ impl Patchwork {
         // 5. The patches still left to take - A list of patch ids separated by
         //    a slash starting from the first patch the current player can take
         //    or '-' if no patches are left
+        //    If the order is omitted the patches are sorted by id and prefixed by '?'
         if self.patches.is_empty() {
             state.push('-');
         } else {
+            let mut patch_ids = self.patches.iter().map(|patch| patch.id).collect::<Vec<_>>();
+            if mask.patch_order {
+                patch_ids.sort_unstable();
+                state.push('?');
+            }
             state.push_str(
-                self.patches
+                patch_ids
                     .iter()
-                    .map(|patch| format!("{:?}", patch.id))
+                    .map(|patch_id| format!("{patch_id:?}"))
                     .collect::<Vec<String>>()
                     .join("/")
                     .as_str(),
@@ -276,4 +399,126 @@ impl Patchwork {
 
         Ok(state)
     }
+
+    fn format_quilt_board_notation(tiles: u128, omit: bool) -> String {
+        if omit {
+            "?".to_string()
+        } else {
+            format!("{tiles:021X}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::GameOptions;
+
+    use super::*;
+
+    fn get_state(seed: u64, plies: usize) -> Patchwork {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+        for action_seed in 0..plies as u64 {
+            let action = state.get_seeded_random_action(seed + action_seed);
+            state.do_action(action, false).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn test_full_notation_round_trips() {
+        for seed in 0..10 {
+            let state = get_state(seed, 12);
+            let notation = state.save_to_notation().unwrap();
+
+            assert_eq!(notation, state.save_to_partial_notation(NotationMask::default()).unwrap());
+            assert_eq!(notation, Patchwork::load_from_notation(&notation).unwrap().save_to_notation().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_partial_notation_is_loaded_with_defaults() {
+        let state = get_state(42, 12);
+        let mask = NotationMask {
+            player_1_quilt_board: true,
+            player_2_quilt_board: false,
+            patch_order: true,
+        };
+
+        let notation = state.save_to_partial_notation(mask).unwrap();
+        assert!(notation.starts_with("?B"));
+        assert!(notation.contains(" ?"));
+
+        let partial_state = Patchwork::load_from_notation(&notation).unwrap();
+
+        // omitted quilt board is empty but the button income is kept
+        assert_eq!(0, partial_state.player_1.quilt_board.tiles);
+        assert_eq!(
+            state.player_1.quilt_board.button_income,
+            partial_state.player_1.quilt_board.button_income
+        );
+        assert_eq!(state.player_2.quilt_board.tiles, partial_state.player_2.quilt_board.tiles);
+
+        // omitted patch order is loaded in ascending order of the patch ids
+        let mut patch_ids = state.patches.iter().map(|patch| patch.id).collect::<Vec<_>>();
+        patch_ids.sort_unstable();
+        assert_eq!(
+            patch_ids,
+            partial_state.patches.iter().map(|patch| patch.id).collect::<Vec<_>>()
+        );
+
+        assert_eq!(notation, partial_state.save_to_partial_notation(mask).unwrap());
+    }
+
+    #[test]
+    fn test_moves_can_be_played_from_a_masked_state() {
+        // player 1 has the special tile
+        let state = Patchwork::load_from_notation(
+            "1FFFFFFFFFFFFFFFFFFFEB10I0P27 000000000000000000000B5I0P20 5 N 1/2/3/4/5/6",
+        )
+        .unwrap();
+        assert!(state.is_special_tile_condition_reached_by_player_1());
+
+        let mask = NotationMask {
+            player_1_quilt_board: true,
+            ..NotationMask::default()
+        };
+        let mut partial_state = Patchwork::load_from_notation(&state.save_to_partial_notation(mask).unwrap()).unwrap();
+        assert!(!partial_state.is_special_tile_condition_reached());
+        partial_state.check_invariants().unwrap();
+
+        for _ in 0..2 {
+            let action = partial_state.get_seeded_random_action(42);
+            partial_state.do_action(action, false).unwrap();
+        }
+
+        // the special tile goes to the other player if their quilt board reached it
+        let partial_state =
+            Patchwork::load_from_notation("?B10I0P27 1FFFFFFFFFFFFFFFFFFFEB5I0P20 5 N 1/2/3/4/5/6").unwrap();
+        assert!(partial_state.is_special_tile_condition_reached_by_player_2());
+        partial_state.check_invariants().unwrap();
+    }
+
+    #[test]
+    fn test_notation_of_an_invalid_state_is_rejected() {
+        // player 1 has the special tile without having reached it
+        let notation = "000000000000000000000B5I0P0 000000000000000000000B5I0P0 5 N 1/2/3";
+        assert!(Patchwork::load_from_notation(notation).is_err());
+    }
+
+    #[test]
+    fn test_invalid_partial_notation_is_rejected() {
+        let notation = "000000000000000000000B5I0P0 000000000000000000000B5I0P0 0 N 1/2/3/4/5/6/7/8/9/10/11/12/13/14/15/16/17/18/19/20/21/22/23/24/25/26/27/28/29/30/31/32/0";
+        assert!(Patchwork::load_from_notation(notation).is_ok());
+
+        for invalid in [
+            notation.replacen("000000000000000000000", "??", 1),
+            notation.replacen(" 1/", " ?1/?", 1),
+            notation.replacen(" N ", " ? ", 1),
+            notation.replacen("1/2/3", "?1/1/3", 1),
+        ] {
+            assert!(Patchwork::load_from_notation(&invalid).is_err(), "{invalid}");
+        }
+    }
 }
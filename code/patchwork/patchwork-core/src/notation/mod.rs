@@ -2,4 +2,5 @@ mod action_notation;
 mod game_notation;
 mod notation;
 
+pub use game_notation::NotationMask;
 pub use notation::Notation;
@@ -1,9 +1,12 @@
 mod patch;
+mod patch_circle;
 mod patch_manager;
 mod patch_transformation;
 
 pub use patch::Patch;
 
+pub use patch_circle::{PatchCircle, PatchCircleIter, PatchCircleSlice};
+
 pub use patch_manager::PatchManager;
 
 pub use patch_transformation::PatchTransformation;
@@ -0,0 +1,366 @@
+use std::{
+    hash::{Hash, Hasher},
+    ops::Index,
+};
+
+use crate::{Patch, PatchManager};
+
+/// The iterator over the patches of a [`PatchCircle`] or [`PatchCircleSlice`].
+pub type PatchCircleIter<'a> = std::iter::Map<std::slice::Iter<'a, u8>, fn(&u8) -> &'static Patch>;
+
+/// The patches that are still available in the game in circle order starting
+/// with the patch directly after the neutral token.
+///
+/// As all patches are `'static`, only the ids of the patches are stored in a
+/// fixed size array. This makes the patch circle [`Copy`] so that cloning a
+/// game state does not need a heap allocation for the patches.
+#[derive(Clone, Copy)]
+pub struct PatchCircle {
+    /// The ids of the patches. Only the first `len` ids are valid.
+    ids: [u8; Self::CAPACITY],
+    /// The amount of patches in the circle.
+    len: u8,
+}
+
+impl PatchCircle {
+    /// The maximum amount of patches in the circle.
+    pub const CAPACITY: usize = PatchManager::AMOUNT_OF_PATCHES as usize;
+
+    /// Creates a new empty [`PatchCircle`].
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            ids: [0; Self::CAPACITY],
+            len: 0,
+        }
+    }
+
+    /// Gets the amount of patches in the circle.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    /// Whether there are no patches left in the circle.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the ids of the patches in circle order.
+    #[inline]
+    #[must_use]
+    pub fn ids(&self) -> &[u8] {
+        &self.ids[..self.len()]
+    }
+
+    /// Gets a view of the patches in circle order.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> PatchCircleSlice<'_> {
+        PatchCircleSlice { ids: self.ids() }
+    }
+
+    /// Gets a view of the first patches in circle order.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The maximum amount of patches. Fewer patches are returned
+    ///   if the circle contains fewer patches.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub fn take(&self, amount: usize) -> PatchCircleSlice<'_> {
+        PatchCircleSlice {
+            ids: &self.ids[..self.len().min(amount)],
+        }
+    }
+
+    /// Gets the patch at the given index of the circle.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&'static Patch> {
+        self.as_slice().get(index)
+    }
+
+    /// Gets the first patch of the circle (directly after the neutral token).
+    #[inline]
+    #[must_use]
+    pub fn first(&self) -> Option<&'static Patch> {
+        self.as_slice().first()
+    }
+
+    /// Gets the last patch of the circle (directly before the neutral token).
+    #[inline]
+    #[must_use]
+    pub fn last(&self) -> Option<&'static Patch> {
+        self.as_slice().last()
+    }
+
+    /// Iterates over the patches in circle order.
+    #[inline]
+    pub fn iter(&self) -> PatchCircleIter<'_> {
+        self.as_slice().iter()
+    }
+
+    /// Appends the patch to the end of the circle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the circle is already full.
+    pub fn push(&mut self, patch: &'static Patch) {
+        assert!(self.len() < Self::CAPACITY, "[PatchCircle::push] The patch circle is full");
+        self.ids[self.len()] = patch.id;
+        self.len += 1;
+    }
+
+    /// Removes the last patch of the circle.
+    ///
+    /// # Returns
+    ///
+    /// The removed patch or `None` if the circle is empty.
+    pub fn pop(&mut self) -> Option<&'static Patch> {
+        if self.is_empty() {
+            return None;
+        }
+
+        self.len -= 1;
+        Some(PatchManager::get_patch(self.ids[self.len()]))
+    }
+
+    /// Rotates the circle in-place such that the first `amount` patches move
+    /// to the end.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of patches.
+    pub fn rotate_left(&mut self, amount: usize) {
+        let len = self.len();
+        self.ids[..len].rotate_left(amount);
+    }
+
+    /// Rotates the circle in-place such that the last `amount` patches move
+    /// to the front.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of patches.
+    pub fn rotate_right(&mut self, amount: usize) {
+        let len = self.len();
+        self.ids[..len].rotate_right(amount);
+    }
+
+    /// Shortens the circle to the first `len` patches. Has no effect if the
+    /// circle already contains fewer patches.
+    pub const fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.len = len as u8;
+        }
+    }
+
+    /// Removes all patches from the circle.
+    pub const fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl Default for PatchCircle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PartialEq for PatchCircle {
+    fn eq(&self, other: &Self) -> bool {
+        // the ids after the end of the circle are stale
+        self.ids() == other.ids()
+    }
+}
+
+impl Eq for PatchCircle {}
+
+impl Hash for PatchCircle {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.ids().hash(state);
+    }
+}
+
+impl std::fmt::Debug for PatchCircle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.ids()).finish()
+    }
+}
+
+impl FromIterator<&'static Patch> for PatchCircle {
+    fn from_iter<T: IntoIterator<Item = &'static Patch>>(iter: T) -> Self {
+        let mut circle = Self::new();
+        for patch in iter {
+            circle.push(patch);
+        }
+        circle
+    }
+}
+
+impl Index<usize> for PatchCircle {
+    type Output = Patch;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        PatchManager::get_patch(self.ids()[index])
+    }
+}
+
+impl<'a> IntoIterator for &'a PatchCircle {
+    type Item = &'static Patch;
+    type IntoIter = PatchCircleIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// A view of consecutive patches of a [`PatchCircle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PatchCircleSlice<'a> {
+    /// The ids of the patches.
+    ids: &'a [u8],
+}
+
+impl<'a> PatchCircleSlice<'a> {
+    /// Gets the amount of patches in the view.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Whether the view contains no patches.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+
+    /// Gets the ids of the patches in circle order.
+    #[inline]
+    #[must_use]
+    pub const fn ids(&self) -> &'a [u8] {
+        self.ids
+    }
+
+    /// Gets the patch at the given index of the view.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, index: usize) -> Option<&'static Patch> {
+        self.ids.get(index).map(get_patch)
+    }
+
+    /// Gets the first patch of the view.
+    #[inline]
+    #[must_use]
+    pub fn first(&self) -> Option<&'static Patch> {
+        self.ids.first().map(get_patch)
+    }
+
+    /// Gets the last patch of the view.
+    #[inline]
+    #[must_use]
+    pub fn last(&self) -> Option<&'static Patch> {
+        self.ids.last().map(get_patch)
+    }
+
+    /// Iterates over the patches in circle order.
+    #[inline]
+    pub fn iter(&self) -> PatchCircleIter<'a> {
+        self.ids.iter().map(get_patch as fn(&u8) -> &'static Patch)
+    }
+}
+
+impl Index<usize> for PatchCircleSlice<'_> {
+    type Output = Patch;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        PatchManager::get_patch(self.ids[index])
+    }
+}
+
+impl<'a> IntoIterator for &PatchCircleSlice<'a> {
+    type Item = &'static Patch;
+    type IntoIter = PatchCircleIter<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Gets the patch with the given id.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn get_patch(id: &u8) -> &'static Patch {
+    PatchManager::get_patch(*id)
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{GameOptions, Patchwork};
+
+    use super::*;
+
+    #[test]
+    fn test_patch_circle_yields_the_same_patches_as_a_vec() {
+        for seed in 0..10 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut patches = PatchManager::generate_patches(Some(seed));
+
+            while !state.is_terminated() {
+                assert_eq!(patches.len(), state.patches.len());
+                assert_eq!(patches, state.patches.iter().collect::<Vec<_>>());
+
+                let amount = patches.len().min(PatchManager::MAX_AMOUNT_OF_CHOOSABLE_TILES as usize);
+                assert_eq!(
+                    &patches[..amount],
+                    state.patches_available_to_current_player().iter().collect::<Vec<_>>()
+                );
+
+                let action = state.get_seeded_random_action(seed);
+                if action.is_patch_placement() && !action.is_special_patch_placement() {
+                    // the reference implementation of the patch circle as a vec
+                    let patch_index = action.get_patch_index() as usize;
+                    let len = patches.len();
+                    patches.rotate_left(patch_index + 1);
+                    patches.remove(len - 1);
+                }
+                state.do_action(action, false).unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn test_push_and_pop() {
+        let mut circle = PatchCircle::new();
+        assert!(circle.is_empty());
+        assert_eq!(None, circle.pop());
+
+        circle.push(PatchManager::get_patch(3));
+        circle.push(PatchManager::get_patch(1));
+        assert_eq!(&[3, 1], circle.ids());
+        assert_eq!(1, circle[1].id);
+
+        circle.rotate_right(1);
+        assert_eq!(&[1, 3], circle.ids());
+        assert_eq!(Some(PatchManager::get_patch(3)), circle.pop());
+        assert_eq!(1, circle.len());
+
+        // the stale id after the end of the circle is not compared
+        assert_eq!(std::iter::once(PatchManager::get_patch(1)).collect::<PatchCircle>(), circle);
+    }
+}
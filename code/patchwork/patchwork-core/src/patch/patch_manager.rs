@@ -1,10 +1,12 @@
 use lazy_static::lazy_static;
 use rand::prelude::*;
-use rand_xoshiro::Xoshiro256PlusPlus;
 
 use patchwork_macros::generate_patches;
 
-use crate::patch::{Patch, PatchTransformation};
+use crate::{
+    patch::{Patch, PatchTransformation},
+    GameRng,
+};
 
 pub struct PatchManager {
     /// The patches.
@@ -15,6 +17,11 @@ pub struct PatchManager {
     pub normalized_tiles: [[[u8; 5]; 3]; Self::AMOUNT_OF_PATCHES as usize],
     /// The different ways this patch can be placed on the board.
     pub transformations: [Vec<PatchTransformation>; Self::AMOUNT_OF_PATCHES as usize],
+    /// The tiles of every transformation together with the patch id and the
+    /// transformation index sorted by the tiles. Transformations of different
+    /// patches with the same tiles are only contained once with the lowest
+    /// patch id.
+    pub tiling_lookup: Vec<(u128, u8, u16)>,
 }
 
 impl PatchManager {
@@ -119,6 +126,71 @@ impl PatchManager {
         &transformations[patch_transformation_index as usize]
     }
 
+    /// Gets the amount of transformations of the patch with the given id.
+    ///
+    /// This is at most [`PatchManager::MAX_AMOUNT_OF_TRANSFORMATIONS`] but
+    /// usually less as most patches are smaller or symmetric.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch_id` - The id of the patch.
+    ///
+    /// # Returns
+    ///
+    /// The amount of transformations of the patch.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub fn transformation_count(patch_id: u8) -> usize {
+        Self::get_transformations(patch_id).len()
+    }
+
+    /// Gets the amount of transformations of all patches together.
+    ///
+    /// # Returns
+    ///
+    /// The sum of the amount of transformations of every patch.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of patches (38)
+    #[must_use]
+    pub fn total_transformations() -> usize {
+        (0..Self::AMOUNT_OF_PATCHES).map(Self::transformation_count).sum()
+    }
+
+    /// Gets the patch and the transformation that cover exactly the given
+    /// tiles of the quilt board. This is the reverse lookup of
+    /// `get_transformation(patch_id, patch_transformation_index).tiles`.
+    ///
+    /// As some patches have the same shape (e.g. all special patches), the
+    /// canonical transformation with the lowest patch id and transformation
+    /// index is returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `tiles` - The tiles of the quilt board covered by the patch.
+    ///
+    /// # Returns
+    ///
+    /// The patch id and the transformation index or `None` if no
+    /// transformation covers exactly the given tiles.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(log 𝑛)` where `𝑛` is the amount of distinct transformations.
+    #[must_use]
+    pub fn action_for_tiling(tiles: u128) -> Option<(u8, u16)> {
+        let lookup = &Self::get_instance().tiling_lookup;
+        lookup
+            .binary_search_by_key(&tiles, |(lookup_tiles, _, _)| *lookup_tiles)
+            .ok()
+            .map(|index| (lookup[index].1, lookup[index].2))
+    }
+
     ///  Generates all patches in the game (excluding special patches) and shuffles them randomly.
     ///
     /// # Arguments
@@ -144,15 +216,61 @@ impl PatchManager {
         }
 
         if let Some(seed) = seed {
-            let mut rng = Xoshiro256PlusPlus::seed_from_u64(seed);
+            let mut rng = GameRng::seed_from_u64(seed);
             patches.shuffle(&mut rng);
         } else {
-            patches.shuffle(&mut thread_rng());
+            GameRng::with_thread_rng(|rng| patches.shuffle(rng));
         }
         patches.push(Self::get_starting_patch());
         patches
     }
 
+    /// Generates all patches in the game (excluding special patches) in the
+    /// given order. The starting patch is always placed last so that the
+    /// neutral token starts between it and the first patch of the order.
+    ///
+    /// # Arguments
+    ///
+    /// * `order` - The ids of all non-starting patches in clockwise order.
+    ///
+    /// # Returns
+    ///
+    /// A list of all patches in the game (excluding special patches) in the given order.
+    ///
+    /// # Panics
+    ///
+    /// If the order is not a permutation of the ids of all non-starting patches.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of patches (33)
+    #[must_use]
+    pub fn generate_patches_in_order(
+        order: &[u8; Self::AMOUNT_OF_NON_STARTING_PATCHES as usize],
+    ) -> Vec<&'static Patch> {
+        let first_id = Self::AMOUNT_OF_STARTING_PATCHES;
+        let last_id = Self::AMOUNT_OF_STARTING_PATCHES + Self::AMOUNT_OF_NON_STARTING_PATCHES;
+
+        let mut seen = [false; Self::AMOUNT_OF_NON_STARTING_PATCHES as usize];
+        for &patch_id in order {
+            assert!(
+                (first_id..last_id).contains(&patch_id),
+                "[PatchManager::generate_patches_in_order] Patch id {patch_id} is not a non-starting patch"
+            );
+            let seen_patch = &mut seen[(patch_id - first_id) as usize];
+            assert!(
+                !*seen_patch,
+                "[PatchManager::generate_patches_in_order] Patch id {patch_id} occurs more than once"
+            );
+            *seen_patch = true;
+        }
+
+        let mut patches = Vec::with_capacity(Self::AMOUNT_OF_NORMAL_PATCHES as usize);
+        patches.extend(order.iter().map(|&patch_id| Self::get_patch(patch_id)));
+        patches.push(Self::get_starting_patch());
+        patches
+    }
+
     ///  Gets the special patch with the given index.
     ///
     /// # Remarks
@@ -701,3 +819,55 @@ lazy_static! {
         )
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_transformation_counts_match_transformations() {
+        let instance = PatchManager::get_instance();
+
+        for (patch_id, transformations) in (0..PatchManager::AMOUNT_OF_PATCHES).zip(&instance.transformations) {
+            let count = PatchManager::transformation_count(patch_id);
+            assert_eq!(transformations.len(), count);
+            assert!(count > 0 && count <= PatchManager::MAX_AMOUNT_OF_TRANSFORMATIONS as usize);
+        }
+
+        assert_eq!(
+            instance.transformations.iter().map(Vec::len).sum::<usize>(),
+            PatchManager::total_transformations()
+        );
+        assert_eq!(
+            (0..PatchManager::AMOUNT_OF_PATCHES)
+                .map(PatchManager::transformation_count)
+                .sum::<usize>(),
+            PatchManager::total_transformations()
+        );
+    }
+
+    #[test]
+    fn test_action_for_tiling_round_trips_every_transformation() {
+        for patch in &PatchManager::get_instance().patches {
+            for (index, transformation) in PatchManager::get_transformations(patch.id).iter().enumerate() {
+                let (patch_id, transformation_index) = PatchManager::action_for_tiling(transformation.tiles).unwrap();
+
+                assert_eq!(
+                    transformation.tiles,
+                    PatchManager::get_transformation(patch_id, transformation_index).tiles
+                );
+                assert!((patch_id, usize::from(transformation_index)) <= (patch.id, index));
+                if patch_id == patch.id {
+                    assert_eq!(index, usize::from(transformation_index));
+                }
+            }
+        }
+
+        assert_eq!(None, PatchManager::action_for_tiling(0));
+        assert_eq!(None, PatchManager::action_for_tiling((1 << 81) - 1));
+        // all special patches have the same shape
+        assert_eq!(Some((33, 0)), PatchManager::action_for_tiling(1));
+    }
+}
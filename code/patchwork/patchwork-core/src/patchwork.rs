@@ -1,7 +1,14 @@
-use std::{cmp::Ordering, fmt::Display};
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
 
 pub use crate::game::*;
-use crate::{Patch, PatchManager, PlayerState, QuiltBoard, Termination, TerminationType, TimeBoard};
+use crate::{
+    time_board_flags, ActionKind, PatchCircle, PatchCircleSlice, PatchManager, PlayerState, QuiltBoard, Termination,
+    TerminationType, TimeBoard,
+};
 
 /// Represents the type of turn that is currently being played.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
@@ -18,6 +25,96 @@ pub enum TurnType {
     SpecialPhantom,
 }
 
+impl TurnType {
+    /// Gets the turn types that can follow this turn type after an action of
+    /// the given kind was taken with [`Patchwork::do_action`].
+    ///
+    /// The legal transitions are:
+    ///
+    /// | Turn type               | Action kind                  | Forced switch | Next turn type                                    |
+    /// |-------------------------|------------------------------|---------------|---------------------------------------------------|
+    /// | `Normal`                | `Walking` / `PatchPlacement` | no            | `Normal`, `SpecialPatchPlacement`                 |
+    /// | `Normal`                | `Walking` / `PatchPlacement` | yes           | `Normal`, `NormalPhantom`, `SpecialPhantom`       |
+    /// | `SpecialPatchPlacement` | `SpecialPatchPlacement`      | both          | `Normal`                                          |
+    /// | `NormalPhantom`         | `Phantom`                    | no            | `NormalPhantom` (no-op)                           |
+    /// | `NormalPhantom`         | `Phantom`                    | yes           | `Normal`                                          |
+    /// | `SpecialPhantom`        | `Phantom`                    | no            | `SpecialPhantom` (no-op)                          |
+    /// | `SpecialPhantom`        | `Phantom`                    | yes           | `SpecialPatchPlacement`                           |
+    ///
+    /// After a normal turn the next turn type depends on the state: a player
+    /// that moves over a special patch has to place it
+    /// (`SpecialPatchPlacement`) unless their quilt board is full. With a
+    /// forced player switch the opponent instead gets a phantom turn
+    /// (`SpecialPhantom`) and the same happens if the player would be allowed
+    /// to take another turn (`NormalPhantom`).
+    ///
+    /// # Arguments
+    ///
+    /// * `action_kind` - The kind of the action that is taken.
+    /// * `force_player_switch` - Whether the player switch is forced.
+    ///
+    /// # Returns
+    ///
+    /// The possible next turn types. Empty if the action kind cannot be taken
+    /// in this turn type.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn next_after(&self, action_kind: ActionKind, force_player_switch: bool) -> &'static [Self] {
+        match (self, action_kind, force_player_switch) {
+            (Self::Normal, ActionKind::Walking | ActionKind::PatchPlacement, false) => {
+                &[Self::Normal, Self::SpecialPatchPlacement]
+            }
+            (Self::Normal, ActionKind::Walking | ActionKind::PatchPlacement, true) => {
+                &[Self::Normal, Self::NormalPhantom, Self::SpecialPhantom]
+            }
+            (Self::SpecialPatchPlacement, ActionKind::SpecialPatchPlacement, _)
+            | (Self::NormalPhantom, ActionKind::Phantom, true) => &[Self::Normal],
+            (Self::NormalPhantom, ActionKind::Phantom, false) => &[Self::NormalPhantom],
+            (Self::SpecialPhantom, ActionKind::Phantom, false) => &[Self::SpecialPhantom],
+            (Self::SpecialPhantom, ActionKind::Phantom, true) => &[Self::SpecialPatchPlacement],
+            _ => &[],
+        }
+    }
+}
+
+/// A lightweight key identifying the structure of a game state.
+///
+/// The key consists of the quilt boards, button balances, button incomes and
+/// positions of both players as well as the status flags. In contrast to the
+/// full Zobrist hash of the transposition table the order (and content) of the
+/// remaining patch circle is ignored. This means states that were reached by
+/// taking the same patches in a different order share the same structural key
+/// while their full hashes differ. The key is therefore only suited for caches
+/// of order-insensitive values (e.g. evaluators that do not look at the
+/// available patches).
+///
+/// As the key stores the structure directly instead of hashing it, two keys are
+/// only equal if the structure of the states is equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StructuralKey {
+    /// The tiles of the quilt board of player 1.
+    pub player_1_tiles: u128,
+    /// The tiles of the quilt board of player 2.
+    pub player_2_tiles: u128,
+    /// The button balance of player 1.
+    pub player_1_button_balance: i32,
+    /// The button balance of player 2.
+    pub player_2_button_balance: i32,
+    /// The button income of player 1.
+    pub player_1_button_income: u8,
+    /// The button income of player 2.
+    pub player_2_button_income: u8,
+    /// The position of player 1 on the time board.
+    pub player_1_position: u8,
+    /// The position of player 2 on the time board.
+    pub player_2_position: u8,
+    /// The status flags of the game.
+    pub status_flags: u8,
+}
+
 /// Different flags for the status of the game.
 #[rustfmt::skip]
 pub mod status_flags {
@@ -33,6 +130,8 @@ pub mod status_flags {
     pub const PLAYER_1_FIRST_AT_END: u8 = 0b0001_0000; // 16
     /// If the second player was first to reach the goal.
     pub const PLAYER_2_FIRST_AT_END: u8 = 0b0010_0000; // 32
+    /// If the special tile (e.g. the 7x7 tile) is not awarded in this game.
+    pub const SPECIAL_TILE_BONUS_DISABLED: u8 = 0b0100_0000; // 64
 
     /// The flags for both players combined.
     pub const BOTH_PLAYERS: u8 = PLAYER_1 | PLAYER_2; // 3
@@ -45,11 +144,14 @@ pub mod status_flags {
 }
 
 /// Represents the full state of the patchwork board game.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+///
+/// The [`Patchwork::ply`] counter is only additional information and is not
+/// part of the equality or hash of the state.
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
 pub struct Patchwork {
     /// The patches that are available to be purchased.
     #[serde(serialize_with = "serialize_patches", deserialize_with = "deserialize_patches")]
-    pub patches: Vec<&'static Patch>,
+    pub patches: PatchCircle,
     /// The time board, which is a 9x9 grid of tiles.
     pub time_board: TimeBoard,
     /// The first player in the game.
@@ -64,11 +166,57 @@ pub struct Patchwork {
     /// * The current player
     /// * Whether a player has the special tile
     /// * Whether a player was first to reach the end
+    /// * Whether the special tile bonus is disabled for this game
     ///
     /// It is illegal to have both players be the current player.
     /// It is illegal to have both players have the special tile.
     /// It is illegal to have both players be first to reach the end.
     pub(crate) status_flags: u8,
+    /// The points every empty tile on the quilt board is worth at the end of
+    /// the game. This is not part of the notation of the game.
+    #[serde(default = "default_empty_tile_penalty")]
+    pub(crate) empty_tile_penalty: i32,
+    /// The rules how the players are scored at the end of the game. This is
+    /// not part of the notation of the game.
+    #[serde(skip)]
+    pub(crate) scoring_rules: ScoringRulesRef,
+    /// The amount of actions taken since the initial state (see
+    /// [`Patchwork::ply`]). This is not part of the notation of the game.
+    #[serde(skip)]
+    pub(crate) ply: u32,
+}
+
+impl PartialEq for Patchwork {
+    fn eq(&self, other: &Self) -> bool {
+        self.patches == other.patches
+            && self.time_board == other.time_board
+            && self.player_1 == other.player_1
+            && self.player_2 == other.player_2
+            && self.turn_type == other.turn_type
+            && self.status_flags == other.status_flags
+            && self.empty_tile_penalty == other.empty_tile_penalty
+            && self.scoring_rules == other.scoring_rules
+    }
+}
+
+impl Eq for Patchwork {}
+
+impl Hash for Patchwork {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.patches.hash(state);
+        self.time_board.hash(state);
+        self.player_1.hash(state);
+        self.player_2.hash(state);
+        self.turn_type.hash(state);
+        self.status_flags.hash(state);
+        self.empty_tile_penalty.hash(state);
+        self.scoring_rules.hash(state);
+    }
+}
+
+/// Every empty tile costs 2 points in the default rules.
+const fn default_empty_tile_penalty() -> i32 {
+    QuiltBoard::EMPTY_TILE_PENALTY
 }
 
 // Impl block for different getters and setters
@@ -180,6 +328,202 @@ impl Patchwork {
         }
     }
 
+    /// Returns a new state with the states of both players swapped.
+    ///
+    /// Everything that belongs to a player is swapped: the player states, the
+    /// positions on the time board, the current player as well as which
+    /// player has the special tile and which player was first to reach the
+    /// goal. The swapped state is the same position seen from the other
+    /// player's perspective, e.g. for data augmentation or to check that an
+    /// evaluator is antisymmetric.
+    ///
+    /// # Returns
+    ///
+    /// The state with both players swapped.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of tiles on the time board.
+    #[must_use]
+    pub fn swap_players(&self) -> Self {
+        const fn swap_flags(flags: u8, flag_1: u8, flag_2: u8) -> u8 {
+            let mut swapped = flags & !(flag_1 | flag_2);
+            if flags & flag_1 > 0 {
+                swapped |= flag_2;
+            }
+            if flags & flag_2 > 0 {
+                swapped |= flag_1;
+            }
+            swapped
+        }
+
+        let mut time_board = self.time_board.clone();
+        for tile in &mut time_board.tiles {
+            *tile = swap_flags(*tile, time_board_flags::PLAYER_1, time_board_flags::PLAYER_2);
+        }
+
+        let mut status_flags = swap_flags(self.status_flags, status_flags::PLAYER_1, status_flags::PLAYER_2);
+        status_flags = swap_flags(
+            status_flags,
+            status_flags::PLAYER_1_HAS_SPECIAL_TILE,
+            status_flags::PLAYER_2_HAS_SPECIAL_TILE,
+        );
+        status_flags = swap_flags(
+            status_flags,
+            status_flags::PLAYER_1_FIRST_AT_END,
+            status_flags::PLAYER_2_FIRST_AT_END,
+        );
+
+        Self {
+            patches: self.patches,
+            time_board,
+            player_1: self.player_2.clone(),
+            player_2: self.player_1.clone(),
+            turn_type: self.turn_type.clone(),
+            status_flags,
+            empty_tile_penalty: self.empty_tile_penalty,
+            scoring_rules: self.scoring_rules,
+            ply: self.ply,
+        }
+    }
+
+    /// Gets the amount of actions that were taken since the initial state.
+    ///
+    /// Every successful [`Patchwork::do_action`] increments the counter and
+    /// every [`Patchwork::undo_action`] decrements it again. Phantom actions
+    /// are not counted as they do not change the position. States loaded
+    /// from the notation or deserialized start at `0`.
+    ///
+    /// # Returns
+    ///
+    /// The amount of actions taken.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn ply(&self) -> u32 {
+        self.ply
+    }
+
+    /// Gets the points every empty tile on the quilt board is worth at the
+    /// end of the game.
+    ///
+    /// # Returns
+    ///
+    /// The penalty for every empty tile (negative in the standard rules).
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn get_empty_tile_penalty(&self) -> i32 {
+        self.empty_tile_penalty
+    }
+
+    /// Gets the rules how the players are scored at the end of the game.
+    ///
+    /// # Returns
+    ///
+    /// The scoring rules of the game.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn get_scoring_rules(&self) -> &'static dyn ScoringRules {
+        self.scoring_rules.0
+    }
+
+    /// Clones this state into the given state. In contrast to [`Clone::clone`]
+    /// the existing allocations of the given state (e.g. the list of patches)
+    /// are reused instead of allocating new ones. This is useful for code
+    /// paths that repeatedly copy states into the same buffer.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - The state to clone this state into.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of patches.
+    pub fn clone_into(&self, dst: &mut Self) {
+        const fn clone_player_into(src: &PlayerState, dst: &mut PlayerState) {
+            dst.position = src.position;
+            dst.button_balance = src.button_balance;
+            dst.quilt_board.tiles = src.quilt_board.tiles;
+            dst.quilt_board.button_income = src.quilt_board.button_income;
+            dst.quilt_board.placed_patch_ids = src.quilt_board.placed_patch_ids;
+        }
+
+        dst.patches = self.patches;
+        dst.time_board.tiles = self.time_board.tiles;
+        clone_player_into(&self.player_1, &mut dst.player_1);
+        clone_player_into(&self.player_2, &mut dst.player_2);
+        dst.turn_type = self.turn_type.clone();
+        dst.status_flags = self.status_flags;
+        dst.empty_tile_penalty = self.empty_tile_penalty;
+        dst.scoring_rules = self.scoring_rules;
+        dst.ply = self.ply;
+    }
+
+    /// Compares only the game relevant state of both states. In contrast to
+    /// [`PartialEq`] the following fields are ignored:
+    ///
+    /// * The positions of the players beyond [`TimeBoard::MAX_POSITION`] that are
+    ///   only kept to be able to undo actions (the clamped positions are compared).
+    /// * The ids of the patches placed on the quilt boards (these are already
+    ///   ignored by the equality of [`QuiltBoard`]).
+    ///
+    /// The compared fields are the patches (including their order), the time
+    /// board, the clamped positions, button balances, quilt board tiles and
+    /// button incomes of both players, the turn type, the status flags, the
+    /// empty tile penalty and the scoring rules.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The state to compare with.
+    ///
+    /// # Returns
+    ///
+    /// Whether both states represent the same game position.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of patches.
+    #[must_use]
+    pub fn equals_ignoring_diagnostics(&self, other: &Self) -> bool {
+        fn player_equals(a: &PlayerState, b: &PlayerState) -> bool {
+            a.get_position() == b.get_position()
+                && a.button_balance == b.button_balance
+                && a.quilt_board.tiles == b.quilt_board.tiles
+                && a.quilt_board.button_income == b.quilt_board.button_income
+        }
+
+        self.status_flags == other.status_flags
+            && self.turn_type == other.turn_type
+            && self.empty_tile_penalty == other.empty_tile_penalty
+            && self.scoring_rules == other.scoring_rules
+            && player_equals(&self.player_1, &other.player_1)
+            && player_equals(&self.player_2, &other.player_2)
+            && self.time_board.tiles == other.time_board.tiles
+            && self.patches == other.patches
+    }
+
+    /// Returns if the special tile (e.g. the 7x7 tile) is awarded in this game.
+    ///
+    /// # Returns
+    ///
+    /// If the player that first fills a 7x7 area gets the special tile bonus.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn is_special_tile_bonus_enabled(&self) -> bool {
+        self.status_flags & status_flags::SPECIAL_TILE_BONUS_DISABLED == 0
+    }
+
     /// Returns if the special tile condition has already been reached by either player.
     ///
     /// # Returns
@@ -230,8 +574,8 @@ impl Patchwork {
     ///
     /// # Undefined Behavior
     ///
-    /// If the special tile condition has already been reached. This will panic
-    /// in debug mode.
+    /// If the special tile condition has already been reached or the special
+    /// tile bonus is disabled. This will panic in debug mode.
     ///
     /// # Complexity
     ///
@@ -241,6 +585,10 @@ impl Patchwork {
             !self.is_special_tile_condition_reached(),
             "[Patchwork::set_special_tile_condition] Special tile condition has already been reached"
         );
+        debug_assert!(
+            self.is_special_tile_bonus_enabled(),
+            "[Patchwork::set_special_tile_condition] Special tile bonus is disabled"
+        );
 
         if Self::is_flag_player_1(player_flag) {
             self.status_flags |= status_flags::PLAYER_1_HAS_SPECIAL_TILE;
@@ -350,7 +698,8 @@ impl Patchwork {
         }
     }
 
-    /// Gets the score of the given player.
+    /// Gets the score of the given player according to the scoring rules of
+    /// the game.
     ///
     /// # Arguments
     ///
@@ -361,19 +710,150 @@ impl Patchwork {
     ///
     /// The score of the given player.
     #[must_use]
-    pub const fn get_score(&self, player_flag: u8) -> i32 {
-        let player = &self.get_player(player_flag);
+    pub fn get_score(&self, player_flag: u8) -> i32 {
+        self.scoring_rules.score(self, player_flag)
+    }
 
-        let mut score = player.quilt_board.score() + player.button_balance;
+    /// Gets the amount of special patches that are still on the time board
+    /// ahead of both players.
+    ///
+    /// A special patch is claimed by the first player that passes it, so only
+    /// the special patches ahead of the leading player are still open.
+    ///
+    /// # Returns
+    ///
+    /// The amount of special patches that can still be obtained by either
+    /// player.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of tiles ahead of the leading player.
+    #[must_use]
+    pub fn get_open_special_patches_remaining(&self) -> usize {
+        let leading_position = self.player_1.get_position().max(self.player_2.get_position());
+        self.time_board
+            .get_amount_special_patches_in_range(usize::from(leading_position) + 1..TimeBoard::TILES)
+    }
+
+    /// Gets the patches the current player may choose from. These are the
+    /// next (up to) 3 patches after the neutral token in the patch circle.
+    /// Whether the current player can afford or place the patches is not
+    /// checked.
+    ///
+    /// # Returns
+    ///
+    /// The patches available to the current player in circle order.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn patches_available_to_current_player(&self) -> PatchCircleSlice<'_> {
+        self.patches.take(PatchManager::MAX_AMOUNT_OF_CHOOSABLE_TILES as usize)
+    }
+
+    /// Gets the position of the neutral token in the patch circle. The
+    /// position is given as the id of the patch directly after the neutral
+    /// token, which is the first patch the current player may choose.
+    ///
+    /// # Returns
+    ///
+    /// The id of the patch after the neutral token or `None` if no patches are
+    /// left.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn patch_circle_position(&self) -> Option<u8> {
+        self.patches.first().map(|patch| patch.id)
+    }
 
-        if (Self::is_flag_player_1(player_flag) && (self.status_flags & status_flags::PLAYER_1_HAS_SPECIAL_TILE) > 0)
-            || (Self::is_flag_player_2(player_flag)
-                && (self.status_flags & status_flags::PLAYER_2_HAS_SPECIAL_TILE) > 0)
-        {
-            score += QuiltBoard::BOARD_EXTRA_BUTTON_INCOME;
+    /// Gets the structural key of the game state.
+    ///
+    /// The structural key ignores the order of the remaining patches. See
+    /// [`StructuralKey`] for the difference to the full Zobrist hash.
+    ///
+    /// # Returns
+    ///
+    /// The structural key of the game state.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn structural_key(&self) -> StructuralKey {
+        StructuralKey {
+            player_1_tiles: self.player_1.quilt_board.tiles,
+            player_2_tiles: self.player_2.quilt_board.tiles,
+            player_1_button_balance: self.player_1.button_balance,
+            player_2_button_balance: self.player_2.button_balance,
+            player_1_button_income: self.player_1.quilt_board.button_income,
+            player_2_button_income: self.player_2.quilt_board.button_income,
+            player_1_position: self.player_1.get_position(),
+            player_2_position: self.player_2.get_position(),
+            status_flags: self.status_flags,
+        }
+    }
+
+    /// Gets a deterministic hash of the whole game state without the Zobrist
+    /// tables of the transposition table. This allows hashing game states in
+    /// players that are built without the transposition table.
+    ///
+    /// In contrast to the [`StructuralKey`] all parts of the state that are
+    /// compared for equality are hashed (including the order of the patch
+    /// circle), so equal states always have the same hash. The hash is stable
+    /// across runs, builds and platforms but unlike the Zobrist hash it cannot
+    /// be updated incrementally.
+    ///
+    /// # Returns
+    ///
+    /// The hash of the game state.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛 + 𝑚)` where `𝑛` is the amount of tiles on the time board (usually 54)
+    /// and `𝑚` is the amount of patches.
+    #[must_use]
+    pub fn structural_hash(&self) -> u64 {
+        /// Mixes the value into the hash (like `FxHash`).
+        const fn mix(hash: u64, value: u64) -> u64 {
+            (hash.rotate_left(5) ^ value).wrapping_mul(0x517C_C1B7_2722_0A95)
         }
 
-        score
+        let mut hash = mix(0, self.patches.len() as u64);
+        for patch_id in self.patches.ids() {
+            hash = mix(hash, u64::from(*patch_id));
+        }
+        for tiles in self.time_board.tiles.chunks(8) {
+            let mut bytes = [0; 8];
+            bytes[..tiles.len()].copy_from_slice(tiles);
+            hash = mix(hash, u64::from_le_bytes(bytes));
+        }
+        for player in [&self.player_1, &self.player_2] {
+            hash = mix(hash, player.quilt_board.tiles as u64);
+            hash = mix(hash, (player.quilt_board.tiles >> 64) as u64);
+            hash = mix(hash, u64::from(player.quilt_board.button_income));
+            hash = mix(hash, u64::from(player.position));
+            hash = mix(hash, u64::from(player.button_balance as u32));
+        }
+        let turn_type = match self.turn_type {
+            TurnType::Normal => 0,
+            TurnType::SpecialPatchPlacement => 1,
+            TurnType::NormalPhantom => 2,
+            TurnType::SpecialPhantom => 3,
+        };
+        hash = mix(hash, turn_type);
+        hash = mix(hash, u64::from(self.status_flags));
+        hash = mix(hash, u64::from(self.empty_tile_penalty as u32));
+        for byte in self.scoring_rules.name().bytes() {
+            hash = mix(hash, u64::from(byte));
+        }
+
+        // finalizer of SplitMix64 so that every bit depends on every input
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        hash ^ (hash >> 31)
     }
 
     /// Gets the termination result of the given state.
@@ -387,27 +867,31 @@ impl Patchwork {
     /// The termination result of the game associated with the given state.
     #[must_use]
     pub fn get_termination_result(&self) -> Termination {
+        let player_1_breakdown = self.scoring_rules.score_breakdown(self, status_flags::PLAYER_1);
+        let player_2_breakdown = self.scoring_rules.score_breakdown(self, status_flags::PLAYER_2);
         let player_1_score = self.get_score(status_flags::PLAYER_1);
         let player_2_score = self.get_score(status_flags::PLAYER_2);
 
         let termination = match player_1_score.cmp(&player_2_score) {
             Ordering::Less => TerminationType::Player2Won,
             Ordering::Greater => TerminationType::Player1Won,
-            Ordering::Equal => {
-                if (self.status_flags & status_flags::PLAYER_1_FIRST_AT_END) > 0 {
-                    TerminationType::Player1Won
-                } else if (self.status_flags & status_flags::PLAYER_2_FIRST_AT_END) > 0 {
-                    TerminationType::Player2Won
-                } else {
-                    panic!("[Patchwork::get_termination_result] Both players have the same score and neither was first to reach the end")
-                }
-            }
+            Ordering::Equal => self.scoring_rules.tie_break(self),
+        };
+        let first_at_end = if self.player_1_was_first_to_reach_goal() {
+            Some(status_flags::PLAYER_1)
+        } else if self.player_2_was_first_to_reach_goal() {
+            Some(status_flags::PLAYER_2)
+        } else {
+            None
         };
 
         Termination {
             termination,
             player_1_score,
             player_2_score,
+            player_1_breakdown,
+            player_2_breakdown,
+            first_at_end,
         }
     }
 }
@@ -504,18 +988,326 @@ impl Display for Patchwork {
     }
 }
 
-fn serialize_patches<S>(patches: &[&'static Patch], serializer: S) -> Result<S::Ok, S::Error>
+fn serialize_patches<S>(patches: &PatchCircle, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: serde::Serializer,
 {
-    let patches = patches.iter().map(|patch| patch.id).collect::<Vec<_>>();
-    serde_bytes::serialize(&patches, serializer)
+    serde_bytes::serialize(patches.ids(), serializer)
 }
 
-fn deserialize_patches<'de, D>(deserializer: D) -> Result<Vec<&'static Patch>, D::Error>
+fn deserialize_patches<'de, D>(deserializer: D) -> Result<PatchCircle, D::Error>
 where
     D: serde::Deserializer<'de>,
 {
     let patches: Vec<u8> = serde_bytes::deserialize(deserializer)?;
-    Ok(patches.into_iter().map(PatchManager::get_patch).collect::<Vec<_>>())
+    if patches.len() > PatchCircle::CAPACITY {
+        return Err(serde::de::Error::invalid_length(patches.len(), &"at most all patches of the game"));
+    }
+    Ok(patches.into_iter().map(PatchManager::get_patch).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use std::collections::HashSet;
+
+    use rand::{Rng, SeedableRng};
+
+    use crate::{
+        ActionId, ActionKind, GameOptions, GameRng, Notation, Patchwork, PositionCorpus, TerminationType, TimeBoard,
+        TurnType,
+    };
+
+    #[test]
+    fn test_turn_type_transitions_match_do_action() {
+        let mut random = GameRng::seed_from_u64(42);
+        let mut observed = HashSet::new();
+
+        for seed in 0..200 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+
+            while !state.is_terminated() {
+                let valid_actions = state.get_valid_actions();
+                let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+                let force_player_switch = random.gen_bool(0.5);
+                let turn_type = state.turn_type.clone();
+
+                state.do_action(action, force_player_switch).unwrap();
+                assert!(
+                    turn_type.next_after(action.get_kind(), force_player_switch).contains(&state.turn_type),
+                    "Unexpected transition from {turn_type:?} with {action:?} (forced: {force_player_switch}) to {:?}",
+                    state.turn_type
+                );
+                observed.insert((turn_type, action.get_kind(), force_player_switch, state.turn_type.clone()));
+            }
+        }
+
+        // every documented transition actually occurs
+        let turn_types = [
+            TurnType::Normal,
+            TurnType::SpecialPatchPlacement,
+            TurnType::NormalPhantom,
+            TurnType::SpecialPhantom,
+        ];
+        let action_kinds = [
+            ActionKind::Walking,
+            ActionKind::PatchPlacement,
+            ActionKind::SpecialPatchPlacement,
+            ActionKind::Phantom,
+            ActionKind::Null,
+        ];
+        for turn_type in &turn_types {
+            for action_kind in action_kinds {
+                for force_player_switch in [false, true] {
+                    for next_turn_type in turn_type.next_after(action_kind, force_player_switch) {
+                        let transition = (turn_type.clone(), action_kind, force_player_switch, next_turn_type.clone());
+                        assert!(observed.contains(&transition), "Transition {transition:?} never occurred");
+                    }
+                }
+            }
+        }
+        assert!(turn_types.iter().all(|turn_type| observed.iter().any(|transition| transition.0 == *turn_type)));
+    }
+
+    #[test]
+    fn test_structural_hash_of_equal_states_is_equal() {
+        let mut random = GameRng::seed_from_u64(42);
+        let mut hashes = HashSet::new();
+        let mut states = HashSet::new();
+
+        for seed in 0..100 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            while !state.is_terminated() {
+                let valid_actions = state.get_valid_actions();
+                let action = valid_actions[random.gen::<usize>() % valid_actions.len()];
+
+                // an equal state that was built independently of the state
+                let mut copy = state.clone();
+                copy.do_action(action, false).unwrap();
+                copy.undo_action(action, false).unwrap();
+                assert_eq!(state, copy);
+                assert_eq!(state.structural_hash(), copy.structural_hash());
+
+                hashes.insert(state.structural_hash());
+                states.insert(state.clone());
+                state.do_action(action, false).unwrap();
+            }
+        }
+
+        // distinct states of random games should (practically) never collide
+        assert_eq!(states.len(), hashes.len());
+        // the hash does not depend on the run or the build
+        assert_eq!(
+            15_699_912_725_426_880_607,
+            Patchwork::get_initial_state(Some(GameOptions::new(0))).structural_hash()
+        );
+    }
+
+    #[test]
+    fn test_swap_players_twice_is_identity() {
+        for state in PositionCorpus::standard() {
+            assert_eq!(state, state.swap_players().swap_players());
+        }
+    }
+
+    #[test]
+    fn test_swap_players_swaps_player_ownership() {
+        for state in PositionCorpus::standard() {
+            let swapped = state.swap_players();
+
+            assert_eq!(state.player_1, swapped.player_2);
+            assert_eq!(state.player_2, swapped.player_1);
+            assert_eq!(state.is_player_1(), swapped.is_player_2());
+            assert_eq!(state.current_player(), swapped.current_player());
+            assert_eq!(
+                state.time_board.get_player_positions(),
+                (
+                    swapped.time_board.get_player_positions().1,
+                    swapped.time_board.get_player_positions().0
+                )
+            );
+            assert_eq!(
+                state.is_special_tile_condition_reached_by_player_1(),
+                swapped.is_special_tile_condition_reached_by_player_2()
+            );
+            assert_eq!(
+                state.player_1_was_first_to_reach_goal(),
+                swapped.player_2_was_first_to_reach_goal()
+            );
+            assert_eq!(
+                state.is_special_tile_bonus_enabled(),
+                swapped.is_special_tile_bonus_enabled()
+            );
+            assert_eq!(state.get_valid_actions().len(), swapped.get_valid_actions().len());
+        }
+    }
+
+    #[test]
+    fn test_tie_on_score_is_won_by_goal_order() {
+        for (status_flags, winner) in [(16, TerminationType::Player1Won), (32, TerminationType::Player2Won)] {
+            let state = Patchwork::load_from_notation(&format!(
+                "000000000000000000000B20I0P53 000000000000000000000B20I0P53 {status_flags} N -"
+            ))
+            .unwrap();
+
+            assert!(state.is_terminated());
+
+            let termination = state.get_termination_result();
+            assert!(termination.is_tie_on_score());
+            assert_eq!(termination.player_1_score, termination.player_2_score);
+            assert_eq!(winner, termination.termination);
+        }
+
+        let state =
+            Patchwork::load_from_notation("000000000000000000000B21I0P53 000000000000000000000B20I0P53 32 N -").unwrap();
+        let termination = state.get_termination_result();
+        assert!(!termination.is_tie_on_score());
+        assert_eq!(TerminationType::Player1Won, termination.termination);
+    }
+
+    #[test]
+    fn test_tie_break_metadata_is_populated() {
+        let state =
+            Patchwork::load_from_notation("000000000000000000000B20I0P53 000000000000000000000B20I0P53 32 N -").unwrap();
+        let termination = state.get_termination_result();
+        assert!(termination.is_tie_on_score());
+        assert_eq!(Some(Patchwork::get_player_2_flag()), termination.first_at_end);
+        assert_eq!(0, termination.margin());
+
+        let state =
+            Patchwork::load_from_notation("000000000000000000000B23I0P53 000000000000000000000B20I0P53 32 N -").unwrap();
+        let termination = state.get_termination_result();
+        assert!(!termination.is_tie_on_score());
+        assert_eq!(Some(Patchwork::get_player_2_flag()), termination.first_at_end);
+        assert_eq!(3, termination.margin());
+    }
+
+    #[test]
+    fn test_available_patches_advance_around_the_circle() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let patches = state.patches;
+
+        assert_eq!(patches.take(3), state.patches_available_to_current_player());
+        assert_eq!(Some(patches[0].id), state.patch_circle_position());
+
+        let action = state
+            .get_valid_actions()
+            .into_iter()
+            .find(|action| action.is_patch_placement() && action.get_patch_index() == 1)
+            .unwrap();
+        state.do_action(action, false).unwrap();
+
+        // the neutral token moves to the bought patch which is removed
+        assert_eq!(&patches.ids()[2..5], state.patches_available_to_current_player().ids());
+        assert_eq!(Some(patches[2].id), state.patch_circle_position());
+        assert_eq!(patches.len() - 1, state.patches.len());
+        assert_eq!(patches[0].id, state.patches.last().unwrap().id);
+    }
+
+    #[test]
+    fn test_clone_into_equals_clone() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut buffer = Patchwork::get_initial_state(Some(GameOptions::new(7)));
+
+        let mut seed = 0;
+        while !state.is_terminated() {
+            state.clone_into(&mut buffer);
+            assert_eq!(state.clone(), buffer);
+            assert_eq!(state.player_1.quilt_board.placed_patch_ids, buffer.player_1.quilt_board.placed_patch_ids);
+            assert_eq!(state.player_2.quilt_board.placed_patch_ids, buffer.player_2.quilt_board.placed_patch_ids);
+
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+            seed += 1;
+        }
+
+        state.clone_into(&mut buffer);
+        assert_eq!(state, buffer);
+    }
+
+    #[test]
+    fn test_clone_into_copies_the_ply() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for seed in 0..5 {
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+        }
+        let mut buffer = Patchwork::get_initial_state(Some(GameOptions::new(7)));
+        buffer.do_action(buffer.get_seeded_random_action(0), false).unwrap();
+
+        // the ply is neither part of the equality nor of the hash
+        state.clone_into(&mut buffer);
+        assert_eq!(state.clone().ply(), buffer.ply());
+    }
+
+    #[test]
+    fn test_equals_ignoring_diagnostics_ignores_overshooting_position() {
+        // player 1 walks past the end of the time board while player 2 is already there
+        let mut walked =
+            Patchwork::load_from_notation("000000000000000000000B5I0P50 000000000000000000000B5I0P53 33 N -").unwrap();
+        walked.do_action(ActionId::walking(50), false).unwrap();
+
+        let loaded =
+            Patchwork::load_from_notation("000000000000000000000B8I0P53 000000000000000000000B5I0P53 33 N -").unwrap();
+
+        assert!(walked.player_1.position > TimeBoard::MAX_POSITION);
+        assert_ne!(walked, loaded);
+        assert!(walked.equals_ignoring_diagnostics(&loaded));
+        assert!(loaded.equals_ignoring_diagnostics(&walked));
+
+        let mut other = loaded;
+        other.player_1.button_balance += 1;
+        assert!(!walked.equals_ignoring_diagnostics(&other));
+    }
+
+    #[test]
+    fn test_equals_ignoring_diagnostics_for_different_move_orders() {
+        let state = Patchwork::load_from_notation(
+            "000000000000000000000B50I0P45 00000000000000000000FB49I0P44 2 N 18/28/10/30/31/15/17/9/13/4/16/27/20/6/7/5/19/24/21/25/3/26/2/8/22/23/29/14/1/32/12/11/0",
+        )
+        .unwrap();
+        let play = |actions: [&str; 7]| {
+            let mut state = state.clone();
+            for action in actions {
+                state.do_action(ActionId::load_from_notation(action).unwrap(), false).unwrap();
+            }
+            state
+        };
+
+        // player 1 walks before or after placing patch 28, so the last patch
+        // moves player 1 past the end of the time board by a different amount
+        let first = play([
+            "P18I0═0‖4↻0↔0P0",
+            "W45",
+            "W47",
+            "P28I0═0‖0↻0↔0P1",
+            "S═0‖4",
+            "P10I0═0‖7↻0↔0P0",
+            "P30I0═0‖4↻0↔0P1",
+        ]);
+        let second = play([
+            "P18I0═0‖4↻0↔0P0",
+            "P28I0═0‖0↻0↔0P1",
+            "W47",
+            "W48",
+            "S═0‖4",
+            "P10I0═0‖7↻0↔0P0",
+            "P30I0═0‖4↻0↔0P1",
+        ]);
+
+        assert_ne!(first, second);
+        assert!(first.equals_ignoring_diagnostics(&second));
+        assert!(second.equals_ignoring_diagnostics(&first));
+        assert!(!first.equals_ignoring_diagnostics(&state));
+    }
+
+    #[test]
+    fn test_available_patches_near_the_end_of_the_circle() {
+        let mut state = Patchwork::get_initial_state(None);
+        state.patches.truncate(2);
+        assert_eq!(2, state.patches_available_to_current_player().len());
+
+        state.patches.clear();
+        assert!(state.patches_available_to_current_player().is_empty());
+        assert_eq!(None, state.patch_circle_position());
+    }
 }
@@ -1,3 +1,8 @@
+mod placed_patch_ids;
+mod placement_cache;
 mod quilt_board;
 
-pub use quilt_board::QuiltBoard;
+use placed_patch_ids::PlacedPatchIds;
+
+pub use placement_cache::PlacementCache;
+pub use quilt_board::{QuiltBoard, QuiltBoardDiff};
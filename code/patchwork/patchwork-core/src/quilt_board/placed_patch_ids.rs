@@ -0,0 +1,130 @@
+use crate::PatchManager;
+
+/// The ids of the (non-special) patches placed on a quilt board in placement
+/// order.
+///
+/// The ids are stored inline so that cloning a board never allocates. Every
+/// normal patch can be placed at most once, so the capacity is never exceeded.
+/// The ids are (de)serialized like a `Vec<u8>`.
+#[derive(Clone, Copy)]
+pub struct PlacedPatchIds {
+    ids: [u8; PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize],
+    len: u8,
+}
+
+impl PlacedPatchIds {
+    /// Creates a new [`PlacedPatchIds`] without any placed patches.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            ids: [0; PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize],
+            len: 0,
+        }
+    }
+
+    /// Appends the id of a placed patch.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch_id` - The id of the placed patch.
+    ///
+    /// # Panics
+    ///
+    /// If more patches than there are normal patches were placed.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub const fn push(&mut self, patch_id: u8) {
+        self.ids[self.len as usize] = patch_id;
+        self.len += 1;
+    }
+
+    /// Removes the id of the last placed patch.
+    ///
+    /// # Returns
+    ///
+    /// The id of the last placed patch or `None` if no patch was placed.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub const fn pop(&mut self) -> Option<u8> {
+        if self.len == 0 {
+            return None;
+        }
+
+        self.len -= 1;
+        Some(self.ids[self.len as usize])
+    }
+
+    /// Removes all ids.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[cfg(test)]
+    pub const fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Gets the ids of the placed patches in placement order.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] {
+        &self.ids[..self.len as usize]
+    }
+}
+
+impl Default for PlacedPatchIds {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// the ids after the length are leftovers of removed patches
+impl PartialEq for PlacedPatchIds {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for PlacedPatchIds {}
+
+impl std::fmt::Debug for PlacedPatchIds {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.as_slice()).finish()
+    }
+}
+
+impl serde::Serialize for PlacedPatchIds {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.as_slice())
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for PlacedPatchIds {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let ids = Vec::<u8>::deserialize(deserializer)?;
+
+        let mut placed_patch_ids = Self::new();
+        if ids.len() > placed_patch_ids.ids.len() {
+            return Err(serde::de::Error::invalid_length(
+                ids.len(),
+                &"at most one id for every normal patch",
+            ));
+        }
+        placed_patch_ids.ids[..ids.len()].copy_from_slice(&ids);
+        placed_patch_ids.len = ids.len() as u8;
+        Ok(placed_patch_ids)
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+
+use crate::{Patch, PatchManager};
+
+/// A bounded cache for the valid placements of patches on quilt boards.
+///
+/// During a search the same patch is tested against the same quilt board over
+/// and over again as many different paths lead to the same boards. The cache
+/// stores the indices of all transformations of a patch that fit on a board
+/// keyed by the id of the patch and the tiles of the board. Whether a
+/// transformation fits depends on every tile it could cover, which for every
+/// patch is the whole board, so all tiles are part of the key.
+///
+/// When the cache is full it is cleared completely. This keeps the memory
+/// bounded without the bookkeeping a replacement strategy would need.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlacementCache {
+    /// The indices of the fitting transformations keyed by patch id and board tiles.
+    entries: HashMap<(u8, u128), Vec<u16>>,
+    /// The maximum amount of entries in the cache.
+    capacity: usize,
+    /// The amount of lookups that were answered from the cache.
+    hits: usize,
+    /// The amount of lookups that had to generate the placements.
+    misses: usize,
+}
+
+impl PlacementCache {
+    /// The default maximum amount of entries in the cache.
+    pub const DEFAULT_CAPACITY: usize = 1 << 14;
+
+    /// Creates a new empty [`PlacementCache`].
+    ///
+    /// # Arguments
+    ///
+    /// * `capacity` - The maximum amount of entries in the cache.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self { entries: HashMap::new(), capacity: capacity.max(1), hits: 0, misses: 0 }
+    }
+
+    /// Gets the indices of all transformations of the given patch that fit on
+    /// a quilt board with the given tiles. The placements are generated and
+    /// inserted into the cache if they are not cached yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - The patch to get the placements for.
+    /// * `tiles` - The tiles of the quilt board.
+    ///
+    /// # Returns
+    ///
+    /// The indices of the fitting transformations in ascending order.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)` if the placements are cached, otherwise `𝒪(𝑛)` where `n` is the
+    /// amount of transformations of the given patch.
+    pub fn get_transformation_indices(&mut self, patch: &'static Patch, tiles: u128) -> &[u16] {
+        let key = (patch.id, tiles);
+
+        if self.entries.contains_key(&key) {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+            if self.entries.len() >= self.capacity {
+                self.entries.clear();
+            }
+
+            let transformation_indices = PatchManager::get_transformations(patch.id)
+                .iter()
+                .enumerate()
+                .filter(|(_, transformation)| (tiles & transformation.tiles) == 0)
+                .map(|(index, _)| index as u16)
+                .collect();
+            self.entries.insert(key, transformation_indices);
+        }
+
+        &self.entries[&key]
+    }
+
+    /// Removes all entries from the cache and resets the statistics.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of entries in the cache.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+
+    /// Gets the amount of entries in the cache.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the cache has no entries.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Gets the maximum amount of entries in the cache.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Gets the amount of lookups that were answered from the cache since it
+    /// was last cleared.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Gets the amount of lookups that had to generate the placements since
+    /// the cache was last cleared.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub const fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+impl Default for PlacementCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{GameOptions, Patchwork};
+
+    use super::*;
+
+    #[test]
+    fn test_cached_and_uncached_generation_agree() {
+        let mut cache = PlacementCache::new(64);
+
+        for seed in 0..20 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut action_seed = seed;
+            while !state.is_terminated() {
+                let mut cached_actions = vec![];
+                state.get_valid_actions_into_cached(&mut cached_actions, &mut cache);
+                assert_eq!(state.get_valid_actions(), cached_actions);
+
+                // the second lookup is answered from the cache
+                state.get_valid_actions_into_cached(&mut cached_actions, &mut cache);
+                assert_eq!(state.get_valid_actions(), cached_actions);
+
+                state.do_action(state.get_seeded_random_action(action_seed), false).unwrap();
+                action_seed += 1;
+            }
+        }
+
+        assert!(cache.hits() > 0);
+        assert!(cache.len() <= cache.capacity());
+    }
+
+    #[test]
+    fn test_cache_is_bounded_and_clearable() {
+        let mut cache = PlacementCache::new(2);
+        let patch = PatchManager::get_patch(1);
+
+        cache.get_transformation_indices(patch, 0);
+        cache.get_transformation_indices(patch, 0);
+        cache.get_transformation_indices(patch, 1);
+        assert_eq!((1, 2, 2), (cache.hits(), cache.misses(), cache.len()));
+
+        cache.get_transformation_indices(patch, 2);
+        assert_eq!(1, cache.len());
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!((0, 0), (cache.hits(), cache.misses()));
+    }
+}
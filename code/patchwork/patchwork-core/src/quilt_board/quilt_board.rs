@@ -1,14 +1,40 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
 
+use super::PlacedPatchIds;
 use crate::{ActionId, Patch, PatchManager};
 
 // The quilt board of the player.
-#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct QuiltBoard {
     /// The tiles of the quilt board.
     pub tiles: u128,
     /// The amount of buttons this board generates.
     pub button_income: u8,
+    /// The ids of the (non-special) patches placed on this board in placement order.
+    ///
+    /// This is only additional information for analysis and is not part of
+    /// the equality or hash of the board as it cannot be restored from the
+    /// notation of a board.
+    #[serde(default)]
+    pub(crate) placed_patch_ids: PlacedPatchIds,
+}
+
+impl PartialEq for QuiltBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.tiles == other.tiles && self.button_income == other.button_income
+    }
+}
+
+impl Eq for QuiltBoard {}
+
+impl Hash for QuiltBoard {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.tiles.hash(state);
+        self.button_income.hash(state);
+    }
 }
 
 impl Default for QuiltBoard {
@@ -26,6 +52,9 @@ impl QuiltBoard {
     pub const TILES: u8 = Self::ROWS * Self::COLUMNS;
     /// The amount of buttons a 7x7 board generates.
     pub const BOARD_EXTRA_BUTTON_INCOME: i32 = 7;
+    /// The points every empty tile is worth at the end of the game in the
+    /// standard rules.
+    pub const EMPTY_TILE_PENALTY: i32 = -2;
 
     // ─────────────────────────────────────────────── UTILITY FUNCTIONS ───────────────────────────────────────────────
 
@@ -83,6 +112,7 @@ impl QuiltBoard {
         Self {
             tiles: 0,
             button_income: 0,
+            placed_patch_ids: PlacedPatchIds::new(),
         }
     }
 
@@ -105,6 +135,55 @@ impl QuiltBoard {
         Self {
             tiles: bits,
             button_income: 0,
+            placed_patch_ids: PlacedPatchIds::new(),
+        }
+    }
+
+    /// Gets the difference between this board and the given board.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The board to compare this board to (e.g. the board after a move).
+    ///
+    /// # Returns
+    ///
+    /// A new [`QuiltBoard`] with the tiles that are filled in `other` but not
+    /// in this board and the button income `other` has in addition to this
+    /// board.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub const fn diff(&self, other: &Self) -> Self {
+        Self {
+            tiles: other.tiles & !self.tiles,
+            button_income: other.button_income.saturating_sub(self.button_income),
+            placed_patch_ids: PlacedPatchIds::new(),
+        }
+    }
+
+    /// Gets a displayable view of the given board that highlights the tiles
+    /// that are filled in `other` but not in this board.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The board to compare this board to (e.g. the board after a move).
+    ///
+    /// # Returns
+    ///
+    /// The [`QuiltBoardDiff`] between this board and the given board.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub const fn display_diff<'a>(&'a self, other: &'a Self) -> QuiltBoardDiff<'a> {
+        QuiltBoardDiff {
+            before: self,
+            after: other,
         }
     }
 
@@ -188,6 +267,33 @@ impl QuiltBoard {
         Self::TILES as u32 - self.tiles_filled()
     }
 
+    /// The empty tiles that can never be covered by a normal patch as every
+    /// transformation of every normal patch that covers the tile would overlap
+    /// a filled tile or the edge of the board.
+    ///
+    /// These tiles can only be filled with a special patch and will otherwise
+    /// stay empty until the end of the game, i.e. each of them is a guaranteed
+    /// [`QuiltBoard::EMPTY_TILE_PENALTY`] for the score.
+    ///
+    /// # Returns
+    ///
+    /// The dead tiles in the same layout as [`QuiltBoard::tiles`].
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the amount of transformations of all normal patches.
+    #[must_use]
+    pub fn dead_tiles(&self) -> u128 {
+        const BOARD: u128 = (1 << QuiltBoard::TILES) - 1;
+
+        let coverable_tiles = (0..PatchManager::AMOUNT_OF_NORMAL_PATCHES)
+            .flat_map(PatchManager::get_transformations)
+            .filter(|transformation| self.tiles & transformation.tiles == 0)
+            .fold(0, |coverable_tiles, transformation| coverable_tiles | transformation.tiles);
+
+        BOARD & !self.tiles & !coverable_tiles
+    }
+
     /// The percentage of tiles that are filled.
     ///
     /// # Returns
@@ -206,7 +312,7 @@ impl QuiltBoard {
     /// The score the player has with this quilt board.
     ///
     /// The score is calculated by taking the amount of tiles that are not
-    /// filled and multiplying it by -2.
+    /// filled and multiplying it by [`QuiltBoard::EMPTY_TILE_PENALTY`].
     ///
     /// # Returns
     ///
@@ -218,11 +324,50 @@ impl QuiltBoard {
     #[inline]
     #[must_use]
     pub const fn score(&self) -> i32 {
-        -2 * (self.tiles_free() as i32)
+        self.score_with_penalty(Self::EMPTY_TILE_PENALTY)
+    }
+
+    /// The score the player has with this quilt board if every empty tile is
+    /// worth the given penalty.
+    ///
+    /// # Arguments
+    ///
+    /// * `empty_tile_penalty` - The points every empty tile is worth.
+    ///
+    /// # Returns
+    ///
+    /// The score the player has with this quilt board.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub const fn score_with_penalty(&self, empty_tile_penalty: i32) -> i32 {
+        empty_tile_penalty * (self.tiles_free() as i32)
     }
 
     // ──────────────────────────────────────────────────── GETTERS ────────────────────────────────────────────────────
 
+    /// Gets the ids of the patches that were placed on this board in the
+    /// order they were placed. Special patches are not included.
+    ///
+    /// Boards that were loaded from a notation do not know which patches were
+    /// placed on them and therefore return an empty slice.
+    ///
+    /// # Returns
+    ///
+    /// The ids of the placed patches in placement order.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    #[must_use]
+    pub fn placed_patch_ids(&self) -> &[u8] {
+        self.placed_patch_ids.as_slice()
+    }
+
     /// Gets the tile at the given row and column.
     ///
     /// # Arguments
@@ -331,8 +476,14 @@ impl QuiltBoard {
             let patch = PatchManager::get_patch(patch_id);
             let transformation = PatchManager::get_transformation(patch_id, patch_transformation_index);
 
-            self.button_income += patch.button_income;
+            debug_assert!(
+                (self.tiles & transformation.tiles) == 0 && (transformation.tiles >> Self::TILES) == 0,
+                "[QuiltBoard::do_action] Invalid action! The patch {patch_id} overlaps filled tiles or extends off the board!"
+            );
+
+            self.add_patch_income(patch);
             self.tiles |= transformation.tiles;
+            self.placed_patch_ids.push(patch_id);
         } else if action.is_special_patch_placement() {
             let index = action.get_quilt_board_index();
 
@@ -369,8 +520,14 @@ impl QuiltBoard {
             let patch = PatchManager::get_patch(patch_id);
             let transformation = PatchManager::get_transformation(patch_id, patch_transformation_index);
 
-            self.button_income -= patch.button_income;
+            self.remove_patch_income(patch);
             self.tiles &= !transformation.tiles;
+
+            let removed_patch_id = self.placed_patch_ids.pop();
+            debug_assert!(
+                removed_patch_id.is_none() || removed_patch_id == Some(patch_id),
+                "[QuiltBoard::undo_action] Invalid action! The patch was not the last placed patch!"
+            );
         } else if action.is_special_patch_placement() {
             let index = action.get_quilt_board_index();
 
@@ -386,6 +543,39 @@ impl QuiltBoard {
         }
     }
 
+    /// Adds the button income of the given patch to the button income of the
+    /// quilt board.
+    ///
+    /// The button income is tracked incrementally so that it never has to be
+    /// recomputed from the placed patches.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - The patch that was placed on the quilt board.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub const fn add_patch_income(&mut self, patch: &Patch) {
+        self.button_income += patch.button_income;
+    }
+
+    /// Removes the button income of the given patch from the button income of
+    /// the quilt board. This is the inverse of [`QuiltBoard::add_patch_income`].
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - The patch that was removed from the quilt board.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub const fn remove_patch_income(&mut self, patch: &Patch) {
+        self.button_income -= patch.button_income;
+    }
+
     // ─────────────────────────────────────────────── GET VALID ACTIONS ───────────────────────────────────────────────
 
     /// Gets the valid actions for the given patch.
@@ -410,6 +600,52 @@ impl QuiltBoard {
         is_player_1: bool,
     ) -> Vec<ActionId> {
         let mut actions = vec![];
+        self.push_valid_actions_for_patch(patch, patch_index, is_player_1, &mut actions);
+        actions
+    }
+
+    /// Counts the valid placements of the given patch without creating the
+    /// actions. This are the transformations of the patch that do not overlap
+    /// a filled tile (see [`QuiltBoard::push_valid_actions_for_patch`]).
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - The patch to count the valid placements for.
+    ///
+    /// # Returns
+    ///
+    /// The amount of valid placements of the given patch.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of transformations for the given patch.
+    #[must_use]
+    pub fn count_valid_placements_for_patch(&self, patch: &Patch) -> usize {
+        PatchManager::get_transformations(patch.id)
+            .iter()
+            .filter(|transformation| self.tiles & transformation.tiles == 0)
+            .count()
+    }
+
+    /// Pushes the valid actions for the given patch onto the given buffer.
+    /// The buffer is not cleared before the actions are pushed.
+    ///
+    /// # Arguments
+    ///
+    /// * `patch` - The patch to get the valid actions for.
+    /// * `patch_index` - The index of the patch in the list of patches.
+    /// * `actions` - The buffer to push the valid actions onto.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of transformations for the given patch.
+    pub fn push_valid_actions_for_patch(
+        &self,
+        patch: &'static Patch,
+        patch_index: u8,
+        is_player_1: bool,
+        actions: &mut Vec<ActionId>,
+    ) {
         for (patch_transformation_index, transformation) in
             PatchManager::get_transformations(patch.id).iter().enumerate()
         {
@@ -421,7 +657,6 @@ impl QuiltBoard {
                 ActionId::patch_placement(patch.id, patch_index, patch_transformation_index as u16, is_player_1);
             actions.push(action);
         }
-        actions
     }
 
     /// Gets the valid actions for the given special patch.
@@ -440,6 +675,21 @@ impl QuiltBoard {
     #[must_use]
     pub fn get_valid_actions_for_special_patch(&self) -> Vec<ActionId> {
         let mut valid_actions: Vec<ActionId> = vec![];
+        self.push_valid_actions_for_special_patch(&mut valid_actions);
+        valid_actions
+    }
+
+    /// Pushes the valid actions for the special patch onto the given buffer.
+    /// The buffer is not cleared before the actions are pushed.
+    ///
+    /// # Arguments
+    ///
+    /// * `valid_actions` - The buffer to push the valid actions onto.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of tiles on the quilt board.
+    pub fn push_valid_actions_for_special_patch(&self, valid_actions: &mut Vec<ActionId>) {
         for index in 0..Self::TILES {
             if (self.tiles >> index) & 1 > 0 {
                 continue;
@@ -448,7 +698,6 @@ impl QuiltBoard {
             let action_id = ActionId::special_patch_placement(index);
             valid_actions.push(action_id);
         }
-        valid_actions
     }
 
     // ─────────────────────────────────────────── ROTATE AND FLIP UTILITIES ───────────────────────────────────────────
@@ -692,3 +941,298 @@ impl Display for QuiltBoard {
         write!(f, "Button income: {}", self.button_income)
     }
 }
+
+/// A displayable view of the difference between two quilt boards (see
+/// [`QuiltBoard::display_diff`]).
+///
+/// Tiles that are filled in both boards are shown as `█`, tiles that are only
+/// filled in the later board are highlighted as `▓` and empty tiles are shown
+/// as `░`.
+#[derive(Debug, Clone, Copy)]
+pub struct QuiltBoardDiff<'a> {
+    /// The board before the change.
+    pub before: &'a QuiltBoard,
+    /// The board after the change.
+    pub after: &'a QuiltBoard,
+}
+
+impl Display for QuiltBoardDiff<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let diff = self.before.diff(self.after);
+        let mut result = String::new();
+
+        for row in 0..QuiltBoard::ROWS {
+            for column in 0..QuiltBoard::COLUMNS {
+                let tile = if diff.get(row, column) {
+                    "▓"
+                } else if self.after.get(row, column) {
+                    "█"
+                } else {
+                    "░"
+                };
+                result.push_str(tile);
+            }
+            result.push('\n');
+        }
+
+        write!(f, "{result}")?;
+        write!(f, "Button income: {} (+{})", self.after.button_income, diff.button_income)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256PlusPlus;
+
+    use super::*;
+    use crate::{GameOptions, Patchwork};
+
+    fn recompute_button_income(placed_patches: &[u8]) -> u8 {
+        placed_patches
+            .iter()
+            .map(|patch_id| PatchManager::get_patch(*patch_id).button_income)
+            .sum()
+    }
+
+    #[test]
+    fn test_incremental_button_income_matches_recomputation() {
+        for seed in 0..100 {
+            let mut random = Xoshiro256PlusPlus::seed_from_u64(seed);
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut history: Vec<(ActionId, bool)> = vec![];
+            let mut placed_patches: [Vec<u8>; 2] = [vec![], vec![]];
+
+            while !state.is_terminated() {
+                // mix in some undos to check that make/unmake keeps the income consistent
+                if !history.is_empty() && random.gen_bool(0.2) {
+                    let (action, was_player_1) = history.pop().unwrap();
+                    state.undo_action(action, false).unwrap();
+                    if action.is_patch_placement() {
+                        placed_patches[usize::from(!was_player_1)].pop();
+                    }
+                } else {
+                    let valid_actions = state.get_valid_actions();
+                    let action = valid_actions[random.gen_range(0..valid_actions.len())];
+                    let is_player_1 = state.is_player_1();
+                    state.do_action(action, false).unwrap();
+                    history.push((action, is_player_1));
+                    if action.is_patch_placement() {
+                        placed_patches[usize::from(!is_player_1)].push(action.get_patch_id());
+                    }
+                }
+
+                assert_eq!(
+                    recompute_button_income(&placed_patches[0]),
+                    state.player_1.quilt_board.button_income
+                );
+                assert_eq!(
+                    recompute_button_income(&placed_patches[1]),
+                    state.player_2.quilt_board.button_income
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_isolated_hole_is_dead_tile() {
+        let hole = 1 << QuiltBoard::get_index(4, 4);
+        let neighbors = [(3, 4), (5, 4), (4, 3), (4, 5)]
+            .into_iter()
+            .fold(0, |tiles, (row, column)| tiles | 1 << QuiltBoard::get_index(row, column));
+
+        // every patch covering the hole would overlap one of the filled neighbors
+        let board = QuiltBoard::from_bits(neighbors);
+        assert_eq!(hole, board.dead_tiles());
+
+        // a hole of two tiles can be covered by a patch with two tiles
+        let board = QuiltBoard::from_bits(neighbors & !(1 << QuiltBoard::get_index(4, 5)));
+        assert_eq!(0, board.dead_tiles());
+
+        assert_eq!(0, QuiltBoard::new().dead_tiles());
+        assert_eq!(0, QuiltBoard::from_bits((1 << QuiltBoard::TILES) - 1).dead_tiles());
+    }
+
+    #[test]
+    fn test_placed_patch_ids_in_placement_order() {
+        let mut quilt_board = QuiltBoard::new();
+        let mut placed = vec![];
+
+        for patch_id in [5, 0, 12] {
+            let action = quilt_board
+                .get_valid_actions_for_patch(PatchManager::get_patch(patch_id), 0, true)
+                .into_iter()
+                .next()
+                .unwrap();
+            quilt_board.do_action(action);
+            placed.push(patch_id);
+
+            assert_eq!(placed.as_slice(), quilt_board.placed_patch_ids());
+        }
+
+        assert_eq!(&[5, 0, 12], quilt_board.placed_patch_ids());
+    }
+
+    #[test]
+    fn test_placed_patch_ids_serialize_like_a_vec() {
+        let mut quilt_board = QuiltBoard::new();
+        for patch_id in [5, 0] {
+            quilt_board.placed_patch_ids.push(patch_id);
+        }
+
+        let json = serde_json::to_value(&quilt_board).unwrap();
+        assert_eq!(serde_json::json!([5, 0]), json["placed_patch_ids"]);
+
+        let deserialized = serde_json::from_value::<QuiltBoard>(json).unwrap();
+        assert_eq!(&[5, 0], deserialized.placed_patch_ids());
+    }
+
+    #[test]
+    fn test_diff_is_footprint_of_placed_patch() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut placements = 0;
+
+        for seed in 0.. {
+            if state.is_terminated() {
+                break;
+            }
+
+            let action = state.get_seeded_random_action(seed);
+            let player_flag = state.get_current_player();
+            let before = state.get_player(player_flag).quilt_board.clone();
+            state.do_action(action, false).unwrap();
+
+            if !action.is_patch_placement() {
+                continue;
+            }
+
+            let patch_id = action.get_patch_id();
+            let transformation = PatchManager::get_transformation(patch_id, action.get_patch_transformation_index());
+            let after = &state.get_player(player_flag).quilt_board;
+            let diff = before.diff(after);
+
+            assert_eq!(transformation.tiles, diff.tiles);
+            assert_eq!(PatchManager::get_patch(patch_id).button_income, diff.button_income);
+            assert_eq!(0, after.diff(&before).tiles);
+            placements += 1;
+        }
+
+        assert!(placements > 0);
+    }
+
+    #[test]
+    fn test_display_diff_highlights_new_tiles() {
+        let before = QuiltBoard::from_bits(0b1);
+        let after = QuiltBoard::from_bits(0b111);
+
+        let display = before.display_diff(&after).to_string();
+
+        assert_eq!(Some("█▓▓░░░░░░"), display.lines().next());
+        assert_eq!(2, display.matches('▓').count());
+    }
+
+    /// Gets the highest row and column of the set tiles.
+    fn get_extent(tiles: u128) -> (u8, u8) {
+        (0..QuiltBoard::TILES)
+            .filter(|index| (tiles >> index) & 1 > 0)
+            .map(QuiltBoard::get_row_column)
+            .fold((0, 0), |(max_row, max_column), (row, column)| (max_row.max(row), max_column.max(column)))
+    }
+
+    #[test]
+    fn test_transformations_are_shifted_shapes_on_the_board() {
+        for patch_id in 0..PatchManager::AMOUNT_OF_NORMAL_PATCHES {
+            let transformations = PatchManager::get_transformations(patch_id);
+            let amount_tiles = PatchManager::get_tiles(patch_id).iter().flatten().filter(|tile| **tile > 0).count();
+            let shapes = transformations
+                .iter()
+                .filter(|transformation| transformation.row == 0 && transformation.column == 0)
+                .map(|transformation| transformation.tiles)
+                .collect::<Vec<_>>();
+
+            for transformation in transformations {
+                let offset = transformation.row * QuiltBoard::COLUMNS + transformation.column;
+                let shape = transformation.tiles >> offset;
+                let (max_row, max_column) = get_extent(shape);
+
+                assert_eq!(0, transformation.tiles >> QuiltBoard::TILES);
+                assert_eq!(transformation.tiles, shape << offset);
+                assert_eq!(amount_tiles, transformation.tiles.count_ones() as usize);
+                assert!(transformation.row + max_row < QuiltBoard::ROWS);
+                assert!(transformation.column + max_column < QuiltBoard::COLUMNS);
+                assert!(shapes.contains(&shape), "patch {patch_id} has an unknown shape {shape:b}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_placements_on_random_boards_are_legal_and_complete() {
+        let mut random = Xoshiro256PlusPlus::seed_from_u64(42);
+
+        for _ in 0..5_000 {
+            let density = random.gen_range(0.0..0.8);
+            let tiles = (0..QuiltBoard::TILES)
+                .filter(|_| random.gen_bool(density))
+                .fold(0u128, |tiles, index| tiles | (1 << index));
+            let board = QuiltBoard::from_bits(tiles);
+
+            for patch_id in 0..PatchManager::AMOUNT_OF_NORMAL_PATCHES {
+                let patch = PatchManager::get_patch(patch_id);
+                let actions = board.get_valid_actions_for_patch(patch, 0, true);
+
+                for action in &actions {
+                    let transformation =
+                        PatchManager::get_transformation(patch_id, action.get_patch_transformation_index());
+                    assert_eq!(0, tiles & transformation.tiles);
+
+                    let mut placed = board.clone();
+                    placed.do_action(*action);
+                    assert_eq!(tiles | transformation.tiles, placed.tiles);
+                    assert_eq!(tiles.count_ones() + patch.amount_tiles(), placed.tiles.count_ones());
+                }
+
+                let fitting = PatchManager::get_transformations(patch_id)
+                    .iter()
+                    .filter(|transformation| tiles & transformation.tiles == 0)
+                    .count();
+                assert_eq!(fitting, actions.len());
+                assert_eq!(fitting, board.count_valid_placements_for_patch(patch));
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_placements_in_random_states_are_legal() {
+        let mut states = 0;
+
+        for seed in 0..200 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut action_seed = seed;
+
+            while !state.is_terminated() {
+                let board = &state.current_player().quilt_board;
+                for action in state.get_valid_actions() {
+                    if action.is_patch_placement() {
+                        let patch_id = action.get_patch_id();
+                        let transformation =
+                            PatchManager::get_transformation(patch_id, action.get_patch_transformation_index());
+                        assert_eq!(0, board.tiles & transformation.tiles);
+                        assert_eq!(0, transformation.tiles >> QuiltBoard::TILES);
+                    } else if action.is_special_patch_placement() {
+                        assert_eq!(0, (board.tiles >> action.get_quilt_board_index()) & 1);
+                    }
+
+                    let mut next_state = state.clone();
+                    next_state.do_action(action, false).unwrap();
+                }
+
+                state.do_action(state.get_seeded_random_action(action_seed), false).unwrap();
+                action_seed += 1;
+                states += 1;
+            }
+        }
+
+        assert!(states > 1_000);
+    }
+}
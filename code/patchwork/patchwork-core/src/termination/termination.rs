@@ -15,6 +15,38 @@ pub struct Termination {
     pub player_1_score: i32,
     /// The score of player 2.
     pub player_2_score: i32,
+    /// What the score of player 1 is made up of.
+    pub player_1_breakdown: ScoreBreakdown,
+    /// What the score of player 2 is made up of.
+    pub player_2_breakdown: ScoreBreakdown,
+    /// The flag of the player that reached the end of the time board first
+    /// (see [`crate::status_flags`]) or `None` if no player reached it yet.
+    pub first_at_end: Option<u8>,
+}
+
+/// The components the score of a player is made up of.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct ScoreBreakdown {
+    /// The buttons the player owns.
+    pub button_balance: i32,
+    /// The points for the empty tiles on the quilt board. Not positive for
+    /// the standard rules.
+    pub empty_tile_penalty: i32,
+    /// The points for the special tile. `0` if the player did not get it.
+    pub special_tile_bonus: i32,
+}
+
+impl ScoreBreakdown {
+    /// Returns the score of the player, i.e. the sum of all components.
+    ///
+    /// # Returns
+    ///
+    /// The score of the player.
+    #[inline]
+    #[must_use]
+    pub const fn total(&self) -> i32 {
+        self.button_balance + self.empty_tile_penalty + self.special_tile_bonus
+    }
 }
 impl Termination {
     /// Returns the score of the game. Positive if player 1 won, negative if player 2 won, 0 if draw. The score is calculated by taking the difference between the score of player 1 and player 2.
@@ -27,4 +59,28 @@ impl Termination {
     pub const fn score(&self) -> i32 {
         self.player_1_score - self.player_2_score
     }
+
+    /// Returns whether both players have the same score. The game is still
+    /// won by the player who reached the end of the time board first.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the game was decided by the goal order instead of the score.
+    #[inline]
+    #[must_use]
+    pub const fn is_tie_on_score(&self) -> bool {
+        self.player_1_score == self.player_2_score
+    }
+
+    /// Returns by how many points the winner is ahead of the loser.
+    ///
+    /// # Returns
+    ///
+    /// The absolute score difference. `0` if the game was decided by the
+    /// tie-break.
+    #[inline]
+    #[must_use]
+    pub const fn margin(&self) -> u32 {
+        self.score().unsigned_abs()
+    }
 }
@@ -0,0 +1,3 @@
+mod thread_affinity;
+
+pub use thread_affinity::*;
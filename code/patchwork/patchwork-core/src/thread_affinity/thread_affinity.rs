@@ -0,0 +1,84 @@
+/// Resets the CPU affinity of the current thread to the affinity of the
+/// process (the affinity of the main thread of the process).
+///
+/// Spawned threads inherit the affinity of the thread that spawned them. The
+/// helper threads of a search call this so that they are not restricted to
+/// the core a pinned thread started the search on (e.g. the pinned workers of
+/// a comparison). Otherwise all threads of the search would share one core.
+///
+/// On other platforms than Linux threads do not inherit the affinity of the
+/// spawning thread, so this does nothing there.
+#[cfg(target_os = "linux")]
+pub fn reset_thread_affinity() {
+    let size = std::mem::size_of::<libc::cpu_set_t>();
+    // SAFETY: the set is a plain bit mask that is only passed with its own size
+    unsafe {
+        let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+        if libc::sched_getaffinity(libc::getpid(), size, std::ptr::addr_of_mut!(set)) == 0 {
+            libc::sched_setaffinity(0, size, std::ptr::addr_of!(set));
+        }
+    }
+}
+
+/// Resets the CPU affinity of the current thread to the affinity of the
+/// process (the affinity of the main thread of the process).
+///
+/// On other platforms than Linux threads do not inherit the affinity of the
+/// spawning thread, so this does nothing.
+#[cfg(not(target_os = "linux"))]
+pub const fn reset_thread_affinity() {}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    const SIZE: usize = std::mem::size_of::<libc::cpu_set_t>();
+
+    fn get_thread_cores() -> libc::c_int {
+        // SAFETY: the set is a plain bit mask that is only passed with its own size
+        unsafe {
+            let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+            assert_eq!(0, libc::sched_getaffinity(0, SIZE, std::ptr::addr_of_mut!(set)));
+            libc::CPU_COUNT(&set)
+        }
+    }
+
+    fn pin_to_single_core() {
+        // SAFETY: the set is a plain bit mask that is only passed with its own size
+        unsafe {
+            let mut set = std::mem::zeroed::<libc::cpu_set_t>();
+            assert_eq!(0, libc::sched_getaffinity(0, SIZE, std::ptr::addr_of_mut!(set)));
+            let core = (0..libc::CPU_SETSIZE as usize)
+                .find(|core| libc::CPU_ISSET(*core, &set))
+                .unwrap();
+
+            libc::CPU_ZERO(&mut set);
+            libc::CPU_SET(core, &mut set);
+            assert_eq!(0, libc::sched_setaffinity(0, SIZE, std::ptr::addr_of!(set)));
+        }
+    }
+
+    #[test]
+    fn test_reset_thread_affinity_undoes_inherited_pinning() {
+        let process_cores = std::thread::spawn(get_thread_cores).join().unwrap();
+
+        let (inherited_cores, reset_cores) = std::thread::spawn(|| {
+            pin_to_single_core();
+
+            std::thread::spawn(|| {
+                let inherited_cores = get_thread_cores();
+                reset_thread_affinity();
+                (inherited_cores, get_thread_cores())
+            })
+            .join()
+            .unwrap()
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(1, inherited_cores);
+        assert_eq!(process_cores, reset_cores);
+    }
+}
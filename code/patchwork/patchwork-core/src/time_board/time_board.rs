@@ -1,4 +1,5 @@
 use std::{
+    cmp::Ordering,
     fmt::Display,
     ops::{Range, RangeInclusive},
 };
@@ -22,7 +23,7 @@ pub mod time_board_flags {
 pub struct TimeBoard {
     /// The tiles of the time board.
     #[serde(with = "serde_bytes")]
-    pub tiles: [u8; 54],
+    pub tiles: [u8; Self::TILES],
 }
 
 impl Default for TimeBoard {
@@ -32,8 +33,11 @@ impl Default for TimeBoard {
 }
 
 impl TimeBoard {
+    /// The amount of tiles on the time board.
+    pub const TILES: usize = 54;
+
     /// The maximum position on the time board.
-    pub const MAX_POSITION: u8 = 53;
+    pub const MAX_POSITION: u8 = (Self::TILES - 1) as u8;
 
     /// The amount of special patches on the time board.
     pub const AMOUNT_OF_SPECIAL_PATCHES: usize = 5;
@@ -41,38 +45,42 @@ impl TimeBoard {
     /// The amount of button income triggers on the time board.
     pub const AMOUNT_OF_BUTTON_INCOME_TRIGGERS: usize = 9;
 
+    /// The positions of the special patches on the time board.
+    pub const SPECIAL_PATCH_POSITIONS: [u8; Self::AMOUNT_OF_SPECIAL_PATCHES] = [26, 32, 38, 44, 50];
+
+    /// The positions of the button income triggers on the time board.
+    pub const BUTTON_INCOME_TRIGGER_POSITIONS: [u8; Self::AMOUNT_OF_BUTTON_INCOME_TRIGGERS] =
+        [5, 11, 17, 23, 29, 35, 41, 47, 53];
+
     /// The position of the first special patch.
-    pub const FIRST_SPECIAL_PATCH_POSITION: u8 = 26;
+    pub const FIRST_SPECIAL_PATCH_POSITION: u8 = Self::SPECIAL_PATCH_POSITIONS[0];
     /// The position of the last special patch.
-    pub const SECOND_SPECIAL_PATCH_POSITION: u8 = 50;
+    pub const SECOND_SPECIAL_PATCH_POSITION: u8 = Self::SPECIAL_PATCH_POSITIONS[Self::AMOUNT_OF_SPECIAL_PATCHES - 1];
 
     /// The position of the first button income trigger.
-    pub const FIRST_BUTTON_INCOME_TRIGGER_POSITION: u8 = 5;
+    pub const FIRST_BUTTON_INCOME_TRIGGER_POSITION: u8 = Self::BUTTON_INCOME_TRIGGER_POSITIONS[0];
     /// The position of the last button income trigger.
-    pub const LAST_BUTTON_INCOME_TRIGGER_POSITION: u8 = 53;
+    pub const LAST_BUTTON_INCOME_TRIGGER_POSITION: u8 =
+        Self::BUTTON_INCOME_TRIGGER_POSITIONS[Self::AMOUNT_OF_BUTTON_INCOME_TRIGGERS - 1];
 
     /// Creates a new time board.
     #[must_use]
     pub const fn new() -> Self {
-        let mut tiles = [0; 54];
+        let mut tiles = [0; Self::TILES];
 
         tiles[0] = time_board_flags::PLAYER_1 | time_board_flags::PLAYER_2;
 
-        tiles[5] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[11] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[17] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[23] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[29] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[35] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[41] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[47] = time_board_flags::BUTTON_INCOME_TRIGGER;
-        tiles[53] = time_board_flags::BUTTON_INCOME_TRIGGER;
-
-        tiles[26] = time_board_flags::SPECIAL_PATCH;
-        tiles[32] = time_board_flags::SPECIAL_PATCH;
-        tiles[38] = time_board_flags::SPECIAL_PATCH;
-        tiles[44] = time_board_flags::SPECIAL_PATCH;
-        tiles[50] = time_board_flags::SPECIAL_PATCH;
+        let mut i = 0;
+        while i < Self::AMOUNT_OF_BUTTON_INCOME_TRIGGERS {
+            tiles[Self::BUTTON_INCOME_TRIGGER_POSITIONS[i] as usize] = time_board_flags::BUTTON_INCOME_TRIGGER;
+            i += 1;
+        }
+
+        let mut i = 0;
+        while i < Self::AMOUNT_OF_SPECIAL_PATCHES {
+            tiles[Self::SPECIAL_PATCH_POSITIONS[i] as usize] = time_board_flags::SPECIAL_PATCH;
+            i += 1;
+        }
 
         Self { tiles }
     }
@@ -90,7 +98,7 @@ impl TimeBoard {
     /// `𝒪(𝟣)`
     #[must_use]
     pub const fn get_special_patches(&self) -> &[u8] {
-        &[26, 32, 38, 44, 50]
+        &Self::SPECIAL_PATCH_POSITIONS
     }
 
     /// Sets the special patch at the given index.
@@ -326,7 +334,7 @@ impl TimeBoard {
     /// `𝒪(𝟣)`
     #[must_use]
     pub const fn get_button_income_triggers(&self) -> &[u8] {
-        &[5, 11, 17, 23, 29, 35, 41, 47, 53]
+        &Self::BUTTON_INCOME_TRIGGER_POSITIONS
     }
 
     /// Checks if there is a button income trigger at the given index.
@@ -568,6 +576,47 @@ impl TimeBoard {
         (player_1_position, player_2_position)
     }
 
+    /// Gets the player whose turn it is according to the positions of the
+    /// players on the time board. The player whose time token is furthest
+    /// behind takes the next turn.
+    ///
+    /// Only the normal turns follow from the positions. If both tokens are on
+    /// the same tile, the player whose token is on top moves next, which is
+    /// not stored on the time board. A player placing a special patch keeps
+    /// the turn even if the token is ahead.
+    ///
+    /// # Returns
+    ///
+    /// * `Option<u8>` - The player flag of the player to move or `None` if both players are on the same tile.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of tiles on the time board (usually 54).
+    #[must_use]
+    pub fn player_to_move(&self) -> Option<u8> {
+        let (player_1_position, player_2_position) = self.get_player_positions();
+        match player_1_position.cmp(&player_2_position) {
+            Ordering::Less => Some(time_board_flags::PLAYER_1),
+            Ordering::Greater => Some(time_board_flags::PLAYER_2),
+            Ordering::Equal => None,
+        }
+    }
+
+    /// Gets the amount of tiles between the time tokens of both players.
+    ///
+    /// # Returns
+    ///
+    /// * `u8` - The distance between both players. `0` if both players are on the same tile.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the amount of tiles on the time board (usually 54).
+    #[must_use]
+    pub fn distance_between_players(&self) -> u8 {
+        let (player_1_position, player_2_position) = self.get_player_positions();
+        player_1_position.abs_diff(player_2_position)
+    }
+
     /// Sets the position of the given player.
     ///
     /// # Arguments
@@ -661,3 +710,84 @@ fn get_str_for_tile(tile: u8) -> String {
 
     result_str
 }
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::{GameOptions, Patchwork, TurnType};
+
+    use super::*;
+
+    #[test]
+    fn test_constants_match_board_layout() {
+        let time_board = TimeBoard::default();
+
+        assert_eq!(TimeBoard::TILES, time_board.tiles.len());
+        assert_eq!(usize::from(TimeBoard::MAX_POSITION), time_board.tiles.len() - 1);
+
+        let special_patches = (0..TimeBoard::TILES)
+            .filter(|index| time_board.is_special_patch_at(*index))
+            .map(|index| index as u8)
+            .collect::<Vec<_>>();
+        assert_eq!(TimeBoard::SPECIAL_PATCH_POSITIONS.to_vec(), special_patches);
+        assert_eq!(TimeBoard::AMOUNT_OF_SPECIAL_PATCHES, special_patches.len());
+        assert_eq!(Some(&TimeBoard::FIRST_SPECIAL_PATCH_POSITION), special_patches.first());
+        assert_eq!(Some(&TimeBoard::SECOND_SPECIAL_PATCH_POSITION), special_patches.last());
+
+        let button_income_triggers = (0..TimeBoard::TILES)
+            .filter(|index| time_board.is_button_income_trigger_at(*index))
+            .map(|index| index as u8)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            TimeBoard::BUTTON_INCOME_TRIGGER_POSITIONS.to_vec(),
+            button_income_triggers
+        );
+        assert_eq!(
+            TimeBoard::AMOUNT_OF_BUTTON_INCOME_TRIGGERS,
+            button_income_triggers.len()
+        );
+        assert_eq!(
+            Some(&TimeBoard::FIRST_BUTTON_INCOME_TRIGGER_POSITION),
+            button_income_triggers.first()
+        );
+        assert_eq!(
+            Some(&TimeBoard::LAST_BUTTON_INCOME_TRIGGER_POSITION),
+            button_income_triggers.last()
+        );
+    }
+
+    #[test]
+    fn test_player_to_move_matches_current_player() {
+        let mut compared_states = 0;
+        for seed in 0..100 {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut action_seed = seed;
+            while !state.is_terminated() {
+                let (player_1_position, player_2_position) = state.time_board.get_player_positions();
+                assert_eq!(
+                    player_1_position.abs_diff(player_2_position),
+                    state.time_board.distance_between_players()
+                );
+
+                if let Some(player_to_move) = state.time_board.player_to_move() {
+                    if state.turn_type == TurnType::Normal {
+                        assert_eq!(
+                            Patchwork::is_flag_player_1(player_to_move),
+                            state.is_player_1(),
+                            "Different player to move in {state}"
+                        );
+                        compared_states += 1;
+                    }
+                } else {
+                    assert_eq!(player_1_position, player_2_position);
+                }
+
+                state.do_action(state.get_seeded_random_action(action_seed), false).unwrap();
+                action_seed += 1;
+            }
+        }
+
+        assert!(compared_states > 1000);
+    }
+}
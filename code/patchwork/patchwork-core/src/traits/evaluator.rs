@@ -5,6 +5,11 @@ pub mod evaluator_constants {
     pub const POSITIVE_INFINITY: i32 = 100_000;
     /// The minimum evaluation for a losing game. No evaluation should be lower than this.
     pub const NEGATIVE_INFINITY: i32 = -POSITIVE_INFINITY;
+    /// The scale of the logistic function that maps evaluations to win probabilities.
+    ///
+    /// An evaluation of this size corresponds to a win probability of about 73%. The scale was chosen by hand so that the
+    /// evaluations of the static evaluator map to plausible win probabilities and was not fitted to game outcomes.
+    pub const WIN_PROBABILITY_SCALE: f32 = 75.0;
 }
 
 /// A game evaluator for the 2 player game Patchwork.
@@ -82,6 +87,31 @@ pub trait Evaluator: Sync {
 
         score
     }
+
+    /// Returns the probability that player 1 wins the game from the given state.
+    ///
+    /// The default implementation maps the evaluation of intermediate states through a logistic function with the
+    /// scale [`evaluator_constants::WIN_PROBABILITY_SCALE`]. Evaluators that directly estimate probabilities should
+    /// override this.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game state to get the win probability for.
+    ///
+    /// # Returns
+    ///
+    /// The probability in `[0, 1]` that player 1 wins the game. Terminal states are always `0` or `1`.
+    fn win_probability(&self, game: &Patchwork) -> f32 {
+        if game.is_terminated() {
+            return match game.get_termination_result().termination {
+                TerminationType::Player1Won => 1.0,
+                TerminationType::Player2Won => 0.0,
+            };
+        }
+
+        let evaluation = self.evaluate_intermediate_node(game) as f32;
+        1.0 / (1.0 + (-evaluation / evaluator_constants::WIN_PROBABILITY_SCALE).exp())
+    }
 }
 
 /// A game evaluator that is stable.
@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+use crate::{ActionId, Patchwork};
+
+/// A base trait for all observers of a game.
+///
+/// Observers are notified by the game loop after every move. This decouples
+/// logging and analysis from the game loop itself.
+pub trait GameObserver {
+    /// Called after a player chose an action.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state in which the action was chosen (before the action was taken).
+    /// * `action` - The action that was chosen.
+    /// * `evaluation` - The evaluation of the state reported by the engine if available.
+    /// * `principal_variation` - The principal variation reported by the engine. Empty if not available.
+    ///
+    /// # Returns
+    ///
+    /// An error if the observer failed to process the move.
+    fn on_move(
+        &mut self,
+        state: &Patchwork,
+        action: ActionId,
+        evaluation: Option<i32>,
+        principal_variation: &[ActionId],
+    ) -> Result<()>;
+
+    /// Called after moves were taken back, e.g. because a human player
+    /// requested to undo their last action. The next call of
+    /// [`GameObserver::on_move`] is for the state the game continues from.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The state the game continues from.
+    /// * `undone_moves` - The amount of moves that were taken back.
+    ///
+    /// # Returns
+    ///
+    /// An error if the observer failed to process the undo.
+    fn on_undo(&mut self, _state: &Patchwork, _undone_moves: usize) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called after the game ended.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The terminal state of the game.
+    ///
+    /// # Returns
+    ///
+    /// An error if the observer failed to process the end of the game.
+    fn on_game_end(&mut self, _state: &Patchwork) -> Result<()> {
+        Ok(())
+    }
+}
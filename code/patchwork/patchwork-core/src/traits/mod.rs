@@ -1,11 +1,13 @@
 mod evaluator;
+mod game_observer;
 mod logging;
 mod player;
 mod tree_policy;
 mod tree_policy_node;
 
 pub use evaluator::{evaluator_constants, Evaluator, StableEvaluator};
+pub use game_observer::GameObserver;
 pub use logging::Logging;
-pub use player::{Player, PlayerResult};
-pub use tree_policy::{ScoredTreePolicy, TreePolicy};
+pub use player::{IterationTiming, Player, PlayerResult, SearchDiagnostics, TranspositionTableDiagnostics};
+pub use tree_policy::{ExplorationTreePolicy, ScoredTreePolicy, TreePolicy};
 pub use tree_policy_node::TreePolicyNode;
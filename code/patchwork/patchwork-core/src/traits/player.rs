@@ -2,7 +2,7 @@ use anyhow::Result;
 
 pub type PlayerResult<T> = Result<T>;
 
-use crate::{ActionId, Patchwork};
+use crate::{ActionId, Logging, Patchwork};
 
 /// A base trait for all players.
 pub trait Player {
@@ -19,4 +19,202 @@ pub trait Player {
     ///
     /// The action that the player wants to take.
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId>;
+
+    /// A method that returns the action that the player wants to take when
+    /// only the given actions may be chosen at the root of the search (e.g.
+    /// to analyze specific candidate actions).
+    ///
+    /// Players that cannot restrict their search choose their action as usual
+    /// and fail if it is not among the given actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The current state of the game.
+    /// * `actions` - The valid actions the player may choose from. If empty
+    ///   all valid actions may be chosen.
+    ///
+    /// # Returns
+    ///
+    /// The action that the player wants to take out of the given actions.
+    ///
+    /// # Errors
+    ///
+    /// If the player cannot restrict its search and chose another action.
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        let action = self.get_action(game)?;
+
+        if actions.is_empty() || actions.contains(&action) {
+            Ok(action)
+        } else {
+            Err(anyhow::Error::msg(format!(
+                "[Player::get_restricted_action] {} cannot restrict its search and chose {action:?} instead of one \
+                 of {actions:?}",
+                self.name()
+            )))
+        }
+    }
+
+    /// A method that returns the action that the player wants to take together
+    /// with diagnostics about the search that was done to find the action.
+    ///
+    /// Players that do not search only report the time it took to get the
+    /// action.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The current state of the game.
+    ///
+    /// # Returns
+    ///
+    /// The action that the player wants to take and the diagnostics of the
+    /// search.
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        let start_time = std::time::Instant::now();
+        let action = self.get_action(game)?;
+
+        Ok((
+            action,
+            SearchDiagnostics {
+                time: start_time.elapsed(),
+                ..SearchDiagnostics::default()
+            },
+        ))
+    }
+
+    /// A method that returns the action that the player wants to take out of
+    /// the given actions together with diagnostics about the search that was
+    /// done to find the action.
+    ///
+    /// Players that cannot report diagnostics for a restricted search only
+    /// report the time it took to get the action.
+    ///
+    /// # Arguments
+    ///
+    /// * `state` - The current state of the game.
+    /// * `actions` - The valid actions the player may choose from. If empty
+    ///   all valid actions may be chosen.
+    ///
+    /// # Returns
+    ///
+    /// The action that the player wants to take out of the given actions and
+    /// the diagnostics of the search.
+    ///
+    /// # Errors
+    ///
+    /// If the player cannot restrict its search and chose another action.
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        if actions.is_empty() {
+            return self.get_action_with_diagnostics(game);
+        }
+
+        let start_time = std::time::Instant::now();
+        let action = self.get_restricted_action(game, actions)?;
+
+        Ok((
+            action,
+            SearchDiagnostics {
+                time: start_time.elapsed(),
+                ..SearchDiagnostics::default()
+            },
+        ))
+    }
+
+    /// Replaces the logging of the player, e.g. to write the progress of the
+    /// following searches somewhere else.
+    ///
+    /// Players that do not log anything ignore the logging.
+    ///
+    /// # Arguments
+    ///
+    /// * `logging` - The logging the player uses from now on.
+    fn set_logging(&mut self, _logging: Logging) {}
+
+    /// Clears all state the player accumulated over the moves of a game (e.g.
+    /// search trees or transposition tables) so that the same player can
+    /// start a new game as if it was newly created.
+    ///
+    /// Players without such state do not have to implement this.
+    fn reset(&mut self) {}
+}
+
+/// Diagnostics about a search of a tree-search player.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchDiagnostics {
+    /// The depth that was reached by the search.
+    pub depth: usize,
+    /// The number of nodes that were searched.
+    pub nodes: usize,
+    /// The time the search took.
+    pub time: std::time::Duration,
+    /// Diagnostics about the transposition table if one was used.
+    pub transposition_table: Option<TranspositionTableDiagnostics>,
+    /// The principal variation starting from the searched state.
+    pub principal_variation: Vec<ActionId>,
+    /// The predicted and actual times of the completed iterations if the
+    /// search uses iterative deepening.
+    pub iterations: Vec<IterationTiming>,
+    /// The evaluations of root actions from the perspective of the player to
+    /// move. The first entry is the chosen action, the second (if any) the
+    /// best alternative. Searches that do not evaluate the alternatives
+    /// exactly only report the chosen action.
+    pub root_evaluations: Vec<(ActionId, i32)>,
+}
+
+impl SearchDiagnostics {
+    /// Gets the evaluation of the chosen action.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation from the perspective of the player to move or `None` if
+    /// the player did not report it.
+    #[must_use]
+    pub fn evaluation(&self) -> Option<i32> {
+        self.root_evaluations.first().map(|(_, evaluation)| *evaluation)
+    }
+
+    /// Gets the margin of the chosen action, i.e. how much better the chosen
+    /// action is evaluated than the best alternative. A large margin means
+    /// that every alternative would have been clearly worse.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the chosen action minus the evaluation of the best
+    /// alternative or `None` if the player did not report both.
+    #[must_use]
+    pub fn margin(&self) -> Option<i32> {
+        match self.root_evaluations.as_slice() {
+            [(_, chosen), (_, alternative), ..] => Some(chosen - alternative),
+            _ => None,
+        }
+    }
+}
+
+/// The time a single completed iteration of an iterative deepening search
+/// took together with the time that was predicted for it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct IterationTiming {
+    /// The depth that was searched in the iteration.
+    pub depth: usize,
+    /// The time that was predicted for the iteration before it was started.
+    /// `None` if there were not enough previous iterations to predict it.
+    pub predicted: Option<std::time::Duration>,
+    /// The time the iteration actually took.
+    pub actual: std::time::Duration,
+}
+
+/// Diagnostics about the usage of a transposition table during a search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TranspositionTableDiagnostics {
+    /// The amount of entries in the transposition table after the search.
+    pub entries: usize,
+    /// The capacity of the transposition table (in entries).
+    pub capacity: usize,
+    /// The amount of accesses to the transposition table during the search.
+    pub accesses: usize,
+    /// The amount of accesses during the search that hit an entry.
+    pub hits: usize,
 }
@@ -24,6 +24,116 @@ pub trait TreePolicy: Sync {
         parent: &Node,
         children: impl Iterator<Item = &'a Node>,
     ) -> &'a Node;
+
+    /// Selects up to `amount` distinct children of the parent node to explore
+    /// in parallel.
+    ///
+    /// The default implementation selects the children one after another.
+    /// After each pick a virtual loss is applied by counting a virtual visit of
+    /// the parent node and the picked child is excluded from the following
+    /// picks.
+    ///
+    /// # Arguments
+    ///
+    /// * `parent` - The parent node to select the best children from.
+    /// * `children` - The children of the parent node.
+    /// * `amount` - The maximum amount of children to select.
+    ///
+    /// # Returns
+    ///
+    /// The selected children in the order they were picked. Contains fewer
+    /// than `amount` children if the parent node has fewer children.
+    fn select_batch<'a, Node: TreePolicyNode>(
+        &self,
+        parent: &Node,
+        children: impl Iterator<Item = &'a Node>,
+        amount: usize,
+    ) -> Vec<&'a Node> {
+        let mut candidates = children.map(|child| VirtualLossNode::new(child, 0)).collect::<Vec<_>>();
+        let amount = amount.min(candidates.len());
+        let mut selected = Vec::with_capacity(amount);
+
+        for virtual_visits in 0..amount {
+            let virtual_parent = VirtualLossNode::new(parent, virtual_visits);
+            let picked = self.select_node(&virtual_parent, candidates.iter());
+            let picked_index = candidates
+                .iter()
+                .position(|candidate| std::ptr::eq(candidate, picked))
+                .expect("[TreePolicy::select_batch] The selected node is not a candidate.");
+
+            selected.push(candidates.swap_remove(picked_index).node);
+        }
+
+        selected
+    }
+}
+
+/// A node wrapper that counts additional virtual visits (virtual loss) for
+/// selecting multiple nodes in parallel.
+struct VirtualLossNode<'a, Node: TreePolicyNode> {
+    /// The wrapped node.
+    node: &'a Node,
+    /// The amount of virtual visits added to the wrapped node.
+    virtual_visits: usize,
+}
+
+impl<'a, Node: TreePolicyNode> VirtualLossNode<'a, Node> {
+    /// Creates a new [`VirtualLossNode`] wrapping the given node.
+    const fn new(node: &'a Node, virtual_visits: usize) -> Self {
+        Self { node, virtual_visits }
+    }
+}
+
+impl<Node: TreePolicyNode> TreePolicyNode for VirtualLossNode<'_, Node> {
+    type Player = Node::Player;
+
+    fn visit_count(&self) -> usize {
+        self.node.visit_count() + self.virtual_visits
+    }
+
+    fn current_player(&self) -> Self::Player {
+        self.node.current_player()
+    }
+
+    fn wins_for(&self, player: Self::Player) -> i32 {
+        self.node.wins_for(player)
+    }
+
+    fn maximum_score_for(&self, player: Self::Player) -> f64 {
+        self.node.maximum_score_for(player)
+    }
+
+    fn minimum_score_for(&self, player: Self::Player) -> f64 {
+        self.node.minimum_score_for(player)
+    }
+
+    fn score_range(&self) -> f64 {
+        self.node.score_range()
+    }
+
+    fn score_sum_for(&self, player: Self::Player) -> f64 {
+        self.node.score_sum_for(player)
+    }
+
+    fn prior_value(&self) -> f64 {
+        self.node.prior_value()
+    }
+}
+
+/// A tree policy that balances exploration and exploitation with a
+/// configurable exploration constant.
+pub trait ExplorationTreePolicy: TreePolicy {
+    /// Creates the tree policy with the given exploration constant. All other
+    /// parameters of the policy keep their default values.
+    ///
+    /// # Arguments
+    ///
+    /// * `exploration_constant` - The exploration constant for the policy.
+    ///
+    /// # Returns
+    ///
+    /// The tree policy with the given exploration constant.
+    fn with_exploration_constant(exploration_constant: f64) -> Self;
 }
 
 /// A convenience trait for tree policies that score nodes and choose the node
@@ -83,3 +193,99 @@ impl<T: ScoredTreePolicy> TreePolicy for T {
         best_node.expect("[ScoredTreePolicy::select_node] No children were given to select.")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    struct TestNode {
+        visit_count: usize,
+        wins: i32,
+    }
+
+    impl TreePolicyNode for TestNode {
+        type Player = ();
+
+        fn visit_count(&self) -> usize {
+            self.visit_count
+        }
+
+        fn current_player(&self) -> Self::Player {}
+
+        fn wins_for(&self, (): Self::Player) -> i32 {
+            self.wins
+        }
+
+        fn maximum_score_for(&self, (): Self::Player) -> f64 {
+            1.0
+        }
+
+        fn minimum_score_for(&self, (): Self::Player) -> f64 {
+            -1.0
+        }
+
+        fn score_sum_for(&self, (): Self::Player) -> f64 {
+            f64::from(self.wins)
+        }
+    }
+
+    /// A policy that selects the child with the most wins and records the
+    /// visit counts of the parent it was called with.
+    #[derive(Default)]
+    struct MostWinsPolicy {
+        parent_visit_counts: Mutex<Vec<usize>>,
+    }
+
+    impl TreePolicy for MostWinsPolicy {
+        fn select_node<'a, Node: TreePolicyNode>(
+            &self,
+            parent: &Node,
+            children: impl Iterator<Item = &'a Node>,
+        ) -> &'a Node {
+            self.parent_visit_counts.lock().unwrap().push(parent.visit_count());
+            children
+                .max_by_key(|child| child.wins_for(parent.current_player()))
+                .unwrap()
+        }
+    }
+
+    fn create_children() -> Vec<TestNode> {
+        [3, 7, 1, 5, 4]
+            .into_iter()
+            .map(|wins| TestNode { visit_count: 10, wins })
+            .collect()
+    }
+
+    #[test]
+    fn test_select_batch_returns_distinct_children() {
+        let policy = MostWinsPolicy::default();
+        let parent = TestNode {
+            visit_count: 50,
+            wins: 0,
+        };
+        let children = create_children();
+
+        let selected = policy.select_batch(&parent, children.iter(), 3);
+
+        assert_eq!(vec![7, 5, 4], selected.iter().map(|child| child.wins).collect::<Vec<_>>());
+        assert_eq!(vec![50, 51, 52], *policy.parent_visit_counts.lock().unwrap());
+    }
+
+    #[test]
+    fn test_select_batch_is_limited_by_children() {
+        let policy = MostWinsPolicy::default();
+        let parent = TestNode {
+            visit_count: 50,
+            wins: 0,
+        };
+        let children = create_children();
+
+        let selected = policy.select_batch(&parent, children.iter(), 10);
+
+        assert_eq!(vec![7, 5, 4, 3, 1], selected.iter().map(|child| child.wins).collect::<Vec<_>>());
+    }
+}
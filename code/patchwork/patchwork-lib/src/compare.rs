@@ -0,0 +1,797 @@
+use std::{
+    collections::BTreeMap,
+    panic,
+    sync::{
+        atomic::{self, AtomicI32, AtomicU32, AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use anyhow::Error;
+use patchwork_core::{
+    ActionId, GameObserver, GameRecord, Notation, Patchwork, Player, PlayerResult, SearchDiagnostics, TerminationType,
+};
+
+/// A game that was played in a comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedGame {
+    pub player_1_name: String,
+    pub player_2_name: String,
+    pub result: TerminationType,
+    /// The initial state and all actions of the game. Only recorded if the
+    /// records were requested (see [`run_compare`]).
+    pub record: Option<GameRecord>,
+}
+
+/// The result of a comparison of two players.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompareResult {
+    pub wins_player_1: u32,
+    pub wins_player_2: u32,
+    /// The amount of games where both players had the same score and the
+    /// winner was determined by who reached the end first.
+    pub score_ties: u32,
+    pub max_player_1_score: i32,
+    pub max_player_2_score: i32,
+    pub min_player_1_score: i32,
+    pub min_player_2_score: i32,
+    pub sum_player_1_score: i32,
+    pub sum_player_2_score: i32,
+    /// The summed time player 1 needed for all turns in nanoseconds.
+    pub sum_time_player_1: u64,
+    /// The summed time player 2 needed for all turns in nanoseconds.
+    pub sum_time_player_2: u64,
+    pub turns_player_1: u64,
+    pub turns_player_2: u64,
+    /// The summed amount of plies of all finished games.
+    pub plies: u64,
+    /// The summed amount of legal moves over all plies of all finished games.
+    pub legal_moves: u64,
+    /// How often player 1 played each first move (in action notation) of all finished games.
+    pub first_moves_player_1: BTreeMap<String, u64>,
+    /// How often player 2 played each first move (in action notation) of all finished games.
+    pub first_moves_player_2: BTreeMap<String, u64>,
+    /// The ids of the CPU cores the worker threads were pinned to (in the
+    /// order the workers were started). Empty if the workers were not pinned.
+    pub pinned_cores: Vec<usize>,
+}
+
+impl CompareResult {
+    /// Creates a result without any finished games.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            wins_player_1: 0,
+            wins_player_2: 0,
+            score_ties: 0,
+            max_player_1_score: i32::MIN,
+            max_player_2_score: i32::MIN,
+            min_player_1_score: i32::MAX,
+            min_player_2_score: i32::MAX,
+            sum_player_1_score: 0,
+            sum_player_2_score: 0,
+            sum_time_player_1: 0,
+            sum_time_player_2: 0,
+            turns_player_1: 0,
+            turns_player_2: 0,
+            plies: 0,
+            legal_moves: 0,
+            first_moves_player_1: BTreeMap::new(),
+            first_moves_player_2: BTreeMap::new(),
+            pinned_cores: vec![],
+        }
+    }
+
+    /// Gets the amount of finished games.
+    #[must_use]
+    pub const fn games(&self) -> u32 {
+        self.wins_player_1 + self.wins_player_2
+    }
+
+    /// Gets the average amount of plies per game or `0.0` if no game was finished.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_plies(&self) -> f64 {
+        if self.games() == 0 {
+            return 0.0;
+        }
+        self.plies as f64 / f64::from(self.games())
+    }
+
+    /// Gets the average amount of legal moves per ply or `0.0` if no ply was played.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn average_branching_factor(&self) -> f64 {
+        if self.plies == 0 {
+            return 0.0;
+        }
+        self.legal_moves as f64 / self.plies as f64
+    }
+}
+
+impl Default for CompareResult {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gets the Shannon entropy of the given move distribution in bits.
+///
+/// An engine that always plays the same move has an entropy of `0.0` while an engine
+/// that plays `n` different moves equally often has an entropy of `log2(n)`.
+///
+/// # Arguments
+///
+/// * `frequencies` - How often each move was played.
+///
+/// # Returns
+///
+/// The entropy in bits or `0.0` if no move was played.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn move_entropy(frequencies: &BTreeMap<String, u64>) -> f64 {
+    let total = frequencies.values().sum::<u64>() as f64;
+    if total == 0.0 {
+        return 0.0;
+    }
+
+    frequencies
+        .values()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / total;
+            probability * (1.0 / probability).log2()
+        })
+        .sum()
+}
+
+/// Limits the rate at which games are played. Every game reserves the next
+/// free time slot before it starts, so that the `n`-th game of a comparison
+/// starts at least `n` slots after the comparison started and the games
+/// finish at most with the given rate on average.
+struct GameThrottle {
+    /// The time between the starts of two games.
+    interval: std::time::Duration,
+    /// The time the next game may start at.
+    next_start: Mutex<std::time::Instant>,
+}
+
+impl GameThrottle {
+    /// Creates a new throttle for the given amount of games per second.
+    ///
+    /// # Errors
+    ///
+    /// If the amount of games per second is not positive.
+    fn new(games_per_second: f64) -> anyhow::Result<Self> {
+        if !games_per_second.is_finite() || games_per_second <= 0.0 {
+            return Err(Error::msg(format!(
+                "The throttle has to be a positive amount of games per second but was {games_per_second}"
+            )));
+        }
+
+        let interval = std::time::Duration::from_secs_f64(1.0 / games_per_second);
+        Ok(Self {
+            interval,
+            next_start: Mutex::new(std::time::Instant::now() + interval),
+        })
+    }
+
+    /// Reserves the next time slot and sleeps until it is reached.
+    fn wait(&self) {
+        let start = {
+            let mut next_start = self.next_start.lock().unwrap();
+            let start = (*next_start).max(std::time::Instant::now());
+            *next_start = start + self.interval;
+            start
+        };
+        std::thread::sleep(start.saturating_duration_since(std::time::Instant::now()));
+    }
+}
+
+/// Plays games between the players in parallel until the given amount of
+/// games is finished. Apart from the given observers and the callback this
+/// has no side effects.
+///
+/// # Arguments
+///
+/// * `iterations` - The total amount of games including the games of the initial result.
+/// * `create_player_1` - Creates the first player. Every worker constructs its own player.
+/// * `create_player_2` - Creates the second player. Every worker constructs its own player.
+/// * `parallelization` - The amount of games that are played in parallel.
+/// * `pin_cores` - Whether every worker thread is pinned to its own CPU core so that the timings are not skewed by the
+///   scheduling of the operating system. With more workers than cores the cores are assigned round robin. Only the
+///   worker thread is pinned, the helper threads of a parallel search reset their affinity (see
+///   [`patchwork_core::reset_thread_affinity`]).
+/// * `throttle` - The maximum amount of games per second. Workers sleep before starting a game to keep below it.
+/// * `record_games` - Whether the full record of every game is passed to the callback. The records are only kept until
+///   the callback returned.
+/// * `observers` - The observers that are notified about every move and finished game.
+/// * `initial` - The result of the games that were already played, e.g. of a resumed comparison.
+/// * `update` - The time between two calls of the callback.
+/// * `on_update` - Called regularly and after the last game with the current result and the games that finished since
+///   the last call. The games are not kept after the call.
+///
+/// # Returns
+///
+/// The result of all games.
+///
+/// # Errors
+///
+/// If the callback returns an error, a player could not be created or a worker panicked. The callback is still called
+/// after the panic so that the finished games are not lost.
+#[allow(clippy::too_many_lines, clippy::too_many_arguments)]
+pub fn run_compare(
+    iterations: usize,
+    create_player_1: impl Fn() -> PlayerResult<Box<dyn Player>> + Sync,
+    create_player_2: impl Fn() -> PlayerResult<Box<dyn Player>> + Sync,
+    parallelization: usize,
+    pin_cores: bool,
+    throttle: Option<f64>,
+    record_games: bool,
+    observers: &Mutex<Vec<Box<dyn GameObserver + Send>>>,
+    initial: &CompareResult,
+    update: std::time::Duration,
+    mut on_update: impl FnMut(&CompareResult, Vec<RecordedGame>) -> anyhow::Result<()>,
+) -> anyhow::Result<CompareResult> {
+    let max_player_1_score = AtomicI32::new(initial.max_player_1_score);
+    let max_player_2_score = AtomicI32::new(initial.max_player_2_score);
+    let min_player_1_score = AtomicI32::new(initial.min_player_1_score);
+    let min_player_2_score = AtomicI32::new(initial.min_player_2_score);
+    let sum_player_1_score = AtomicI32::new(initial.sum_player_1_score);
+    let sum_player_2_score = AtomicI32::new(initial.sum_player_2_score);
+    let sum_time_player_1 = AtomicU64::new(initial.sum_time_player_1);
+    let sum_time_player_2 = AtomicU64::new(initial.sum_time_player_2);
+    let n_time_player_1 = AtomicU64::new(initial.turns_player_1);
+    let n_time_player_2 = AtomicU64::new(initial.turns_player_2);
+    let wins_player_1 = AtomicU32::new(initial.wins_player_1);
+    let wins_player_2 = AtomicU32::new(initial.wins_player_2);
+    let score_ties = AtomicU32::new(initial.score_ties);
+    let plies = AtomicU64::new(initial.plies);
+    let legal_moves = AtomicU64::new(initial.legal_moves);
+    let first_moves = Mutex::new([
+        initial.first_moves_player_1.clone(),
+        initial.first_moves_player_2.clone(),
+    ]);
+    let panic_cause = Mutex::new(None);
+    let core_ids = if pin_cores {
+        core_affinity::get_core_ids()
+            .filter(|core_ids| !core_ids.is_empty())
+            .ok_or_else(|| Error::msg("The CPU cores to pin the worker threads to could not be determined"))?
+    } else {
+        vec![]
+    };
+    let pinned_cores = Mutex::new(vec![]);
+    let throttle = throttle.map(GameThrottle::new).transpose()?;
+
+    // finished games are counted and buffered while holding this lock so that
+    // every update contains exactly the games that are counted in the result
+    let pending_games = Mutex::new(vec![]);
+    let mut report = || -> anyhow::Result<CompareResult> {
+        let mut pending_games = pending_games.lock().unwrap();
+        let [first_moves_player_1, first_moves_player_2] = first_moves.lock().unwrap().clone();
+        let result = CompareResult {
+            wins_player_1: wins_player_1.load(Ordering::Relaxed),
+            wins_player_2: wins_player_2.load(Ordering::Relaxed),
+            score_ties: score_ties.load(Ordering::Relaxed),
+            max_player_1_score: max_player_1_score.load(Ordering::Relaxed),
+            max_player_2_score: max_player_2_score.load(Ordering::Relaxed),
+            min_player_1_score: min_player_1_score.load(Ordering::Relaxed),
+            min_player_2_score: min_player_2_score.load(Ordering::Relaxed),
+            sum_player_1_score: sum_player_1_score.load(Ordering::Relaxed),
+            sum_player_2_score: sum_player_2_score.load(Ordering::Relaxed),
+            sum_time_player_1: sum_time_player_1.load(Ordering::Relaxed),
+            sum_time_player_2: sum_time_player_2.load(Ordering::Relaxed),
+            turns_player_1: n_time_player_1.load(Ordering::Relaxed),
+            turns_player_2: n_time_player_2.load(Ordering::Relaxed),
+            plies: plies.load(Ordering::Relaxed),
+            legal_moves: legal_moves.load(Ordering::Relaxed),
+            first_moves_player_1,
+            first_moves_player_2,
+            pinned_cores: vec![],
+        };
+        on_update(&result, std::mem::take(&mut *pending_games))?;
+
+        // the lock is held until the update is done
+        drop(pending_games);
+        Ok(result)
+    };
+
+    let observed = !observers.lock().unwrap().is_empty();
+    let iterations_done = AtomicU32::new(initial.games());
+    std::thread::scope(|s| {
+        let mut handles = vec![];
+
+        for worker in 0..parallelization {
+            let iterations = iterations as u32;
+            let core_id = (!core_ids.is_empty()).then(|| core_ids[worker % core_ids.len()]);
+            let pinned_cores = &pinned_cores;
+            let throttle = &throttle;
+            let iterations_done = &iterations_done;
+            let max_player_1_score = &max_player_1_score;
+            let max_player_2_score = &max_player_2_score;
+            let min_player_1_score = &min_player_1_score;
+            let min_player_2_score = &min_player_2_score;
+            let sum_player_1_score = &sum_player_1_score;
+            let sum_player_2_score = &sum_player_2_score;
+            let sum_time_player_1 = &sum_time_player_1;
+            let sum_time_player_2 = &sum_time_player_2;
+            let turns_player_1 = &n_time_player_1;
+            let turns_player_2 = &n_time_player_2;
+            let wins_player_1 = &wins_player_1;
+            let wins_player_2 = &wins_player_2;
+            let score_ties = &score_ties;
+            let plies = &plies;
+            let legal_moves = &legal_moves;
+            let first_moves = &first_moves;
+            let pending_games = &pending_games;
+            let panic_cause = &panic_cause;
+            let create_player_1 = &create_player_1;
+            let create_player_2 = &create_player_2;
+            handles.push(s.spawn(move || {
+                // the player factories are only called, the state shared between the workers are atomics and mutexes
+                let panic_result = panic::catch_unwind(panic::AssertUnwindSafe(move || {
+                    if let Some(core_id) = core_id {
+                        assert!(
+                            core_affinity::set_for_current(core_id),
+                            "Could not pin the worker thread to core {}",
+                            core_id.id
+                        );
+                        pinned_cores.lock().unwrap().push(core_id.id);
+                    }
+
+                    let mut player_1 = create_player_1().unwrap();
+                    let mut player_2 = create_player_2().unwrap();
+
+                    'outer: while iterations_done.load(Ordering::Acquire) < iterations {
+                        if let Some(throttle) = throttle {
+                            throttle.wait();
+                        }
+
+                        // the players are reused for every game of this worker
+                        player_1.reset();
+                        player_2.reset();
+
+                        let mut state = Patchwork::get_initial_state(None);
+                        let mut record = record_games.then(|| GameRecord::new(state.clone()));
+                        // moves are buffered per game so that the observers see the
+                        // moves of the games running in parallel in one piece
+                        let mut moves = vec![];
+                        let mut game_legal_moves = 0;
+                        let mut game_first_moves = [None, None];
+                        loop {
+                            if iterations_done.load(Ordering::Acquire) >= iterations {
+                                break 'outer;
+                            }
+
+                            game_legal_moves += state.legal_move_count() as u64;
+                            let start_time = std::time::Instant::now();
+                            let (action, diagnostics) = if state.is_player_1() {
+                                let action = get_observed_action(player_1.as_mut(), &state, observed);
+                                let end =
+                                    u64::try_from(std::time::Instant::now().duration_since(start_time).as_nanos())
+                                        .unwrap();
+
+                                sum_time_player_1.fetch_add(end, Ordering::Relaxed);
+                                turns_player_1.fetch_add(1, Ordering::Relaxed);
+                                action
+                            } else {
+                                let action = get_observed_action(player_2.as_mut(), &state, observed);
+                                let end =
+                                    u64::try_from(std::time::Instant::now().duration_since(start_time).as_nanos())
+                                        .unwrap();
+                                sum_time_player_2.fetch_add(end, Ordering::Relaxed);
+                                turns_player_2.fetch_add(1, Ordering::Relaxed);
+                                action
+                            };
+
+                            game_first_moves[usize::from(!state.is_player_1())].get_or_insert(action);
+
+                            let mut next_state = state.clone();
+                            next_state.do_action(action, false).unwrap();
+                            moves.push((std::mem::replace(&mut state, next_state), action, diagnostics));
+                            if let Some(record) = &mut record {
+                                record.push(action);
+                            }
+
+                            if state.is_terminated() {
+                                let termination = state.get_termination_result();
+
+                                {
+                                    let mut pending_games = pending_games.lock().unwrap();
+                                    // games finishing after the target was reached are discarded
+                                    if iterations_done.load(Ordering::Acquire) >= iterations {
+                                        break 'outer;
+                                    }
+
+                                    match termination.termination {
+                                        TerminationType::Player1Won => {
+                                            wins_player_1.fetch_add(1, Ordering::Relaxed);
+                                        }
+                                        TerminationType::Player2Won => {
+                                            wins_player_2.fetch_add(1, Ordering::Relaxed);
+                                        }
+                                    }
+
+                                    if termination.is_tie_on_score() {
+                                        score_ties.fetch_add(1, Ordering::Relaxed);
+                                    }
+
+                                    pending_games.push(RecordedGame {
+                                        player_1_name: player_1.name().to_string(),
+                                        player_2_name: player_2.name().to_string(),
+                                        result: termination.termination,
+                                        record: record.take(),
+                                    });
+
+                                    max_player_1_score.fetch_max(termination.player_1_score, Ordering::Relaxed);
+                                    max_player_2_score.fetch_max(termination.player_2_score, Ordering::Relaxed);
+                                    min_player_1_score.fetch_min(termination.player_1_score, Ordering::Relaxed);
+                                    min_player_2_score.fetch_min(termination.player_2_score, Ordering::Relaxed);
+                                    sum_player_1_score.fetch_add(termination.player_1_score, Ordering::Relaxed);
+                                    sum_player_2_score.fetch_add(termination.player_2_score, Ordering::Relaxed);
+                                    plies.fetch_add(moves.len() as u64, Ordering::Relaxed);
+                                    legal_moves.fetch_add(game_legal_moves, Ordering::Relaxed);
+                                    {
+                                        let mut first_moves = first_moves.lock().unwrap();
+                                        for (frequencies, action) in first_moves.iter_mut().zip(game_first_moves) {
+                                            if let Some(action) = action {
+                                                let action = action.save_to_notation().unwrap();
+                                                *frequencies.entry(action).or_insert(0) += 1;
+                                            }
+                                        }
+                                    }
+                                    iterations_done.fetch_add(1, Ordering::Release);
+                                    drop(pending_games);
+                                }
+
+                                {
+                                    let mut observers = observers.lock().unwrap();
+                                    for (state, action, diagnostics) in &moves {
+                                        notify_move(&mut observers, state, *action, diagnostics).unwrap();
+                                    }
+                                    notify_game_end(&mut observers, &state).unwrap();
+                                }
+                                break;
+                            }
+                        }
+                    }
+                }));
+
+                if let Err(cause) = panic_result {
+                    let cause = cause.downcast_ref::<String>().map_or_else(
+                        || format!("{cause:?}"),
+                        std::string::ToString::to_string,
+                    );
+                    *panic_cause.lock().unwrap() =
+                        Some(format!("Panic in thread {:?}: {cause}", std::thread::current().id()));
+                    iterations_done.store(u32::MAX, Ordering::SeqCst);
+                }
+            }));
+        }
+        loop {
+            let iterations_done = iterations_done.load(Ordering::Relaxed) as usize;
+            if iterations_done >= iterations {
+                break;
+            }
+            report()?;
+            std::thread::sleep(update);
+        }
+
+        for handle in handles {
+            let _ = handle.join();
+        }
+
+        anyhow::Result::<()>::Ok(())
+    })?;
+
+    atomic::fence(Ordering::SeqCst);
+    let result = report()?;
+
+    if let Some(cause) = panic_cause.into_inner().unwrap() {
+        return Err(Error::msg(cause));
+    }
+
+    Ok(CompareResult {
+        pinned_cores: pinned_cores.into_inner().unwrap(),
+        ..result
+    })
+}
+
+/// Gets the action of the player. The diagnostics of the search are only requested if the moves are observed as some
+/// players have to do additional work to report them which would be included in the measured time.
+fn get_observed_action(player: &mut dyn Player, state: &Patchwork, observed: bool) -> (ActionId, SearchDiagnostics) {
+    if observed {
+        player.get_action_with_diagnostics(state).unwrap()
+    } else {
+        (player.get_action(state).unwrap(), SearchDiagnostics::default())
+    }
+}
+
+/// Notifies the observers about the move.
+fn notify_move(
+    observers: &mut [Box<dyn GameObserver + Send>],
+    state: &Patchwork,
+    action: ActionId,
+    diagnostics: &SearchDiagnostics,
+) -> anyhow::Result<()> {
+    for observer in observers {
+        observer.on_move(state, action, diagnostics.evaluation(), &diagnostics.principal_variation)?;
+    }
+    Ok(())
+}
+
+/// Notifies the observers about the end of the game.
+fn notify_game_end(observers: &mut [Box<dyn GameObserver + Send>], state: &Patchwork) -> anyhow::Result<()> {
+    for observer in observers {
+        observer.on_game_end(state)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use greedy_player::GreedyPlayer;
+    use random_player::RandomPlayer;
+
+    use super::*;
+
+    #[allow(clippy::unnecessary_wraps)] // the signature of a player factory of `run_compare`
+    fn random_player() -> PlayerResult<Box<dyn Player>> {
+        Ok(Box::new(RandomPlayer::new("Random Player", None)))
+    }
+
+    #[test]
+    fn test_game_shape_statistics_averages() {
+        let mut result = CompareResult::new();
+        assert!(result.average_plies().abs() < f64::EPSILON);
+        assert!(result.average_branching_factor().abs() < f64::EPSILON);
+
+        result.wins_player_1 = 2;
+        result.wins_player_2 = 1;
+        result.plies = 40 + 44 + 48;
+        result.legal_moves = 400 + 1100 + 900;
+
+        assert!((result.average_plies() - 44.0).abs() < f64::EPSILON);
+        assert!((result.average_branching_factor() - 2400.0 / 132.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_move_entropy_of_fixed_distribution() {
+        let frequencies = |counts: &[u64]| {
+            counts
+                .iter()
+                .enumerate()
+                .map(|(index, count)| (format!("W{index}"), *count))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        assert!(move_entropy(&BTreeMap::new()).abs() < f64::EPSILON);
+        // always playing the same opening has no diversity
+        assert!(move_entropy(&frequencies(&[10])).abs() < f64::EPSILON);
+        assert!((move_entropy(&frequencies(&[5, 5, 5, 5])) - 2.0).abs() < f64::EPSILON);
+        // p = (1/2, 1/4, 1/4) → 1/2 · 1 + 2 · 1/4 · 2 = 1.5
+        assert!((move_entropy(&frequencies(&[4, 2, 2])) - 1.5).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_run_compare_returns_totals_of_all_games() {
+        const GAMES: usize = 6;
+
+        let observers = Mutex::new(vec![]);
+        let mut updated_games = vec![];
+
+        let result = run_compare(
+            GAMES,
+            random_player,
+            random_player,
+            2,
+            false,
+            None,
+            false,
+            &observers,
+            &CompareResult::new(),
+            std::time::Duration::from_millis(1),
+            |_, mut new_games| {
+                updated_games.append(&mut new_games);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(GAMES as u32, result.games());
+        assert_eq!(GAMES, updated_games.len());
+        // the records are only kept if they were requested
+        assert!(updated_games.iter().all(|game| game.record.is_none()));
+        assert_eq!(
+            result.wins_player_1 as usize,
+            updated_games
+                .iter()
+                .filter(|game| game.result == TerminationType::Player1Won)
+                .count()
+        );
+        assert!(result.score_ties <= result.games());
+        assert!(result.plies >= GAMES as u64);
+        // the turns of games that were aborted after the target was reached are timed as well
+        assert!(result.plies <= result.turns_player_1 + result.turns_player_2);
+        assert!(result.max_player_1_score >= result.min_player_1_score);
+        assert!(result.max_player_2_score >= result.min_player_2_score);
+        assert_eq!(GAMES as u64, result.first_moves_player_1.values().sum::<u64>());
+        assert_eq!(GAMES as u64, result.first_moves_player_2.values().sum::<u64>());
+    }
+
+    #[test]
+    fn test_recorded_games_replay_to_their_results() {
+        const GAMES: usize = 3;
+
+        let observers = Mutex::new(vec![]);
+        let mut updated_games = vec![];
+
+        run_compare(
+            GAMES,
+            random_player,
+            random_player,
+            2,
+            false,
+            None,
+            true,
+            &observers,
+            &CompareResult::new(),
+            std::time::Duration::from_millis(1),
+            |_, mut new_games| {
+                updated_games.append(&mut new_games);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!(GAMES, updated_games.len());
+        for game in updated_games {
+            let final_state = game.record.unwrap().replay().unwrap();
+            assert!(final_state.is_terminated());
+            assert_eq!(game.result, final_state.get_termination_result().termination);
+        }
+    }
+
+    #[test]
+    fn test_failing_player_creation_is_an_error() {
+        let result = run_compare(
+            2,
+            random_player,
+            || Err(Error::msg("No such player")),
+            1,
+            false,
+            None,
+            false,
+            &Mutex::new(vec![]),
+            &CompareResult::new(),
+            std::time::Duration::from_millis(1),
+            |_, _| Ok(()),
+        );
+
+        assert!(result.is_err());
+    }
+
+    #[derive(Default)]
+    struct CountingObserver {
+        evaluations: std::sync::Arc<Mutex<Vec<Option<i32>>>>,
+        games: std::sync::Arc<Mutex<usize>>,
+    }
+
+    impl GameObserver for CountingObserver {
+        fn on_move(
+            &mut self,
+            _: &Patchwork,
+            _: ActionId,
+            evaluation: Option<i32>,
+            _: &[ActionId],
+        ) -> anyhow::Result<()> {
+            self.evaluations.lock().unwrap().push(evaluation);
+            Ok(())
+        }
+
+        fn on_game_end(&mut self, _: &Patchwork) -> anyhow::Result<()> {
+            *self.games.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_run_compare_notifies_observers_once_per_move() {
+        const GAMES: usize = 2;
+
+        let observer = CountingObserver::default();
+        let evaluations = std::sync::Arc::clone(&observer.evaluations);
+        let games = std::sync::Arc::clone(&observer.games);
+        let observer: Box<dyn GameObserver + Send> = Box::new(observer);
+        let observers = Mutex::new(vec![observer]);
+
+        let result = run_compare(
+            GAMES,
+            || Ok(Box::new(GreedyPlayer::<evaluator::StaticEvaluator>::new("Greedy Player", None))),
+            random_player,
+            1,
+            false,
+            None,
+            false,
+            &observers,
+            &CompareResult::new(),
+            std::time::Duration::from_millis(1),
+            |_, _| Ok(()),
+        )
+        .unwrap();
+
+        let evaluations = evaluations.lock().unwrap().clone();
+        assert_eq!(GAMES, *games.lock().unwrap());
+        assert_eq!(result.plies, evaluations.len() as u64);
+        // the evaluations reported by the greedy player are passed on
+        assert!(evaluations.iter().any(Option::is_some));
+    }
+
+    #[test]
+    fn test_pinned_workers_run_on_distinct_cores() {
+        const WORKERS: usize = 2;
+
+        if core_affinity::get_core_ids().map_or(0, |core_ids| core_ids.len()) < WORKERS {
+            println!("Skipping test as there are less than {WORKERS} CPU cores");
+            return;
+        }
+
+        let observers = Mutex::new(vec![]);
+        let run = |pin_cores| {
+            run_compare(
+                WORKERS,
+                random_player,
+                random_player,
+                WORKERS,
+                pin_cores,
+                None,
+                false,
+                &observers,
+                &CompareResult::new(),
+                std::time::Duration::from_millis(1),
+                |_, _| Ok(()),
+            )
+            .unwrap()
+        };
+
+        assert!(run(false).pinned_cores.is_empty());
+
+        let mut pinned_cores = run(true).pinned_cores;
+        assert_eq!(WORKERS, pinned_cores.len());
+        pinned_cores.sort_unstable();
+        pinned_cores.dedup();
+        assert_eq!(WORKERS, pinned_cores.len(), "Workers were pinned to the same core");
+    }
+
+    #[test]
+    fn test_throttle_limits_games_per_second() {
+        const GAMES: usize = 4;
+        const GAMES_PER_SECOND: f64 = 10.0;
+
+        let observers = Mutex::new(vec![]);
+
+        let start = std::time::Instant::now();
+        let result = run_compare(
+            GAMES,
+            random_player,
+            random_player,
+            2,
+            false,
+            Some(GAMES_PER_SECOND),
+            false,
+            &observers,
+            &CompareResult::new(),
+            std::time::Duration::from_millis(1),
+            |_, _| Ok(()),
+        )
+        .unwrap();
+        let elapsed = start.elapsed();
+
+        assert_eq!(GAMES as u32, result.games());
+        assert!(
+            elapsed.as_secs_f64() >= GAMES as f64 / GAMES_PER_SECOND,
+            "{GAMES} games took only {elapsed:?}"
+        );
+        assert!(GameThrottle::new(0.0).is_err());
+    }
+}
@@ -1,7 +1,12 @@
 pub use action_orderer::*;
 pub use patchwork_core::{
-    status_flags, time_board_flags, Action, ActionId, GameOptions, NaturalActionId, Notation, Patch, PatchManager,
-    PatchTransformation, Patchwork, PatchworkError, PlayerState, QuiltBoard, Termination, TerminationType, TimeBoard,
+    compute_value_target, compute_value_targets, get_value_target, status_flags, time_board_flags, Action, ActionId,
+    ActionKind, ActionsSummary, CsvGameObserver, GameObserver, GameOptions, GameOptionsBuilder, GameRecord, GameReplay,
+    GameRng, NaturalActionId, Notation, NotationMask, NumberedActions, Patch, PatchCircle, PatchCircleSlice,
+    PatchManager, PatchOptionSummary, PatchOrder, PatchTransformation, Patchwork, PatchworkError, PlacementCache,
+    PlayerState, PositionCorpus, QuiltBoard, QuiltBoardDiff, RngDraw, RngLog, ScoreBreakdown, ScoringRules,
+    ScoringRulesRef, StandardScoringRules, StructuralKey, Termination, TerminationType, TimeBoard, TurnType,
+    WalkingSummary, VALUE_TARGET_SCALE,
 };
 
 pub mod evaluator {
@@ -10,18 +15,20 @@ pub mod evaluator {
 }
 
 pub mod player {
+    #[cfg(feature = "neural")]
     pub use alphazero_player::*;
+    pub use endgame_solver::*;
     pub use greedy_player::*;
     pub use human_player::*;
     pub use mcts_player::*;
     pub use minimax_player::*;
-    pub use patchwork_core::{Logging, Player};
+    pub use patchwork_core::{IterationTiming, Logging, Player, SearchDiagnostics, TranspositionTableDiagnostics};
     pub use principal_variation_search_player::*;
     pub use random_player::*;
 }
 
 pub mod tree_policy {
-    pub use patchwork_core::{TreePolicy, TreePolicyNode};
+    pub use patchwork_core::{ExplorationTreePolicy, TreePolicy, TreePolicyNode};
     pub use tree_policy::*;
 }
 
@@ -32,7 +39,11 @@ pub mod prelude {
     pub use patchwork_core::{ActionId, Patch, Patchwork, Termination, TerminationType};
 }
 
+pub mod compare;
 mod game_manager;
+mod what_if;
+
+pub use what_if::{explore_replies, WhatIfBranch, WhatIfReply, WhatIfTree};
 
 #[cfg(test)]
 mod tests {
@@ -41,6 +52,7 @@ mod tests {
     use ::evaluator::StaticEvaluator;
 
     use super::player::*;
+    use super::tree_policy::UCTPolicy;
     use super::*;
 
     #[test]
@@ -51,7 +63,7 @@ mod tests {
 
     #[test]
     fn greedy_player() {
-        let player: GreedyPlayer = GreedyPlayer::new("Greedy Player");
+        let player: GreedyPlayer = GreedyPlayer::new("Greedy Player", None);
         let player = Box::new(player);
         test_player(player);
     }
@@ -63,6 +75,9 @@ mod tests {
             Some(MinimaxOptions {
                 depth: 3,
                 amount_actions_per_piece: 3,
+                sampling_strategy: SamplingStrategy::TopScore,
+                iterative: false,
+                tie_breaking_seed: None,
             }),
         ));
         test_player(player);
@@ -77,6 +92,12 @@ mod tests {
                 logging: Logging::Disabled,
                 time_limit: std::time::Duration::from_secs(1),
                 features: PVSFeatures::default(),
+                max_depth: None,
+                instability_time_extension: None,
+                time_prediction: false,
+                placement_cache: false,
+                minimize_loss_margin: false,
+                adaptive_time: false,
             }),
         );
         test_player(player);
@@ -92,6 +113,12 @@ mod tests {
                 leaf_parallelization: NonZeroUsize::new(1).unwrap(),
                 root_parallelization: NonZeroUsize::new(1).unwrap(),
                 logging: Logging::Disabled,
+                skill: None,
+                exploration_constant: None,
+                exploration_decay: None,
+                opponent_model: None,
+                max_nodes: None,
+                seed: None,
             }),
         );
         let player = Box::new(player);
@@ -99,6 +126,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "neural")]
     #[ignore = "AlphaZero player is not yet implemented"]
     fn alphazero_player() {
         let player: AlphaZeroPlayer = AlphaZeroPlayer::new(
@@ -116,8 +144,123 @@ mod tests {
         test_player(player);
     }
 
+    #[test]
+    fn search_players_play_forced_move_without_searching() {
+        // player 1 has to place a special patch on the only free tile of the quilt board
+        let state = Patchwork::load_from_notation(
+            "1FFFFFFFFFFFFFFFFFFFEB10I0P27 000000000000000000000B5I0P20 5 Y 1/2/3",
+        )
+        .unwrap();
+        let valid_actions = state.get_valid_actions();
+        assert_eq!(1, valid_actions.len());
+
+        let time_limit = std::time::Duration::from_secs(10);
+        let players: Vec<Box<dyn Player>> = vec![
+            Box::new(MinimaxPlayer::<StaticEvaluator>::new("Minimax Player", None)),
+            Box::new(EndgameSolver::<StaticEvaluator>::new("Endgame Solver", None)),
+            DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::new(
+                "PVS Player",
+                Some(PVSOptions { time_limit, ..Default::default() }),
+            ),
+            Box::new(MCTSPlayer::<UCTPolicy, StaticEvaluator>::new(
+                "MCTS Player",
+                Some(MCTSOptions { end_condition: MCTSEndCondition::Time(time_limit), ..Default::default() }),
+            )),
+        ];
+
+        for mut player in players {
+            let start_time = std::time::Instant::now();
+            let action = player.get_action(&state).unwrap();
+
+            assert_eq!(valid_actions[0], action, "{} did not play the forced move", player.name());
+            assert!(
+                start_time.elapsed() < std::time::Duration::from_secs(1),
+                "{} searched the forced move",
+                player.name()
+            );
+        }
+    }
+
+    /// The file containing the moves and the final score of a game between
+    /// the greedy and the minimax player. Run the tests with `UPDATE_GOLDEN=1`
+    /// to regenerate it after a deliberate change of the move generation,
+    /// the action ordering or the evaluation.
+    const GOLDEN_GAME_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden/seed_42_game.txt");
+
+    /// Plays a full game from seed 42 between two players that break ties
+    /// with a fixed seed. Every line contains the ply, the player to move and
+    /// the action in the notation. The last line contains the final score.
+    fn play_golden_game() -> Vec<String> {
+        let mut player_1: GreedyPlayer = GreedyPlayer::new(
+            "Greedy Player",
+            Some(GreedyOptions {
+                tie_breaking_seed: Some(42),
+                ..GreedyOptions::default()
+            }),
+        );
+        let mut player_2 = MinimaxPlayer::<StaticEvaluator>::new(
+            "Minimax Player",
+            Some(MinimaxOptions {
+                depth: 2,
+                amount_actions_per_piece: 2,
+                tie_breaking_seed: Some(42),
+                ..MinimaxOptions::default()
+            }),
+        );
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut lines = vec![];
+        while !state.is_terminated() {
+            let (player, number): (&mut dyn Player, _) = if state.is_player_1() {
+                (&mut player_1, 1)
+            } else {
+                (&mut player_2, 2)
+            };
+            let action = player.get_action(&state).unwrap();
+            lines.push(format!(
+                "{:03} {number} {}",
+                lines.len() + 1,
+                action.save_to_notation().unwrap()
+            ));
+            state.do_action(action, false).unwrap();
+        }
+
+        let termination = state.get_termination_result();
+        lines.push(format!("score {} {}", termination.player_1_score, termination.player_2_score));
+        lines
+    }
+
+    #[test]
+    fn game_matches_golden_file() {
+        let lines = play_golden_game();
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            std::fs::create_dir_all(std::path::Path::new(GOLDEN_GAME_FILE).parent().unwrap()).unwrap();
+            std::fs::write(GOLDEN_GAME_FILE, lines.join("\n") + "\n").unwrap();
+            return;
+        }
+
+        let golden = std::fs::read_to_string(GOLDEN_GAME_FILE).unwrap();
+        let golden = golden.lines().collect::<Vec<_>>();
+
+        // every move after the first difference follows from it
+        if let Some((expected, actual)) = golden.iter().zip(&lines).find(|(expected, actual)| *expected != actual) {
+            panic!("The game diverged (run with UPDATE_GOLDEN=1 to accept):\nexpected: {expected}\nactual:   {actual}");
+        }
+        assert_eq!(
+            golden.len(),
+            lines.len(),
+            "The game has a different length (run with UPDATE_GOLDEN=1 to accept)"
+        );
+    }
+
+    #[test]
+    fn golden_game_is_reproducible() {
+        assert_eq!(play_golden_game(), play_golden_game());
+    }
+
     fn test_player(mut player: Box<dyn Player>) {
-        let mut state = Patchwork::get_initial_state(Some(GameOptions { seed: 42 }));
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
         loop {
             let action_result = player.get_action(&state);
 
@@ -0,0 +1,183 @@
+use patchwork_core::{ActionId, Evaluator, Patchwork, PatchworkError, Player, PlayerResult};
+
+/// The reply that is played after a move of a [`WhatIfBranch`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhatIfReply {
+    /// The action that is played as reply.
+    pub action: ActionId,
+    /// The evaluation of the position after the reply from the perspective of
+    /// the player to move in the explored position.
+    pub evaluation: i32,
+}
+
+/// A single move of the player to move in the explored position together with
+/// the best reply to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhatIfBranch {
+    /// The action of the player to move in the explored position.
+    pub action: ActionId,
+    /// The evaluation of the position after the action from the perspective of
+    /// the player to move in the explored position.
+    pub evaluation: i32,
+    /// The best reply to the action or `None` if the game is terminated after
+    /// the action.
+    pub reply: Option<WhatIfReply>,
+}
+
+/// A shallow game tree of the best moves in a position and the best reply to
+/// each of them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WhatIfTree {
+    /// The explored position.
+    pub state: Patchwork,
+    /// The explored moves. The candidate move comes first (if given), the
+    /// other moves are ordered from the best to the worst evaluation.
+    pub branches: Vec<WhatIfBranch>,
+}
+
+/// Explores what happens after the best moves of the player to move.
+///
+/// The valid actions of the position are ranked with the evaluator and for
+/// the candidate action as well as the best `amount` other actions the reply
+/// is searched with the given player. The reply is the action of the player to
+/// move after the action, which is the opponent in most cases but can also be
+/// the same player again as patchwork does not strictly alternate turns.
+///
+/// # Arguments
+///
+/// * `state` - The position to explore.
+/// * `candidate` - A move that should always be explored in addition to the
+///   best moves.
+/// * `amount` - The amount of best moves to explore besides the candidate.
+/// * `evaluator` - The evaluator used to rank and score the moves and replies.
+/// * `replier` - The player that searches the best reply to every move.
+///
+/// # Returns
+///
+/// The shallow game tree of the explored moves and their replies.
+///
+/// # Errors
+///
+/// If the candidate move is not valid in the position or if the player fails
+/// to find a reply.
+///
+/// # Complexity
+///
+/// `𝒪(𝑛 + 𝑚 · 𝑟)` where `𝑛` is the amount of valid actions, `𝑚` is the amount
+/// of explored moves and `𝑟` is the complexity of the reply search.
+pub fn explore_replies(
+    state: &Patchwork,
+    candidate: Option<ActionId>,
+    amount: usize,
+    evaluator: &impl Evaluator,
+    replier: &mut dyn Player,
+) -> PlayerResult<WhatIfTree> {
+    let valid_actions = state.get_valid_actions();
+    let perspective = if state.is_player_1() { 1 } else { -1 };
+
+    if let Some(candidate) = candidate {
+        if !valid_actions.contains(&candidate) {
+            return Err(PatchworkError::InvalidActionError {
+                reason: "[explore_replies] The candidate action is not valid in the given state",
+                action: candidate,
+                state: Box::new(state.clone()),
+            }
+            .into());
+        }
+    }
+
+    let mut ranked_actions = valid_actions
+        .into_iter()
+        .map(|action| {
+            let mut next_state = state.clone();
+            next_state.do_action(action, false)?;
+            let evaluation = perspective * evaluator.evaluate_node(&next_state);
+            Ok((action, next_state, evaluation))
+        })
+        .collect::<Result<Vec<_>, PatchworkError>>()?;
+    ranked_actions.sort_by_key(|(action, _, evaluation)| (Some(*action) != candidate, -evaluation));
+    ranked_actions.truncate(amount + usize::from(candidate.is_some()));
+
+    let mut branches = Vec::with_capacity(ranked_actions.len());
+    for (action, next_state, evaluation) in ranked_actions {
+        let reply = if next_state.is_terminated() {
+            None
+        } else {
+            let reply_action = replier.get_action(&next_state)?;
+            let mut reply_state = next_state;
+            reply_state.do_action(reply_action, false)?;
+
+            Some(WhatIfReply {
+                action: reply_action,
+                evaluation: perspective * evaluator.evaluate_node(&reply_state),
+            })
+        };
+
+        branches.push(WhatIfBranch {
+            action,
+            evaluation,
+            reply,
+        });
+    }
+
+    Ok(WhatIfTree {
+        state: state.clone(),
+        branches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use evaluator::StaticEvaluator;
+    use greedy_player::GreedyPlayer;
+    use patchwork_core::GameOptions;
+
+    use super::*;
+
+    fn get_position() -> Patchwork {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for seed in 0..6 {
+            state.do_action(state.get_seeded_random_action(seed), false).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn test_explored_replies_are_legal_and_scored() {
+        let state = get_position();
+        let evaluator = StaticEvaluator::new();
+        let mut replier: GreedyPlayer = GreedyPlayer::new("Greedy Player", None);
+
+        let candidate = *state.get_valid_actions().last().unwrap();
+        let tree = explore_replies(&state, Some(candidate), 3, &evaluator, &mut replier).unwrap();
+
+        assert_eq!(state, tree.state);
+        assert_eq!(4, tree.branches.len());
+        assert_eq!(candidate, tree.branches[0].action);
+        assert!(tree.branches[1..]
+            .windows(2)
+            .all(|pair| pair[0].evaluation >= pair[1].evaluation));
+
+        let perspective = if state.is_player_1() { 1 } else { -1 };
+        for branch in &tree.branches {
+            let mut next_state = state.clone();
+            next_state.do_action(branch.action, false).unwrap();
+            assert_eq!(perspective * evaluator.evaluate_node(&next_state), branch.evaluation);
+
+            let reply = branch.reply.as_ref().expect("The game should not be terminated");
+            assert!(next_state.get_valid_actions().contains(&reply.action));
+
+            next_state.do_action(reply.action, false).unwrap();
+            assert_eq!(perspective * evaluator.evaluate_node(&next_state), reply.evaluation);
+        }
+    }
+
+    #[test]
+    fn test_invalid_candidate_is_rejected() {
+        let state = get_position();
+        let mut replier: GreedyPlayer = GreedyPlayer::new("Greedy Player", None);
+
+        let result = explore_replies(&state, Some(ActionId::phantom()), 3, &StaticEvaluator::new(), &mut replier);
+        assert!(result.is_err());
+    }
+}
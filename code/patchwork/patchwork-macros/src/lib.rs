@@ -21,6 +21,7 @@ pub fn generate_patches(input: TokenStream) -> TokenStream {
         normalized_tiles,
         transformations,
     } = parse_macro_input!(input as Patches);
+    let tiling_lookup = generate_tiling_lookup(&patches, &transformations);
 
     quote! {
         PatchManager {
@@ -35,12 +36,45 @@ pub fn generate_patches(input: TokenStream) -> TokenStream {
             ],
             transformations: [
                 #(#transformations),*
+            ],
+            tiling_lookup: vec![
+                #(#tiling_lookup),*
             ]
         }
     }
     .into()
 }
 
+/// Generates a table of all transformations sorted by their tiling. If
+/// multiple transformations have the same tiling, only the one with the lowest
+/// patch id and transformation index is kept.
+fn generate_tiling_lookup(
+    patches: &[Patch],
+    transformations: &[PatchTransformations],
+) -> Vec<proc_macro2::TokenStream> {
+    let mut lookup = patches
+        .iter()
+        .zip(transformations)
+        .flat_map(|(patch, transformations)| {
+            transformations
+                .transformations
+                .iter()
+                .enumerate()
+                .map(|(index, transformation)| (transformation.tiling, patch.id, index as u16))
+        })
+        .collect::<Vec<_>>();
+    lookup.sort_unstable();
+    lookup.dedup_by_key(|(tiling, _, _)| *tiling);
+
+    lookup
+        .into_iter()
+        .map(|(tiling, patch_id, index)| {
+            let tiling_literal = format!("{tiling:#083b}u128").parse::<proc_macro2::Literal>().unwrap();
+            quote! { (#tiling_literal, #patch_id, #index) }
+        })
+        .collect()
+}
+
 #[allow(clippy::struct_field_names)]
 struct Patches {
     pub patches: Vec<Patch>,
@@ -0,0 +1,223 @@
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use anyhow::Error;
+use patchwork_lib::player::Logging;
+use rustyline::{history::FileHistory, Editor};
+use serde::Deserialize;
+
+use super::{interactive_get_player, with_handicap, with_name, PlayerType};
+
+/// The players read from a config file given with `--config`.
+///
+/// The file is either a JSON file (ending in `.json`) or a TOML file, e.g.
+///
+/// ```toml
+/// [player-1]
+/// name = "Fast PVS"
+/// type = "pvs"
+/// options = { time = 0.5, eval = "score", lmr = false }
+///
+/// [player-2]
+/// type = "mcts"
+/// handicap = 3
+/// options = { iter = 1000, policy = "puct" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerConfig {
+    /// The first player.
+    #[serde(rename = "player-1")]
+    pub player_1: Option<PlayerSpec>,
+    /// The second player.
+    #[serde(rename = "player-2")]
+    pub player_2: Option<PlayerSpec>,
+}
+
+/// The definition of a single player in a [`PlayerConfig`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PlayerSpec {
+    /// The name the player is shown under instead of its default name.
+    pub name: Option<String>,
+    /// The type of the player as in the player names, e.g. `pvs` or `mcts`.
+    #[serde(rename = "type")]
+    pub player_type: String,
+    /// The amount of moves the player plays greedily.
+    pub handicap: Option<usize>,
+    /// The options of the player with the same keys and values as in the
+    /// player names. Booleans are written as `yes` and `no`.
+    #[serde(default)]
+    pub options: BTreeMap<String, OptionValue>,
+}
+
+/// The value of an option of a [`PlayerSpec`].
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum OptionValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => write!(f, "{}", if *value { "yes" } else { "no" }),
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::String(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+impl PlayerConfig {
+    /// Reads the players from the given JSON or TOML file.
+    ///
+    /// # Errors
+    ///
+    /// If the file could not be read or is not a valid config.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|error| Error::msg(format!("Could not read the config {}: {error}", path.display())))?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let config = if is_json {
+            serde_json::from_str(&content).map_err(Error::from)
+        } else {
+            toml::from_str(&content).map_err(Error::from)
+        };
+        config.map_err(|error| Error::msg(format!("Invalid config {}: {error}", path.display())))
+    }
+}
+
+impl PlayerSpec {
+    /// Gets the player name that creates the specified player with
+    /// [`get_player`](super::get_player), e.g. `pvs(eval: score, time: 0.5)`.
+    pub fn get_player_name(&self) -> String {
+        if self.options.is_empty() {
+            return self.player_type.clone();
+        }
+
+        let options = self
+            .options
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}"))
+            .collect::<Vec<_>>();
+        format!("{}({})", self.player_type, options.join(", "))
+    }
+
+    /// Applies the handicap and the name of the spec to the player created
+    /// from [`PlayerSpec::get_player_name`].
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The created player.
+    /// * `handicap_moves` - The amount of handicap moves overriding the handicap of the spec.
+    pub fn configure(&self, player: PlayerType, handicap_moves: Option<usize>) -> PlayerType {
+        let player = with_handicap(player, handicap_moves.or(self.handicap));
+        with_name(player, self.name.as_deref())
+    }
+}
+
+/// Gets the player at the given position like [`interactive_get_player`].
+/// If no player name is given the player is created from the spec of the
+/// config instead.
+pub fn interactive_get_configured_player(
+    rl: &mut Editor<(), FileHistory>,
+    player_name: Option<String>,
+    spec: Option<&PlayerSpec>,
+    handicap_moves: Option<usize>,
+    player_position: usize,
+    logging: Logging,
+) -> anyhow::Result<PlayerType> {
+    let Some(spec) = spec.filter(|_| player_name.is_none()) else {
+        let player = interactive_get_player(rl, player_name, player_position, logging)?;
+        return Ok(with_handicap(player, handicap_moves));
+    };
+
+    let player = interactive_get_player(rl, Some(spec.get_player_name()), player_position, logging)?;
+    Ok(spec.configure(player, handicap_moves))
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_lib::player::Player;
+
+    use crate::common::get_player;
+
+    use super::*;
+
+    #[test]
+    fn test_configured_players_match_the_spec() {
+        let toml = r#"
+            [player-1]
+            name = "Fast PVS"
+            type = "pvs"
+            options = { time = 0.5, eval = "score", lmr = false }
+
+            [player-2]
+            type = "mcts"
+            handicap = 3
+            options = { iter = 100, policy = "puct" }
+        "#;
+        let json = r#"{
+            "player-1": { "name": "Fast PVS", "type": "pvs", "options": { "time": 0.5, "eval": "score", "lmr": false } },
+            "player-2": { "type": "mcts", "handicap": 3, "options": { "iter": 100, "policy": "puct" } }
+        }"#;
+
+        let directory = std::env::temp_dir().join(format!("patchwork-player-config-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("players.toml"), toml).unwrap();
+        std::fs::write(directory.join("players.json"), json).unwrap();
+        let toml_config = PlayerConfig::load(directory.join("players.toml"));
+        let json_config = PlayerConfig::load(directory.join("players.json"));
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        let config = toml_config.unwrap();
+        assert_eq!(config, json_config.unwrap());
+
+        let player_1 = config.player_1.unwrap();
+        let player_2 = config.player_2.unwrap();
+        assert_eq!("pvs(eval: score, lmr: no, time: 0.5)", player_1.get_player_name());
+        assert_eq!("mcts(iter: 100, policy: puct)", player_2.get_player_name());
+
+        let player_1 = player_1.configure(
+            get_player(&player_1.get_player_name(), Logging::Disabled).unwrap(),
+            None,
+        );
+        let player_2 = player_2.configure(
+            get_player(&player_2.get_player_name(), Logging::Disabled).unwrap(),
+            None,
+        );
+
+        assert_eq!("Fast PVS", player_1.name());
+        assert_eq!(
+            "named(name: Fast PVS) pvs(eval: score, lmr: no, time: 0.5)",
+            player_1.get_construct_name()
+        );
+        assert_eq!(
+            "handicap(moves: 3) mcts(iter: 100, policy: puct)",
+            player_2.get_construct_name()
+        );
+        let expected = with_handicap(
+            get_player("mcts(iter: 100, policy: puct)", Logging::Disabled).unwrap(),
+            Some(3),
+        );
+        assert_eq!(expected.name(), player_2.name());
+    }
+
+    #[test]
+    fn test_unknown_fields_are_rejected() {
+        let directory = std::env::temp_dir().join(format!("patchwork-invalid-player-config-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        std::fs::write(directory.join("players.toml"), "[player-1]\ntype = \"pvs\"\ntime = 5\n").unwrap();
+        let config = PlayerConfig::load(directory.join("players.toml"));
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert!(config.is_err());
+    }
+}
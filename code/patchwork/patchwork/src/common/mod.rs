@@ -1,8 +1,12 @@
+mod config;
 mod logging;
+mod observer;
 mod player;
 
 pub const CTRL_C_MESSAGE: &str = "Received CTRL-C command.";
 pub const CTRL_D_MESSAGE: &str = "Received CTRL-D command.";
 
+pub use config::*;
 pub use logging::*;
+pub use observer::*;
 pub use player::*;
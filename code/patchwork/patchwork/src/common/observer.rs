@@ -0,0 +1,154 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+};
+
+use patchwork_lib::{
+    evaluator::{Evaluator, RolloutEvaluator, ScoreEvaluator, StaticEvaluator, WinLossEvaluator},
+    player::SearchDiagnostics,
+    ActionId, CsvGameObserver, GameObserver, Patchwork,
+};
+#[cfg(feature = "neural")]
+use patchwork_lib::evaluator::NeuralNetworkEvaluator;
+
+pub type ObserverType = Box<dyn GameObserver + Send>;
+
+pub fn get_csv_observer(path: &str) -> anyhow::Result<ObserverType> {
+    let writer = BufWriter::new(File::create(path)?);
+    Ok(Box::new(CsvGameObserver::new(writer)?))
+}
+
+/// Records the evaluation of every position of a game from the perspective of
+/// player 1 and writes the evaluation curve (ply vs. evaluation) as CSV once
+/// the game ended.
+pub struct EvaluationCurveObserver<W: Write> {
+    evaluator: Box<dyn Evaluator + Send>,
+    writer: W,
+    evaluations: Vec<i32>,
+}
+
+impl<W: Write> EvaluationCurveObserver<W> {
+    pub const HEADER: &'static str = "ply,evaluation";
+
+    pub fn new(evaluator: Box<dyn Evaluator + Send>, writer: W) -> Self {
+        Self {
+            evaluator,
+            writer,
+            evaluations: vec![],
+        }
+    }
+
+    #[cfg(test)]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> GameObserver for EvaluationCurveObserver<W> {
+    fn on_move(&mut self, state: &Patchwork, _: ActionId, _: Option<i32>, _: &[ActionId]) -> anyhow::Result<()> {
+        self.evaluations.push(self.evaluator.evaluate_node(state));
+        Ok(())
+    }
+
+    fn on_undo(&mut self, _: &Patchwork, undone_moves: usize) -> anyhow::Result<()> {
+        self.evaluations.truncate(self.evaluations.len().saturating_sub(undone_moves));
+        Ok(())
+    }
+
+    fn on_game_end(&mut self, state: &Patchwork) -> anyhow::Result<()> {
+        self.evaluations.push(self.evaluator.evaluate_node(state));
+
+        writeln!(self.writer, "{}", Self::HEADER)?;
+        for (ply, evaluation) in self.evaluations.drain(..).enumerate() {
+            writeln!(self.writer, "{ply},{evaluation}")?;
+        }
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+pub fn get_evaluator(evaluator: &str) -> anyhow::Result<Box<dyn Evaluator + Send>> {
+    Ok(match evaluator {
+        "static" => Box::<StaticEvaluator>::default(),
+        "win" => Box::<WinLossEvaluator>::default(),
+        "score" => Box::<ScoreEvaluator>::default(),
+        #[cfg(feature = "neural")]
+        "nn" => Box::<NeuralNetworkEvaluator>::default(),
+        #[cfg(not(feature = "neural"))]
+        "nn" => anyhow::bail!("The evaluator \"nn\" needs neural networks but the `neural` feature is disabled"),
+        "rollout" => Box::<RolloutEvaluator>::default(),
+        _ => anyhow::bail!("Unknown evaluator \"{evaluator}\". Available evaluators: static, win, score, nn, rollout"),
+    })
+}
+
+pub fn get_evaluation_curve_observer(path: &str, evaluator: &str) -> anyhow::Result<ObserverType> {
+    let writer = BufWriter::new(File::create(path)?);
+    Ok(Box::new(EvaluationCurveObserver::new(
+        get_evaluator(evaluator)?,
+        writer,
+    )))
+}
+
+pub fn notify_move(
+    observers: &mut [ObserverType],
+    state: &Patchwork,
+    action: ActionId,
+    diagnostics: &SearchDiagnostics,
+) -> anyhow::Result<()> {
+    for observer in observers {
+        observer.on_move(state, action, diagnostics.evaluation(), &diagnostics.principal_variation)?;
+    }
+    Ok(())
+}
+
+pub fn notify_undo(observers: &mut [ObserverType], state: &Patchwork, undone_moves: usize) -> anyhow::Result<()> {
+    for observer in observers {
+        observer.on_undo(state, undone_moves)?;
+    }
+    Ok(())
+}
+
+pub fn notify_game_end(observers: &mut [ObserverType], state: &Patchwork) -> anyhow::Result<()> {
+    for observer in observers {
+        observer.on_game_end(state)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_lib::GameOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_evaluation_curve_has_one_row_per_ply() {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut observer = EvaluationCurveObserver::new(Box::<StaticEvaluator>::default(), vec![]);
+
+        let mut plies = 0;
+        while !state.is_terminated() {
+            let action = state.get_valid_actions()[0];
+            observer.on_move(&state, action, None, &[]).unwrap();
+            state.do_action(action, false).unwrap();
+            plies += 1;
+        }
+        observer.on_game_end(&state).unwrap();
+
+        let csv = String::from_utf8(observer.into_inner()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(Some(EvaluationCurveObserver::<Vec<u8>>::HEADER), lines.next());
+
+        let rows = lines.collect::<Vec<_>>();
+        // one row for every position including the terminal one
+        assert_eq!(plies + 1, rows.len());
+        for (ply, row) in rows.into_iter().enumerate() {
+            let (row_ply, evaluation) = row.split_once(',').unwrap();
+            assert_eq!(ply, row_ply.parse::<usize>().unwrap());
+            assert!(
+                evaluation.parse::<i32>().is_ok(),
+                "evaluation '{evaluation}' is not numeric"
+            );
+        }
+    }
+}
@@ -1,31 +1,42 @@
-use std::{io::Write, num::NonZeroUsize};
+use std::io::Write;
+#[cfg(feature = "neural")]
+use std::num::NonZeroUsize;
 
 use anyhow::Error;
 use patchwork_lib::{
-    evaluator::{Evaluator, NeuralNetworkEvaluator, ScoreEvaluator, StaticEvaluator, WinLossEvaluator},
+    evaluator::{Evaluator, RolloutEvaluator, ScoreEvaluator, StaticEvaluator, StaticWeights, WinLossEvaluator},
     player::{
-        AlphaZeroEndCondition, AlphaZeroOptions, AlphaZeroPlayer, DefaultPVSPlayer, FailingStrategy, GreedyPlayer,
-        HumanPlayer, LazySMPFeature, Logging, MCTSEndCondition, MCTSOptions, MCTSPlayer, MinimaxOptions, MinimaxPlayer,
-        PVSOptions, Player, RandomOptions, RandomPlayer, Size, TranspositionTableFeature,
+        DefaultPVSPlayer, EndgameOptions, EndgameSolver, ExplorationDecay, FailingStrategy, GreedyMetric, GreedyOptions,
+        GreedyPlayer, HandicapPlayer, HumanPlayer, LazySMPFeature, Logging, MCTSEndCondition, MCTSOptions, MCTSPlayer,
+        MinimaxOptions, MinimaxPlayer, OpponentModel, PVSOptions, Player, RandomOptions, RandomPlayer, RandomWeighting,
+        RandomizedOpeningPlayer, SamplingStrategy, SearchDiagnostics, Size, TranspositionTableFeature,
+    },
+    tree_policy::{
+        ExplorationTreePolicy, PUCTPolicy, PartiallyScoredUCTPolicy, ScoredUCTPolicy, UCTPolicy, ValueUCTPolicy,
     },
-    tree_policy::{PUCTPolicy, PartiallyScoredUCTPolicy, ScoredUCTPolicy, TreePolicy, UCTPolicy},
     ActionId, ActionOrderer, EvaluationActionOrderer, Patchwork, TableActionOrderer,
 };
+#[cfg(feature = "neural")]
+use patchwork_lib::{
+    evaluator::NeuralNetworkEvaluator,
+    player::{AlphaZeroEndCondition, AlphaZeroOptions, AlphaZeroPlayer},
+    tree_policy::TreePolicy,
+};
 use regex::Regex;
 use rustyline::{error::ReadlineError, history::FileHistory, Editor};
+use upi::UpiEnginePlayer;
 
 use super::{CTRL_C_MESSAGE, CTRL_D_MESSAGE};
 
 pub enum PlayerType {
     BuildIn(Box<dyn Player>, String),
-    #[allow(dead_code)]
-    Upi(String), // TODO: implement extern UPI
+    Upi(Box<UpiEnginePlayer>, String),
 }
 
 impl PlayerType {
     pub fn get_construct_name(&self) -> &str {
         match self {
-            Self::Upi(name) | Self::BuildIn(_, name) => name,
+            Self::Upi(_, name) | Self::BuildIn(_, name) => name,
         }
     }
 }
@@ -34,7 +45,7 @@ impl Player for PlayerType {
     fn name(&self) -> &str {
         match self {
             Self::BuildIn(player, _) => player.name(),
-            Self::Upi(_) => unimplemented!("[PlayerType::name] UPI is not yet implemented."),
+            Self::Upi(player, _) => player.name(),
         }
     }
 
@@ -49,11 +60,69 @@ impl Player for PlayerType {
 
         match self {
             Self::BuildIn(player, _) => player.get_action(game),
-            Self::Upi(_) => unimplemented!("[PlayerType::get_action] UPI is not yet implemented."),
+            Self::Upi(player, _) => player.get_action(game),
+        }
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> anyhow::Result<ActionId> {
+        match self {
+            Self::BuildIn(player, _) => player.get_restricted_action(game, actions),
+            Self::Upi(player, _) => player.get_restricted_action(game, actions),
+        }
+    }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> anyhow::Result<(ActionId, SearchDiagnostics)> {
+        // Same shortcut as in `get_action`, no search is done in this case.
+        let actions = game.get_valid_actions();
+        if actions.len() == 1 {
+            return Ok((actions[0], SearchDiagnostics::default()));
+        }
+
+        match self {
+            Self::BuildIn(player, _) => player.get_action_with_diagnostics(game),
+            Self::Upi(player, _) => player.get_action_with_diagnostics(game),
+        }
+    }
+
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> anyhow::Result<(ActionId, SearchDiagnostics)> {
+        match self {
+            Self::BuildIn(player, _) => player.get_restricted_action_with_diagnostics(game, actions),
+            Self::Upi(player, _) => player.get_restricted_action_with_diagnostics(game, actions),
+        }
+    }
+
+    fn set_logging(&mut self, logging: Logging) {
+        match self {
+            Self::BuildIn(player, _) => player.set_logging(logging),
+            Self::Upi(player, _) => player.set_logging(logging),
+        }
+    }
+
+    fn reset(&mut self) {
+        match self {
+            Self::BuildIn(player, _) => player.reset(),
+            Self::Upi(player, _) => player.reset(),
         }
     }
 }
 
+/// The maximum time an extern player may take to answer a command.
+const EXTERN_PLAYER_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// The error if no player could be created from the given name.
+#[derive(Debug)]
+pub struct GetPlayerError {
+    /// The logging that was passed to [`get_player`] so that it can be used
+    /// for another player.
+    pub logging: Logging,
+    /// Why no player could be created.
+    pub reason: String,
+}
+
 pub fn interactive_get_player(
     rl: &mut Editor<(), FileHistory>,
     player_name: Option<String>,
@@ -61,13 +130,16 @@ pub fn interactive_get_player(
     logging: Logging,
 ) -> anyhow::Result<PlayerType> {
     if let Some(player_name) = player_name {
-        let Ok(player) = get_player(player_name.as_str(), logging) else {
-            println!("Could not find player {player_name}. Available players: ");
-            for p in get_available_players() {
-                println!("  {p}");
+        let player = match get_player(player_name.as_str(), logging) {
+            Ok(player) => player,
+            Err(error) => {
+                println!("{}. Available players: ", error.reason);
+                for p in get_available_players() {
+                    println!("  {p}");
+                }
+                std::io::stdout().flush()?;
+                return Err(Error::msg(format!("Could not find player {player_position}")));
             }
-            std::io::stdout().flush()?;
-            return Err(Error::msg(format!("Could not find player {player_position}")));
         };
         Ok(player)
     } else {
@@ -85,9 +157,9 @@ fn ask_for_player(
         match rl.readline(format!("Player {player_position}: ").as_str()) {
             Ok(player) => match get_player(player.trim(), logging) {
                 Ok(player) => return Ok(player),
-                Err(d) => {
-                    logging = d;
-                    println!("Could not find player {player}. Available players: ");
+                Err(error) => {
+                    logging = error.logging;
+                    println!("{}. Available players: ", error.reason);
                     for player in get_available_players() {
                         println!("  {player}");
                     }
@@ -101,12 +173,76 @@ fn ask_for_player(
     }
 }
 
-pub fn get_player(name: &str, logging: Logging) -> Result<PlayerType, Logging> {
+pub fn get_player(name: &str, logging: Logging) -> Result<PlayerType, GetPlayerError> {
+    // the command of an extern player is case sensitive
+    if let Some(captures) = Regex::new(r"(?i)^extern\s+(?<command>.+)$").unwrap().captures(name.trim()) {
+        let mut command = captures["command"].split_whitespace();
+        let program = command.next().unwrap();
+        let args = command.collect::<Vec<_>>();
+        return match UpiEnginePlayer::new(program, &args, EXTERN_PLAYER_TIMEOUT) {
+            Ok(player) => Ok(PlayerType::Upi(Box::new(player), name.trim().to_string())),
+            Err(error) => Err(GetPlayerError {
+                logging,
+                reason: format!("Could not start the extern player {program}: {error}"),
+            }),
+        };
+    }
+
+    let original_name = name;
     let name = name.to_ascii_lowercase();
     let name = name.as_str();
 
-    if name.starts_with("extern") {
-        unimplemented!("[get_player_from_str] Extern upi players are not yet implemented.");
+    if let Some(reason) = get_unsupported_reason(name) {
+        return Err(GetPlayerError { logging, reason });
+    }
+
+    if let Some(captures) = Regex::new(r"(?i)^handicap\(moves:\s*(?<moves>\d+)\)\s*(?<player>.+)$")
+        .unwrap()
+        .captures(original_name.trim())
+    {
+        let Ok(handicap_moves) = captures["moves"].parse::<usize>() else {
+            return Err(GetPlayerError {
+                logging,
+                reason: format!("Invalid amount of handicap moves in {name}"),
+            });
+        };
+        return get_player(&captures["player"], logging).map(|player| with_handicap(player, Some(handicap_moves)));
+    }
+
+    if let Some(captures) = Regex::new(
+        r"(?i)^opening\(moves:\s*(?<moves>\d+),\s*top:\s*(?<top>\d+)(,\s*seed:\s*(?<seed>\d+))?\)\s*(?<player>.+)$",
+    )
+    .unwrap()
+    .captures(original_name.trim())
+    {
+        let (Ok(moves), Ok(top), Ok(seed)) = (
+            captures["moves"].parse::<usize>(),
+            captures["top"].parse::<usize>(),
+            captures.name("seed").map_or(Ok(0), |seed| seed.as_str().parse::<u64>()),
+        ) else {
+            return Err(GetPlayerError {
+                logging,
+                reason: format!("Invalid randomized opening in {name}"),
+            });
+        };
+        return get_player(&captures["player"], logging).map(|player| {
+            let (inner, construct_name): (Box<dyn Player>, String) = match player {
+                PlayerType::BuildIn(player, construct_name) => (player, construct_name),
+                PlayerType::Upi(player, construct_name) => (player, construct_name),
+            };
+            PlayerType::BuildIn(
+                Box::new(RandomizedOpeningPlayer::new(inner, moves, top, seed)),
+                format!("opening(moves: {moves}, top: {top}, seed: {seed}) {construct_name}"),
+            )
+        });
+    }
+
+    if let Some(captures) = Regex::new(r"(?i)^named\(name:\s*(?<name>[^)]+)\)\s*(?<player>.+)$")
+        .unwrap()
+        .captures(original_name.trim())
+    {
+        let name = captures["name"].trim();
+        return get_player(&captures["player"], logging).map(|player| with_name(player, Some(name)));
     }
 
     if let Some(player) = parse_human_player(name) {
@@ -125,6 +261,10 @@ pub fn get_player(name: &str, logging: Logging) -> Result<PlayerType, Logging> {
         return Ok(PlayerType::BuildIn(player, name.to_string()));
     }
 
+    if let Some(player) = parse_endgame_player(name) {
+        return Ok(PlayerType::BuildIn(player, name.to_string()));
+    }
+
     let (player_option, logging) = parse_pvs_player(name, logging);
     if let Some(player) = player_option {
         return Ok(PlayerType::BuildIn(player, name.to_string()));
@@ -135,12 +275,132 @@ pub fn get_player(name: &str, logging: Logging) -> Result<PlayerType, Logging> {
         return Ok(PlayerType::BuildIn(player, name.to_string()));
     }
 
+    #[cfg(feature = "neural")]
     let (player_option, logging) = parse_alphazero_player(name, logging.unwrap());
+    #[cfg(feature = "neural")]
     if let Some(player) = player_option {
         return Ok(PlayerType::BuildIn(player, name.to_string()));
     }
 
-    Err(logging.unwrap())
+    Err(GetPlayerError {
+        logging: logging.unwrap(),
+        reason: format!("Could not find player {name}"),
+    })
+}
+
+/// Lets the given player play its first moves greedily (see [`HandicapPlayer`]).
+///
+/// The construct name of the returned player is prefixed with
+/// `handicap(moves: <handicap_moves>)` so that [`get_player`] creates the same
+/// player from it again. Without handicap moves the player is returned as is.
+pub fn with_handicap(player: PlayerType, handicap_moves: Option<usize>) -> PlayerType {
+    let Some(handicap_moves) = handicap_moves else {
+        return player;
+    };
+
+    let (player, name): (Box<dyn Player>, String) = match player {
+        PlayerType::BuildIn(player, name) => (player, name),
+        PlayerType::Upi(player, name) => (player, name),
+    };
+    PlayerType::BuildIn(
+        Box::new(HandicapPlayer::new(player, handicap_moves)),
+        format!("handicap(moves: {handicap_moves}) {name}"),
+    )
+}
+
+/// A player that is shown under another name than the name of the wrapped
+/// player (see [`with_name`]).
+struct NamedPlayer {
+    /// The name the player is shown under.
+    name: String,
+    /// The player that plays the moves.
+    inner: Box<dyn Player>,
+}
+
+impl Player for NamedPlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action(&mut self, game: &Patchwork) -> anyhow::Result<ActionId> {
+        self.inner.get_action(game)
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> anyhow::Result<ActionId> {
+        self.inner.get_restricted_action(game, actions)
+    }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> anyhow::Result<(ActionId, SearchDiagnostics)> {
+        self.inner.get_action_with_diagnostics(game)
+    }
+
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> anyhow::Result<(ActionId, SearchDiagnostics)> {
+        self.inner.get_restricted_action_with_diagnostics(game, actions)
+    }
+
+    fn set_logging(&mut self, logging: Logging) {
+        self.inner.set_logging(logging);
+    }
+
+    fn reset(&mut self) {
+        self.inner.reset();
+    }
+}
+
+/// Shows the given player under the given name, e.g. to tell apart two
+/// differently configured players of the same type.
+///
+/// The construct name of the returned player is prefixed with
+/// `named(name: <name>)` so that [`get_player`] creates the same player from
+/// it again. Without a name the player is returned as is.
+pub fn with_name(player: PlayerType, name: Option<&str>) -> PlayerType {
+    let Some(name) = name else {
+        return player;
+    };
+
+    let (inner, construct_name): (Box<dyn Player>, String) = match player {
+        PlayerType::BuildIn(player, construct_name) => (player, construct_name),
+        PlayerType::Upi(player, construct_name) => (player, construct_name),
+    };
+    PlayerType::BuildIn(
+        Box::new(NamedPlayer {
+            name: name.to_string(),
+            inner,
+        }),
+        format!("named(name: {name}) {construct_name}"),
+    )
+}
+
+/// Whether the player with the given name uses a neural network, i.e. is an
+/// `AlphaZero` player or uses the neural network evaluator.
+fn requires_neural_feature(name: &str) -> bool {
+    name.starts_with("alphazero") || Regex::new(r"eval:\s*nn\b").unwrap().is_match(name)
+}
+
+/// Whether the player with the given name is a greedy player that has weights
+/// for an evaluator other than the static evaluator, which would ignore them.
+fn ignores_static_weights(name: &str) -> bool {
+    let Some(options) = Regex::new(r"^greedy\((?<options>.*)\)").unwrap().captures(name) else {
+        return false;
+    };
+    options["options"].contains("weights:")
+        && Regex::new(r"eval:\s*(win|score|nn)\b").unwrap().is_match(&options["options"])
+}
+
+/// Returns why the player with the given name cannot be created although its
+/// name is valid or `None` if nothing prevents the player from being created.
+fn get_unsupported_reason(name: &str) -> Option<String> {
+    if !cfg!(feature = "neural") && requires_neural_feature(name) {
+        return Some(format!("The player {name} needs neural networks but the `neural` feature is disabled"));
+    }
+    if ignores_static_weights(name) {
+        return Some(format!("The player {name} has weights but only the static evaluator uses weights"));
+    }
+    None
 }
 
 pub fn get_available_players() -> Vec<String> {
@@ -148,17 +408,25 @@ pub fn get_available_players() -> Vec<String> {
         "human",
         "human(name: string)",
         "random",
-        "random(seed: uint)",
+        "random(seed: uint, weight: uniform|income)",
         "greedy",
-        "greedy(eval: static|win|score|nn)",
+        "greedy(eval: static|win|score|nn, metric: value|efficiency, weights: [float, float, float, float])",
         "minimax",
-        "minimax(depth: uint, patches: uint)",
+        "minimax(depth: uint, patches: uint, sampling: first|top|diverse|random, seed: uint, iterative: yes|no)",
+        "endgame",
+        "endgame(remaining: uint)",
         "pvs",
-        "pvs(time: float, ord: table | eval, eval: static|win|score|nn, fail: hard|soft, asp: yes|no, lmr: yes|no, lmp: yes|no, ext: yes|no, tt: enabled|disabled, smp: yes|no)",
+        "pvs(time: float, depth: int, unstable: float, predict: yes|no, adaptive: yes|no, cache: yes|no, margin: yes|no, ord: table | eval, eval: static|win|score|nn, fail: hard|soft, asp: yes|no, lmr: yes|no, lmp: yes|no, ext: yes|no, tt: enabled|disabled, smp: yes|no, mtdf: yes|no)",
         "mcts",
-        "mcts(time: float, iter: uint, tree: reuse|new, root: uint, leaf: uint, policy: uct|partial-score|score|puct, eval: static|win|score|nn)",
+        "mcts(time: float, iter: uint, tree: reuse|new, root: uint, leaf: uint, policy: uct|partial-score|score|value|puct, explore: float, decay: float..float, opponent: greedy, nodes: uint, eval: static|win|score|nn|rollout)",
+        #[cfg(feature = "neural")]
         "alphazero",
+        #[cfg(feature = "neural")]
         "alphazero(time: float, iter: uint, policy: uct|partial-score|score|puct)",
+        "handicap(moves: uint) <player>",
+        "opening(moves: uint, top: uint, seed: uint) <player>",
+        "named(name: string) <player>",
+        "extern <program> <arguments> (a UPI engine)",
     ]
     .iter()
     .map(|s| (*s).to_string())
@@ -218,12 +486,28 @@ fn parse_random_player(mut name: &str) -> Option<Box<dyn Player>> {
         player_name = format!("RandomPlayer(seed: {seed}");
     }
 
+    if let Some(weighting) = Regex::new(r"weight:\s*(?<weight>uniform|income)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("weight"))
+        .map(|o| o.as_str())
+    {
+        options.weighting = match weighting {
+            "uniform" => RandomWeighting::Uniform,
+            "income" => RandomWeighting::ButtonIncome,
+            _ => unreachable!(),
+        };
+    }
+
     Some(Box::new(RandomPlayer::new(player_name, Some(options))))
 }
 
 fn parse_greedy_player(mut name: &str) -> Option<Box<dyn Player>> {
-    fn create_player<Eval: Evaluator + Default + 'static>(player_name: impl Into<String>) -> Box<dyn Player> {
-        Box::new(GreedyPlayer::<Eval>::new(player_name))
+    fn create_player<Eval: Evaluator + Default + 'static>(
+        player_name: impl Into<String>,
+        options: GreedyOptions,
+    ) -> Box<dyn Player> {
+        Box::new(GreedyPlayer::<Eval>::new(player_name, Some(options)))
     }
 
     if name == "greedy" {
@@ -240,6 +524,7 @@ fn parse_greedy_player(mut name: &str) -> Option<Box<dyn Player>> {
         .and_then(|o| o.name("options"))
         .map(|o| o.as_str())?;
 
+    let mut options = GreedyOptions::default();
     let mut evaluator = "static";
 
     if let Some(eval) = Regex::new(r"eval:\s*(?<eval>static|win|score|nn)")
@@ -251,11 +536,61 @@ fn parse_greedy_player(mut name: &str) -> Option<Box<dyn Player>> {
         evaluator = eval;
     }
 
+    if let Some(metric) = Regex::new(r"metric:\s*(?<metric>value|efficiency)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("metric"))
+        .map(|o| o.as_str())
+    {
+        options.metric = match metric {
+            "value" => GreedyMetric::Evaluation,
+            "efficiency" => GreedyMetric::Efficiency,
+            _ => unreachable!(),
+        };
+    }
+
+    // the weights of the static evaluator in the order board, score, position, button income
+    let weights = Regex::new(r"weights:\s*\[(?<weights>[^\]]*)\]")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("weights"))
+        .and_then(|o| {
+            let weights = o
+                .as_str()
+                .split(',')
+                .map(|weight| weight.trim().parse::<f64>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            Some(StaticWeights::from_array(weights.try_into().ok()?))
+        });
+
+    let metric = match options.metric {
+        GreedyMetric::Evaluation => "value",
+        GreedyMetric::Efficiency => "efficiency",
+    };
+    let player_name = weights.map_or_else(
+        || format!("GreedyPlayer(eval: {evaluator}, metric: {metric})"),
+        |weights| {
+            let [board, score, position, button_income] = weights.to_array();
+            format!(
+                "GreedyPlayer(eval: {evaluator}, metric: {metric}, weights: [{board}, {score}, {position}, {button_income}])"
+            )
+        },
+    );
+
+    if let Some(weights) = weights {
+        return Some(Box::new(GreedyPlayer {
+            name: player_name,
+            evaluator: StaticEvaluator::with_weights(weights),
+            options,
+        }));
+    }
+
     let player: Box<dyn Player> = match evaluator {
-        "static" => create_player::<StaticEvaluator>("GreedyPlayer(eval: static)"),
-        "win" => create_player::<WinLossEvaluator>("GreedyPlayer(eval: win)"),
-        "score" => create_player::<ScoreEvaluator>("GreedyPlayer(eval: score)"),
-        "nn" => create_player::<NeuralNetworkEvaluator>("GreedyPlayer(eval: nn)"),
+        "static" => create_player::<StaticEvaluator>(player_name, options),
+        "win" => create_player::<WinLossEvaluator>(player_name, options),
+        "score" => create_player::<ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
+        "nn" => create_player::<NeuralNetworkEvaluator>(player_name, options),
         _ => unreachable!(),
     };
 
@@ -297,15 +632,85 @@ fn parse_minimax_player(mut name: &str) -> Option<Box<dyn Player>> {
         options.amount_actions_per_piece = patches;
     }
 
+    let seed = Regex::new(r"seed:\s*(?<seed>\d+)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("seed"))
+        .and_then(|o| o.as_str().parse().ok())
+        .unwrap_or(0);
+
+    if let Some(sampling) = Regex::new(r"sampling:\s*(?<sampling>first|top|diverse|random)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("sampling"))
+    {
+        options.sampling_strategy = match sampling.as_str() {
+            "first" => SamplingStrategy::First,
+            "top" => SamplingStrategy::TopScore,
+            "diverse" => SamplingStrategy::Diverse,
+            "random" => SamplingStrategy::Random { seed },
+            _ => unreachable!(),
+        };
+    }
+
+    if let Some(iterative) = Regex::new(r"iterative:\s*(?<iterative>yes|no)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("iterative"))
+        .map(|o| o.as_str())
+    {
+        options.iterative = iterative == "yes";
+    }
+
     Some(Box::new(MinimaxPlayer::<StaticEvaluator>::new(
         format!(
-            "MinimaxPlayer(depth: {}, patches: {})",
-            options.depth, options.amount_actions_per_piece
+            "MinimaxPlayer(depth: {}, patches: {}, sampling: {}, iterative: {})",
+            options.depth,
+            options.amount_actions_per_piece,
+            match options.sampling_strategy {
+                SamplingStrategy::First => "first".to_string(),
+                SamplingStrategy::TopScore => "top".to_string(),
+                SamplingStrategy::Diverse => "diverse".to_string(),
+                SamplingStrategy::Random { seed } => format!("random, seed: {seed}"),
+            },
+            if options.iterative { "yes" } else { "no" }
         ),
         Some(options),
     )))
 }
 
+fn parse_endgame_player(mut name: &str) -> Option<Box<dyn Player>> {
+    if name == "endgame" {
+        name = "endgame()";
+    }
+
+    if !name.starts_with("endgame") {
+        return None;
+    }
+
+    let passed_options = Regex::new(r"endgame\((?<options>.*)\)")
+        .unwrap()
+        .captures(name)
+        .and_then(|o| o.name("options"))
+        .map(|o| o.as_str())?;
+
+    let mut options = EndgameOptions::default();
+
+    if let Some(max_remaining_time) = Regex::new(r"remaining:\s*(?<remaining>\d+)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("remaining"))
+        .and_then(|o| o.as_str().parse().ok())
+    {
+        options.max_remaining_time = max_remaining_time;
+    }
+
+    Some(Box::new(EndgameSolver::<StaticEvaluator>::new(
+        format!("EndgameSolver(remaining: {})", options.max_remaining_time),
+        Some(options),
+    )))
+}
+
 #[allow(clippy::too_many_lines)]
 fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>>, Option<Logging>) {
     fn create_player<Orderer: ActionOrderer + Default + 'static, Eval: Evaluator + Default + 'static>(
@@ -346,6 +751,60 @@ fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>
         options.time_limit = std::time::Duration::from_secs_f64(time_limit);
     }
 
+    if let Some(max_depth) = Regex::new(r"depth:\s*(?<depth>\d+)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("depth"))
+        .and_then(|o| o.as_str().parse().ok())
+    {
+        options.max_depth = Some(max_depth);
+    }
+
+    if let Some(instability_time_extension) = Regex::new(r"unstable:\s*(?<unstable>\d+(?:\.\d+)?)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("unstable"))
+        .and_then(|o| o.as_str().parse().ok())
+    {
+        options.instability_time_extension = Some(std::time::Duration::from_secs_f64(instability_time_extension));
+    }
+
+    if let Some(time_prediction) = Regex::new(r"predict:\s*(?<predict>yes|no)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("predict"))
+        .map(|o| o.as_str())
+    {
+        options.time_prediction = time_prediction == "yes";
+    }
+
+    if let Some(placement_cache) = Regex::new(r"cache:\s*(?<cache>yes|no)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("cache"))
+        .map(|o| o.as_str())
+    {
+        options.placement_cache = placement_cache == "yes";
+    }
+
+    if let Some(minimize_loss_margin) = Regex::new(r"margin:\s*(?<margin>yes|no)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("margin"))
+        .map(|o| o.as_str())
+    {
+        options.minimize_loss_margin = minimize_loss_margin == "yes";
+    }
+
+    if let Some(adaptive_time) = Regex::new(r"adaptive:\s*(?<adaptive>yes|no)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("adaptive"))
+        .map(|o| o.as_str())
+    {
+        options.adaptive_time = adaptive_time == "yes";
+    }
+
     if let Some(failing_strategy) = Regex::new(r"fail:\s*(?<fail>hard|soft)")
         .unwrap()
         .captures(passed_options)
@@ -424,6 +883,15 @@ fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>
         };
     }
 
+    if let Some(mtdf) = Regex::new(r"mtdf:\s*(?<mtdf>yes|no)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("mtdf"))
+        .map(|o| o.as_str())
+    {
+        options.features.mtdf = mtdf == "yes";
+    }
+
     if let Some(order) = Regex::new(r"ord:\s*(?<orderer>table)")
         .unwrap()
         .captures(passed_options)
@@ -443,7 +911,7 @@ fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>
     }
 
     let player_name = format!(
-        "PVSPlayer(time: {:?}, ord: {orderer}, eval: {evaluator}, fail: {}, asp: {}, lmr: {}, lmp: {}, ext: {}, tt: {}, smp: {})",
+        "PVSPlayer(time: {:?}, ord: {orderer}, eval: {evaluator}, fail: {}, asp: {}, lmr: {}, lmp: {}, ext: {}, tt: {}, smp: {}, mtdf: {})",
         options.time_limit,
         options.features.failing_strategy,
         options.features.aspiration_window,
@@ -451,13 +919,15 @@ fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>
         options.features.late_move_pruning,
         options.features.search_extensions,
         options.features.transposition_table,
-        options.features.lazy_smp
+        options.features.lazy_smp,
+        options.features.mtdf
     );
 
     let player: Box<dyn Player> = match (orderer, evaluator) {
         ("table", "static") => create_player::<TableActionOrderer, StaticEvaluator>(player_name, options),
         ("table", "win") => create_player::<TableActionOrderer, WinLossEvaluator>(player_name, options),
         ("table", "score") => create_player::<TableActionOrderer, ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
         ("table", "nn") => create_player::<TableActionOrderer, NeuralNetworkEvaluator>(player_name, options),
         ("eval", "static") => {
             create_player::<EvaluationActionOrderer<StaticEvaluator>, StaticEvaluator>(player_name, options)
@@ -468,6 +938,7 @@ fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>
         ("eval", "score") => {
             create_player::<EvaluationActionOrderer<ScoreEvaluator>, ScoreEvaluator>(player_name, options)
         }
+        #[cfg(feature = "neural")]
         ("eval", "nn") => create_player::<EvaluationActionOrderer<NeuralNetworkEvaluator>, NeuralNetworkEvaluator>(
             player_name,
             options,
@@ -480,7 +951,7 @@ fn parse_pvs_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>
 
 #[allow(clippy::too_many_lines)]
 fn parse_mcts_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>>, Option<Logging>) {
-    fn create_player<Policy: TreePolicy + Default + 'static, Eval: Evaluator + Default + 'static>(
+    fn create_player<Policy: ExplorationTreePolicy + Default + 'static, Eval: Evaluator + Default + 'static>(
         player_name: impl Into<String>,
         options: MCTSOptions,
     ) -> Box<dyn Player> {
@@ -552,7 +1023,16 @@ fn parse_mcts_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player
         options.leaf_parallelization = leaf_parallelization;
     }
 
-    if let Some(pol) = Regex::new(r"policy:\s*(?<policy>uct|partial-score|score|puct)")
+    if let Some(skill) = Regex::new(r"skill:\s*(?<skill>\d+(?:\.\d+)?)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("skill"))
+        .and_then(|o| o.as_str().parse().ok())
+    {
+        options.skill = Some(skill);
+    }
+
+    if let Some(pol) = Regex::new(r"policy:\s*(?<policy>uct|partial-score|score|value|puct)")
         .unwrap()
         .captures(passed_options)
         .and_then(|o| o.name("policy"))
@@ -561,7 +1041,38 @@ fn parse_mcts_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player
         policy = pol;
     }
 
-    if let Some(eval) = Regex::new(r"eval:\s*(?<eval>static|win|score|nn)")
+    if let Some(exploration_constant) = Regex::new(r"explore:\s*(?<explore>\d+(?:\.\d+)?)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("explore"))
+        .and_then(|o| o.as_str().parse().ok())
+    {
+        options.exploration_constant = Some(exploration_constant);
+    }
+
+    if let Some(decay) = Regex::new(r"decay:\s*(?<opening>\d+(?:\.\d+)?)\.\.(?<endgame>\d+(?:\.\d+)?)")
+        .unwrap()
+        .captures(passed_options)
+    {
+        if let (Ok(opening), Ok(endgame)) = (decay["opening"].parse(), decay["endgame"].parse()) {
+            options.exploration_decay = Some(ExplorationDecay::new(opening, endgame));
+        }
+    }
+
+    if Regex::new(r"opponent:\s*greedy").unwrap().is_match(passed_options) {
+        options.opponent_model = Some(OpponentModel::Greedy);
+    }
+
+    if let Some(max_nodes) = Regex::new(r"nodes:\s*(?<nodes>\d+)")
+        .unwrap()
+        .captures(passed_options)
+        .and_then(|o| o.name("nodes"))
+        .and_then(|o| o.as_str().parse().ok())
+    {
+        options.max_nodes = Some(max_nodes);
+    }
+
+    if let Some(eval) = Regex::new(r"eval:\s*(?<eval>static|win|score|nn|rollout)")
         .unwrap()
         .captures(passed_options)
         .and_then(|o| o.name("eval"))
@@ -584,25 +1095,40 @@ fn parse_mcts_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player
         ("uct", "static") => create_player::<UCTPolicy, StaticEvaluator>(player_name, options),
         ("uct", "win") => create_player::<UCTPolicy, WinLossEvaluator>(player_name, options),
         ("uct", "score") => create_player::<UCTPolicy, ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
         ("uct", "nn") => create_player::<UCTPolicy, NeuralNetworkEvaluator>(player_name, options),
+        ("uct", "rollout") => create_player::<UCTPolicy, RolloutEvaluator>(player_name, options),
         ("partial-score", "static") => create_player::<PartiallyScoredUCTPolicy, StaticEvaluator>(player_name, options),
         ("partial-score", "win") => create_player::<PartiallyScoredUCTPolicy, WinLossEvaluator>(player_name, options),
         ("partial-score", "score") => create_player::<PartiallyScoredUCTPolicy, ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
         ("partial-score", "nn") => create_player::<PartiallyScoredUCTPolicy, NeuralNetworkEvaluator>(player_name, options),
+        ("partial-score", "rollout") => create_player::<PartiallyScoredUCTPolicy, RolloutEvaluator>(player_name, options),
         ("score", "static") => create_player::<ScoredUCTPolicy, StaticEvaluator>(player_name, options),
         ("score", "win") => create_player::<ScoredUCTPolicy, WinLossEvaluator>(player_name, options),
         ("score", "score") => create_player::<ScoredUCTPolicy, ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
         ("score", "nn") => create_player::<ScoredUCTPolicy, NeuralNetworkEvaluator>(player_name, options),
+        ("score", "rollout") => create_player::<ScoredUCTPolicy, RolloutEvaluator>(player_name, options),
+        ("value", "static") => create_player::<ValueUCTPolicy, StaticEvaluator>(player_name, options),
+        ("value", "win") => create_player::<ValueUCTPolicy, WinLossEvaluator>(player_name, options),
+        ("value", "score") => create_player::<ValueUCTPolicy, ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
+        ("value", "nn") => create_player::<ValueUCTPolicy, NeuralNetworkEvaluator>(player_name, options),
+        ("value", "rollout") => create_player::<ValueUCTPolicy, RolloutEvaluator>(player_name, options),
         ("puct", "static") => create_player::<PUCTPolicy, StaticEvaluator>(player_name, options),
         ("puct", "win") => create_player::<PUCTPolicy, WinLossEvaluator>(player_name, options),
         ("puct", "score") => create_player::<PUCTPolicy, ScoreEvaluator>(player_name, options),
+        #[cfg(feature = "neural")]
         ("puct", "nn") => create_player::<PUCTPolicy, NeuralNetworkEvaluator>(player_name, options),
+        ("puct", "rollout") => create_player::<PUCTPolicy, RolloutEvaluator>(player_name, options),
         _ => unreachable!(),
     };
 
     (Some(player), None)
 }
 
+#[cfg(feature = "neural")]
 fn parse_alphazero_player(mut name: &str, logging: Logging) -> (Option<Box<dyn Player>>, Option<Logging>) {
     fn create_player<Policy: TreePolicy + Default + 'static>(
         player_name: &str,
@@ -696,3 +1222,83 @@ fn parse_alphazero_player(mut name: &str, logging: Logging) -> (Option<Box<dyn P
 
     (Some(player), None)
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_lib::GameOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_classical_players_play_valid_actions() {
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+
+        for name in ["random", "greedy", "minimax(depth: 1)", "pvs(time: 0.1)", "mcts(iter: 10)"] {
+            let mut player = get_player(name, Logging::Disabled).unwrap();
+            let action = player.get_action(&state).unwrap();
+            assert!(state.get_valid_actions().contains(&action), "{name} played {action:?}");
+        }
+    }
+
+    #[test]
+    fn test_handicap_player_can_be_recreated_from_construct_name() {
+        let player = with_handicap(get_player("mcts(iter: 10)", Logging::Disabled).unwrap(), Some(3));
+        assert_eq!("handicap(moves: 3) mcts(iter: 10)", player.get_construct_name());
+
+        let player = get_player(player.get_construct_name(), Logging::Disabled).unwrap();
+        assert_eq!("handicap(moves: 3) mcts(iter: 10)", player.get_construct_name());
+        assert!(player.name().ends_with("(handicap: 3)"), "{}", player.name());
+    }
+
+    #[test]
+    fn test_randomized_opening_player_can_be_recreated_from_construct_name() {
+        let player = get_player("opening(moves: 4, top: 3) greedy", Logging::Disabled).unwrap();
+        assert_eq!("opening(moves: 4, top: 3, seed: 0) greedy", player.get_construct_name());
+
+        let player = get_player(player.get_construct_name(), Logging::Disabled).unwrap();
+        assert_eq!("opening(moves: 4, top: 3, seed: 0) greedy", player.get_construct_name());
+        assert!(
+            player.name().ends_with("(opening: top 3 in 4 moves)"),
+            "{}",
+            player.name()
+        );
+    }
+
+    #[test]
+    fn test_named_player_can_be_recreated_from_construct_name() {
+        let player = with_name(
+            get_player("mcts(iter: 10)", Logging::Disabled).unwrap(),
+            Some("Fast MCTS"),
+        );
+        assert_eq!("named(name: Fast MCTS) mcts(iter: 10)", player.get_construct_name());
+
+        let player = get_player(player.get_construct_name(), Logging::Disabled).unwrap();
+        assert_eq!("named(name: Fast MCTS) mcts(iter: 10)", player.get_construct_name());
+        assert_eq!("Fast MCTS", player.name());
+    }
+
+    #[test]
+    fn test_greedy_player_weights_need_static_evaluator() {
+        let player = get_player("greedy(eval: static, weights: [1, 2, 3, 4])", Logging::Disabled).unwrap();
+        assert!(player.name().ends_with("weights: [1, 2, 3, 4])"), "{}", player.name());
+
+        for name in ["greedy(eval: win, weights: [1, 2, 3, 4])", "greedy(weights: [1, 2, 3, 4], eval: score)"] {
+            let Err(error) = get_player(name, Logging::Disabled) else {
+                panic!("{name} could be created although the weights are ignored");
+            };
+            assert!(error.reason.contains("only the static evaluator"), "{}", error.reason);
+        }
+    }
+
+    #[test]
+    #[cfg(not(feature = "neural"))]
+    fn test_neural_players_need_neural_feature() {
+        for name in ["alphazero", "alphazero(iter: 10)", "greedy(eval: nn)", "mcts(eval: nn)"] {
+            let Err(error) = get_player(name, Logging::Disabled) else {
+                panic!("{name} could be created without the `neural` feature");
+            };
+            assert!(error.reason.contains("`neural` feature"), "{}", error.reason);
+        }
+        assert!(crate::common::get_evaluator("nn").is_err());
+    }
+}
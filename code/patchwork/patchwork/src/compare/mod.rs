@@ -1,19 +1,24 @@
 use std::{
+    collections::{BTreeMap, HashMap},
     fs::OpenOptions,
-    io::{BufWriter, Write},
-    panic,
-    path::Path,
-    sync::atomic::{self, AtomicI32, AtomicU32, AtomicU64, Ordering},
+    io::{BufWriter, IsTerminal, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
 };
 
 use anyhow::Error;
 use clap::Parser;
 use rustyline::{error::ReadlineError, history::FileHistory, Editor};
 
-use crate::common::{get_logging, get_player, interactive_get_player, PlayerType, CTRL_C_MESSAGE, CTRL_D_MESSAGE};
+use crate::common::{
+    get_csv_observer, get_logging, get_player, interactive_get_configured_player, interactive_get_player,
+    ObserverType, PlayerConfig, PlayerType, CTRL_C_MESSAGE, CTRL_D_MESSAGE,
+};
 use patchwork_lib::{
+    compare::{move_entropy, run_compare, CompareResult, RecordedGame},
     player::{Logging, Player},
-    Patchwork, TerminationType,
+    GameRecord, TerminationType,
 };
 
 #[derive(Debug, Parser, Default)]
@@ -27,28 +32,425 @@ struct CmdArgs {
     logging_player_1: String,
     #[arg(long = "logging-2", alias = "l2", default_value = "disabled")]
     logging_player_2: String,
+    #[arg(long = "handicap-1", alias = "h1")]
+    handicap_player_1: Option<usize>,
+    #[arg(long = "handicap-2", alias = "h2")]
+    handicap_player_2: Option<usize>,
+    #[arg(long = "config", short = 'c')]
+    config: Option<String>,
+    #[arg(long = "gauntlet", num_args = 1..)]
+    gauntlet: Vec<String>,
     #[arg(long = "games", short = 'g')]
     games: Option<usize>,
     #[arg(long = "update", short = 'u', default_value = "100")]
     update: u64,
     #[arg(long = "parallel", short = 'p')]
     parallel: Option<usize>,
+    #[arg(long = "pin-cores")]
+    pin_cores: bool,
+    #[arg(long = "throttle")]
+    throttle: Option<f64>,
+    #[arg(long = "csv")]
+    csv: Option<String>,
+    #[arg(long = "save-full")]
+    save_full: Option<String>,
+    #[arg(long = "json-out")]
+    json_out: Option<String>,
+    #[arg(long = "resume")]
+    resume: bool,
+    #[arg(long = "plain", alias = "no-tty")]
+    plain: bool,
+}
+
+/// Writes the records of full games to a directory. The games are encoded
+/// with bincode and sharded into files of [`FullGameWriter::GAMES_PER_FILE`]
+/// games each so that large comparisons can be re-analyzed move by move later.
+struct FullGameWriter {
+    /// The directory the files are written to.
+    directory: PathBuf,
+    /// The amount of games per file.
+    games_per_file: usize,
+    /// The games that were not written yet as the current file is not full.
+    pending: Vec<GameRecord>,
+    /// The index of the next file that is written.
+    next_file: usize,
+}
+
+impl FullGameWriter {
+    /// The default amount of games per file.
+    const GAMES_PER_FILE: usize = 1000;
+
+    /// Creates a writer for the given directory. The directory is created if
+    /// it does not exist. Existing files (e.g. of a resumed comparison) are
+    /// kept and new files are numbered after them.
+    fn new(directory: impl Into<PathBuf>, games_per_file: usize) -> anyhow::Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        let next_file = get_full_game_files(&directory)?.len();
+
+        Ok(Self {
+            directory,
+            games_per_file,
+            pending: vec![],
+            next_file,
+        })
+    }
+
+    /// Adds the records of the given games and writes every file that is full.
+    fn write(&mut self, games: Vec<RecordedGame>) -> anyhow::Result<()> {
+        self.pending.extend(games.into_iter().filter_map(|game| game.record));
+        while self.pending.len() >= self.games_per_file {
+            let games = self.pending.drain(..self.games_per_file).collect::<Vec<_>>();
+            self.write_file(&games)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the remaining games to a last file that is not full.
+    fn finish(mut self) -> anyhow::Result<()> {
+        if !self.pending.is_empty() {
+            let games = std::mem::take(&mut self.pending);
+            self.write_file(&games)?;
+        }
+        Ok(())
+    }
+
+    fn write_file(&mut self, games: &[GameRecord]) -> anyhow::Result<()> {
+        let path = self.directory.join(format!("games_{:05}.game.bin", self.next_file));
+        let mut writer = BufWriter::new(std::fs::File::create(path)?);
+        bincode::serialize_into(&mut writer, games)?;
+        writer.flush()?;
+
+        self.next_file += 1;
+        Ok(())
+    }
+}
+
+/// Gets all files written by a [`FullGameWriter`] in the given directory in the order they were written.
+/// Every file contains the bincode encoded list of [`GameRecord`]s.
+fn get_full_game_files(directory: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut files = std::fs::read_dir(directory)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<Vec<_>, _>>()?;
+    files.retain(|path| {
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("games_") && name.ends_with(".game.bin"))
+    });
+    files.sort();
+    Ok(files)
+}
+
+/// The machine-readable summary of a comparison that is written as JSON.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CompareSummary {
+    /// The amount of finished games.
+    pub games: u32,
+    /// The amount of games where both players had the same score.
+    pub score_ties: u32,
+    /// The average amount of plies per game.
+    pub average_plies: f64,
+    /// The average amount of legal moves per ply.
+    pub average_branching_factor: f64,
+    /// The Elo difference of player 1 to player 2 estimated from the win rate
+    /// or `None` if a player won every game.
+    pub elo_difference: Option<f64>,
+    pub player_1: PlayerSummary,
+    pub player_2: PlayerSummary,
+}
+
+/// The summary of one player of a comparison.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PlayerSummary {
+    pub name: String,
+    pub wins: u32,
+    /// The share of the games the player won between `0.0` and `1.0`.
+    pub win_rate: f64,
+    pub average_score: f64,
+    /// The maximum score or `None` if no game was finished.
+    pub max_score: Option<i32>,
+    /// The minimum score or `None` if no game was finished.
+    pub min_score: Option<i32>,
+    /// The summed time the player needed for all turns in nanoseconds.
+    pub sum_time_ns: u64,
+    /// The average time the player needed for a turn in nanoseconds.
+    pub average_time_ns: f64,
+    pub turns: u64,
+    /// How often the player played each first move (in action notation).
+    pub first_moves: BTreeMap<String, u64>,
+    /// The Shannon entropy of the first moves in bits.
+    pub first_move_entropy: f64,
+}
+
+impl CompareSummary {
+    /// Summarizes the given result of a comparison.
+    ///
+    /// # Arguments
+    ///
+    /// * `result` - The result of the comparison.
+    /// * `player_1_name` - The name of the first player.
+    /// * `player_2_name` - The name of the second player.
+    ///
+    /// # Returns
+    ///
+    /// The summary of the comparison.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn new(result: &CompareResult, player_1_name: &str, player_2_name: &str) -> Self {
+        let games = f64::from(result.games().max(1));
+        let summarize = |name: &str, wins: u32, scores: [i32; 3], sum_time_ns: u64, turns: u64, first_moves| {
+            let [sum_score, max_score, min_score] = scores;
+            PlayerSummary {
+                name: name.to_string(),
+                wins,
+                win_rate: f64::from(wins) / games,
+                average_score: f64::from(sum_score) / games,
+                max_score: Some(max_score).filter(|score| *score != i32::MIN),
+                min_score: Some(min_score).filter(|score| *score != i32::MAX),
+                sum_time_ns,
+                average_time_ns: sum_time_ns as f64 / turns.max(1) as f64,
+                turns,
+                first_move_entropy: move_entropy(first_moves),
+                first_moves: first_moves.clone(),
+            }
+        };
+
+        let player_1 = summarize(
+            player_1_name,
+            result.wins_player_1,
+            [
+                result.sum_player_1_score,
+                result.max_player_1_score,
+                result.min_player_1_score,
+            ],
+            result.sum_time_player_1,
+            result.turns_player_1,
+            &result.first_moves_player_1,
+        );
+        let player_2 = summarize(
+            player_2_name,
+            result.wins_player_2,
+            [
+                result.sum_player_2_score,
+                result.max_player_2_score,
+                result.min_player_2_score,
+            ],
+            result.sum_time_player_2,
+            result.turns_player_2,
+            &result.first_moves_player_2,
+        );
+
+        // logistic Elo model: E = 1 / (1 + 10^(-D / 400))
+        let win_rate = player_1.win_rate;
+        let elo_difference = (result.games() > 0 && win_rate > 0.0 && win_rate < 1.0)
+            .then(|| -400.0 * (1.0 / win_rate - 1.0).log10());
+
+        Self {
+            games: result.games(),
+            score_ties: result.score_ties,
+            average_plies: result.average_plies(),
+            average_branching_factor: result.average_branching_factor(),
+            elo_difference,
+            player_1,
+            player_2,
+        }
+    }
 }
 
-struct RecordedGame {
-    pub player_1_name: String,
-    pub player_2_name: String,
-    pub result: TerminationType,
+/// Writes the summaries of comparisons as pretty printed JSON to the given
+/// file. A single summary is written as an object and several summaries (of a
+/// gauntlet) as an array.
+fn write_json_summaries(path: &Path, summaries: &[CompareSummary]) -> anyhow::Result<()> {
+    let mut writer = BufWriter::new(std::fs::File::create(path)?);
+    if let [summary] = summaries {
+        serde_json::to_writer_pretty(&mut writer, summary)?;
+    } else {
+        serde_json::to_writer_pretty(&mut writer, summaries)?;
+    }
+    writeln!(writer)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// The state of a comparison that is written after every progress update so
+/// that an interrupted comparison can be resumed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CompareCheckpoint {
+    /// The name the first player was constructed from.
+    player_1: String,
+    /// The name the second player was constructed from.
+    player_2: String,
+    /// The result of the finished games without the recorded games.
+    result: CompareResult,
 }
 
+impl CompareCheckpoint {
+    /// The name of the checkpoint file in the rating folder.
+    const FILE_NAME: &'static str = "checkpoint.txt";
+
+    /// Creates a checkpoint for a comparison of the given players without any
+    /// finished games.
+    fn new(player_1: impl Into<String>, player_2: impl Into<String>) -> Self {
+        Self {
+            player_1: player_1.into(),
+            player_2: player_2.into(),
+            result: CompareResult::new(),
+        }
+    }
+
+    /// Loads the checkpoint from the given file.
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        fn get<T: FromStr>(values: &HashMap<&str, &str>, key: &str) -> anyhow::Result<T> {
+            values
+                .get(key)
+                .and_then(|value| value.parse().ok())
+                .ok_or_else(|| Error::msg(format!("Checkpoint value \"{key}\" is missing or invalid")))
+        }
+
+        fn get_frequencies(values: &HashMap<&str, &str>, key: &str) -> anyhow::Result<BTreeMap<String, u64>> {
+            let value = values
+                .get(key)
+                .ok_or_else(|| Error::msg(format!("Checkpoint value \"{key}\" is missing")))?;
+            value
+                .split(',')
+                .filter(|entry| !entry.is_empty())
+                .map(|entry| {
+                    entry
+                        .rsplit_once(':')
+                        .and_then(|(action, count)| Some((action.to_string(), count.parse().ok()?)))
+                        .ok_or_else(|| Error::msg(format!("Checkpoint value \"{key}\" is invalid")))
+                })
+                .collect()
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let values = content
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .collect::<HashMap<_, _>>();
+
+        let result = CompareResult {
+            wins_player_1: get(&values, "wins_player_1")?,
+            wins_player_2: get(&values, "wins_player_2")?,
+            score_ties: get(&values, "score_ties")?,
+            max_player_1_score: get(&values, "max_player_1_score")?,
+            max_player_2_score: get(&values, "max_player_2_score")?,
+            min_player_1_score: get(&values, "min_player_1_score")?,
+            min_player_2_score: get(&values, "min_player_2_score")?,
+            sum_player_1_score: get(&values, "sum_player_1_score")?,
+            sum_player_2_score: get(&values, "sum_player_2_score")?,
+            sum_time_player_1: get(&values, "sum_time_player_1")?,
+            sum_time_player_2: get(&values, "sum_time_player_2")?,
+            turns_player_1: get(&values, "turns_player_1")?,
+            turns_player_2: get(&values, "turns_player_2")?,
+            plies: get(&values, "plies")?,
+            legal_moves: get(&values, "legal_moves")?,
+            first_moves_player_1: get_frequencies(&values, "first_moves_player_1")?,
+            first_moves_player_2: get_frequencies(&values, "first_moves_player_2")?,
+            pinned_cores: vec![],
+        };
+        if get::<u32>(&values, "games")? != result.games() {
+            return Err(Error::msg("Checkpoint value \"games\" does not match the amount of wins"));
+        }
+
+        Ok(Self {
+            player_1: get(&values, "player_1")?,
+            player_2: get(&values, "player_2")?,
+            result,
+        })
+    }
+
+    /// Saves the checkpoint to the given file. The checkpoint is written to a
+    /// temporary file first so that an interruption never leaves a partially
+    /// written checkpoint behind.
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let temporary_path = path.with_extension("tmp");
+        {
+            let result = &self.result;
+            let mut writer = BufWriter::new(std::fs::File::create(&temporary_path)?);
+            writeln!(writer, "player_1={}", self.player_1)?;
+            writeln!(writer, "player_2={}", self.player_2)?;
+            writeln!(writer, "games={}", result.games())?;
+            writeln!(writer, "wins_player_1={}", result.wins_player_1)?;
+            writeln!(writer, "wins_player_2={}", result.wins_player_2)?;
+            writeln!(writer, "score_ties={}", result.score_ties)?;
+            writeln!(writer, "max_player_1_score={}", result.max_player_1_score)?;
+            writeln!(writer, "max_player_2_score={}", result.max_player_2_score)?;
+            writeln!(writer, "min_player_1_score={}", result.min_player_1_score)?;
+            writeln!(writer, "min_player_2_score={}", result.min_player_2_score)?;
+            writeln!(writer, "sum_player_1_score={}", result.sum_player_1_score)?;
+            writeln!(writer, "sum_player_2_score={}", result.sum_player_2_score)?;
+            writeln!(writer, "sum_time_player_1={}", result.sum_time_player_1)?;
+            writeln!(writer, "sum_time_player_2={}", result.sum_time_player_2)?;
+            writeln!(writer, "turns_player_1={}", result.turns_player_1)?;
+            writeln!(writer, "turns_player_2={}", result.turns_player_2)?;
+            writeln!(writer, "plies={}", result.plies)?;
+            writeln!(writer, "legal_moves={}", result.legal_moves)?;
+            for (key, frequencies) in [
+                ("first_moves_player_1", &result.first_moves_player_1),
+                ("first_moves_player_2", &result.first_moves_player_2),
+            ] {
+                let frequencies = frequencies
+                    .iter()
+                    .map(|(action, count)| format!("{action}:{count}"))
+                    .collect::<Vec<_>>();
+                writeln!(writer, "{key}={}", frequencies.join(","))?;
+            }
+            writer.flush()?;
+        }
+        std::fs::rename(temporary_path, path)?;
+        Ok(())
+    }
+}
+
+#[allow(clippy::too_many_lines)]
 pub fn handle_compare(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> anyhow::Result<()> {
     let args = CmdArgs::parse_from(args);
 
     let player_1_logging = get_logging(args.logging_player_1.as_str())?;
     let player_2_logging = get_logging(args.logging_player_2.as_str())?;
 
-    let player_1 = interactive_get_player(rl, args.player_1, 1, player_1_logging)?;
-    let player_2 = interactive_get_player(rl, args.player_2, 2, player_2_logging)?;
+    let rating_folder = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("analysis").join("player-rating");
+    let checkpoint = if args.resume {
+        Some(CompareCheckpoint::load(&rating_folder.join(CompareCheckpoint::FILE_NAME))?)
+    } else {
+        None
+    };
+
+    let config = args.config.map(PlayerConfig::load).transpose()?.unwrap_or_default();
+    let spec_1 = config.player_1.as_ref();
+    let spec_2 = config.player_2.as_ref();
+
+    // the players of the config take precedence over the players of the checkpoint
+    let player_1_name = args.player_1.or_else(|| {
+        checkpoint
+            .as_ref()
+            .filter(|_| spec_1.is_none())
+            .map(|c| c.player_1.clone())
+    });
+    let player_2_name = args.player_2.or_else(|| {
+        checkpoint
+            .as_ref()
+            .filter(|_| spec_2.is_none())
+            .map(|c| c.player_2.clone())
+    });
+    let player_1 =
+        interactive_get_configured_player(rl, player_1_name, spec_1, args.handicap_player_1, 1, player_1_logging)?;
+    let player_2 =
+        interactive_get_configured_player(rl, player_2_name, spec_2, args.handicap_player_2, 2, player_2_logging)?;
+
+    // the opponents of a gauntlet are player 2 followed by the players 3, 4, ...
+    let mut gauntlet_opponents = vec![];
+    if !args.gauntlet.is_empty() {
+        if checkpoint.is_some() || args.save_full.is_some() {
+            return Err(Error::msg(
+                "A gauntlet can neither be resumed nor saved with --save-full",
+            ));
+        }
+        for (index, opponent) in args.gauntlet.iter().enumerate() {
+            let logging = get_logging(args.logging_player_2.as_str())?;
+            gauntlet_opponents.push(interactive_get_player(rl, Some(opponent.clone()), index + 3, logging)?);
+        }
+    }
 
     let games = if let Some(games) = args.games {
         games
@@ -91,23 +493,99 @@ pub fn handle_compare(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> an
         }
     };
 
-    compare(
+    let mut observers = vec![];
+    if let Some(csv) = args.csv {
+        observers.push(get_csv_observer(&csv)?);
+    }
+
+    if !gauntlet_opponents.is_empty() {
+        gauntlet_opponents.insert(0, player_2);
+        let rows = gauntlet(
+            games,
+            &player_1,
+            &gauntlet_opponents,
+            std::time::Duration::from_millis(args.update),
+            parallelization,
+            args.pin_cores,
+            args.throttle,
+            &Mutex::new(observers),
+            &rating_folder,
+            args.plain || !std::io::stdout().is_terminal(),
+        )?;
+        if let Some(json_out) = args.json_out {
+            let summaries = rows
+                .iter()
+                .map(|row| CompareSummary::new(&row.result, player_1.name(), &row.opponent))
+                .collect::<Vec<_>>();
+            write_json_summaries(Path::new(&json_out), &summaries)?;
+        }
+        return Ok(());
+    }
+
+    let result = compare(
         games,
         &player_1,
         &player_2,
         std::time::Duration::from_millis(args.update),
         parallelization,
-    )
+        args.pin_cores,
+        args.throttle,
+        &Mutex::new(observers),
+        &rating_folder,
+        args.save_full.as_deref().map(Path::new),
+        checkpoint,
+        args.plain || !std::io::stdout().is_terminal(),
+    )?;
+    if let Some(json_out) = args.json_out {
+        let summary = CompareSummary::new(&result, player_1.name(), player_2.name());
+        write_json_summaries(Path::new(&json_out), &[summary])?;
+    }
+    Ok(())
 }
 
-#[allow(clippy::too_many_lines)]
+/// Compares the players and writes the progress to stdout as well as the
+/// checkpoint, the played games and the final result to the rating folder.
+/// If a directory for full games is given the records of all games are
+/// written there as well.
+///
+/// # Returns
+///
+/// The result of the comparison (see [`run_compare`]).
+#[allow(clippy::too_many_arguments)]
 fn compare(
     iterations: usize,
     player_1: &PlayerType,
     player_2: &PlayerType,
     update: std::time::Duration,
     parallelization: usize,
-) -> anyhow::Result<()> {
+    pin_cores: bool,
+    throttle: Option<f64>,
+    observers: &Mutex<Vec<ObserverType>>,
+    rating_folder: &Path,
+    full_games_folder: Option<&Path>,
+    resume: Option<CompareCheckpoint>,
+    plain: bool,
+) -> anyhow::Result<CompareResult> {
+    let checkpoint_path = rating_folder.join(CompareCheckpoint::FILE_NAME);
+    let games_output = rating_folder.join("games.txt");
+    let display_output = rating_folder.join("output.txt");
+
+    let mut checkpoint = if let Some(checkpoint) = resume {
+        if checkpoint.player_1 != player_1.get_construct_name() || checkpoint.player_2 != player_2.get_construct_name() {
+            return Err(Error::msg(format!(
+                "The checkpoint was created for {} vs. {} and cannot be resumed with {} vs. {}",
+                checkpoint.player_1,
+                checkpoint.player_2,
+                player_1.get_construct_name(),
+                player_2.get_construct_name()
+            )));
+        }
+        println!("Resuming comparison after {} games", checkpoint.result.games());
+        checkpoint
+    } else {
+        CompareCheckpoint::new(player_1.get_construct_name(), player_2.get_construct_name())
+    };
+
     println!(
         "Comparing {} iterations with {} threads: {} vs. {}",
         iterations,
@@ -116,9 +594,7 @@ fn compare(
         player_2.name()
     );
     {
-        let rating_folder = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("analysis").join("player-rating");
-        let display_output = rating_folder.join("output.txt");
-        let output = OpenOptions::new().append(true).create(true).open(display_output)?;
+        let output = OpenOptions::new().append(true).create(true).open(&display_output)?;
         let mut writer = BufWriter::new(output);
         writeln!(
             writer,
@@ -129,226 +605,228 @@ fn compare(
             player_2.name()
         )?;
     }
+    checkpoint.save(&checkpoint_path)?;
+    let mut full_game_writer = full_games_folder
+        .map(|folder| FullGameWriter::new(folder, FullGameWriter::GAMES_PER_FILE))
+        .transpose()?;
 
-    let max_player_1_score = AtomicI32::new(i32::MIN);
-    let max_player_2_score = AtomicI32::new(i32::MIN);
-    let min_player_1_score = AtomicI32::new(i32::MAX);
-    let min_player_2_score = AtomicI32::new(i32::MAX);
-    let sum_player_1_score = AtomicI32::new(0);
-    let sum_player_2_score = AtomicI32::new(0);
-    let sum_time_player_1 = AtomicU64::new(0);
-    let sum_time_player_2 = AtomicU64::new(0);
-    let n_time_player_1 = AtomicU64::new(0);
-    let n_time_player_2 = AtomicU64::new(0);
-    let wins_player_1 = AtomicU32::new(0);
-    let wins_player_2 = AtomicU32::new(0);
-
-    print!("\n\n\n\n\n");
-
-    let mut recorded_games = vec![];
-    let iterations_done = AtomicU32::new(0);
-    std::thread::scope(|s| {
-        let mut handles = vec![];
-
-        for _ in 0..parallelization {
-            let iterations = iterations as u32;
-            let iterations_done = &iterations_done;
-            let max_player_1_score = &max_player_1_score;
-            let max_player_2_score = &max_player_2_score;
-            let min_player_1_score = &min_player_1_score;
-            let min_player_2_score = &min_player_2_score;
-            let sum_player_1_score = &sum_player_1_score;
-            let sum_player_2_score = &sum_player_2_score;
-            let sum_time_player_1 = &sum_time_player_1;
-            let sum_time_player_2 = &sum_time_player_2;
-            let turns_player_1 = &n_time_player_1;
-            let turns_player_2 = &n_time_player_2;
-            let wins_player_1 = &wins_player_1;
-            let wins_player_2 = &wins_player_2;
-            let player_1_str = player_1.get_construct_name();
-            let player_2_str = player_2.get_construct_name();
-            handles.push(s.spawn(move || {
-                let panic_result = panic::catch_unwind(move || {
-                    let mut recorded_games = vec![];
-                    let mut player_1 = get_player(player_1_str, Logging::Disabled).unwrap();
-                    let mut player_2 = get_player(player_2_str, Logging::Disabled).unwrap();
-
-                    'outer: while iterations_done.load(Ordering::Acquire) < iterations {
-                        let mut state = Patchwork::get_initial_state(None);
-                        loop {
-                            if iterations_done.load(Ordering::Acquire) >= iterations {
-                                break 'outer;
-                            }
-
-                            let start_time = std::time::Instant::now();
-                            let action = if state.is_player_1() {
-                                let action = player_1.get_action(&state).unwrap();
-                                let end =
-                                    u64::try_from(std::time::Instant::now().duration_since(start_time).as_nanos())
-                                        .unwrap();
-
-                                sum_time_player_1.fetch_add(end, Ordering::Relaxed);
-                                turns_player_1.fetch_add(1, Ordering::Relaxed);
-                                action
-                            } else {
-                                let action = player_2.get_action(&state).unwrap();
-                                let end =
-                                    u64::try_from(std::time::Instant::now().duration_since(start_time).as_nanos())
-                                        .unwrap();
-                                sum_time_player_2.fetch_add(end, Ordering::Relaxed);
-                                turns_player_2.fetch_add(1, Ordering::Relaxed);
-                                action
-                            };
-
-                            let mut next_state = state.clone();
-                            next_state.do_action(action, false).unwrap();
-                            state = next_state;
-
-                            if state.is_terminated() {
-                                let termination = state.get_termination_result();
-
-                                match termination.termination {
-                                    TerminationType::Player1Won => {
-                                        wins_player_1.fetch_add(1, Ordering::Relaxed);
-                                    }
-                                    TerminationType::Player2Won => {
-                                        wins_player_2.fetch_add(1, Ordering::Relaxed);
-                                    }
-                                }
-
-                                recorded_games.push(RecordedGame {
-                                    player_1_name: player_1.name().to_string(),
-                                    player_2_name: player_2.name().to_string(),
-                                    result: termination.termination,
-                                });
-
-                                max_player_1_score.fetch_max(termination.player_1_score, Ordering::Relaxed);
-                                max_player_2_score.fetch_max(termination.player_2_score, Ordering::Relaxed);
-                                min_player_1_score.fetch_min(termination.player_1_score, Ordering::Relaxed);
-                                min_player_2_score.fetch_min(termination.player_2_score, Ordering::Relaxed);
-                                sum_player_1_score.fetch_add(termination.player_1_score, Ordering::Relaxed);
-                                sum_player_2_score.fetch_add(termination.player_2_score, Ordering::Relaxed);
-                                iterations_done.fetch_add(1, Ordering::Release);
-                                break;
-                            }
-                        }
-                    }
+    if !plain {
+        // reserve the lines that are overwritten by the progress
+        print!("\n\n\n\n\n");
+    }
 
-                    recorded_games
-                });
-
-                match panic_result {
-                    Ok(recorded_games) => recorded_games,
-                    Err(cause) => {
-                        iterations_done.store(u32::MAX, Ordering::SeqCst);
-                        std::thread::sleep(std::time::Duration::from_secs(1)); // Wait progress printing
-
-                        println!("\n\n\n\n\n");
-
-                        println!("Panic in thread: {:?}", std::thread::current().id());
-                        cause.downcast_ref::<String>().map_or_else(
-                            || {
-                                println!("Cause: {cause:?}");
-                            },
-                            |cause| {
-                                println!("Cause: {cause}");
-                            },
-                        );
-
-                        println!("\n\n\n\n\n");
-                        vec![]
-                    }
-                }
-            }));
-        }
-        loop {
-            let iterations_done = iterations_done.load(Ordering::Relaxed) as usize;
-            if iterations_done >= iterations {
-                break;
-            }
-            print_progress(
-                &mut std::io::stdout(),
-                iterations_done,
-                iterations,
-                wins_player_1.load(Ordering::Relaxed) as usize,
-                wins_player_2.load(Ordering::Relaxed) as usize,
-                max_player_1_score.load(Ordering::Relaxed),
-                max_player_2_score.load(Ordering::Relaxed),
-                min_player_1_score.load(Ordering::Relaxed),
-                min_player_2_score.load(Ordering::Relaxed),
-                f64::from(sum_player_1_score.load(Ordering::Relaxed)),
-                f64::from(sum_player_2_score.load(Ordering::Relaxed)),
-                sum_time_player_1.load(Ordering::Relaxed) as f64,
-                sum_time_player_2.load(Ordering::Relaxed) as f64,
-                n_time_player_1.load(Ordering::Relaxed) as f64,
-                n_time_player_2.load(Ordering::Relaxed) as f64,
-                player_1.name(),
-                player_2.name(),
-            )?;
-            std::thread::sleep(update);
-        }
+    let initial_result = checkpoint.result.clone();
+    let result = run_compare(
+        iterations,
+        player_factory(player_1),
+        player_factory(player_2),
+        parallelization,
+        pin_cores,
+        throttle,
+        full_game_writer.is_some(),
+        observers,
+        &initial_result,
+        update,
+        |result, new_games| {
+            print_progress(&mut std::io::stdout(), plain, iterations, result, player_1.name(), player_2.name())?;
 
-        for handle in handles {
-            match handle.join() {
-                Ok(games) => recorded_games.extend(games),
-                Err(_) => {}
+            // the new games are written before the checkpoint so that a
+            // checkpoint always contains exactly the games written to the games file
+            write_recorded_games(&games_output, &new_games)?;
+            if let Some(full_game_writer) = &mut full_game_writer {
+                full_game_writer.write(new_games)?;
             }
-        }
+            checkpoint.result = result.clone();
+            checkpoint.save(&checkpoint_path)
+        },
+    )?;
+    if let Some(full_game_writer) = full_game_writer {
+        full_game_writer.finish()?;
+    }
+
+    let output = OpenOptions::new().append(true).create(true).open(display_output)?;
+    let mut writer = BufWriter::new(output);
+    print_progress(&mut writer, true, iterations, &result, player_1.name(), player_2.name())?;
 
-        anyhow::Result::<()>::Ok(())
-    })?;
+    Ok(result)
+}
 
-    atomic::fence(Ordering::SeqCst);
+/// The result of the challenger of a gauntlet against one of the opponents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GauntletRow {
+    /// The name of the opponent.
+    opponent: String,
+    /// The result of the games with the challenger as player 1 and the opponent as player 2.
+    result: CompareResult,
+}
 
-    print_progress(
-        &mut std::io::stdout(),
-        iterations_done.load(Ordering::Relaxed) as usize,
+/// Tests the challenger against every opponent one after another and writes
+/// the progress to stdout as well as the result per opponent to the rating
+/// folder.
+///
+/// # Returns
+///
+/// The result against every opponent (see [`run_gauntlet`]).
+#[allow(clippy::too_many_arguments)]
+fn gauntlet(
+    iterations: usize,
+    challenger: &PlayerType,
+    opponents: &[PlayerType],
+    update: std::time::Duration,
+    parallelization: usize,
+    pin_cores: bool,
+    throttle: Option<f64>,
+    observers: &Mutex<Vec<ObserverType>>,
+    rating_folder: &Path,
+    plain: bool,
+) -> anyhow::Result<Vec<GauntletRow>> {
+    println!(
+        "Gauntlet of {} against {} opponents with {} iterations each and {} threads",
+        challenger.name(),
+        opponents.len(),
         iterations,
-        wins_player_1.load(Ordering::Relaxed) as usize,
-        wins_player_2.load(Ordering::Relaxed) as usize,
-        max_player_1_score.load(Ordering::Relaxed),
-        max_player_2_score.load(Ordering::Relaxed),
-        min_player_1_score.load(Ordering::Relaxed),
-        min_player_2_score.load(Ordering::Relaxed),
-        f64::from(sum_player_1_score.load(Ordering::Relaxed)),
-        f64::from(sum_player_2_score.load(Ordering::Relaxed)),
-        sum_time_player_1.load(Ordering::Relaxed) as f64,
-        sum_time_player_2.load(Ordering::Relaxed) as f64,
-        n_time_player_1.load(Ordering::Relaxed) as f64,
-        n_time_player_2.load(Ordering::Relaxed) as f64,
-        player_1.name(),
-        player_2.name(),
+        parallelization
+    );
+
+    let rows = run_gauntlet(
+        iterations,
+        challenger,
+        opponents,
+        parallelization,
+        pin_cores,
+        throttle,
+        observers,
+        update,
+        |opponent, result| {
+            print_progress(
+                &mut std::io::stdout(),
+                plain,
+                iterations,
+                result,
+                challenger.name(),
+                opponent.name(),
+            )
+        },
+        |opponent| {
+            println!("Opponent {}", opponent.name());
+            if !plain {
+                // reserve the lines that are overwritten by the progress
+                print!("\n\n\n\n\n");
+            }
+            Ok(())
+        },
     )?;
 
-    let rating_folder = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().join("analysis").join("player-rating");
-    let display_output = rating_folder.join("output.txt");
-    let games_output = rating_folder.join("games.txt");
+    print_gauntlet(&mut std::io::stdout(), challenger.name(), &rows)?;
+    let output = OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(rating_folder.join("output.txt"))?;
+    print_gauntlet(&mut BufWriter::new(output), challenger.name(), &rows)?;
 
-    let output = OpenOptions::new().append(true).create(true).open(display_output)?;
-    let mut writer = BufWriter::new(output);
-    print_progress(
-        &mut writer,
-        iterations_done.load(Ordering::Relaxed) as usize,
-        iterations,
-        wins_player_1.load(Ordering::Relaxed) as usize,
-        wins_player_2.load(Ordering::Relaxed) as usize,
-        max_player_1_score.load(Ordering::Relaxed),
-        max_player_2_score.load(Ordering::Relaxed),
-        min_player_1_score.load(Ordering::Relaxed),
-        min_player_2_score.load(Ordering::Relaxed),
-        f64::from(sum_player_1_score.load(Ordering::Relaxed)),
-        f64::from(sum_player_2_score.load(Ordering::Relaxed)),
-        sum_time_player_1.load(Ordering::Relaxed) as f64,
-        sum_time_player_2.load(Ordering::Relaxed) as f64,
-        n_time_player_1.load(Ordering::Relaxed) as f64,
-        n_time_player_2.load(Ordering::Relaxed) as f64,
-        player_1.name(),
-        player_2.name(),
+    Ok(rows)
+}
+
+/// Plays the given amount of games of the challenger (as player 1) against
+/// every opponent (as player 2) one after another. Every pairing is played
+/// like a comparison (see [`run_compare`]).
+///
+/// # Arguments
+///
+/// * `iterations` - The amount of games against every opponent.
+/// * `challenger` - The player that is tested.
+/// * `opponents` - The panel of opponents the challenger plays against.
+/// * `on_update` - Called regularly with the opponent and the current result against it.
+/// * `on_opponent` - Called with the opponent before the games against it start.
+///
+/// The other arguments are passed on to [`run_compare`].
+///
+/// # Returns
+///
+/// The result against every opponent in the order of the opponents.
+///
+/// # Errors
+///
+/// If a callback returns an error or a worker panicked.
+#[allow(clippy::too_many_arguments)]
+fn run_gauntlet(
+    iterations: usize,
+    challenger: &PlayerType,
+    opponents: &[PlayerType],
+    parallelization: usize,
+    pin_cores: bool,
+    throttle: Option<f64>,
+    observers: &Mutex<Vec<ObserverType>>,
+    update: std::time::Duration,
+    mut on_update: impl FnMut(&PlayerType, &CompareResult) -> anyhow::Result<()>,
+    mut on_opponent: impl FnMut(&PlayerType) -> anyhow::Result<()>,
+) -> anyhow::Result<Vec<GauntletRow>> {
+    let mut rows = Vec::with_capacity(opponents.len());
+    for opponent in opponents {
+        on_opponent(opponent)?;
+        let result = run_compare(
+            iterations,
+            player_factory(challenger),
+            player_factory(opponent),
+            parallelization,
+            pin_cores,
+            throttle,
+            false,
+            observers,
+            &CompareResult::new(),
+            update,
+            |result, _| on_update(opponent, result),
+        )?;
+        rows.push(GauntletRow {
+            opponent: opponent.name().to_string(),
+            result,
+        });
+    }
+    Ok(rows)
+}
+
+/// Writes a table with one row per opponent of a gauntlet.
+fn print_gauntlet(output: &mut impl Write, challenger_name: &str, rows: &[GauntletRow]) -> anyhow::Result<()> {
+    writeln!(output, "Gauntlet of {challenger_name}:")?;
+    writeln!(
+        output,
+        "{: <30} {: >7} {: >7} {: >7} {: >8} {: >10} {: >10}",
+        "Opponent", "Games", "Wins", "Losses", "Win rate", "Avg score", "Avg opp."
     )?;
+    for row in rows {
+        let result = &row.result;
+        let games = f64::from(result.games().max(1));
+        writeln!(
+            output,
+            "{: <30} {: >7} {: >7} {: >7} {: >7.2}% {: >10.2} {: >10.2}",
+            row.opponent.chars().take(30).collect::<String>(),
+            result.games(),
+            result.wins_player_1,
+            result.wins_player_2,
+            f64::from(result.wins_player_1) / games * 100.0,
+            f64::from(result.sum_player_1_score) / games,
+            f64::from(result.sum_player_2_score) / games
+        )?;
+    }
+    output.flush()?;
+    Ok(())
+}
 
-    let output = OpenOptions::new().append(true).create(true).open(games_output)?;
+/// Gets a factory that creates a new instance of the player for every worker
+/// of [`run_compare`].
+pub fn player_factory(player: &PlayerType) -> impl Fn() -> anyhow::Result<Box<dyn Player>> + Sync + '_ {
+    let name = player.get_construct_name();
+    move || {
+        get_player(name, Logging::Disabled)
+            .map(|player| Box::new(player) as Box<dyn Player>)
+            .map_err(|error| Error::msg(error.reason))
+    }
+}
+
+fn write_recorded_games(path: &PathBuf, games: &[RecordedGame]) -> anyhow::Result<()> {
+    let output = OpenOptions::new().append(true).create(true).open(path)?;
     let mut writer = BufWriter::new(output);
-    for game in recorded_games {
+    for game in games {
         // Write Portable Game Notation (PGN)
         writeln!(
             writer,
@@ -361,57 +839,69 @@ fn compare(
             }
         )?;
     }
+    writer.flush()?;
 
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
 fn print_progress(
     output: &mut impl Write,
-    iteration: usize,
+    plain: bool,
     iterations: usize,
-    wins_player_1: usize,
-    wins_player_2: usize,
-    max_player_1_score: i32,
-    max_player_2_score: i32,
-    min_player_1_score: i32,
-    min_player_2_score: i32,
-    avg_player_1_score: f64,
-    avg_player_2_score: f64,
-    sum_time_player_1: f64,
-    sum_time_player_2: f64,
-    turns_player_1: f64,
-    turns_player_2: f64,
+    result: &CompareResult,
     player_1_name: &str,
     player_2_name: &str,
 ) -> anyhow::Result<()> {
-    let avg_player_1_score = avg_player_1_score / iteration as f64;
-    let avg_player_2_score = avg_player_2_score / iteration as f64;
+    let iteration = result.games();
+    let score_ties = result.score_ties;
+    let wins_player_1 = result.wins_player_1;
+    let wins_player_2 = result.wins_player_2;
+    let max_player_1_score = result.max_player_1_score;
+    let max_player_2_score = result.max_player_2_score;
+    let min_player_1_score = result.min_player_1_score;
+    let min_player_2_score = result.min_player_2_score;
+    let turns_player_1 = result.turns_player_1;
+    let turns_player_2 = result.turns_player_2;
+
+    let avg_player_1_score = f64::from(result.sum_player_1_score) / f64::from(iteration);
+    let avg_player_2_score = f64::from(result.sum_player_2_score) / f64::from(iteration);
 
-    let avg_player_1_time = sum_time_player_1 / turns_player_1;
-    let avg_player_2_time = sum_time_player_2 / turns_player_2;
+    let avg_player_1_time = result.sum_time_player_1 as f64 / turns_player_1 as f64;
+    let avg_player_2_time = result.sum_time_player_2 as f64 / turns_player_2 as f64;
 
-    write!(output, "\x1b[4A\r")?;
-    writeln!(output, "Iteration {iteration: >7} / {iterations}")?;
+    if !plain {
+        // move the cursor up to overwrite the previous progress
+        write!(output, "\x1b[4A\r")?;
+    }
+    writeln!(
+        output,
+        "Iteration {iteration: >7} / {iterations} [score ties resolved by goal order: {score_ties}, avg plies: {:.2}, avg branching factor: {:.2}]",
+        result.average_plies(),
+        result.average_branching_factor()
+    )?;
     writeln!(output,
-        "Player 1: {: >7} wins  ({:0>5.2}%) [avg score: {: >6.02}, max score: {: >3}, min score: {: >3}, avg time: {: >9.3?}, turns: {}]                       ",
+        "Player 1: {: >7} wins  ({:0>5.2}%) [avg score: {: >6.02}, max score: {: >3}, min score: {: >3}, avg time: {: >9.3?}, turns: {}, distinct first moves: {}, first move entropy: {:.2} bits]                       ",
         wins_player_1,
-        (wins_player_1 as f64 / iteration as f64 * 100.0),
+        (f64::from(wins_player_1) / f64::from(iteration) * 100.0),
         avg_player_1_score,
         if max_player_1_score == i32::MIN { 0 } else { max_player_1_score },
         if min_player_1_score == i32::MAX { 0 } else { min_player_1_score },
         std::time::Duration::from_nanos(avg_player_1_time.round() as u64),
-        turns_player_1
+        turns_player_1,
+        result.first_moves_player_1.len(),
+        move_entropy(&result.first_moves_player_1)
     )?;
     writeln!(output,
-        "Player 2: {: >7} wins  ({:0>5.2}%) [avg score: {: >6.02}, max score: {: >3}, min score: {: >3}, avg time: {: >9.3?}, turns: {}]                       ",
+        "Player 2: {: >7} wins  ({:0>5.2}%) [avg score: {: >6.02}, max score: {: >3}, min score: {: >3}, avg time: {: >9.3?}, turns: {}, distinct first moves: {}, first move entropy: {:.2} bits]                       ",
         wins_player_2,
-        (wins_player_2 as f64 / iteration as f64 * 100.0),
+        (f64::from(wins_player_2) / f64::from(iteration) * 100.0),
         avg_player_2_score,
         if max_player_2_score == i32::MIN { 0 } else { max_player_2_score },
         if min_player_2_score == i32::MAX { 0 } else { min_player_2_score },
         std::time::Duration::from_nanos(avg_player_2_time.round() as u64),
-        turns_player_2
+        turns_player_2,
+        result.first_moves_player_2.len(),
+        move_entropy(&result.first_moves_player_2)
     )?;
     let progress_bar_length = 100;
 
@@ -420,21 +910,323 @@ fn print_progress(
             output,
             "{} {} {}  ",
             &player_1_name.chars().take(30).collect::<String>(),
-            "█".repeat(progress_bar_length),
+            (if plain { "-" } else { "█" }).repeat(progress_bar_length),
             &player_2_name.chars().take(30).collect::<String>(),
         )?;
     } else {
-        let progress_player_1 = (wins_player_1 as f64 / iteration as f64 * progress_bar_length as f64).round() as usize;
+        let progress_player_1 =
+            (f64::from(wins_player_1) / f64::from(iteration) * progress_bar_length as f64).round() as usize;
         let progress_player_2 = (progress_bar_length as i32 - progress_player_1 as i32).max(0) as usize;
-        writeln!(
-            output,
-            "{} \x1b[0;32m{}\x1b[0;31m{}\x1b[0m {}  ",
-            &player_1_name.chars().take(30).collect::<String>(),
-            "█".repeat(progress_player_1),
-            "█".repeat(progress_player_2),
-            &player_2_name.chars().take(30).collect::<String>(),
-        )?;
+        if plain {
+            writeln!(
+                output,
+                "{} {}{} {}",
+                &player_1_name.chars().take(30).collect::<String>(),
+                "#".repeat(progress_player_1),
+                "-".repeat(progress_player_2),
+                &player_2_name.chars().take(30).collect::<String>(),
+            )?;
+        } else {
+            writeln!(
+                output,
+                "{} \x1b[0;32m{}\x1b[0;31m{}\x1b[0m {}  ",
+                &player_1_name.chars().take(30).collect::<String>(),
+                "█".repeat(progress_player_1),
+                "█".repeat(progress_player_2),
+                &player_2_name.chars().take(30).collect::<String>(),
+            )?;
+        }
     }
     output.flush()?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load_full_games(directory: &Path) -> Vec<GameRecord> {
+        let mut games = vec![];
+        for path in get_full_game_files(directory).unwrap() {
+            let reader = std::io::BufReader::new(std::fs::File::open(path).unwrap());
+            games.extend(bincode::deserialize_from::<_, Vec<GameRecord>>(reader).unwrap());
+        }
+        games
+    }
+
+    #[test]
+    fn test_plain_progress_contains_no_escape_sequences() {
+        let finished = CompareResult {
+            wins_player_1: 2,
+            wins_player_2: 1,
+            max_player_1_score: 10,
+            min_player_1_score: -20,
+            sum_player_1_score: 5,
+            sum_time_player_1: 1_000_000,
+            turns_player_1: 60,
+            plies: 120,
+            legal_moves: 2400,
+            ..CompareResult::new()
+        };
+
+        for result in [CompareResult::new(), finished] {
+            let mut plain = vec![];
+            print_progress(&mut plain, true, 10, &result, "Player 1", "Player 2").unwrap();
+            let plain = String::from_utf8(plain).unwrap();
+            assert!(!plain.contains('\x1b'), "Plain output contains escape sequences:\n{plain}");
+            assert_eq!(4, plain.lines().count());
+
+            let mut tty = vec![];
+            print_progress(&mut tty, false, 10, &result, "Player 1", "Player 2").unwrap();
+            assert!(String::from_utf8(tty).unwrap().contains("\x1b[4A"));
+        }
+    }
+
+    #[test]
+    fn test_gauntlet_reports_a_row_per_opponent() {
+        const GAMES: usize = 2;
+
+        let challenger = get_player("greedy", Logging::Disabled).unwrap();
+        let opponents = [
+            get_player("random", Logging::Disabled).unwrap(),
+            get_player("greedy", Logging::Disabled).unwrap(),
+        ];
+        let mut started_opponents = vec![];
+
+        let rows = run_gauntlet(
+            GAMES,
+            &challenger,
+            &opponents,
+            1,
+            false,
+            None,
+            &Mutex::new(vec![]),
+            std::time::Duration::from_millis(1),
+            |_, _| Ok(()),
+            |opponent| {
+                started_opponents.push(opponent.name().to_string());
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        let opponent_names = opponents
+            .iter()
+            .map(|opponent| opponent.name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(opponent_names, started_opponents);
+        assert_eq!(
+            opponent_names,
+            rows.iter().map(|row| row.opponent.clone()).collect::<Vec<_>>()
+        );
+        for row in &rows {
+            assert_eq!(GAMES as u32, row.result.games());
+        }
+
+        let mut output = vec![];
+        print_gauntlet(&mut output, challenger.name(), &rows).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        // the title, the header and one row per opponent
+        assert_eq!(2 + opponents.len(), output.lines().count());
+        for (line, opponent) in output.lines().skip(2).zip(&opponent_names) {
+            // the names of the opponents are truncated to 30 characters
+            let opponent = opponent.chars().take(30).collect::<String>();
+            assert!(line.starts_with(&opponent), "Unexpected row {line}");
+        }
+    }
+
+    #[test]
+    fn test_progress_contains_first_move_entropy() {
+        let frequencies = |counts: &[u64]| {
+            counts
+                .iter()
+                .enumerate()
+                .map(|(index, count)| (format!("W{index}"), *count))
+                .collect::<BTreeMap<_, _>>()
+        };
+
+        let result = CompareResult {
+            wins_player_1: 8,
+            first_moves_player_1: frequencies(&[4, 2, 2]),
+            first_moves_player_2: frequencies(&[8]),
+            ..CompareResult::new()
+        };
+        let mut output = vec![];
+        print_progress(&mut output, true, 8, &result, "Player 1", "Player 2").unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("distinct first moves: 3, first move entropy: 1.50 bits"));
+        assert!(output.contains("distinct first moves: 1, first move entropy: 0.00 bits"));
+    }
+
+    #[test]
+    fn test_json_summary_round_trips() {
+        let result = CompareResult {
+            wins_player_1: 3,
+            wins_player_2: 1,
+            score_ties: 1,
+            max_player_1_score: 20,
+            max_player_2_score: 5,
+            min_player_1_score: 10,
+            min_player_2_score: -30,
+            sum_player_1_score: 60,
+            sum_player_2_score: -40,
+            sum_time_player_1: 1000,
+            sum_time_player_2: 3000,
+            turns_player_1: 80,
+            turns_player_2: 80,
+            plies: 160,
+            legal_moves: 1600,
+            first_moves_player_1: BTreeMap::from([("W".to_string(), 4)]),
+            first_moves_player_2: BTreeMap::from([("W".to_string(), 2), ("P0".to_string(), 2)]),
+            ..CompareResult::new()
+        };
+        let summary = CompareSummary::new(&result, "Player 1", "Player 2");
+
+        let directory = std::env::temp_dir().join(format!("patchwork-json-summary-{}", std::process::id()));
+        std::fs::create_dir_all(&directory).unwrap();
+        let path = directory.join("summary.json");
+        write_json_summaries(&path, std::slice::from_ref(&summary)).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(summary, serde_json::from_str::<CompareSummary>(&json).unwrap());
+
+        let value = serde_json::from_str::<serde_json::Value>(&json).unwrap();
+        for field in [
+            "games",
+            "score_ties",
+            "average_plies",
+            "average_branching_factor",
+            "elo_difference",
+        ] {
+            assert!(value.get(field).is_some(), "Missing field {field}");
+        }
+        for field in [
+            "name",
+            "wins",
+            "win_rate",
+            "average_score",
+            "max_score",
+            "min_score",
+            "sum_time_ns",
+            "average_time_ns",
+            "turns",
+            "first_moves",
+            "first_move_entropy",
+        ] {
+            assert!(value["player_1"].get(field).is_some(), "Missing field player_1.{field}");
+            assert!(value["player_2"].get(field).is_some(), "Missing field player_2.{field}");
+        }
+
+        assert_eq!(4, summary.games);
+        assert!((summary.player_1.win_rate - 0.75).abs() < f64::EPSILON);
+        assert!((summary.player_2.average_score + 10.0).abs() < f64::EPSILON);
+        assert!((summary.player_2.first_move_entropy - 1.0).abs() < f64::EPSILON);
+        // a win rate of 75% is an Elo difference of about 191
+        assert!((summary.elo_difference.unwrap() - 190.85).abs() < 0.01);
+        assert_eq!(
+            None,
+            CompareSummary::new(&CompareResult::new(), "1", "2").player_1.max_score
+        );
+    }
+
+    #[test]
+    fn test_resumed_compare_reaches_target_exactly_once() {
+        const STOPPED_AFTER: usize = 3;
+        const TARGET: usize = 8;
+
+        let rating_folder = std::env::temp_dir().join(format!("patchwork-compare-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&rating_folder).unwrap();
+
+        let player_1 = get_player("random", Logging::Disabled).unwrap();
+        let player_2 = get_player("random", Logging::Disabled).unwrap();
+        let update = std::time::Duration::from_millis(1);
+        let observers = Mutex::new(vec![]);
+        let checkpoint_path = rating_folder.join(CompareCheckpoint::FILE_NAME);
+
+        compare(
+            STOPPED_AFTER,
+            &player_1,
+            &player_2,
+            update,
+            2,
+            false,
+            None,
+            &observers,
+            &rating_folder,
+            None,
+            None,
+            true,
+        )
+        .unwrap();
+        let checkpoint = CompareCheckpoint::load(&checkpoint_path).unwrap();
+        assert_eq!(STOPPED_AFTER as u32, checkpoint.result.games());
+
+        let result = compare(
+            TARGET,
+            &player_1,
+            &player_2,
+            update,
+            2,
+            false,
+            None,
+            &observers,
+            &rating_folder,
+            None,
+            Some(checkpoint),
+            true,
+        )
+        .unwrap();
+        let checkpoint = CompareCheckpoint::load(&checkpoint_path).unwrap();
+        let games = std::fs::read_to_string(rating_folder.join("games.txt")).unwrap();
+        std::fs::remove_dir_all(&rating_folder).unwrap();
+
+        assert_eq!(TARGET as u32, checkpoint.result.games());
+        assert_eq!(TARGET, games.matches("Game:").count());
+        assert!(checkpoint.result.plies >= TARGET as u64);
+        assert_eq!(checkpoint.result, result);
+    }
+
+    #[test]
+    fn test_full_saved_games_match_results() {
+        const GAMES: usize = 5;
+
+        let directory = std::env::temp_dir().join(format!("patchwork-compare-full-{}", std::process::id()));
+        let player_1 = get_player("random", Logging::Disabled).unwrap();
+        let player_2 = get_player("random", Logging::Disabled).unwrap();
+        let observers = Mutex::new(vec![]);
+
+        let mut writer = FullGameWriter::new(&directory, 2).unwrap();
+        let mut results = vec![];
+        run_compare(
+            GAMES,
+            player_factory(&player_1),
+            player_factory(&player_2),
+            2,
+            false,
+            None,
+            true,
+            &observers,
+            &CompareResult::new(),
+            std::time::Duration::from_millis(1),
+            |_, new_games| {
+                results.extend(new_games.iter().map(|game| game.result));
+                writer.write(new_games)
+            },
+        )
+        .unwrap();
+        writer.finish().unwrap();
+
+        let files = get_full_game_files(&directory).unwrap().len();
+        let games = load_full_games(&directory);
+        std::fs::remove_dir_all(&directory).unwrap();
+
+        assert_eq!(GAMES.div_ceil(2), files);
+        assert_eq!(GAMES, games.len());
+        for (game, result) in games.iter().zip(results) {
+            let final_state = game.replay().unwrap();
+            assert!(final_state.is_terminated());
+            assert_eq!(result, final_state.get_termination_result().termination);
+        }
+    }
+}
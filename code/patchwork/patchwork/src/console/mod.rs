@@ -1,8 +1,17 @@
 use clap::Parser;
 use rustyline::{history::FileHistory, Editor};
 
-use crate::common::{interactive_get_logging, interactive_get_player, PlayerType};
-use patchwork_lib::{player::Player, GameOptions, Notation, Patchwork, TerminationType};
+use crate::common::{
+    get_csv_observer, get_evaluation_curve_observer, get_evaluator, interactive_get_configured_player,
+    interactive_get_logging, notify_game_end, notify_move, notify_undo, ObserverType, PlayerConfig, PlayerType,
+    CTRL_C_MESSAGE, CTRL_D_MESSAGE,
+};
+use patchwork_lib::{
+    evaluator::Evaluator,
+    player::{Player, SearchDiagnostics, UndoRequest},
+    ActionId, GameOptions, GameRecord, GameReplay, Notation, Patchwork, TerminationType,
+};
+use rustyline::error::ReadlineError;
 
 #[derive(Debug, Parser, Default)]
 #[command(no_binary_name(true))]
@@ -15,24 +24,108 @@ struct CmdArgs {
     logging_player_1: Option<String>,
     #[arg(long = "logging-2", alias = "l2")]
     logging_player_2: Option<String>,
+    #[arg(long = "handicap-1", alias = "h1")]
+    handicap_player_1: Option<usize>,
+    #[arg(long = "handicap-2", alias = "h2")]
+    handicap_player_2: Option<usize>,
+    #[arg(long = "config", short = 'c')]
+    config: Option<String>,
     #[arg(long = "seed", short = 's')]
     seed: Option<u64>,
+    #[arg(long = "replay", short = 'r')]
+    replay: Option<String>,
+    #[arg(long = "eval", short = 'e', default_value = "static")]
+    evaluator: String,
+    #[arg(long = "csv")]
+    csv: Option<String>,
+    #[arg(long = "eval-curve")]
+    eval_curve: Option<String>,
+    #[arg(long = "save")]
+    save: Option<String>,
 }
 
 pub fn handle_console(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> anyhow::Result<()> {
     let args = CmdArgs::parse_from(args);
 
+    if let Some(replay) = args.replay {
+        return handle_console_replay(rl, &replay, &args.evaluator);
+    }
+
+    let config = args.config.map(PlayerConfig::load).transpose()?.unwrap_or_default();
+
     let player_1_logging = interactive_get_logging(rl, 1, args.logging_player_1)?;
     let player_2_logging = interactive_get_logging(rl, 2, args.logging_player_2)?;
 
-    let player_1 = interactive_get_player(rl, args.player_1, 1, player_1_logging)?;
-    let player_2 = interactive_get_player(rl, args.player_2, 2, player_2_logging)?;
+    let player_1 = interactive_get_configured_player(
+        rl,
+        args.player_1,
+        config.player_1.as_ref(),
+        args.handicap_player_1,
+        1,
+        player_1_logging,
+    )?;
+    let player_2 = interactive_get_configured_player(
+        rl,
+        args.player_2,
+        config.player_2.as_ref(),
+        args.handicap_player_2,
+        2,
+        player_2_logging,
+    )?;
+
+    let mut observers = vec![];
+    if let Some(csv) = args.csv {
+        observers.push(get_csv_observer(&csv)?);
+    }
+    if let Some(eval_curve) = args.eval_curve {
+        observers.push(get_evaluation_curve_observer(&eval_curve, &args.evaluator)?);
+    }
+
+    let player_1_name = player_1.name().to_string();
+    let player_2_name = player_2.name().to_string();
+    let record = handle_console_repl(player_1, player_2, args.seed, &mut observers)?;
+
+    if let Some(save) = args.save {
+        save_game(&save, &record, [&player_1_name, &player_2_name], args.seed)?;
+        println!("Saved the game to {save}");
+    }
+    Ok(())
+}
+
+/// Writes the finished game to the given file. The names of the players, the
+/// seed and the result are written as comments in front of the record so that
+/// the file can be loaded again as a [`GameRecord`].
+fn save_game(path: &str, record: &GameRecord, player_names: [&str; 2], seed: Option<u64>) -> anyhow::Result<()> {
+    let termination = record.replay()?.get_termination_result();
+    let winner = match termination.termination {
+        TerminationType::Player1Won => 1,
+        TerminationType::Player2Won => 2,
+    };
+
+    let mut lines = vec![
+        format!("# Player 1: {}", player_names[0]),
+        format!("# Player 2: {}", player_names[1]),
+        format!("# Seed: {}", seed.map_or_else(|| "-".to_string(), |seed| seed.to_string())),
+        format!(
+            "# Result: Player {winner} won ({} : {})",
+            termination.player_1_score, termination.player_2_score
+        ),
+    ];
+    lines.push(record.save_to_notation()?);
 
-    handle_console_repl(player_1, player_2, args.seed)
+    std::fs::write(path, lines.join("\n") + "\n")?;
+    Ok(())
 }
 
-fn handle_console_repl(mut player_1: PlayerType, mut player_2: PlayerType, seed: Option<u64>) -> anyhow::Result<()> {
-    let mut state = Patchwork::get_initial_state(seed.map(|seed| GameOptions { seed }));
+fn handle_console_repl(
+    mut player_1: PlayerType,
+    mut player_2: PlayerType,
+    seed: Option<u64>,
+    observers: &mut [ObserverType],
+) -> anyhow::Result<GameRecord> {
+    let mut state = Patchwork::get_initial_state(seed.map(GameOptions::new));
+    let mut record = GameRecord::new(state.clone());
+    let mut history = vec![];
 
     let mut i = 1;
     loop {
@@ -44,14 +137,33 @@ fn handle_console_repl(mut player_1: PlayerType, mut player_2: PlayerType, seed:
 
         let start_time = std::time::Instant::now();
         let action = if state.is_player_1() {
-            player_1.get_action(&state)?
+            player_1.get_action_with_diagnostics(&state)
         } else {
-            player_2.get_action(&state)?
+            player_2.get_action_with_diagnostics(&state)
+        };
+        let (action, diagnostics) = match action {
+            Ok(action) => action,
+            Err(error) if error.is::<UndoRequest>() => {
+                // restore the state before the last action of the requesting player
+                let is_player_1 = state.is_player_1();
+                let Some(index) = history.iter().rposition(|old: &Patchwork| old.is_player_1() == is_player_1) else {
+                    println!("Nothing to undo");
+                    continue;
+                };
+                let undone_moves = history.len() - index;
+                i = index + 1;
+                history.truncate(index + 1);
+                record.actions.truncate(index);
+                state = history.pop().unwrap();
+                notify_undo(observers, &state, undone_moves)?;
+                continue;
+            }
+            Err(error) => return Err(error),
         };
         let end_time = std::time::Instant::now();
 
         #[cfg(debug_assertions)]
-        if old_state != state {
+        if !old_state.equals_ignoring_diagnostics(&state) {
             println!("─────────────────────────────────────────────────── ERROR ───────────────────────────────────────────────────");
             println!("Old state:");
             println!("{old_state}");
@@ -60,23 +172,25 @@ fn handle_console_repl(mut player_1: PlayerType, mut player_2: PlayerType, seed:
             panic!("State changed!");
         }
 
+        let player_name = if state.is_player_1() {
+            player_1.name()
+        } else {
+            player_2.name()
+        };
         println!(
-            "Player '{}' chose action: {} ({}) after {:?}",
-            if state.is_player_1() {
-                player_1.name()
-            } else {
-                player_2.name()
-            },
-            action,
-            action.save_to_notation().unwrap_or_else(|_| "######".to_string()),
-            end_time - start_time
+            "{}",
+            format_move(player_name, &state, action, end_time - start_time, &diagnostics)
         );
 
+        notify_move(observers, &state, action, &diagnostics)?;
+
         let mut next_state = state.clone();
         next_state.do_action(action, false)?;
-        state = next_state;
+        history.push(std::mem::replace(&mut state, next_state));
+        record.push(action);
 
         if state.is_terminated() {
+            notify_game_end(observers, &state)?;
             let termination = state.get_termination_result();
 
             println!("────────────────────────────────────────────────── RESULT ────────────────────────────────────────────────────");
@@ -95,5 +209,315 @@ fn handle_console_repl(mut player_1: PlayerType, mut player_2: PlayerType, seed:
         i += 1;
     }
 
-    Ok(())
+    Ok(record)
+}
+
+/// Formats the action a player chose together with the time it took and the
+/// evaluation of the action and its margin, i.e. how much better it was
+/// evaluated than the best alternative. A forced action has no alternative and
+/// therefore a margin of zero.
+///
+/// # Arguments
+///
+/// * `player_name` - The name of the player that chose the action.
+/// * `state` - The state the action was chosen in.
+/// * `action` - The chosen action.
+/// * `time` - The time it took to choose the action.
+/// * `diagnostics` - The diagnostics of the search of the action.
+///
+/// # Returns
+///
+/// The line describing the move with `-` for values the player did not report.
+fn format_move(
+    player_name: &str,
+    state: &Patchwork,
+    action: ActionId,
+    time: std::time::Duration,
+    diagnostics: &SearchDiagnostics,
+) -> String {
+    let format_value = |value: Option<i32>| value.map_or_else(|| "-".to_string(), |value| value.to_string());
+    let is_forced = state.get_valid_actions().len() == 1;
+    let margin = if is_forced { Some(0) } else { diagnostics.margin() };
+
+    format!(
+        "Player '{player_name}' chose action: {action} ({}) after {time:?} (evaluation: {}, margin: {})",
+        action.save_to_notation().unwrap_or_else(|_| "######".to_string()),
+        format_value(diagnostics.evaluation()),
+        format_value(margin)
+    )
+}
+
+fn handle_console_replay(rl: &mut Editor<(), FileHistory>, file: &str, evaluator: &str) -> anyhow::Result<()> {
+    let evaluator = get_evaluator(evaluator)?;
+
+    let record = GameRecord::load_from_notation(&std::fs::read_to_string(file)?)?;
+    let mut replay = GameReplay::new(record);
+
+    print_replay_state(&replay, evaluator.as_ref());
+    loop {
+        let line = match rl.readline("replay> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                println!("{CTRL_C_MESSAGE}");
+                return Ok(());
+            }
+            Err(ReadlineError::Eof) => {
+                println!("{CTRL_D_MESSAGE}");
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        match line.trim() {
+            "" | "n" | "next" => {
+                if !replay.step_forward()? {
+                    println!("Already at the end of the game.");
+                    continue;
+                }
+            }
+            "p" | "prev" | "previous" => {
+                if !replay.step_backward()? {
+                    println!("Already at the start of the game.");
+                    continue;
+                }
+            }
+            "f" | "first" => replay.go_to(0)?,
+            "l" | "last" => replay.go_to(replay.record().len())?,
+            "q" | "quit" | "exit" => return Ok(()),
+            "h" | "help" => {
+                println!("Replay commands:");
+                println!("    n, next        Step one ply forward (default)");
+                println!("    p, prev        Step one ply backward");
+                println!("    f, first       Go to the initial state");
+                println!("    l, last        Go to the final state");
+                println!("    <ply>          Go to the given ply");
+                println!("    q, quit        Exit the replay");
+                continue;
+            }
+            other => {
+                let Ok(ply) = other.parse::<usize>() else {
+                    println!("Unknown replay command \"{other}\". Type \"help\" for more information.");
+                    continue;
+                };
+                replay.go_to(ply)?;
+            }
+        }
+
+        print_replay_state(&replay, evaluator.as_ref());
+    }
+}
+
+fn print_replay_state(replay: &GameReplay, evaluator: &dyn Evaluator) {
+    let state = replay.state();
+
+    println!(
+        "──────────────────────────────────────────────── PLY {} / {} ────────────────────────────────────────────────",
+        replay.ply(),
+        replay.record().len()
+    );
+    println!("{state}");
+
+    if let Some(action) = replay.previous_action() {
+        println!(
+            "Previous action: {} ({})",
+            action,
+            action.save_to_notation().unwrap_or_else(|_| "######".to_string())
+        );
+    }
+    println!("Evaluation (player 1 perspective): {}", evaluator.evaluate_node(state));
+
+    if state.is_terminated() {
+        let termination = state.get_termination_result();
+        match termination.termination {
+            TerminationType::Player1Won => println!("Player 1 won!"),
+            TerminationType::Player2Won => println!("Player 2 won!"),
+        }
+        println!("{}", termination.player_1_score);
+        println!("{}", termination.player_2_score);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use patchwork_lib::{
+        player::{GreedyPlayer, RandomOptions, RandomPlayer},
+        GameObserver,
+    };
+
+    use super::*;
+
+    #[derive(Default)]
+    struct CountingObserver {
+        actions: Arc<Mutex<Vec<ActionId>>>,
+        evaluations: Arc<Mutex<Vec<Option<i32>>>>,
+        undone_moves: Arc<Mutex<Vec<usize>>>,
+        games: Arc<Mutex<usize>>,
+    }
+
+    impl GameObserver for CountingObserver {
+        fn on_move(
+            &mut self,
+            _: &Patchwork,
+            action: ActionId,
+            evaluation: Option<i32>,
+            _: &[ActionId],
+        ) -> anyhow::Result<()> {
+            self.actions.lock().unwrap().push(action);
+            self.evaluations.lock().unwrap().push(evaluation);
+            Ok(())
+        }
+
+        fn on_undo(&mut self, _: &Patchwork, undone_moves: usize) -> anyhow::Result<()> {
+            let remaining = self.actions.lock().unwrap().len() - undone_moves;
+            self.actions.lock().unwrap().truncate(remaining);
+            self.evaluations.lock().unwrap().truncate(remaining);
+            self.undone_moves.lock().unwrap().push(undone_moves);
+            Ok(())
+        }
+
+        fn on_game_end(&mut self, _: &Patchwork) -> anyhow::Result<()> {
+            *self.games.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_observer_called_once_per_move() {
+        let observer = CountingObserver::default();
+        let actions = Arc::clone(&observer.actions);
+        let evaluations = Arc::clone(&observer.evaluations);
+        let games = Arc::clone(&observer.games);
+        let mut observers: Vec<ObserverType> = vec![Box::new(observer)];
+
+        let player_1 = PlayerType::BuildIn(Box::new(<GreedyPlayer>::default()), "greedy".to_string());
+        let player_2 = PlayerType::BuildIn(
+            Box::new(RandomPlayer::new("Random Player 2", Some(RandomOptions::new(2)))),
+            "random".to_string(),
+        );
+
+        let final_state = handle_console_repl(player_1, player_2, Some(42), &mut observers)
+            .unwrap()
+            .replay()
+            .unwrap();
+
+        // replaying exactly the observed actions has to result in the final state
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for action in actions.lock().unwrap().iter() {
+            assert!(!state.is_terminated());
+            state.do_action(*action, false).unwrap();
+        }
+
+        assert_eq!(final_state, state);
+        assert_eq!(1, *games.lock().unwrap());
+
+        // the evaluations reported by the greedy player are passed on
+        let evaluations = evaluations.lock().unwrap();
+        assert_eq!(actions.lock().unwrap().len(), evaluations.len());
+        assert!(evaluations.iter().any(Option::is_some));
+    }
+
+    /// A player that plays the first valid action and requests to undo its
+    /// last action once after the given amount of its own moves.
+    struct UndoingPlayer {
+        moves: usize,
+        undo_after: Option<usize>,
+    }
+
+    impl Player for UndoingPlayer {
+        fn name(&self) -> &'static str {
+            "Undoing Player"
+        }
+
+        fn get_action(&mut self, game: &Patchwork) -> anyhow::Result<ActionId> {
+            if self.undo_after == Some(self.moves) {
+                self.undo_after = None;
+                return Err(UndoRequest.into());
+            }
+            self.moves += 1;
+            Ok(game.get_valid_actions()[0])
+        }
+    }
+
+    #[test]
+    fn test_observers_are_notified_of_undone_moves() {
+        let observer = CountingObserver::default();
+        let actions = Arc::clone(&observer.actions);
+        let undone_moves = Arc::clone(&observer.undone_moves);
+        let mut observers: Vec<ObserverType> = vec![Box::new(observer)];
+
+        let player_1 = PlayerType::BuildIn(
+            Box::new(UndoingPlayer {
+                moves: 0,
+                undo_after: Some(3),
+            }),
+            "undoing".to_string(),
+        );
+        let player_2 = PlayerType::BuildIn(
+            Box::new(UndoingPlayer {
+                moves: 0,
+                undo_after: None,
+            }),
+            "undoing".to_string(),
+        );
+
+        let record = handle_console_repl(player_1, player_2, Some(42), &mut observers).unwrap();
+
+        // the undone moves are no longer part of the observed moves
+        let undone_moves = undone_moves.lock().unwrap().clone();
+        assert_eq!(1, undone_moves.len());
+        assert!(undone_moves[0] > 0);
+        assert_eq!(record.actions, *actions.lock().unwrap());
+    }
+
+    #[test]
+    fn test_forced_action_has_no_margin() {
+        // player 1 cannot afford any patch and has to walk
+        let forced = Patchwork::load_from_notation(
+            "000000000000000000000B0I0P0 000000000000000000000B5I0P5 1 N 1/8/10/2/3/4/5/6/7/9",
+        )
+        .unwrap();
+        let free = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        assert_eq!(1, forced.get_valid_actions().len());
+
+        let mut player = PlayerType::BuildIn(Box::new(<GreedyPlayer>::default()), "greedy".to_string());
+        let format = |player: &mut PlayerType, state: &Patchwork| {
+            let (action, diagnostics) = player.get_action_with_diagnostics(state).unwrap();
+            (
+                diagnostics.margin(),
+                format_move(player.name(), state, action, diagnostics.time, &diagnostics),
+            )
+        };
+
+        let (_, line) = format(&mut player, &forced);
+        assert!(line.ends_with("margin: 0)"), "Unexpected line {line}");
+
+        let (margin, line) = format(&mut player, &free);
+        assert!(line.ends_with(&format!("margin: {})", margin.unwrap())), "Unexpected line {line}");
+    }
+
+    #[test]
+    fn test_saved_game_reloads_to_same_game() {
+        let player_1 = PlayerType::BuildIn(
+            Box::new(RandomPlayer::new("Random Player 1", Some(RandomOptions::new(1)))),
+            "random".to_string(),
+        );
+        let player_2 = PlayerType::BuildIn(
+            Box::new(RandomPlayer::new("Random Player 2", Some(RandomOptions::new(2)))),
+            "random".to_string(),
+        );
+        let record = handle_console_repl(player_1, player_2, Some(42), &mut []).unwrap();
+
+        let path = std::env::temp_dir().join(format!("patchwork-console-save-{}.txt", std::process::id()));
+        let path = path.to_str().unwrap();
+        save_game(path, &record, ["Random Player 1", "Random Player 2"], Some(42)).unwrap();
+        let saved = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert!(saved.starts_with("# Player 1: Random Player 1\n# Player 2: Random Player 2\n# Seed: 42\n"));
+        let loaded = GameRecord::load_from_notation(&saved).unwrap();
+        assert_eq!(record, loaded);
+        assert!(loaded.replay().unwrap().is_terminated());
+    }
 }
@@ -27,6 +27,11 @@ pub fn handle_exit_with_error(error: anyhow::Error) -> ! {
                 println!("Reason: {reason}");
                 std::process::exit(1);
             }
+            PatchworkError::InvalidStateError { reason, state } => {
+                println!("Reason: {reason}");
+                println!("State: {state:?}");
+                std::process::exit(1);
+            }
         }
     }
 
@@ -30,20 +30,50 @@ pub fn print_help() {
     println!("                -2,   --player-2      The name of the second player");
     println!("                --l1, --logging-1     The logging configuration of the first player");
     println!("                --l2, --logging-2     The logging configuration of the second player");
+    println!("                --h1, --handicap-1    The amount of moves the first player plays greedily");
+    println!("                --h2, --handicap-2    The amount of moves the second player plays greedily");
+    println!("                -c,   --config        Read the players from the given TOML or JSON file");
     println!("                -s,   --seed          The seed for the initial state");
+    println!("                      --csv           Write every move to the given CSV file");
+    println!("                      --eval-curve    Write the evaluation of every ply to the given CSV file");
+    println!("                      --save          Save the finished game to the given file");
+    println!("                -r,   --replay        Step through the recorded game in the given file instead");
+    println!("                -e,   --eval          The evaluator used for the replay and the evaluation curve");
     println!("    compare   Compare different patchwork ai's against each other");
     println!("                -1,   --player-1      The name of the first player");
     println!("                -2,   --player-2      The name of the second player");
     println!("                --l1, --logging-1     The logging configuration of the first player");
     println!("                --l2, --logging-2     The logging configuration of the second player");
+    println!("                --h1, --handicap-1    The amount of moves the first player plays greedily");
+    println!("                --h2, --handicap-2    The amount of moves the second player plays greedily");
+    println!("                -c,   --config        Read the players from the given TOML or JSON file");
     println!("                -g,   --games         The number of games the players should be compared in");
     println!("                -u,   --update        How often the comparison information should be updated (in ms)");
     println!("                -p,   --parallel      How many games to play in parallel");
+    println!("                      --pin-cores     Pin every worker thread to its own CPU core for stable timings");
+    println!("                      --throttle      The maximum amount of games per second (for background runs)");
+    println!("                      --gauntlet      Test player 1 against player 2 and the given further opponents");
+    println!("                      --csv           Write every move of every game to the given CSV file");
+    println!("                      --save-full     Save the moves of every game in binary files to the given folder");
+    println!("                      --json-out      Write a machine-readable summary of the result to the given JSON file");
+    println!("                      --resume        Resume an interrupted comparison from its checkpoint");
+    println!("                      --plain         Print the progress line by line without ANSI escape codes");
+    println!("                                      (default if the output is not a terminal)");
+    println!("    tune      Tune the weights of the static evaluator against an opponent");
+    println!("                -o,   --opponent      The name of the opponent (default greedy)");
+    println!("                -l,   --logging       The logging configuration of the opponent");
+    println!("                -g,   --games         The number of games for every evaluation of the weights");
+    println!("                -i,   --iterations    The number of passes over all weights");
+    println!("                -s,   --step          The initial relative change of a weight (default 0.25)");
+    println!("                -p,   --parallel      How many games to play in parallel");
+    println!("                      --output        The file the tuned weights are written to");
     println!("    upi       Start Universal Patchwork Interface (UPI) in console mode");
     println!("                -n,   --no-prompt     Do not print the prompt");
+    println!("                -p,   --player        The player that searches the moves (default pvs)");
     println!("    server    Start the patchwork game server");
     println!("                -p,  --port           The port the server should start on. Default 3000");
     println!("                --public             If present listens on 0.0.0.0 else on 127.0.0.1");
+    println!("                --allow-origin       An origin allowed to make cross-origin requests ('*' for any)");
 }
 
 #[cfg(debug_assertions)]
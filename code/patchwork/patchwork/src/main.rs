@@ -4,6 +4,7 @@ mod console;
 mod exit;
 mod help;
 mod server;
+mod tune;
 mod upi;
 
 use rustyline::error::ReadlineError;
@@ -18,6 +19,7 @@ use crate::exit::{handle_exit, handle_exit_with_error};
 use crate::help::print_debug;
 use crate::help::{print_help, print_welcome};
 use crate::server::handle_server;
+use crate::tune::handle_tune;
 use crate::upi::handle_upi;
 
 fn main() {
@@ -74,6 +76,7 @@ fn handle_args() -> anyhow::Result<()> {
         "console" => handle_console(&mut rl, args)?,
         "compare" => handle_compare(&mut rl, args)?,
         "server" => handle_server(&mut rl, args)?,
+        "tune" => handle_tune(&mut rl, args)?,
         _ => {
             print_help();
             handle_exit(1);
@@ -112,6 +115,11 @@ fn match_line(line: &str, rl: &mut Editor<(), FileHistory>) -> anyhow::Result<()
                 println!("Server exited with error: {err}");
             }
         }
+        Some("tune") => {
+            if let Err(err) = handle_tune(rl, args) {
+                println!("Tune exited with error: {err}");
+            }
+        }
         _ => println!("Unknown command \"{line}\". Type \"help\" for more information."),
     }
 
@@ -9,12 +9,14 @@ struct CmdArgs {
     port: Option<u16>,
     #[arg(long, default_value_t = false)]
     public: bool,
+    #[arg(long = "allow-origin")]
+    allowed_origins: Vec<String>,
 }
 
 pub fn handle_server(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> anyhow::Result<()> {
     let args = CmdArgs::parse_from(args);
 
     rl.clear_screen()?;
-    start_server(args.port, args.public)?;
+    start_server(args.port, args.public, &args.allowed_origins)?;
     Ok(())
 }
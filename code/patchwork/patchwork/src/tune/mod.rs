@@ -0,0 +1,252 @@
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    sync::Mutex,
+};
+
+use anyhow::Error;
+use clap::Parser;
+use rustyline::{history::FileHistory, Editor};
+
+use crate::{
+    common::{get_logging, get_player, interactive_get_player, PlayerType},
+    compare::player_factory,
+};
+use patchwork_lib::{
+    compare::{run_compare, CompareResult},
+    evaluator::StaticWeights,
+    player::{Logging, Player},
+};
+
+#[derive(Debug, Parser, Default)]
+#[command(no_binary_name(true))]
+struct CmdArgs {
+    #[arg(long = "opponent", short = 'o', default_value = "greedy")]
+    opponent: String,
+    #[arg(long = "logging", short = 'l', default_value = "disabled")]
+    logging: String,
+    #[arg(long = "games", short = 'g', default_value = "100")]
+    games: usize,
+    #[arg(long = "iterations", short = 'i', default_value = "10")]
+    iterations: usize,
+    #[arg(long = "step", short = 's', default_value = "0.25")]
+    step: f64,
+    #[arg(long = "parallel", short = 'p')]
+    parallel: Option<usize>,
+    #[arg(long = "output", default_value = "tuned_weights.txt")]
+    output: String,
+}
+
+/// The result of tuning the weights of the static evaluator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TuneResult {
+    /// The best weights that were found.
+    weights: StaticWeights,
+    /// The win rate of the best weights against the opponent.
+    win_rate: f64,
+    /// The win rate of the default weights against the opponent.
+    initial_win_rate: f64,
+}
+
+pub fn handle_tune(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> anyhow::Result<()> {
+    let args = CmdArgs::parse_from(args);
+
+    if !(0.0..1.0).contains(&args.step) || args.step == 0.0 {
+        return Err(Error::msg("The step has to be between 0 and 1"));
+    }
+
+    let opponent = interactive_get_player(rl, Some(args.opponent), 2, get_logging(args.logging.as_str())?)?;
+    let available_parallelism: usize = std::thread::available_parallelism().map_or(1, |p| p.get() - 1);
+    let parallelization = args.parallel.unwrap_or(available_parallelism).max(1);
+
+    println!(
+        "Tuning the static evaluator against {} with {} games per evaluation",
+        opponent.name(),
+        args.games
+    );
+    let result = tune(
+        args.iterations,
+        args.games,
+        args.step,
+        &opponent,
+        parallelization,
+        |weights, win_rate| {
+            println!("{} -> {:.2}%", format_weights(weights), win_rate * 100.0);
+            Ok(())
+        },
+    )?;
+
+    let mut stdout = std::io::stdout();
+    write_weights(&mut stdout, &result)?;
+    let mut file = BufWriter::new(File::create(&args.output)?);
+    write_weights(&mut file, &result)?;
+    println!("Wrote the tuned weights to {}", args.output);
+
+    Ok(())
+}
+
+/// Tunes the weights of the static evaluator with a coordinate descent. A
+/// greedy player with the weights plays against the opponent and every
+/// weight is in turn increased and decreased by the relative step. A change
+/// is kept if the win rate improved. After an iteration without any
+/// improvement the step is halved.
+///
+/// # Arguments
+///
+/// * `iterations` - The amount of passes over all weights.
+/// * `games` - The amount of games for every evaluation of weights.
+/// * `step` - The initial relative step of a weight (e.g. `0.25` for ±25%).
+/// * `opponent` - The fixed opponent.
+/// * `parallelization` - The amount of games to play in parallel.
+/// * `on_evaluation` - Called with every evaluated weights and their win rate.
+///
+/// # Returns
+///
+/// The best weights. The win rate of the best weights is never below the win
+/// rate of the default weights as only improvements are kept.
+///
+/// # Errors
+///
+/// If the callback returns an error or the games could not be played.
+fn tune(
+    iterations: usize,
+    games: usize,
+    step: f64,
+    opponent: &PlayerType,
+    parallelization: usize,
+    mut on_evaluation: impl FnMut(&StaticWeights, f64) -> anyhow::Result<()>,
+) -> anyhow::Result<TuneResult> {
+    let mut evaluate = |weights: &StaticWeights| -> anyhow::Result<f64> {
+        let win_rate = get_win_rate(weights, games, opponent, parallelization)?;
+        on_evaluation(weights, win_rate)?;
+        Ok(win_rate)
+    };
+
+    let mut weights = StaticWeights::DEFAULT;
+    let mut win_rate = evaluate(&weights)?;
+    let initial_win_rate = win_rate;
+    let mut step = step;
+
+    for _ in 0..iterations {
+        let mut improved = false;
+        for index in 0..StaticWeights::NAMES.len() {
+            for factor in [1.0 + step, 1.0 - step] {
+                let mut candidate = weights.to_array();
+                candidate[index] *= factor;
+                let candidate = StaticWeights::from_array(candidate);
+
+                let candidate_win_rate = evaluate(&candidate)?;
+                if candidate_win_rate > win_rate {
+                    weights = candidate;
+                    win_rate = candidate_win_rate;
+                    improved = true;
+                    break;
+                }
+            }
+        }
+
+        if !improved {
+            step /= 2.0;
+        }
+    }
+
+    Ok(TuneResult {
+        weights,
+        win_rate,
+        initial_win_rate,
+    })
+}
+
+/// Plays the given amount of games of a greedy player with the given weights
+/// against the opponent.
+///
+/// # Returns
+///
+/// The win rate of the greedy player.
+fn get_win_rate(
+    weights: &StaticWeights,
+    games: usize,
+    opponent: &PlayerType,
+    parallelization: usize,
+) -> anyhow::Result<f64> {
+    let challenger = get_player(
+        &format!("greedy(eval: static, weights: {})", format_weights(weights)),
+        Logging::Disabled,
+    )
+    .map_err(|error| Error::msg(error.reason))?;
+
+    let result = run_compare(
+        games,
+        player_factory(&challenger),
+        player_factory(opponent),
+        parallelization,
+        false,
+        None,
+        false,
+        &Mutex::new(vec![]),
+        &CompareResult::new(),
+        std::time::Duration::from_secs(1),
+        |_, _| Ok(()),
+    )?;
+
+    Ok(f64::from(result.wins_player_1) / f64::from(result.games().max(1)))
+}
+
+/// Formats the weights as a list in the order of [`StaticWeights::NAMES`].
+fn format_weights(weights: &StaticWeights) -> String {
+    let weights = weights.to_array().map(|weight| weight.to_string());
+    format!("[{}]", weights.join(", "))
+}
+
+/// Writes the tuned weights with one `name = value` line per weight.
+fn write_weights(output: &mut impl Write, result: &TuneResult) -> anyhow::Result<()> {
+    writeln!(
+        output,
+        "# win rate: {:.2}% (default weights: {:.2}%)",
+        result.win_rate * 100.0,
+        result.initial_win_rate * 100.0
+    )?;
+    write!(output, "{}", result.weights)?;
+    output.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tuning_does_not_worsen_the_win_rate() {
+        let opponent = get_player("random", Logging::Disabled).unwrap();
+        let mut evaluations = vec![];
+
+        let result = tune(1, 2, 0.5, &opponent, 1, |weights, win_rate| {
+            evaluations.push((*weights, win_rate));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!((StaticWeights::DEFAULT, result.initial_win_rate), evaluations[0]);
+        assert!(result.win_rate >= result.initial_win_rate);
+        assert!(evaluations.contains(&(result.weights, result.win_rate)));
+        assert!(evaluations.iter().all(|(_, win_rate)| *win_rate <= result.win_rate));
+        if result.weights != StaticWeights::DEFAULT {
+            assert!(result.win_rate > result.initial_win_rate);
+        }
+
+        // the tuned weights are used by the greedy player that evaluated them
+        let player = get_player(
+            &format!("greedy(eval: static, weights: {})", format_weights(&result.weights)),
+            Logging::Disabled,
+        )
+        .unwrap();
+        let weights = format_weights(&result.weights);
+        assert!(player.name().ends_with(&format!("weights: {weights})")), "{}", player.name());
+
+        let mut output = vec![];
+        write_weights(&mut output, &result).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains(&format!("board = {}", result.weights.board)));
+        assert!(output.contains(&format!("button_income = {}", result.weights.button_income)));
+    }
+}
@@ -5,9 +5,11 @@ use std::{
 
 use anyhow::Error;
 use clap::Parser;
+use patchwork_lib::player::Logging;
 use rustyline::{error::ReadlineError, history::FileHistory, Editor};
 use upi::start_upi;
 
+use crate::common::get_player;
 use crate::{CTRL_C_MESSAGE, CTRL_D_MESSAGE};
 
 #[derive(Debug, Parser, Default)]
@@ -15,6 +17,8 @@ use crate::{CTRL_C_MESSAGE, CTRL_D_MESSAGE};
 struct CmdArgs {
     #[arg(long = "no-prompt", short = 'n', default_value = "false")]
     no_prompt: bool,
+    #[arg(long = "player", short = 'p', default_value = "pvs")]
+    player: String,
 }
 
 pub fn handle_upi(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> anyhow::Result<()> {
@@ -28,8 +32,15 @@ pub fn handle_upi(rl: &mut Editor<(), FileHistory>, args: Vec<String>) -> anyhow
         let close_flag = Arc::new(AtomicBool::new(false));
         // upi thread
         let close_flag_clone = close_flag.clone();
+        let player_name = args.player;
         s.spawn(move || {
-            let _ = start_upi(upi_receiver, upi_sender);
+            // the player is created in the upi thread as players cannot be sent between threads
+            match get_player(&player_name, Logging::Disabled) {
+                Ok(mut player) => {
+                    let _ = start_upi(upi_receiver, upi_sender, &mut player);
+                }
+                Err(error) => println!("{}", error.reason),
+            }
             close_flag_clone.store(true, std::sync::atomic::Ordering::SeqCst);
         });
 
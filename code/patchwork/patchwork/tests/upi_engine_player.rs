@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use patchwork_lib::{
+    player::{Player, RandomOptions, RandomPlayer},
+    GameOptions, Patchwork,
+};
+use upi::UpiEnginePlayer;
+
+#[test]
+fn test_play_against_own_binary_as_upi_engine() {
+    const MOVES: usize = 6;
+
+    let mut engine = UpiEnginePlayer::new(
+        env!("CARGO_BIN_EXE_patchwork"),
+        &["upi", "--no-prompt", "--player", "greedy"],
+        Duration::from_secs(30),
+    )
+    .unwrap();
+    let mut opponent = RandomPlayer::new("Random Player", Some(RandomOptions::new(42)));
+    assert_eq!("upi", engine.name());
+
+    let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+    for _ in 0..MOVES {
+        let action = if state.is_player_1() {
+            engine.get_action(&state).unwrap()
+        } else {
+            opponent.get_action(&state).unwrap()
+        };
+        assert!(state.get_valid_actions().contains(&action), "Invalid action {action:?}");
+        state.do_action(action, false).unwrap();
+    }
+    engine.reset();
+}
@@ -0,0 +1,67 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use patchwork_core::{GameOptions, Logging, Patchwork};
+use principal_variation_search_player::{
+    DefaultPVSPlayer, LazySMPFeature, PVSFeatures, PVSOptions, TranspositionTableFeature,
+};
+
+/// The depth every search is done to. Without a transposition table the
+/// searches with and without the placement cache explore exactly the same
+/// tree, so the difference in time is the difference in the node rate.
+const SEARCH_DEPTH: u8 = 5;
+
+fn get_positions() -> Vec<Patchwork> {
+    (0..4)
+        .map(|seed| {
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            for action_seed in 0..(seed * 4) {
+                state.do_action(state.get_seeded_random_action(action_seed), false).unwrap();
+            }
+            state
+        })
+        .collect()
+}
+
+fn pvs_search(c: &mut Criterion, placement_cache: bool) {
+    let bench_name = format!("pvs.search(depth: {SEARCH_DEPTH}, placement_cache: {placement_cache})");
+    let positions = get_positions();
+
+    let mut group = c.benchmark_group("pvs");
+    group.sample_size(10);
+    group.bench_function(&bench_name, |b| {
+        b.iter(|| {
+            for position in &positions {
+                let mut player = <DefaultPVSPlayer>::new(
+                    "PVS Player",
+                    Some(PVSOptions {
+                        time_limit: std::time::Duration::from_secs(30),
+                        features: PVSFeatures {
+                            transposition_table: TranspositionTableFeature::Disabled,
+                            lazy_smp: LazySMPFeature::No,
+                            ..Default::default()
+                        },
+                        logging: Logging::Disabled,
+                        max_depth: Some(SEARCH_DEPTH),
+                        instability_time_extension: None,
+                        time_prediction: false,
+                        placement_cache,
+                        minimize_loss_margin: false,
+                        adaptive_time: false,
+                    }),
+                );
+                black_box(player.get_action(black_box(position)).unwrap());
+            }
+        });
+    });
+    group.finish();
+}
+
+fn pvs_search_uncached(c: &mut Criterion) {
+    pvs_search(c, false);
+}
+
+fn pvs_search_cached(c: &mut Criterion) {
+    pvs_search(c, true);
+}
+
+criterion_group!(benches, pvs_search_uncached, pvs_search_cached);
+criterion_main!(benches);
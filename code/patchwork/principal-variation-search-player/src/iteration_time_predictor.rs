@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use patchwork_core::IterationTiming;
+
+/// Predicts the time the next iteration of the iterative deepening will take
+/// from the times of the previous iterations.
+///
+/// The prediction assumes that the effective branching factor observed
+/// between the last two iterations stays the same for the next iteration.
+/// With it the search can stop early instead of starting an iteration that
+/// would be abandoned at the time limit anyway.
+#[allow(clippy::redundant_pub_crate)] // false positive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct IterationTimePredictor {
+    /// The predicted and actual times of the completed iterations.
+    iterations: Vec<IterationTiming>,
+}
+
+impl IterationTimePredictor {
+    /// Clears all recorded iterations so that the predictor can be used for
+    /// a new search.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub fn reset(&mut self) {
+        self.iterations.clear();
+    }
+
+    /// Records a completed iteration together with the time that was
+    /// predicted for it before it was recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `depth` - The depth that was searched in the iteration.
+    /// * `actual` - The time the iteration took.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[inline]
+    pub fn record_iteration(&mut self, depth: usize, actual: Duration) {
+        let predicted = self.predict_next_iteration();
+        self.iterations.push(IterationTiming {
+            depth,
+            predicted,
+            actual,
+        });
+    }
+
+    /// Predicts the time the next iteration will take. The time of the last
+    /// iteration is multiplied with the effective branching factor (the
+    /// ratio between the times of the last two iterations), which is at
+    /// least one as a deeper iteration never takes less time.
+    ///
+    /// # Returns
+    ///
+    /// The predicted time of the next iteration or `None` if less than two
+    /// iterations were recorded.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn predict_next_iteration(&self) -> Option<Duration> {
+        let [.., previous, last] = self.iterations.as_slice() else {
+            return None;
+        };
+
+        // last · (last / previous) in nanoseconds to avoid rounding errors
+        let last = last.actual.as_nanos();
+        let predicted = (last * last / previous.actual.as_nanos().max(1)).max(last);
+        Some(Duration::from_nanos(u64::try_from(predicted).unwrap_or(u64::MAX)))
+    }
+
+    /// Whether the next iteration is predicted to finish within the time
+    /// limit. Without a prediction the next iteration is always started.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - The time that already elapsed since the search started.
+    /// * `time_limit` - The time limit of the search.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the next iteration should be started, `false` if it would
+    /// overrun the time limit.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    #[must_use]
+    pub fn should_start_next_iteration(&self, elapsed: Duration, time_limit: Duration) -> bool {
+        self.predict_next_iteration().is_none_or(|predicted| elapsed.saturating_add(predicted) <= time_limit)
+    }
+
+    /// Gets the predicted and actual times of all recorded iterations.
+    ///
+    /// # Returns
+    ///
+    /// The recorded iterations in the order they were completed.
+    #[must_use]
+    pub fn get_iterations(&self) -> &[IterationTiming] {
+        &self.iterations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_predictor(timings: &[u64]) -> IterationTimePredictor {
+        let mut predictor = IterationTimePredictor::default();
+        for (depth, timing) in timings.iter().enumerate() {
+            predictor.record_iteration(depth + 1, Duration::from_millis(*timing));
+        }
+        predictor
+    }
+
+    #[test]
+    fn test_prediction_uses_branching_factor_of_last_iterations() {
+        let predictor = get_predictor(&[1, 3, 9]);
+
+        let predicted = predictor.get_iterations().iter().map(|iteration| iteration.predicted);
+        assert!(predicted.eq([None, None, Some(Duration::from_millis(9))]));
+        assert_eq!(Some(Duration::from_millis(27)), predictor.predict_next_iteration());
+        assert_eq!(None, get_predictor(&[5]).predict_next_iteration());
+
+        // a faster iteration does not predict an even faster next iteration
+        assert_eq!(Some(Duration::from_millis(4)), get_predictor(&[8, 4]).predict_next_iteration());
+    }
+
+    #[test]
+    fn test_predictor_stops_when_next_iteration_overruns() {
+        let predictor = get_predictor(&[10, 40, 160]);
+        let elapsed = Duration::from_millis(210);
+
+        // the next iteration is predicted to take 640ms
+        assert!(predictor.should_start_next_iteration(elapsed, Duration::from_secs(1)));
+        assert!(predictor.should_start_next_iteration(elapsed, Duration::from_millis(850)));
+        assert!(!predictor.should_start_next_iteration(elapsed, Duration::from_millis(849)));
+        assert!(!predictor.should_start_next_iteration(elapsed, Duration::from_millis(500)));
+
+        // without enough iterations the next iteration is always started
+        assert!(get_predictor(&[10]).should_start_next_iteration(elapsed, Duration::ZERO));
+    }
+}
@@ -1,4 +1,5 @@
 mod constants;
+mod iteration_time_predictor;
 pub(crate) mod lmp_flags;
 mod pvs_options;
 mod pvs_player;
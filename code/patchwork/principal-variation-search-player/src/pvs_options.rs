@@ -7,6 +7,7 @@ use patchwork_core::Logging;
 use transposition_table::Size;
 
 /// Different options for the Principal Variation Search (PVS) algorithm.
+#[allow(clippy::struct_excessive_bools)]
 pub struct PVSOptions {
     /// The time limit for the search.
     pub time_limit: std::time::Duration,
@@ -14,6 +15,32 @@ pub struct PVSOptions {
     pub features: PVSFeatures,
     /// If logging configuration for what should be printed.
     pub logging: Logging,
+    /// The maximum depth to search. The iterative deepening stops at this
+    /// depth even if there is time left. `None` to only stop at the time limit.
+    pub max_depth: Option<u8>,
+    /// The additional time the search is given when the best action changed
+    /// between the last two iterations of the iterative deepening (search
+    /// instability). `None` to never extend the time limit.
+    pub instability_time_extension: Option<std::time::Duration>,
+    /// Whether the iterative deepening stops early if the next iteration is
+    /// predicted to not finish within the time limit. The prediction is based
+    /// on the branching factor observed between the previous iterations.
+    pub time_prediction: bool,
+    /// Whether the placements of the patches are cached during a search so
+    /// that they are not generated again for every node with the same quilt
+    /// board.
+    pub placement_cache: bool,
+    /// Whether terminal positions are evaluated by their score difference
+    /// inside the won and lost evaluation ranges. In clearly lost positions
+    /// the search then minimizes the losing margin instead of treating all
+    /// losses as equal, as the final scores matter for tournaments and
+    /// tiebreaks.
+    pub minimize_loss_margin: bool,
+    /// Whether the time limit is scaled by the complexity of the position
+    /// (see [`Patchwork::complexity_estimate`](patchwork_core::Patchwork::complexity_estimate))
+    /// so that more time is spent on complex positions. The time limit is
+    /// then the time for a position of average complexity.
+    pub adaptive_time: bool,
 }
 
 impl PVSOptions {
@@ -34,6 +61,12 @@ impl PVSOptions {
             time_limit,
             features,
             logging,
+            max_depth: None,
+            instability_time_extension: None,
+            time_prediction: false,
+            placement_cache: true,
+            minimize_loss_margin: false,
+            adaptive_time: false,
         }
     }
 }
@@ -44,6 +77,12 @@ impl Default for PVSOptions {
             time_limit: std::time::Duration::from_secs(10),
             features: PVSFeatures::default(),
             logging: Logging::default(),
+            max_depth: None,
+            instability_time_extension: None,
+            time_prediction: false,
+            placement_cache: true,
+            minimize_loss_margin: false,
+            adaptive_time: false,
         }
     }
 }
@@ -67,6 +106,9 @@ pub struct PVSFeatures {
     /// If [Lazy SMP](https://www.chessprogramming.org/Lazy_SMP) should be used. Requires the transposition table
     /// feature to be enabled.
     pub lazy_smp: LazySMPFeature,
+    /// If [MTD(f)](https://www.chessprogramming.org/MTD(f)) should be used to search each iteration instead of
+    /// aspiration windows. Converges a lot faster with the fail-soft failing strategy.
+    pub mtdf: bool,
 }
 
 impl Default for PVSFeatures {
@@ -79,6 +121,7 @@ impl Default for PVSFeatures {
             late_move_pruning: true,
             search_extensions: true,
             lazy_smp: LazySMPFeature::default(),
+            mtdf: false,
         }
     }
 }
@@ -88,6 +131,10 @@ impl Default for PVSFeatures {
 /// The lazy SMP feature is used to parallelize the search by sharing a
 /// transposition table between multiple threads. Because of this the lazy SMP
 /// feature can only be enabled if the transposition table feature is enabled.
+///
+/// A search with the lazy SMP feature is not reproducible as the threads race
+/// on the shared transposition table. Only a single-threaded search (lazy SMP
+/// disabled) searches the same nodes every time.
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum LazySMPFeature {
@@ -108,8 +155,10 @@ impl Display for LazySMPFeature {
 
 impl Default for LazySMPFeature {
     fn default() -> Self {
+        // a single available thread leaves no thread for lazy SMP
         std::thread::available_parallelism()
-            .map(|n| unsafe { NonZeroUsize::new_unchecked(n.get() / 2) })
+            .ok()
+            .and_then(|n| NonZeroUsize::new(n.get() / 2))
             .map_or(Self::No, Self::Yes)
     }
 }
@@ -6,13 +6,17 @@ use std::{
         Arc,
     },
     thread::{Scope, ScopedJoinHandle},
+    time::Duration,
 };
 
 use action_orderer::{ActionOrderer, TableActionOrderer};
 use evaluator::StaticEvaluator;
 
-use patchwork_core::{ActionId, Evaluator, Logging, Patchwork, Player, PlayerResult};
-use transposition_table::TranspositionTable;
+use patchwork_core::{
+    reset_thread_affinity, ActionId, Evaluator, IterationTiming, Logging, Patchwork, Player, PlayerResult,
+    SearchDiagnostics, TranspositionTableDiagnostics,
+};
+use transposition_table::{TranspositionTable, TranspositionTableStatistics};
 
 use crate::{
     constants::{
@@ -56,6 +60,20 @@ pub struct PVSPlayer<
     pub options: PVSOptions,
     /// The transposition table for storing previously searched positions.
     transposition_table: Arc<TranspositionTable>,
+    /// The deepest depth that was completely searched in the last search.
+    last_search_depth: usize,
+    /// The number of nodes searched by the main worker in the last search.
+    /// Only counted if search statistics are enabled.
+    last_search_nodes: usize,
+    /// Whether the best action changed between the last two completed
+    /// iterations of the last search.
+    last_search_unstable: bool,
+    /// The evaluation of the best action of the last search from the
+    /// perspective of the player to move. `None` if no search was done.
+    last_search_evaluation: Option<i32>,
+    /// The predicted and actual times of the completed iterations of the
+    /// main worker in the last search.
+    last_search_iterations: Vec<IterationTiming>,
     orderer: PhantomData<Orderer>,
     evaluator: PhantomData<Eval>,
 }
@@ -100,20 +118,245 @@ impl<
     }
 
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.search(game, None)
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        if actions.is_empty() {
+            return self.search(game, None);
+        }
+
+        let action = self.search(game, Some(actions))?;
+        if actions.contains(&action) {
+            Ok(action)
+        } else {
+            // no iteration was completed and the fallback action is not allowed
+            Ok(actions[0])
+        }
+    }
+
+    fn get_action_with_diagnostics(&mut self, game: &Patchwork) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        self.get_restricted_action_with_diagnostics(game, &[])
+    }
+
+    fn get_restricted_action_with_diagnostics(
+        &mut self,
+        game: &Patchwork,
+        actions: &[ActionId],
+    ) -> PlayerResult<(ActionId, SearchDiagnostics)> {
+        let start_time = std::time::Instant::now();
+        let statistics = &self.transposition_table.statistics;
+        let accesses = statistics.accesses.load(TranspositionTableStatistics::LOAD_ORDERING);
+        let hits = statistics.hits();
+
+        let action = self.get_restricted_action(game, actions)?;
+        let time = start_time.elapsed();
+
+        let transposition_table = match self.options.features.transposition_table {
+            TranspositionTableFeature::Disabled => None,
+            TranspositionTableFeature::Enabled { .. } | TranspositionTableFeature::SymmetryEnabled { .. } => {
+                let statistics = &self.transposition_table.statistics;
+                Some(TranspositionTableDiagnostics {
+                    entries: statistics.entries.load(TranspositionTableStatistics::LOAD_ORDERING),
+                    capacity: statistics.capacity.load(TranspositionTableStatistics::LOAD_ORDERING),
+                    accesses: statistics.accesses.load(TranspositionTableStatistics::LOAD_ORDERING) - accesses,
+                    hits: statistics.hits() - hits,
+                })
+            }
+        };
+
+        let mut principal_variation = self.get_principal_variation(game, self.last_search_depth);
+        if principal_variation.first() != Some(&action) {
+            // the transposition table is disabled or the entry of the root was overwritten
+            principal_variation = vec![action];
+        }
+
+        let mut root_evaluations = vec![];
+        if let Some(evaluation) = self.last_search_evaluation {
+            root_evaluations.push((action, evaluation));
+            // the best alternative may not be one of the actions the search was restricted to
+            if actions.is_empty() {
+                root_evaluations.extend(self.search_best_alternative(game, action)?);
+            }
+        }
+
+        Ok((
+            action,
+            SearchDiagnostics {
+                depth: self.last_search_depth,
+                nodes: self.last_search_nodes,
+                time,
+                transposition_table,
+                principal_variation,
+                iterations: self.last_search_iterations.clone(),
+                root_evaluations,
+            },
+        ))
+    }
+
+    fn set_logging(&mut self, logging: Logging) {
+        self.options.logging = logging;
+    }
+
+    fn reset(&mut self) {
+        // The workers only hold the transposition table during a search
+        if let Some(transposition_table) = Arc::get_mut(&mut self.transposition_table) {
+            transposition_table.clear();
+        }
+        self.last_search_depth = 0;
+        self.last_search_nodes = 0;
+        self.last_search_unstable = false;
+        self.last_search_evaluation = None;
+        self.last_search_iterations.clear();
+    }
+}
+
+impl<
+        const TRANSPOSITION_TABLE_SYMMETRY_TYPE: char,
+        const SOFT_FAILING_STRATEGY: bool,
+        const ENABLE_LATE_MOVE_REDUCTIONS: bool,
+        const ENABLE_LATE_MOVE_PRUNING: bool,
+        const ENABLE_ASPIRATION_WINDOWS: bool,
+        const ENABLE_SEARCH_EXTENSIONS: bool,
+        const ENABLE_SEARCH_STATISTICS: bool,
+        Orderer: ActionOrderer,
+        Eval: Evaluator,
+    >
+    PVSPlayer<
+        TRANSPOSITION_TABLE_SYMMETRY_TYPE,
+        SOFT_FAILING_STRATEGY,
+        ENABLE_LATE_MOVE_REDUCTIONS,
+        ENABLE_LATE_MOVE_PRUNING,
+        ENABLE_ASPIRATION_WINDOWS,
+        ENABLE_SEARCH_EXTENSIONS,
+        ENABLE_SEARCH_STATISTICS,
+        Orderer,
+        Eval,
+    >
+{
+    /// The minimum factor the time limit is scaled with for adaptive time.
+    const MIN_ADAPTIVE_TIME_FACTOR: f32 = 0.5;
+    /// The maximum factor the time limit is scaled with for adaptive time.
+    const MAX_ADAPTIVE_TIME_FACTOR: f32 = 1.5;
+
+    /// Gets the time limit for the search of the given position. With
+    /// [`PVSOptions::adaptive_time`] the time limit is scaled by the
+    /// complexity of the position, otherwise it is the configured time limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The position to search.
+    ///
+    /// # Returns
+    ///
+    /// The time limit for the search (without the instability extension).
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `n` is the number of valid actions.
+    #[must_use]
+    pub fn get_time_limit(&self, game: &Patchwork) -> Duration {
+        if !self.options.adaptive_time {
+            return self.options.time_limit;
+        }
+
+        let factor = game
+            .complexity_estimate()
+            .clamp(Self::MIN_ADAPTIVE_TIME_FACTOR, Self::MAX_ADAPTIVE_TIME_FACTOR);
+        self.options.time_limit.mul_f32(factor)
+    }
+
+    /// Gets the principal variation of the last search from the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The state to get the principal variation for. This is
+    ///   usually the root state of the last search.
+    /// * `depth` - The maximum length of the principal variation.
+    ///
+    /// # Returns
+    ///
+    /// The principal variation. Every action is legal in the state reached by
+    /// the previous actions when taken with a forced player switch.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝑛)` where `𝑛` is the depth of the principal variation.
+    #[must_use]
+    pub fn get_principal_variation(&self, game: &Patchwork, depth: usize) -> Vec<ActionId> {
+        self.transposition_table.get_pv_line(game, depth)
+    }
+
+    /// Gets the deepest depth that was completely searched by the main worker
+    /// in the last search.
+    ///
+    /// # Returns
+    ///
+    /// The depth of the last completed iteration of the iterative deepening.
+    #[must_use]
+    pub const fn get_last_search_depth(&self) -> usize {
+        self.last_search_depth
+    }
+
+    /// Gets whether the best action changed between the last two completed
+    /// iterations of the iterative deepening in the last search.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the last search was unstable, `false` otherwise.
+    #[must_use]
+    pub const fn is_last_search_unstable(&self) -> bool {
+        self.last_search_unstable
+    }
+
+    /// Searches the best action for the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The state to search the best action for.
+    /// * `root_actions` - The valid actions the search is restricted to at
+    ///   the root. `None` to search all valid actions.
+    ///
+    /// # Returns
+    ///
+    /// The best action found by the search.
+    fn search(&mut self, game: &Patchwork, root_actions: Option<&[ActionId]>) -> PlayerResult<ActionId> {
+        // A forced move is played without searching
+        let valid_actions = root_actions.map_or_else(|| game.get_valid_actions(), <[ActionId]>::to_vec);
+        if valid_actions.len() == 1 {
+            self.last_search_depth = 0;
+            self.last_search_nodes = 0;
+            self.last_search_unstable = false;
+            self.last_search_evaluation = None;
+            self.last_search_iterations.clear();
+            return Ok(valid_actions[0]);
+        }
+
         std::thread::scope(|scope| {
             let search_canceled = Arc::new(AtomicBool::new(false));
+            let search_unstable = Arc::new(AtomicBool::new(false));
             let mut handles = vec![];
-            let time_limit = self.options.time_limit;
+            let time_limit = self.get_time_limit(game);
+            let unstable_time_limit = time_limit + self.options.instability_time_extension.unwrap_or_default();
 
             // Timer thread
             let timer_search_canceled = Arc::clone(&search_canceled);
+            let timer_search_unstable = Arc::clone(&search_unstable);
             handles.push(scope.spawn(move || {
                 let start_time = std::time::Instant::now();
 
                 // Periodic check if the search was already canceled by itself
-                while start_time.elapsed() < time_limit
-                    && !timer_search_canceled.load(std::sync::atomic::Ordering::Acquire)
-                {
+                while !timer_search_canceled.load(std::sync::atomic::Ordering::Acquire) {
+                    // An unstable search is given more time to resolve the instability
+                    let time_limit = if timer_search_unstable.load(std::sync::atomic::Ordering::Acquire) {
+                        unstable_time_limit
+                    } else {
+                        time_limit
+                    };
+                    if start_time.elapsed() >= time_limit {
+                        break;
+                    }
+
                     std::thread::sleep(std::time::Duration::from_millis(100));
                 }
                 // Stop search after time limit
@@ -127,7 +370,12 @@ impl<
                 LazySMPFeature::No => {}
                 LazySMPFeature::Yes(parallelization) => {
                     for _ in 0..(parallelization.get() - 1) {
-                        let worker = self.start_worker(scope, game.clone(), Arc::clone(&search_canceled));
+                        let worker = self.start_worker(
+                            scope,
+                            game.clone(),
+                            root_actions.map(<[ActionId]>::to_vec),
+                            Arc::clone(&search_canceled),
+                        );
 
                         handles.push(worker);
                     }
@@ -135,7 +383,13 @@ impl<
             }
 
             // Start Main Thread
-            let main_worker_result = self.start_main_worker(game.clone(), &search_canceled);
+            let main_worker_result = self.start_main_worker(
+                game.clone(),
+                root_actions.map(<[ActionId]>::to_vec),
+                time_limit,
+                &search_canceled,
+                &search_unstable,
+            );
 
             let mut results = vec![main_worker_result];
             for handle in handles {
@@ -149,39 +403,69 @@ impl<
             Ok(action)
         })
     }
-}
 
-impl<
-        const TRANSPOSITION_TABLE_SYMMETRY_TYPE: char,
-        const SOFT_FAILING_STRATEGY: bool,
-        const ENABLE_LATE_MOVE_REDUCTIONS: bool,
-        const ENABLE_LATE_MOVE_PRUNING: bool,
-        const ENABLE_ASPIRATION_WINDOWS: bool,
-        const ENABLE_SEARCH_EXTENSIONS: bool,
-        const ENABLE_SEARCH_STATISTICS: bool,
-        Orderer: ActionOrderer,
-        Eval: Evaluator,
-    >
-    PVSPlayer<
-        TRANSPOSITION_TABLE_SYMMETRY_TYPE,
-        SOFT_FAILING_STRATEGY,
-        ENABLE_LATE_MOVE_REDUCTIONS,
-        ENABLE_LATE_MOVE_PRUNING,
-        ENABLE_ASPIRATION_WINDOWS,
-        ENABLE_SEARCH_EXTENSIONS,
-        ENABLE_SEARCH_STATISTICS,
-        Orderer,
-        Eval,
-    >
-{
+    /// Searches the best alternative to the chosen action to the depth of the
+    /// last search. The search only bounds the evaluations of the other root
+    /// actions, so the best of them is searched again with an exact window.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The state that was searched.
+    /// * `chosen_action` - The action that was chosen by the last search.
+    ///
+    /// # Returns
+    ///
+    /// The best alternative together with its evaluation from the perspective
+    /// of the player to move or `None` if there is no alternative.
+    fn search_best_alternative(
+        &self,
+        game: &Patchwork,
+        chosen_action: ActionId,
+    ) -> PlayerResult<Option<(ActionId, i32)>> {
+        let alternatives = game
+            .get_valid_actions()
+            .into_iter()
+            .filter(|action| *action != chosen_action)
+            .collect::<Vec<_>>();
+        if alternatives.is_empty() || self.last_search_depth == 0 {
+            return Ok(None);
+        }
+
+        let mut worker = DefaultPVSWorker::<
+            false,
+            TRANSPOSITION_TABLE_SYMMETRY_TYPE,
+            SOFT_FAILING_STRATEGY,
+            ENABLE_LATE_MOVE_REDUCTIONS,
+            ENABLE_LATE_MOVE_PRUNING,
+            ENABLE_ASPIRATION_WINDOWS,
+            ENABLE_SEARCH_EXTENSIONS,
+            false,
+        >::new(Arc::new(AtomicBool::new(false)), Arc::clone(&self.transposition_table));
+        worker.set_max_depth(Some(u8::try_from(self.last_search_depth).unwrap_or(u8::MAX)));
+        worker.set_mtdf(self.options.features.mtdf);
+        worker.set_placement_cache(self.options.placement_cache);
+        worker.set_minimize_loss_margin(self.options.minimize_loss_margin);
+        worker.set_root_actions(Some(alternatives));
+
+        worker.search(game.clone())
+    }
+
     fn start_worker<'scope>(
         &mut self,
         scope: &'scope Scope<'scope, 'static>,
         game: Patchwork,
+        root_actions: Option<Vec<ActionId>>,
         search_canceled: Arc<AtomicBool>,
     ) -> ScopedJoinHandle<'scope, PlayerResult<Option<(ActionId, i32)>>> {
         let transposition_table = Arc::clone(&self.transposition_table);
+        let max_depth = self.options.max_depth;
+        let mtdf = self.options.features.mtdf;
+        let placement_cache = self.options.placement_cache;
+        let minimize_loss_margin = self.options.minimize_loss_margin;
         scope.spawn(move || {
+            // the helper threads are not bound to the core of a pinned main thread
+            reset_thread_affinity();
+
             let mut worker = DefaultPVSWorker::<
                 false,
                 TRANSPOSITION_TABLE_SYMMETRY_TYPE,
@@ -192,6 +476,11 @@ impl<
                 ENABLE_SEARCH_EXTENSIONS,
                 false,
             >::new(Arc::clone(&search_canceled), transposition_table);
+            worker.set_max_depth(max_depth);
+            worker.set_mtdf(mtdf);
+            worker.set_placement_cache(placement_cache);
+            worker.set_minimize_loss_margin(minimize_loss_margin);
+            worker.set_root_actions(root_actions);
 
             let result = worker.search(game);
 
@@ -204,7 +493,10 @@ impl<
     fn start_main_worker(
         &mut self,
         game: Patchwork,
+        root_actions: Option<Vec<ActionId>>,
+        time_limit: Duration,
         search_canceled: &Arc<AtomicBool>,
+        search_unstable: &Arc<AtomicBool>,
     ) -> PlayerResult<Option<(ActionId, i32)>> {
         let mut worker = DefaultPVSWorker::<
             true,
@@ -216,12 +508,25 @@ impl<
             ENABLE_SEARCH_EXTENSIONS,
             ENABLE_SEARCH_STATISTICS,
         >::new(Arc::clone(search_canceled), Arc::clone(&self.transposition_table));
+        worker.set_max_depth(self.options.max_depth);
+        worker.set_mtdf(self.options.features.mtdf);
+        worker.set_placement_cache(self.options.placement_cache);
+        worker.set_minimize_loss_margin(self.options.minimize_loss_margin);
+        worker.set_root_actions(root_actions);
+        worker.set_instability_signal(Arc::clone(search_unstable));
+        if self.options.time_prediction {
+            worker.set_time_prediction(time_limit, self.options.instability_time_extension.unwrap_or_default());
+        }
 
         if ENABLE_SEARCH_STATISTICS {
             worker.set_logging(&mut self.options.logging);
         }
 
         let result = worker.search(game);
+        self.last_search_depth = worker.get_completed_depth();
+        self.last_search_nodes = worker.statistics.total_nodes_searched;
+        self.last_search_unstable = worker.is_search_unstable();
+        self.last_search_iterations = worker.get_iteration_timings().to_vec();
 
         search_canceled.store(true, Ordering::Release);
 
@@ -251,8 +556,10 @@ impl<
         }
 
         if let Some(action) = best_action {
+            self.last_search_evaluation = Some(best_evaluation);
             return *action;
         }
+        self.last_search_evaluation = None;
 
         let _ = self.write_log("No Worker returned an Action. Using Transposition Table");
 
@@ -363,6 +670,11 @@ impl<
             name: "Principal Variation Search Player".to_string(),
             options,
             transposition_table,
+            last_search_depth: 0,
+            last_search_nodes: 0,
+            last_search_unstable: false,
+            last_search_evaluation: None,
+            last_search_iterations: vec![],
             evaluator: PhantomData,
             orderer: PhantomData,
         }
@@ -611,3 +923,152 @@ impl<Orderer: ActionOrderer + Default, Eval: Evaluator + Default>
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{GameOptions, Notation};
+
+    use super::*;
+
+    #[test]
+    fn test_principal_variation_is_legal() {
+        let mut player = DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::default();
+        player.options.time_limit = std::time::Duration::from_millis(500);
+        player.options.logging = Logging::Disabled;
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for _ in 0..5 {
+            let action = player.get_action(&state).unwrap();
+
+            let mut pv_state = state.clone();
+            for pv_action in player.get_principal_variation(&state, 20) {
+                assert!(
+                    pv_state.get_valid_actions().contains(&pv_action),
+                    "PV action {pv_action} is illegal in state {pv_state}"
+                );
+                pv_state.do_action(pv_action, true).unwrap();
+            }
+
+            state.do_action(action, false).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_max_depth_caps_search_depth() {
+        // a shallow depth that every machine searches far below the time limit
+        const MAX_DEPTH: u8 = 2;
+
+        let mut player = DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::default();
+        player.options.time_limit = std::time::Duration::from_secs(30);
+        player.options.max_depth = Some(MAX_DEPTH);
+        player.options.logging = Logging::Disabled;
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for _ in 0..5 {
+            let start_time = std::time::Instant::now();
+            let action = player.get_action(&state).unwrap();
+
+            assert!(
+                player.get_last_search_depth() <= usize::from(MAX_DEPTH),
+                "Searched to depth {} with a maximum depth of {MAX_DEPTH}",
+                player.get_last_search_depth()
+            );
+            assert!(
+                start_time.elapsed() < player.options.time_limit,
+                "Search did not stop at the maximum depth"
+            );
+
+            state.do_action(action, false).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_adaptive_time_spends_more_time_on_complex_positions() {
+        let mut player = DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::default();
+        player.options.time_limit = Duration::from_secs(2);
+
+        let mut complex = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        loop {
+            if complex.complexity_estimate() >= 1.5 {
+                break;
+            }
+            let action = complex.get_valid_actions()[0];
+            complex.do_action(action, false).unwrap();
+        }
+        let simple =
+            Patchwork::load_from_notation("1FFFFFFFFFFFFFFFFFFFEB10I0P27 000000000000000000000B5I0P20 5 Y 1/2/3")
+                .unwrap();
+
+        assert_eq!(player.options.time_limit, player.get_time_limit(&complex));
+        assert_eq!(player.options.time_limit, player.get_time_limit(&simple));
+
+        player.options.adaptive_time = true;
+        assert_eq!(Duration::from_secs(3), player.get_time_limit(&complex));
+        assert_eq!(Duration::from_secs(1), player.get_time_limit(&simple));
+    }
+
+    #[test]
+    fn test_root_evaluations_contain_the_best_alternative() {
+        let mut player = DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::default();
+        player.options.time_limit = std::time::Duration::from_secs(30);
+        player.options.max_depth = Some(2);
+        player.options.logging = Logging::Disabled;
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for _ in 0..4 {
+            let (action, diagnostics) = player.get_action_with_diagnostics(&state).unwrap();
+            if state.get_valid_actions().len() == 1 {
+                // a forced action is not searched
+                assert!(diagnostics.root_evaluations.is_empty());
+                state.do_action(action, false).unwrap();
+                continue;
+            }
+
+            let [(chosen_action, _), (alternative, _)] = diagnostics.root_evaluations[..] else {
+                panic!("Expected the top 2 root evaluations, got {:?}", diagnostics.root_evaluations);
+            };
+            assert_eq!(action, chosen_action);
+            assert_ne!(action, alternative);
+            assert!(state.get_valid_actions().contains(&alternative));
+            // the chosen action is the best action at the searched depth
+            assert!(diagnostics.margin().unwrap() >= 0);
+
+            state.do_action(action, false).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_diagnostics_are_consistent() {
+        const MAX_DEPTH: u8 = 3;
+
+        let mut player = DefaultPVSPlayer::<TableActionOrderer, StaticEvaluator>::default();
+        // the generous time limit is never reached so that the depth does not depend on the speed of the machine
+        player.options.time_limit = std::time::Duration::from_secs(30);
+        player.options.max_depth = Some(MAX_DEPTH);
+        // a helper thread reaching the maximum depth first would stop the main search that reports the diagnostics
+        player.options.features.lazy_smp = LazySMPFeature::No;
+        player.options.logging = Logging::Disabled;
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        for _ in 0..3 {
+            let start_time = std::time::Instant::now();
+            let (action, diagnostics) = player.get_action_with_diagnostics(&state).unwrap();
+
+            // a forced move is played without searching
+            let expected_depth = if state.get_valid_actions().len() == 1 { 0 } else { usize::from(MAX_DEPTH) };
+            assert_eq!(expected_depth, diagnostics.depth);
+            assert!(diagnostics.nodes >= diagnostics.depth, "Less nodes than depth searched");
+            assert!(diagnostics.time <= start_time.elapsed());
+            assert_eq!(diagnostics.principal_variation.first(), Some(&action));
+            assert!(diagnostics.principal_variation.len() <= diagnostics.depth.max(1));
+            assert_eq!(diagnostics.depth, diagnostics.iterations.len());
+            assert!(diagnostics.iterations.iter().map(|iteration| iteration.depth).eq(1..=diagnostics.depth));
+
+            let transposition_table = diagnostics.transposition_table.unwrap();
+            assert!(transposition_table.hits <= transposition_table.accesses);
+            assert!(transposition_table.entries <= transposition_table.capacity);
+
+            state.do_action(action, false).unwrap();
+        }
+    }
+}
@@ -1,12 +1,18 @@
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
 use action_orderer::{ActionList, ActionOrderer, TableActionOrderer};
 use evaluator::StaticEvaluator;
 use itertools::Itertools;
-use patchwork_core::{evaluator_constants, ActionId, Evaluator, Logging, Notation, Patchwork, PlayerResult, TurnType};
+use patchwork_core::{
+    evaluator_constants, ActionId, Evaluator, IterationTiming, Logging, Notation, Patchwork, PlacementCache,
+    PlayerResult, TerminationType, TurnType,
+};
 use transposition_table::{EvaluationType, TranspositionTable};
 
 use crate::{
@@ -18,6 +24,7 @@ use crate::{
         DEFAULT_LMR_APPLY_AFTER_PLYS, DEFAULT_MAX_SEARCH_EXTENSIONS, DEFAULT_SOFT_FAILING_STRATEGY,
         DEFAULT_TRANSPOSITION_TABLE_SYMMETRY_TYPE,
     },
+    iteration_time_predictor::IterationTimePredictor,
     lmp_flags::LMPFlags,
     search_recorder::SearchRecorder,
     SearchStatistics,
@@ -86,6 +93,40 @@ pub struct PVSWorker<
     best_action: Option<ActionId>,
     /// The best evaluation found so far.
     best_evaluation: Option<i32>,
+    /// The maximum depth the iterative deepening searches to.
+    max_depth: usize,
+    /// The deepest depth for which a search iteration was completed.
+    completed_depth: usize,
+    /// Whether the best action changed between the last two completed
+    /// iterations of the iterative deepening.
+    search_instability: bool,
+    /// The flag the search instability is published to while searching so
+    /// that the time management can react to it.
+    instability_signal: Option<Arc<AtomicBool>>,
+    /// Records the times of the completed iterations of the iterative
+    /// deepening and predicts the time of the next iteration.
+    iteration_time_predictor: IterationTimePredictor,
+    /// The time limit of the search used to stop the iterative deepening
+    /// before an iteration that is predicted to overrun it. `None` to only
+    /// stop when the search is canceled.
+    time_limit: Option<Duration>,
+    /// The additional time the search is given while it is unstable.
+    instability_time_extension: Duration,
+    /// The cache for the placements of the patches used for the action
+    /// generation. `None` to generate the placements for every node.
+    placement_cache: Option<PlacementCache>,
+    /// Whether each iteration of the iterative deepening is searched with
+    /// MTD(f) instead of a (aspiration) window search.
+    enable_mtdf: bool,
+    /// Whether terminal positions are evaluated by their score difference so
+    /// that the search minimizes the losing margin in lost positions.
+    minimize_loss_margin: bool,
+    /// The actions the search is restricted to at the root. `None` to search
+    /// all valid actions.
+    root_actions: Option<Vec<ActionId>>,
+    /// The buffers for the actions and their scores, one for every ply. They
+    /// are passed down the recursion so that no node allocates.
+    action_buffers: Vec<ActionBuffer>,
     /// The logging to use.
     logging: Option<&'worker mut Logging>,
     // The search recorder used to record the search tree
@@ -159,6 +200,11 @@ impl<
     /// maximum window size.
     pub const MAX_BETA_BOUND: i32 = evaluator_constants::POSITIVE_INFINITY + 1;
 
+    /// The range at the end of the won and lost evaluations in which terminal
+    /// positions are graded by their score difference if the losing margin is
+    /// minimized. The score difference of a game can never exceed this range.
+    pub const LOSS_MARGIN_RANGE: i32 = 1_000;
+
     pub const IS_MAIN_WORKER: bool = IS_MAIN_WORKER;
     pub const SOFT_FAILING_STRATEGY: bool = SOFT_FAILING_STRATEGY;
     pub const ENABLE_LATE_MOVE_REDUCTIONS: bool = ENABLE_LATE_MOVE_REDUCTIONS;
@@ -215,6 +261,18 @@ impl<
             transposition_table,
             best_action: None,
             best_evaluation: None,
+            max_depth: Self::MAX_DEPTH,
+            completed_depth: 0,
+            search_instability: false,
+            instability_signal: None,
+            iteration_time_predictor: IterationTimePredictor::default(),
+            time_limit: None,
+            instability_time_extension: Duration::ZERO,
+            placement_cache: None,
+            enable_mtdf: false,
+            minimize_loss_margin: false,
+            root_actions: None,
+            action_buffers: vec![ActionBuffer::default(); Self::MAX_DEPTH + 1],
             logging: None,
             search_recorder: SearchRecorder::<ENABLE_SEARCH_RECORDER>::new(),
         }
@@ -231,12 +289,137 @@ impl<
         self.logging = Some(logging);
     }
 
+    /// Sets the maximum depth to search to.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_depth` - The maximum depth to search to. `None` to search until
+    ///   the search is canceled.
+    pub fn set_max_depth(&mut self, max_depth: Option<u8>) {
+        self.max_depth = max_depth.map_or(Self::MAX_DEPTH, |max_depth| usize::from(max_depth).min(Self::MAX_DEPTH));
+    }
+
+    /// Sets whether the iterations of the iterative deepening should be
+    /// searched with [MTD(f)](https://www.chessprogramming.org/MTD(f)).
+    ///
+    /// # Arguments
+    ///
+    /// * `enable_mtdf` - Whether to use MTD(f) instead of aspiration windows.
+    pub const fn set_mtdf(&mut self, enable_mtdf: bool) {
+        self.enable_mtdf = enable_mtdf;
+    }
+
+    /// Sets the flag the search instability is published to after every
+    /// completed iteration of the iterative deepening.
+    ///
+    /// # Arguments
+    ///
+    /// * `instability_signal` - The flag to publish the search instability to.
+    pub fn set_instability_signal(&mut self, instability_signal: Arc<AtomicBool>) {
+        self.instability_signal = Some(instability_signal);
+    }
+
+    /// Sets the time limit that is used to predict whether the next iteration
+    /// of the iterative deepening can be completed. The iterative deepening
+    /// stops early if the next iteration is predicted to overrun the time
+    /// limit instead of starting an iteration that would be abandoned.
+    ///
+    /// # Arguments
+    ///
+    /// * `time_limit` - The time limit of the search.
+    /// * `instability_time_extension` - The additional time the search is
+    ///   given while it is unstable.
+    pub const fn set_time_prediction(&mut self, time_limit: Duration, instability_time_extension: Duration) {
+        self.time_limit = Some(time_limit);
+        self.instability_time_extension = instability_time_extension;
+    }
+
+    /// Sets whether the placements of the patches are cached during the
+    /// search. The cache is cleared at the start of every search.
+    ///
+    /// # Arguments
+    ///
+    /// * `enable_placement_cache` - Whether the placements should be cached.
+    pub fn set_placement_cache(&mut self, enable_placement_cache: bool) {
+        self.placement_cache = enable_placement_cache.then(PlacementCache::default);
+    }
+
+    /// Sets whether terminal positions are evaluated by their score
+    /// difference. All lost positions are then no longer equal and the search
+    /// plays the line with the smallest losing margin when every line loses.
+    ///
+    /// # Arguments
+    ///
+    /// * `minimize_loss_margin` - Whether the losing margin should be minimized.
+    pub const fn set_minimize_loss_margin(&mut self, minimize_loss_margin: bool) {
+        self.minimize_loss_margin = minimize_loss_margin;
+    }
+
+    /// Sets the actions the search is restricted to at the root. The
+    /// transposition table is neither probed nor stored at a restricted root
+    /// as its entry would not hold the evaluation of all valid actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `root_actions` - The valid actions to search at the root. `None` to
+    ///   search all valid actions.
+    pub fn set_root_actions(&mut self, root_actions: Option<Vec<ActionId>>) {
+        self.root_actions = root_actions;
+    }
+
+    // ────────────────────────────────────────── GETTERS ──────────────────────────────────────────
+
+    /// Gets the deepest depth for which a search iteration was completed in
+    /// the last search.
+    ///
+    /// # Returns
+    ///
+    /// The depth of the last completed search iteration.
+    #[must_use]
+    pub const fn get_completed_depth(&self) -> usize {
+        self.completed_depth
+    }
+
+    /// Gets whether the best action changed between the last two completed
+    /// iterations of the iterative deepening in the last search.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the search was unstable, `false` otherwise.
+    #[must_use]
+    pub const fn is_search_unstable(&self) -> bool {
+        self.search_instability
+    }
+
+    /// Gets the predicted and actual times of the completed iterations of the
+    /// iterative deepening in the last search.
+    ///
+    /// # Returns
+    ///
+    /// The times of the completed iterations in the order of their depth.
+    #[must_use]
+    pub fn get_iteration_timings(&self) -> &[IterationTiming] {
+        self.iteration_time_predictor.get_iterations()
+    }
+
+    /// Gets the cache for the placements of the patches of the last search.
+    ///
+    /// # Returns
+    ///
+    /// The placement cache or `None` if the placements are not cached.
+    #[cfg(test)]
+    #[must_use]
+    pub const fn get_placement_cache(&self) -> Option<&PlacementCache> {
+        self.placement_cache.as_ref()
+    }
+
     // ──────────────────────── ITERATIVE DEEPENING AND ASPIRATION WINDOWS  ────────────────────────
 
     /// Does a Iterative Deepening Principal Variation Search (PVS) with the
     /// given parameters.
     ///
-    /// Stops the search when the `search_canceled` flag is set to `true`.
+    /// Stops the search when the `search_canceled` flag is set to `true` or
+    /// the maximum depth was searched.
     ///
     /// # Arguments
     ///
@@ -252,14 +435,28 @@ impl<
             beta = Self::ASPIRATION_WINDOWS_STARTING_BETA;
         }
 
+        self.completed_depth = 0;
+        self.search_instability = false;
+        self.iteration_time_predictor.reset();
+        if let Some(placement_cache) = self.placement_cache.as_mut() {
+            placement_cache.clear();
+        }
         self.statistics.reset_iterative_deepening_iteration(); /* STATISTICS */
 
+        let search_start = Instant::now();
+        let mut iteration_start = search_start;
+
         // [Iterative Deepening](https://www.chessprogramming.org/Iterative_Deepening) loop
-        while depth < Self::MAX_DEPTH {
+        while depth < Self::MAX_DEPTH && depth <= self.max_depth {
             let best_action = self.best_action;
             let best_evaluation = self.best_evaluation;
 
-            let evaluation = self.principal_variation_search::<false>(&mut game, 0, depth, alpha, beta, 0)?;
+            let evaluation = if self.enable_mtdf {
+                // the evaluation of the previous iteration is the first guess
+                self.mtdf(&mut game, depth, best_evaluation.unwrap_or(0))?
+            } else {
+                self.bounded_search(&mut game, depth, alpha, beta)?
+            };
 
             self.search_recorder.print_to_file(); /* SEARCH RECORDER */
 
@@ -269,28 +466,35 @@ impl<
                 break;
             }
 
-            // [Aspiration Windows](https://www.chessprogramming.org/Aspiration_Windows)
-            if Self::ENABLE_ASPIRATION_WINDOWS && evaluation <= alpha {
-                // The best found evaluation is less then or equal to the lower bound (alpha),
-                // so we need to research at the same depth.
-                (alpha, beta, delta) = self.update_aspiration_window_lower_bound(alpha, beta, evaluation, delta);
-
-                self.best_action = best_action;
-                self.best_evaluation = best_evaluation;
-                self.statistics.increment_aspiration_window_fail_low(); /* STATISTICS */
-                continue;
-            } else if Self::ENABLE_ASPIRATION_WINDOWS && evaluation >= beta {
-                (alpha, beta, delta) = self.update_aspiration_window_upper_bound(alpha, beta, evaluation, delta);
-
-                self.best_action = best_action;
-                self.best_evaluation = best_evaluation;
-                self.statistics.increment_aspiration_window_fail_low(); /* STATISTICS */
-                continue;
-            } else if !Self::ENABLE_ASPIRATION_WINDOWS {
-                debug_assert!(evaluation > alpha, "[PVSWorker::update_aspiration_window_lower_bound] Assert evaluation({evaluation}) <= alpha({alpha}) should imply aspiration window but was not.");
-                debug_assert!(evaluation < beta,  "[PVSWorker::update_aspiration_window_upper_bound] Assert evaluation({evaluation}) >= beta({beta}) should imply aspiration window but was not.");
+            // MTD(f) always converges to the exact evaluation, so no re-search is needed
+            if !self.enable_mtdf {
+                // [Aspiration Windows](https://www.chessprogramming.org/Aspiration_Windows)
+                if Self::ENABLE_ASPIRATION_WINDOWS && evaluation <= alpha {
+                    // The best found evaluation is less then or equal to the lower bound (alpha),
+                    // so we need to research at the same depth.
+                    (alpha, beta, delta) = self.update_aspiration_window_lower_bound(alpha, beta, evaluation, delta);
+
+                    self.best_action = best_action;
+                    self.best_evaluation = best_evaluation;
+                    self.statistics.increment_aspiration_window_fail_low(); /* STATISTICS */
+                    continue;
+                } else if Self::ENABLE_ASPIRATION_WINDOWS && evaluation >= beta {
+                    (alpha, beta, delta) = self.update_aspiration_window_upper_bound(alpha, beta, evaluation, delta);
+
+                    self.best_action = best_action;
+                    self.best_evaluation = best_evaluation;
+                    self.statistics.increment_aspiration_window_fail_low(); /* STATISTICS */
+                    continue;
+                } else if !Self::ENABLE_ASPIRATION_WINDOWS {
+                    debug_assert!(evaluation > alpha, "[PVSWorker::update_aspiration_window_lower_bound] Assert evaluation({evaluation}) <= alpha({alpha}) should imply aspiration window but was not.");
+                    debug_assert!(evaluation < beta,  "[PVSWorker::update_aspiration_window_upper_bound] Assert evaluation({evaluation}) >= beta({beta}) should imply aspiration window but was not.");
+                }
             }
 
+            self.completed_depth = depth;
+            self.update_search_instability(best_action);
+            self.iteration_time_predictor.record_iteration(depth, iteration_start.elapsed());
+
             if self.best_evaluation == Some(evaluator_constants::POSITIVE_INFINITY) {
                 // We found a winning game, so we can stop searching
                 break;
@@ -307,13 +511,19 @@ impl<
                 (alpha, beta, delta) = self.reset_aspiration_window(alpha, beta, evaluation, delta);
             }
 
+            if !self.should_start_next_iteration(search_start.elapsed()) {
+                // The next iteration would only be abandoned at the time limit
+                break;
+            }
+
             depth += 1;
+            iteration_start = Instant::now();
             self.statistics.reset_iterative_deepening_iteration(); /* STATISTICS */
         }
 
         /* STATISTICS */
         if Self::IS_MAIN_WORKER && Self::ENABLE_SEARCH_STATISTICS {
-            let _ = self.write_statistics(&game, depth); // ignore errors
+            let _ = self.write_statistics(&game, depth.min(self.max_depth)); // ignore errors
         }
 
         if let Some(action) = self.best_action.take() {
@@ -323,6 +533,49 @@ impl<
         Ok(None)
     }
 
+    /// Updates the search instability after an iteration of the iterative
+    /// deepening was completed. The search is unstable if the best action
+    /// differs from the best action of the previous iteration.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous_best_action` - The best action of the previous iteration
+    ///   or `None` if this was the first iteration.
+    fn update_search_instability(&mut self, previous_best_action: Option<ActionId>) {
+        self.search_instability = previous_best_action.is_some() && previous_best_action != self.best_action;
+        self.statistics.set_search_instability(self.search_instability); /* STATISTICS */
+
+        if let Some(instability_signal) = &self.instability_signal {
+            instability_signal.store(self.search_instability, Ordering::Release);
+        }
+    }
+
+    /// Whether the next iteration of the iterative deepening should be started.
+    /// This is always the case if no time limit for the time prediction is
+    /// set.
+    ///
+    /// # Arguments
+    ///
+    /// * `elapsed` - The time that elapsed since the search started.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the next iteration is predicted to overrun the time limit,
+    /// `true` otherwise.
+    fn should_start_next_iteration(&self, elapsed: Duration) -> bool {
+        let Some(time_limit) = self.time_limit else {
+            return true;
+        };
+
+        let time_limit = if self.search_instability {
+            time_limit + self.instability_time_extension
+        } else {
+            time_limit
+        };
+
+        self.iteration_time_predictor.should_start_next_iteration(elapsed, time_limit)
+    }
+
     /// Updates the lower bound of the aspiration window after it failed with a
     /// [Fail-Low](https://www.chessprogramming.org/Fail-Low#Root_with_Aspiration)
     /// This means the correct evaluation is below the aspiration window.
@@ -442,6 +695,99 @@ impl<
         (alpha, beta, delta)
     }
 
+    // ────────────────────────────────────────── MTD(f) ───────────────────────────────────────────
+
+    /// Searches the given game to the given depth with the given window
+    /// around the root.
+    ///
+    /// With the fail-soft strategy the returned evaluation is a bound outside
+    /// of the window if the search fails:
+    /// * an upper bound if the evaluation is less than or equal to `alpha`
+    /// * a lower bound if the evaluation is greater than or equal to `beta`
+    ///
+    /// With the fail-hard strategy the evaluation is clamped to the window.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game to search in.
+    /// * `depth` - The depth to search.
+    /// * `alpha` - The lower bound of the window.
+    /// * `beta` - The upper bound of the window.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the game state or a bound of it.
+    ///
+    /// # Complexity
+    ///
+    /// Same as `principal_variation_search`
+    pub fn bounded_search(&mut self, game: &mut Patchwork, depth: usize, alpha: i32, beta: i32) -> PlayerResult<i32> {
+        let mut action_buffers = std::mem::take(&mut self.action_buffers);
+        let evaluation = self.principal_variation_search::<false>(game, 0, depth, alpha, beta, 0, &mut action_buffers);
+        self.action_buffers = action_buffers;
+
+        evaluation
+    }
+
+    /// Searches the given game to the given depth with
+    /// [MTD(f)](https://www.chessprogramming.org/MTD(f)).
+    ///
+    /// MTD(f) repeatedly does zero window searches around a guess of the
+    /// evaluation and narrows the upper and lower bound of the evaluation until
+    /// they meet. Re-searches are cheap as most of the tree is already stored
+    /// in the transposition table. The bounds only converge quickly with the
+    /// fail-soft strategy, with the fail-hard strategy every search only moves
+    /// the bounds by one.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The game to search in.
+    /// * `depth` - The depth to search.
+    /// * `first_guess` - The first guess for the evaluation, usually the
+    ///   evaluation of the previous iteration.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the game state.
+    ///
+    /// # Complexity
+    ///
+    /// Same as `principal_variation_search` times the number of zero window
+    /// searches needed.
+    pub fn mtdf(&mut self, game: &mut Patchwork, depth: usize, first_guess: i32) -> PlayerResult<i32> {
+        let previous_best_action = self.best_action;
+        let mut best_action = None;
+        let mut evaluation = first_guess.clamp(
+            evaluator_constants::NEGATIVE_INFINITY,
+            evaluator_constants::POSITIVE_INFINITY,
+        );
+        let mut lower_bound = Self::MIN_ALPHA_BOUND;
+        let mut upper_bound = Self::MAX_BETA_BOUND;
+
+        while lower_bound < upper_bound {
+            let beta = if evaluation == lower_bound { evaluation + 1 } else { evaluation };
+
+            evaluation = self.bounded_search(game, depth, beta - 1, beta)?;
+
+            if self.search_canceled.load(Ordering::Acquire) {
+                return Ok(0);
+            }
+
+            if evaluation < beta {
+                upper_bound = evaluation;
+            } else {
+                lower_bound = evaluation;
+                // the root action that failed high is at least as good as the lower bound
+                best_action = self.best_action.filter(|action| !action.is_null());
+            }
+        }
+
+        self.best_action = best_action.or(previous_best_action);
+        self.best_evaluation = Some(evaluation);
+
+        Ok(evaluation)
+    }
+
     // ──────────────────────────────── PRINCIPAL VARIATION SEARCH  ────────────────────────────────
 
     /// Does a Principal Variation Search (PVS) with the given parameters.
@@ -455,6 +801,7 @@ impl<
     /// * `beta` - The upper bound.
     /// * `num_extensions` - The number of search extensions that have been
     ///    applied so far.
+    /// * `action_buffers` - The buffers for this and all following plies.
     ///
     /// # Returns
     ///
@@ -467,6 +814,7 @@ impl<
     /// Best-case: `𝒪(√𝑏ᵈ)` where `𝑏` is the branching factor and `𝑑` is the
     ///         depth to search.
     #[allow(clippy::too_many_lines)]
+    #[allow(clippy::too_many_arguments)]
     #[allow(clippy::useless_let_if_seq)]
     #[rustfmt::skip]
     fn principal_variation_search<const ZERO_WINDOW_SEARCH: bool>(
@@ -477,6 +825,7 @@ impl<
         alpha: i32,
         beta: i32,
         num_extensions: usize,
+        action_buffers: &mut [ActionBuffer],
     ) -> PlayerResult<i32> {
         self.statistics.increment_nodes_searched(); /* STATISTICS */
         self.search_recorder.push_state(game.clone()); /* SEARCH RECORDER */
@@ -489,13 +838,15 @@ impl<
 
         // skip phantom moves
         if matches!(game.turn_type, TurnType::NormalPhantom | TurnType::SpecialPhantom) {
-            let evaluation = self.phantom_skip::<ZERO_WINDOW_SEARCH>(game, ply_from_root, depth, alpha, beta, num_extensions)?;
+            let evaluation = self.phantom_skip::<ZERO_WINDOW_SEARCH>(game, ply_from_root, depth, alpha, beta, num_extensions, action_buffers)?;
             self.search_recorder.pop_state_with_value(evaluation, alpha, beta, format!("Phantom Action ({ZERO_WINDOW_SEARCH})").as_str()); /* SEARCH RECORDER */
             return Ok(evaluation);
         }
 
+        let is_restricted_root = ply_from_root == 0 && self.root_actions.is_some();
+
         // Transposition table lookup
-        if Self::ENABLE_TRANSPOSITION_TABLE {
+        if Self::ENABLE_TRANSPOSITION_TABLE && !is_restricted_root {
             if let Some((table_action, table_evaluation)) =
                 self.transposition_table.probe_hash_entry(game, alpha, beta, depth)
             {
@@ -515,11 +866,23 @@ impl<
             return Ok(evaluation);
         }
 
-        let mut actions = game.get_valid_actions();
-        let mut scores = vec![0.0; actions.len()];
-        let mut action_list = self.get_action_list(game, ply_from_root, &mut actions, &mut scores);
+        let (ActionBuffer { actions, scores }, action_buffers) = action_buffers
+            .split_first_mut()
+            .expect("[PVSWorker::principal_variation_search] No action buffer left for the ply");
+        match self.placement_cache.as_mut() {
+            Some(placement_cache) => game.get_valid_actions_into_cached(actions, placement_cache),
+            None => game.get_valid_actions_into(actions),
+        }
+        if let Some(root_actions) = self.root_actions.as_ref().filter(|_| is_restricted_root) {
+            actions.retain(|action| root_actions.contains(action));
+        }
+        scores.clear();
+        scores.resize(actions.len(), 0.0);
+        let mut action_list = self.get_action_list(game, ply_from_root, actions, scores);
         let mut is_pv_node = true;
         let mut best_action = ActionId::null();
+        let mut best_evaluation = Self::MIN_ALPHA_BOUND;
+        let is_full_window = alpha == Self::MIN_ALPHA_BOUND && beta == Self::MAX_BETA_BOUND;
         let mut alpha = alpha;
         let mut evaluation_bound = EvaluationType::UpperBound;
         let mut lmp_flags = self.get_late_move_pruning_flags(&action_list);
@@ -552,6 +915,7 @@ impl<
                     -beta,
                     -alpha,
                     num_extensions + extension,
+                    action_buffers,
                 )?;
             } else {
                 self.statistics.increment_zero_window_search(); /* STATISTICS */
@@ -568,6 +932,7 @@ impl<
                         next_depth,
                         -alpha,
                         num_extensions + extension,
+                        action_buffers,
                     )?;
                     needs_full_search = evaluation > alpha;
                     if needs_full_search {
@@ -583,6 +948,7 @@ impl<
                         next_depth, // do not apply search extensions in zws
                         -alpha,
                         num_extensions + extension,
+                        action_buffers,
                     )?;
 
                     if evaluation > alpha && evaluation < beta && !ZERO_WINDOW_SEARCH {
@@ -605,6 +971,7 @@ impl<
                             -beta,
                             -alpha,
                             num_extensions + extension,
+                            action_buffers,
                         )?;
                     }
                 }
@@ -620,7 +987,17 @@ impl<
             if evaluation >= beta {
                 self.statistics.increment_fail_high(is_pv_node); /* STATISTICS */
 
-                self.store_transposition_table(game, depth, beta, EvaluationType::LowerBound, action);
+                if !is_restricted_root {
+                    self.store_transposition_table(game, depth, beta, EvaluationType::LowerBound, action);
+                }
+
+                // Cannot happen in Zero window search anyways since ply is 0
+                if ply_from_root == 0 && !ZERO_WINDOW_SEARCH {
+                    // needed for searches with external bounds (e.g. MTD(f)), a fail-high
+                    // at the root still yields an action that is at least as good as beta
+                    self.best_action = Some(action);
+                    self.best_evaluation = Some(if Self::SOFT_FAILING_STRATEGY { evaluation } else { beta });
+                }
 
                 return Ok(if Self::SOFT_FAILING_STRATEGY {
                     self.search_recorder.pop_state_with_value(evaluation, alpha, beta, format!("Fail-Soft Beta-Cutoff ({ZERO_WINDOW_SEARCH})").as_str()); /* SEARCH RECORDER */
@@ -631,6 +1008,8 @@ impl<
                 });
             }
 
+            best_evaluation = best_evaluation.max(evaluation);
+
             // Cannot happen in Zero window search anyways since alpha = beta - 1
             if evaluation > alpha && !ZERO_WINDOW_SEARCH {
                 evaluation_bound = EvaluationType::Exact;
@@ -641,21 +1020,32 @@ impl<
             is_pv_node = false;
         }
 
+        // Fail-soft returns the best evaluation found even if it is less than
+        // alpha, which is still a valid upper bound at All-Nodes
+        let evaluation = if Self::SOFT_FAILING_STRATEGY && evaluation_bound == EvaluationType::UpperBound {
+            best_evaluation
+        } else {
+            alpha
+        };
+
         // In case of a UpperBound we store a null action, as the true best
         // action is unknown
-        self.store_transposition_table(game, depth, alpha, evaluation_bound, best_action);
+        if !is_restricted_root {
+            self.store_transposition_table(game, depth, evaluation, evaluation_bound, best_action);
+        }
 
         // Cannot happen in Zero window search anyways since ply is 0
         if ply_from_root == 0 && !ZERO_WINDOW_SEARCH {
             self.best_action = Some(best_action);
-            self.best_evaluation = Some(alpha);
+            self.best_evaluation = Some(evaluation);
         }
 
         // Check assumptions
         // If we are in the first ply then the evaluation_bound has to be exact
-        // or iff aspiration windows are used can also be an upper bound
+        // or iff aspiration windows or a bounded window (e.g. MTD(f)) are used
+        // can also be an upper bound
         debug_assert!(
-            ply_from_root != 0 || evaluation_bound == EvaluationType::Exact || Self::ENABLE_ASPIRATION_WINDOWS,
+            ply_from_root != 0 || evaluation_bound == EvaluationType::Exact || Self::ENABLE_ASPIRATION_WINDOWS || !is_full_window,
             "[PVSWorker::principal_variation_search] Assert about ply_from_root: {}, evaluation_bound: {:?}, aspiration_windows: {}, alpha: {:?}.",
             ply_from_root,
             evaluation_bound,
@@ -663,9 +1053,9 @@ impl<
             alpha
         );
 
-        self.search_recorder.pop_state_with_value(alpha, alpha, beta, format!("Full Search ({ZERO_WINDOW_SEARCH})").as_str()); /* SEARCH RECORDER */
+        self.search_recorder.pop_state_with_value(evaluation, alpha, beta, format!("Full Search ({ZERO_WINDOW_SEARCH})").as_str()); /* SEARCH RECORDER */
 
-        Ok(alpha)
+        Ok(evaluation)
     }
 
     /// Does a Zero/Scout Window Search (ZWS) with the given parameters.
@@ -681,6 +1071,7 @@ impl<
     /// * `beta` - The upper bound.
     /// * `num_extensions` - The number of search extensions that have been
     ///     applied so far.
+    /// * `action_buffers` - The buffers for this and all following plies.
     ///
     /// # Returns
     ///
@@ -697,10 +1088,11 @@ impl<
         depth: usize,
         beta: i32,
         num_extensions: usize,
+        action_buffers: &mut [ActionBuffer],
     ) -> PlayerResult<i32> {
         let alpha = beta - 1;
 
-        self.principal_variation_search::<true>(game, ply_from_root, depth, alpha, beta, num_extensions)
+        self.principal_variation_search::<true>(game, ply_from_root, depth, alpha, beta, num_extensions, action_buffers)
     }
 
     /// Skips the current search if it is a phantom move.
@@ -714,6 +1106,7 @@ impl<
     /// * `beta` - The upper bound.
     /// * `num_extensions` - The number of search extensions that have been
     ///    applied so far.
+    /// * `action_buffers` - The buffers for this and all following plies.
     ///
     /// # Returns
     ///
@@ -723,6 +1116,7 @@ impl<
     ///
     /// Same as `principal_variation_search`
     #[inline]
+    #[allow(clippy::too_many_arguments)]
     #[rustfmt::skip]
     fn phantom_skip<const ZERO_WINDOW_SEARCH: bool>(
         &mut self,
@@ -732,6 +1126,7 @@ impl<
         alpha: i32,
         beta: i32,
         num_extensions: usize,
+        action_buffers: &mut [ActionBuffer],
     ) -> PlayerResult<i32> {
         game.do_action(ActionId::phantom(), true)?;
 
@@ -739,9 +1134,10 @@ impl<
             game,
             ply_from_root,
             depth,
-            alpha,
-            beta,
+            -beta,
+            -alpha,
             num_extensions,
+            action_buffers,
         )?;
 
         game.undo_action(ActionId::phantom(), true)?;
@@ -780,7 +1176,11 @@ impl<
         self.statistics.increment_leaf_nodes_searched(); /* STATISTICS */
 
         let color = if game.is_player_1() { 1 } else { -1 };
-        let evaluation = color * self.evaluator.evaluate_node(game);
+        let evaluation = if self.minimize_loss_margin && game.is_terminated() {
+            color * Self::loss_margin_evaluation(game)
+        } else {
+            color * self.evaluator.evaluate_node(game)
+        };
 
         // self.store_transposition_table(game, 0, evaluation, EvaluationType::Exact, ActionId::null());
 
@@ -795,6 +1195,36 @@ impl<
         evaluation
     }
 
+    /// Evaluates the given terminal game by its score difference. Won games
+    /// are still always evaluated higher than lost games, but a larger score
+    /// difference is preferred between games with the same winner.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The terminated game to evaluate.
+    ///
+    /// # Returns
+    ///
+    /// The evaluation of the game from the perspective of player 1 in the
+    /// last [`Self::LOSS_MARGIN_RANGE`] of the won or lost evaluations.
+    ///
+    /// # Complexity
+    ///
+    /// `𝒪(𝟣)`
+    fn loss_margin_evaluation(game: &Patchwork) -> i32 {
+        let termination = game.get_termination_result();
+        let score = termination.score().clamp(-Self::LOSS_MARGIN_RANGE, Self::LOSS_MARGIN_RANGE);
+
+        match termination.termination {
+            TerminationType::Player1Won => {
+                evaluator_constants::POSITIVE_INFINITY - Self::LOSS_MARGIN_RANGE + score.max(0)
+            }
+            TerminationType::Player2Won => {
+                evaluator_constants::NEGATIVE_INFINITY + Self::LOSS_MARGIN_RANGE + score.min(0)
+            }
+        }
+    }
+
     // ───────────────────────────────────── LATE MOVE PRUNING ─────────────────────────────────────
 
     /// Gets the late move pruning flags for the given action list.
@@ -1015,6 +1445,10 @@ impl<
         ply_from_root: usize,
         search_extensions: usize,
     ) -> bool {
+        if !Self::ENABLE_LATE_MOVE_REDUCTIONS {
+            return false;
+        }
+
         if action_index < Self::LMR_AMOUNT_FULL_DEPTH_ACTIONS {
             return false;
         }
@@ -1247,10 +1681,11 @@ impl<
         let mean_branching_factor = self.statistics.nodes_searched as f64 / (self.statistics.nodes_searched - self.statistics.leaf_nodes_searched) as f64;
         let player_1_pos = game.player_1.get_position();
         let player_2_pos = game.player_2.get_position();
+        let distance = game.time_board.distance_between_players();
 
         writeln!(writer, "───────────── Principal Variation Search Player ─────────────")?;
         writeln!(writer, "Features:            [{features}]")?;
-        writeln!(writer, "Depth:               {:?} started from (1: {}, 2: {}, type: {:?})", depth, player_1_pos, player_2_pos, game.turn_type)?;
+        writeln!(writer, "Depth:               {:?} started from (1: {}, 2: {}, distance: {}, type: {:?})", depth, player_1_pos, player_2_pos, distance, game.turn_type)?;
         writeln!(writer, "Time:                {:?}", std::time::Instant::now().duration_since(self.statistics.start_time))?;
         writeln!(writer, "Nodes searched:      {:?}", self.statistics.nodes_searched)?;
         writeln!(writer, "Branching factor:    {average_branching_factor:.2} AVG / {effective_branching_factor:.2} EFF / {mean_branching_factor:.2} MEAN")?;
@@ -1261,6 +1696,13 @@ impl<
         writeln!(writer, "Search Extensions:   {:?} SP, {:?} ST ({})", self.statistics.special_patch_extensions, self.statistics.special_tile_extensions, if Self::ENABLE_SEARCH_EXTENSIONS { "enabled" } else { "disabled" })?;
         writeln!(writer, "LMR (Fail/All):      {:?}/{:?} ({:.2}%)", self.statistics.late_move_reduction_fails, self.statistics.late_move_reductions, self.statistics.late_move_reduction_fail_rate() * 100.0)?;
         writeln!(writer, "LMP:                 {:?}", self.statistics.late_move_pruning)?;
+        writeln!(writer, "Search instability:  {:?}", self.statistics.search_instability)?;
+        if let Some(iteration) = self.iteration_time_predictor.get_iterations().last() {
+            writeln!(writer, "Iteration time:      {:?} (predicted {:?})", iteration.actual, iteration.predicted)?;
+        }
+        if let Some(placement_cache) = &self.placement_cache {
+            writeln!(writer, "Placement cache:     {:?} hits / {:?} misses ({:?} entries)", placement_cache.hits(), placement_cache.misses(), placement_cache.len())?;
+        }
         writeln!(writer, "Principal Variation: {pv_actions}")?;
         if Self::ENABLE_TRANSPOSITION_TABLE {
             self.transposition_table.statistics.write_statistics(writer)?;
@@ -1273,3 +1715,291 @@ impl<
         Ok(())
     }
 }
+
+/// The reusable buffers for the valid actions of a node and their scores.
+#[derive(Debug, Clone, Default)]
+struct ActionBuffer {
+    /// The valid actions of the node.
+    actions: Vec<ActionId>,
+    /// The scores of the actions used for the move ordering.
+    scores: Vec<f64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+    use transposition_table::Size;
+
+    use super::*;
+
+    // Transposition table disabled, fail-soft and no selectivity, so both
+    // searches have to explore exactly the same tree.
+    type TestWorker<'worker> = PVSWorker<'worker, false, 'd', true, false, false, false, false, false>;
+
+    fn get_test_positions() -> Vec<Patchwork> {
+        (0..4)
+            .map(|seed| {
+                let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+                for _ in 0..seed {
+                    let action = state.get_valid_actions()[0];
+                    state.do_action(action, false).unwrap();
+                }
+                state
+            })
+            .collect()
+    }
+
+    fn new_worker<'worker>() -> TestWorker<'worker> {
+        TestWorker::new(Arc::new(AtomicBool::new(false)), Arc::new(TranspositionTable::empty()))
+    }
+
+    #[test]
+    fn test_mtdf_converges_to_full_window_evaluation() {
+        for mut state in get_test_positions() {
+            for depth in 1..=2 {
+                let full_window_evaluation = new_worker()
+                    .bounded_search(&mut state, depth, TestWorker::MIN_ALPHA_BOUND, TestWorker::MAX_BETA_BOUND)
+                    .unwrap();
+
+                for first_guess in [-100, 0, full_window_evaluation, 100] {
+                    let mtdf_evaluation = new_worker().mtdf(&mut state, depth, first_guess).unwrap();
+
+                    assert_eq!(full_window_evaluation, mtdf_evaluation);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_bounded_search_returns_fail_soft_bounds() {
+        for mut state in get_test_positions() {
+            let evaluation = new_worker()
+                .bounded_search(&mut state, 2, TestWorker::MIN_ALPHA_BOUND, TestWorker::MAX_BETA_BOUND)
+                .unwrap();
+
+            let upper_bound = new_worker().bounded_search(&mut state, 2, evaluation + 10, evaluation + 11).unwrap();
+            // the best evaluation below alpha is returned instead of alpha itself
+            assert!(upper_bound < evaluation + 10 && upper_bound >= evaluation);
+
+            let lower_bound = new_worker().bounded_search(&mut state, 2, evaluation - 11, evaluation - 10).unwrap();
+            assert!(lower_bound >= evaluation - 10 && lower_bound <= evaluation);
+        }
+    }
+
+    #[test]
+    fn test_bounded_search_mirrors_window_at_phantom_actions() {
+        // Fail-hard so that a window that is not mirrored cannot be hidden by
+        // a fail-soft bound that happens to be exact.
+        type FailHardWorker<'worker> = PVSWorker<'worker, false, 'd', false, false, false, false, false, false>;
+
+        let search = |state: &mut Patchwork, alpha: i32, beta: i32| {
+            FailHardWorker::new(Arc::new(AtomicBool::new(false)), Arc::new(TranspositionTable::empty()))
+                .bounded_search(state, 1, alpha, beta)
+                .unwrap()
+        };
+
+        let mut phantom_positions = 0;
+        for state in get_test_positions() {
+            for action in state.get_valid_actions() {
+                let mut state = state.clone();
+                state.do_action(action, true).unwrap();
+                if !matches!(state.turn_type, TurnType::NormalPhantom | TurnType::SpecialPhantom) {
+                    continue;
+                }
+                phantom_positions += 1;
+
+                let evaluation = search(&mut state, FailHardWorker::MIN_ALPHA_BOUND, FailHardWorker::MAX_BETA_BOUND);
+                let bounded_evaluation = search(&mut state, evaluation - 5, evaluation + 1);
+
+                assert_eq!(evaluation, bounded_evaluation, "Wrong evaluation for {state}");
+            }
+        }
+        assert!(phantom_positions > 0, "No phantom position was found");
+    }
+
+    #[test]
+    fn test_bounded_search_without_reductions_does_not_depend_on_window() {
+        // late move reductions are disabled, otherwise a reduced search could
+        // fail differently depending on the window
+        for mut state in get_test_positions() {
+            for depth in 1..=3 {
+                let evaluation = new_worker()
+                    .bounded_search(&mut state, depth, TestWorker::MIN_ALPHA_BOUND, TestWorker::MAX_BETA_BOUND)
+                    .unwrap();
+                let bounded_evaluation = new_worker()
+                    .bounded_search(&mut state, depth, evaluation - 5, evaluation + 1)
+                    .unwrap();
+
+                assert_eq!(evaluation, bounded_evaluation, "Wrong evaluation at depth {depth} for {state}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_action_buffers_are_kept_between_searches() {
+        let mut state = get_test_positions().remove(0);
+        let mut worker = new_worker();
+
+        let evaluation = worker.bounded_search(&mut state, 3, TestWorker::MIN_ALPHA_BOUND, TestWorker::MAX_BETA_BOUND);
+        assert_eq!(TestWorker::MAX_DEPTH + 1, worker.action_buffers.len());
+        assert!(worker.action_buffers[..3].iter().all(|buffer| buffer.actions.capacity() > 0));
+        assert_eq!(
+            evaluation.unwrap(),
+            worker
+                .bounded_search(&mut state, 3, TestWorker::MIN_ALPHA_BOUND, TestWorker::MAX_BETA_BOUND)
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_search_instability_is_flagged_when_best_action_flips() {
+        // Same worker as above but with search statistics to check that the
+        // instability is surfaced there as well.
+        type StatisticsWorker<'worker> = PVSWorker<'worker, false, 'd', true, false, false, false, false, true>;
+
+        let search = |state: &Patchwork, depth: u8| {
+            let mut worker =
+                StatisticsWorker::new(Arc::new(AtomicBool::new(false)), Arc::new(TranspositionTable::empty()));
+            worker.set_max_depth(Some(depth));
+            let (action, _) = worker.search(state.clone()).unwrap().unwrap();
+            (action, worker.is_search_unstable(), worker.statistics.search_instability)
+        };
+
+        let mut flips = 0;
+        for state in get_test_positions() {
+            let (action, unstable, _) = search(&state, 1);
+            assert!(!unstable, "A single iteration cannot be unstable");
+
+            let mut previous_action = action;
+            for depth in 2..=3 {
+                let (action, unstable, statistics_unstable) = search(&state, depth);
+
+                assert_eq!(previous_action != action, unstable, "Wrong instability at depth {depth} for {state}");
+                assert_eq!(unstable, statistics_unstable);
+
+                flips += usize::from(unstable);
+                previous_action = action;
+            }
+        }
+        assert!(flips > 0, "The best action never flipped between depths");
+    }
+
+    #[test]
+    fn test_placement_cache_does_not_change_search() {
+        let search = |worker: &mut TestWorker<'_>, state: &Patchwork, depth: u8| {
+            worker.set_max_depth(Some(depth));
+            worker.search(state.clone()).unwrap().unwrap()
+        };
+
+        for state in get_test_positions() {
+            let uncached = search(&mut new_worker(), &state, 3);
+
+            let mut worker = new_worker();
+            worker.set_placement_cache(true);
+            assert_eq!(uncached, search(&mut worker, &state, 3));
+
+            let placement_cache = worker.get_placement_cache().unwrap();
+            let deep_search_entries = placement_cache.len();
+            assert!(placement_cache.hits() > 0);
+
+            // the cache is cleared at the start of every search so only the root placements are cached
+            search(&mut worker, &state, 1);
+            let placement_cache = worker.get_placement_cache().unwrap();
+            assert!(placement_cache.len() <= 3 && placement_cache.len() < deep_search_entries);
+            assert_eq!(placement_cache.len(), placement_cache.misses());
+        }
+    }
+
+    #[test]
+    fn test_minimize_loss_margin_plays_higher_scoring_losing_line() {
+        /// The outcome of the game under optimal play from the perspective
+        /// of player 1. Twice the score difference so that the winner of a
+        /// score tie is still preferred.
+        fn get_outcome(game: &Patchwork) -> i32 {
+            if game.is_terminated() {
+                let termination = game.get_termination_result();
+                let tie_break = match termination.termination {
+                    TerminationType::Player1Won => 1,
+                    TerminationType::Player2Won => -1,
+                };
+                return 2 * termination.score() + tie_break;
+            }
+
+            let outcomes = game.get_valid_actions().into_iter().map(|action| {
+                let mut next_state = game.clone();
+                next_state.do_action(action, false).unwrap();
+                get_outcome(&next_state)
+            });
+            if game.is_player_1() {
+                outcomes.max().unwrap()
+            } else {
+                outcomes.min().unwrap()
+            }
+        }
+
+        let search = |state: &Patchwork, minimize_loss_margin: bool| {
+            let mut worker = new_worker();
+            worker.set_max_depth(Some(16));
+            worker.set_minimize_loss_margin(minimize_loss_margin);
+            worker.search(state.clone()).unwrap().unwrap().0
+        };
+
+        let mut lost_positions = 0;
+        let mut improved_positions = 0;
+        for seed in 0..100 {
+            // play random moves until the game is almost over
+            let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+            let mut action_seed = seed;
+            while !state.is_terminated() && state.player_1.get_position().min(state.player_2.get_position()) < 50 {
+                state.do_action(state.get_seeded_random_action(action_seed), false).unwrap();
+                action_seed += 1;
+            }
+            if state.is_terminated() || state.get_valid_actions().len() == 1 {
+                continue;
+            }
+
+            // the outcome of every action from the perspective of the current player
+            let perspective = if state.is_player_1() { 1 } else { -1 };
+            let get_action_outcome = |action: ActionId| {
+                let mut next_state = state.clone();
+                next_state.do_action(action, false).unwrap();
+                perspective * get_outcome(&next_state)
+            };
+            let outcomes = state.get_valid_actions().into_iter().map(get_action_outcome).collect::<Vec<_>>();
+            let best_outcome = *outcomes.iter().max().unwrap();
+            if best_outcome > 0 || outcomes.iter().all(|outcome| *outcome == best_outcome) {
+                // only positions where every line loses but with different margins are interesting
+                continue;
+            }
+            lost_positions += 1;
+
+            assert_eq!(best_outcome, get_action_outcome(search(&state, true)), "Not the smallest loss for {state}");
+            improved_positions += usize::from(get_action_outcome(search(&state, false)) < best_outcome);
+        }
+
+        assert!(lost_positions > 0, "No lost position was found");
+        assert!(improved_positions > 0, "Win maximization always played the smallest loss as well");
+    }
+
+    #[test]
+    fn test_single_threaded_search_is_reproducible() {
+        type StatisticsWorker<'worker> = PVSWorker<'worker, false, 'e', false, true, true, true, true, true>;
+
+        let search = |state: &Patchwork| {
+            let transposition_table = Arc::new(TranspositionTable::new(Size::MiB(1), false));
+            let mut worker = StatisticsWorker::new(Arc::new(AtomicBool::new(false)), transposition_table);
+            worker.set_max_depth(Some(3));
+            let result = worker.search(state.clone()).unwrap().unwrap();
+            (result, worker.statistics.total_nodes_searched)
+        };
+
+        for state in get_test_positions() {
+            let (first_result, first_nodes) = search(&state);
+            let (second_result, second_nodes) = search(&state);
+
+            assert!(first_nodes > 0);
+            assert_eq!(first_nodes, second_nodes, "Different node counts for {state}");
+            assert_eq!(first_result, second_result);
+        }
+    }
+}
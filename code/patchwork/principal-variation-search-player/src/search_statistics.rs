@@ -5,6 +5,8 @@ pub struct SearchStatistics<const ACTIVE: bool> {
     pub nodes_searched_previous_iteration: usize,
     /// The number of nodes searched.
     pub nodes_searched: usize,
+    /// The number of nodes searched over all iterations of iterative deepening.
+    pub total_nodes_searched: usize,
     /// The number of leaf nodes searched.
     pub leaf_nodes_searched: usize,
     /// The time when the search started.
@@ -31,6 +33,9 @@ pub struct SearchStatistics<const ACTIVE: bool> {
     pub late_move_reduction_fails: usize,
     /// The number of times late move pruning was performed.
     pub late_move_pruning: usize,
+    /// Whether the best action changed between the last two completed
+    /// iterations of iterative deepening.
+    pub search_instability: bool,
 }
 
 impl<const ACTIVE: bool> Default for SearchStatistics<ACTIVE> {
@@ -38,6 +43,7 @@ impl<const ACTIVE: bool> Default for SearchStatistics<ACTIVE> {
         Self {
             nodes_searched_previous_iteration: 0,
             nodes_searched: 0,
+            total_nodes_searched: 0,
             leaf_nodes_searched: 0,
             start_time: std::time::Instant::now(),
             fail_high: 0,
@@ -51,6 +57,7 @@ impl<const ACTIVE: bool> Default for SearchStatistics<ACTIVE> {
             late_move_reductions: 0,
             late_move_reduction_fails: 0,
             late_move_pruning: 0,
+            search_instability: false,
         }
     }
 }
@@ -63,6 +70,7 @@ impl<const ACTIVE: bool> SearchStatistics<ACTIVE> {
         }
         self.nodes_searched_previous_iteration = 0;
         self.nodes_searched = 0;
+        self.total_nodes_searched = 0;
         self.leaf_nodes_searched = 0;
         self.start_time = std::time::Instant::now();
         self.fail_high = 0;
@@ -76,6 +84,7 @@ impl<const ACTIVE: bool> SearchStatistics<ACTIVE> {
         self.late_move_reductions = 0;
         self.late_move_reduction_fails = 0;
         self.late_move_pruning = 0;
+        self.search_instability = false;
     }
 
     /// Resets some statistics after one iteration of iterative deepening.
@@ -126,6 +135,7 @@ impl<const ACTIVE: bool> SearchStatistics<ACTIVE> {
             return;
         }
         self.nodes_searched += 1;
+        self.total_nodes_searched += 1;
     }
 
     /// Increments the number of leaf nodes searched.
@@ -234,6 +244,16 @@ impl<const ACTIVE: bool> SearchStatistics<ACTIVE> {
         self.late_move_reduction_fails += 1;
     }
 
+    /// Sets whether the best action changed between the last two completed
+    /// iterations of iterative deepening.
+    #[inline]
+    pub const fn set_search_instability(&mut self, search_instability: bool) {
+        if !ACTIVE {
+            return;
+        }
+        self.search_instability = search_instability;
+    }
+
     /// Returns the rate of failing zero window searches in relation to the number of zero window searches.
     #[inline]
     #[must_use]
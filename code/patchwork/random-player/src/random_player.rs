@@ -1,7 +1,6 @@
-use crate::RandomOptions;
-use patchwork_core::{ActionId, Patchwork, Player, PlayerResult};
+use crate::{RandomOptions, RandomWeighting};
+use patchwork_core::{ActionId, GameRng, Patchwork, Player, PlayerResult};
 use rand::{seq::SliceRandom, SeedableRng};
-use rand_xoshiro::Xoshiro256PlusPlus;
 use anyhow::anyhow;
 
 /// A computer player that takes random actions.
@@ -10,7 +9,9 @@ pub struct RandomPlayer {
     /// The name of the player.
     name: String,
     /// The random number generator used to choose actions.
-    rng: Xoshiro256PlusPlus,
+    rng: GameRng,
+    /// How the valid actions are weighted when choosing one of them.
+    weighting: RandomWeighting,
 }
 
 impl RandomPlayer {
@@ -19,7 +20,8 @@ impl RandomPlayer {
         let options = options.unwrap_or_default();
         Self {
             name: name.into(),
-            rng: Xoshiro256PlusPlus::seed_from_u64(options.seed),
+            rng: GameRng::seed_from_u64(options.seed),
+            weighting: options.weighting,
         }
     }
 }
@@ -36,13 +38,127 @@ impl Player for RandomPlayer {
     }
 
     fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
-        game.get_valid_actions()
-            .choose(&mut self.rng)
+        self.get_restricted_action(game, &[])
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        let valid_actions = if actions.is_empty() {
+            game.get_valid_actions()
+        } else {
+            actions.to_vec()
+        };
+
+        if self.weighting == RandomWeighting::Uniform {
+            return valid_actions
+                .choose(&mut self.rng)
+                .copied()
+                .ok_or_else(|| anyhow!("No valid actions"));
+        }
+
+        let weighting = self.weighting;
+        valid_actions
+            .choose_weighted(&mut self.rng, |action| weighting.get_weight(*action))
             .copied()
-            .ok_or_else(|| anyhow!("No valid actions"))
+            .map_err(|error| anyhow!("No valid actions: {error}"))
 
         // let mut valid_actions = game.get_valid_actions().into_iter().collect::<Vec<_>>();
         // let random_index = self.rng.gen_range(0..valid_actions.len());
         // Ok(valid_actions.remove(random_index))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use super::*;
+
+    fn has_income(action: ActionId) -> bool {
+        RandomWeighting::ButtonIncome.get_weight(action) > 1
+    }
+
+    /// Gets an initial state where between a quarter and three quarters of
+    /// the valid actions place a patch with a button income.
+    fn get_state_with_differing_patch_values() -> Patchwork {
+        (0..1000)
+            .map(|seed| Patchwork::get_initial_state(Some(GameOptions::new(seed))))
+            .find(|state| {
+                let valid_actions = state.get_valid_actions();
+                let income_actions = valid_actions.iter().filter(|action| has_income(**action)).count();
+                (valid_actions.len()..=3 * valid_actions.len()).contains(&(4 * income_actions))
+            })
+            .unwrap()
+    }
+
+    /// Gets the share of the chosen actions that place a patch with a button income.
+    fn get_income_share(player: &mut RandomPlayer, state: &Patchwork, samples: usize) -> f64 {
+        let income_actions = (0..samples)
+            .map(|_| player.get_action(state).unwrap())
+            .filter(|action| has_income(*action))
+            .count();
+
+        income_actions as f64 / samples as f64
+    }
+
+    #[test]
+    fn test_weighting_makes_choice_non_uniform() {
+        let state = get_state_with_differing_patch_values();
+        let valid_actions = state.get_valid_actions();
+        let income_actions = valid_actions.iter().filter(|action| has_income(**action)).count();
+        let expected_uniform_share = income_actions as f64 / valid_actions.len() as f64;
+
+        let mut uniform = RandomPlayer::new("Uniform", Some(RandomOptions::new(42)));
+        let mut weighted = RandomPlayer::new(
+            "Weighted",
+            Some(RandomOptions {
+                weighting: RandomWeighting::ButtonIncome,
+                ..RandomOptions::new(42)
+            }),
+        );
+
+        let uniform_share = get_income_share(&mut uniform, &state, 5000);
+        let weighted_share = get_income_share(&mut weighted, &state, 5000);
+
+        assert!((uniform_share - expected_uniform_share).abs() < 0.05);
+        assert!(
+            weighted_share > expected_uniform_share + 0.05,
+            "Weighted share {weighted_share} is not above uniform share {expected_uniform_share}"
+        );
+    }
+
+    #[test]
+    fn test_weighted_choice_is_seeded() {
+        let state = get_state_with_differing_patch_values();
+        let options = RandomOptions {
+            weighting: RandomWeighting::ButtonIncome,
+            ..RandomOptions::new(7)
+        };
+
+        let mut player_1 = RandomPlayer::new("Player 1", Some(options.clone()));
+        let mut player_2 = RandomPlayer::new("Player 2", Some(options));
+
+        for _ in 0..100 {
+            assert_eq!(player_1.get_action(&state).unwrap(), player_2.get_action(&state).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_restricted_action_is_within_the_given_actions() {
+        let state = get_state_with_differing_patch_values();
+        let valid_actions = state.get_valid_actions();
+        let actions = &valid_actions[valid_actions.len() / 2..];
+
+        for weighting in [RandomWeighting::Uniform, RandomWeighting::ButtonIncome] {
+            let mut player = RandomPlayer::new(
+                "Random Player",
+                Some(RandomOptions {
+                    weighting,
+                    ..RandomOptions::new(7)
+                }),
+            );
+            for _ in 0..100 {
+                assert!(actions.contains(&player.get_restricted_action(&state, actions).unwrap()));
+            }
+        }
+    }
+}
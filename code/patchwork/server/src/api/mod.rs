@@ -1,11 +1,18 @@
 use std::{collections::HashMap, net::SocketAddr};
 
-use crate::serialization::PatchworkState;
+use crate::{
+    comparison::compare_handler,
+    engine::bestmove_handler,
+    evaluation::evaluate_handler,
+    health::health_handler,
+    metrics::{metrics_handler, METRICS},
+    serialization::PatchworkState,
+};
 use axum::{
     extract::{
         self,
         ws::{Message, WebSocket, WebSocketUpgrade},
-        ConnectInfo, Path,
+        ConnectInfo, Path, Query,
     },
     http::StatusCode,
     response::IntoResponse,
@@ -14,7 +21,7 @@ use axum::{
 };
 use futures_util::{stream::StreamExt, SinkExt};
 use lazy_static::lazy_static;
-use patchwork_lib::{GameOptions, Patchwork};
+use patchwork_lib::{ActionId, GameOptions, Notation, Patchwork};
 use uuid::Uuid;
 
 #[derive(Debug, Clone, serde::Serialize)]
@@ -30,6 +37,16 @@ pub struct Options {
     seed: Option<u64>,
 }
 
+/// The options of a connection to the UPI WebSocket.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct UpiOptions {
+    /// The state in the patchwork notation to resume the game from. Defaults
+    /// to a new game.
+    position: Option<String>,
+    /// The seed of a new game. Ignored if a position is given.
+    seed: Option<u64>,
+}
+
 lazy_static! {
     static ref GAMES: std::sync::Mutex<HashMap<Uuid, RunningGame>> = std::sync::Mutex::new(HashMap::new());
 }
@@ -43,10 +60,17 @@ pub fn api_router() -> Router {
         // .route("/do_action(game_id, state)")
         // .route("/upi/:uuid", get(ws_handler)) // set_option player
         .route("/upi", get(ws_handler)) // set_option player
+        .route("/metrics", get(metrics_handler))
+        .route("/health", get(health_handler))
+        .route("/evaluate", post(evaluate_handler))
+        .route("/bestmove", post(bestmove_handler))
+        .route("/compare", post(compare_handler))
         .fallback_service(any(not_found))
 }
 
 async fn game_handler(Path(uuid): Path<Uuid>, payload: Option<extract::Json<Options>>) -> impl IntoResponse {
+    METRICS.record_game_served();
+
     if let Some(game) = GAMES.lock().unwrap().get(&uuid) {
         // existing game
         return Json(game.clone());
@@ -55,7 +79,7 @@ async fn game_handler(Path(uuid): Path<Uuid>, payload: Option<extract::Json<Opti
     // new game
     let new_game = RunningGame {
         state: PatchworkState(Patchwork::get_initial_state(
-            payload.and_then(|o| o.seed).map(|seed| GameOptions { seed }),
+            payload.and_then(|o| o.seed).map(GameOptions::new),
         )),
         player_1: "player_1".to_string(),
         player_2: "player_2".to_string(),
@@ -66,18 +90,27 @@ async fn game_handler(Path(uuid): Path<Uuid>, payload: Option<extract::Json<Opti
     Json(new_game)
 }
 
-async fn ws_handler(ws: WebSocketUpgrade, ConnectInfo(addr): ConnectInfo<SocketAddr>) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, addr))
+async fn ws_handler(
+    ws: WebSocketUpgrade,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(options): Query<UpiOptions>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let session = UpiSession::new(&options)?;
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, addr, session)))
 }
 
-async fn handle_socket(socket: WebSocket, who: SocketAddr) {
+async fn handle_socket(socket: WebSocket, who: SocketAddr, mut session: UpiSession) {
     let (mut sender, mut receiver) = socket.split();
 
     while let Some(Ok(msg)) = receiver.next().await {
         match msg {
-            Message::Text(_) => {
-                println!("Received message from `{who}`: {msg:?}");
-                let _ = sender.send(Message::Text("Hello!".to_string())).await;
+            Message::Text(text) => {
+                let _search = METRICS.start_search("upi");
+                println!("Received message from `{who}`: {text:?}");
+                let response = session.handle_message(&text);
+                if sender.send(Message::Text(response)).await.is_err() {
+                    break;
+                }
             }
             Message::Binary(_) | Message::Ping(_) | Message::Pong(_) => {}
             Message::Close(_) => {
@@ -87,6 +120,150 @@ async fn handle_socket(socket: WebSocket, who: SocketAddr) {
     }
 }
 
+/// The game played over one connection to the UPI WebSocket.
+///
+/// Every text message is answered with exactly one text message:
+///
+/// * `position` - The current state in the patchwork notation.
+/// * `validactions` - The valid actions in the action notation separated by spaces.
+/// * Any other message is taken as an action in the action notation. The
+///   action is taken and the new state is sent back. Invalid actions are
+///   answered with `error <reason>` and do not change the state.
+#[derive(Debug, Clone)]
+struct UpiSession {
+    /// The current state of the game.
+    state: Patchwork,
+}
+
+impl UpiSession {
+    /// Creates a new session either from the given position or a new game.
+    ///
+    /// # Errors
+    ///
+    /// `400 Bad Request` if the position is not a valid state or the game is
+    /// already over.
+    fn new(options: &UpiOptions) -> Result<Self, (StatusCode, String)> {
+        let Some(position) = &options.position else {
+            return Ok(Self {
+                state: Patchwork::get_initial_state(options.seed.map(GameOptions::new)),
+            });
+        };
+
+        let state = Patchwork::load_from_notation(position)
+            .and_then(|state| state.check_invariants().map(|()| state))
+            .map_err(|error| {
+                (
+                    StatusCode::BAD_REQUEST,
+                    format!("Invalid position '{position}': {error}"),
+                )
+            })?;
+        if state.is_terminated() {
+            return Err((StatusCode::BAD_REQUEST, "The game is already over".to_string()));
+        }
+
+        Ok(Self { state })
+    }
+
+    /// Handles one message of the client and returns the response.
+    fn handle_message(&mut self, message: &str) -> String {
+        match message.trim() {
+            "position" => self.position(),
+            "validactions" => self
+                .state
+                .get_valid_actions()
+                .into_iter()
+                .map(|action| action.save_to_notation().unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(" "),
+            action => {
+                let result = ActionId::load_from_notation(action)
+                    .and_then(|action| self.state.do_action_validated(action, false));
+                match result {
+                    Ok(()) => self.position(),
+                    Err(error) => format!("error {error}"),
+                }
+            }
+        }
+    }
+
+    /// The current state in the patchwork notation.
+    fn position(&self) -> String {
+        self.state
+            .save_to_notation()
+            .unwrap_or_else(|error| format!("error {error}"))
+    }
+}
+
 async fn not_found() -> impl IntoResponse {
     (StatusCode::NOT_FOUND, "404 Not Found")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mid_game_state(seed: u64, plies: usize) -> Patchwork {
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+        for _ in 0..plies {
+            let action = state.get_seeded_random_action(seed);
+            state.do_action(action, false).unwrap();
+        }
+        state
+    }
+
+    #[test]
+    fn test_resumed_game_continues_from_position() {
+        let mut expected = mid_game_state(42, 20);
+        let options = UpiOptions {
+            position: Some(expected.save_to_notation().unwrap()),
+            seed: None,
+        };
+        let mut session = UpiSession::new(&options).unwrap();
+        assert_eq!(expected.save_to_notation().unwrap(), session.handle_message("position"));
+
+        while !expected.is_terminated() {
+            let valid_actions = session.handle_message("validactions");
+            let action = expected.get_seeded_random_action(7);
+            let notation = action.save_to_notation().unwrap();
+            assert!(valid_actions.split(' ').any(|valid| valid == notation));
+
+            expected.do_action(action, false).unwrap();
+            assert_eq!(expected.save_to_notation().unwrap(), session.handle_message(&notation));
+        }
+    }
+
+    #[test]
+    fn test_invalid_actions_do_not_change_the_state() {
+        let state = mid_game_state(3, 10);
+        let options = UpiOptions {
+            position: Some(state.save_to_notation().unwrap()),
+            seed: None,
+        };
+        let mut session = UpiSession::new(&options).unwrap();
+
+        assert!(session.handle_message("not an action").starts_with("error"));
+        assert_eq!(state.save_to_notation().unwrap(), session.handle_message("position"));
+    }
+
+    #[test]
+    fn test_invalid_positions_are_rejected() {
+        let options = UpiOptions {
+            position: Some("invalid".to_string()),
+            seed: None,
+        };
+        let (status, _) = UpiSession::new(&options).unwrap_err();
+        assert_eq!(StatusCode::BAD_REQUEST, status);
+
+        let mut terminated = mid_game_state(5, 0);
+        while !terminated.is_terminated() {
+            let action = terminated.get_seeded_random_action(5);
+            terminated.do_action(action, false).unwrap();
+        }
+        let options = UpiOptions {
+            position: Some(terminated.save_to_notation().unwrap()),
+            seed: None,
+        };
+        let (status, _) = UpiSession::new(&options).unwrap_err();
+        assert_eq!(StatusCode::BAD_REQUEST, status);
+    }
+}
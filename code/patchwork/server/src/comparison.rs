@@ -0,0 +1,171 @@
+use std::sync::Mutex;
+
+use axum::{http::StatusCode, Json};
+use patchwork_lib::compare::{run_compare, CompareResult};
+
+use crate::{
+    engine::{Engine, ENGINE},
+    metrics::METRICS,
+};
+
+/// The default amount of games of a comparison.
+pub const DEFAULT_COMPARE_GAMES: usize = 10;
+/// The maximum amount of games that can be played with one request.
+pub const MAX_COMPARE_GAMES: usize = 100;
+
+/// The request to compare two engines by playing games between them.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct CompareRequest {
+    /// The engine playing as player 1 (`random`, `greedy` or `mcts`).
+    player_1: String,
+    /// The engine playing as player 2 (`random`, `greedy` or `mcts`).
+    player_2: String,
+    /// The amount of iterations for the MCTS engines.
+    iterations: Option<usize>,
+    /// The amount of games to play. Defaults to [`DEFAULT_COMPARE_GAMES`].
+    games: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CompareResponse {
+    player_1: String,
+    player_2: String,
+    games: u32,
+    wins_player_1: u32,
+    wins_player_2: u32,
+    /// The amount of games where both players had the same score.
+    score_ties: u32,
+    average_score_player_1: f64,
+    average_score_player_2: f64,
+    /// The average amount of plies per game.
+    average_plies: f64,
+}
+
+impl CompareResponse {
+    fn new(player_1: Engine, player_2: Engine, result: &CompareResult) -> Self {
+        let games = f64::from(result.games().max(1));
+        Self {
+            player_1: player_1.name().to_string(),
+            player_2: player_2.name().to_string(),
+            games: result.games(),
+            wins_player_1: result.wins_player_1,
+            wins_player_2: result.wins_player_2,
+            score_ties: result.score_ties,
+            average_score_player_1: f64::from(result.sum_player_1_score) / games,
+            average_score_player_2: f64::from(result.sum_player_2_score) / games,
+            average_plies: result.average_plies(),
+        }
+    }
+}
+
+/// Plays games between two engines and responds with the result of the
+/// comparison. The games are played one after another on the [`ENGINE`] so
+/// that the async runtime is not blocked.
+///
+/// Responds with `400 Bad Request` if an engine is unknown or the amount of
+/// games is not between 1 and [`MAX_COMPARE_GAMES`] and with
+/// `500 Internal Server Error` if a game failed.
+pub async fn compare_handler(
+    Json(request): Json<CompareRequest>,
+) -> Result<Json<CompareResponse>, (StatusCode, String)> {
+    let player_1 =
+        Engine::parse(&request.player_1, request.iterations).map_err(|error| (StatusCode::BAD_REQUEST, error))?;
+    let player_2 =
+        Engine::parse(&request.player_2, request.iterations).map_err(|error| (StatusCode::BAD_REQUEST, error))?;
+    let games = request.games.unwrap_or(DEFAULT_COMPARE_GAMES);
+    if !(1..=MAX_COMPARE_GAMES).contains(&games) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Between 1 and {MAX_COMPARE_GAMES} games can be played at once"),
+        ));
+    }
+
+    let _search = METRICS.start_search("compare");
+    let result = ENGINE
+        .run(move || {
+            run_compare(
+                games,
+                move || Ok(player_1.create_player()),
+                move || Ok(player_2.create_player()),
+                1,
+                false,
+                None,
+                false,
+                &Mutex::new(vec![]),
+                &CompareResult::new(),
+                std::time::Duration::from_millis(10),
+                |_, _| Ok(()),
+            )
+            .map_err(|error| error.to_string())
+        })
+        .await
+        .and_then(|result| result)
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error))?;
+
+    Ok(Json(CompareResponse::new(player_1, player_2, &result)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, Method},
+    };
+    use tower::ServiceExt;
+
+    use crate::create_app;
+
+    use super::*;
+
+    async fn post(body: serde_json::Value) -> (StatusCode, Vec<u8>) {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/compare")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = create_app(None).oneshot(request).await.unwrap();
+        let status = response.status();
+
+        (status, to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec())
+    }
+
+    #[test]
+    fn test_compare_plays_the_requested_games() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        rt.block_on(async {
+            let (status, body) = post(serde_json::json!({
+                "player_1": "greedy",
+                "player_2": "random",
+                "games": 3,
+            }))
+            .await;
+            assert_eq!(StatusCode::OK, status);
+
+            let response = serde_json::from_slice::<CompareResponse>(&body).unwrap();
+            assert_eq!("greedy", response.player_1);
+            assert_eq!("random", response.player_2);
+            assert_eq!(3, response.games);
+            assert_eq!(3, response.wins_player_1 + response.wins_player_2);
+            assert!(response.average_plies > 0.0);
+        });
+    }
+
+    #[test]
+    fn test_compare_rejects_invalid_requests() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        rt.block_on(async {
+            let (status, _) = post(serde_json::json!({ "player_1": "unknown", "player_2": "random" })).await;
+            assert_eq!(StatusCode::BAD_REQUEST, status);
+
+            for games in [0, MAX_COMPARE_GAMES + 1] {
+                let (status, _) =
+                    post(serde_json::json!({ "player_1": "random", "player_2": "random", "games": games })).await;
+                assert_eq!(StatusCode::BAD_REQUEST, status);
+            }
+        });
+    }
+}
@@ -0,0 +1,330 @@
+use std::{num::NonZeroUsize, sync::Arc};
+
+use axum::{http::StatusCode, Json};
+use lazy_static::lazy_static;
+use patchwork_lib::{
+    evaluator::StaticEvaluator,
+    player::{GreedyPlayer, Logging, MCTSEndCondition, MCTSOptions, MCTSPlayer, Player, RandomPlayer},
+    tree_policy::UCTPolicy,
+    ActionId, Notation, Patchwork,
+};
+use tokio::sync::Semaphore;
+
+use crate::metrics::METRICS;
+
+/// The default amount of iterations of the MCTS engine.
+pub const DEFAULT_MCTS_ITERATIONS: usize = 1000;
+/// The maximum amount of iterations of the MCTS engine for one request.
+pub const MAX_MCTS_ITERATIONS: usize = 100_000;
+
+lazy_static! {
+    /// The engine handle all searches of the server are run on.
+    pub static ref ENGINE: EngineHandle = EngineHandle::new(
+        std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)
+    );
+}
+
+/// A handle to run the synchronous and CPU-heavy searches of the engines
+/// without blocking the threads of the async runtime.
+///
+/// Every search is run on the blocking thread pool of tokio. At most
+/// `max_concurrent_searches` searches run at the same time, further searches
+/// wait asynchronously until a search finished.
+#[derive(Debug, Clone)]
+pub struct EngineHandle {
+    /// The permits for the searches that may run at the same time.
+    permits: Arc<Semaphore>,
+}
+
+impl EngineHandle {
+    /// Creates a new [`EngineHandle`].
+    ///
+    /// # Arguments
+    ///
+    /// * `max_concurrent_searches` - The maximum amount of searches that run at the same time.
+    ///
+    /// # Returns
+    ///
+    /// The new [`EngineHandle`].
+    #[must_use]
+    pub fn new(max_concurrent_searches: NonZeroUsize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(max_concurrent_searches.get())),
+        }
+    }
+
+    /// Runs the given search on the blocking thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `search` - The search to run.
+    ///
+    /// # Returns
+    ///
+    /// The result of the search.
+    ///
+    /// # Errors
+    ///
+    /// If the search panicked or the handle was closed.
+    pub async fn run<T, Search>(&self, search: Search) -> Result<T, String>
+    where
+        T: Send + 'static,
+        Search: FnOnce() -> T + Send + 'static,
+    {
+        let permit = Arc::clone(&self.permits)
+            .acquire_owned()
+            .await
+            .map_err(|_| "[EngineHandle::run] The engine handle was closed".to_string())?;
+
+        tokio::task::spawn_blocking(move || {
+            // the permit is released when the search finished even if the caller is no longer waiting
+            let _permit = permit;
+            search()
+        })
+        .await
+        .map_err(|_| "[EngineHandle::run] The search panicked".to_string())
+    }
+
+    /// Gets the action the given engine plays in the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The engine to search with.
+    /// * `state` - The state to search.
+    ///
+    /// # Returns
+    ///
+    /// The action of the engine.
+    ///
+    /// # Errors
+    ///
+    /// If the search failed or panicked.
+    pub async fn get_action(&self, engine: Engine, state: Patchwork) -> Result<ActionId, String> {
+        self.run(move || engine.create_player().get_action(&state).map_err(|error| error.to_string()))
+            .await?
+    }
+}
+
+/// The engines that can be used to search for the best move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Engine {
+    /// A random player.
+    Random,
+    /// A greedy player with the static evaluator.
+    Greedy,
+    /// A single threaded MCTS player with the given amount of iterations.
+    Mcts { iterations: usize },
+}
+
+impl Engine {
+    /// Parses the engine with the given name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the engine (`random`, `greedy` or `mcts`).
+    /// * `iterations` - The amount of iterations for the MCTS engine.
+    ///
+    /// # Returns
+    ///
+    /// The engine or an error message if the name is unknown.
+    pub fn parse(name: &str, iterations: Option<usize>) -> Result<Self, String> {
+        match name {
+            "random" => Ok(Self::Random),
+            "greedy" => Ok(Self::Greedy),
+            "mcts" => Ok(Self::Mcts {
+                iterations: iterations.unwrap_or(DEFAULT_MCTS_ITERATIONS).clamp(1, MAX_MCTS_ITERATIONS),
+            }),
+            _ => Err(format!("Unknown engine '{name}'. Possible engines: random, greedy, mcts")),
+        }
+    }
+
+    /// Gets the name of the engine.
+    #[must_use]
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Random => "random",
+            Self::Greedy => "greedy",
+            Self::Mcts { .. } => "mcts",
+        }
+    }
+
+    pub(crate) fn create_player(self) -> Box<dyn Player> {
+        match self {
+            Self::Random => Box::new(RandomPlayer::new("Random", None)),
+            Self::Greedy => Box::new(GreedyPlayer::<StaticEvaluator>::new("Greedy", None)),
+            Self::Mcts { iterations } => Box::new(MCTSPlayer::<UCTPolicy, StaticEvaluator>::new(
+                "MCTS",
+                Some(MCTSOptions::new(
+                    NonZeroUsize::MIN,
+                    NonZeroUsize::MIN,
+                    MCTSEndCondition::Iterations(iterations),
+                    false,
+                    Logging::Disabled,
+                )),
+            )),
+        }
+    }
+}
+
+/// The request to search the best move in a state.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BestMoveRequest {
+    /// The state in the patchwork notation.
+    state: String,
+    /// The engine to use (`random`, `greedy` or `mcts`). Defaults to `greedy`.
+    engine: Option<String>,
+    /// The amount of iterations for the MCTS engine.
+    iterations: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct BestMoveResponse {
+    engine: String,
+    /// The best move in the action notation.
+    action: String,
+}
+
+/// Searches the best move in the given state with the chosen engine. The
+/// search is run on the [`ENGINE`] so that the async runtime is not blocked.
+///
+/// Responds with `400 Bad Request` if the engine is unknown or the state is
+/// invalid and with `500 Internal Server Error` if the search failed.
+pub async fn bestmove_handler(
+    Json(request): Json<BestMoveRequest>,
+) -> Result<Json<BestMoveResponse>, (StatusCode, String)> {
+    let engine = Engine::parse(request.engine.as_deref().unwrap_or("greedy"), request.iterations)
+        .map_err(|error| (StatusCode::BAD_REQUEST, error))?;
+    let state = Patchwork::load_from_notation(&request.state)
+        .map_err(|error| (StatusCode::BAD_REQUEST, format!("Invalid state '{}': {error}", request.state)))?;
+    if state.is_terminated() {
+        return Err((StatusCode::BAD_REQUEST, "The game is already over".to_string()));
+    }
+
+    let _search = METRICS.start_search(engine.name());
+    let action = ENGINE
+        .get_action(engine, state)
+        .await
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error))?;
+    let action = action
+        .save_to_notation()
+        .map_err(|error| (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()))?;
+
+    Ok(Json(BestMoveResponse {
+        engine: engine.name().to_string(),
+        action,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, time::Duration};
+
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, Method},
+    };
+    use futures_util::future::join_all;
+    use patchwork_lib::GameOptions;
+    use tower::ServiceExt;
+
+    use crate::create_app;
+
+    use super::*;
+
+    #[test]
+    fn test_blocked_searches_do_not_starve_the_runtime() {
+        const SEARCHES: usize = 8;
+
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let engine = EngineHandle::new(NonZeroUsize::new(SEARCHES).unwrap());
+
+        rt.block_on(async {
+            let mut senders = vec![];
+            let mut searches = vec![];
+            for index in 0..SEARCHES {
+                let (sender, receiver) = mpsc::channel::<()>();
+                senders.push(sender);
+                let engine = engine.clone();
+                searches.push(tokio::spawn(async move {
+                    engine
+                        .run(move || {
+                            receiver.recv().unwrap();
+                            index
+                        })
+                        .await
+                }));
+            }
+
+            // all searches are blocked but the runtime still serves requests
+            let request = Request::builder().uri("/api/health").body(Body::empty()).unwrap();
+            let response = tokio::time::timeout(Duration::from_secs(5), create_app(None).oneshot(request))
+                .await
+                .expect("The runtime is blocked by the searches")
+                .unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+
+            for sender in senders {
+                sender.send(()).unwrap();
+            }
+            let results = tokio::time::timeout(Duration::from_secs(5), join_all(searches)).await.unwrap();
+            for (index, result) in results.into_iter().enumerate() {
+                assert_eq!(Ok(index), result.unwrap());
+            }
+        });
+    }
+
+    #[test]
+    fn test_concurrent_bestmove_requests_complete() {
+        const REQUESTS: u64 = 16;
+
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        rt.block_on(async {
+            let requests = (0..REQUESTS).map(|seed| async move {
+                let state = Patchwork::get_initial_state(Some(GameOptions::new(seed)));
+                let engine = if seed % 2 == 0 { "greedy" } else { "mcts" };
+                let body = serde_json::json!({
+                    "state": state.save_to_notation().unwrap(),
+                    "engine": engine,
+                    "iterations": 50,
+                });
+                let request = Request::builder()
+                    .method(Method::POST)
+                    .uri("/api/bestmove")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap();
+                let response = create_app(None).oneshot(request).await.unwrap();
+                assert_eq!(StatusCode::OK, response.status());
+
+                let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                let response = serde_json::from_slice::<BestMoveResponse>(&body).unwrap();
+                assert_eq!(engine, response.engine);
+
+                let action = ActionId::load_from_notation(&response.action).unwrap();
+                assert!(state.get_valid_actions().contains(&action));
+            });
+
+            join_all(requests).await;
+        });
+    }
+
+    #[test]
+    fn test_bestmove_rejects_unknown_engine() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let state = Patchwork::get_initial_state(None).save_to_notation().unwrap();
+
+        rt.block_on(async {
+            let body = serde_json::json!({ "state": state, "engine": "unknown" });
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/api/bestmove")
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            let response = create_app(None).oneshot(request).await.unwrap();
+            assert_eq!(StatusCode::BAD_REQUEST, response.status());
+        });
+    }
+}
@@ -0,0 +1,172 @@
+use axum::{http::StatusCode, Json};
+use patchwork_lib::{
+    evaluator::{Evaluator, ScoreEvaluator, StaticEvaluator, WinLossEvaluator},
+    GameRecord, Notation, Patchwork,
+};
+
+/// The maximum amount of positions that can be evaluated with one request.
+pub const MAX_EVALUATION_POSITIONS: usize = 512;
+
+/// The request to evaluate either a list of states or every position of a
+/// recorded game.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct EvaluationRequest {
+    /// The evaluator to use (`static`, `win` or `score`). Defaults to `static`.
+    evaluator: Option<String>,
+    /// A list of states in the patchwork notation.
+    #[serde(default)]
+    states: Vec<String>,
+    /// A recorded game. Every position of the game from the initial state to
+    /// the final state is evaluated.
+    game: Option<GameRecord>,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PositionEvaluation {
+    /// The evaluation from the perspective of player 1.
+    evaluation: i32,
+    /// The probability that player 1 wins the game.
+    win_probability: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct EvaluationResponse {
+    evaluator: String,
+    evaluations: Vec<PositionEvaluation>,
+}
+
+/// Evaluates a list of states and every position of a recorded game with the
+/// chosen evaluator, e.g. to draw the evaluation bar of a whole game. The
+/// evaluations of the states come first.
+///
+/// Responds with `413 Payload Too Large` if more than
+/// [`MAX_EVALUATION_POSITIONS`] positions are given and with
+/// `400 Bad Request` if the evaluator is unknown or a position is invalid.
+pub async fn evaluate_handler(
+    Json(request): Json<EvaluationRequest>,
+) -> Result<Json<EvaluationResponse>, (StatusCode, String)> {
+    let evaluator_name = request.evaluator.unwrap_or_else(|| "static".to_string());
+    let evaluator: Box<dyn Evaluator> = match evaluator_name.as_str() {
+        "static" => Box::<StaticEvaluator>::default(),
+        "win" => Box::<WinLossEvaluator>::default(),
+        "score" => Box::<ScoreEvaluator>::default(),
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("Unknown evaluator '{evaluator_name}'. Possible evaluators: static, win, score"),
+            ))
+        }
+    };
+
+    let amount_of_positions = request.states.len() + request.game.as_ref().map_or(0, |game| game.len() + 1);
+    if amount_of_positions > MAX_EVALUATION_POSITIONS {
+        return Err((
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("At most {MAX_EVALUATION_POSITIONS} positions can be evaluated at once"),
+        ));
+    }
+
+    let positions = get_positions(&request.states, request.game).map_err(|error| (StatusCode::BAD_REQUEST, error))?;
+    let evaluations = positions
+        .iter()
+        .map(|state| PositionEvaluation {
+            evaluation: evaluator.evaluate_node(state),
+            win_probability: evaluator.win_probability(state),
+        })
+        .collect();
+
+    Ok(Json(EvaluationResponse { evaluator: evaluator_name, evaluations }))
+}
+
+fn get_positions(states: &[String], game: Option<GameRecord>) -> Result<Vec<Patchwork>, String> {
+    let mut positions = states
+        .iter()
+        .map(|notation| {
+            Patchwork::load_from_notation(notation).map_err(|error| format!("Invalid state '{notation}': {error}"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if let Some(game) = game {
+        let mut state = game.initial_state;
+        positions.push(state.clone());
+        for action in game.actions {
+            state.do_action_validated(action, false).map_err(|error| error.to_string())?;
+            positions.push(state.clone());
+        }
+    }
+
+    Ok(positions)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::{header, Method},
+    };
+    use patchwork_lib::GameOptions;
+    use tower::ServiceExt;
+
+    use crate::create_app;
+
+    use super::*;
+
+    async fn post(body: serde_json::Value) -> (StatusCode, Vec<u8>) {
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/api/evaluate")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = create_app(None).oneshot(request).await.unwrap();
+        let status = response.status();
+
+        (status, to_bytes(response.into_body(), usize::MAX).await.unwrap().to_vec())
+    }
+
+    #[test]
+    fn test_evaluate_returns_one_evaluation_per_position() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        let mut state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let mut game = GameRecord::new(state.clone());
+        for seed in 0..10 {
+            let action = state.get_seeded_random_action(seed);
+            state.do_action(action, false).unwrap();
+            game.push(action);
+        }
+
+        rt.block_on(async {
+            let (status, body) = post(serde_json::json!({ "evaluator": "static", "game": game })).await;
+            assert_eq!(StatusCode::OK, status);
+
+            let response = serde_json::from_slice::<EvaluationResponse>(&body).unwrap();
+            assert_eq!("static", response.evaluator);
+            assert_eq!(game.len() + 1, response.evaluations.len());
+
+            let positions = get_positions(&[], Some(game)).unwrap();
+            for (position, evaluation) in positions.iter().zip(&response.evaluations) {
+                assert_eq!(StaticEvaluator::new().evaluate_node(position), evaluation.evaluation);
+            }
+        });
+    }
+
+    #[test]
+    fn test_evaluate_rejects_too_many_positions() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let notation = Patchwork::get_initial_state(None).save_to_notation().unwrap();
+
+        rt.block_on(async {
+            let (status, _) = post(serde_json::json!({ "states": [notation, notation] })).await;
+            assert_eq!(StatusCode::OK, status);
+
+            let too_many_states = vec![notation; MAX_EVALUATION_POSITIONS + 1];
+            let (status, _) = post(serde_json::json!({ "states": too_many_states })).await;
+            assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, status);
+
+            let (status, _) = post(serde_json::json!({ "evaluator": "unknown", "states": [] })).await;
+            assert_eq!(StatusCode::BAD_REQUEST, status);
+        });
+    }
+}
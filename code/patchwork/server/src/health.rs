@@ -0,0 +1,59 @@
+use std::time::Instant;
+
+use axum::{response::IntoResponse, Json};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// The time the server was started.
+    pub static ref START_TIME: Instant = Instant::now();
+}
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct Health {
+    name: String,
+    version: String,
+    profile: String,
+    uptime_seconds: f64,
+}
+
+/// Serves the name, version, build profile and uptime of the server. This
+/// can be used as a liveness probe.
+pub async fn health_handler() -> impl IntoResponse {
+    Json(Health {
+        name: env!("CARGO_PKG_NAME").to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        profile: if cfg!(debug_assertions) { "debug" } else { "release" }.to_string(),
+        uptime_seconds: START_TIME.elapsed().as_secs_f64(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Request,
+        http::StatusCode,
+    };
+    use tower::ServiceExt;
+
+    use crate::create_app;
+
+    use super::*;
+
+    #[test]
+    fn test_health_endpoint_returns_version() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        rt.block_on(async {
+            let request = Request::builder().uri("/api/health").body(Body::empty()).unwrap();
+            let response = create_app(None).oneshot(request).await.unwrap();
+            assert_eq!(StatusCode::OK, response.status());
+
+            let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+            let health = serde_json::from_slice::<Health>(&body).unwrap();
+            assert_eq!("server", health.name);
+            assert_eq!(env!("CARGO_PKG_VERSION"), health.version);
+            assert!(health.uptime_seconds >= 0.0);
+        });
+    }
+}
@@ -1,7 +1,12 @@
-use axum::{http::Method, routing::get, Router};
-use std::{net::SocketAddr, time::Duration};
+use axum::{
+    http::{HeaderValue, Method},
+    middleware,
+    routing::get,
+    Router,
+};
+use std::{io, net::SocketAddr, time::Duration};
 use tower_http::{
-    cors::{Any, CorsLayer},
+    cors::{AllowOrigin, Any, CorsLayer},
     timeout::TimeoutLayer,
     trace::TraceLayer,
 };
@@ -9,14 +14,35 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use crate::{
     api::api_router,
+    health::START_TIME,
+    metrics::track_requests,
     web::{index_handler, web_handler},
 };
 
 mod api;
+mod comparison;
+mod engine;
+mod evaluation;
+mod health;
+mod metrics;
 mod serialization;
 mod web;
 
-pub fn start_server(port: Option<u16>, public: bool) -> tokio::io::Result<()> {
+/// Starts the patchwork server and blocks until it is shut down.
+///
+/// # Arguments
+///
+/// * `port` - The port to listen on. Defaults to 3000.
+/// * `public` - Whether to listen on all interfaces instead of only on localhost.
+/// * `allowed_origins` - The origins that are allowed to make cross-origin requests. `*` allows any origin. If
+///   empty, cross-origin requests are not allowed.
+///
+/// # Errors
+///
+/// If the runtime could not be created or an allowed origin is not a valid header value.
+pub fn start_server(port: Option<u16>, public: bool, allowed_origins: &[String]) -> tokio::io::Result<()> {
+    let cors = create_cors_layer(allowed_origins)?;
+    lazy_static::initialize(&START_TIME);
     let rt = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
 
     rt.block_on(async {
@@ -32,21 +58,7 @@ pub fn start_server(port: Option<u16>, public: bool) -> tokio::io::Result<()> {
             .with(tracing_subscriber::fmt::layer().without_time())
             .init();
 
-        let mut app = Router::new()
-            .route("/", get(index_handler))
-            .route("/index.html", get(index_handler))
-            .nest("/api", api_router())
-            .fallback_service(get(web_handler))
-            .layer((TraceLayer::new_for_http(), TimeoutLayer::new(Duration::from_secs(10))));
-
-        if cfg!(debug_assertions) {
-            let cors = CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods([Method::GET, Method::POST, Method::OPTIONS, Method::CONNECT])
-                .allow_headers(Any);
-            app = app.layer(cors);
-        }
-
+        let app = create_app(cors);
         let listener = tokio::net::TcpListener::bind(addr.to_string()).await.unwrap();
 
         println!("Starting patchwork server on {addr}");
@@ -58,6 +70,51 @@ pub fn start_server(port: Option<u16>, public: bool) -> tokio::io::Result<()> {
     Ok(())
 }
 
+fn create_app(cors: Option<CorsLayer>) -> Router {
+    let app = Router::new()
+        .route("/", get(index_handler))
+        .route("/index.html", get(index_handler))
+        .nest("/api", api_router())
+        .fallback_service(get(web_handler))
+        .layer((
+            TraceLayer::new_for_http(),
+            TimeoutLayer::new(Duration::from_secs(10)),
+            middleware::from_fn(track_requests),
+        ));
+
+    match cors {
+        Some(cors) => app.layer(cors),
+        None => app,
+    }
+}
+
+fn create_cors_layer(allowed_origins: &[String]) -> io::Result<Option<CorsLayer>> {
+    if allowed_origins.is_empty() {
+        return Ok(None);
+    }
+
+    let allow_origin = if allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        let origins = allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(origin).map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidInput, format!("Invalid allowed origin '{origin}'"))
+                })
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        AllowOrigin::list(origins)
+    };
+
+    Ok(Some(
+        CorsLayer::new()
+            .allow_origin(allow_origin)
+            .allow_methods([Method::GET, Method::POST, Method::OPTIONS, Method::CONNECT])
+            .allow_headers(Any),
+    ))
+}
+
 #[allow(clippy::redundant_pub_crate)]
 async fn shutdown_signal() {
     let ctrl_c = async {
@@ -83,3 +140,42 @@ async fn shutdown_signal() {
     println!("Received CTRL-C command. Exiting application...");
     std::process::exit(0);
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::Body, extract::Request, http::header};
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn get_allow_origin(allowed_origins: &[String], origin: &str) -> Option<HeaderValue> {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let app = create_app(create_cors_layer(allowed_origins).unwrap());
+        let request =
+            Request::builder().uri("/api/metrics").header(header::ORIGIN, origin).body(Body::empty()).unwrap();
+
+        let response = rt.block_on(app.oneshot(request)).unwrap();
+        response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).cloned()
+    }
+
+    #[test]
+    fn test_cors_header_only_for_allowed_origins() {
+        let allowed_origins = vec!["https://patchwork.example".to_string()];
+
+        assert_eq!(
+            Some(HeaderValue::from_static("https://patchwork.example")),
+            get_allow_origin(&allowed_origins, "https://patchwork.example")
+        );
+        assert_eq!(None, get_allow_origin(&allowed_origins, "https://evil.example"));
+        assert_eq!(None, get_allow_origin(&[], "https://patchwork.example"));
+        assert_eq!(
+            Some(HeaderValue::from_static("*")),
+            get_allow_origin(&["*".to_string()], "https://evil.example")
+        );
+    }
+
+    #[test]
+    fn test_invalid_origin_is_rejected() {
+        assert!(create_cors_layer(&["https://patchwork.example\n".to_string()]).is_err());
+    }
+}
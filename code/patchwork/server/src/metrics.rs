@@ -0,0 +1,208 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::Request,
+    http::header,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use lazy_static::lazy_static;
+
+lazy_static! {
+    /// The registry of all metrics of the server.
+    pub static ref METRICS: Metrics = Metrics::default();
+}
+
+/// The registry of the metrics of the server that can be scraped in the
+/// Prometheus text format.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    /// The amount of HTTP requests handled.
+    http_requests: AtomicU64,
+    /// The amount of games served.
+    games_served: AtomicU64,
+    /// The amount of searches that are currently running.
+    active_searches: AtomicU64,
+    /// The amount of finished searches.
+    finished_searches: AtomicU64,
+    /// The total time of all finished searches in microseconds.
+    search_time_micros: AtomicU64,
+    /// The amount of requests per engine.
+    engine_requests: Mutex<BTreeMap<String, u64>>,
+}
+
+/// A running search that is tracked by the [`Metrics`]. The search is
+/// finished when the guard is dropped.
+#[derive(Debug)]
+pub struct SearchGuard<'a> {
+    /// The metrics the search is tracked by.
+    metrics: &'a Metrics,
+    /// The time the search started.
+    start_time: Instant,
+}
+
+impl Metrics {
+    /// Records that a game was served.
+    pub fn record_game_served(&self) {
+        self.games_served.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records a request to the given engine and starts tracking the search
+    /// for it.
+    ///
+    /// # Arguments
+    ///
+    /// * `engine` - The name of the engine the request is for.
+    ///
+    /// # Returns
+    ///
+    /// The guard that finishes the search when it is dropped.
+    pub fn start_search(&self, engine: &str) -> SearchGuard<'_> {
+        *self.engine_requests.lock().unwrap().entry(engine.to_string()).or_default() += 1;
+        self.active_searches.fetch_add(1, Ordering::Relaxed);
+
+        SearchGuard { metrics: self, start_time: Instant::now() }
+    }
+
+    /// Records that a search finished after the given time.
+    fn finish_search(&self, search_time: Duration) {
+        self.active_searches.fetch_sub(1, Ordering::Relaxed);
+        self.finished_searches.fetch_add(1, Ordering::Relaxed);
+        self.search_time_micros
+            .fetch_add(u64::try_from(search_time.as_micros()).unwrap_or(u64::MAX), Ordering::Relaxed);
+    }
+
+    /// Renders all metrics in the Prometheus text format.
+    ///
+    /// # Returns
+    ///
+    /// The metrics in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let finished_searches = self.finished_searches.load(Ordering::Relaxed);
+        #[allow(clippy::cast_precision_loss)]
+        let average_search_time = if finished_searches == 0 {
+            0.0
+        } else {
+            self.search_time_micros.load(Ordering::Relaxed) as f64 / finished_searches as f64 / 1_000_000.0
+        };
+
+        let mut output = String::new();
+        let mut write_metric = |name: &str, kind: &str, help: &str, values: &[(String, String)]| {
+            let _ = writeln!(output, "# HELP {name} {help}");
+            let _ = writeln!(output, "# TYPE {name} {kind}");
+            for (labels, value) in values {
+                let _ = writeln!(output, "{name}{labels} {value}");
+            }
+        };
+
+        write_metric(
+            "patchwork_http_requests_total",
+            "counter",
+            "The total number of HTTP requests handled.",
+            &[(String::new(), self.http_requests.load(Ordering::Relaxed).to_string())],
+        );
+        write_metric(
+            "patchwork_games_served_total",
+            "counter",
+            "The total number of games served.",
+            &[(String::new(), self.games_served.load(Ordering::Relaxed).to_string())],
+        );
+        write_metric(
+            "patchwork_active_searches",
+            "gauge",
+            "The number of searches that are currently running.",
+            &[(String::new(), self.active_searches.load(Ordering::Relaxed).to_string())],
+        );
+        write_metric(
+            "patchwork_search_time_seconds_average",
+            "gauge",
+            "The average time of the finished searches in seconds.",
+            &[(String::new(), average_search_time.to_string())],
+        );
+        write_metric(
+            "patchwork_engine_requests_total",
+            "counter",
+            "The total number of requests per engine.",
+            &self
+                .engine_requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(engine, requests)| (format!("{{engine=\"{engine}\"}}"), requests.to_string()))
+                .collect::<Vec<_>>(),
+        );
+
+        output
+    }
+}
+
+impl Drop for SearchGuard<'_> {
+    fn drop(&mut self) {
+        self.metrics.finish_search(self.start_time.elapsed());
+    }
+}
+
+/// The middleware that counts every request handled by the server.
+pub async fn track_requests(request: Request, next: Next) -> Response {
+    METRICS.http_requests.fetch_add(1, Ordering::Relaxed);
+    next.run(request).await
+}
+
+/// Serves the metrics in the Prometheus text format.
+pub async fn metrics_handler() -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], METRICS.render())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Method, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    use crate::create_app;
+
+    use super::*;
+
+    async fn send(method: Method, uri: &str) -> (StatusCode, String) {
+        let request = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+        let response = create_app(None).oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[test]
+    fn test_metrics_endpoint_exposes_metrics() {
+        let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+
+        rt.block_on(async {
+            let (status, _) = send(Method::POST, "/api/game/67e55044-10b1-426f-9247-bb680e5fe0c8").await;
+            assert_eq!(StatusCode::OK, status);
+            drop(METRICS.start_search("test"));
+
+            let (status, metrics) = send(Method::GET, "/api/metrics").await;
+            assert_eq!(StatusCode::OK, status);
+            for name in [
+                "patchwork_http_requests_total",
+                "patchwork_games_served_total",
+                "patchwork_active_searches",
+                "patchwork_search_time_seconds_average",
+                "patchwork_engine_requests_total{engine=\"test\"}",
+            ] {
+                assert!(metrics.contains(&format!("{name} ")), "missing metric '{name}' in:\n{metrics}");
+            }
+            assert!(!metrics.contains("patchwork_games_served_total 0"));
+        });
+    }
+}
@@ -2,7 +2,7 @@ use std::fmt;
 
 use serde::ser::{SerializeSeq, SerializeStruct};
 
-use patchwork_lib::{time_board_flags, Patch, PatchManager, Patchwork, PlayerState, QuiltBoard, TimeBoard};
+use patchwork_lib::{time_board_flags, Patch, PatchCircle, PatchManager, Patchwork, PlayerState, QuiltBoard, TimeBoard};
 
 pub struct PatchworkState(pub Patchwork);
 
@@ -207,7 +207,7 @@ impl serde::Serialize for PatchSerialization {
 }
 
 struct PatchesSerialization<'a> {
-    patches: &'a Vec<&'static Patch>,
+    patches: &'a PatchCircle,
 }
 
 impl serde::Serialize for PatchesSerialization<'_> {
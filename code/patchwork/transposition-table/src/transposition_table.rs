@@ -401,6 +401,10 @@ impl TranspositionTable {
 
     /// Gets the principal variation line from the transposition table.
     ///
+    /// The line is truncated before the first action that is not legal in the
+    /// state reached by the previous actions (e.g. because of an index
+    /// collision), so only legal actions are returned.
+    ///
     /// # Arguments
     ///
     /// * `game` - The game state to get the PV line for.
@@ -424,6 +428,11 @@ impl TranspositionTable {
                     break;
                 }
 
+                // every PV action has to be legal in the state reached by the previous PV actions
+                if !current_game.get_valid_actions().contains(&action) {
+                    break;
+                }
+
                 let game_clone = current_game.clone();
 
                 let result = current_game.do_action(action, true);
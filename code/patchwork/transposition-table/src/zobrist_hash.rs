@@ -1,10 +1,11 @@
-use patchwork_core::{PatchManager, Patchwork, PlayerState, QuiltBoard, TimeBoard};
+use patchwork_core::{PatchManager, Patchwork, QuiltBoard, TimeBoard};
+use rand::{Rng, SeedableRng};
+use rand_xoshiro::Xoshiro256PlusPlus;
 
-/// TODO: use new values
 /// A Zobrist hash implementation for Patchwork.
 /// This is used to hash the game state.
 ///
-/// The Zobrist hash needs at least ≈16.71 kiB of memory to store the random numbers.
+/// The Zobrist hash needs at least ≈20.61 kiB of memory to store the random numbers.
 /// This is calculated as follows:
 ///
 /// ```math
@@ -13,12 +14,12 @@ use patchwork_core::{PatchManager, Patchwork, PlayerState, QuiltBoard, TimeBoard
 /// 5         // the hashes for the status flags
 /// 81  * 2 + // every piece on the quilt board for both players
 /// 54  * 2 + // every position on the time board for both players
-/// 353 * 2 + // every button balance for both players
+/// 603 * 2 + // every button balance for both players
 /// 32  * 2   // every button income for both players
-/// = 2139 u64s
+/// = 2639 u64s
 ///
-/// 2139 * 8 = 17112 bytes // each u64 is 8 bytes
-/// 17112 bytes / 1024 = 16.7109375 kiB
+/// 2639 * 8 = 21112 bytes // each u64 is 8 bytes
+/// 21112 bytes / 1024 = 20.6171875 kiB
 /// ```
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct ZobristHash {
@@ -56,6 +57,13 @@ pub struct ZobristHash {
 }
 
 impl ZobristHash {
+    /// The version of the hash keys. The keys are generated from the version
+    /// so that the same version always results in the same hashes. This
+    /// allows sharing transposition tables across builds. The version has to
+    /// be increased for every deliberate change of the keys or of what is
+    /// hashed.
+    pub const VERSION: u64 = 1;
+
     /// The amount of patches the zobrist hash uses to hash from all the available patches.
     ///
     /// Technically 3 this is incorrect as this can result in the same hash for different games
@@ -69,7 +77,10 @@ impl ZobristHash {
 
     /// The maximum button balance a player can have is bounded by the game.
     ///
-    /// * A player has `5` buttons at the start of the game.
+    /// * A player has `5` buttons at the start of the game. Variants can
+    ///   configure the amount of starting buttons (see
+    ///   `GameOptions::starting_buttons`) so up to `255` buttons have to be
+    ///   supported.
     /// * There are only `9` button income triggers that can yield a maximum amount
     ///   of `30` buttons each (see `MAX_BUTTON_INCOME` estimate below).
     /// * The player can get `1` button income for every tile he walks on the
@@ -77,14 +88,14 @@ impl ZobristHash {
     /// * The only other income source are the `7` buttons from a full quilt board.
     ///
     /// Because of this the maximum button balance a player can have is bounded
-    /// by `5 + 9 · 30 + 7 + 53 · 1 = 335`. This is a upper bound and not the
+    /// by `255 + 9 · 30 + 7 + 53 · 1 = 585`. This is a upper bound and not the
     /// actual maximum because of the same reason as the `MAX_BUTTON_INCOME`
     /// estimate below. Furthermore the player can only choose between the
     /// walking action and the action to place a tile. Therefore he cannot get
     /// both at the same time. It would probably be possible to lower the bound
-    /// to `5 + 9 · 30 + 7 = 282` (remove the walking actions) and
-    /// still be correct. But to be safe the bound is kept at `335`.
-    pub const MAX_BUTTON_BALANCE: usize = PlayerState::STARTING_BUTTON_BALANCE as usize
+    /// to `255 + 9 · 30 + 7 = 532` (remove the walking actions) and
+    /// still be correct. But to be safe the bound is kept at `585`.
+    pub const MAX_BUTTON_BALANCE: usize = u8::MAX as usize
         + TimeBoard::AMOUNT_OF_BUTTON_INCOME_TRIGGERS * Self::MAX_BUTTON_INCOME
         + QuiltBoard::BOARD_EXTRA_BUTTON_INCOME as usize
         + TimeBoard::MAX_POSITION as usize;
@@ -230,7 +241,8 @@ impl ZobristHash {
     /// * The button income of both players
     /// * The player whose turn it is
     ///
-    /// The random numbers are generated using the [`rand`] crate.
+    /// The random numbers are generated deterministically from
+    /// [`ZobristHash::VERSION`] with a [`Xoshiro256PlusPlus`] generator.
     ///
     /// # Returns
     ///
@@ -240,6 +252,8 @@ impl ZobristHash {
     pub fn new() -> Self {
         // QUESTION: maybe we can reduce the amount of memory required by using 2 rank 1 vectors and then using the dot
         // product to create a matrix on the fly?
+        let mut random = Xoshiro256PlusPlus::seed_from_u64(Self::VERSION);
+
         let mut zobrist_patches_table =
             [0; (PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize) * (PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize)];
         let mut zobrist_special_patches_table = [0; PatchManager::AMOUNT_OF_SPECIAL_PATCHES as usize];
@@ -255,39 +269,39 @@ impl ZobristHash {
         for i in
             0..((PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize) * (PatchManager::AMOUNT_OF_NORMAL_PATCHES as usize))
         {
-            zobrist_patches_table[i] = rand::random();
+            zobrist_patches_table[i] = random.gen();
         }
 
         for i in 0..PatchManager::AMOUNT_OF_SPECIAL_PATCHES as usize {
-            zobrist_special_patches_table[i] = rand::random();
+            zobrist_special_patches_table[i] = random.gen();
         }
 
         for i in 0..QuiltBoard::TILES as usize {
-            zobrist_player_1_quilt_board_table[i] = rand::random();
-            zobrist_player_2_quilt_board_table[i] = rand::random();
+            zobrist_player_1_quilt_board_table[i] = random.gen();
+            zobrist_player_2_quilt_board_table[i] = random.gen();
         }
 
         for i in 0..=(TimeBoard::MAX_POSITION as usize) {
-            zobrist_player_1_position_table[i] = rand::random();
-            zobrist_player_2_position_table[i] = rand::random();
+            zobrist_player_1_position_table[i] = random.gen();
+            zobrist_player_2_position_table[i] = random.gen();
         }
 
         for i in 0..=Self::MAX_BUTTON_BALANCE {
-            zobrist_player_1_button_balance_table[i] = rand::random();
-            zobrist_player_2_button_balance_table[i] = rand::random();
+            zobrist_player_1_button_balance_table[i] = random.gen();
+            zobrist_player_2_button_balance_table[i] = random.gen();
         }
 
         for i in 0..=Self::MAX_BUTTON_INCOME {
-            zobrist_player_1_button_income_table[i] = rand::random();
-            zobrist_player_2_button_income_table[i] = rand::random();
+            zobrist_player_1_button_income_table[i] = random.gen();
+            zobrist_player_2_button_income_table[i] = random.gen();
         }
 
         Self {
-            zobrist_player_2_to_move: rand::random(),
-            zobrist_player_1_has_special_tile: rand::random(),
-            zobrist_player_2_has_special_tile: rand::random(),
-            zobrist_player_1_was_first_to_reach_goal: rand::random(),
-            zobrist_player_2_was_first_to_reach_goal: rand::random(),
+            zobrist_player_2_to_move: random.gen(),
+            zobrist_player_1_has_special_tile: random.gen(),
+            zobrist_player_2_has_special_tile: random.gen(),
+            zobrist_player_1_was_first_to_reach_goal: random.gen(),
+            zobrist_player_2_was_first_to_reach_goal: random.gen(),
             zobrist_patches_table,
             zobrist_special_patches_table,
             zobrist_player_1_quilt_board_table,
@@ -327,8 +341,8 @@ impl ZobristHash {
         }
 
         // Hash special patches on board
-        for (index, special_patch_index) in [26, 32, 38, 44, 50].iter().enumerate() {
-            if game.time_board.is_special_patch_at(*special_patch_index) {
+        for (index, special_patch_index) in TimeBoard::SPECIAL_PATCH_POSITIONS.iter().enumerate() {
+            if game.time_board.is_special_patch_at(usize::from(*special_patch_index)) {
                 hash ^= self.zobrist_special_patches_table[index];
             }
         }
@@ -391,3 +405,81 @@ impl Default for ZobristHash {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{GameOptions, Notation, PositionCorpus};
+
+    use super::*;
+
+    /// The file containing the expected hashes of canonical positions. Run the
+    /// tests with `UPDATE_GOLDEN=1` to regenerate it after increasing
+    /// [`ZobristHash::VERSION`].
+    const TEST_VECTOR_FILE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/golden/zobrist_hash.txt");
+
+    /// Gets the canonical positions of the test vectors. These are the initial
+    /// states of some seeds and all positions of the standard corpus that can
+    /// be saved as notation.
+    fn canonical_positions() -> Vec<String> {
+        (0..4)
+            .map(|seed| Patchwork::get_initial_state(Some(GameOptions::new(seed))))
+            .chain(PositionCorpus::standard())
+            .filter_map(|state| state.save_to_notation().ok())
+            .collect()
+    }
+
+    #[test]
+    fn test_hash_matches_test_vectors() {
+        let zobrist_hash = ZobristHash::new();
+        let header = format!("# version {}", ZobristHash::VERSION);
+
+        if std::env::var_os("UPDATE_GOLDEN").is_some() {
+            let mut lines = vec![header];
+            for notation in canonical_positions() {
+                let state = Patchwork::load_from_notation(&notation).unwrap();
+                lines.push(format!("{:016x} {notation}", zobrist_hash.hash(&state)));
+            }
+            std::fs::create_dir_all(std::path::Path::new(TEST_VECTOR_FILE).parent().unwrap()).unwrap();
+            std::fs::write(TEST_VECTOR_FILE, lines.join("\n") + "\n").unwrap();
+            return;
+        }
+
+        let test_vectors = std::fs::read_to_string(TEST_VECTOR_FILE).unwrap();
+        let mut lines = test_vectors.lines();
+        assert_eq!(
+            Some(header.as_str()),
+            lines.next(),
+            "The test vectors were created for another version of the hash keys"
+        );
+
+        let mut amount = 0;
+        for line in lines {
+            let (expected, notation) = line.split_once(' ').unwrap();
+            let state = Patchwork::load_from_notation(notation).unwrap();
+            assert_eq!(
+                expected,
+                format!("{:016x}", zobrist_hash.hash(&state)),
+                "The hash of {notation} changed without increasing the version"
+            );
+            amount += 1;
+        }
+        assert!(amount > 0, "No test vectors found");
+    }
+
+    #[test]
+    fn test_structural_key_ignores_patch_order() {
+        let state_1 = Patchwork::load_from_notation(
+            "000000000000000000000B5I0P0 000000000000000000000B5I0P0 1 N 1/2/3/4/5/6/7/8/9/10",
+        )
+        .unwrap();
+        let state_2 = Patchwork::load_from_notation(
+            "000000000000000000000B5I0P0 000000000000000000000B5I0P0 1 N 2/1/3/4/5/6/7/8/9/10",
+        )
+        .unwrap();
+
+        let zobrist_hash = ZobristHash::new();
+
+        assert_eq!(state_1.structural_key(), state_2.structural_key());
+        assert_ne!(zobrist_hash.hash(&state_1), zobrist_hash.hash(&state_2));
+    }
+}
@@ -2,8 +2,254 @@ mod partially_scored_uct_policy;
 mod puct_policy;
 mod scored_uct_policy;
 mod uct_policy;
+mod value_uct_policy;
 
 pub use partially_scored_uct_policy::PartiallyScoredUCTPolicy;
 pub use puct_policy::{FPUStrategy, PUCTPolicy};
 pub use scored_uct_policy::ScoredUCTPolicy;
 pub use uct_policy::UCTPolicy;
+pub use value_uct_policy::ValueUCTPolicy;
+
+#[cfg(test)]
+mod tests {
+    use patchwork_core::{ExplorationTreePolicy, TreePolicy, TreePolicyNode};
+
+    use super::*;
+
+    const LOW_EXPLORATION_CONSTANT: f64 = 0.5;
+    const HIGH_EXPLORATION_CONSTANT: f64 = 2.0;
+
+    struct TestNode {
+        visit_count: usize,
+        wins: i32,
+    }
+
+    impl TreePolicyNode for TestNode {
+        type Player = ();
+
+        fn visit_count(&self) -> usize {
+            self.visit_count
+        }
+
+        fn current_player(&self) -> Self::Player {}
+
+        fn wins_for(&self, (): Self::Player) -> i32 {
+            self.wins
+        }
+
+        fn maximum_score_for(&self, (): Self::Player) -> f64 {
+            1.0
+        }
+
+        fn minimum_score_for(&self, (): Self::Player) -> f64 {
+            -1.0
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn score_sum_for(&self, (): Self::Player) -> f64 {
+            f64::from(2 * self.wins) - self.visit_count as f64
+        }
+    }
+
+    /// Counts how often the less visited of two children is selected over all
+    /// pairs of children with (nearly) equal value estimates.
+    #[allow(clippy::cast_precision_loss)]
+    fn count_less_visited_selections(policy: &impl TreePolicy) -> usize {
+        let mut selections = 0;
+
+        for less_visits in 1..=10 {
+            for more_visits in (less_visits + 1)..=30 {
+                for less_wins in 0..=less_visits {
+                    for more_wins in 0..=more_visits {
+                        let less_value = f64::from(less_wins) / f64::from(less_visits);
+                        let more_value = f64::from(more_wins) / f64::from(more_visits);
+                        if (less_value - more_value).abs() > 0.2 {
+                            continue;
+                        }
+
+                        let parent = TestNode {
+                            visit_count: (less_visits + more_visits) as usize,
+                            wins: 0,
+                        };
+                        let less_visited = TestNode {
+                            visit_count: less_visits as usize,
+                            wins: less_wins,
+                        };
+                        let more_visited = TestNode {
+                            visit_count: more_visits as usize,
+                            wins: more_wins,
+                        };
+
+                        let selected = policy.select_node(&parent, [&more_visited, &less_visited].into_iter());
+                        selections += usize::from(selected.visit_count == less_visited.visit_count);
+                    }
+                }
+            }
+        }
+
+        selections
+    }
+
+    fn test_higher_exploration_selects_less_visited<Policy: ExplorationTreePolicy>() {
+        let low = count_less_visited_selections(&Policy::with_exploration_constant(LOW_EXPLORATION_CONSTANT));
+        let high = count_less_visited_selections(&Policy::with_exploration_constant(HIGH_EXPLORATION_CONSTANT));
+
+        assert!(
+            high > low,
+            "less visited child selected {high} times with c = {HIGH_EXPLORATION_CONSTANT}, \
+             {low} times with c = {LOW_EXPLORATION_CONSTANT}"
+        );
+    }
+
+    #[test]
+    fn test_uct_higher_exploration_selects_less_visited() {
+        test_higher_exploration_selects_less_visited::<UCTPolicy>();
+    }
+
+    #[test]
+    fn test_scored_uct_higher_exploration_selects_less_visited() {
+        test_higher_exploration_selects_less_visited::<ScoredUCTPolicy>();
+    }
+
+    #[test]
+    fn test_partially_scored_uct_higher_exploration_selects_less_visited() {
+        test_higher_exploration_selects_less_visited::<PartiallyScoredUCTPolicy>();
+    }
+
+    #[test]
+    fn test_value_uct_higher_exploration_selects_less_visited() {
+        test_higher_exploration_selects_less_visited::<ValueUCTPolicy>();
+    }
+
+    /// A node with explicit evaluator values instead of values derived from the wins.
+    struct ValuedTestNode {
+        visit_count: usize,
+        wins: i32,
+        value_sum: f64,
+        maximum_value: f64,
+        minimum_value: f64,
+    }
+
+    impl TreePolicyNode for ValuedTestNode {
+        type Player = ();
+
+        fn visit_count(&self) -> usize {
+            self.visit_count
+        }
+
+        fn current_player(&self) -> Self::Player {}
+
+        fn wins_for(&self, (): Self::Player) -> i32 {
+            self.wins
+        }
+
+        fn maximum_score_for(&self, (): Self::Player) -> f64 {
+            self.maximum_value
+        }
+
+        fn minimum_score_for(&self, (): Self::Player) -> f64 {
+            self.minimum_value
+        }
+
+        fn score_sum_for(&self, (): Self::Player) -> f64 {
+            self.value_sum
+        }
+    }
+
+    #[test]
+    fn test_value_uct_prefers_higher_margin_over_higher_win_rate() {
+        let parent = ValuedTestNode {
+            visit_count: 20,
+            wins: 0,
+            value_sum: 0.0,
+            maximum_value: 40.0,
+            minimum_value: -10.0,
+        };
+        // wins 6 of 10 games by 1 point and loses 4 by 2 points
+        let narrow_wins = ValuedTestNode {
+            visit_count: 10,
+            wins: 6 - 4,
+            value_sum: 6.0f64.mul_add(1.0, -4.0 * 2.0),
+            maximum_value: 1.0,
+            minimum_value: -2.0,
+        };
+        // wins 4 of 10 games by 40 points and loses 6 by 2 points
+        let clear_wins = ValuedTestNode {
+            visit_count: 10,
+            wins: 4 - 6,
+            value_sum: 4.0f64.mul_add(40.0, -6.0 * 2.0),
+            maximum_value: 40.0,
+            minimum_value: -2.0,
+        };
+        let children = [&narrow_wins, &clear_wins];
+
+        let selected = UCTPolicy::default().select_node(&parent, children.into_iter());
+        assert!(std::ptr::eq(selected, &raw const narrow_wins));
+        let selected = ValueUCTPolicy::<50>::default().select_node(&parent, children.into_iter());
+        assert!(std::ptr::eq(selected, &raw const clear_wins));
+        // without any value portion the policy is plain UCT
+        let selected = ValueUCTPolicy::<0>::default().select_node(&parent, children.into_iter());
+        assert!(std::ptr::eq(selected, &raw const narrow_wins));
+    }
+    fn selects_visited_child(policy: &impl TreePolicy) -> bool {
+        let parent = TestNode {
+            visit_count: 20,
+            wins: 0,
+        };
+        let visited = TestNode {
+            visit_count: 10,
+            wins: 9,
+        };
+        let unvisited = TestNode {
+            visit_count: 0,
+            wins: 0,
+        };
+
+        let selected = policy.select_node(&parent, [&unvisited, &visited, &unvisited].into_iter());
+        selected.visit_count == visited.visit_count
+    }
+
+    #[test]
+    fn test_uct_fpu_prefers_promising_visited_child() {
+        assert!(!selects_visited_child(&UCTPolicy::default()));
+        assert!(selects_visited_child(&UCTPolicy::default().with_fpu(FPUStrategy::Reduction(0.0))));
+        assert!(selects_visited_child(&UCTPolicy::default().with_fpu(FPUStrategy::Absolute(-1.0))));
+    }
+
+    #[test]
+    fn test_scored_uct_fpu_prefers_promising_visited_child() {
+        assert!(!selects_visited_child(&ScoredUCTPolicy::default()));
+        assert!(selects_visited_child(&ScoredUCTPolicy::default().with_fpu(FPUStrategy::Reduction(0.0))));
+        assert!(selects_visited_child(&ScoredUCTPolicy::default().with_fpu(FPUStrategy::Absolute(-1.0))));
+    }
+    #[test]
+    fn test_uct_select_batch_returns_distinct_children() {
+        let policy = UCTPolicy::default();
+        let parent = TestNode {
+            visit_count: 100,
+            wins: 0,
+        };
+        let children = (1..=6)
+            .map(|visit_count| TestNode {
+                visit_count: visit_count * 5,
+                wins: i32::try_from(visit_count).unwrap(),
+            })
+            .collect::<Vec<_>>();
+
+        let selected = policy.select_batch(&parent, children.iter(), 4);
+        assert_eq!(4, selected.len());
+        for (index, child) in selected.iter().enumerate() {
+            assert!(
+                selected[index + 1..].iter().all(|other| !std::ptr::eq(*child, *other)),
+                "child {index} was selected multiple times"
+            );
+        }
+        assert!(std::ptr::eq(
+            selected[0],
+            policy.select_node(&parent, children.iter())
+        ));
+
+        assert_eq!(children.len(), policy.select_batch(&parent, children.iter(), 10).len());
+    }
+}
+
@@ -1,4 +1,4 @@
-use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
+use patchwork_core::{ExplorationTreePolicy, ScoredTreePolicy, TreePolicyNode};
 
 /// An implementation of the UCT (Upper Confidence Bound 1 applied to trees)
 /// tree policy but partially taking into account the final score of the game.
@@ -64,6 +64,12 @@ impl<const SCORE_PORTION: u8> Default for PartiallyScoredUCTPolicy<SCORE_PORTION
     }
 }
 
+impl<const SCORE_PORTION: u8> ExplorationTreePolicy for PartiallyScoredUCTPolicy<SCORE_PORTION> {
+    fn with_exploration_constant(exploration_constant: f64) -> Self {
+        Self::new(exploration_constant)
+    }
+}
+
 impl<const SCORE_PORTION: u8> ScoredTreePolicy for PartiallyScoredUCTPolicy<SCORE_PORTION> {
     fn get_score<Player: Copy>(
         &self,
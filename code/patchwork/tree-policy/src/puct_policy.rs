@@ -1,4 +1,4 @@
-use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
+use patchwork_core::{ExplorationTreePolicy, ScoredTreePolicy, TreePolicyNode};
 
 /// The First Play Urgency (FPU) strategy to use for the MCTS. The FPU is used to give a exploitation value to unvisited
 /// nodes. The original `AlphaZero` paper used a value of -1 for the FPU (see [AlphaZero paper, and Lc0 v0.19.1](https://lczero.org/blog/2018/12/alphazero-paper-and-lc0-v0191/)).
@@ -158,6 +158,16 @@ impl PUCTPolicy {
     }
 }
 
+impl ExplorationTreePolicy for PUCTPolicy {
+    /// Creates the [`PUCTPolicy`] with the given c init exploration parameter.
+    fn with_exploration_constant(exploration_constant: f64) -> Self {
+        Self {
+            c_init: exploration_constant,
+            ..Self::default()
+        }
+    }
+}
+
 impl ScoredTreePolicy for PUCTPolicy {
     fn get_score<Player: Copy>(
         &self,
@@ -1,4 +1,6 @@
-use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
+use patchwork_core::{ExplorationTreePolicy, ScoredTreePolicy, TreePolicyNode};
+
+use crate::FPUStrategy;
 
 /// An implementation of the UCT (Upper Confidence Bound 1 applied to trees)
 /// tree policy but taking into account the final score of the game.
@@ -19,6 +21,10 @@ use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
 ///      𝒸 = exploration constant (usually √2)
 /// ```
 ///
+/// Unvisited children are infinitely attractive unless a First Play Urgency
+/// (FPU) strategy is set. With an FPU strategy unvisited children are scored
+/// with the FPU value instead.
+///
 /// # See also
 ///
 /// - [Wikipedia article on UCT](https://en.wikipedia.org/wiki/Monte_Carlo_tree_search#Exploration_and_exploitation)
@@ -26,6 +32,8 @@ use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
 pub struct ScoredUCTPolicy {
     /// The exploration parameter for the UCT policy.
     exploration_constant: f64,
+    /// The First Play Urgency (FPU) strategy to score unvisited children with.
+    fpu_strategy: Option<FPUStrategy>,
 }
 
 impl ScoredUCTPolicy {
@@ -40,7 +48,26 @@ impl ScoredUCTPolicy {
     /// The new [`ScoredUCTPolicy`].
     #[must_use]
     pub const fn new(exploration_constant: f64) -> Self {
-        Self { exploration_constant }
+        Self {
+            exploration_constant,
+            fpu_strategy: None,
+        }
+    }
+
+    /// Sets the First Play Urgency (FPU) strategy used to score unvisited
+    /// children.
+    ///
+    /// # Arguments
+    ///
+    /// * `fpu_strategy` - The FPU strategy to score unvisited children with.
+    ///
+    /// # Returns
+    ///
+    /// The [`ScoredUCTPolicy`] with the given FPU strategy.
+    #[must_use]
+    pub const fn with_fpu(mut self, fpu_strategy: FPUStrategy) -> Self {
+        self.fpu_strategy = Some(fpu_strategy);
+        self
     }
 }
 
@@ -50,6 +77,12 @@ impl Default for ScoredUCTPolicy {
     }
 }
 
+impl ExplorationTreePolicy for ScoredUCTPolicy {
+    fn with_exploration_constant(exploration_constant: f64) -> Self {
+        Self::new(exploration_constant)
+    }
+}
+
 impl ScoredTreePolicy for ScoredUCTPolicy {
     fn get_score<Player: Copy>(
         &self,
@@ -60,6 +93,12 @@ impl ScoredTreePolicy for ScoredUCTPolicy {
         let parent_visit_count = parent.visit_count() as f64;
         let parent_player = parent.current_player();
 
+        if child.visit_count() == 0 {
+            return self.fpu_strategy.as_ref().map_or(f64::INFINITY, |fpu_strategy| {
+                fpu_strategy.get_fpu(parent.average_score_for(parent_player))
+            });
+        }
+
         let exploitation_score = child.average_score_for(parent_player);
 
         let exploration = (parent_visit_count.ln() / child_visit_count).sqrt();
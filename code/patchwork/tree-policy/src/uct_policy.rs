@@ -1,4 +1,6 @@
-use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
+use patchwork_core::{ExplorationTreePolicy, ScoredTreePolicy, TreePolicyNode};
+
+use crate::FPUStrategy;
 
 /// An implementation of the UCT (Upper Confidence Bound 1 applied to trees)
 /// tree policy.
@@ -14,12 +16,18 @@ use patchwork_core::{ScoredTreePolicy, TreePolicyNode};
 ///      𝒸 = exploration constant (usually √2)
 /// ```
 ///
+/// Unvisited children are infinitely attractive unless a First Play Urgency
+/// (FPU) strategy is set. With an FPU strategy unvisited children are scored
+/// with the FPU value instead.
+///
 /// # See also
 ///
 /// - [Wikipedia article on UCT](https://en.wikipedia.org/wiki/Monte_Carlo_tree_search#Exploration_and_exploitation)
 pub struct UCTPolicy {
     /// The exploration parameter for the UCT policy.
     exploration_constant: f64,
+    /// The First Play Urgency (FPU) strategy to score unvisited children with.
+    fpu_strategy: Option<FPUStrategy>,
 }
 
 impl UCTPolicy {
@@ -34,7 +42,26 @@ impl UCTPolicy {
     /// The new [`UCTPolicy`].
     #[must_use]
     pub const fn new(exploration_constant: f64) -> Self {
-        Self { exploration_constant }
+        Self {
+            exploration_constant,
+            fpu_strategy: None,
+        }
+    }
+
+    /// Sets the First Play Urgency (FPU) strategy used to score unvisited
+    /// children.
+    ///
+    /// # Arguments
+    ///
+    /// * `fpu_strategy` - The FPU strategy to score unvisited children with.
+    ///
+    /// # Returns
+    ///
+    /// The [`UCTPolicy`] with the given FPU strategy.
+    #[must_use]
+    pub const fn with_fpu(mut self, fpu_strategy: FPUStrategy) -> Self {
+        self.fpu_strategy = Some(fpu_strategy);
+        self
     }
 }
 
@@ -44,6 +71,12 @@ impl Default for UCTPolicy {
     }
 }
 
+impl ExplorationTreePolicy for UCTPolicy {
+    fn with_exploration_constant(exploration_constant: f64) -> Self {
+        Self::new(exploration_constant)
+    }
+}
+
 impl ScoredTreePolicy for UCTPolicy {
     fn get_score<Player: Copy>(
         &self,
@@ -54,6 +87,17 @@ impl ScoredTreePolicy for UCTPolicy {
         let parent_visit_count = parent.visit_count() as f64;
         let parent_player = parent.current_player();
 
+        if child.visit_count() == 0 {
+            return self.fpu_strategy.as_ref().map_or(f64::INFINITY, |fpu_strategy| {
+                let parent_value = if parent.visit_count() == 0 {
+                    0.0
+                } else {
+                    f64::from(parent.wins_for(parent_player)) / parent_visit_count
+                };
+                fpu_strategy.get_fpu(parent_value)
+            });
+        }
+
         let exploitation_wins = f64::from(child.wins_for(parent_player)) / child_visit_count;
 
         let exploration = (parent_visit_count.ln() / child_visit_count).sqrt();
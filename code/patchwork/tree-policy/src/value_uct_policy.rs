@@ -0,0 +1,108 @@
+use patchwork_core::{ExplorationTreePolicy, ScoredTreePolicy, TreePolicyNode};
+
+/// An implementation of the UCT (Upper Confidence Bound 1 applied to trees)
+/// tree policy that blends the win rate with the average leaf value.
+///
+/// The exploitation term is blended from the win rate and the average value
+/// the evaluator assigned to the visited leaves.
+/// The score sum of a node is the sum of the evaluator values that were
+/// backed up through the node, so the average score is a running average of
+/// the value estimates instead of only counting wins and losses. This suits
+/// the continuous scoring of Patchwork where a narrow win and a clear win are
+/// different outcomes. The average value is normalized to `[-1, 1]` with the
+/// minimum and maximum score of the parent node so that it can be blended
+/// with the win rate and the usual UCT exploration term.
+///
+/// The value portion parameter is a value between 0 and 100, where 0 means
+/// that only the wins are taken into account (plain UCT) and 100 means that
+/// only the values are taken into account.
+///
+/// The default portion is 50% for the values and the rest for the wins.
+///
+/// # Formula
+///
+/// ```math
+/// 𝓅 · 𝓋 + (1 - 𝓅) · 𝓌 / 𝑛 + 𝒸 · √(㏑ 𝒩 / 𝑛)
+///
+/// with 𝓅 = The portion that values should be taken into account
+///      𝓋 = 2 · (∑𝓈ᵢ / 𝑛 - minᵢ 𝓈ᵢ) / |maxᵢ 𝓈ᵢ - minᵢ 𝓈ᵢ| - 1
+///      𝓈ᵢ = The evaluator value of the 𝒾's visit
+///      𝓌 = The wins of the child node from the perspective of the parent
+///      𝑛 = The amount of visits of the child node
+///      𝒩 = The amount of visits of the parent node
+///      𝒸 = exploration constant (usually √2)
+/// ```
+///
+/// # See also
+///
+/// - [Wikipedia article on UCT](https://en.wikipedia.org/wiki/Monte_Carlo_tree_search#Exploration_and_exploitation)
+/// - [`ScoredUCTPolicy`](crate::ScoredUCTPolicy)
+pub struct ValueUCTPolicy<const VALUE_PORTION: u8 = 50> {
+    /// The exploration parameter for the UCT policy.
+    exploration_constant: f64,
+}
+
+impl<const VALUE_PORTION: u8> ValueUCTPolicy<VALUE_PORTION> {
+    /// The const parameter [`VALUE_PORTION`] as a percentage.
+    const PORTION: f64 = VALUE_PORTION as f64 / 100f64;
+
+    /// Creates a new [`ValueUCTPolicy`] with the given exploration constant.
+    ///
+    /// # Arguments
+    ///
+    /// * `exploration_constant` - The exploration constant for the UCT policy.
+    ///
+    /// # Returns
+    ///
+    /// The new [`ValueUCTPolicy`].
+    #[must_use]
+    pub const fn new(exploration_constant: f64) -> Self {
+        Self { exploration_constant }
+    }
+}
+
+impl<const VALUE_PORTION: u8> Default for ValueUCTPolicy<VALUE_PORTION> {
+    fn default() -> Self {
+        Self::new(2f64.sqrt())
+    }
+}
+
+impl<const VALUE_PORTION: u8> ExplorationTreePolicy for ValueUCTPolicy<VALUE_PORTION> {
+    fn with_exploration_constant(exploration_constant: f64) -> Self {
+        Self::new(exploration_constant)
+    }
+}
+
+impl<const VALUE_PORTION: u8> ScoredTreePolicy for ValueUCTPolicy<VALUE_PORTION> {
+    fn get_score<Player: Copy>(
+        &self,
+        parent: &impl TreePolicyNode<Player = Player>,
+        child: &impl TreePolicyNode<Player = Player>,
+    ) -> f64 {
+        let child_visit_count = child.visit_count() as f64;
+        let parent_visit_count = parent.visit_count() as f64;
+        let parent_player = parent.current_player();
+
+        if child.visit_count() == 0 {
+            return f64::INFINITY;
+        }
+
+        let exploitation_wins = f64::from(child.wins_for(parent_player)) / child_visit_count;
+
+        let score_range = parent.score_range();
+        let exploitation_value = if score_range > 0.0 {
+            let minimum_score = parent.minimum_score_for(parent_player);
+            (2.0 * (child.average_score_for(parent_player) - minimum_score) / score_range - 1.0).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let exploration = (parent_visit_count.ln() / child_visit_count).sqrt();
+        let exploration_wins = self.exploration_constant * exploration;
+
+        Self::PORTION.mul_add(
+            exploitation_value,
+            (1f64 - Self::PORTION).mul_add(exploitation_wins, exploration_wins),
+        )
+    }
+}
@@ -1,32 +1,176 @@
 // https://www.wbec-ridderkerk.nl/html/UCIProtocol.html
 
-use std::sync::mpsc::{Receiver, Sender};
+use std::{
+    io::Write,
+    sync::mpsc::{Receiver, Sender},
+};
+
+use patchwork_core::{ActionId, Logging, Notation, Patchwork, Player, SearchDiagnostics};
+
+mod upi_engine_player;
+
+pub use upi_engine_player::UpiEnginePlayer;
+
+/// How much diagnostic output is sent as `info string` lines during and after
+/// a search. The modes correspond to the levels of [`Logging`] (see
+/// [`get_logging`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+enum DebugMode {
+    /// No diagnostics are sent (`debug off`).
+    #[default]
+    Disabled,
+    /// The depth, nodes, time and transposition table usage of the search are sent (`debug on`).
+    Enabled,
+    /// Additionally the principal variation and the times of the iterations are sent (`debug verbose`).
+    Verbose,
+}
+
+/// A writer that sends every written line as an `info string` line so that
+/// the logging of a player reaches the GUI during the search.
+struct InfoStringWriter {
+    /// The channel the lines are sent to.
+    message_sender: Sender<String>,
+    /// The written bytes that do not form a complete line yet.
+    buffer: Vec<u8>,
+}
+
+impl Write for InfoStringWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n') {
+            let line = self.buffer.drain(..=end).collect::<Vec<_>>();
+            let line = String::from_utf8_lossy(&line);
+            if !line.trim().is_empty() {
+                self.message_sender
+                    .send(format!("info string {}\n", line.trim_end()))
+                    .map_err(|error| std::io::Error::new(std::io::ErrorKind::BrokenPipe, error))?;
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.buffer.push(b'\n');
+            self.write(&[])?;
+        }
+        Ok(())
+    }
+}
+
+/// Gets the logging of the player for the given debug mode. The logging of
+/// the player is sent as `info string` lines during the search.
+///
+/// # Arguments
+///
+/// * `debug_mode` - The debug mode that was set with the `debug` command.
+/// * `message_sender` - The channel the lines are sent to.
+///
+/// # Returns
+///
+/// The logging that corresponds to the debug mode.
+fn get_logging(debug_mode: DebugMode, message_sender: &Sender<String>) -> Logging {
+    let writer = || -> Box<dyn Write> {
+        Box::new(InfoStringWriter {
+            message_sender: message_sender.clone(),
+            buffer: vec![],
+        })
+    };
+
+    match debug_mode {
+        DebugMode::Disabled => Logging::Disabled,
+        DebugMode::Enabled => Logging::Enabled {
+            progress_writer: writer(),
+        },
+        DebugMode::Verbose => Logging::Verbose {
+            progress_writer: writer(),
+            debug_writer: writer(),
+        },
+    }
+}
 
 /// Starts the UPI server
 ///
+/// # Arguments
+///
+/// * `message_receiver` - The channel the commands are received from.
+/// * `message_sender` - The channel the responses are sent to.
+/// * `player` - The player that searches the best move on `go`.
+///
 /// # Errors
 ///
 /// This function will return an error if sending or receiving messages over the channels fails.
-pub fn start_upi(message_receiver: Receiver<String>, message_sender: Sender<String>) -> anyhow::Result<()> {
+pub fn start_upi(
+    message_receiver: Receiver<String>,
+    message_sender: Sender<String>,
+    player: &mut dyn Player,
+) -> anyhow::Result<()> {
+    let mut state = None;
+    let mut debug_mode = DebugMode::default();
+
     while let Ok(msg) = message_receiver.recv() {
-        let msg = msg.trim().to_lowercase();
-        let mut split_message = msg.split_whitespace();
-        match split_message.next() {
-            Some("upi") => {
+        // only the command is case insensitive as the notation of states and actions is not
+        let msg = msg.trim();
+        let (command, arguments) = msg.split_once(char::is_whitespace).unwrap_or((msg, ""));
+        match command.to_lowercase().as_str() {
+            "upi" => {
                 let authors = env!("CARGO_PKG_AUTHORS").split(':').collect::<Vec<_>>().join(" & ");
                 let message = format!("id name {}\nid author {}\nupiok\n", env!("CARGO_PKG_NAME"), authors);
                 message_sender.send(message)?;
             }
-            Some("isready") => {
+            "isready" => {
                 message_sender.send("readyok\n".to_string())?;
             }
-            // debug [on|off]
+            "upinewgame" => {
+                // no transposition table or search tree of the previous game may leak into the next one
+                player.reset();
+                state = None;
+            }
+            // position <state notation> [moves <action notation>...]
+            "position" => match parse_position(arguments) {
+                Ok(position) => state = Some(position),
+                Err(error) => message_sender.send(format!("info string invalid position: {error}\n"))?,
+            },
+            // go [searchmoves <action notation>...]
+            "go" => {
+                let Some(state) = &state else {
+                    message_sender.send("info string no position was set\n".to_string())?;
+                    continue;
+                };
+                let search_moves = match parse_search_moves(state, arguments) {
+                    Ok(search_moves) => search_moves,
+                    Err(error) => {
+                        message_sender.send(format!("info string invalid searchmoves: {error}\n"))?;
+                        continue;
+                    }
+                };
+                let best_move = search(player, state, &search_moves, debug_mode).and_then(|(action, diagnostics)| {
+                    if let Some(diagnostics) = diagnostics {
+                        send_diagnostics(&message_sender, &diagnostics, debug_mode)?;
+                    }
+                    Ok(action.save_to_notation()?)
+                });
+                match best_move {
+                    Ok(action) => message_sender.send(format!("bestmove {action}\n"))?,
+                    Err(error) => message_sender.send(format!("info string search failed: {error}\n"))?,
+                }
+            }
+            // debug [on|off|verbose]
+            "debug" => {
+                debug_mode = match arguments.trim().to_lowercase().as_str() {
+                    "off" => DebugMode::Disabled,
+                    "on" => DebugMode::Enabled,
+                    "verbose" => DebugMode::Verbose,
+                    _ => {
+                        message_sender.send(format!("info string invalid debug mode: {arguments}\n"))?;
+                        continue;
+                    }
+                };
+                player.set_logging(get_logging(debug_mode, &message_sender));
+            }
             // setoption name [value]
-            // ucinewgame
-            // position [fen  | startpos ]  moves  ....
-            // go
             // stop
-            Some("quit") => {
+            "quit" => {
                 break;
             }
             _ => {
@@ -40,3 +184,344 @@ pub fn start_upi(message_receiver: Receiver<String>, message_sender: Sender<Stri
 
     Ok(())
 }
+
+/// Searches the best action of the player.
+///
+/// # Arguments
+///
+/// * `player` - The player that searches the best action.
+/// * `state` - The state to search the best action for.
+/// * `search_moves` - The actions the search is restricted to. If empty all valid actions are searched.
+/// * `debug_mode` - Whether diagnostics of the search should be collected.
+///
+/// # Returns
+///
+/// The best action and the diagnostics of the search if the debug mode is not disabled.
+///
+/// # Errors
+///
+/// If the search of the player fails.
+fn search(
+    player: &mut dyn Player,
+    state: &Patchwork,
+    search_moves: &[ActionId],
+    debug_mode: DebugMode,
+) -> anyhow::Result<(ActionId, Option<SearchDiagnostics>)> {
+    if debug_mode == DebugMode::Disabled {
+        return Ok((player.get_restricted_action(state, search_moves)?, None));
+    }
+
+    let (action, diagnostics) = player.get_restricted_action_with_diagnostics(state, search_moves)?;
+    Ok((action, Some(diagnostics)))
+}
+
+/// Sends the diagnostics of a search as `info string` lines.
+///
+/// # Arguments
+///
+/// * `message_sender` - The channel the lines are sent to.
+/// * `diagnostics` - The diagnostics of the search.
+/// * `debug_mode` - How much of the diagnostics should be sent.
+///
+/// # Errors
+///
+/// If sending a line fails.
+fn send_diagnostics(
+    message_sender: &Sender<String>,
+    diagnostics: &SearchDiagnostics,
+    debug_mode: DebugMode,
+) -> anyhow::Result<()> {
+    message_sender.send(format!(
+        "info string depth {} nodes {} time {}\n",
+        diagnostics.depth,
+        diagnostics.nodes,
+        diagnostics.time.as_millis()
+    ))?;
+    if let Some(transposition_table) = diagnostics.transposition_table {
+        message_sender.send(format!(
+            "info string transposition table entries {} capacity {} accesses {} hits {}\n",
+            transposition_table.entries,
+            transposition_table.capacity,
+            transposition_table.accesses,
+            transposition_table.hits
+        ))?;
+    }
+
+    if debug_mode != DebugMode::Verbose {
+        return Ok(());
+    }
+
+    if !diagnostics.principal_variation.is_empty() {
+        let principal_variation = diagnostics
+            .principal_variation
+            .iter()
+            .map(|action| action.save_to_notation().unwrap_or_else(|_| action.to_string()))
+            .collect::<Vec<_>>();
+        message_sender.send(format!("info string pv {}\n", principal_variation.join(" ")))?;
+    }
+    for iteration in &diagnostics.iterations {
+        message_sender.send(format!(
+            "info string iteration depth {} time {} predicted {}\n",
+            iteration.depth,
+            iteration.actual.as_millis(),
+            iteration
+                .predicted
+                .map_or_else(|| "none".to_string(), |predicted| predicted.as_millis().to_string())
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Parses the arguments of the `position` command.
+///
+/// # Arguments
+///
+/// * `arguments` - The notation of a state optionally followed by `moves` and the notation of the actions that were
+///   taken from that state.
+///
+/// # Returns
+///
+/// The state after all given actions were taken.
+///
+/// # Errors
+///
+/// If the state or one of the actions is invalid.
+fn parse_position(arguments: &str) -> anyhow::Result<Patchwork> {
+    let (notation, actions) = arguments.split_once(" moves ").unwrap_or((arguments, ""));
+
+    let mut state = Patchwork::load_from_notation(notation.trim())?;
+    for action in actions.split_whitespace() {
+        state.do_action(ActionId::load_from_notation(action)?, false)?;
+    }
+    Ok(state)
+}
+
+/// Parses the actions after `searchmoves` in the arguments of the `go`
+/// command.
+///
+/// # Arguments
+///
+/// * `state` - The state the search is started from.
+/// * `arguments` - The arguments of the `go` command.
+///
+/// # Returns
+///
+/// The actions the search is restricted to or an empty list if all valid actions should be searched.
+///
+/// # Errors
+///
+/// If one of the actions is invalid in the given state.
+fn parse_search_moves(state: &Patchwork, arguments: &str) -> anyhow::Result<Vec<ActionId>> {
+    let mut tokens = arguments.split_whitespace();
+    if !tokens.any(|token| token.eq_ignore_ascii_case("searchmoves")) {
+        return Ok(vec![]);
+    }
+
+    let valid_actions = state.get_valid_actions();
+    tokens
+        .map(|notation| {
+            let action = ActionId::load_from_notation(notation)?;
+            if !valid_actions.contains(&action) {
+                anyhow::bail!("{notation} is not a valid action in the position");
+            }
+            Ok(action)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{mpsc::channel, Arc, Mutex},
+        thread::JoinHandle,
+        time::Duration,
+    };
+
+    use patchwork_core::{GameOptions, PlayerResult, PositionCorpus, SearchDiagnostics};
+    use principal_variation_search_player::{DefaultPVSPlayer, LazySMPFeature, PVSFeatures, PVSOptions};
+
+    use super::*;
+
+    /// A player that records the diagnostics of every search of the wrapped
+    /// player.
+    struct DiagnosticsRecorder {
+        inner: Box<dyn Player>,
+        diagnostics: Arc<Mutex<Vec<SearchDiagnostics>>>,
+    }
+
+    impl Player for DiagnosticsRecorder {
+        fn name(&self) -> &str {
+            self.inner.name()
+        }
+
+        fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+            let (action, diagnostics) = self.inner.get_action_with_diagnostics(game)?;
+            self.diagnostics.lock().unwrap().push(diagnostics);
+            Ok(action)
+        }
+
+        fn reset(&mut self) {
+            self.inner.reset();
+        }
+    }
+
+    /// Starts the UPI server in a separate thread.
+    ///
+    /// # Returns
+    ///
+    /// The channel to send commands, the channel to receive responses and the handle of the server thread.
+    fn start_test_upi(
+        create_player: impl FnOnce() -> Box<dyn Player> + Send + 'static,
+    ) -> (Sender<String>, Receiver<String>, JoinHandle<anyhow::Result<()>>) {
+        let (command_sender, command_receiver) = channel();
+        let (response_sender, response_receiver) = channel();
+        let upi = std::thread::spawn(move || start_upi(command_receiver, response_sender, create_player().as_mut()));
+
+        (command_sender, response_receiver, upi)
+    }
+
+    /// Receives all responses up to and including the next `bestmove`.
+    fn receive_until_bestmove(response_receiver: &Receiver<String>) -> Vec<String> {
+        let mut responses = vec![];
+        loop {
+            let response = response_receiver.recv().unwrap();
+            let is_best_move = response.starts_with("bestmove ");
+            responses.push(response);
+            if is_best_move {
+                return responses;
+            }
+        }
+    }
+
+    /// Creates a single threaded PVS player.
+    fn create_pvs_player(time_limit: Duration, max_depth: Option<u8>) -> Box<dyn Player> {
+        <DefaultPVSPlayer>::new(
+            "PVS Player",
+            Some(PVSOptions {
+                time_limit,
+                max_depth,
+                features: PVSFeatures {
+                    lazy_smp: LazySMPFeature::No,
+                    ..PVSFeatures::default()
+                },
+                ..PVSOptions::default()
+            }),
+        )
+    }
+
+    #[test]
+    fn test_go_searchmoves_restricts_the_best_move() {
+        let (command_sender, response_receiver, upi) =
+            start_test_upi(|| create_pvs_player(Duration::from_millis(200), None));
+
+        for state in PositionCorpus::generate(8, PositionCorpus::DEFAULT_SEED) {
+            let Ok(notation) = state.save_to_notation() else {
+                continue;
+            };
+            // restrict the search to the last valid actions, which are rarely the best ones
+            let valid_actions = state.get_valid_actions();
+            let search_moves = &valid_actions[valid_actions.len().saturating_sub(2)..];
+            let search_moves_notation = search_moves
+                .iter()
+                .map(|action| action.save_to_notation().unwrap())
+                .collect::<Vec<_>>();
+
+            command_sender.send(format!("position {notation}")).unwrap();
+            command_sender
+                .send(format!("go searchmoves {}", search_moves_notation.join(" ")))
+                .unwrap();
+
+            let response = response_receiver.recv().unwrap();
+            let best_move = response.trim().strip_prefix("bestmove ").unwrap();
+            assert!(
+                search_moves.contains(&ActionId::load_from_notation(best_move).unwrap()),
+                "bestmove {best_move} is not one of {search_moves_notation:?}"
+            );
+        }
+
+        command_sender.send("quit".to_string()).unwrap();
+        upi.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn test_upinewgame_clears_the_transposition_table() {
+        let diagnostics = Arc::new(Mutex::new(vec![]));
+        let recorded_diagnostics = Arc::clone(&diagnostics);
+        // a fixed depth makes the searches of the same position comparable
+        let (command_sender, response_receiver, upi) = start_test_upi(move || {
+            Box::new(DiagnosticsRecorder {
+                inner: create_pvs_player(Duration::from_secs(30), Some(3)),
+                diagnostics: recorded_diagnostics,
+            })
+        });
+        let notation = Patchwork::get_initial_state(Some(GameOptions::new(42)))
+            .save_to_notation()
+            .unwrap();
+
+        for command in ["", "", "upinewgame"] {
+            if !command.is_empty() {
+                command_sender.send(command.to_string()).unwrap();
+            }
+            command_sender.send(format!("position {notation}")).unwrap();
+            command_sender.send("go".to_string()).unwrap();
+            assert!(response_receiver.recv().unwrap().starts_with("bestmove "));
+        }
+
+        command_sender.send("quit".to_string()).unwrap();
+        upi.join().unwrap().unwrap();
+
+        let diagnostics = diagnostics.lock().unwrap();
+        let [first, repeated, new_game] =
+            [0, 1, 2].map(|index| diagnostics[index].transposition_table.unwrap());
+        assert_ne!(first, repeated, "The repeated search should reuse the table of the first search");
+        assert_eq!(first, new_game, "The search after upinewgame should start from a clean table");
+    }
+
+    #[test]
+    fn test_debug_toggles_info_strings() {
+        let (command_sender, response_receiver, upi) =
+            start_test_upi(|| create_pvs_player(Duration::from_millis(200), None));
+        let state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        command_sender.send(format!("position {}", state.save_to_notation().unwrap())).unwrap();
+
+        for debug_mode in ["on", "verbose"] {
+            command_sender.send(format!("debug {debug_mode}")).unwrap();
+            command_sender.send("go".to_string()).unwrap();
+            let responses = receive_until_bestmove(&response_receiver);
+            let diagnostics = responses
+                .iter()
+                .position(|response| response.starts_with("info string depth "))
+                .unwrap_or_else(|| panic!("Expected info strings with debug {debug_mode} but got {responses:?}"));
+            // the logging of the player is sent during the search before the diagnostics
+            assert!(
+                responses[..diagnostics]
+                    .iter()
+                    .any(|response| response.starts_with("info string ")),
+                "Expected the logging of the search with debug {debug_mode} but got {responses:?}"
+            );
+        }
+
+        // a restricted search reports the depth it reached and not only the time
+        let search_moves = state.get_valid_actions()[..2]
+            .iter()
+            .map(|action| action.save_to_notation().unwrap())
+            .collect::<Vec<_>>();
+        command_sender.send(format!("go searchmoves {}", search_moves.join(" "))).unwrap();
+        let responses = receive_until_bestmove(&response_receiver);
+        assert!(
+            responses
+                .iter()
+                .any(|response| response.starts_with("info string depth ") && !response.starts_with("info string depth 0 ")),
+            "Expected the depth of the restricted search but got {responses:?}"
+        );
+
+        command_sender.send("debug off".to_string()).unwrap();
+        command_sender.send("go".to_string()).unwrap();
+        let responses = receive_until_bestmove(&response_receiver);
+        assert_eq!(1, responses.len(), "Expected only the bestmove with debug off but got {responses:?}");
+
+        command_sender.send("quit".to_string()).unwrap();
+        upi.join().unwrap().unwrap();
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{channel, Receiver, RecvTimeoutError},
+    time::{Duration, Instant},
+};
+
+use anyhow::Error;
+use patchwork_core::{ActionId, Notation, Patchwork, Player, PlayerResult};
+
+/// A player that queries an external engine speaking the Universal Patchwork
+/// Interface (UPI). This allows playing against third-party engines the same
+/// way chess GUIs play against UCI engines.
+///
+/// The engine is started as a child process when the player is created and
+/// is asked to quit when the player is dropped.
+pub struct UpiEnginePlayer {
+    /// The name of the player as reported by the engine.
+    name: String,
+    /// The engine process.
+    process: Child,
+    /// The standard input of the engine the commands are written to.
+    stdin: ChildStdin,
+    /// The lines the engine wrote to its standard output.
+    lines: Receiver<String>,
+    /// The maximum time the engine may take to answer a command.
+    timeout: Duration,
+}
+
+impl UpiEnginePlayer {
+    /// The time the engine is given to quit before it is killed.
+    const QUIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+    /// Starts the given engine and waits until it is ready.
+    ///
+    /// # Arguments
+    ///
+    /// * `program` - The program of the engine.
+    /// * `args` - The arguments the program is started with.
+    /// * `timeout` - The maximum time the engine may take to answer a command (including searching a move).
+    ///
+    /// # Returns
+    ///
+    /// The new [`UpiEnginePlayer`].
+    ///
+    /// # Errors
+    ///
+    /// If the engine could not be started or does not answer the UPI handshake in time.
+    pub fn new<S: AsRef<std::ffi::OsStr>>(program: S, args: &[S], timeout: Duration) -> anyhow::Result<Self> {
+        let mut process = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let stdin = process.stdin.take().unwrap();
+        let stdout = process.stdout.take().unwrap();
+
+        // the output is read in a separate thread so that waiting for an answer can time out
+        let (sender, lines) = channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut player = Self {
+            name: "UPI Engine".to_string(),
+            process,
+            stdin,
+            lines,
+            timeout,
+        };
+
+        player.send("upi")?;
+        let mut name = None;
+        for line in player.receive_until("upiok")? {
+            if let Some(engine_name) = line.strip_prefix("id name ") {
+                name = Some(engine_name.trim().to_string());
+            }
+        }
+        if let Some(name) = name {
+            player.name = name;
+        }
+        player.send("isready")?;
+        player.receive_until("readyok")?;
+
+        Ok(player)
+    }
+
+    /// Lets the engine search the best action in the given state.
+    ///
+    /// # Arguments
+    ///
+    /// * `game` - The state to search the best action for.
+    /// * `search_moves` - The actions the engine is restricted to. If empty
+    ///   all valid actions may be chosen.
+    ///
+    /// # Returns
+    ///
+    /// The best action of the engine.
+    ///
+    /// # Errors
+    ///
+    /// If the engine does not answer in time or plays an action that is not allowed.
+    fn go(&mut self, game: &Patchwork, search_moves: &[ActionId]) -> PlayerResult<ActionId> {
+        // lines of an earlier search that timed out must not be taken as the answer to this one
+        self.discard_pending_lines();
+
+        self.send(&format!("position {}", game.save_to_notation()?))?;
+        if search_moves.is_empty() {
+            self.send("go")?;
+        } else {
+            let search_moves = search_moves
+                .iter()
+                .map(Notation::save_to_notation)
+                .collect::<Result<Vec<_>, _>>()?;
+            self.send(&format!("go searchmoves {}", search_moves.join(" ")))?;
+        }
+
+        let lines = match self.receive_until("bestmove") {
+            Ok(lines) => lines,
+            Err(error) => {
+                // the engine is still searching, stop it and wait for the late best move so that
+                // it is not read by the next search
+                if self.send("stop").is_ok() {
+                    let _ = self.receive_until("bestmove");
+                }
+                return Err(error);
+            }
+        };
+        let best_move = lines.last().unwrap().trim().trim_start_matches("bestmove").trim();
+        let action = ActionId::load_from_notation(best_move)?;
+
+        let is_allowed = if search_moves.is_empty() {
+            game.get_valid_actions().contains(&action)
+        } else {
+            search_moves.contains(&action)
+        };
+        if !is_allowed {
+            return Err(Error::msg(format!(
+                "[UpiEnginePlayer::go] The engine played the invalid action {best_move}"
+            )));
+        }
+        Ok(action)
+    }
+
+    /// Writes a command to the engine.
+    fn send(&mut self, command: &str) -> anyhow::Result<()> {
+        writeln!(self.stdin, "{command}")?;
+        self.stdin.flush()?;
+        Ok(())
+    }
+
+    /// Discards all lines the engine wrote that were not read yet.
+    fn discard_pending_lines(&self) {
+        while self.lines.try_recv().is_ok() {}
+    }
+
+    /// Reads lines of the engine until a line starting with the given prefix
+    /// was read.
+    ///
+    /// # Returns
+    ///
+    /// All read lines including the line with the prefix as the last line.
+    ///
+    /// # Errors
+    ///
+    /// If the engine does not write the line in time or exited.
+    fn receive_until(&self, prefix: &str) -> anyhow::Result<Vec<String>> {
+        let deadline = Instant::now() + self.timeout;
+        let mut lines = vec![];
+
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(remaining) {
+                Ok(line) => {
+                    let is_answer = line.trim().starts_with(prefix);
+                    lines.push(line);
+                    if is_answer {
+                        return Ok(lines);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(Error::msg(format!(
+                        "[UpiEnginePlayer::receive_until] The engine did not answer with \"{prefix}\" within {:?}",
+                        self.timeout
+                    )));
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(Error::msg(format!(
+                        "[UpiEnginePlayer::receive_until] The engine exited before answering with \"{prefix}\""
+                    )));
+                }
+            }
+        }
+    }
+}
+
+impl Player for UpiEnginePlayer {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_action(&mut self, game: &Patchwork) -> PlayerResult<ActionId> {
+        self.go(game, &[])
+    }
+
+    fn get_restricted_action(&mut self, game: &Patchwork, actions: &[ActionId]) -> PlayerResult<ActionId> {
+        self.go(game, actions)
+    }
+
+    fn reset(&mut self) {
+        let _ = self.send("upinewgame");
+    }
+}
+
+impl Drop for UpiEnginePlayer {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+
+        let deadline = Instant::now() + Self::QUIT_TIMEOUT;
+        while Instant::now() < deadline {
+            if !matches!(self.process.try_wait(), Ok(None)) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.process.kill();
+        let _ = self.process.wait();
+    }
+}
+
+#[cfg(test)]
+#[cfg(unix)]
+mod tests {
+    use patchwork_core::GameOptions;
+
+    use super::*;
+
+    #[test]
+    fn test_late_best_move_after_timeout_is_not_read_by_next_go() {
+        let first_state = Patchwork::get_initial_state(Some(GameOptions::new(42)));
+        let first_action = first_state.get_valid_actions()[1];
+        let mut second_state = first_state.clone();
+        second_state.do_action(first_action, false).unwrap();
+        let second_action = second_state.get_valid_actions()[0];
+        assert_ne!(first_action, second_action);
+
+        // answers the first search only after the timeout and every other search immediately
+        let script = format!(
+            r#"searches=0
+            while read -r command; do
+                case "$command" in
+                    upi) echo "id name Slow Engine"; echo "upiok" ;;
+                    isready) echo "readyok" ;;
+                    go*)
+                        searches=$((searches + 1))
+                        if [ "$searches" = 1 ]; then sleep 0.5; echo "bestmove {}"; else echo "bestmove {}"; fi ;;
+                    quit) exit 0 ;;
+                esac
+            done"#,
+            first_action.save_to_notation().unwrap(),
+            second_action.save_to_notation().unwrap()
+        );
+        let mut player = UpiEnginePlayer::new("sh", &["-c", &script], Duration::from_millis(300)).unwrap();
+        assert_eq!("Slow Engine", player.name());
+
+        assert!(player.get_action(&first_state).is_err());
+        assert_eq!(second_action, player.get_action(&second_state).unwrap());
+    }
+}